@@ -0,0 +1,287 @@
+// ANSI escape sequences in logs (colors from CI runners, test frameworks,
+// `ls --color`). They can be stripped, leaving plain text, or turned into
+// styled spans the frontend renders as colors. Cursor movement, erase and
+// other control sequences carry nothing worth keeping and are always dropped;
+// a bare carriage return (progress bars) keeps only the text written last.
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+const NAMED_COLORS: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+// Spans mode is meant for display; past this the frontend should page
+const MAX_SPANS: usize = 200_000;
+
+#[derive(Serialize, Clone, Default, PartialEq)]
+pub struct Style {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bg: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dim: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub underline: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub strikethrough: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub inverse: bool,
+    // OSC 8 hyperlink target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Span {
+    pub text: String,
+    #[serde(flatten)]
+    pub style: Style,
+}
+
+// Colors 0-15 keep their names so the frontend can theme them; the 256-color
+// cube, the grey ramp and true colors become hex
+fn palette_color(index: u16) -> String {
+    match index {
+        0..=7 => NAMED_COLORS[index as usize].to_string(),
+        8..=15 => format!("bright-{}", NAMED_COLORS[index as usize - 8]),
+        16..=231 => {
+            let index = index - 16;
+            let level = |value: u16| if value == 0 { 0 } else { 55 + value * 40 };
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                level(index / 36),
+                level(index / 6 % 6),
+                level(index % 6)
+            )
+        }
+        _ => {
+            let grey = 8 + (index.min(255) - 232) * 10;
+            format!("#{:02x}{:02x}{:02x}", grey, grey, grey)
+        }
+    }
+}
+
+// An extended color (38/48): `5;n` or `2;r;g;b`, consuming its parameters
+fn extended_color(params: &mut std::slice::Iter<'_, u16>) -> Option<String> {
+    match params.next()? {
+        5 => params.next().map(|index| palette_color(*index)),
+        2 => {
+            let (r, g, b) = (params.next()?, params.next()?, params.next()?);
+            Some(format!("#{:02x}{:02x}{:02x}", r.min(&255), g.min(&255), b.min(&255)))
+        }
+        _ => None,
+    }
+}
+
+fn apply_sgr(style: &mut Style, params: &[u16]) {
+    // ESC[m is a reset
+    if params.is_empty() {
+        *style = Style {
+            link: style.link.take(),
+            ..Style::default()
+        };
+        return;
+    }
+    let mut params = params.iter();
+    while let Some(param) = params.next() {
+        match param {
+            0 => {
+                *style = Style {
+                    link: style.link.take(),
+                    ..Style::default()
+                }
+            }
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 | 21 => style.underline = true,
+            7 => style.inverse = true,
+            9 => style.strikethrough = true,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = false,
+            27 => style.inverse = false,
+            29 => style.strikethrough = false,
+            30..=37 => style.fg = Some(palette_color(param - 30)),
+            38 => style.fg = extended_color(&mut params),
+            39 => style.fg = None,
+            40..=47 => style.bg = Some(palette_color(param - 40)),
+            48 => style.bg = extended_color(&mut params),
+            49 => style.bg = None,
+            90..=97 => style.fg = Some(palette_color(param - 90 + 8)),
+            100..=107 => style.bg = Some(palette_color(param - 100 + 8)),
+            _ => {}
+        }
+    }
+}
+
+pub struct Parsed {
+    pub spans: Vec<Span>,
+    pub sequences: usize,
+}
+
+impl Parsed {
+    pub fn plain_text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+struct Builder {
+    spans: Vec<Span>,
+    // Text of the current line since its last bare carriage return
+    pending: String,
+}
+
+impl Builder {
+    fn push(&mut self, c: char, style: &Style) {
+        match self.spans.last_mut() {
+            Some(last) if last.style == *style => last.text.push(c),
+            _ => self.spans.push(Span {
+                text: c.to_string(),
+                style: style.clone(),
+            }),
+        }
+        self.pending.push(c);
+        if c == '\n' {
+            self.pending.clear();
+        }
+    }
+
+    // A bare \r: the line is overwritten, so what was written since the line
+    // started is removed again
+    fn carriage_return(&mut self) {
+        let mut remove = self.pending.len();
+        self.pending.clear();
+        while remove > 0 {
+            let Some(last) = self.spans.last_mut() else {
+                break;
+            };
+            if last.text.len() <= remove {
+                remove -= last.text.len();
+                self.spans.pop();
+            } else {
+                let keep = last.text.len() - remove;
+                last.text.truncate(keep);
+                remove = 0;
+            }
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Parsed {
+    let mut builder = Builder {
+        spans: Vec::new(),
+        pending: String::new(),
+    };
+    let mut style = Style::default();
+    let mut sequences = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' | '\u{9b}' => {
+                sequences += 1;
+                let kind = if c == '\u{9b}' { Some('[') } else { chars.next() };
+                match kind {
+                    // CSI: parameters, intermediates, then a final byte
+                    Some('[') => {
+                        let mut raw = String::new();
+                        let mut final_byte = None;
+                        for next in chars.by_ref() {
+                            if ('\u{40}'..='\u{7e}').contains(&next) {
+                                final_byte = Some(next);
+                                break;
+                            }
+                            raw.push(next);
+                        }
+                        if final_byte == Some('m') {
+                            let params: Vec<u16> =
+                                raw.split([';', ':']).map(|param| param.parse().unwrap_or(0)).collect();
+                            apply_sgr(&mut style, if raw.is_empty() { &[] } else { &params });
+                        }
+                    }
+                    // OSC: up to BEL or ESC \
+                    Some(']') => {
+                        let mut body = String::new();
+                        while let Some(next) = chars.next() {
+                            if next == '\u{7}' {
+                                break;
+                            }
+                            if next == '\u{1b}' {
+                                chars.next_if_eq(&'\\');
+                                break;
+                            }
+                            body.push(next);
+                        }
+                        // OSC 8 ; params ; URI opens a hyperlink, an empty URI closes it
+                        if let Some(rest) = body.strip_prefix("8;") {
+                            let uri = rest.split_once(';').map(|(_, uri)| uri).unwrap_or("");
+                            style.link = (!uri.is_empty()).then(|| uri.to_string());
+                        }
+                    }
+                    // Two-character sequences (ESC 7, ESC =, charset selection ESC ( B)
+                    Some('(' | ')' | '*' | '+') => {
+                        chars.next();
+                    }
+                    _ => {}
+                }
+            }
+            '\r' if !matches!(chars.peek(), Some('\n') | None) => builder.carriage_return(),
+            // Other C0 controls besides tab and line breaks render as nothing
+            '\u{0}'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1a}' | '\u{1c}'..='\u{1f}' | '\u{7f}' => {}
+            _ => builder.push(c, &style),
+        }
+    }
+    Parsed {
+        spans: builder.spans,
+        sequences,
+    }
+}
+
+fn convert_ansi_blocking(app: &AppHandle, mode: &str) -> Result<serde_json::Value, String> {
+    let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let content = storage
+        .active()
+        .raw_content
+        .as_deref()
+        .ok_or_else(|| "No content stored".to_string())?;
+    let parsed = parse(content);
+    match mode {
+        "strip" => {
+            let result = parsed.plain_text();
+            let length = result.len();
+            storage.active_mut().edit(Slot::Formatted, result);
+            Ok(serde_json::json!({
+                "mode": mode,
+                "sequences_removed": parsed.sequences,
+                "formatted_length": length
+            }))
+        }
+        "spans" => {
+            drop(storage);
+            let truncated = parsed.spans.len() > MAX_SPANS;
+            let spans: Vec<&Span> = parsed.spans.iter().take(MAX_SPANS).collect();
+            Ok(serde_json::json!({
+                "mode": mode,
+                "sequences": parsed.sequences,
+                "spans": spans,
+                "truncated": truncated
+            }))
+        }
+        _ => Err(format!("Unknown ANSI mode: {}; use strip or spans", mode)),
+    }
+}
+
+// `mode` "strip" stores the active document's text without escape sequences
+// as its formatted content; "spans" returns the text as styled spans instead
+#[tauri::command]
+pub async fn convert_ansi(app: AppHandle, mode: String) -> Result<serde_json::Value, String> {
+    crate::run_blocking("convert_ansi", move || convert_ansi_blocking(&app, &mode)).await
+}
@@ -0,0 +1,83 @@
+// JSON files in the app data directory. Writes go to a temp file that is then
+// renamed over the old one, so a crash mid-write can't corrupt the last good
+// copy. Loading treats a missing file as absent; one that can't be read or
+// parsed is reported and copied aside first, so the next save can't destroy
+// the only copy of the user's data.
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+// `name` inside the app data directory, which is created if needed
+pub fn path(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(name))
+}
+
+// A subdirectory of the app data directory, created if needed
+pub fn dir(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = path(app, name)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {} directory: {}", name, e))?;
+    Ok(dir)
+}
+
+// `what` names the file in errors ("settings", "snippets")
+pub fn write(app: &AppHandle, file: &str, contents: &str, what: &str) -> Result<(), String> {
+    let path = path(app, file)?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, contents).map_err(|e| format!("Failed to write {}: {}", what, e))?;
+    std::fs::rename(&temp_path, &path).map_err(|e| format!("Failed to write {}: {}", what, e))
+}
+
+pub fn save<T: Serialize + ?Sized>(app: &AppHandle, file: &str, value: &T, what: &str) -> Result<(), String> {
+    let json = serde_json::to_string(value).map_err(|e| format!("Failed to serialize {}: {}", what, e))?;
+    write(app, file, &json, what)
+}
+
+// Ok(None) when the file doesn't exist yet
+pub fn read<T: DeserializeOwned>(app: &AppHandle, file: &str) -> Result<Option<T>, String> {
+    let path = path(app, file)?;
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", file, e)),
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {}: {}", file, e))
+}
+
+// For setup, where there's no caller to return an error to. A file that is
+// corrupt or from a newer version is copied to `<file>.unreadable-<time>`
+// before None is returned and the caller falls back to its defaults.
+pub fn load<T: DeserializeOwned>(app: &AppHandle, file: &str) -> Option<T> {
+    match read(app, file) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e);
+            back_up(app, file);
+            None
+        }
+    }
+}
+
+// Copies `file` aside before a load that couldn't use it lets it be overwritten
+pub fn back_up(app: &AppHandle, file: &str) {
+    let Ok(path) = path(app, file) else {
+        return;
+    };
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let backup = path.with_file_name(format!("{}.unreadable-{}", file, stamp));
+    match std::fs::copy(&path, &backup) {
+        Ok(_) => eprintln!("Kept a copy of the unreadable {} at {}", file, backup.display()),
+        Err(e) => eprintln!("Failed to back up the unreadable {}: {}", file, e),
+    }
+}
@@ -0,0 +1,194 @@
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+// Refuse to pull anything larger than this into memory for formatting
+const MAX_EXTRACT_SIZE: u64 = 512 * 1024 * 1024;
+
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+fn archive_kind(path: &str) -> Result<ArchiveKind, String> {
+    let mut header = [0u8; 4];
+    let read = File::open(path)
+        .and_then(|mut f| f.read(&mut header))
+        .map_err(|e| format!("Failed to open archive: {}", e))?;
+
+    let lower = path.to_lowercase();
+    if read >= 4 && &header == b"PK\x03\x04" {
+        Ok(ArchiveKind::Zip)
+    } else if read >= 2 && header[..2] == [0x1f, 0x8b] {
+        Ok(ArchiveKind::TarGz)
+    } else if lower.ends_with(".tar") {
+        Ok(ArchiveKind::Tar)
+    } else {
+        Err("Unsupported archive format (expected .zip, .tar or .tar.gz)".to_string())
+    }
+}
+
+fn open_tar(path: &str, gzipped: bool) -> Result<tar::Archive<Box<dyn Read>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let reader: Box<dyn Read> = if gzipped {
+        Box::new(GzDecoder::new(BufReader::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+fn list_zip(path: &str) -> Result<Vec<serde_json::Value>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Failed to read zip archive: {}", e))?;
+
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| format!("Failed to read zip entry: {}", e))?;
+        let modified = entry.last_modified().map(|dt| {
+            format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                dt.year(),
+                dt.month(),
+                dt.day(),
+                dt.hour(),
+                dt.minute(),
+                dt.second()
+            )
+        });
+        entries.push(serde_json::json!({
+            "name": entry.name(),
+            "size": entry.size(),
+            "compressed_size": entry.compressed_size(),
+            "is_dir": entry.is_dir(),
+            "modified": modified
+        }));
+    }
+    Ok(entries)
+}
+
+fn list_tar(path: &str, gzipped: bool) -> Result<Vec<serde_json::Value>, String> {
+    let mut archive = open_tar(path, gzipped)?;
+    let mut entries = Vec::new();
+    for entry in archive.entries().map_err(|e| format!("Failed to read tar archive: {}", e))? {
+        let entry = entry.map_err(|e| format!("Failed to read tar entry: {}", e))?;
+        let header = entry.header();
+        let name = entry
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        entries.push(serde_json::json!({
+            "name": name,
+            "size": header.size().unwrap_or(0),
+            "compressed_size": serde_json::Value::Null,
+            "is_dir": header.entry_type().is_dir(),
+            "modified": header.mtime().ok()
+        }));
+    }
+    Ok(entries)
+}
+
+fn list_archive_entries_blocking(file_path: String) -> Result<serde_json::Value, String> {
+    let (format, entries) = match archive_kind(&file_path)? {
+        ArchiveKind::Zip => ("zip", list_zip(&file_path)?),
+        ArchiveKind::Tar => ("tar", list_tar(&file_path, false)?),
+        ArchiveKind::TarGz => ("tar.gz", list_tar(&file_path, true)?),
+    };
+
+    Ok(serde_json::json!({
+        "format": format,
+        "entry_count": entries.len(),
+        "entries": entries
+    }))
+}
+
+#[tauri::command]
+pub async fn list_archive_entries(file_path: String) -> Result<serde_json::Value, String> {
+    crate::run_blocking("list_archive_entries", move || list_archive_entries_blocking(file_path)).await
+}
+
+fn read_entry_limited<R: Read>(reader: R, size: u64) -> Result<Vec<u8>, String> {
+    if size > MAX_EXTRACT_SIZE {
+        return Err(format!("Entry is too large to load ({} bytes)", size));
+    }
+    let mut data = Vec::with_capacity(size as usize);
+    reader
+        .take(MAX_EXTRACT_SIZE)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Failed to extract entry: {}", e))?;
+    Ok(data)
+}
+
+fn extract_zip_entry(path: &str, entry_name: &str) -> Result<Vec<u8>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Failed to read zip archive: {}", e))?;
+    let entry = archive
+        .by_name(entry_name)
+        .map_err(|e| format!("Entry '{}' not found: {}", entry_name, e))?;
+    let size = entry.size();
+    read_entry_limited(entry, size)
+}
+
+fn extract_tar_entry(path: &str, entry_name: &str, gzipped: bool) -> Result<Vec<u8>, String> {
+    let mut archive = open_tar(path, gzipped)?;
+    for entry in archive.entries().map_err(|e| format!("Failed to read tar archive: {}", e))? {
+        let entry = entry.map_err(|e| format!("Failed to read tar entry: {}", e))?;
+        let matches = entry
+            .path()
+            .map(|p| p.to_string_lossy() == entry_name)
+            .unwrap_or(false);
+        if matches {
+            let size = entry.header().size().unwrap_or(0);
+            return read_entry_limited(entry, size);
+        }
+    }
+    Err(format!("Entry '{}' not found", entry_name))
+}
+
+fn extract_archive_entry_blocking(
+    file_path: String,
+    entry_name: String,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let data = match archive_kind(&file_path)? {
+        ArchiveKind::Zip => extract_zip_entry(&file_path, &entry_name)?,
+        ArchiveKind::Tar => extract_tar_entry(&file_path, &entry_name, false)?,
+        ArchiveKind::TarGz => extract_tar_entry(&file_path, &entry_name, true)?,
+    };
+
+    let size = data.len();
+    let content = String::from_utf8(data)
+        .map_err(|_| format!("Entry '{}' is not valid UTF-8 text", entry_name))?;
+
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let document = storage.active_mut();
+    document.raw_content = Some(content.into());
+    document.formatted_content = None;
+    document.clear_history();
+
+    Ok(serde_json::json!({
+        "success": true,
+        "entry_name": entry_name,
+        "size": size
+    }))
+}
+
+#[tauri::command]
+pub async fn extract_archive_entry(
+    app: AppHandle,
+    file_path: String,
+    entry_name: String,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("extract_archive_entry", move || {
+        extract_archive_entry_blocking(file_path, entry_name, app.state::<AppState>().inner())
+    })
+    .await
+}
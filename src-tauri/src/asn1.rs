@@ -0,0 +1,350 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::formatters::Formatter;
+use crate::x509;
+
+// Guard against maliciously deep nesting
+const MAX_DEPTH: usize = 64;
+
+const KNOWN_OIDS: &[(&str, &str)] = &[
+    ("1.2.840.113549.1.1.1", "rsaEncryption"),
+    ("1.2.840.113549.1.1.5", "sha1WithRSAEncryption"),
+    ("1.2.840.113549.1.1.10", "RSASSA-PSS"),
+    ("1.2.840.113549.1.1.11", "sha256WithRSAEncryption"),
+    ("1.2.840.113549.1.1.12", "sha384WithRSAEncryption"),
+    ("1.2.840.113549.1.1.13", "sha512WithRSAEncryption"),
+    ("1.2.840.113549.1.7.1", "pkcs7-data"),
+    ("1.2.840.113549.1.7.2", "pkcs7-signedData"),
+    ("1.2.840.113549.1.9.1", "emailAddress"),
+    ("1.2.840.113549.1.9.14", "extensionRequest"),
+    ("1.2.840.10045.2.1", "ecPublicKey"),
+    ("1.2.840.10045.3.1.7", "prime256v1"),
+    ("1.2.840.10045.4.3.2", "ecdsa-with-SHA256"),
+    ("1.2.840.10045.4.3.3", "ecdsa-with-SHA384"),
+    ("1.3.132.0.34", "secp384r1"),
+    ("1.3.132.0.35", "secp521r1"),
+    ("1.3.101.112", "Ed25519"),
+    ("1.3.101.110", "X25519"),
+    ("2.5.4.3", "commonName"),
+    ("2.5.4.5", "serialNumber"),
+    ("2.5.4.6", "countryName"),
+    ("2.5.4.7", "localityName"),
+    ("2.5.4.8", "stateOrProvinceName"),
+    ("2.5.4.10", "organizationName"),
+    ("2.5.4.11", "organizationalUnitName"),
+    ("2.5.29.14", "subjectKeyIdentifier"),
+    ("2.5.29.15", "keyUsage"),
+    ("2.5.29.17", "subjectAltName"),
+    ("2.5.29.19", "basicConstraints"),
+    ("2.5.29.31", "cRLDistributionPoints"),
+    ("2.5.29.32", "certificatePolicies"),
+    ("2.5.29.35", "authorityKeyIdentifier"),
+    ("2.5.29.37", "extKeyUsage"),
+    ("1.3.6.1.5.5.7.1.1", "authorityInfoAccess"),
+    ("1.3.6.1.5.5.7.3.1", "serverAuth"),
+    ("1.3.6.1.5.5.7.3.2", "clientAuth"),
+    ("1.3.6.1.5.5.7.48.1", "ocsp"),
+    ("1.3.6.1.5.5.7.48.2", "caIssuers"),
+    ("1.3.6.1.4.1.11129.2.4.2", "ctPrecertificateSCTs"),
+    ("2.16.840.1.101.3.4.2.1", "sha256"),
+    ("2.16.840.1.101.3.4.2.2", "sha384"),
+    ("2.16.840.1.101.3.4.2.3", "sha512"),
+    ("1.3.14.3.2.26", "sha1"),
+];
+
+pub fn oid_name(oid: &str) -> Option<&'static str> {
+    KNOWN_OIDS.iter().find(|(id, _)| *id == oid).map(|(_, name)| *name)
+}
+
+fn universal_tag_name(tag: u32) -> &'static str {
+    match tag {
+        1 => "BOOLEAN",
+        2 => "INTEGER",
+        3 => "BIT STRING",
+        4 => "OCTET STRING",
+        5 => "NULL",
+        6 => "OBJECT IDENTIFIER",
+        10 => "ENUMERATED",
+        12 => "UTF8String",
+        16 => "SEQUENCE",
+        17 => "SET",
+        19 => "PrintableString",
+        20 => "T61String",
+        22 => "IA5String",
+        23 => "UTCTime",
+        24 => "GeneralizedTime",
+        26 => "VisibleString",
+        30 => "BMPString",
+        _ => "UNKNOWN",
+    }
+}
+
+// Accept PEM, hex (with optional spaces/colons) or base64 DER
+pub fn input_to_der(text: &str) -> Result<Vec<u8>, String> {
+    let trimmed = text.trim();
+    if trimmed.contains("-----BEGIN") {
+        return x509::pem_to_der(trimmed);
+    }
+
+    let compact: String = trimmed
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .collect();
+    if compact.is_empty() {
+        return Err("Empty ASN.1 input".to_string());
+    }
+    if compact.len().is_multiple_of(2) && compact.chars().all(|c| c.is_ascii_hexdigit()) {
+        return (0..compact.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&compact[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|e| format!("Invalid hex input: {}", e));
+    }
+
+    let base64: String = trimmed.split_whitespace().collect();
+    STANDARD
+        .decode(base64.as_bytes())
+        .map_err(|e| format!("Input is not valid PEM, hex or base64: {}", e))
+}
+
+struct Header {
+    class: u8,
+    constructed: bool,
+    tag: u32,
+    header_len: usize,
+    length: usize,
+}
+
+fn read_header(data: &[u8]) -> Result<Header, String> {
+    let first = *data.first().ok_or("Unexpected end of data reading tag")?;
+    let class = first >> 6;
+    let constructed = first & 0x20 != 0;
+    let mut pos = 1;
+
+    let mut tag = u32::from(first & 0x1f);
+    if tag == 0x1f {
+        tag = 0;
+        loop {
+            let b = *data.get(pos).ok_or("Unexpected end of data reading tag")?;
+            pos += 1;
+            if tag > u32::MAX >> 7 {
+                return Err("Tag number too large".to_string());
+            }
+            tag = (tag << 7) | u32::from(b & 0x7f);
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+    }
+
+    let len_byte = *data.get(pos).ok_or("Unexpected end of data reading length")?;
+    pos += 1;
+    let length = if len_byte & 0x80 == 0 {
+        usize::from(len_byte)
+    } else {
+        let count = usize::from(len_byte & 0x7f);
+        if count == 0 {
+            return Err("Indefinite-length encoding is not supported (BER, not DER)".to_string());
+        }
+        if count > 8 {
+            return Err(format!("Length field too long ({} bytes)", count));
+        }
+        let mut length = 0usize;
+        for _ in 0..count {
+            let b = *data.get(pos).ok_or("Unexpected end of data reading length")?;
+            pos += 1;
+            length = (length << 8) | usize::from(b);
+        }
+        length
+    };
+
+    // `pos` is at most data.len(), so this can't overflow the way pos + length can
+    if length > data.len() - pos {
+        return Err(format!(
+            "Length {} at offset exceeds remaining {} bytes",
+            length,
+            data.len() - pos
+        ));
+    }
+
+    Ok(Header { class, constructed, tag, header_len: pos, length })
+}
+
+fn decode_oid(bytes: &[u8]) -> String {
+    let mut parts: Vec<u64> = Vec::new();
+    let mut value: u64 = 0;
+    for b in bytes.iter() {
+        if value > u64::MAX >> 7 {
+            return format!("(OID arc too large) {}", hex_preview(bytes));
+        }
+        value = (value << 7) | u64::from(b & 0x7f);
+        if b & 0x80 == 0 {
+            if parts.is_empty() {
+                let first = if value < 80 { value / 40 } else { 2 };
+                parts.push(first);
+                parts.push(value - first * 40);
+            } else {
+                parts.push(value);
+            }
+            value = 0;
+        }
+    }
+    parts.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(".")
+}
+
+fn hex_preview(bytes: &[u8]) -> String {
+    let shown: Vec<String> = bytes.iter().take(32).map(|b| format!("{:02X}", b)).collect();
+    if bytes.len() > 32 {
+        format!("{}… ({} bytes)", shown.join(" "), bytes.len())
+    } else {
+        shown.join(" ")
+    }
+}
+
+fn describe_primitive(tag: u32, value: &[u8]) -> String {
+    match tag {
+        1 => (value.first().copied().unwrap_or(0) != 0).to_string(),
+        2 | 10 => {
+            if value.len() <= 8 {
+                let mut n: i64 = if value.first().is_some_and(|b| b & 0x80 != 0) { -1 } else { 0 };
+                for b in value {
+                    n = (n << 8) | i64::from(*b);
+                }
+                n.to_string()
+            } else {
+                hex_preview(value)
+            }
+        }
+        5 => String::new(),
+        6 => {
+            let oid = decode_oid(value);
+            match oid_name(&oid) {
+                Some(name) => format!("{} ({})", oid, name),
+                None => oid,
+            }
+        }
+        12 | 19 | 20 | 22 | 23 | 24 | 26 => String::from_utf8_lossy(value).into_owned(),
+        30 => {
+            let units: Vec<u16> = value
+                .chunks(2)
+                .map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => hex_preview(value),
+    }
+}
+
+fn dump_node(data: &[u8], offset: usize, depth: usize, out: &mut String) -> Result<(), String> {
+    if depth > MAX_DEPTH {
+        return Err("ASN.1 structure nested too deeply".to_string());
+    }
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let header = read_header(&data[pos..])?;
+        let value_start = pos + header.header_len;
+        let value = &data[value_start..value_start + header.length];
+
+        let name = match header.class {
+            0 => universal_tag_name(header.tag).to_string(),
+            1 => format!("[APPLICATION {}]", header.tag),
+            2 => format!("[{}]", header.tag),
+            _ => format!("[PRIVATE {}]", header.tag),
+        };
+        let indent = "  ".repeat(depth);
+        let location = format!("{:>6}:{:<4}", offset + pos, header.length);
+
+        if header.constructed {
+            out.push_str(&format!("{} {}{} {{\n", location, indent, name));
+            dump_node(value, offset + value_start, depth + 1, out)?;
+            out.push_str(&format!("{} {}}}\n", " ".repeat(location.len()), indent));
+        } else if header.class == 0 && header.tag == 4 && value.first() == Some(&0x30) && dump_node(value, 0, 0, &mut String::new()).is_ok() {
+            // OCTET STRINGs frequently wrap further DER (e.g. certificate extensions)
+            out.push_str(&format!("{} {}{} (encapsulates) {{\n", location, indent, name));
+            dump_node(value, offset + value_start, depth + 1, out)?;
+            out.push_str(&format!("{} {}}}\n", " ".repeat(location.len()), indent));
+        } else if header.class == 0 && header.tag == 3 && value.len() > 1 && value[0] == 0 && value[1] == 0x30 && dump_node(&value[1..], 0, 0, &mut String::new()).is_ok() {
+            out.push_str(&format!("{} {}{} (encapsulates) {{\n", location, indent, name));
+            dump_node(&value[1..], offset + value_start + 1, depth + 1, out)?;
+            out.push_str(&format!("{} {}}}\n", " ".repeat(location.len()), indent));
+        } else {
+            let rendered = if header.class == 0 { describe_primitive(header.tag, value) } else { hex_preview(value) };
+            out.push_str(format!("{} {}{} {}\n", location, indent, name, rendered).trim_end());
+            out.push('\n');
+        }
+
+        pos = value_start + header.length;
+    }
+
+    Ok(())
+}
+
+pub fn dump_asn1(text: &str) -> Result<String, String> {
+    let der = input_to_der(text)?;
+
+    let mut result = String::new();
+    result.push_str("ASN.1 Structure (offset:length):\n");
+    result.push_str("================================\n\n");
+    dump_node(&der, 0, 0, &mut result)?;
+    Ok(result)
+}
+
+pub struct Asn1Formatter;
+
+impl Formatter for Asn1Formatter {
+    fn id(&self) -> &'static str {
+        "asn1"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "ASN.1 / DER Dump"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "text"
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        dump_asn1(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dumps_nested_structure() {
+        // SEQUENCE { INTEGER 5, OID 1.2.840.113549.1.1.11, NULL }
+        let dump = dump_asn1("30 10 02 01 05 06 09 2A 86 48 86 F7 0D 01 01 0B 05 00").unwrap();
+        assert!(dump.contains("INTEGER 5"), "{}", dump);
+        assert!(dump.contains("1.2.840.113549.1.1.11 (sha256WithRSAEncryption)"), "{}", dump);
+    }
+
+    #[test]
+    fn long_form_length_overflow_is_an_error() {
+        // A length of usize::MAX would wrap pos + length around to a small value
+        assert!(dump_asn1("04 88 FF FF FF FF FF FF FF FF 00").is_err());
+        assert!(dump_asn1("30 84 FF FF FF FF").is_err());
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        assert!(dump_asn1("30 05 02 01").is_err());
+        assert!(dump_asn1("1F 81").is_err());
+    }
+
+    #[test]
+    fn oversized_tag_is_an_error() {
+        assert!(dump_asn1("1F FF FF FF FF FF 7F 00").is_err());
+    }
+
+    #[test]
+    fn oversized_oid_arc_does_not_overflow() {
+        let oid = decode_oid(&[0x2A, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]);
+        assert!(oid.starts_with("(OID arc too large)"), "{}", oid);
+        assert_eq!(decode_oid(&[0x55, 0x04, 0x03]), "2.5.4.3");
+    }
+}
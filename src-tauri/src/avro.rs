@@ -0,0 +1,424 @@
+// Decodes Avro into plain JSON, given a pasted `.avsc` schema or one from a
+// Confluent schema registry. Binary input may be a single datum (or several
+// back to back), a registry-framed message (magic byte 0, 4-byte schema id) or
+// an object container file, which carries its own schema. Avro's JSON encoding
+// wraps union values as `{"type": value}`; those wrappers are removed so the
+// result reads like ordinary JSON. The output replaces the active document's
+// content, ready for the JSON formatter, summary and tree.
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+const CONTAINER_MAGIC: &[u8] = b"Obj\x01";
+const PRIMITIVES: &[&str] = &["null", "boolean", "int", "long", "float", "double", "bytes", "string"];
+// Datums decoded from one binary input before giving up
+const MAX_DATUMS: usize = 1_000_000;
+
+fn registry_get(url: &str) -> Result<serde_json::Value, String> {
+    let response = crate::fetch::http_client()?
+        .get(crate::fetch::parse_http_url(url)?)
+        .send()
+        .map_err(|e| format!("Failed to reach the schema registry: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "The schema registry responded with {} for {}",
+            response.status(),
+            url
+        ));
+    }
+    serde_json::from_reader(response).map_err(|e| format!("Unreadable schema registry reply: {}", e))
+}
+
+// The schema text registered under `id`
+pub fn registry_schema(registry_url: &str, id: u32) -> Result<String, String> {
+    let url = format!("{}/schemas/ids/{}", registry_url.trim_end_matches('/'), id);
+    let reply = registry_get(&url)?;
+    reply["schema"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("Schema {} has no Avro definition", id))
+}
+
+// The latest schema registered for `subject`, with its id
+fn registry_latest(registry_url: &str, subject: &str) -> Result<(u32, String), String> {
+    let url = format!(
+        "{}/subjects/{}/versions/latest",
+        registry_url.trim_end_matches('/'),
+        subject.trim()
+    );
+    let reply = registry_get(&url)?;
+    let id = reply["id"].as_u64().unwrap_or_default() as u32;
+    let schema = reply["schema"]
+        .as_str()
+        .ok_or_else(|| format!("Subject {} has no Avro definition", subject))?;
+    Ok((id, schema.to_string()))
+}
+
+// Named types (records, enums, fixed) by short and full name, for resolving
+// references while walking Avro-JSON
+fn collect_names<'a>(
+    schema: &'a serde_json::Value,
+    namespace: Option<&str>,
+    names: &mut HashMap<String, &'a serde_json::Value>,
+) {
+    match schema {
+        serde_json::Value::Array(variants) => variants
+            .iter()
+            .for_each(|variant| collect_names(variant, namespace, names)),
+        serde_json::Value::Object(object) => {
+            let namespace = object
+                .get("namespace")
+                .and_then(|namespace| namespace.as_str())
+                .or(namespace);
+            if let Some(name) = object.get("name").and_then(|name| name.as_str()) {
+                names.insert(name.to_string(), schema);
+                if let Some(namespace) = namespace.filter(|_| !name.contains('.')) {
+                    names.insert(format!("{}.{}", namespace, name), schema);
+                }
+            }
+            for key in ["type", "items", "values"] {
+                if let Some(child) = object.get(key) {
+                    collect_names(child, namespace, names);
+                }
+            }
+            if let Some(fields) = object.get("fields").and_then(|fields| fields.as_array()) {
+                for field in fields {
+                    if let Some(child) = field.get("type") {
+                        collect_names(child, namespace, names);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// The names a union branch goes by in Avro-JSON: "string", "array", or a
+// named type's short and full names
+fn branch_names(schema: &serde_json::Value) -> Vec<String> {
+    match schema {
+        serde_json::Value::String(name) => vec![name.clone()],
+        serde_json::Value::Object(object) => {
+            let mut found = Vec::new();
+            if let Some(name) = object.get("name").and_then(|name| name.as_str()) {
+                found.push(name.to_string());
+                if let Some(namespace) = object.get("namespace").and_then(|namespace| namespace.as_str()) {
+                    found.push(format!("{}.{}", namespace, name));
+                }
+            }
+            if let Some(kind) = object.get("type").and_then(|kind| kind.as_str()) {
+                found.push(kind.to_string());
+            }
+            found
+        }
+        _ => Vec::new(),
+    }
+}
+
+// Strips union wrappers from an Avro-JSON value following `schema`
+fn plain_json(
+    value: serde_json::Value,
+    schema: &serde_json::Value,
+    names: &HashMap<String, &serde_json::Value>,
+    depth: usize,
+) -> Result<serde_json::Value, String> {
+    if depth > 256 {
+        return Err("Schema nesting is too deep".to_string());
+    }
+    match schema {
+        serde_json::Value::String(name) if PRIMITIVES.contains(&name.as_str()) => Ok(value),
+        serde_json::Value::String(name) => match names.get(name.as_str()) {
+            Some(named) => plain_json(value, named, names, depth + 1),
+            None => Err(format!("Unknown type {} in the schema", name)),
+        },
+        serde_json::Value::Array(variants) => {
+            if value.is_null() {
+                return Ok(value);
+            }
+            let serde_json::Value::Object(object) = &value else {
+                return Ok(value);
+            };
+            let Some((key, inner)) = object.iter().next().filter(|_| object.len() == 1) else {
+                return Ok(value);
+            };
+            // The branch's schema; for a reference, the named type behind it
+            let branch = variants.iter().find(|variant| {
+                branch_names(variant).contains(key)
+                    || variant
+                        .as_str()
+                        .and_then(|name| names.get(name))
+                        .is_some_and(|named| branch_names(named).contains(key))
+            });
+            match branch {
+                Some(branch) => plain_json(inner.clone(), branch, names, depth + 1),
+                // A record that happens to have one field, not a wrapper
+                None => Ok(value),
+            }
+        }
+        serde_json::Value::Object(definition) => {
+            let kind = definition.get("type").cloned().unwrap_or_default();
+            match kind.as_str() {
+                Some("record") | Some("error") => {
+                    let mut object = match value {
+                        serde_json::Value::Object(object) => object,
+                        other => return Ok(other),
+                    };
+                    let fields = definition.get("fields").and_then(|fields| fields.as_array());
+                    for field in fields.into_iter().flatten() {
+                        let (Some(name), Some(field_type)) =
+                            (field.get("name").and_then(|n| n.as_str()), field.get("type"))
+                        else {
+                            continue;
+                        };
+                        if let Some(child) = object.remove(name) {
+                            object.insert(name.to_string(), plain_json(child, field_type, names, depth + 1)?);
+                        }
+                    }
+                    Ok(serde_json::Value::Object(object))
+                }
+                Some("array") => match (value, definition.get("items")) {
+                    (serde_json::Value::Array(items), Some(item_type)) => items
+                        .into_iter()
+                        .map(|item| plain_json(item, item_type, names, depth + 1))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(serde_json::Value::Array),
+                    (value, _) => Ok(value),
+                },
+                Some("map") => match (value, definition.get("values")) {
+                    (serde_json::Value::Object(entries), Some(value_type)) => entries
+                        .into_iter()
+                        .map(|(key, entry)| Ok((key, plain_json(entry, value_type, names, depth + 1)?)))
+                        .collect::<Result<serde_json::Map<_, _>, String>>()
+                        .map(serde_json::Value::Object),
+                    (value, _) => Ok(value),
+                },
+                // enum, fixed, and primitives written as {"type": "long", "logicalType": ...}
+                Some(name) if !PRIMITIVES.contains(&name) && name != "enum" && name != "fixed" => {
+                    plain_json(value, &kind, names, depth + 1)
+                }
+                Some(_) => Ok(value),
+                // {"type": {...}} or {"type": [...]}
+                None => plain_json(value, &kind, names, depth + 1),
+            }
+        }
+        _ => Ok(value),
+    }
+}
+
+fn decode_avro_json(text: &str, schema_text: &str) -> Result<serde_json::Value, String> {
+    let schema: serde_json::Value =
+        serde_json::from_str(schema_text).map_err(|e| format!("The schema isn't valid JSON: {}", e))?;
+    // Parsed for validation only; Avro-JSON is walked with the schema as JSON
+    apache_avro::Schema::parse(&schema).map_err(|e| format!("Invalid Avro schema: {}", e))?;
+    let mut names = HashMap::new();
+    collect_names(&schema, None, &mut names);
+
+    let value: serde_json::Value = serde_json::from_str(text.trim()).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let schema_is_array = schema.get("type").and_then(|kind| kind.as_str()) == Some("array");
+    match value {
+        // A list of datums, unless the schema itself describes an array
+        serde_json::Value::Array(datums) if !schema_is_array => datums
+            .into_iter()
+            .map(|datum| plain_json(datum, &schema, &names, 0))
+            .collect::<Result<Vec<_>, _>>()
+            .map(serde_json::Value::Array),
+        value => plain_json(value, &schema, &names, 0),
+    }
+}
+
+fn to_json(value: apache_avro::types::Value) -> Result<serde_json::Value, String> {
+    serde_json::Value::try_from(value).map_err(|e| format!("Failed to convert Avro value: {}", e))
+}
+
+// Every datum in `bytes`, or the only one unwrapped
+fn decode_datums(bytes: &[u8], schema: &apache_avro::Schema) -> Result<serde_json::Value, String> {
+    let mut reader = bytes;
+    let mut datums = Vec::new();
+    while !reader.is_empty() {
+        if datums.len() >= MAX_DATUMS {
+            return Err(format!("More than {} datums", MAX_DATUMS));
+        }
+        let offset = bytes.len() - reader.len();
+        let value = apache_avro::from_avro_datum(schema, &mut reader, None)
+            .map_err(|e| format!("Data doesn't match the schema at byte {}: {}", offset, e))?;
+        datums.push(to_json(value)?);
+    }
+    match datums.len() {
+        0 => Err("No data to decode".to_string()),
+        1 => Ok(datums.pop().unwrap_or_default()),
+        _ => Ok(serde_json::Value::Array(datums)),
+    }
+}
+
+// Input given as text: base64, or hex when it's only hex digits
+fn decode_text_bytes(text: &str) -> Result<Vec<u8>, String> {
+    let compact: String = text.split_whitespace().collect();
+    let hex = compact.strip_prefix("0x").unwrap_or(&compact);
+    if !hex.is_empty() && hex.len().is_multiple_of(2) && hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string()))
+            .collect();
+    }
+    STANDARD
+        .decode(compact.as_bytes())
+        .map_err(|e| format!("Data is neither hex nor valid base64: {}", e))
+}
+
+struct SchemaSource {
+    text: Option<String>,
+    registry_url: Option<String>,
+    subject: Option<String>,
+    id: Option<u32>,
+}
+
+impl SchemaSource {
+    // The pasted schema, else the registry's for `subject` or `id` (or the id
+    // framed into the data); None when nothing identifies one
+    fn resolve(&self, framed_id: Option<u32>) -> Result<Option<(String, Option<u32>)>, String> {
+        if let Some(text) = &self.text {
+            return Ok(Some((text.clone(), None)));
+        }
+        let Some(registry_url) = &self.registry_url else {
+            return Ok(None);
+        };
+        if let Some(subject) = &self.subject {
+            let (id, schema) = registry_latest(registry_url, subject)?;
+            return Ok(Some((schema, Some(id))));
+        }
+        match self.id.or(framed_id) {
+            Some(id) => Ok(Some((registry_schema(registry_url, id)?, Some(id)))),
+            None => Ok(None),
+        }
+    }
+}
+
+fn decode_binary(
+    bytes: &[u8],
+    source: &SchemaSource,
+) -> Result<(serde_json::Value, &'static str, Option<u32>), String> {
+    if bytes.starts_with(CONTAINER_MAGIC) {
+        // The container's own schema is the writer schema; a given one is
+        // used as the reader schema
+        let reader_schema = source
+            .resolve(None)?
+            .map(|(text, _)| apache_avro::Schema::parse_str(&text).map_err(|e| format!("Invalid Avro schema: {}", e)))
+            .transpose()?;
+        let reader = match &reader_schema {
+            Some(schema) => apache_avro::Reader::with_schema(schema, bytes),
+            None => apache_avro::Reader::new(bytes),
+        }
+        .map_err(|e| format!("Invalid Avro container file: {}", e))?;
+        let mut records = Vec::new();
+        for value in reader {
+            if records.len() >= MAX_DATUMS {
+                return Err(format!("More than {} records", MAX_DATUMS));
+            }
+            records.push(to_json(value.map_err(|e| format!("Failed to read a record: {}", e))?)?);
+        }
+        return Ok((serde_json::Value::Array(records), "container", None));
+    }
+
+    let framed_id = (bytes.len() >= 5 && bytes[0] == 0 && source.text.is_none())
+        .then(|| u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]));
+    let (text, id) = source
+        .resolve(framed_id)?
+        .ok_or_else(|| "Provide a schema, or a schema registry URL with a subject or schema id".to_string())?;
+    let schema = apache_avro::Schema::parse_str(&text).map_err(|e| format!("Invalid Avro schema: {}", e))?;
+    // Registry framing is only stripped when the schema came from the frame
+    match framed_id.filter(|framed| Some(*framed) == id) {
+        Some(_) => Ok((decode_datums(&bytes[5..], &schema)?, "registry-framed", id)),
+        None => Ok((decode_datums(bytes, &schema)?, "binary", id)),
+    }
+}
+
+fn decode_avro_blocking(
+    app: &AppHandle,
+    source: SchemaSource,
+    file_path: Option<String>,
+    data: Option<String>,
+    json: bool,
+) -> Result<serde_json::Value, String> {
+    let (value, encoding, schema_id) = if json {
+        let snapshot = match data {
+            Some(_) => None,
+            None => Some(
+                app.state::<AppState>()
+                    .inner()
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .snapshot_raw()?,
+            ),
+        };
+        let text = snapshot
+            .as_ref()
+            .map_or(data.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+        let (schema, id) = source.resolve(None)?.ok_or_else(|| {
+            "Avro-JSON needs a schema, or a schema registry URL with a subject or schema id".to_string()
+        })?;
+        (decode_avro_json(text, &schema)?, "json", id)
+    } else {
+        let bytes = match (file_path, data) {
+            (Some(path), _) => std::fs::read(&path).map_err(|e| format!("Failed to read file: {}", e))?,
+            (None, Some(data)) => decode_text_bytes(&data)?,
+            (None, None) => {
+                let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                storage
+                    .active()
+                    .binary_content
+                    .clone()
+                    .ok_or_else(|| "No binary content stored to decode".to_string())?
+            }
+        };
+        decode_binary(&bytes, &source)?
+    };
+
+    let records = value.as_array().map(|records| records.len());
+    let pretty = crate::formatters::to_string_pretty(&value).map_err(|e| format!("Failed to format JSON: {}", e))?;
+    let length = pretty.len();
+    let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let document = storage.active_mut();
+    document.edit(Slot::Raw, pretty);
+    document.formatted_content = None;
+    document.format_type = Some("json".to_string());
+    Ok(serde_json::json!({
+        "encoding": encoding,
+        "schema_id": schema_id,
+        "records": records,
+        "length": length
+    }))
+}
+
+// Decodes Avro into JSON in the active document. The schema is `schema` (.avsc
+// text) or comes from the registry at `registry_url`, by `subject` (latest
+// version), `schema_id`, or the id framed into the data. Binary input is read
+// from `file_path`, `data` (base64 or hex) or the active document's binary
+// content; with `json`, `data` or the active document's text is Avro-JSON.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn decode_avro(
+    app: AppHandle,
+    schema: Option<String>,
+    registry_url: Option<String>,
+    subject: Option<String>,
+    schema_id: Option<u32>,
+    file_path: Option<String>,
+    data: Option<String>,
+    json: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let present = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+    crate::run_blocking("decode_avro", move || {
+        let source = SchemaSource {
+            text: present(schema),
+            registry_url: present(registry_url),
+            subject: present(subject),
+            id: schema_id,
+        };
+        decode_avro_blocking(&app, source, present(file_path), present(data), json.unwrap_or(false))
+    })
+    .await
+}
@@ -0,0 +1,196 @@
+// Runs one formatter over every matching file in a directory, e.g. to reformat
+// a folder of fixtures. Outputs go to a target folder (mirroring the input
+// layout) or replace the files in place; either way each file is written via a
+// temporary file and a rename, so a failure never leaves one half-written.
+// Progress is reported per file and one failing file doesn't stop the rest.
+use globset::{Glob, GlobMatcher};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::formatters::FormatterRegistry;
+use crate::jobs::{spawn_job, CancelToken};
+
+pub const BATCH_PROGRESS_EVENT: &str = "batch://progress";
+// Failures listed in the summary; the count covers all of them
+const MAX_LISTED_FAILURES: usize = 200;
+
+// Relative paths of the matching files, sorted. Hidden directories (.git and
+// the like) and the output directory are skipped.
+fn collect_files(
+    root: &Path,
+    matcher: &GlobMatcher,
+    recursive: bool,
+    exclude: Option<&Path>,
+    token: &CancelToken,
+) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        token.check()?;
+        let entries =
+            std::fs::read_dir(&directory).map_err(|e| format!("Failed to read {}: {}", directory.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if recursive && !hidden && exclude.is_none_or(|exclude| path != exclude) {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+                if matcher.is_match(&relative) {
+                    files.push(relative);
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn write_atomically(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".devmate-tmp");
+    let temp_path = path.with_file_name(temp_name);
+    std::fs::write(&temp_path, content).map_err(|e| format!("Failed to write: {}", e))?;
+    std::fs::rename(&temp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        format!("Failed to replace the file: {}", e)
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn batch_format_blocking(
+    app: &AppHandle,
+    token: &CancelToken,
+    directory: String,
+    pattern: String,
+    format_type: String,
+    output_directory: Option<String>,
+    in_place: bool,
+    recursive: bool,
+) -> Result<serde_json::Value, String> {
+    let root = PathBuf::from(&directory);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", directory));
+    }
+    let output_root = match (output_directory, in_place) {
+        (Some(_), true) => return Err("Choose either an output directory or in-place, not both".to_string()),
+        (None, false) => return Err("Choose an output directory or in-place formatting".to_string()),
+        (Some(output), false) => Some(PathBuf::from(output)),
+        (None, true) => None,
+    };
+    if output_root.as_deref() == Some(root.as_path()) {
+        return Err("The output directory is the input directory; use in-place formatting instead".to_string());
+    }
+    let matcher = Glob::new(&pattern)
+        .map_err(|e| format!("Invalid file pattern: {}", e))?
+        .compile_matcher();
+
+    let format_type = crate::settings::resolve_format(app, format_type);
+    let registry = app.state::<FormatterRegistry>();
+    let formatter = registry
+        .get(&format_type)
+        .ok_or_else(|| "Unknown format type".to_string())?;
+
+    let files = collect_files(&root, &matcher, recursive, output_root.as_deref(), token)?;
+    let total = files.len();
+    let mut succeeded = 0;
+    let mut unchanged = 0;
+    let mut failures = Vec::new();
+    let mut failed = 0;
+    for (index, relative) in files.iter().enumerate() {
+        token.check()?;
+        let source = root.join(relative);
+        let target = match &output_root {
+            Some(output_root) => output_root.join(relative),
+            None => source.clone(),
+        };
+        let outcome = std::fs::read_to_string(&source)
+            .map_err(|e| format!("Failed to read: {}", e))
+            .and_then(|content| {
+                let formatted = formatter.format(&content)?;
+                if in_place && formatted == content {
+                    return Ok(false);
+                }
+                write_atomically(&target, &formatted).map(|_| true)
+            });
+
+        let path = relative.to_string_lossy().replace('\\', "/");
+        let (status, error) = match outcome {
+            Ok(true) => {
+                succeeded += 1;
+                ("formatted", None)
+            }
+            Ok(false) => {
+                unchanged += 1;
+                ("unchanged", None)
+            }
+            Err(error) => {
+                failed += 1;
+                if failures.len() < MAX_LISTED_FAILURES {
+                    failures.push(serde_json::json!({ "path": path, "error": error }));
+                }
+                ("failed", Some(error))
+            }
+        };
+        let _ = app.emit(
+            BATCH_PROGRESS_EVENT,
+            serde_json::json!({
+                "directory": directory,
+                "index": index + 1,
+                "total": total,
+                "path": path,
+                "status": status,
+                "error": error
+            }),
+        );
+    }
+
+    Ok(serde_json::json!({
+        "directory": directory,
+        "output_directory": output_root.map(|path| path.to_string_lossy().into_owned()),
+        "format_type": format_type,
+        "total": total,
+        "succeeded": succeeded,
+        "unchanged": unchanged,
+        "failed": failed,
+        "failures": failures
+    }))
+}
+
+// Formats every file under `directory` whose relative path matches `pattern`
+// (a glob such as "**/*.json"; every file by default), into `output_directory`
+// or in place. Subdirectories are included unless `recursive` is false. Runs
+// as a cancellable job; files already written stay written on cancel.
+#[tauri::command]
+pub fn batch_format(
+    app: AppHandle,
+    directory: String,
+    pattern: Option<String>,
+    format_type: String,
+    output_directory: Option<String>,
+    in_place: Option<bool>,
+    recursive: Option<bool>,
+) -> Result<u64, String> {
+    spawn_job(&app, "batch-format", move |app, token| {
+        batch_format_blocking(
+            app,
+            token,
+            directory,
+            pattern
+                .filter(|pattern| !pattern.trim().is_empty())
+                .unwrap_or_else(|| "*".to_string()),
+            format_type,
+            output_directory.filter(|output| !output.trim().is_empty()),
+            in_place.unwrap_or(false),
+            recursive.unwrap_or(true),
+        )
+    })
+}
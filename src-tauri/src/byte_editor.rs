@@ -0,0 +1,141 @@
+// Hex-edit access to the active document's binary content: read a byte range
+// as a dump, overwrite bytes in place (undoable like any other edit) and
+// export the result. There is no insert or delete; the length never changes.
+// Saving to a file goes through save_content_to_file with "binary".
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+// A dump is for looking at; larger ranges are read page by page
+const MAX_READ_BYTES: usize = 64 * 1024;
+const ROW_BYTES: usize = 16;
+
+fn to_hex(bytes: &[u8], separator: &str) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn printable(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| {
+            if b.is_ascii_graphic() || *b == b' ' {
+                *b as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+// Hex digits with optional whitespace, `:`/`-` separators or 0x prefixes
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: String = text
+        .split([' ', '\t', '\r', '\n', ':', '-', ','])
+        .map(|token| token.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+    if digits.is_empty() {
+        return Err("No bytes given".to_string());
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("Odd number of hex digits".to_string());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| format!("Invalid hex byte: {}", &digits[i..i + 2]))
+        })
+        .collect()
+}
+
+// `length` bytes from `offset` as a hex dump: 16-byte rows with their offset,
+// hex and printable characters
+#[tauri::command]
+pub fn read_bytes(app: AppHandle, offset: usize, length: Option<usize>) -> Result<Value, String> {
+    let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let bytes = storage
+        .active()
+        .binary_content
+        .as_deref()
+        .ok_or_else(|| "No binary content stored".to_string())?;
+    if offset > bytes.len() {
+        return Err(format!("Offset {} is past the end ({} bytes)", offset, bytes.len()));
+    }
+    let end = offset
+        .saturating_add(length.unwrap_or(256).min(MAX_READ_BYTES))
+        .min(bytes.len());
+    let range = &bytes[offset..end];
+    let rows: Vec<Value> = range
+        .chunks(ROW_BYTES)
+        .enumerate()
+        .map(|(index, row)| {
+            json!({
+                "offset": offset + index * ROW_BYTES,
+                "address": format!("{:08x}", offset + index * ROW_BYTES),
+                "hex": to_hex(row, " "),
+                "ascii": printable(row)
+            })
+        })
+        .collect();
+    Ok(json!({
+        "offset": offset,
+        "length": range.len(),
+        "total": bytes.len(),
+        "hex": to_hex(range, ""),
+        "rows": rows
+    }))
+}
+
+// Overwrites bytes from `offset` with `hex` (e.g. "de ad be ef"). The range
+// must lie within the content; the overwritten bytes are returned and the
+// edit can be undone.
+#[tauri::command]
+pub fn write_bytes(app: AppHandle, offset: usize, hex: String) -> Result<Value, String> {
+    let bytes = parse_hex(&hex)?;
+    let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let document = storage.active_mut();
+    let previous = document.edit_bytes(offset, &bytes)?;
+    let info = document.info();
+    Ok(json!({
+        "offset": offset,
+        "written": bytes.len(),
+        "previous": to_hex(&previous, " "),
+        "current": to_hex(&bytes, " "),
+        "binary_length": info.binary_length,
+        "undo_steps": info.undo_steps,
+        "redo_steps": info.redo_steps
+    }))
+}
+
+fn export_bytes_blocking(app: &AppHandle, encoding: &str) -> Result<String, String> {
+    let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let bytes = storage
+        .active()
+        .binary_content
+        .as_deref()
+        .ok_or_else(|| "No binary content stored".to_string())?;
+    match encoding {
+        "base64" => Ok(STANDARD.encode(bytes)),
+        "hex" => Ok(to_hex(bytes, "")),
+        "data-uri" => {
+            let mime = crate::detect::sniff_magic(bytes)
+                .map(|detection| detection.mime)
+                .unwrap_or("application/octet-stream");
+            Ok(format!("data:{};base64,{}", mime, STANDARD.encode(bytes)))
+        }
+        _ => Err(format!("Unknown encoding: {}; use base64, hex or data-uri", encoding)),
+    }
+}
+
+// The binary content, edits included, as base64 (default), hex or a data URI
+#[tauri::command]
+pub async fn export_bytes(app: AppHandle, encoding: Option<String>) -> Result<String, String> {
+    let encoding = encoding.unwrap_or_else(|| "base64".to_string());
+    crate::run_blocking("export_bytes", move || export_bytes_blocking(&app, &encoding)).await
+}
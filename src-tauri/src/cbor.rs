@@ -0,0 +1,282 @@
+// Minimal CBOR (RFC 8949) reader for the binary structures other decoders
+// run into, such as WebAuthn attestation objects and COSE keys. Items keep
+// their CBOR types (integer map keys, byte strings, tags) so callers can
+// interpret them before turning them into JSON.
+use serde_json::{json, Map, Value};
+
+// Guard against maliciously deep nesting
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Unsigned(u64),
+    // Stored as the value itself, i.e. -1 - n for the encoded n
+    Negative(i128),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Item>),
+    Map(Vec<(Item, Item)>),
+    Tag(u64, Box<Item>),
+    Bool(bool),
+    Null,
+    Undefined,
+    Simple(u8),
+    Float(f64),
+}
+
+impl Item {
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            Item::Unsigned(n) => Some(*n as i128),
+            Item::Negative(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Item::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Item::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    // Map lookup by text key
+    pub fn get(&self, key: &str) -> Option<&Item> {
+        self.entries().find(|(k, _)| k.as_text() == Some(key)).map(|(_, v)| v)
+    }
+
+    // Map lookup by integer key, as COSE uses
+    pub fn get_int(&self, key: i128) -> Option<&Item> {
+        self.entries().find(|(k, _)| k.as_int() == Some(key)).map(|(_, v)| v)
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (&Item, &Item)> {
+        let entries: &[(Item, Item)] = match self {
+            Item::Map(entries) => entries,
+            _ => &[],
+        };
+        entries.iter().map(|(k, v)| (k, v))
+    }
+
+    // JSON rendering: byte strings as hex, map keys as their text or number,
+    // tags as {"tag", "value"}
+    pub fn to_json(&self) -> Value {
+        match self {
+            Item::Unsigned(n) => json!(n),
+            Item::Negative(n) => match i64::try_from(*n) {
+                Ok(n) => json!(n),
+                Err(_) => json!(n.to_string()),
+            },
+            Item::Bytes(bytes) => json!(hex(bytes)),
+            Item::Text(text) => json!(text),
+            Item::Array(items) => Value::Array(items.iter().map(Item::to_json).collect()),
+            Item::Map(entries) => {
+                let mut object = Map::new();
+                for (key, value) in entries {
+                    object.insert(key.key_string(), value.to_json());
+                }
+                Value::Object(object)
+            }
+            Item::Tag(tag, value) => json!({ "tag": tag, "value": value.to_json() }),
+            Item::Bool(b) => json!(b),
+            Item::Null => Value::Null,
+            Item::Undefined => json!("undefined"),
+            Item::Simple(n) => json!(format!("simple({})", n)),
+            Item::Float(f) if f.is_finite() => json!(f),
+            Item::Float(f) => json!(f.to_string()),
+        }
+    }
+
+    pub fn key_string(&self) -> String {
+        match self {
+            Item::Text(text) => text.clone(),
+            Item::Unsigned(n) => n.to_string(),
+            Item::Negative(n) => n.to_string(),
+            Item::Bytes(bytes) => format!("h'{}'", hex(bytes)),
+            other => other.to_json().to_string(),
+        }
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, count: usize) -> Result<&[u8], String> {
+        if self.data.len() - self.offset < count {
+            return Err(format!("CBOR data ends early at offset {}", self.offset));
+        }
+        let bytes = &self.data[self.offset..self.offset + count];
+        self.offset += count;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn argument(&mut self, info: u8) -> Result<u64, String> {
+        Ok(match info {
+            0..=23 => info as u64,
+            24 => self.byte()? as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().expect("2 bytes")) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes")) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes")),
+            _ => return Err(format!("Invalid CBOR length encoding at offset {}", self.offset - 1)),
+        })
+    }
+
+    fn length(&mut self, info: u8) -> Result<usize, String> {
+        let length = self.argument(info)?;
+        // Every element takes at least a byte, so longer can't be genuine
+        if length > (self.data.len() - self.offset) as u64 {
+            return Err(format!("CBOR length {} runs past the end of the data", length));
+        }
+        Ok(length as usize)
+    }
+
+    fn at_break(&mut self) -> bool {
+        if self.data.get(self.offset) == Some(&0xff) {
+            self.offset += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Concatenated chunks of an indefinite-length byte or text string
+    fn chunks(&mut self, major: u8, depth: usize) -> Result<Vec<u8>, String> {
+        let mut joined = Vec::new();
+        while !self.at_break() {
+            match self.item(depth + 1)? {
+                Item::Bytes(bytes) if major == 2 => joined.extend(bytes),
+                Item::Text(text) if major == 3 => joined.extend(text.into_bytes()),
+                _ => return Err("Mismatched chunk in indefinite-length CBOR string".to_string()),
+            }
+        }
+        Ok(joined)
+    }
+
+    fn item(&mut self, depth: usize) -> Result<Item, String> {
+        if depth > MAX_DEPTH {
+            return Err("CBOR nesting too deep".to_string());
+        }
+        let initial = self.byte()?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        Ok(match (major, info) {
+            (0, _) => Item::Unsigned(self.argument(info)?),
+            (1, _) => Item::Negative(-1 - self.argument(info)? as i128),
+            (2, 31) => Item::Bytes(self.chunks(2, depth)?),
+            (2, _) => {
+                let length = self.length(info)?;
+                Item::Bytes(self.take(length)?.to_vec())
+            }
+            (3, _) => {
+                let bytes = if info == 31 {
+                    self.chunks(3, depth)?
+                } else {
+                    let length = self.length(info)?;
+                    self.take(length)?.to_vec()
+                };
+                Item::Text(String::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8 in CBOR text: {}", e))?)
+            }
+            (4, 31) => {
+                let mut items = Vec::new();
+                while !self.at_break() {
+                    items.push(self.item(depth + 1)?);
+                }
+                Item::Array(items)
+            }
+            (4, _) => {
+                let length = self.length(info)?;
+                let items = (0..length).map(|_| self.item(depth + 1)).collect::<Result<_, _>>()?;
+                Item::Array(items)
+            }
+            (5, 31) => {
+                let mut entries = Vec::new();
+                while !self.at_break() {
+                    entries.push((self.item(depth + 1)?, self.item(depth + 1)?));
+                }
+                Item::Map(entries)
+            }
+            (5, _) => {
+                let length = self.length(info)?;
+                let entries = (0..length)
+                    .map(|_| Ok((self.item(depth + 1)?, self.item(depth + 1)?)))
+                    .collect::<Result<_, String>>()?;
+                Item::Map(entries)
+            }
+            (6, _) => {
+                let tag = self.argument(info)?;
+                Item::Tag(tag, Box::new(self.item(depth + 1)?))
+            }
+            (7, 20) => Item::Bool(false),
+            (7, 21) => Item::Bool(true),
+            (7, 22) => Item::Null,
+            (7, 23) => Item::Undefined,
+            (7, 24) => Item::Simple(self.byte()?),
+            (7, 25) => Item::Float(half_to_f64(u16::from_be_bytes(
+                self.take(2)?.try_into().expect("2 bytes"),
+            ))),
+            (7, 26) => Item::Float(f32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes")) as f64),
+            (7, 27) => Item::Float(f64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes"))),
+            (7, 0..=19) => Item::Simple(info),
+            _ => {
+                return Err(format!(
+                    "Unexpected CBOR byte 0x{:02x} at offset {}",
+                    initial,
+                    self.offset - 1
+                ))
+            }
+        })
+    }
+}
+
+fn half_to_f64(half: u16) -> f64 {
+    let exponent = (half >> 10) & 0x1f;
+    let mantissa = (half & 0x3ff) as f64;
+    let magnitude = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25),
+    };
+    if half & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+// Decodes the first item in `data` and returns it with the number of bytes
+// it took, for formats that put more data after a CBOR item
+pub fn decode_prefix(data: &[u8]) -> Result<(Item, usize), String> {
+    let mut reader = Reader { data, offset: 0 };
+    let item = reader.item(0)?;
+    Ok((item, reader.offset))
+}
+
+// Decodes `data` as exactly one CBOR item
+pub fn decode(data: &[u8]) -> Result<Item, String> {
+    let (item, used) = decode_prefix(data)?;
+    if used != data.len() {
+        return Err(format!(
+            "{} bytes of trailing data after the CBOR item",
+            data.len() - used
+        ));
+    }
+    Ok(item)
+}
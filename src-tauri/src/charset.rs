@@ -0,0 +1,122 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::fs::File;
+use std::io::Read;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+// Enough bytes for the detector to settle on a guess without reading whole files
+const SNIFF_SIZE: usize = 64 * 1024;
+
+pub struct Detected {
+    pub encoding: &'static Encoding,
+    pub bom_length: usize,
+    pub confident: bool,
+}
+
+pub fn detect_encoding(bytes: &[u8]) -> Detected {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        return Detected { encoding, bom_length, confident: true };
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        return Detected { encoding: UTF_8, bom_length: 0, confident: true };
+    }
+
+    // BOM-less UTF-16 shows up as NUL bytes in every other position
+    let sample = &bytes[..bytes.len().min(SNIFF_SIZE)];
+    let even_nuls = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_nuls = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+    let half = sample.len() / 2;
+    if half > 0 && odd_nuls * 3 > half && even_nuls * 10 < half {
+        return Detected { encoding: UTF_16LE, bom_length: 0, confident: false };
+    }
+    if half > 0 && even_nuls * 3 > half && odd_nuls * 10 < half {
+        return Detected { encoding: UTF_16BE, bom_length: 0, confident: false };
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(sample, sample.len() == bytes.len());
+    let (encoding, confident) = detector.guess_assess(None, true);
+    Detected { encoding, bom_length: 0, confident }
+}
+
+// Decode bytes to UTF-8, using the requested encoding label or falling back to detection
+pub fn decode_to_utf8(bytes: &[u8], label: Option<&str>) -> Result<(String, &'static str, bool), String> {
+    let encoding = match label.filter(|l| !l.is_empty() && *l != "auto") {
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| format!("Unknown character encoding: {}", label))?,
+        None => detect_encoding(bytes).encoding,
+    };
+
+    let (decoded, used, had_errors) = encoding.decode(bytes);
+    Ok((decoded.into_owned(), used.name(), had_errors))
+}
+
+fn detect_file_encoding_blocking(file_path: String) -> Result<serde_json::Value, String> {
+    let mut file = File::open(&file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut buffer = vec![0u8; SNIFF_SIZE];
+    let read = file.read(&mut buffer).map_err(|e| format!("Failed to read file: {}", e))?;
+    buffer.truncate(read);
+
+    let detected = detect_encoding(&buffer);
+    Ok(serde_json::json!({
+        "encoding": detected.encoding.name(),
+        "has_bom": detected.bom_length > 0,
+        "confident": detected.confident
+    }))
+}
+
+#[tauri::command]
+pub async fn detect_file_encoding(file_path: String) -> Result<serde_json::Value, String> {
+    crate::run_blocking("detect_file_encoding", move || detect_file_encoding_blocking(file_path)).await
+}
+
+fn transcode_to_utf8_blocking(
+    file_path: Option<String>,
+    encoding: Option<String>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    // The result goes to the document that was active when the call was made;
+    // the lock is only held to read the input and store the result
+    let document_id = state.lock().map_err(|e| e.to_string())?.active_id;
+    let bytes = match file_path.filter(|p| !p.is_empty()) {
+        Some(path) => std::fs::read(&path).map_err(|e| format!("Failed to read file: {}", e))?,
+        None => state
+            .lock()
+            .map_err(|e| e.to_string())?
+            .document(document_id)
+            .and_then(|document| document.binary_content.clone())
+            .ok_or_else(|| "No binary content stored to transcode".to_string())?,
+    };
+
+    let (content, used, had_errors) = decode_to_utf8(&bytes, encoding.as_deref())?;
+    let length = content.len();
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let document = storage
+        .document_mut(document_id)
+        .ok_or_else(|| "The document was closed while it was being transcoded".to_string())?;
+    document.raw_content = Some(content.into());
+    document.formatted_content = None;
+    document.clear_history();
+
+    Ok(serde_json::json!({
+        "success": true,
+        "source_encoding": used,
+        "had_replacement_characters": had_errors,
+        "length": length
+    }))
+}
+
+#[tauri::command]
+pub async fn transcode_to_utf8(
+    app: AppHandle,
+    file_path: Option<String>,
+    encoding: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("transcode_to_utf8", move || {
+        transcode_to_utf8_blocking(file_path, encoding, app.state::<AppState>().inner())
+    })
+    .await
+}
@@ -0,0 +1,225 @@
+use crc::{Crc, CRC_32_ISCSI, CRC_32_ISO_HDLC, CRC_64_XZ};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use tauri::{AppHandle, Manager};
+use xxhash_rust::{xxh3::Xxh3, xxh32::Xxh32, xxh64::Xxh64};
+
+use crate::jobs::{spawn_job, CancelToken};
+use crate::progress::ProgressReporter;
+use crate::AppState;
+
+const CHECKSUM_CHUNK_SIZE: usize = 1024 * 1024;
+
+pub const CHECKSUM_PROGRESS_EVENT: &str = "checksum-progress";
+
+static CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+static CRC32C: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+static CRC64: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+
+// Adler-32 as used by zlib; the crc crate doesn't cover it
+struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    const MOD: u32 = 65_521;
+
+    fn new() -> Self {
+        Adler32 { a: 1, b: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        // 5552 is the largest block that can't overflow u32 before reducing
+        for block in data.chunks(5552) {
+            for byte in block {
+                self.a += u32::from(*byte);
+                self.b += self.a;
+            }
+            self.a %= Self::MOD;
+            self.b %= Self::MOD;
+        }
+    }
+
+    fn finalize(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+enum Hasher {
+    Md5(Md5),
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+    Crc32(crc::Digest<'static, u32>),
+    Crc32c(crc::Digest<'static, u32>),
+    Crc64(crc::Digest<'static, u64>),
+    Adler32(Adler32),
+    Xxh32(Box<Xxh32>),
+    Xxh64(Box<Xxh64>),
+    Xxh3(Box<Xxh3>),
+}
+
+impl Hasher {
+    fn new(algorithm: &str) -> Result<Self, String> {
+        match algorithm {
+            "md5" => Ok(Hasher::Md5(Md5::new())),
+            "sha256" | "sha-256" => Ok(Hasher::Sha256(Sha256::new())),
+            "blake3" => Ok(Hasher::Blake3(Box::new(blake3::Hasher::new()))),
+            "crc32" => Ok(Hasher::Crc32(CRC32.digest())),
+            "crc32c" => Ok(Hasher::Crc32c(CRC32C.digest())),
+            "crc64" => Ok(Hasher::Crc64(CRC64.digest())),
+            "adler32" => Ok(Hasher::Adler32(Adler32::new())),
+            "xxh32" => Ok(Hasher::Xxh32(Box::new(Xxh32::new(0)))),
+            "xxh64" => Ok(Hasher::Xxh64(Box::new(Xxh64::new(0)))),
+            "xxh3" => Ok(Hasher::Xxh3(Box::new(Xxh3::new()))),
+            _ => Err(format!("Unknown checksum algorithm: {}", algorithm)),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Md5(h) => h.update(data),
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+            Hasher::Crc32(h) | Hasher::Crc32c(h) => h.update(data),
+            Hasher::Crc64(h) => h.update(data),
+            Hasher::Adler32(h) => h.update(data),
+            Hasher::Xxh32(h) => h.update(data),
+            Hasher::Xxh64(h) => h.update(data),
+            Hasher::Xxh3(h) => h.update(data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Hasher::Md5(h) => to_hex(&h.finalize()),
+            Hasher::Sha256(h) => to_hex(&h.finalize()),
+            Hasher::Blake3(h) => h.finalize().to_hex().to_string(),
+            Hasher::Crc32(h) | Hasher::Crc32c(h) => format!("{:08x}", h.finalize()),
+            Hasher::Crc64(h) => format!("{:016x}", h.finalize()),
+            Hasher::Adler32(h) => format!("{:08x}", h.finalize()),
+            Hasher::Xxh32(h) => format!("{:08x}", h.digest()),
+            Hasher::Xxh64(h) => format!("{:016x}", h.digest()),
+            Hasher::Xxh3(h) => format!("{:016x}", h.digest()),
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn build_hashers(algorithms: &[String]) -> Result<Vec<(String, Hasher)>, String> {
+    if algorithms.is_empty() {
+        return Err("No checksum algorithms selected".to_string());
+    }
+
+    let mut hashers = Vec::new();
+    for algorithm in algorithms.iter() {
+        let name = algorithm.to_lowercase();
+        hashers.push((name.clone(), Hasher::new(&name)?));
+    }
+    Ok(hashers)
+}
+
+fn finalize_all(hashers: Vec<(String, Hasher)>) -> serde_json::Map<String, serde_json::Value> {
+    let mut checksums = serde_json::Map::new();
+    for (name, hasher) in hashers {
+        checksums.insert(name, serde_json::Value::String(hasher.finalize_hex()));
+    }
+    checksums
+}
+
+fn compute_checksum_blocking(
+    text: String,
+    algorithms: Vec<String>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let mut hashers = build_hashers(&algorithms)?;
+
+    let content = if text.is_empty() {
+        let storage = state.lock().map_err(|e| e.to_string())?;
+        storage.active().raw_content.as_deref().unwrap_or_default().to_string()
+    } else {
+        text
+    };
+    for (_, hasher) in hashers.iter_mut() {
+        hasher.update(content.as_bytes());
+    }
+
+    Ok(serde_json::json!({
+        "length": content.len(),
+        "checksums": finalize_all(hashers)
+    }))
+}
+
+#[tauri::command]
+pub async fn compute_checksum(
+    app: AppHandle,
+    text: String,
+    algorithms: Vec<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("compute_checksum", move || {
+        compute_checksum_blocking(text, algorithms, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+fn compute_file_checksum_blocking(
+    app: &AppHandle,
+    token: &CancelToken,
+    file_path: String,
+    algorithms: Vec<String>,
+) -> Result<serde_json::Value, String> {
+    let mut hashers = build_hashers(&algorithms)?;
+
+    let mut file = File::open(&file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let total_bytes = file
+        .metadata()
+        .map_err(|e| format!("Failed to get file metadata: {}", e))?
+        .len();
+
+    // Read in fixed-size chunks so multi-GB files never have to fit in memory
+    let mut buffer = vec![0u8; CHECKSUM_CHUNK_SIZE];
+    let mut processed: u64 = 0;
+    let mut reporter = ProgressReporter::new(
+        app,
+        CHECKSUM_PROGRESS_EVENT,
+        total_bytes,
+        serde_json::json!({ "file_path": file_path }),
+    );
+    loop {
+        token.check()?;
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        for (_, hasher) in hashers.iter_mut() {
+            hasher.update(&buffer[..read]);
+        }
+        processed += read as u64;
+        reporter.update(processed);
+    }
+
+    Ok(serde_json::json!({
+        "file_path": file_path,
+        "file_size": processed,
+        "checksums": finalize_all(hashers)
+    }))
+}
+
+// Hashing a multi-GB file takes a while, so it runs as a cancellable job
+#[tauri::command]
+pub fn compute_file_checksum(
+    app: AppHandle,
+    file_path: String,
+    algorithms: Vec<String>,
+) -> Result<u64, String> {
+    spawn_job(&app, "checksum", move |app, token| {
+        compute_file_checksum_blocking(app, token, file_path, algorithms)
+    })
+}
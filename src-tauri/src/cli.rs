@@ -0,0 +1,126 @@
+// Headless entry point sharing the formatter registry with the app:
+//
+//   devmate fmt json < response.json
+//   devmate fmt yaml config.yaml
+//   devmate jwt decode eyJhbGciOi...
+//   devmate detect payload.txt
+//   devmate formats
+//
+// Input comes from a file argument or stdin, output goes to stdout and errors
+// to stderr, so it composes in scripts and CI. Plugin formatters aren't loaded;
+// only the built-in ones are available here.
+use std::io::{Read, Write};
+
+use crate::formatters::{autodetect, FormatterRegistry};
+
+const USAGE: &str = "\
+Usage:
+  devmate fmt <format> [file]       format a file or stdin (see `devmate formats`)
+  devmate jwt decode [token]        decode a JWT given as an argument or on stdin
+  devmate detect [file]             guess the format of a file or stdin
+  devmate formats                   list the available formats
+  devmate help                      show this message
+
+Run without arguments (or with file paths) to start the app.";
+
+const SUBCOMMANDS: &[&str] = &["fmt", "jwt", "detect", "formats", "help", "--help", "-h"];
+
+// Whether the arguments ask for the CLI rather than the app
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    args.get(1)
+        .is_some_and(|command| SUBCOMMANDS.contains(&command.as_str()))
+}
+
+fn read_input(path: Option<&String>) -> Result<String, String> {
+    match path.filter(|path| path.as_str() != "-") {
+        Some(path) => {
+            let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            Ok(crate::charset::decode_to_utf8(&bytes, None)?.0)
+        }
+        None => {
+            let mut bytes = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|e| format!("Failed to read stdin: {}", e))?;
+            Ok(crate::charset::decode_to_utf8(&bytes, None)?.0)
+        }
+    }
+}
+
+fn write_output(text: &str) -> Result<(), String> {
+    let mut stdout = std::io::stdout().lock();
+    let newline: &[u8] = if text.ends_with('\n') { b"" } else { b"\n" };
+    stdout
+        .write_all(text.as_bytes())
+        .and_then(|_| stdout.write_all(newline))
+        .and_then(|_| stdout.flush())
+        .map_err(|e| format!("Failed to write output: {}", e))
+}
+
+fn format_with(registry: &FormatterRegistry, format_type: &str, input: &str) -> Result<(), String> {
+    let formatter = registry
+        .get(format_type)
+        .ok_or_else(|| format!("Unknown format {}; run `devmate formats` for the list", format_type))?;
+    write_output(&formatter.format(input)?)
+}
+
+// Runs the CLI and returns the process exit code: 0 on success, 1 when the
+// input couldn't be processed, 2 for usage errors
+pub fn run(args: &[String]) -> i32 {
+    let registry = FormatterRegistry::with_builtins();
+    let command = args.get(1).map(String::as_str).unwrap_or("help");
+    let rest = &args[2.min(args.len())..];
+
+    let outcome = match command {
+        "fmt" => match rest.first() {
+            Some(format_type) => read_input(rest.get(1)).and_then(|input| format_with(&registry, format_type, &input)),
+            None => return usage_error("fmt needs a format, e.g. `devmate fmt json`"),
+        },
+        "jwt" => match rest.first().map(String::as_str) {
+            Some("decode") => {
+                let token = match rest.get(1) {
+                    Some(token) if token != "-" => Ok(token.clone()),
+                    _ => read_input(None),
+                };
+                token.and_then(|token| format_with(&registry, "jwt", token.trim()))
+            }
+            _ => return usage_error("Usage: devmate jwt decode [token]"),
+        },
+        "detect" => read_input(rest.first()).and_then(|input| {
+            let guesses: Vec<serde_json::Value> = autodetect::guess_formats(&input, true, &registry)
+                .iter()
+                .map(|guess| guess.to_json())
+                .collect();
+            let text = serde_json::to_string_pretty(&guesses).map_err(|e| e.to_string())?;
+            write_output(&text)
+        }),
+        "formats" => {
+            let lines: Vec<String> = registry
+                .describe()
+                .iter()
+                .map(|formatter| {
+                    format!(
+                        "{:<14} {}",
+                        formatter["id"].as_str().unwrap_or_default(),
+                        formatter["display_name"].as_str().unwrap_or_default()
+                    )
+                })
+                .collect();
+            write_output(&lines.join("\n"))
+        }
+        _ => write_output(USAGE),
+    };
+
+    match outcome {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("devmate: {}", error);
+            1
+        }
+    }
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("devmate: {}\n\n{}", message, USAGE);
+    2
+}
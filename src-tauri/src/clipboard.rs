@@ -0,0 +1,74 @@
+// Clipboard round trips that stay in the backend: the clipboard is read into
+// the active document and formatted there, and results are copied out of
+// storage, so huge payloads never pass through the webview.
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::responses::FormatJobResult;
+use crate::AppState;
+
+// Replaces the active document's raw content with the clipboard text and formats it
+pub fn format_clipboard_blocking(app: &AppHandle, format_type: String) -> Result<FormatJobResult, String> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+    if text.is_empty() {
+        return Err("Clipboard has no text".to_string());
+    }
+    {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let document = storage.active_mut();
+        document.raw_content = Some(text.into());
+        document.formatted_content = None;
+        document.binary_content = None;
+        document.clear_history();
+    }
+
+    // An empty input tells run_formatter to use the stored raw content
+    let (formatted, store) = crate::run_formatter(app, String::new(), &format_type)?;
+    let formatted_length = formatted.len();
+    if store {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let document = storage.active_mut();
+        document.formatted_content = Some(formatted);
+        document.format_type = Some(format_type.clone());
+    }
+    Ok(FormatJobResult {
+        format_type,
+        formatted_length,
+        stored: store,
+    })
+}
+
+#[tauri::command]
+pub async fn format_from_clipboard(app: AppHandle, format_type: String) -> Result<FormatJobResult, String> {
+    crate::run_blocking("format_from_clipboard", move || {
+        let format_type = crate::settings::resolve_format(&app, format_type);
+        format_clipboard_blocking(&app, format_type)
+    })
+    .await
+}
+
+// Copies the formatted content (or raw, with content_type "raw") and returns the bytes copied
+#[tauri::command]
+pub async fn copy_result_to_clipboard(app: AppHandle, content_type: Option<String>) -> Result<usize, String> {
+    crate::run_blocking("copy_result_to_clipboard", move || {
+        let content = {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            let document = storage.active();
+            match content_type.as_deref().unwrap_or("formatted") {
+                "raw" => document.raw_content.clone(),
+                "formatted" => document.formatted_content.clone(),
+                _ => return Err("Invalid content type".to_string()),
+            }
+            .ok_or_else(|| "No content stored".to_string())?
+        };
+        // The clipboard API takes ownership of the text, so copy outside the lock
+        app.clipboard()
+            .write_text(content.to_string())
+            .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+        Ok(content.len())
+    })
+    .await
+}
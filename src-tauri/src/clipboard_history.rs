@@ -0,0 +1,256 @@
+// Opt-in clipboard history. While enabled, a background thread polls the
+// system clipboard and keeps the most recent distinct texts in memory only.
+// Entries that look like credentials are masked in listings and previews but
+// can still be loaded into a document on request.
+use regex::Regex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::AppState;
+
+pub const CLIPBOARD_CAPTURED_EVENT: &str = "clipboard://captured";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(750);
+const MAX_ENTRIES: usize = 100;
+// Bigger copies are skipped rather than kept around
+const MAX_ENTRY_BYTES: usize = 8 * 1024 * 1024;
+const PREVIEW_CHARS: usize = 160;
+
+struct ClipEntry {
+    id: u64,
+    text: String,
+    captured_at: String,
+    // Why the entry is masked, if it looks like a secret
+    secret: Option<&'static str>,
+}
+
+impl ClipEntry {
+    fn summary(&self) -> serde_json::Value {
+        let preview = match self.secret {
+            Some(_) => mask(&self.text),
+            None => {
+                let preview: String = self.text.chars().take(PREVIEW_CHARS).collect();
+                if preview.len() < self.text.len() {
+                    format!("{}…", preview)
+                } else {
+                    preview
+                }
+            }
+        };
+        serde_json::json!({
+            "id": self.id,
+            "preview": preview,
+            "length": self.text.len(),
+            "captured_at": self.captured_at,
+            "masked": self.secret.is_some(),
+            "secret_kind": self.secret
+        })
+    }
+}
+
+#[derive(Default)]
+struct HistoryState {
+    // Newest first
+    entries: VecDeque<ClipEntry>,
+    next_id: u64,
+    last_hash: Option<blake3::Hash>,
+}
+
+#[derive(Default)]
+pub struct ClipboardHistory {
+    enabled: AtomicBool,
+    // Bumped on every enable, so a watcher from an earlier enable exits
+    generation: AtomicU64,
+    state: Mutex<HistoryState>,
+}
+
+fn secret_patterns() -> &'static [(&'static str, Regex)] {
+    static PATTERNS: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            ("private-key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+            ("jwt", r"\beyJ[A-Za-z0-9_-]{5,}\.eyJ[A-Za-z0-9_-]{5,}\.[A-Za-z0-9_-]*"),
+            ("aws-access-key", r"\b(?:AKIA|ASIA)[A-Z0-9]{16}\b"),
+            ("github-token", r"\b(?:ghp|gho|ghu|ghs|ghr)_[A-Za-z0-9]{36}\b|\bgithub_pat_[A-Za-z0-9_]{40,}"),
+            ("slack-token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+            ("api-key", r"\bsk-[A-Za-z0-9_-]{20,}"),
+            ("bearer-token", r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]{20,}=*"),
+            (
+                "credential",
+                r#"(?i)\b(?:password|passwd|pwd|secret|api[_-]?key|access[_-]?token|client[_-]?secret)\b["']?\s*[:=]\s*["']?[^\s"']{6,}"#,
+            ),
+        ]
+        .into_iter()
+        .map(|(kind, pattern)| (kind, Regex::new(pattern).expect("valid secret pattern")))
+        .collect()
+    })
+}
+
+// Shannon entropy in bits per character
+fn entropy(text: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    for b in text.bytes() {
+        counts[b as usize] += 1;
+    }
+    let len = text.len() as f64;
+    counts
+        .iter()
+        .filter(|c| **c > 0)
+        .map(|c| {
+            let p = *c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// The kind of secret `text` appears to contain, if any
+pub fn detect_secret(text: &str) -> Option<&'static str> {
+    if let Some((kind, _)) = secret_patterns().iter().find(|(_, regex)| regex.is_match(text)) {
+        return Some(kind);
+    }
+    // A lone long random-looking token is most likely a key of some kind
+    let trimmed = text.trim();
+    if (32..=512).contains(&trimmed.len())
+        && !trimmed.contains(char::is_whitespace)
+        && trimmed.bytes().any(|b| b.is_ascii_digit())
+        && trimmed.bytes().any(|b| b.is_ascii_alphabetic())
+        && entropy(trimmed) >= 4.0
+    {
+        return Some("high-entropy");
+    }
+    None
+}
+
+// Keeps just enough of a secret to recognize it
+pub fn mask(text: &str) -> String {
+    let trimmed = text.trim();
+    let head: String = trimmed.chars().take(4).collect();
+    format!("{}{} ({} chars)", head, "•".repeat(8), trimmed.chars().count())
+}
+
+fn capture(app: &AppHandle, text: String) {
+    if text.trim().is_empty() || text.len() > MAX_ENTRY_BYTES {
+        return;
+    }
+    let history = app.state::<ClipboardHistory>();
+    let Ok(mut state) = history.state.lock() else {
+        return;
+    };
+    let hash = blake3::hash(text.as_bytes());
+    if state.last_hash == Some(hash) {
+        return;
+    }
+    state.last_hash = Some(hash);
+    // Copying something again moves it to the top instead of duplicating it
+    state.entries.retain(|entry| entry.text != text);
+
+    state.next_id += 1;
+    let entry = ClipEntry {
+        id: state.next_id,
+        secret: detect_secret(&text),
+        text,
+        captured_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let _ = app.emit(CLIPBOARD_CAPTURED_EVENT, entry.summary());
+    state.entries.push_front(entry);
+    state.entries.truncate(MAX_ENTRIES);
+}
+
+fn watch(app: AppHandle, generation: u64) {
+    let history = app.state::<ClipboardHistory>();
+    // Whatever is on the clipboard when watching starts isn't a new copy
+    if let Ok(text) = app.clipboard().read_text() {
+        if let Ok(mut state) = history.state.lock() {
+            state.last_hash = Some(blake3::hash(text.as_bytes()));
+        }
+    }
+    while history.enabled.load(Ordering::Relaxed) && history.generation.load(Ordering::Relaxed) == generation {
+        std::thread::sleep(POLL_INTERVAL);
+        // Non-text clipboard contents read as an error; just skip them
+        if let Ok(text) = app.clipboard().read_text() {
+            capture(&app, text);
+        }
+    }
+}
+
+// Turning the watcher off keeps the history; clear_clipboard_history drops it
+#[tauri::command]
+pub fn set_clipboard_watch(app: AppHandle, enabled: bool) -> bool {
+    let history = app.state::<ClipboardHistory>();
+    let was_enabled = history.enabled.swap(enabled, Ordering::Relaxed);
+    if enabled && !was_enabled {
+        let generation = history.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let app = app.clone();
+        std::thread::spawn(move || watch(app, generation));
+    }
+    enabled
+}
+
+// Newest first. `query` matches case-insensitively; masked entries only match
+// on their secret kind, so searching can't be used to probe their contents.
+#[tauri::command]
+pub fn list_clipboard_history(
+    query: Option<String>,
+    limit: Option<usize>,
+    history: State<ClipboardHistory>,
+) -> Result<serde_json::Value, String> {
+    let state = history.state.lock().map_err(|e| e.to_string())?;
+    let query = query.filter(|q| !q.is_empty()).map(|q| q.to_lowercase());
+    let entries: Vec<serde_json::Value> = state
+        .entries
+        .iter()
+        .filter(|entry| {
+            query.as_ref().is_none_or(|q| match entry.secret {
+                Some(kind) => kind.contains(q.as_str()),
+                None => entry.text.to_lowercase().contains(q),
+            })
+        })
+        .take(limit.unwrap_or(MAX_ENTRIES))
+        .map(ClipEntry::summary)
+        .collect();
+    Ok(serde_json::json!({
+        "watching": history.enabled.load(Ordering::Relaxed),
+        "entries": entries
+    }))
+}
+
+// Loads an entry as the active document's raw content, ready for formatting
+#[tauri::command]
+pub fn load_clipboard_entry(id: u64, history: State<ClipboardHistory>, state: State<AppState>) -> Result<usize, String> {
+    let text = {
+        let history = history.state.lock().map_err(|e| e.to_string())?;
+        history
+            .entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.text.clone())
+            .ok_or_else(|| format!("Clipboard entry {} not found", id))?
+    };
+    let length = text.len();
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let document = storage.active_mut();
+    document.raw_content = Some(text.into());
+    document.formatted_content = None;
+    document.binary_content = None;
+    document.clear_history();
+    Ok(length)
+}
+
+#[tauri::command]
+pub fn delete_clipboard_entry(id: u64, history: State<ClipboardHistory>) -> Result<bool, String> {
+    let mut state = history.state.lock().map_err(|e| e.to_string())?;
+    let before = state.entries.len();
+    state.entries.retain(|entry| entry.id != id);
+    Ok(state.entries.len() < before)
+}
+
+#[tauri::command]
+pub fn clear_clipboard_history(history: State<ClipboardHistory>) -> Result<(), String> {
+    let mut state = history.state.lock().map_err(|e| e.to_string())?;
+    state.entries.clear();
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+// Split on `delimiter` while respecting double-quoted fields ("a,b",c)
+pub fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+// The inverse of split_fields: quotes a field when it holds the delimiter, a
+// quote or a line break, doubling any quotes inside
+pub fn quote_field(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter) || field.contains(['"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn detect_delimiter(text: &str) -> char {
+    let sample: Vec<&str> = text.lines().take(20).collect();
+    [('\t', "\t"), ('|', "|"), (',', ","), (';', ";")]
+        .iter()
+        .find(|(_, pattern)| {
+            let first = sample.first().map(|l| l.matches(pattern).count()).unwrap_or(0);
+            first > 0 && sample.iter().all(|l| l.matches(pattern).count() == first)
+        })
+        .map(|(c, _)| *c)
+        .unwrap_or(',')
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().count()
+}
+
+pub fn align(text: &str, delimiter: char, separator: &str) -> String {
+    let rows: Vec<Vec<String>> = text.lines().map(|line| split_fields(line, delimiter)).collect();
+    let column_count = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+
+    let mut widths = vec![0; column_count];
+    for row in rows.iter() {
+        for (i, field) in row.iter().enumerate() {
+            widths[i] = widths[i].max(display_width(field));
+        }
+    }
+
+    // Right-align purely numeric columns so digits line up
+    let numeric: Vec<bool> = (0..column_count)
+        .map(|i| {
+            rows.iter()
+                .skip(1)
+                .filter_map(|r| r.get(i))
+                .filter(|f| !f.is_empty())
+                .all(|f| f.parse::<f64>().is_ok())
+                && rows.len() > 1
+        })
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let padding = " ".repeat(widths[i] - display_width(field));
+                    if numeric[i] {
+                        format!("{}{}", padding, field)
+                    } else if i + 1 == row.len() {
+                        field.clone()
+                    } else {
+                        format!("{}{}", field, padding)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(separator)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Reverse of `align`: collapse padded columns back into delimited text
+pub fn unalign(text: &str, separator: &str, delimiter: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let fields: Vec<&str> = if separator.trim().is_empty() {
+                line.split("  ").map(str::trim).filter(|f| !f.is_empty()).collect()
+            } else {
+                line.split(separator.trim()).map(str::trim).collect()
+            };
+            fields.join(delimiter)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn align_columns_blocking(
+    mode: String,
+    delimiter: Option<String>,
+    separator: Option<String>,
+    state: &AppState,
+) -> Result<String, String> {
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let content = &snapshot.text;
+
+    let separator = separator.unwrap_or_else(|| "  ".to_string());
+    let result = match mode.as_str() {
+        "align" => {
+            let delimiter = match delimiter.as_deref() {
+                None | Some("") | Some("auto") => detect_delimiter(content),
+                Some("\\t") | Some("tab") => '\t',
+                Some(d) => d.chars().next().unwrap_or(','),
+            };
+            align(content, delimiter, &separator)
+        }
+        "unalign" => {
+            let delimiter = match delimiter.as_deref() {
+                None | Some("") | Some("auto") => ",".to_string(),
+                Some("\\t") | Some("tab") => "\t".to_string(),
+                Some(d) => d.to_string(),
+            };
+            unalign(content, &separator, &delimiter)
+        }
+        _ => return Err(format!("Unknown column mode: {}", mode)),
+    };
+
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.snapshot_document(&snapshot)?.edit(Slot::Formatted, result.clone());
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn align_columns(
+    app: AppHandle,
+    mode: String,
+    delimiter: Option<String>,
+    separator: Option<String>,
+) -> Result<String, String> {
+    crate::run_blocking("align_columns", move || {
+        align_columns_blocking(mode, delimiter, separator, app.state::<AppState>().inner())
+    })
+    .await
+}
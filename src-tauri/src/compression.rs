@@ -0,0 +1,181 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::{DeflateDecoder, GzDecoder, MultiGzDecoder, ZlibDecoder};
+use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use std::io::{Read, Seek, SeekFrom, Write};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+// Identify the codec from its magic bytes; raw deflate has no header and can't be sniffed
+pub fn detect_codec(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        Some("gzip")
+    } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Some("zstd")
+    } else if bytes.len() >= 2 && bytes[0] & 0x0f == 8 && (u16::from(bytes[0]) << 8 | u16::from(bytes[1])) % 31 == 0 {
+        Some("zlib")
+    } else {
+        None
+    }
+}
+
+// gzip or zstd from a file's first bytes, leaving the file at its start.
+// Only these two are checked: they have unambiguous magic numbers, and they're
+// what rotated logs and dumps come compressed with.
+pub fn sniff_file(file: &mut std::fs::File) -> Result<Option<&'static str>, String> {
+    let mut magic = [0u8; 4];
+    let mut read = 0;
+    while read < magic.len() {
+        match file.read(&mut magic[read..]).map_err(|e| format!("Failed to read file: {}", e))? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    file.seek(SeekFrom::Start(0))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(detect_codec(&magic[..read]).filter(|codec| matches!(*codec, "gzip" | "zstd")))
+}
+
+// A streaming decompressor over `reader`; concatenated gzip members (as
+// `cat a.gz b.gz` produces) are read as one stream
+pub fn decoder<'a, R: Read + 'a>(reader: R, codec: &str) -> Result<Box<dyn Read + 'a>, String> {
+    match codec {
+        "gzip" => Ok(Box::new(MultiGzDecoder::new(reader))),
+        "zstd" => Ok(Box::new(
+            zstd::stream::read::Decoder::new(reader).map_err(|e| format!("Failed to read zstd data: {}", e))?,
+        )),
+        _ => Err(format!("Can't stream {} data", codec)),
+    }
+}
+
+pub fn compress_bytes(data: &[u8], codec: &str) -> Result<Vec<u8>, String> {
+    let map_err = |e: std::io::Error| format!("Failed to compress with {}: {}", codec, e);
+    match codec {
+        "gzip" => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).map_err(map_err)?;
+            encoder.finish().map_err(map_err)
+        }
+        "zlib" => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).map_err(map_err)?;
+            encoder.finish().map_err(map_err)
+        }
+        "deflate" => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).map_err(map_err)?;
+            encoder.finish().map_err(map_err)
+        }
+        "brotli" => {
+            let mut output = Vec::new();
+            {
+                let mut writer = brotli::CompressorWriter::new(&mut output, 4096, 9, 22);
+                writer.write_all(data).map_err(map_err)?;
+            }
+            Ok(output)
+        }
+        "zstd" => zstd::stream::encode_all(data, 0).map_err(map_err),
+        _ => Err(format!("Unknown compression codec: {}", codec)),
+    }
+}
+
+pub fn decompress_bytes(data: &[u8], codec: &str) -> Result<Vec<u8>, String> {
+    let map_err = |e: std::io::Error| format!("Failed to decompress {} data: {}", codec, e);
+    let mut output = Vec::new();
+    match codec {
+        "gzip" => GzDecoder::new(data).read_to_end(&mut output).map_err(map_err)?,
+        "zlib" => ZlibDecoder::new(data).read_to_end(&mut output).map_err(map_err)?,
+        "deflate" => DeflateDecoder::new(data).read_to_end(&mut output).map_err(map_err)?,
+        "brotli" => brotli::Decompressor::new(data, 4096)
+            .read_to_end(&mut output)
+            .map_err(map_err)?,
+        "zstd" => return zstd::stream::decode_all(data).map_err(map_err),
+        _ => return Err(format!("Unknown compression codec: {}", codec)),
+    };
+    Ok(output)
+}
+
+fn content_or_stored(text: String, state: &AppState) -> Result<String, String> {
+    if text.is_empty() {
+        let storage = state.lock().map_err(|e| e.to_string())?;
+        Ok(storage.active().raw_content.as_deref().unwrap_or_default().to_string())
+    } else {
+        Ok(text)
+    }
+}
+
+fn compress_content_blocking(
+    text: String,
+    codec: String,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let content = content_or_stored(text, state)?;
+    let compressed = compress_bytes(content.as_bytes(), &codec)?;
+
+    Ok(serde_json::json!({
+        "codec": codec,
+        "original_size": content.len(),
+        "compressed_size": compressed.len(),
+        "base64": STANDARD.encode(&compressed)
+    }))
+}
+
+#[tauri::command]
+pub async fn compress_content(
+    app: AppHandle,
+    text: String,
+    codec: String,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("compress_content", move || {
+        compress_content_blocking(text, codec, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+fn decompress_content_blocking(
+    text: String,
+    codec: Option<String>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let content = content_or_stored(text, state)?;
+    let compact: String = content.split_whitespace().collect();
+    let data = STANDARD
+        .decode(compact.as_bytes())
+        .map_err(|e| format!("Invalid base64 encoding: {}", e))?;
+
+    let codec = match codec.filter(|c| !c.is_empty() && c != "auto") {
+        Some(codec) => codec,
+        None => detect_codec(&data)
+            .map(|c| c.to_string())
+            .ok_or_else(|| "Could not detect compression format; please choose a codec".to_string())?,
+    };
+
+    let decompressed = decompress_bytes(&data, &codec)?;
+    let decompressed_size = decompressed.len();
+    let (text, is_binary) = match String::from_utf8(decompressed) {
+        Ok(text) => (text, false),
+        Err(e) => (STANDARD.encode(e.as_bytes()), true),
+    };
+
+    Ok(serde_json::json!({
+        "codec": codec,
+        "compressed_size": data.len(),
+        "decompressed_size": decompressed_size,
+        "is_binary": is_binary,
+        "content": text
+    }))
+}
+
+#[tauri::command]
+pub async fn decompress_content(
+    app: AppHandle,
+    text: String,
+    codec: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("decompress_content", move || {
+        decompress_content_blocking(text, codec, app.state::<AppState>().inner())
+    })
+    .await
+}
@@ -0,0 +1,184 @@
+// A single portable file holding a devmate setup (settings, snippets, HTTP
+// environments and collections, and saved scripts) so a team can share one
+// configuration. Sections are optional,
+// so a bundle can carry just the snippets, and unknown sections or settings
+// from a newer version are skipped on import. Machine-specific settings (local
+// database paths) are never exported, and secrets only on request.
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::http_client::{self, HttpEnvironment};
+use crate::http_collections::{self, HttpCollection};
+use crate::scripting::{self, SavedScript};
+use crate::settings;
+use crate::snippets::{self, Snippet};
+
+const BUNDLE_FORMAT: &str = "devmate-config";
+const BUNDLE_VERSION: u32 = 1;
+const SECTIONS: &[&str] = &[
+    "settings",
+    "snippets",
+    "http_environments",
+    "http_collections",
+    "scripts",
+];
+
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    format: String,
+    version: u32,
+    #[serde(default)]
+    exported_at: String,
+    // Whether secret snippets and credential-like environment values are in
+    // the bundle; an overwriting import keeps local ones when they aren't
+    #[serde(default)]
+    secrets_included: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    settings: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    snippets: Option<Vec<Snippet>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    http_environments: Option<Vec<HttpEnvironment>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    http_collections: Option<Vec<HttpCollection>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scripts: Option<Vec<SavedScript>>,
+}
+
+// The chosen sections, or all of them
+fn check_sections(sections: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let Some(sections) = sections.filter(|sections| !sections.is_empty()) else {
+        return Ok(SECTIONS.iter().map(|section| section.to_string()).collect());
+    };
+    for section in &sections {
+        if !SECTIONS.contains(&section.as_str()) {
+            return Err(format!(
+                "Unknown section {}; expected one of {}",
+                section,
+                SECTIONS.join(", ")
+            ));
+        }
+    }
+    Ok(sections)
+}
+
+// Writes the chosen sections (or all of them) to `path`. Secret snippets,
+// credential-like environment variables and sensitive headers in environments
+// and collections are left out unless `include_secrets` is set. Returns what
+// was written per section.
+#[tauri::command]
+pub fn export_config_bundle(
+    app: AppHandle,
+    path: String,
+    sections: Option<Vec<String>>,
+    include_secrets: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let sections = check_sections(sections)?;
+    let include_secrets = include_secrets.unwrap_or(false);
+    let wanted = |section: &str| sections.iter().any(|wanted| wanted == section);
+
+    let mut summary = serde_json::Map::new();
+    let mut bundle = Bundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        secrets_included: include_secrets,
+        settings: None,
+        snippets: None,
+        http_environments: None,
+        http_collections: None,
+        scripts: None,
+    };
+    if wanted("settings") {
+        let values = settings::portable(&app);
+        summary.insert("settings".to_string(), values.len().into());
+        bundle.settings = Some(values);
+    }
+    if wanted("snippets") {
+        let shared = snippets::shareable(&app, include_secrets)?;
+        summary.insert("snippets".to_string(), shared.len().into());
+        bundle.snippets = Some(shared);
+    }
+    if wanted("http_environments") {
+        let (environments, withheld) = http_client::shareable_environments(&app, include_secrets)?;
+        summary.insert(
+            "http_environments".to_string(),
+            serde_json::json!({ "count": environments.len(), "withheld_values": withheld }),
+        );
+        bundle.http_environments = Some(environments);
+    }
+    if wanted("http_collections") {
+        let (collections, withheld) = http_collections::shareable(&app, include_secrets)?;
+        summary.insert(
+            "http_collections".to_string(),
+            serde_json::json!({ "count": collections.len(), "withheld_values": withheld }),
+        );
+        bundle.http_collections = Some(collections);
+    }
+    if wanted("scripts") {
+        let scripts = scripting::shareable(&app)?;
+        summary.insert("scripts".to_string(), scripts.len().into());
+        bundle.scripts = Some(scripts);
+    }
+
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| format!("Failed to serialize bundle: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(serde_json::Value::Object(summary))
+}
+
+// Applies the chosen sections (or all of those present) from the bundle at
+// `path`. `mode` "merge" (the default) folds the bundle into the local setup,
+// with the bundle winning on conflicts; "overwrite" replaces each imported
+// section. Returns what changed per section.
+#[tauri::command]
+pub fn import_config_bundle(
+    app: AppHandle,
+    path: String,
+    mode: Option<String>,
+    sections: Option<Vec<String>>,
+) -> Result<serde_json::Value, String> {
+    let overwrite = match mode.as_deref().unwrap_or("merge") {
+        "merge" => false,
+        "overwrite" => true,
+        other => return Err(format!("Unknown import mode {}; expected merge or overwrite", other)),
+    };
+    let sections = check_sections(sections)?;
+    let wanted = |section: &str| sections.iter().any(|wanted| wanted == section);
+
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let bundle: Bundle = serde_json::from_str(&text).map_err(|e| format!("Not a devmate config bundle: {}", e))?;
+    if bundle.format != BUNDLE_FORMAT {
+        return Err("Not a devmate config bundle".to_string());
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Config bundle version {} is newer than this devmate supports",
+            bundle.version
+        ));
+    }
+
+    // Settings go first: they're the only section that can be rejected as a
+    // whole, and nothing has changed yet if they are
+    let mut summary = serde_json::Map::new();
+    if let Some(values) = bundle.settings.filter(|_| wanted("settings")) {
+        let changed = settings::import(&app, values, overwrite)?;
+        summary.insert("settings".to_string(), serde_json::json!({ "changed": changed }));
+    }
+    if let Some(shared) = bundle.snippets.filter(|_| wanted("snippets")) {
+        let result = snippets::import(&app, shared, overwrite, !bundle.secrets_included)?;
+        summary.insert("snippets".to_string(), result);
+    }
+    if let Some(environments) = bundle.http_environments.filter(|_| wanted("http_environments")) {
+        let result = http_client::import_environments(&app, environments, overwrite, !bundle.secrets_included)?;
+        summary.insert("http_environments".to_string(), result);
+    }
+    if let Some(collections) = bundle.http_collections.filter(|_| wanted("http_collections")) {
+        let result = http_collections::import(&app, collections, overwrite, !bundle.secrets_included)?;
+        summary.insert("http_collections".to_string(), result);
+    }
+    if let Some(scripts) = bundle.scripts.filter(|_| wanted("scripts")) {
+        let result = scripting::import(&app, scripts, overwrite)?;
+        summary.insert("scripts".to_string(), result);
+    }
+    Ok(serde_json::Value::Object(summary))
+}
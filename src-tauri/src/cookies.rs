@@ -0,0 +1,308 @@
+// `Cookie:` and `Set-Cookie:` strings parsed into their parts and built back.
+// Set-Cookie attributes are normalized (Domain, Path, Expires, Max-Age,
+// SameSite, Secure, HttpOnly, Partitioned) and the expiry is described
+// relative to now, so "is this cookie still alive" doesn't need date math.
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::jwt::human_duration;
+use crate::AppState;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SetCookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub expires: Option<String>,
+    #[serde(default)]
+    pub max_age: Option<i64>,
+    #[serde(default)]
+    pub same_site: Option<String>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    #[serde(default)]
+    pub partitioned: bool,
+    // Attributes this parser doesn't know, kept so building round-trips
+    #[serde(default)]
+    pub other: Vec<(String, Option<String>)>,
+}
+
+fn pair(text: &str) -> (String, String) {
+    match text.split_once('=') {
+        Some((name, value)) => (name.trim().to_string(), value.trim().to_string()),
+        // A bare token is a cookie with an empty name
+        None => (String::new(), text.trim().to_string()),
+    }
+}
+
+// `name=value; name2=value2` as sent in a Cookie header
+pub fn parse_cookie_header(text: &str) -> Vec<(String, String)> {
+    text.split(';')
+        .filter(|piece| !piece.trim().is_empty())
+        .map(pair)
+        .collect()
+}
+
+pub fn parse_set_cookie(text: &str) -> SetCookie {
+    let mut pieces = text.split(';');
+    let (name, value) = pair(pieces.next().unwrap_or(""));
+    let mut cookie = SetCookie {
+        name,
+        value,
+        ..SetCookie::default()
+    };
+    for piece in pieces.filter(|piece| !piece.trim().is_empty()) {
+        let (key, value) = match piece.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim().to_string())),
+            None => (piece.trim(), None),
+        };
+        match key.to_ascii_lowercase().as_str() {
+            // A leading dot is ignored by browsers
+            "domain" => cookie.domain = value.map(|domain| domain.trim_start_matches('.').to_string()),
+            "path" => cookie.path = value,
+            "expires" => cookie.expires = value,
+            "max-age" => cookie.max_age = value.and_then(|age| age.parse().ok()),
+            "samesite" => cookie.same_site = value,
+            "secure" => cookie.secure = true,
+            "httponly" => cookie.http_only = true,
+            "partitioned" => cookie.partitioned = true,
+            _ => cookie.other.push((key.to_string(), value)),
+        }
+    }
+    cookie
+}
+
+pub fn build_set_cookie(cookie: &SetCookie) -> String {
+    let mut parts = vec![format!("{}={}", cookie.name, cookie.value)];
+    if let Some(domain) = &cookie.domain {
+        parts.push(format!("Domain={}", domain));
+    }
+    if let Some(path) = &cookie.path {
+        parts.push(format!("Path={}", path));
+    }
+    if let Some(expires) = &cookie.expires {
+        parts.push(format!("Expires={}", expires));
+    }
+    if let Some(max_age) = cookie.max_age {
+        parts.push(format!("Max-Age={}", max_age));
+    }
+    if let Some(same_site) = &cookie.same_site {
+        parts.push(format!("SameSite={}", same_site));
+    }
+    if cookie.secure {
+        parts.push("Secure".to_string());
+    }
+    if cookie.http_only {
+        parts.push("HttpOnly".to_string());
+    }
+    if cookie.partitioned {
+        parts.push("Partitioned".to_string());
+    }
+    for (key, value) in &cookie.other {
+        parts.push(match value {
+            Some(value) => format!("{}={}", key, value),
+            None => key.clone(),
+        });
+    }
+    parts.join("; ")
+}
+
+// HTTP dates ("Wed, 21 Oct 2015 07:28:00 GMT"), including the legacy
+// "Wed, 21-Oct-15 07:28:00 GMT" form
+fn parse_expires(text: &str) -> Option<DateTime<Utc>> {
+    let normalized = text.trim().replace('-', " ");
+    DateTime::parse_from_rfc2822(&normalized)
+        .ok()
+        .or_else(|| {
+            let without_day = normalized
+                .split_once(", ")
+                .map_or(normalized.as_str(), |(_, rest)| rest);
+            DateTime::parse_from_str(
+                &format!("{} +0000", without_day.trim_end_matches(" GMT")),
+                "%d %b %y %H:%M:%S %z",
+            )
+            .ok()
+        })
+        .map(|date| date.with_timezone(&Utc))
+}
+
+// When the cookie goes away, in words. Max-Age takes precedence over Expires.
+pub fn lifetime(cookie: &SetCookie, now: DateTime<Utc>) -> Value {
+    if let Some(max_age) = cookie.max_age {
+        return if max_age <= 0 {
+            json!({ "kind": "deleted", "description": "deleted immediately (Max-Age <= 0)" })
+        } else {
+            json!({
+                "kind": "max_age",
+                "expires_at": (now + chrono::Duration::seconds(max_age)).to_rfc3339(),
+                "description": format!("expires in {}", human_duration(max_age))
+            })
+        };
+    }
+    match &cookie.expires {
+        None => json!({ "kind": "session", "description": "session cookie, gone when the browser closes" }),
+        Some(expires) => match parse_expires(expires) {
+            None => json!({ "kind": "invalid", "description": format!("unparseable Expires date: {}", expires) }),
+            Some(date) => {
+                let seconds = (date - now).num_seconds();
+                json!({
+                    "kind": "expires",
+                    "expires_at": date.to_rfc3339(),
+                    "expired": seconds <= 0,
+                    "description": if seconds > 0 {
+                        format!("expires in {}", human_duration(seconds))
+                    } else {
+                        format!("expired {} ago", human_duration(seconds))
+                    }
+                })
+            }
+        },
+    }
+}
+
+// Attribute combinations browsers reject or that weaken the cookie
+pub fn warnings(cookie: &SetCookie) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let same_site = cookie.same_site.as_deref().map(str::to_ascii_lowercase);
+    if !cookie.secure {
+        warnings.push("no Secure attribute, so it's also sent over plain HTTP".to_string());
+    }
+    if !cookie.http_only {
+        warnings.push("no HttpOnly attribute, so scripts can read it".to_string());
+    }
+    match same_site.as_deref() {
+        Some("none") if !cookie.secure => {
+            warnings.push("SameSite=None is rejected by browsers without Secure".to_string())
+        }
+        Some("none" | "lax" | "strict") | None => {}
+        Some(other) => warnings.push(format!("unknown SameSite value {}", other)),
+    }
+    if cookie.partitioned && !cookie.secure {
+        warnings.push("Partitioned requires Secure".to_string());
+    }
+    if (cookie.name.starts_with("__Secure-") || cookie.name.starts_with("__Host-")) && !cookie.secure {
+        warnings.push("the name prefix requires Secure".to_string());
+    }
+    if cookie.name.starts_with("__Host-") && (cookie.domain.is_some() || cookie.path.as_deref() != Some("/")) {
+        warnings.push("__Host- cookies need Path=/ and no Domain".to_string());
+    }
+    warnings
+}
+
+pub fn describe_set_cookie(cookie: &SetCookie, now: DateTime<Utc>) -> Value {
+    let mut value = serde_json::to_value(cookie).unwrap_or_default();
+    value["same_site_effective"] = cookie
+        .same_site
+        .as_deref()
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_else(|| "lax (browser default)".to_string())
+        .into();
+    value["lifetime"] = lifetime(cookie, now);
+    value["warnings"] = warnings(cookie).into();
+    value
+}
+
+// Each line is a `Cookie:` or `Set-Cookie:` header, with or without the
+// name; lines without one are read as Set-Cookie when they carry attributes
+fn parse(text: &str) -> Result<Value, String> {
+    let now = Utc::now();
+    let mut request_cookies = Vec::new();
+    let mut response_cookies = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (name, value) = match line.split_once(':') {
+            Some((name, value)) if name.eq_ignore_ascii_case("cookie") || name.eq_ignore_ascii_case("set-cookie") => {
+                (Some(name.to_ascii_lowercase()), value.trim())
+            }
+            _ => (None, line),
+        };
+        let is_set_cookie = match name.as_deref() {
+            Some(name) => name == "set-cookie",
+            None => value.split(';').skip(1).any(|piece| {
+                let key = piece.split('=').next().unwrap_or("").trim().to_ascii_lowercase();
+                matches!(
+                    key.as_str(),
+                    "domain" | "path" | "expires" | "max-age" | "samesite" | "secure" | "httponly" | "partitioned"
+                )
+            }),
+        };
+        if is_set_cookie {
+            response_cookies.push(describe_set_cookie(&parse_set_cookie(value), now));
+        } else {
+            request_cookies.extend(
+                parse_cookie_header(value)
+                    .into_iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value })),
+            );
+        }
+    }
+    if request_cookies.is_empty() && response_cookies.is_empty() {
+        return Err("No cookies found".to_string());
+    }
+    Ok(json!({ "cookie": request_cookies, "set_cookie": response_cookies }))
+}
+
+fn cookie_text(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    match text.filter(|text| !text.is_empty()) {
+        Some(text) => Ok(text),
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            Ok(storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string())
+        }
+    }
+}
+
+// Cookies from `text` (or the active document's raw content when it's
+// empty): request cookies as name/value pairs, Set-Cookie lines with their
+// attributes, lifetime and warnings
+#[tauri::command]
+pub fn parse_cookies(app: AppHandle, text: Option<String>) -> Result<Value, String> {
+    parse(&cookie_text(&app, text)?)
+}
+
+// Builds header lines from cookies: one `Cookie:` line with every name=value
+// pair (`kind` "cookie"), or a `Set-Cookie:` line per cookie ("set-cookie",
+// the default)
+#[tauri::command]
+pub fn build_cookies(cookies: Vec<SetCookie>, kind: Option<String>) -> Result<String, String> {
+    if cookies.is_empty() {
+        return Err("No cookies to build".to_string());
+    }
+    if let Some(cookie) = cookies
+        .iter()
+        .find(|cookie| cookie.name.contains(['=', ';', ' ', ',']) || cookie.value.contains([';', '\r', '\n']))
+    {
+        return Err(format!(
+            "Cookie {} has characters that aren't allowed in a cookie",
+            cookie.name
+        ));
+    }
+    match kind.as_deref().unwrap_or("set-cookie") {
+        "cookie" => Ok(format!(
+            "Cookie: {}",
+            cookies
+                .iter()
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>()
+                .join("; ")
+        )),
+        "set-cookie" => Ok(cookies
+            .iter()
+            .map(|cookie| format!("Set-Cookie: {}", build_set_cookie(cookie)))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        other => Err(format!("Unknown cookie kind {}; use cookie or set-cookie", other)),
+    }
+}
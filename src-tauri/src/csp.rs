@@ -0,0 +1,429 @@
+// Content-Security-Policy evaluation: splits a policy into directives, flags
+// sources that weaken it ('unsafe-inline', 'unsafe-eval', wildcards, bare
+// schemes) and answers whether a directive would let a given URL load,
+// following the CSP Level 3 fallback chain (script-src-elem -> script-src ->
+// default-src, and so on) and source-matching rules.
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+const KNOWN_DIRECTIVES: &[&str] = &[
+    "default-src",
+    "script-src",
+    "script-src-elem",
+    "script-src-attr",
+    "style-src",
+    "style-src-elem",
+    "style-src-attr",
+    "img-src",
+    "font-src",
+    "connect-src",
+    "media-src",
+    "object-src",
+    "frame-src",
+    "child-src",
+    "worker-src",
+    "manifest-src",
+    "prefetch-src",
+    "base-uri",
+    "form-action",
+    "frame-ancestors",
+    "sandbox",
+    "upgrade-insecure-requests",
+    "block-all-mixed-content",
+    "require-trusted-types-for",
+    "trusted-types",
+    "report-uri",
+    "report-to",
+];
+
+pub struct Directive {
+    pub name: String,
+    pub sources: Vec<String>,
+}
+
+// Directives in order; a repeated directive is ignored by browsers, so only
+// the first is kept and the repeat is reported
+pub fn parse_policy(policy: &str, findings: &mut Vec<Value>) -> Vec<Directive> {
+    let mut directives: Vec<Directive> = Vec::new();
+    for part in policy.split(';').map(str::trim).filter(|part| !part.is_empty()) {
+        let mut tokens = part.split_whitespace();
+        let name = tokens.next().unwrap_or("").to_ascii_lowercase();
+        if directives.iter().any(|directive| directive.name == name) {
+            findings.push(finding(
+                "warning",
+                &name,
+                "repeated directive; browsers ignore all but the first",
+            ));
+            continue;
+        }
+        if !KNOWN_DIRECTIVES.contains(&name.as_str()) {
+            findings.push(finding("warning", &name, "unknown directive"));
+        }
+        directives.push(Directive {
+            name,
+            sources: tokens.map(str::to_string).collect(),
+        });
+    }
+    directives
+}
+
+fn finding(severity: &str, directive: &str, message: &str) -> Value {
+    json!({ "severity": severity, "directive": directive, "message": message })
+}
+
+fn is_nonce_or_hash(source: &str) -> bool {
+    let source = source.to_ascii_lowercase();
+    ["'nonce-", "'sha256-", "'sha384-", "'sha512-"]
+        .iter()
+        .any(|prefix| source.starts_with(prefix))
+}
+
+fn get<'a>(directives: &'a [Directive], name: &str) -> Option<&'a Directive> {
+    directives.iter().find(|directive| directive.name == name)
+}
+
+// The directive that governs `name` when it isn't present itself
+fn fallback_chain(name: &str) -> &'static [&'static str] {
+    match name {
+        "script-src-elem" | "script-src-attr" => &["script-src", "default-src"],
+        "style-src-elem" | "style-src-attr" => &["style-src", "default-src"],
+        "worker-src" => &["child-src", "script-src", "default-src"],
+        "frame-src" => &["child-src", "default-src"],
+        "child-src" | "script-src" | "style-src" | "img-src" | "font-src" | "connect-src" | "media-src"
+        | "object-src" | "manifest-src" | "prefetch-src" => &["default-src"],
+        // base-uri, form-action and frame-ancestors don't fall back
+        _ => &[],
+    }
+}
+
+pub fn effective<'a>(directives: &'a [Directive], name: &str) -> Option<&'a Directive> {
+    get(directives, name).or_else(|| fallback_chain(name).iter().find_map(|name| get(directives, name)))
+}
+
+// Weaknesses worth a reviewer's attention
+pub fn findings(directives: &[Directive], findings: &mut Vec<Value>) {
+    for directive in directives {
+        let name = directive.name.as_str();
+        let sources: Vec<String> = directive
+            .sources
+            .iter()
+            .map(|source| source.to_ascii_lowercase())
+            .collect();
+        let has = |source: &str| sources.iter().any(|candidate| candidate == source);
+        let scripts = name.starts_with("script-src") || name == "default-src";
+        let strict_dynamic = has("'strict-dynamic'");
+        let nonce_or_hash = sources.iter().any(|source| is_nonce_or_hash(source));
+        if has("'unsafe-inline'") {
+            if nonce_or_hash {
+                findings.push(finding(
+                    "info",
+                    name,
+                    "'unsafe-inline' is ignored by CSP2+ browsers because a nonce or hash is present",
+                ));
+            } else if scripts {
+                findings.push(finding(
+                    "high",
+                    name,
+                    "'unsafe-inline' allows inline scripts, defeating XSS protection",
+                ));
+            } else {
+                findings.push(finding("medium", name, "'unsafe-inline' allows inline content"));
+            }
+        }
+        if has("'unsafe-eval'") {
+            findings.push(finding("high", name, "'unsafe-eval' allows eval() and new Function()"));
+        }
+        if has("'unsafe-hashes'") {
+            findings.push(finding(
+                "medium",
+                name,
+                "'unsafe-hashes' allows matching inline event handlers",
+            ));
+        }
+        for source in &sources {
+            let message = match source.as_str() {
+                "*" => Some("wildcard source allows any host"),
+                "http:" | "https:" => Some("scheme-only source allows any host over that scheme"),
+                "data:" if scripts => Some("data: URIs can carry scripts"),
+                "blob:" if scripts => Some("blob: URLs can carry scripts"),
+                source if source.starts_with("http://") => Some("source loads over plain HTTP"),
+                _ => None,
+            };
+            if let Some(message) = message {
+                // Host sources are ignored under 'strict-dynamic'
+                let severity = if strict_dynamic || !scripts { "medium" } else { "high" };
+                findings.push(finding(severity, name, &format!("{}: {}", source, message)));
+            }
+        }
+        if strict_dynamic && !nonce_or_hash {
+            findings.push(finding(
+                "warning",
+                name,
+                "'strict-dynamic' without a nonce or hash blocks every script",
+            ));
+        }
+        if has("'none'") && sources.len() > 1 {
+            findings.push(finding(
+                "warning",
+                name,
+                "'none' is ignored when other sources are listed",
+            ));
+        }
+    }
+
+    let named = |name: &str| get(directives, name).is_some();
+    if effective(directives, "script-src").is_none() {
+        findings.push(finding(
+            "high",
+            "script-src",
+            "no script-src or default-src; scripts load from anywhere",
+        ));
+    }
+    let object_none = effective(directives, "object-src")
+        .is_some_and(|directive| directive.sources.len() == 1 && directive.sources[0] == "'none'");
+    if !object_none {
+        findings.push(finding(
+            "medium",
+            "object-src",
+            "object-src isn't 'none'; plugins can load content",
+        ));
+    }
+    if !named("base-uri") {
+        findings.push(finding(
+            "medium",
+            "base-uri",
+            "missing; an injected <base> tag can redirect relative script URLs",
+        ));
+    }
+    if !named("frame-ancestors") {
+        findings.push(finding(
+            "info",
+            "frame-ancestors",
+            "missing; framing isn't restricted by this policy",
+        ));
+    }
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        _ => None,
+    }
+}
+
+// A host source (`https://*.example.com:443/path/`) against a URL
+fn host_source_matches(source: &str, url: &reqwest::Url, origin: Option<&reqwest::Url>) -> bool {
+    let (scheme, rest) = match source.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme.to_ascii_lowercase()), rest),
+        None => (None, source),
+    };
+    let url_scheme = url.scheme();
+    // Without a scheme, the protected page's scheme applies (http also allows https)
+    let scheme_ok = match scheme.as_deref().or(origin.map(|origin| origin.scheme())) {
+        Some(scheme) => {
+            scheme == url_scheme
+                || (scheme == "http" && url_scheme == "https")
+                || (scheme == "ws" && url_scheme == "wss")
+                || (scheme == "http" && url_scheme == "wss")
+                || (scheme == "https" && url_scheme == "wss")
+        }
+        None => matches!(url_scheme, "http" | "https"),
+    };
+    if !scheme_ok {
+        return false;
+    }
+
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], Some(&rest[index..])),
+        None => (rest, None),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+    let host = host.to_ascii_lowercase();
+    let url_host = url.host_str().unwrap_or("").to_ascii_lowercase();
+    let host_ok = match host.strip_prefix("*.") {
+        Some(suffix) => url_host.ends_with(&format!(".{}", suffix)),
+        None => host == "*" || host == url_host,
+    };
+    if !host_ok {
+        return false;
+    }
+
+    let url_port = url.port_or_known_default();
+    let port_ok = match port {
+        Some("*") => true,
+        Some(port) => port.parse::<u16>().ok() == url_port,
+        // The default port of the URL's scheme, so http sources match https on 443
+        None => url_port == default_port(url_scheme),
+    };
+    if !port_ok {
+        return false;
+    }
+
+    match path {
+        None | Some("/") => true,
+        Some(path) if path.ends_with('/') => url.path().starts_with(path),
+        Some(path) => url.path() == path,
+    }
+}
+
+// Whether `source` lets `url` load. Keywords other than 'self' never match a
+// URL (they govern inline content).
+fn source_matches(source: &str, url: &reqwest::Url, origin: Option<&reqwest::Url>) -> bool {
+    let lower = source.to_ascii_lowercase();
+    match lower.as_str() {
+        "'self'" => origin.is_some_and(|origin| {
+            origin.host_str() == url.host_str()
+                && origin.port_or_known_default() == url.port_or_known_default()
+                && (origin.scheme() == url.scheme() || (origin.scheme() == "http" && url.scheme() == "https"))
+        }),
+        // Network schemes only; data:, blob: and the like need naming
+        "*" => matches!(url.scheme(), "http" | "https" | "ws" | "wss" | "ftp"),
+        _ if lower.starts_with('\'') => false,
+        scheme if scheme.ends_with(':') && !scheme.contains('/') => {
+            let scheme = scheme.trim_end_matches(':');
+            scheme == url.scheme() || (scheme == "http" && url.scheme() == "https")
+        }
+        _ => host_source_matches(source, url, origin),
+    }
+}
+
+pub fn check_url(directives: &[Directive], name: &str, url: &str, origin: Option<&str>) -> Result<Value, String> {
+    let origin = match origin.filter(|origin| !origin.trim().is_empty()) {
+        Some(origin) => Some(reqwest::Url::parse(origin.trim()).map_err(|e| format!("Invalid origin: {}", e))?),
+        None => None,
+    };
+    let url = match reqwest::Url::parse(url.trim()) {
+        Ok(url) => url,
+        // Relative URLs are resolved against the page
+        Err(_) => match &origin {
+            Some(origin) => origin.join(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?,
+            None => return Err("Relative URLs need the page origin".to_string()),
+        },
+    };
+    let name = name.trim().to_ascii_lowercase();
+    let Some(directive) = effective(directives, &name) else {
+        return Ok(json!({
+            "directive": name,
+            "effective_directive": null,
+            "allowed": true,
+            "reason": "no directive governs this; anything is allowed"
+        }));
+    };
+    let strict_dynamic = name.starts_with("script-src")
+        && directive
+            .sources
+            .iter()
+            .any(|source| source.eq_ignore_ascii_case("'strict-dynamic'"));
+    let matched = if strict_dynamic {
+        None
+    } else {
+        directive
+            .sources
+            .iter()
+            .find(|source| source_matches(source, &url, origin.as_ref()))
+    };
+    let reason = match matched {
+        Some(source) => format!("matches {}", source),
+        None if strict_dynamic => {
+            "'strict-dynamic' ignores host sources; only scripts with a nonce or hash, or loaded by them, run"
+                .to_string()
+        }
+        None if directive.sources.iter().any(|source| source == "'self'") && origin.is_none() => {
+            "no source matches ('self' can't be checked without the page origin)".to_string()
+        }
+        None => "no source matches".to_string(),
+    };
+    Ok(json!({
+        "directive": name,
+        "effective_directive": directive.name,
+        "url": url.as_str(),
+        "allowed": matched.is_some(),
+        "matched_source": matched,
+        "reason": reason
+    }))
+}
+
+// Directives and findings for one policy value
+pub fn analyze(policy: &str) -> Value {
+    let mut found = Vec::new();
+    let directives = parse_policy(policy, &mut found);
+    findings(&directives, &mut found);
+    json!({
+        "directives": directives
+            .iter()
+            .map(|directive| json!({ "directive": directive.name, "sources": directive.sources }))
+            .collect::<Vec<_>>(),
+        "findings": found
+    })
+}
+
+fn policy_text(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    let text = match text.filter(|text| !text.is_empty()) {
+        Some(text) => text,
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string()
+        }
+    };
+    // A pasted header line is fine too
+    let text = text.trim();
+    let text = match text.split_once(':') {
+        Some((name, value)) if name.trim().to_ascii_lowercase().starts_with("content-security-policy") => value,
+        _ => text,
+    };
+    Ok(text.trim().to_string())
+}
+
+// Directives and findings for `policy` (or the active document's raw
+// content when it's empty). With `url`, also whether `directive` (default
+// script-src) would let it load; `origin` is the protected page's URL, needed
+// for 'self' and relative URLs.
+#[tauri::command]
+pub fn evaluate_csp(
+    app: AppHandle,
+    policy: Option<String>,
+    directive: Option<String>,
+    url: Option<String>,
+    origin: Option<String>,
+) -> Result<Value, String> {
+    let policy = policy_text(&app, policy)?;
+    if policy.is_empty() {
+        return Err("Empty policy".to_string());
+    }
+    // Multiple policies (comma-joined headers) all have to allow a load
+    let policies: Vec<&str> = policy
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    let mut report = analyze(policies[0]);
+    if let Some(url) = url.filter(|url| !url.trim().is_empty()) {
+        let directive = directive.unwrap_or_else(|| "script-src".to_string());
+        let mut checks = Vec::new();
+        for policy in &policies {
+            let directives = parse_policy(policy, &mut Vec::new());
+            checks.push(check_url(&directives, &directive, &url, origin.as_deref())?);
+        }
+        let allowed = checks.iter().all(|check| check["allowed"] == true);
+        report["check"] = json!({ "allowed": allowed, "policies": checks });
+    }
+    if policies.len() > 1 {
+        report["other_policies"] = policies[1..]
+            .iter()
+            .map(|policy| analyze(policy))
+            .collect::<Vec<_>>()
+            .into();
+    }
+    Ok(report)
+}
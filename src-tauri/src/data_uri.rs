@@ -0,0 +1,132 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use tauri::{AppHandle, Manager};
+
+use crate::detect;
+use crate::AppState;
+
+fn image_metadata(bytes: &[u8]) -> serde_json::Value {
+    let format = detect::sniff_magic(bytes);
+    let dimensions = imagesize::blob_size(bytes).ok();
+    serde_json::json!({
+        "format": format.as_ref().map(|d| d.kind),
+        "mime": format.as_ref().map(|d| d.mime).unwrap_or("application/octet-stream"),
+        "width": dimensions.as_ref().map(|d| d.width),
+        "height": dimensions.as_ref().map(|d| d.height),
+        "size": bytes.len()
+    })
+}
+
+fn image_to_data_uri_blocking(
+    file_path: Option<String>,
+    base64: Option<String>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let bytes = match (file_path, base64) {
+        (Some(path), _) if !path.is_empty() => {
+            std::fs::read(&path).map_err(|e| format!("Failed to read file: {}", e))?
+        }
+        (_, Some(encoded)) => {
+            let compact: String = encoded.split_whitespace().collect();
+            STANDARD
+                .decode(compact.as_bytes())
+                .map_err(|e| format!("Invalid base64 encoding: {}", e))?
+        }
+        _ => return Err("Provide either a file path or base64 data".to_string()),
+    };
+
+    let metadata = image_metadata(&bytes);
+    let mime = metadata["mime"].as_str().unwrap_or("application/octet-stream").to_string();
+    let uri = format!("data:{};base64,{}", mime, STANDARD.encode(&bytes));
+
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let document = storage.active_mut();
+    document.raw_content = Some(uri.clone().into());
+    document.formatted_content = None;
+    document.binary_content = Some(bytes);
+    document.clear_history();
+
+    Ok(serde_json::json!({
+        "data_uri": uri,
+        "metadata": metadata
+    }))
+}
+
+#[tauri::command]
+pub async fn image_to_data_uri(
+    app: AppHandle,
+    file_path: Option<String>,
+    base64: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("image_to_data_uri", move || {
+        image_to_data_uri_blocking(file_path, base64, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+// Split "data:[<mediatype>][;base64],<data>" into its parts
+pub fn parse_data_uri(uri: &str) -> Result<(String, bool, Vec<u8>), String> {
+    let rest = uri
+        .trim()
+        .strip_prefix("data:")
+        .ok_or_else(|| "Invalid data URI: must start with 'data:'".to_string())?;
+    let (header, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| "Invalid data URI: missing ',' separator".to_string())?;
+
+    let is_base64 = header.ends_with(";base64");
+    let media_type = header.trim_end_matches(";base64");
+    let media_type = if media_type.is_empty() { "text/plain;charset=US-ASCII" } else { media_type };
+
+    let bytes = if is_base64 {
+        let compact: String = payload.split_whitespace().collect();
+        STANDARD
+            .decode(compact.as_bytes())
+            .map_err(|e| format!("Invalid base64 in data URI: {}", e))?
+    } else {
+        percent_decode(payload)
+    };
+
+    Ok((media_type.to_string(), is_base64, bytes))
+}
+
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(b) = u8::from_str_radix(hex, 16) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+fn decode_data_uri_blocking(uri: String, state: &AppState) -> Result<serde_json::Value, String> {
+    let (media_type, is_base64, bytes) = parse_data_uri(&uri)?;
+    let metadata = image_metadata(&bytes);
+
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.active_mut().set_binary(bytes);
+
+    Ok(serde_json::json!({
+        "declared_mime": media_type,
+        "is_base64": is_base64,
+        "metadata": metadata
+    }))
+}
+
+#[tauri::command]
+pub async fn decode_data_uri(app: AppHandle, uri: String) -> Result<serde_json::Value, String> {
+    crate::run_blocking("decode_data_uri", move || {
+        decode_data_uri_blocking(uri, app.state::<AppState>().inner())
+    })
+    .await
+}
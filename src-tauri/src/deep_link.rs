@@ -0,0 +1,138 @@
+// devmate:// links for runbooks and chat messages:
+//
+//   devmate://decode/jwt?token=...    load the payload and run a formatter on it
+//   devmate://format/json?text=...    same, for any formatter id
+//   devmate://open?url=https://...    ask the UI to fetch a URL
+//
+// Decoding and formatting happen in a new document. Fetching is never automatic:
+// the UI gets the URL and must confirm before passing it to load_from_url, since
+// anyone can write a link. Outcomes are reported through `deep-link://opened`.
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::formatters::FormatterRegistry;
+use crate::AppState;
+
+pub const DEEP_LINK_EVENT: &str = "deep-link://opened";
+pub const SCHEME: &str = "devmate";
+
+// Links can't carry much more than this anyway; longer payloads are refused
+const MAX_PAYLOAD_BYTES: usize = 1024 * 1024;
+const PAYLOAD_PARAMS: &[&str] = &["token", "text", "data", "input"];
+
+fn report(app: &AppHandle, link: &Url, outcome: Result<serde_json::Value, String>) {
+    let payload = match outcome {
+        Ok(result) => serde_json::json!({ "link": link.as_str(), "result": result }),
+        Err(error) => serde_json::json!({ "link": link.as_str(), "error": error }),
+    };
+    let _ = app.emit(DEEP_LINK_EVENT, payload);
+}
+
+fn query_param(link: &Url, names: &[&str]) -> Option<String> {
+    link.query_pairs()
+        .find(|(key, _)| names.contains(&key.as_ref()))
+        .map(|(_, value)| value.into_owned())
+}
+
+fn run_tool(app: AppHandle, link: Url, action: String) {
+    tauri::async_runtime::spawn(async move {
+        let worker = app.clone();
+        let task_link = link.clone();
+        let outcome = crate::run_blocking("deep_link", move || {
+            let formatter_id = task_link
+                .path_segments()
+                .and_then(|mut segments| segments.next())
+                .filter(|segment| !segment.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| format!("devmate://{}/ needs a tool, e.g. devmate://{}/jwt", action, action))?;
+            if worker.state::<FormatterRegistry>().get(&formatter_id).is_none() {
+                return Err(format!("Unknown tool {}", formatter_id));
+            }
+            let payload = query_param(&task_link, PAYLOAD_PARAMS)
+                .filter(|payload| !payload.is_empty())
+                .ok_or_else(|| "The link has no payload (token, text, data or input)".to_string())?;
+            if payload.len() > MAX_PAYLOAD_BYTES {
+                return Err("The link's payload is too large".to_string());
+            }
+
+            let document_id = {
+                let mut storage = worker.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                let id = storage.insert_document(format!("{} (link)", formatter_id));
+                storage.active_mut().raw_content = Some(payload.clone().into());
+                id
+            };
+            crate::tray::refresh(&worker);
+
+            let (formatted, store) = crate::run_formatter(&worker, payload, &formatter_id)?;
+            let formatted_length = formatted.len();
+            if store {
+                let mut storage = worker.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                if let Some(document) = storage.documents.get_mut(&document_id) {
+                    document.formatted_content = Some(formatted);
+                    document.format_type = Some(formatter_id.clone());
+                }
+            }
+            Ok(serde_json::json!({
+                "action": action,
+                "tool": formatter_id,
+                "document_id": document_id,
+                "formatted_length": formatted_length,
+                "stored": store
+            }))
+        })
+        .await;
+        report(&app, &link, outcome);
+    });
+}
+
+pub fn handle_url(app: &AppHandle, link: Url) {
+    if link.scheme() != SCHEME {
+        return;
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+
+    let action = link.host_str().unwrap_or_default().to_ascii_lowercase();
+    match action.as_str() {
+        "decode" | "format" => run_tool(app.clone(), link, action),
+        "open" => {
+            let outcome = query_param(&link, &["url"])
+                .ok_or_else(|| "devmate://open needs a url parameter".to_string())
+                .and_then(|url| crate::fetch::parse_http_url(&url))
+                .map(|url| {
+                    serde_json::json!({
+                        "action": "open",
+                        "url": url.as_str(),
+                        "requires_confirmation": true
+                    })
+                });
+            report(app, &link, outcome);
+        }
+        _ => report(app, &link, Err(format!("Unknown devmate link action {}", action))),
+    }
+}
+
+// Hooks up link handling; called once from setup
+pub fn register(app: &AppHandle) {
+    // Installed builds register the scheme through the bundle; this covers
+    // development builds on Windows and Linux
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        eprintln!("Failed to register devmate:// links: {}", e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for link in event.urls() {
+            handle_url(&handle, link);
+        }
+    });
+    // The link that launched the app, if any
+    if let Ok(Some(links)) = app.deep_link().get_current() {
+        for link in links {
+            handle_url(app, link);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+// Leading byte signatures for common binary formats
+const MAGIC_SIGNATURES: &[(&[u8], &str, &str)] = &[
+    (&[0x1f, 0x8b], "gzip", "application/gzip"),
+    (&[0x28, 0xb5, 0x2f, 0xfd], "zstd", "application/zstd"),
+    (&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a], "png", "image/png"),
+    (&[0xff, 0xd8, 0xff], "jpeg", "image/jpeg"),
+    (b"GIF87a", "gif", "image/gif"),
+    (b"GIF89a", "gif", "image/gif"),
+    (b"%PDF-", "pdf", "application/pdf"),
+    (b"PK\x03\x04", "zip", "application/zip"),
+    (b"BZh", "bzip2", "application/x-bzip2"),
+    (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], "xz", "application/x-xz"),
+    (b"7z\xbc\xaf\x27\x1c", "7z", "application/x-7z-compressed"),
+    (b"\x7fELF", "elf", "application/x-executable"),
+    (b"PAR1", "parquet", "application/vnd.apache.parquet"),
+    (b"Obj\x01", "avro", "application/avro"),
+    (b"SQLite format 3\0", "sqlite", "application/vnd.sqlite3"),
+];
+
+#[derive(Clone, Debug)]
+pub struct Detection {
+    pub kind: &'static str,
+    pub mime: &'static str,
+    pub confidence: f64,
+}
+
+impl Detection {
+    fn new(kind: &'static str, mime: &'static str, confidence: f64) -> Self {
+        Detection { kind, mime, confidence }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": self.kind,
+            "mime": self.mime,
+            "confidence": self.confidence
+        })
+    }
+}
+
+pub fn sniff_magic(bytes: &[u8]) -> Option<Detection> {
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some(Detection::new("webp", "image/webp", 1.0));
+    }
+    if bytes.len() > 262 && &bytes[257..262] == b"ustar" {
+        return Some(Detection::new("tar", "application/x-tar", 1.0));
+    }
+    MAGIC_SIGNATURES
+        .iter()
+        .find(|(magic, _, _)| bytes.starts_with(magic))
+        .map(|(_, kind, mime)| Detection::new(kind, mime, 1.0))
+}
+
+// Returns candidate types ordered from most to least likely
+pub fn detect(bytes: &[u8]) -> Vec<Detection> {
+    if let Some(magic) = sniff_magic(bytes) {
+        return vec![magic];
+    }
+
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(_) => return vec![Detection::new("binary", "application/octet-stream", 0.9)],
+    };
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return vec![Detection::new("empty", "text/plain", 1.0)];
+    }
+
+    let mut candidates = Vec::new();
+
+    if (trimmed.starts_with('{') || trimmed.starts_with('[')) && serde_json::from_str::<serde_json::Value>(trimmed).is_ok() {
+        candidates.push(Detection::new("json", "application/json", 0.99));
+    } else if trimmed.starts_with('{') && looks_like_ndjson(trimmed) {
+        candidates.push(Detection::new("ndjson", "application/x-ndjson", 0.9));
+    } else if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        candidates.push(Detection::new("json", "application/json", 0.5));
+    }
+
+    if trimmed.starts_with("<?xml") {
+        candidates.push(Detection::new("xml", "application/xml", 0.99));
+    } else if trimmed.to_ascii_lowercase().starts_with("<!doctype html") || trimmed.to_ascii_lowercase().starts_with("<html") {
+        candidates.push(Detection::new("html", "text/html", 0.95));
+    } else if trimmed.starts_with('<') && trimmed.ends_with('>') {
+        candidates.push(Detection::new("xml", "application/xml", 0.8));
+    }
+
+    if trimmed.starts_with("-----BEGIN ") {
+        candidates.push(Detection::new("pem", "application/x-pem-file", 0.99));
+    }
+
+    if looks_like_jwt(trimmed) {
+        candidates.push(Detection::new("jwt", "application/jwt", 0.95));
+    }
+
+    if let Some(inner) = decode_base64_candidate(trimmed) {
+        let confidence = if sniff_magic(&inner).is_some() { 0.9 } else { 0.6 };
+        candidates.push(Detection::new("base64", "text/plain", confidence));
+    }
+
+    if looks_like_yaml(trimmed) {
+        candidates.push(Detection::new("yaml", "application/yaml", 0.6));
+    }
+
+    if looks_like_csv(trimmed) {
+        candidates.push(Detection::new("csv", "text/csv", 0.5));
+    }
+
+    candidates.push(Detection::new("text", "text/plain", 0.1));
+    candidates.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+    candidates
+}
+
+fn looks_like_ndjson(text: &str) -> bool {
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty()).peekable();
+    lines.peek().is_some()
+        && lines.all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok())
+}
+
+fn looks_like_jwt(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('.').collect();
+    parts.len() == 3
+        && parts[0].starts_with("eyJ")
+        && parts.iter().all(|p| p.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+}
+
+fn decode_base64_candidate(text: &str) -> Option<Vec<u8>> {
+    let compact: String = text.split_whitespace().collect();
+    if compact.len() < 8 || !compact.len().is_multiple_of(4) {
+        return None;
+    }
+    if !compact.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '=') {
+        return None;
+    }
+    STANDARD.decode(compact.as_bytes()).ok()
+}
+
+fn looks_like_yaml(text: &str) -> bool {
+    if text.starts_with("---") {
+        return true;
+    }
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect();
+    !lines.is_empty()
+        && lines.iter().all(|l| {
+            l.starts_with("- ") || l.split_once(": ").is_some() || l.ends_with(':')
+        })
+}
+
+fn looks_like_csv(text: &str) -> bool {
+    let lines: Vec<&str> = text.lines().take(20).filter(|l| !l.is_empty()).collect();
+    if lines.len() < 2 {
+        return false;
+    }
+    let columns = lines[0].matches(',').count();
+    columns > 0 && lines.iter().all(|l| l.matches(',').count() == columns)
+}
+
+fn detect_content_type_blocking(
+    text: Option<String>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let content = match text {
+        Some(text) if !text.is_empty() => text,
+        _ => {
+            let storage = state.lock().map_err(|e| e.to_string())?;
+            storage.active().raw_content.as_deref().unwrap_or_default().to_string()
+        }
+    };
+
+    let candidates = detect(content.as_bytes());
+    let best = candidates.first().map(|d| d.to_json());
+
+    Ok(serde_json::json!({
+        "detected": best,
+        "candidates": candidates.iter().map(|d| d.to_json()).collect::<Vec<_>>()
+    }))
+}
+
+#[tauri::command]
+pub async fn detect_content_type(
+    app: AppHandle,
+    text: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("detect_content_type", move || {
+        detect_content_type_blocking(text, app.state::<AppState>().inner())
+    })
+    .await
+}
@@ -0,0 +1,343 @@
+// Talks to the local Docker engine over its API socket (DOCKER_HOST, else
+// /var/run/docker.sock or Docker Desktop's per-user socket; the named pipe on
+// Windows): lists containers and images, loads `docker inspect` output into a
+// document for the JSON tooling, and loads a container's recent log lines into
+// a document for the chunked viewer. Only plain HTTP is spoken, so a DOCKER_HOST
+// that needs TLS isn't supported.
+use std::io::{Read, Write};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+// Larger replies (a huge log tail) are refused rather than held in memory twice
+const MAX_RESPONSE_BYTES: usize = 256 * 1024 * 1024;
+const DEFAULT_LOG_TAIL: u64 = 1000;
+
+trait Connection: Read + Write {}
+impl<T: Read + Write> Connection for T {}
+
+fn connect() -> Result<Box<dyn Connection>, String> {
+    if let Ok(host) = std::env::var("DOCKER_HOST") {
+        if let Some(address) = host.strip_prefix("tcp://") {
+            let stream = std::net::TcpStream::connect(address)
+                .map_err(|e| format!("Failed to connect to Docker at {}: {}", host, e))?;
+            stream.set_read_timeout(Some(API_TIMEOUT)).map_err(|e| e.to_string())?;
+            return Ok(Box::new(stream));
+        }
+        #[cfg(unix)]
+        if let Some(path) = host.strip_prefix("unix://") {
+            return connect_socket(&[std::path::PathBuf::from(path)]);
+        }
+        #[cfg(windows)]
+        if let Some(path) = host.strip_prefix("npipe://") {
+            return connect_pipe(&path.replace('/', "\\"));
+        }
+        return Err(format!("Unsupported DOCKER_HOST {}", host));
+    }
+    #[cfg(unix)]
+    {
+        let mut candidates = vec![std::path::PathBuf::from("/var/run/docker.sock")];
+        if let Some(home) = std::env::var_os("HOME") {
+            candidates.push(std::path::Path::new(&home).join(".docker/run/docker.sock"));
+        }
+        connect_socket(&candidates)
+    }
+    #[cfg(windows)]
+    {
+        connect_pipe(r"\\.\pipe\docker_engine")
+    }
+}
+
+#[cfg(unix)]
+fn connect_socket(candidates: &[std::path::PathBuf]) -> Result<Box<dyn Connection>, String> {
+    let mut last_error = String::from("No Docker socket found");
+    for path in candidates {
+        match std::os::unix::net::UnixStream::connect(path) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(API_TIMEOUT)).map_err(|e| e.to_string())?;
+                return Ok(Box::new(stream));
+            }
+            Err(e) => last_error = format!("Failed to connect to {}: {}", path.display(), e),
+        }
+    }
+    Err(format!("{}. Is Docker running?", last_error))
+}
+
+#[cfg(windows)]
+fn connect_pipe(path: &str) -> Result<Box<dyn Connection>, String> {
+    let pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("Failed to connect to {}: {}. Is Docker running?", path, e))?;
+    Ok(Box::new(pipe))
+}
+
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = body
+            .windows(2)
+            .position(|pair| pair == b"\r\n")
+            .ok_or_else(|| "Malformed chunked response from Docker".to_string())?;
+        let size_text = String::from_utf8_lossy(&body[..line_end]);
+        let size_text = size_text.split(';').next().unwrap_or_default().trim();
+        let size =
+            usize::from_str_radix(size_text, 16).map_err(|_| "Malformed chunked response from Docker".to_string())?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(out);
+        }
+        if body.len() < size {
+            return Err("Docker's response ended early".to_string());
+        }
+        out.extend_from_slice(&body[..size]);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+}
+
+// One GET against the engine API; the body of a 2xx response
+fn get(path: &str) -> Result<Vec<u8>, String> {
+    let mut connection = connect()?;
+    let request = format!("GET {} HTTP/1.1\r\nHost: docker\r\nConnection: close\r\n\r\n", path);
+    connection
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("Failed to send the request to Docker: {}", e))?;
+    let mut response = Vec::new();
+    connection
+        .take(MAX_RESPONSE_BYTES as u64 + 1)
+        .read_to_end(&mut response)
+        .map_err(|e| format!("Failed to read Docker's response: {}", e))?;
+    if response.len() > MAX_RESPONSE_BYTES {
+        return Err(format!("Docker's response is larger than {} bytes", MAX_RESPONSE_BYTES));
+    }
+
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| "Malformed response from Docker".to_string())?;
+    let head = String::from_utf8_lossy(&response[..header_end]).into_owned();
+    let body = &response[header_end + 4..];
+    let status: u16 = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| "Malformed response from Docker".to_string())?;
+    let chunked = head.lines().any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding") && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    let body = if chunked { dechunk(body)? } else { body.to_vec() };
+
+    if !(200..300).contains(&status) {
+        let message = serde_json::from_slice::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|reply| reply["message"].as_str().map(str::to_string))
+            .unwrap_or_else(|| String::from_utf8_lossy(&body).trim().to_string());
+        return Err(format!("Docker responded with {}: {}", status, message));
+    }
+    Ok(body)
+}
+
+fn get_json(path: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_slice(&get(path)?).map_err(|e| format!("Unreadable reply from Docker: {}", e))
+}
+
+// Container ids, names and image references go into the request path as-is
+fn check_reference(reference: &str) -> Result<(), String> {
+    let valid = !reference.is_empty()
+        && reference
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.-:/@".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid container or image reference {}", reference))
+    }
+}
+
+fn new_document(app: &AppHandle, name: String, content: String, format_type: Option<&str>) -> Result<u64, String> {
+    let id = {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let id = storage.insert_document(name);
+        let document = storage.active_mut();
+        document.raw_content = Some(content.into());
+        document.format_type = format_type.map(str::to_string);
+        id
+    };
+    crate::tray::refresh(app);
+    Ok(id)
+}
+
+// Log output of a container without a TTY is framed: an 8-byte header (stream
+// type, three zero bytes, big-endian length) before each piece
+fn demultiplex(mut framed: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(framed.len());
+    while framed.len() >= 8 {
+        let length = u32::from_be_bytes([framed[4], framed[5], framed[6], framed[7]]) as usize;
+        let end = (8 + length).min(framed.len());
+        out.extend_from_slice(&framed[8..end]);
+        framed = &framed[end..];
+    }
+    out
+}
+
+// Engine and API version, to check the connection works
+#[tauri::command]
+pub async fn docker_info() -> Result<serde_json::Value, String> {
+    crate::run_blocking("docker_info", || {
+        let version = get_json("/version")?;
+        Ok(serde_json::json!({
+            "version": version["Version"],
+            "api_version": version["ApiVersion"],
+            "os": version["Os"],
+            "arch": version["Arch"]
+        }))
+    })
+    .await
+}
+
+// Running containers, or all of them with `all`
+#[tauri::command]
+pub async fn docker_list_containers(all: Option<bool>) -> Result<Vec<serde_json::Value>, String> {
+    crate::run_blocking("docker_list_containers", move || {
+        let path = format!("/containers/json?all={}", u8::from(all.unwrap_or(false)));
+        let containers = get_json(&path)?;
+        Ok(containers
+            .as_array()
+            .map(|containers| {
+                containers
+                    .iter()
+                    .map(|container| {
+                        let name = container["Names"][0]
+                            .as_str()
+                            .unwrap_or_default()
+                            .trim_start_matches('/');
+                        serde_json::json!({
+                            "id": container["Id"],
+                            "name": name,
+                            "image": container["Image"],
+                            "state": container["State"],
+                            "status": container["Status"],
+                            "created": container["Created"],
+                            "ports": container["Ports"]
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn docker_list_images() -> Result<Vec<serde_json::Value>, String> {
+    crate::run_blocking("docker_list_images", || {
+        let images = get_json("/images/json")?;
+        Ok(images
+            .as_array()
+            .map(|images| {
+                images
+                    .iter()
+                    .map(|image| {
+                        serde_json::json!({
+                            "id": image["Id"],
+                            "tags": image["RepoTags"],
+                            "size": image["Size"],
+                            "created": image["Created"]
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    })
+    .await
+}
+
+// `docker inspect` for a container or image (`kind`), returned and, with
+// `into_document`, opened as a JSON document
+#[tauri::command]
+pub async fn docker_inspect(
+    app: AppHandle,
+    kind: String,
+    reference: String,
+    into_document: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("docker_inspect", move || {
+        check_reference(&reference)?;
+        let path = match kind.as_str() {
+            "container" => format!("/containers/{}/json", reference),
+            "image" => format!("/images/{}/json", reference),
+            other => return Err(format!("Unknown kind {}; use container or image", other)),
+        };
+        let inspected = get_json(&path)?;
+        let mut reply = serde_json::json!({ "kind": kind, "reference": reference });
+        if into_document.unwrap_or(false) {
+            let json = crate::formatters::to_string_pretty(&inspected).map_err(|e| e.to_string())?;
+            let name = format!("docker inspect: {}", reference);
+            reply["document_id"] = new_document(&app, name, json, Some("json"))?.into();
+        }
+        reply["inspect"] = inspected;
+        Ok(reply)
+    })
+    .await
+}
+
+// Loads the last `tail` lines (1000 by default) of a container's logs into a
+// new document. `since` is a Unix timestamp; `streams` picks "stdout",
+// "stderr" or both (the default).
+#[tauri::command]
+pub async fn docker_container_logs(
+    app: AppHandle,
+    container: String,
+    tail: Option<u64>,
+    since: Option<i64>,
+    timestamps: Option<bool>,
+    streams: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("docker_container_logs", move || {
+        check_reference(&container)?;
+        let (stdout, stderr) = match streams.as_deref() {
+            None | Some("both") => (1, 1),
+            Some("stdout") => (1, 0),
+            Some("stderr") => (0, 1),
+            Some(other) => return Err(format!("Unknown streams value {}; use stdout, stderr or both", other)),
+        };
+        // Logs are framed unless the container has a TTY
+        let inspected = get_json(&format!("/containers/{}/json", container))?;
+        let tty = inspected["Config"]["Tty"].as_bool().unwrap_or(false);
+        let name = inspected["Name"]
+            .as_str()
+            .unwrap_or(&container)
+            .trim_start_matches('/')
+            .to_string();
+
+        let mut path = format!(
+            "/containers/{}/logs?stdout={}&stderr={}&timestamps={}&tail={}",
+            container,
+            stdout,
+            stderr,
+            u8::from(timestamps.unwrap_or(false)),
+            tail.unwrap_or(DEFAULT_LOG_TAIL)
+        );
+        if let Some(since) = since {
+            path.push_str(&format!("&since={}", since));
+        }
+        let raw = get(&path)?;
+        let bytes = if tty { raw } else { demultiplex(&raw) };
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        let lines = text.lines().count();
+        let length = text.len();
+        let document_id = new_document(&app, format!("docker logs: {}", name), text, None)?;
+        Ok(serde_json::json!({
+            "document_id": document_id,
+            "container": name,
+            "lines": lines,
+            "length": length
+        }))
+    })
+    .await
+}
@@ -0,0 +1,92 @@
+use tauri::{AppHandle, State};
+
+use crate::responses::DocumentInfo;
+use crate::{AppState, ContentStorage, Document};
+
+fn describe(storage: &ContentStorage, id: u64, document: &Document) -> DocumentInfo {
+    DocumentInfo {
+        id,
+        active: id == storage.active_id,
+        info: document.info(),
+    }
+}
+
+// Creates an empty document and makes it active
+#[tauri::command]
+pub fn create_document(app: AppHandle, name: Option<String>, state: State<AppState>) -> Result<DocumentInfo, String> {
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let name = name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| format!("Untitled {}", storage.next_id));
+    let id = storage.insert_document(name);
+
+    let info = describe(&storage, id, &storage.documents[&id]);
+    drop(storage);
+    crate::tray::refresh(&app);
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn list_documents(state: State<AppState>) -> Result<Vec<DocumentInfo>, String> {
+    let storage = state.lock().map_err(|e| e.to_string())?;
+    Ok(storage
+        .documents
+        .iter()
+        .map(|(id, document)| describe(&storage, *id, document))
+        .collect())
+}
+
+#[tauri::command]
+pub fn switch_document(app: AppHandle, id: u64, state: State<AppState>) -> Result<DocumentInfo, String> {
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    if !storage.documents.contains_key(&id) {
+        return Err(format!("Document {} not found", id));
+    }
+    storage.active_id = id;
+    let info = describe(&storage, id, storage.active());
+    drop(storage);
+    crate::tray::refresh(&app);
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn rename_document(app: AppHandle, id: u64, name: String, state: State<AppState>) -> Result<(), String> {
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let document = storage
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| format!("Document {} not found", id))?;
+    document.name = name;
+    drop(storage);
+    crate::tray::refresh(&app);
+    Ok(())
+}
+
+// Returns the id of the document that is active afterwards. Closing the last
+// document leaves a fresh empty one in its place.
+#[tauri::command]
+pub fn close_document(app: AppHandle, id: u64, state: State<AppState>) -> Result<u64, String> {
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    if storage.documents.remove(&id).is_none() {
+        return Err(format!("Document {} not found", id));
+    }
+
+    if storage.documents.is_empty() {
+        let name = format!("Untitled {}", storage.next_id);
+        storage.insert_document(name);
+    } else if storage.active_id == id {
+        // Prefer the neighbour to the left, like closing a browser tab
+        let fallback = storage
+            .documents
+            .range(..id)
+            .next_back()
+            .or_else(|| storage.documents.range(id..).next())
+            .map(|(key, _)| *key)
+            .expect("at least one document remains");
+        storage.active_id = fallback;
+    }
+    let active_id = storage.active_id;
+    drop(storage);
+    crate::tray::refresh(&app);
+    Ok(active_id)
+}
@@ -0,0 +1,473 @@
+// Raw email source (RFC 822 / MIME, as saved in .eml files or shown by "view
+// original") decoded into JSON: headers with encoded-words decoded, the
+// multipart structure as a tree, and each part's body undone from its transfer
+// encoding and charset. JSON and XML bodies are pretty-printed, other text is
+// shown as is, and binary attachments are summarized.
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use encoding_rs::Encoding;
+use serde_json::{json, Value};
+
+use crate::formatters::Formatter;
+
+// Nesting beyond this is treated as a leaf rather than parsed further
+const MAX_DEPTH: usize = 32;
+// Binary bodies up to this size are included as base64
+const MAX_INLINE_BINARY: usize = 64 * 1024;
+
+pub fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    match charset.and_then(|label| Encoding::for_label(label.trim().as_bytes())) {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+// Quoted-printable; `header` selects the encoded-word variant where `_` is a space
+pub fn decode_quoted_printable(text: &str, header: bool) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' => {
+                // Soft line break
+                let rest = &bytes[i + 1..];
+                if rest.starts_with(b"\r\n") {
+                    i += 3;
+                    continue;
+                }
+                if rest.starts_with(b"\n") {
+                    i += 2;
+                    continue;
+                }
+                match (
+                    rest.first().copied().and_then(hex_value),
+                    rest.get(1).copied().and_then(hex_value),
+                ) {
+                    (Some(high), Some(low)) => {
+                        out.push(high << 4 | low);
+                        i += 3;
+                        continue;
+                    }
+                    _ => out.push(b'='),
+                }
+            }
+            b'_' if header => out.push(b' '),
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    out
+}
+
+pub fn decode_base64_lenient(text: &str) -> Result<Vec<u8>, String> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let compact = compact.trim_end_matches('=');
+    base64::engine::general_purpose::STANDARD_NO_PAD
+        .decode(compact)
+        .map_err(|e| format!("Invalid base64: {}", e))
+}
+
+// One `=?charset?B|Q?text?=` encoded-word, without the delimiters
+fn decode_encoded_word(word: &str) -> Option<String> {
+    let mut pieces = word.splitn(3, '?');
+    let charset = pieces.next()?;
+    let encoding = pieces.next()?;
+    let text = pieces.next()?;
+    // RFC 2231 allows a language after the charset: utf-8*en
+    let charset = charset.split('*').next().unwrap_or(charset);
+    let bytes = match encoding {
+        "B" | "b" => decode_base64_lenient(text).ok()?,
+        "Q" | "q" => decode_quoted_printable(text, true),
+        _ => return None,
+    };
+    Some(decode_charset(&bytes, Some(charset)))
+}
+
+// Header text with RFC 2047 encoded-words decoded. Whitespace between two
+// adjacent encoded-words is dropped, as the RFC requires.
+pub fn decode_header_value(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut previous_was_word = false;
+    while let Some(start) = rest.find("=?") {
+        let Some(end) = rest[start + 2..]
+            .match_indices("?=")
+            .map(|(index, _)| start + 2 + index)
+            .find(|end| rest[start + 2..*end].matches('?').count() >= 2)
+        else {
+            break;
+        };
+        let between = &rest[..start];
+        match decode_encoded_word(&rest[start + 2..end]) {
+            Some(decoded) => {
+                if !(previous_was_word && between.trim().is_empty()) {
+                    out.push_str(between);
+                }
+                out.push_str(&decoded);
+                previous_was_word = true;
+            }
+            None => {
+                out.push_str(&rest[..end + 2]);
+                previous_was_word = false;
+            }
+        }
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// Unfolded header fields in order, and the body after the blank line
+pub fn split_message(text: &str) -> (Vec<(String, String)>, &str) {
+    // A part may have no headers at all
+    if let Some(body) = text.strip_prefix("\r\n").or_else(|| text.strip_prefix('\n')) {
+        return (Vec::new(), body);
+    }
+    let (head, body) = match (text.find("\r\n\r\n"), text.find("\n\n")) {
+        (Some(crlf), Some(lf)) if lf < crlf => (&text[..lf], &text[lf + 2..]),
+        (Some(crlf), _) => (&text[..crlf], &text[crlf + 4..]),
+        (None, Some(lf)) => (&text[..lf], &text[lf + 2..]),
+        (None, None) => (text, ""),
+    };
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            // Continuation of the previous field
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+pub fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let (Some(high), Some(low)) = (
+                bytes.get(i + 1).copied().and_then(hex_value),
+                bytes.get(i + 2).copied().and_then(hex_value),
+            ) {
+                out.push(high << 4 | low);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+// A structured header such as Content-Type: the lowercased main value and its
+// parameters. RFC 2231 continuations (name*0, name*1) and extended values
+// (name*=utf-8''%E2%82%AC) are joined and decoded.
+pub fn parse_parameters(value: &str) -> (String, Vec<(String, String)>) {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            _ if escaped => {
+                current.push(c);
+                escaped = false;
+            }
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    let main = fields
+        .first()
+        .map(|field| field.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    // (name, section, extended, value)
+    let mut pieces: Vec<(String, usize, bool, String)> = Vec::new();
+    for field in fields.iter().skip(1) {
+        let Some((name, value)) = field.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().to_ascii_lowercase();
+        let (name, extended) = match name.strip_suffix('*') {
+            Some(name) => (name.to_string(), true),
+            None => (name, false),
+        };
+        let (name, section) = match name.split_once('*') {
+            Some((name, section)) => (name.to_string(), section.parse().unwrap_or(0)),
+            None => (name, 0),
+        };
+        pieces.push((name, section, extended, value.trim().to_string()));
+    }
+    pieces.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut parameters: Vec<(String, String)> = Vec::new();
+    let mut index = 0;
+    while index < pieces.len() {
+        let name = pieces[index].0.clone();
+        let mut charset = None;
+        let mut bytes = Vec::new();
+        while index < pieces.len() && pieces[index].0 == name {
+            let (_, section, extended, value) = &pieces[index];
+            if *extended {
+                let mut value = value.as_str();
+                if *section == 0 {
+                    let mut parts = value.splitn(3, '\'');
+                    if let (Some(set), Some(_language), Some(rest)) = (parts.next(), parts.next(), parts.next()) {
+                        charset = Some(set.to_string()).filter(|set| !set.is_empty());
+                        value = rest;
+                    }
+                }
+                bytes.extend(percent_decode(value));
+            } else {
+                bytes.extend_from_slice(value.as_bytes());
+            }
+            index += 1;
+        }
+        let value = decode_charset(&bytes, charset.as_deref());
+        parameters.push((name, decode_header_value(&value)));
+    }
+    (main, parameters)
+}
+
+pub fn parameter<'a>(parameters: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    parameters
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// The parts between `--boundary` lines; preamble and epilogue are dropped
+pub fn split_multipart<'a>(body: &'a str, boundary: &str) -> Vec<&'a str> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == delimiter || trimmed == format!("{}--", delimiter) {
+            if let Some(start) = start {
+                // The line break before the delimiter belongs to it
+                let part = &body[start..offset];
+                let part = part.strip_suffix('\n').unwrap_or(part);
+                parts.push(part.strip_suffix('\r').unwrap_or(part));
+            }
+            if trimmed.ends_with("--") && trimmed != delimiter {
+                return parts;
+            }
+            start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    // Unterminated: keep what follows the last delimiter
+    if let Some(start) = start.filter(|start| *start < body.len()) {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+fn is_json_type(content_type: &str) -> bool {
+    content_type == "application/json" || content_type.ends_with("+json")
+}
+
+fn is_xml_type(content_type: &str) -> bool {
+    matches!(content_type, "application/xml" | "text/xml") || content_type.ends_with("+xml")
+}
+
+// A part body decoded and, where a formatter applies, formatted
+fn body_json(content_type: &str, charset: Option<&str>, bytes: &[u8], part: &mut serde_json::Map<String, Value>) {
+    let textual = content_type.starts_with("text/") || is_json_type(content_type) || is_xml_type(content_type);
+    if !textual {
+        let encoded = (bytes.len() <= MAX_INLINE_BINARY).then(|| STANDARD.encode(bytes));
+        part.insert("formatted_as".into(), "base64".into());
+        part.insert("body".into(), encoded.into());
+        return;
+    }
+    let text = decode_charset(bytes, charset);
+    if is_json_type(content_type) {
+        if let Ok(value) = serde_json::from_str::<Value>(&text) {
+            part.insert("formatted_as".into(), "json".into());
+            part.insert("body".into(), value);
+            return;
+        }
+    }
+    if is_xml_type(content_type) {
+        if let Ok(pretty) = crate::formatters::xml::format_xml(&text) {
+            part.insert("formatted_as".into(), "xml".into());
+            part.insert("body".into(), pretty.into());
+            return;
+        }
+    }
+    let kind = content_type.strip_prefix("text/").unwrap_or("text");
+    part.insert("formatted_as".into(), kind.into());
+    part.insert("body".into(), text.into());
+}
+
+fn parse_part(text: &str, default_type: &str, depth: usize, attachments: &mut Vec<Value>) -> Value {
+    let (headers, body) = split_message(text);
+    let (content_type, type_parameters) = match header(&headers, "Content-Type") {
+        Some(value) => parse_parameters(value),
+        None => (default_type.to_string(), Vec::new()),
+    };
+    let encoding = header(&headers, "Content-Transfer-Encoding")
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "7bit".to_string());
+    let (disposition, disposition_parameters) = match header(&headers, "Content-Disposition") {
+        Some(value) => {
+            let (disposition, parameters) = parse_parameters(value);
+            (Some(disposition), parameters)
+        }
+        None => (None, Vec::new()),
+    };
+    let filename = parameter(&disposition_parameters, "filename").or_else(|| parameter(&type_parameters, "name"));
+    let charset = parameter(&type_parameters, "charset");
+
+    let mut part = serde_json::Map::new();
+    part.insert("content_type".into(), content_type.clone().into());
+    if let Some(charset) = charset {
+        part.insert("charset".into(), charset.into());
+    }
+    part.insert("transfer_encoding".into(), encoding.clone().into());
+    if let Some(disposition) = &disposition {
+        part.insert("disposition".into(), disposition.clone().into());
+    }
+    if let Some(filename) = filename {
+        part.insert("filename".into(), filename.into());
+    }
+    if let Some(id) = header(&headers, "Content-ID") {
+        part.insert("content_id".into(), id.trim_matches(['<', '>']).into());
+    }
+
+    if depth < MAX_DEPTH && content_type.starts_with("multipart/") {
+        let Some(boundary) = parameter(&type_parameters, "boundary") else {
+            part.insert("error".into(), "multipart part without a boundary".into());
+            return Value::Object(part);
+        };
+        // Parts of multipart/digest default to message/rfc822
+        let child_type = if content_type == "multipart/digest" {
+            "message/rfc822"
+        } else {
+            "text/plain"
+        };
+        let parts: Vec<Value> = split_multipart(body, boundary)
+            .into_iter()
+            .map(|child| parse_part(child, child_type, depth + 1, attachments))
+            .collect();
+        part.insert("parts".into(), parts.into());
+        return Value::Object(part);
+    }
+
+    let bytes = match encoding.as_str() {
+        "base64" => match decode_base64_lenient(body) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                part.insert("error".into(), e.into());
+                body.as_bytes().to_vec()
+            }
+        },
+        "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.as_bytes().to_vec(),
+    };
+    part.insert("size".into(), bytes.len().into());
+
+    if depth < MAX_DEPTH && content_type == "message/rfc822" {
+        let inner = String::from_utf8_lossy(&bytes);
+        part.insert("message".into(), parse_message(&inner, depth + 1, attachments));
+        return Value::Object(part);
+    }
+
+    if disposition.as_deref() == Some("attachment") || filename.is_some() {
+        attachments.push(json!({
+            "filename": filename,
+            "content_type": content_type,
+            "size": bytes.len()
+        }));
+    }
+    body_json(&content_type, charset, &bytes, &mut part);
+    Value::Object(part)
+}
+
+fn parse_message(text: &str, depth: usize, attachments: &mut Vec<Value>) -> Value {
+    let (headers, _) = split_message(text);
+    let summary_field = |name: &str| header(&headers, name).map(decode_header_value);
+    let decoded: Vec<Value> = headers
+        .iter()
+        .map(|(name, value)| json!({ "name": name, "value": decode_header_value(value) }))
+        .collect();
+    json!({
+        "summary": {
+            "from": summary_field("From"),
+            "to": summary_field("To"),
+            "cc": summary_field("Cc"),
+            "subject": summary_field("Subject"),
+            "date": summary_field("Date"),
+            "message_id": summary_field("Message-ID")
+        },
+        "headers": decoded,
+        "structure": parse_part(text, "text/plain", depth, attachments)
+    })
+}
+
+pub fn decode_email(text: &str) -> Result<String, String> {
+    let text = text.trim_start();
+    if text.is_empty() {
+        return Err("Empty email input".to_string());
+    }
+    let (headers, _) = split_message(text);
+    if headers.is_empty() {
+        return Err("No email headers found; expected lines like `From: ...` before a blank line".to_string());
+    }
+    let mut attachments = Vec::new();
+    let mut message = parse_message(text, 0, &mut attachments);
+    message["attachments"] = attachments.into();
+    crate::formatters::to_string_pretty(&message).map_err(|e| format!("Failed to format email: {}", e))
+}
+
+pub struct EmailFormatter;
+
+impl Formatter for EmailFormatter {
+    fn id(&self) -> &'static str {
+        "email"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["eml", "mime"]
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Email (MIME) Decoder"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        crate::formatters::layout_options_schema(crate::formatters::PRETTY_OPTIONS)
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        decode_email(input)
+    }
+}
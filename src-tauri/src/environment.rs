@@ -0,0 +1,219 @@
+// Shows the environment devmate runs with and, on request, the one a login
+// shell sets up, which is what a terminal sees. On macOS GUI apps get a much
+// smaller environment than the shell (PATH in particular), and comparing the
+// two answers most "works in the terminal, not in the app" questions. PATH-like
+// variables are split into entries with missing directories and duplicates
+// flagged. Values that look like credentials are masked unless revealed.
+use std::collections::BTreeMap;
+
+use crate::clipboard_history;
+
+#[cfg(unix)]
+const SHELL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+const PATH_VARIABLES: &[&str] = &[
+    "PATH",
+    "MANPATH",
+    "LD_LIBRARY_PATH",
+    "DYLD_LIBRARY_PATH",
+    "PYTHONPATH",
+    "CLASSPATH",
+];
+const SECRET_NAME_PARTS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "API_KEY",
+    "PRIVATE_KEY",
+    "CREDENTIAL",
+];
+
+fn app_environment() -> BTreeMap<String, String> {
+    std::env::vars_os()
+        .map(|(name, value)| {
+            (
+                name.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+        .collect()
+}
+
+// Runs `$SHELL -l -c 'env -0'` so the shell's profile files are applied. The
+// shell is killed if it hangs, e.g. on a profile that waits for input.
+#[cfg(unix)]
+fn login_shell_environment() -> Result<(String, BTreeMap<String, String>), String> {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let mut child = Command::new(&shell)
+        .args(["-l", "-c", "env -0"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", shell, e))?;
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "No output from the shell".to_string())?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break status,
+            None if started.elapsed() > SHELL_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{} didn't finish within {} seconds",
+                    shell,
+                    SHELL_TIMEOUT.as_secs()
+                ));
+            }
+            None => std::thread::sleep(Duration::from_millis(20)),
+        }
+    };
+    let output = reader
+        .join()
+        .map_err(|_| "Failed to read the shell's output".to_string())?
+        .map_err(|e| format!("Failed to read the shell's output: {}", e))?;
+    if !status.success() {
+        return Err(format!("{} exited with {}", shell, status));
+    }
+
+    let variables = output
+        .split(|byte| *byte == 0)
+        .filter_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (name, value) = entry.split_once('=')?;
+            (!name.is_empty()).then(|| (name.to_string(), value.to_string()))
+        })
+        .collect();
+    Ok((shell, variables))
+}
+
+#[cfg(not(unix))]
+fn login_shell_environment() -> Result<(String, BTreeMap<String, String>), String> {
+    Err("Login shell environments are only available on macOS and Linux".to_string())
+}
+
+fn is_secret(name: &str, value: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    SECRET_NAME_PARTS.iter().any(|part| upper.contains(part)) || clipboard_history::detect_secret(value).is_some()
+}
+
+// Entries in order, each with whether it exists and, for repeats, the position
+// of its first occurrence (a later duplicate never takes effect)
+fn split_path_variable(value: &str) -> serde_json::Value {
+    let mut seen: Vec<String> = Vec::new();
+    let entries: Vec<serde_json::Value> = std::env::split_paths(value)
+        .enumerate()
+        .map(|(position, path)| {
+            let display = path.to_string_lossy().into_owned();
+            let normalized = display.trim_end_matches(['/', '\\']).to_string();
+            let duplicate_of = seen.iter().position(|earlier| *earlier == normalized);
+            seen.push(normalized);
+            serde_json::json!({
+                "position": position,
+                "path": display,
+                "exists": !display.is_empty() && path.is_dir(),
+                "duplicate_of": duplicate_of
+            })
+        })
+        .collect();
+    let duplicates = entries.iter().filter(|entry| !entry["duplicate_of"].is_null()).count();
+    serde_json::json!({ "entries": entries, "duplicates": duplicates })
+}
+
+fn describe(
+    variables: &BTreeMap<String, String>,
+    query: &str,
+    reveal_secrets: bool,
+) -> (Vec<serde_json::Value>, serde_json::Map<String, serde_json::Value>) {
+    let mut listed = Vec::new();
+    let mut paths = serde_json::Map::new();
+    for (name, value) in variables {
+        let secret = is_secret(name, value);
+        // Masked values only match by name
+        let matches = query.is_empty()
+            || name.to_lowercase().contains(query)
+            || ((!secret || reveal_secrets) && value.to_lowercase().contains(query));
+        if !matches {
+            continue;
+        }
+        if PATH_VARIABLES.contains(&name.as_str()) {
+            paths.insert(name.clone(), split_path_variable(value));
+        }
+        let shown = if secret && !reveal_secrets {
+            clipboard_history::mask(value)
+        } else {
+            value.clone()
+        };
+        listed.push(serde_json::json!({ "name": name, "value": shown, "secret": secret }));
+    }
+    (listed, paths)
+}
+
+// Variable names only the shell has, only the app has, or that differ
+fn compare(app: &BTreeMap<String, String>, shell: &BTreeMap<String, String>) -> serde_json::Value {
+    let only_in_shell: Vec<&String> = shell.keys().filter(|name| !app.contains_key(*name)).collect();
+    let only_in_app: Vec<&String> = app.keys().filter(|name| !shell.contains_key(*name)).collect();
+    let different: Vec<&String> = app
+        .iter()
+        .filter(|(name, value)| shell.get(*name).is_some_and(|other| other != *value))
+        .map(|(name, _)| name)
+        .collect();
+    serde_json::json!({
+        "only_in_shell": only_in_shell,
+        "only_in_app": only_in_app,
+        "different": different
+    })
+}
+
+// The app's environment, filtered by `query` (matched against names and
+// unmasked values). With `include_login_shell` the login shell's environment
+// and a comparison with the app's are added; a shell that fails is reported
+// in `shell_error` rather than failing the whole call.
+#[tauri::command]
+pub async fn get_environment(
+    query: Option<String>,
+    include_login_shell: Option<bool>,
+    reveal_secrets: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("get_environment", move || {
+        let query = query.unwrap_or_default().trim().to_lowercase();
+        let reveal_secrets = reveal_secrets.unwrap_or(false);
+        let app = app_environment();
+        let (variables, paths) = describe(&app, &query, reveal_secrets);
+        let mut reply = serde_json::json!({
+            "count": app.len(),
+            "variables": variables,
+            "paths": paths
+        });
+
+        if include_login_shell.unwrap_or(false) {
+            match login_shell_environment() {
+                Ok((shell, shell_variables)) => {
+                    let (variables, paths) = describe(&shell_variables, &query, reveal_secrets);
+                    reply["login_shell"] = serde_json::json!({
+                        "shell": shell,
+                        "count": shell_variables.len(),
+                        "variables": variables,
+                        "paths": paths,
+                        "comparison": compare(&app, &shell_variables)
+                    });
+                }
+                Err(error) => reply["shell_error"] = error.into(),
+            }
+        }
+        Ok(reply)
+    })
+    .await
+}
@@ -0,0 +1,112 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::progress::{ProgressReporter, FILE_WRITE_PROGRESS_EVENT};
+use crate::responses::SaveResult;
+use crate::text_buffer::TextBuffer;
+use crate::AppState;
+
+const WRITE_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+enum Snapshot {
+    Text(TextBuffer),
+    Binary(Vec<u8>),
+}
+
+impl Snapshot {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Snapshot::Text(text) => text.as_bytes(),
+            Snapshot::Binary(bytes) => bytes,
+        }
+    }
+}
+
+// Sibling of `path` in the same directory, so the final rename can't cross filesystems
+fn temp_path_for(path: &Path) -> Result<PathBuf, String> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("Invalid file path: {}", path.display()))?;
+    let mut temp_name = OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    Ok(path.with_file_name(temp_name))
+}
+
+fn write_chunks(temp_path: &Path, bytes: &[u8], reporter: &mut ProgressReporter) -> Result<usize, String> {
+    let file = File::create(temp_path).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    let mut written = 0;
+    for chunk in bytes.chunks(WRITE_CHUNK_SIZE) {
+        writer.write_all(chunk).map_err(|e| format!("Failed to write file: {}", e))?;
+        written += chunk.len();
+        reporter.update(written as u64);
+    }
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write file: {}", e.error()))?
+        .sync_all()
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(written)
+}
+
+fn save_content_to_file_blocking(
+    app: &AppHandle,
+    content_type: String,
+    path: String,
+) -> Result<SaveResult, String> {
+    // Only the buffer handle is cloned, so the storage lock is released before
+    // the write and other commands aren't held up by a multi-GB export
+    let snapshot = {
+        let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let document = storage.active();
+        match content_type.as_str() {
+            "raw" => document.raw_content.clone().map(Snapshot::Text),
+            "formatted" => document.formatted_content.clone().map(Snapshot::Text),
+            "binary" => document.binary_content.clone().map(Snapshot::Binary),
+            _ => return Err("Invalid content type".to_string()),
+        }
+        .ok_or_else(|| "No content stored".to_string())?
+    };
+    let bytes = snapshot.as_bytes();
+
+    // The content goes to a temp file that is then renamed over the target,
+    // so a failed write never leaves the target half-written
+    let target = Path::new(&path);
+    let temp_path = temp_path_for(target)?;
+    let mut reporter = ProgressReporter::new(
+        app,
+        FILE_WRITE_PROGRESS_EVENT,
+        bytes.len() as u64,
+        serde_json::json!({ "file_path": path }),
+    );
+    let result = write_chunks(&temp_path, bytes, &mut reporter).and_then(|written| {
+        std::fs::rename(&temp_path, target)
+            .map(|()| written)
+            .map_err(|e| format!("Failed to replace {}: {}", path, e))
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    let written = result?;
+    reporter.update(written as u64);
+
+    Ok(SaveResult {
+        file_path: path,
+        content_type,
+        bytes_written: written,
+    })
+}
+
+// Streams stored content straight to disk so large results never pass through the webview
+#[tauri::command]
+pub async fn save_content_to_file(
+    app: AppHandle,
+    content_type: String,
+    path: String,
+) -> Result<SaveResult, String> {
+    crate::run_blocking("save_content_to_file", move || save_content_to_file_blocking(&app, content_type, path)).await
+}
@@ -0,0 +1,208 @@
+// Loads remote content (presigned S3 links, raw gists, API responses) straight
+// into the active document, so large downloads never pass through the webview.
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::jobs::CancelToken;
+use crate::progress::{self, ProgressReporter};
+use crate::{charset, AppState};
+
+const DEFAULT_MAX_BYTES: u64 = 512 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 10;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const READ_CHUNK_SIZE: usize = 1024 * 1024;
+
+// One client for the whole app so connections and TLS sessions are reused
+pub fn http_client() -> Result<&'static reqwest::blocking::Client, String> {
+    static CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(concat!("devmate/", env!("CARGO_PKG_VERSION")))
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+        .connect_timeout(CONNECT_TIMEOUT)
+        // Downloads may legitimately take a long time; only stalled connects time out
+        .timeout(None)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+pub fn parse_http_url(url: &str) -> Result<reqwest::Url, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(parsed),
+        scheme => Err(format!("Only http and https URLs are supported, not {}", scheme)),
+    }
+}
+
+// Charset named in a Content-Type header, e.g. `text/plain; charset=ISO-8859-1`
+fn content_type_charset(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+// GETs a small text resource (robots.txt, a sitemap) and returns the final URL
+// and the decoded body. Gzipped bodies (sitemap.xml.gz) are unpacked.
+pub fn fetch_text(url: &str, max_bytes: u64) -> Result<(String, String), String> {
+    let response = http_client()?
+        .get(parse_http_url(url)?)
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{} responded with {}", url, status));
+    }
+    let final_url = response.url().to_string();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let mut body = Vec::new();
+    response
+        .take(max_bytes + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Download failed: {}", e))?;
+    if body.starts_with(&[0x1f, 0x8b]) {
+        let mut unpacked = Vec::new();
+        flate2::read::GzDecoder::new(body.as_slice())
+            .take(max_bytes + 1)
+            .read_to_end(&mut unpacked)
+            .map_err(|e| format!("Failed to decompress: {}", e))?;
+        body = unpacked;
+    }
+    if body.len() as u64 > max_bytes {
+        return Err(format!("Response is over the {} byte limit", max_bytes));
+    }
+    let label = content_type.as_deref().and_then(content_type_charset);
+    let (content, _, _) = charset::decode_to_utf8(&body, label).or_else(|_| charset::decode_to_utf8(&body, None))?;
+    Ok((final_url, content))
+}
+
+fn load_from_url_blocking(
+    app: &AppHandle,
+    token: &CancelToken,
+    url: String,
+    headers: HashMap<String, String>,
+    max_bytes: u64,
+) -> Result<serde_json::Value, String> {
+    let url = parse_http_url(&url)?;
+    let mut request = http_client()?.get(url.clone());
+    for (name, value) in &headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let mut response = request.send().map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Server responded with {}", status));
+    }
+    if let Some(length) = response.content_length().filter(|length| *length > max_bytes) {
+        return Err(format!("Response is {} bytes, over the {} byte limit", length, max_bytes));
+    }
+
+    // The body lands in whichever document was active when the request started
+    let document_id = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?.active_id;
+    let final_url = response.url().to_string();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let mut reporter = ProgressReporter::new(
+        app,
+        progress::URL_LOAD_PROGRESS_EVENT,
+        response.content_length().unwrap_or(0),
+        serde_json::json!({ "url": url.as_str() }),
+    );
+
+    let mut body = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+    let mut buffer = vec![0u8; READ_CHUNK_SIZE];
+    loop {
+        token.check()?;
+        let read = response.read(&mut buffer).map_err(|e| format!("Download failed: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        if (body.len() + read) as u64 > max_bytes {
+            return Err(format!("Response is over the {} byte limit", max_bytes));
+        }
+        body.extend_from_slice(&buffer[..read]);
+        reporter.update(body.len() as u64);
+    }
+
+    let label = content_type.as_deref().and_then(content_type_charset);
+    let (content, encoding, had_errors) = charset::decode_to_utf8(&body, label)
+        .or_else(|_| charset::decode_to_utf8(&body, None))?;
+    drop(body);
+    let length = content.len();
+
+    let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let document = storage
+        .documents
+        .get_mut(&document_id)
+        .ok_or_else(|| "The document was closed while the URL was loading".to_string())?;
+    document.raw_content = Some(content.into());
+    document.formatted_content = None;
+    document.binary_content = None;
+    document.clear_history();
+
+    Ok(serde_json::json!({
+        "success": true,
+        "url": final_url,
+        "redirected": final_url != url.as_str(),
+        "status": status.as_u16(),
+        "content_type": content_type,
+        "encoding": encoding,
+        "had_replacement_characters": had_errors,
+        "length": length
+    }))
+}
+
+// `headers` are sent as given (Authorization, cookies, ...); `max_bytes`
+// defaults to 512MB. Progress arrives as `url://load-progress`.
+#[tauri::command]
+pub async fn load_from_url(
+    app: AppHandle,
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    max_bytes: Option<u64>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("load_from_url", move || {
+        load_from_url_blocking(
+            &app,
+            &CancelToken::default(),
+            url,
+            headers.unwrap_or_default(),
+            max_bytes.unwrap_or(DEFAULT_MAX_BYTES),
+        )
+    })
+    .await
+}
+
+// Cancellable variant of load_from_url; the result arrives through `job://finished`
+#[tauri::command]
+pub fn start_url_load(
+    app: AppHandle,
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    max_bytes: Option<u64>,
+) -> Result<u64, String> {
+    crate::jobs::spawn_job(&app, "url-load", move |app, token| {
+        load_from_url_blocking(
+            app,
+            token,
+            url,
+            headers.unwrap_or_default(),
+            max_bytes.unwrap_or(DEFAULT_MAX_BYTES),
+        )
+    })
+}
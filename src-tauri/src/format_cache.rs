@@ -0,0 +1,124 @@
+// Formatter outputs keyed by what produced them, so switching back and forth
+// between views of the same large document doesn't re-run the formatter.
+// Least recently used entries are dropped once the byte budget is exceeded.
+use tauri::State;
+
+use crate::text_buffer::TextBuffer;
+use crate::AppState;
+
+const DEFAULT_MAX_BYTES: usize = 512 * 1024 * 1024;
+
+#[derive(Clone, PartialEq)]
+pub struct CacheKey {
+    content_hash: blake3::Hash,
+    format_type: String,
+    // Serialized formatter options, so differently configured runs don't collide
+    options: String,
+}
+
+impl CacheKey {
+    pub fn new(content: &str, format_type: &str, options: &serde_json::Value) -> Self {
+        CacheKey {
+            content_hash: blake3::hash(content.as_bytes()),
+            format_type: format_type.to_string(),
+            options: options.to_string(),
+        }
+    }
+}
+
+struct CacheEntry {
+    key: CacheKey,
+    // Shared with the document that displays it, so a hit costs no copy
+    output: TextBuffer,
+}
+
+pub struct FormatCache {
+    // Ordered from least to most recently used
+    entries: Vec<CacheEntry>,
+    total_bytes: usize,
+    max_bytes: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl Default for FormatCache {
+    fn default() -> Self {
+        FormatCache {
+            entries: Vec::new(),
+            total_bytes: 0,
+            max_bytes: DEFAULT_MAX_BYTES,
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl FormatCache {
+    pub fn get(&mut self, key: &CacheKey) -> Option<TextBuffer> {
+        let Some(index) = self.entries.iter().position(|entry| entry.key == *key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        let entry = self.entries.remove(index);
+        let output = entry.output.clone();
+        self.entries.push(entry);
+        Some(output)
+    }
+
+    pub fn insert(&mut self, key: CacheKey, output: TextBuffer) {
+        // Outputs larger than the whole budget would just evict everything else
+        if output.len() > self.max_bytes {
+            return;
+        }
+        if let Some(index) = self.entries.iter().position(|entry| entry.key == key) {
+            self.total_bytes -= self.entries.remove(index).output.len();
+        }
+        self.total_bytes += output.len();
+        self.entries.push(CacheEntry { key, output });
+        self.evict();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.total_bytes = 0;
+    }
+
+    fn evict(&mut self) {
+        while self.total_bytes > self.max_bytes && !self.entries.is_empty() {
+            self.total_bytes -= self.entries.remove(0).output.len();
+        }
+    }
+
+    fn info(&self) -> serde_json::Value {
+        serde_json::json!({
+            "entries": self.entries.len(),
+            "total_bytes": self.total_bytes,
+            "max_bytes": self.max_bytes,
+            "hits": self.hits,
+            "misses": self.misses
+        })
+    }
+}
+
+#[tauri::command]
+pub fn get_format_cache_info(state: State<AppState>) -> Result<serde_json::Value, String> {
+    let storage = state.lock().map_err(|e| e.to_string())?;
+    Ok(storage.format_cache.info())
+}
+
+// Changing the budget evicts immediately; zero turns caching off
+#[tauri::command]
+pub fn set_format_cache_limit(max_bytes: usize, state: State<AppState>) -> Result<serde_json::Value, String> {
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.format_cache.max_bytes = max_bytes;
+    storage.format_cache.evict();
+    Ok(storage.format_cache.info())
+}
+
+#[tauri::command]
+pub fn clear_format_cache(state: State<AppState>) -> Result<(), String> {
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.format_cache.clear();
+    Ok(())
+}
@@ -0,0 +1,283 @@
+// Guesses which formatter fits a piece of text, for the "format automatically"
+// button. Large content is judged on a prefix, so checks that need the whole
+// input (full JSON/YAML parses) only run when `complete` is set.
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+
+use super::FormatterRegistry;
+
+// How much of stored content is inspected
+pub const SAMPLE_BYTES: usize = 1024 * 1024;
+
+const SAML_NAMESPACE: &str = "urn:oasis:names:tc:SAML";
+
+#[derive(Clone, Debug)]
+pub struct FormatGuess {
+    pub format: &'static str,
+    // Formatter that handles this format, if one is registered
+    pub formatter_id: Option<&'static str>,
+    pub confidence: f64,
+}
+
+impl FormatGuess {
+    fn new(format: &'static str, formatter_id: Option<&'static str>, confidence: f64) -> Self {
+        FormatGuess {
+            format,
+            formatter_id,
+            confidence,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "format": self.format,
+            "formatter_id": self.formatter_id,
+            "confidence": self.confidence
+        })
+    }
+}
+
+// Candidates ordered from most to least likely; empty input yields none
+pub fn guess_formats(text: &str, complete: bool, registry: &FormatterRegistry) -> Vec<FormatGuess> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Vec::new();
+    }
+
+    let mut guesses = Vec::new();
+    guess_json(trimmed, complete, &mut guesses);
+    guess_stringified_json(trimmed, complete, &mut guesses);
+    guess_markup(trimmed, complete, &mut guesses);
+    guess_pem(trimmed, &mut guesses);
+    if looks_like_diff(trimmed) {
+        guesses.push(FormatGuess::new("diff", Some("diff"), 0.95));
+    }
+    if looks_like_jwt(trimmed) {
+        guesses.push(FormatGuess::new("jwt", Some("jwt"), 0.98));
+    }
+    guess_encoded(trimmed, &mut guesses);
+    if complete {
+        guess_yaml(trimmed, &mut guesses);
+    }
+    guesses.push(FormatGuess::new("text", None, 0.1));
+
+    // A plugin may have replaced or removed a built-in formatter
+    for guess in guesses.iter_mut() {
+        if guess.formatter_id.is_some_and(|id| registry.get(id).is_none()) {
+            guess.formatter_id = None;
+        }
+    }
+    guesses.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+    // Keep the best guess per format
+    let mut seen = Vec::new();
+    guesses.retain(|guess| {
+        let first = !seen.contains(&guess.format);
+        seen.push(guess.format);
+        first
+    });
+    guesses
+}
+
+fn parses_as_json(text: &str) -> bool {
+    serde_json::from_str::<serde::de::IgnoredAny>(text).is_ok()
+}
+
+fn guess_json(text: &str, complete: bool, guesses: &mut Vec<FormatGuess>) {
+    if !(text.starts_with('{') || text.starts_with('[')) {
+        return;
+    }
+    // Extended JSON from mongoexport or a driver; plain JSON still ranks higher
+    // when the text parses, as the JSON formatter handles it too
+    if ["\"$oid\"", "\"$date\"", "\"$numberLong\""].iter().any(|marker| text.contains(marker)) {
+        guesses.push(FormatGuess::new("mongo-json", Some("mongo-json"), 0.9));
+    }
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    let first_line = lines.next().unwrap_or_default();
+    let multi_line = lines.next().is_some();
+
+    if complete && parses_as_json(text) {
+        guesses.push(FormatGuess::new("json", Some("json"), 0.99));
+        return;
+    }
+
+    // NDJSON: every line is a document on its own. A truncated sample may end
+    // mid-line, so the last line only counts when the input is complete.
+    if multi_line && parses_as_json(first_line) {
+        let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+        let checked = if complete { &lines[..] } else { &lines[..lines.len() - 1] };
+        if checked.iter().all(|line| parses_as_json(line)) {
+            guesses.push(FormatGuess::new("ndjson", None, 0.95));
+            return;
+        }
+    }
+
+    if complete {
+        if json5::from_str::<serde_json::Value>(text).is_ok() {
+            guesses.push(FormatGuess::new("json5", Some("json5"), 0.9));
+        } else {
+            // Still most likely JSON; the formatter will point at the error
+            guesses.push(FormatGuess::new("json", Some("json"), 0.6));
+        }
+    } else {
+        guesses.push(FormatGuess::new("json", Some("json"), 0.85));
+    }
+}
+
+// JSON serialized into a string (`"{\"a\":1}"`), or its escaped body on its
+// own. A sample that's cut off can't be parsed, so its opening has to do.
+fn guess_stringified_json(text: &str, complete: bool, guesses: &mut Vec<FormatGuess>) {
+    if complete {
+        let candidate = text.starts_with('"') || text.starts_with("{\\\"") || text.starts_with("[{\\\"");
+        if candidate && crate::json_unwrap::unwrap_stringified(text).is_some() {
+            guesses.push(FormatGuess::new("stringified-json", Some("json-unwrap"), 0.995));
+        }
+    } else if ["\"{\\\"", "\"[{\\\"", "\"[\\\"", "\"\\\"", "{\\\"", "[{\\\""]
+        .iter()
+        .any(|prefix| text.starts_with(prefix))
+    {
+        guesses.push(FormatGuess::new("stringified-json", Some("json-unwrap"), 0.9));
+    }
+}
+
+fn guess_markup(text: &str, complete: bool, guesses: &mut Vec<FormatGuess>) {
+    if !text.starts_with('<') {
+        return;
+    }
+    let opens_tag = text[1..]
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '?' || c == '!' || c == '_');
+    if !opens_tag {
+        return;
+    }
+    if text.contains(SAML_NAMESPACE) {
+        guesses.push(FormatGuess::new("saml", Some("saml"), 0.97));
+    }
+    let lower: String = text.chars().take(32).collect::<String>().to_ascii_lowercase();
+    if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        guesses.push(FormatGuess::new("html", Some("xml"), 0.9));
+    } else if text.starts_with("<?xml") {
+        guesses.push(FormatGuess::new("xml", Some("xml"), 0.97));
+    } else if !complete || text.ends_with('>') {
+        guesses.push(FormatGuess::new("xml", Some("xml"), 0.85));
+    }
+}
+
+fn guess_pem(text: &str, guesses: &mut Vec<FormatGuess>) {
+    if !text.starts_with("-----BEGIN ") {
+        return;
+    }
+    if text.starts_with("-----BEGIN CERTIFICATE-----") {
+        guesses.push(FormatGuess::new("x509", Some("x509"), 0.98));
+    } else {
+        guesses.push(FormatGuess::new("pem", Some("asn1"), 0.95));
+    }
+}
+
+fn looks_like_jwt(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('.').collect();
+    if parts.len() != 3 || !parts[0].starts_with("eyJ") {
+        return false;
+    }
+    URL_SAFE_NO_PAD
+        .decode(parts[0].trim_end_matches('='))
+        .ok()
+        .and_then(|header| serde_json::from_slice::<serde_json::Value>(&header).ok())
+        .is_some_and(|header| header.get("alg").is_some())
+}
+
+// `git diff` / format-patch output, or a plain `diff -u` header pair
+fn looks_like_diff(text: &str) -> bool {
+    if text.starts_with("diff --git ") || (text.starts_with("From ") && text.contains("\ndiff --git ")) {
+        return true;
+    }
+    let mut lines = text.lines();
+    lines.next().is_some_and(|line| line.starts_with("--- "))
+        && lines.next().is_some_and(|line| line.starts_with("+++ "))
+        && lines.next().is_some_and(|line| line.starts_with("@@ -"))
+}
+
+fn is_der_sequence(bytes: &[u8]) -> bool {
+    bytes.len() > 2 && bytes[0] == 0x30 && (bytes[1] < 0x80 || (0x81..=0x84).contains(&bytes[1]))
+}
+
+// Base64, hex and URL-encoded text
+fn guess_encoded(text: &str, guesses: &mut Vec<FormatGuess>) {
+    let compact: String = text.split_whitespace().collect();
+    if compact.len() < 8 {
+        return;
+    }
+
+    let hex_digits = compact
+        .strip_prefix("0x")
+        .unwrap_or(&compact)
+        .replace(':', "");
+    let is_hex = hex_digits.len().is_multiple_of(2) && hex_digits.chars().all(|c| c.is_ascii_hexdigit());
+    if is_hex {
+        let der = hex_digits.starts_with("30") && is_der_sequence(&decode_hex_prefix(&hex_digits));
+        let formatter = if der { Some("asn1") } else { None };
+        // Pure digits are more often a number than a hex dump
+        let confidence = if hex_digits.chars().all(|c| c.is_ascii_digit()) { 0.3 } else { 0.75 };
+        guesses.push(FormatGuess::new("hex", formatter, if der { 0.85 } else { confidence }));
+    }
+
+    let base64_chars = compact.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '=');
+    if base64_chars && compact.len().is_multiple_of(4) {
+        if let Ok(bytes) = STANDARD.decode(compact.as_bytes()) {
+            let guess = match std::str::from_utf8(&bytes) {
+                Ok(decoded) if decoded.contains(SAML_NAMESPACE) => FormatGuess::new("saml", Some("saml"), 0.95),
+                Ok(decoded) if decoded.chars().all(|c| !c.is_control() || c.is_whitespace()) => {
+                    FormatGuess::new("base64", Some("decode"), 0.8)
+                }
+                _ if is_der_sequence(&bytes) => FormatGuess::new("base64-der", Some("asn1"), 0.85),
+                // Hex strings are valid base64 too, so binary results stay below hex
+                _ => FormatGuess::new("base64", Some("decode"), if is_hex { 0.5 } else { 0.65 }),
+            };
+            guesses.push(guess);
+        }
+    }
+
+    if looks_like_url_encoded(text) {
+        let formatter = if text.contains("SAMLResponse=") || text.contains("SAMLRequest=") {
+            Some("saml")
+        } else {
+            None
+        };
+        guesses.push(FormatGuess::new("url-encoded", formatter, 0.7));
+    }
+}
+
+fn decode_hex_prefix(hex: &str) -> Vec<u8> {
+    hex.as_bytes()
+        .chunks(2)
+        .take(4)
+        .filter_map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+fn looks_like_url_encoded(text: &str) -> bool {
+    if text.chars().any(char::is_whitespace) {
+        return false;
+    }
+    let has_escape = text.as_bytes().windows(3).any(|w| {
+        w[0] == b'%' && w[1].is_ascii_hexdigit() && w[2].is_ascii_hexdigit()
+    });
+    let query = text.split_once('?').map(|(_, query)| query).unwrap_or(text);
+    let pairs: Vec<&str> = query.split('&').collect();
+    let form_like = pairs.iter().all(|pair| {
+        pair.split_once('=')
+            .is_some_and(|(key, _)| !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || "_-.[]%".contains(c)))
+    });
+    has_escape || (form_like && (pairs.len() > 1 || text.contains('+')))
+}
+
+fn guess_yaml(text: &str, guesses: &mut Vec<FormatGuess>) {
+    // Plain scalars are valid YAML too; only mappings and sequences count
+    let structured = serde_yaml::from_str::<serde_yaml::Value>(text)
+        .is_ok_and(|value| value.is_mapping() || value.is_sequence());
+    if !structured {
+        return;
+    }
+    let confidence = if text.starts_with("---") || text.starts_with("%YAML") { 0.9 } else { 0.7 };
+    guesses.push(FormatGuess::new("yaml", Some("yaml"), confidence));
+}
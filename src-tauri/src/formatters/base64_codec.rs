@@ -0,0 +1,65 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use super::Formatter;
+
+pub fn encode_base64(text: &str) -> Result<String, String> {
+    if text.is_empty() {
+        return Ok(String::new()); // Return empty string instead of error
+    }
+    Ok(STANDARD.encode(text.as_bytes()))
+}
+
+pub fn decode_base64(text: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(String::new()); // Return empty string instead of error
+    }
+    match STANDARD.decode(text) {
+        Ok(decoded_bytes) => match String::from_utf8(decoded_bytes) {
+            Ok(decoded_string) => Ok(decoded_string),
+            Err(e) => Err(format!("Invalid UTF-8 in decoded data: {}", e)),
+        },
+        Err(e) => Err(format!("Invalid base64 encoding: {}", e)),
+    }
+}
+
+pub struct Base64Encoder;
+
+impl Formatter for Base64Encoder {
+    fn id(&self) -> &'static str {
+        "encode"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Base64 Encode"
+    }
+
+    fn stores_output(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        encode_base64(input)
+    }
+}
+
+pub struct Base64Decoder;
+
+impl Formatter for Base64Decoder {
+    fn id(&self) -> &'static str {
+        "decode"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Base64 Decode"
+    }
+
+    fn stores_output(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        decode_base64(input)
+    }
+}
@@ -0,0 +1,315 @@
+use super::{json_stream, mongo, to_string_pretty, Formatter};
+
+// Above this size the Value tree (several times the input size) is skipped in
+// favour of the streaming pretty-printer
+const STREAMING_THRESHOLD: usize = 64 * 1024 * 1024;
+
+pub fn format_json(text: &str) -> Result<String, String> {
+    format_json_with_progress(text, &mut |_| Ok(()))
+}
+
+// JSONC input (comments, trailing commas, as in tsconfig.json or VS Code
+// settings) is accepted and the output is always strict JSON. Other JSON5
+// syntax such as unquoted keys or single quotes goes through `json5` instead.
+pub fn format_json_with_progress(
+    text: &str,
+    progress: &mut dyn FnMut(usize) -> Result<(), String>,
+) -> Result<String, String> {
+    let stripped = strip_jsonc(text);
+    let text = stripped.as_deref().unwrap_or(text);
+
+    // A Value always comes out with sorted keys, so keeping the input order
+    // means using the streaming printer at any size. It honours the same
+    // options as the tree path.
+    if text.len() > STREAMING_THRESHOLD || !super::options().sort_keys {
+        return json_stream::pretty_print(text, progress);
+    }
+    let result = format_json_tree(text);
+    progress(text.len())?;
+    result
+}
+
+fn format_json_tree(text: &str) -> Result<String, String> {
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(parsed) if mongo::contains_extended(&parsed) => Ok(mongo::pretty_print(&parsed)),
+        Ok(parsed) => {
+            match to_string_pretty(&parsed) {
+                Ok(formatted) => Ok(formatted),
+                Err(e) => Err(format!("Failed to format JSON: {}", e))
+            }
+        },
+        Err(e) => {
+            // Get line and column from serde_json::Error
+            let line = e.line();
+            let column = e.column();
+            let lines: Vec<&str> = text.lines().collect();
+            let error_line = lines.get(line.saturating_sub(1)).unwrap_or(&"");
+
+            // Check for common error patterns and provide user-friendly messages
+            let error_str = e.to_string();
+            let user_friendly_error = if error_str.contains("trailing characters") {
+                // This is likely JSONL/NDJSON format (multiple JSON objects on separate lines)
+                let total_lines = lines.len();
+                format!(
+                    "❌ Invalid JSON Format - Multiple JSON Objects Detected\n\n\
+                    Your file contains {} lines with separate JSON objects (JSONL/NDJSON format).\n\
+                    Standard JSON requires a single object or array.\n\n\
+                    To fix this, you have two options:\n\n\
+                    1. **Convert to JSON Array**: Wrap all objects in square brackets and separate with commas:\n\
+                       [\n         {{first object}},\n         {{second object}}\n       ]\n\n\
+                    2. **Process as JSONL**: Each line is a separate JSON object (not supported in JSON formatter)\n\n\
+                    Error details:\n\
+                    - Line {}: Found additional JSON object after the first one\n\
+                    - Total objects detected: {} lines\n\
+                    - First object ends at character position in line {}",
+                    total_lines, line, total_lines, line
+                )
+            } else if error_str.contains("expected") {
+                // Missing comma, bracket, etc.
+                let mut marker = String::new();
+                for _ in 0..column {
+                    marker.push('-');
+                }
+                marker.push('^');
+                
+                format!(
+                    "❌ JSON Syntax Error\n\n\
+                    There's a syntax error in your JSON at line {} column {}.\n\n\
+                    Error location:\n\
+                    {}\n\
+                    {}\n\n\
+                    Common fixes:\n\
+                    • Add missing comma between properties\n\
+                    • Check for unclosed brackets {{ }} or [ ]\n\
+                    • Ensure strings are properly quoted\n\n\
+                    Detailed error: {}",
+                    line, column + 1, error_line, marker, e
+                )
+            } else {
+                // Generic JSON error with helpful context and precise error location
+                let mut marker = String::new();
+                for _ in 0..column {
+                    marker.push('-');
+                }
+                marker.push('^');
+                
+                format!(
+                    "❌ JSON Parse Error\n\n\
+                    Invalid JSON format detected at line {} column {}.\n\n\
+                    Error location:\n\
+                    {}\n\
+                    {}\n\n\
+                    Please check:\n\
+                    • All strings are enclosed in double quotes\n\
+                    • Properties are separated by commas\n\
+                    • Objects are enclosed in {{ }}\n\
+                    • Arrays are enclosed in [ ]\n\n\
+                    Detailed error: {}\n\n\
+                    Raw parse error:\n\
+                    Parse error on line {} column {}:\n\
+                    {}\n\
+                    {}\n\
+                    {}",
+                    line, column + 1, error_line, marker, e,
+                    line, column + 1, error_line, marker, e
+                )
+            };
+            
+            Err(user_friendly_error)
+        }
+    }
+}
+
+// Blanks out JSONC comments and trailing commas, keeping newlines, so the
+// strict parsers accept the text and error positions still match the input.
+// None when there's nothing to strip, which is always the case for strict JSON.
+fn strip_jsonc(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut stripped: Option<Vec<u8>> = None;
+    let mut blank = |start: usize, end: usize| {
+        let out = stripped.get_or_insert_with(|| bytes.to_vec());
+        for byte in &mut out[start..end] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    };
+
+    let mut in_string = false;
+    // A comma that is trailing if the next token closes the container. Only a
+    // comma after a value counts, so `[,]` and `{,}` stay invalid.
+    let mut pending_comma = None;
+    let mut after_value = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if in_string {
+            match byte {
+                b'\\' => i += 1,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        match byte {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = bytes[i..].iter().position(|b| *b == b'\n').map_or(bytes.len(), |n| i + n);
+                blank(i, end);
+                i = end;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = bytes[i + 2..]
+                    .windows(2)
+                    .position(|pair| pair == b"*/")
+                    .map_or(bytes.len(), |n| i + 2 + n + 2);
+                blank(i, end);
+                i = end;
+                continue;
+            }
+            b',' => {
+                pending_comma = after_value.then_some(i);
+                after_value = false;
+            }
+            b'}' | b']' => {
+                if let Some(comma) = pending_comma.take() {
+                    blank(comma, comma + 1);
+                }
+                after_value = true;
+            }
+            b'{' | b'[' | b':' => {
+                pending_comma = None;
+                after_value = false;
+            }
+            b' ' | b'\t' | b'\r' | b'\n' => {}
+            _ => {
+                in_string = byte == b'"';
+                pending_comma = None;
+                after_value = true;
+            }
+        }
+        i += 1;
+    }
+
+    // Only ASCII bytes were replaced, and only with spaces
+    stripped.map(|bytes| String::from_utf8(bytes).expect("blanking keeps UTF-8 valid"))
+}
+
+// Strict JSON first; fall back to JSON5, which also covers JSONC comments and trailing commas
+pub fn parse_json_relaxed(text: &str) -> Result<serde_json::Value, String> {
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(value) => Ok(value),
+        Err(strict_error) => json5::from_str::<serde_json::Value>(text)
+            .map_err(|_| format!("Invalid JSON: {}", strict_error)),
+    }
+}
+
+pub fn format_json5(text: &str) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Err("Empty JSON5 input".to_string());
+    }
+    let parsed = json5::from_str::<serde_json::Value>(text)
+        .map_err(|e| format!("Invalid JSON5/JSONC: {}", e))?;
+    // Output is always strict JSON so it can be fed to any other tool
+    to_string_pretty(&parsed).map_err(|e| format!("Failed to format JSON: {}", e))
+}
+
+// Strict JSON and JSONC in, strict JSON out
+pub struct JsonFormatter;
+
+impl Formatter for JsonFormatter {
+    fn id(&self) -> &'static str {
+        "json"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "JSON Format"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        super::layout_options_schema(&["indent_width", "use_tabs", "sort_keys", "line_width"])
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        format_json(input)
+    }
+
+    fn format_with_progress(
+        &self,
+        input: &str,
+        progress: &mut dyn FnMut(usize) -> Result<(), String>,
+    ) -> Result<String, String> {
+        format_json_with_progress(input, progress)
+    }
+}
+
+pub struct Json5Formatter;
+
+impl Formatter for Json5Formatter {
+    fn id(&self) -> &'static str {
+        "json5"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["jsonc"]
+    }
+
+    fn display_name(&self) -> &'static str {
+        "JSON5 / JSONC Format"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        super::layout_options_schema(super::PRETTY_OPTIONS)
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        format_json5(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_and_trailing_commas_are_blanked() {
+        let input = "{\n  // compiler options\n  \"a\": [1, 2,], /* inline */\n  \"b\": \"x // y /* z */\",\n}";
+        let stripped = strip_jsonc(input).unwrap();
+        assert_eq!(stripped.len(), input.len());
+        assert_eq!(stripped.lines().count(), input.lines().count());
+        let value: serde_json::Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(value, serde_json::json!({ "a": [1, 2], "b": "x // y /* z */" }));
+    }
+
+    #[test]
+    fn strict_json_is_left_alone() {
+        assert_eq!(strip_jsonc(r#"{"a": "//,]", "b": [1, {"c": "\"/*"}]}"#), None);
+        assert_eq!(strip_jsonc("[1, 2]"), None);
+    }
+
+    #[test]
+    fn commas_without_a_preceding_value_are_not_trailing() {
+        for input in ["[,]", "{,}", "[ , ]", "[1,,]", "{\"a\": [,]}"] {
+            assert_eq!(strip_jsonc(input), None, "{}", input);
+            assert!(format_json(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn jsonc_formats_to_strict_json() {
+        let input = "{\n  \"compilerOptions\": {\n    \"strict\": true, // always\n  },\n}\n";
+        let formatted = format_json(input).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&formatted).unwrap();
+        assert_eq!(value, serde_json::json!({ "compilerOptions": { "strict": true } }));
+        assert!(!formatted.contains("//"));
+    }
+}
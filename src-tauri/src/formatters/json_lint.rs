@@ -0,0 +1,395 @@
+// Lint-style JSON validation: instead of stopping at the first syntax error the
+// checker assumes the most likely fix (a missing comma, a closed string, an
+// inserted value) and carries on, so a hand-edited file gets every problem
+// reported in one pass. Errors use the same line/column and caret snippet as
+// the formatter's single-error output.
+use super::json_stream::{error_location, scan_number, scan_string, skip_whitespace};
+use super::Formatter;
+
+pub const DEFAULT_MAX_ERRORS: usize = 100;
+// Deeper nesting than this is reported instead of recursed into
+const MAX_DEPTH: usize = 512;
+
+struct Linter<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    // Closing brackets of the containers currently open, innermost last
+    closers: Vec<u8>,
+    errors: Vec<(usize, String)>,
+    max_errors: usize,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, pos: usize, message: &str) {
+        // Recovery can trip over the same spot twice; keep the first message
+        if self.full() || self.errors.last().is_some_and(|(last, _)| *last == pos) {
+            return;
+        }
+        self.errors.push((pos, message.to_string()));
+    }
+
+    fn full(&self) -> bool {
+        self.errors.len() >= self.max_errors
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    // Whitespace and comments; comments are reported but otherwise skipped
+    fn skip_trivia(&mut self) {
+        loop {
+            self.pos = skip_whitespace(self.bytes, self.pos);
+            let rest = &self.bytes[self.pos..];
+            if rest.starts_with(b"//") {
+                self.report(self.pos, "comments aren't allowed in JSON");
+                self.pos = self.text[self.pos..]
+                    .find('\n')
+                    .map(|i| self.pos + i)
+                    .unwrap_or(self.text.len());
+            } else if rest.starts_with(b"/*") {
+                self.report(self.pos, "comments aren't allowed in JSON");
+                self.pos = match self.text[self.pos + 2..].find("*/") {
+                    Some(i) => self.pos + 2 + i + 2,
+                    None => self.text.len(),
+                };
+            } else {
+                return;
+            }
+        }
+    }
+
+    // Skips an unusable token (a bare word, a broken number) up to the next
+    // delimiter, always moving forward at least one character
+    fn skip_token(&mut self) {
+        let start = self.pos;
+        while let Some(byte) = self.peek() {
+            if matches!(byte, b',' | b':' | b'{' | b'}' | b'[' | b']' | b'"') || byte.is_ascii_whitespace() {
+                break;
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            self.pos += self.text[start..].chars().next().map(char::len_utf8).unwrap_or(0);
+        }
+    }
+
+    // Whether `byte` closes one of the enclosing containers rather than the
+    // innermost one, i.e. the innermost one was left unclosed
+    fn closes_outer(&self, byte: u8) -> bool {
+        let outer = &self.closers[..self.closers.len().saturating_sub(1)];
+        outer.contains(&byte)
+    }
+
+    fn string(&mut self) {
+        let open = self.pos;
+        let mut resume = open;
+        loop {
+            match scan_string(self.bytes, resume) {
+                Ok(end) => {
+                    self.pos = end;
+                    return;
+                }
+                // A line break inside a string almost always means its closing
+                // quote is missing; the string ends with the line
+                Err((at, _)) if matches!(self.bytes.get(at), None | Some(b'\n' | b'\r')) => {
+                    self.report(open, "string is never closed");
+                    self.pos = at;
+                    return;
+                }
+                Err((at, message)) if message.starts_with("EOF") => {
+                    self.report(at, message);
+                    self.pos = self.text.len();
+                    return;
+                }
+                // Bad escapes and control characters: report, then continue
+                // scanning as if the offending byte had been a plain character
+                Err((at, message)) => {
+                    self.report(at, message);
+                    resume = at;
+                }
+            }
+        }
+    }
+
+    fn single_quoted(&mut self) {
+        self.report(self.pos, "strings must use double quotes");
+        let line_end = self.text[self.pos..]
+            .find('\n')
+            .map(|i| self.pos + i)
+            .unwrap_or(self.text.len());
+        match self.text[self.pos + 1..line_end].find('\'') {
+            Some(i) => self.pos += 1 + i + 1,
+            None => self.pos = line_end,
+        }
+    }
+
+    fn value(&mut self, depth: usize) {
+        self.skip_trivia();
+        let Some(byte) = self.peek() else {
+            self.report(self.pos, "EOF while parsing a value");
+            return;
+        };
+        match byte {
+            b'{' | b'[' if depth >= MAX_DEPTH => {
+                self.report(self.pos, "nesting too deep");
+                self.pos = self.text.len();
+            }
+            b'{' | b'[' => {
+                self.closers.push(if byte == b'{' { b'}' } else { b']' });
+                self.container(byte == b'{', depth + 1);
+                self.closers.pop();
+            }
+            b'"' => self.string(),
+            b'\'' => self.single_quoted(),
+            b'-' | b'0'..=b'9' => match scan_number(self.bytes, self.pos) {
+                Ok(end) => self.pos = end,
+                Err((at, message)) => {
+                    self.report(at, message);
+                    self.skip_token();
+                }
+            },
+            // Missing value, e.g. `[1,,2]` or `{"a":}`; the container handles the delimiter
+            b',' | b'}' | b']' => self.report(self.pos, "expected value"),
+            _ => {
+                let literal = ["true", "false", "null"]
+                    .into_iter()
+                    .find(|literal| self.bytes[self.pos..].starts_with(literal.as_bytes()))
+                    .filter(|literal| {
+                        !self
+                            .bytes
+                            .get(self.pos + literal.len())
+                            .is_some_and(|next| next.is_ascii_alphanumeric() || *next == b'_')
+                    });
+                match literal {
+                    Some(literal) => self.pos += literal.len(),
+                    None => {
+                        self.report(self.pos, "expected value");
+                        self.skip_token();
+                    }
+                }
+            }
+        }
+    }
+
+    // Object members or array items after the opening bracket
+    fn container(&mut self, is_object: bool, depth: usize) {
+        let open = self.pos;
+        let closer = if is_object { b'}' } else { b']' };
+        let never_closed = if is_object {
+            "'{' is never closed"
+        } else {
+            "'[' is never closed"
+        };
+        let expected_separator = if is_object {
+            "expected ',' or '}'"
+        } else {
+            "expected ',' or ']'"
+        };
+        self.pos += 1;
+
+        loop {
+            if self.full() {
+                return;
+            }
+            let item_start = self.pos;
+            self.skip_trivia();
+            match self.peek() {
+                None => {
+                    self.report(open, never_closed);
+                    return;
+                }
+                Some(byte) if byte == closer => {
+                    self.pos += 1;
+                    return;
+                }
+                Some(b',') => {
+                    self.report(self.pos, if is_object { "expected key" } else { "expected value" });
+                    self.pos += 1;
+                    continue;
+                }
+                Some(byte @ (b'}' | b']')) => {
+                    self.report(self.pos, expected_separator);
+                    if self.closes_outer(byte) {
+                        return;
+                    }
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            if is_object {
+                match self.peek() {
+                    Some(b'"') => self.string(),
+                    Some(b'\'') => self.single_quoted(),
+                    Some(b':') => self.report(self.pos, "expected key"),
+                    _ => {
+                        self.report(self.pos, "key must be a string");
+                        self.skip_token();
+                    }
+                }
+                self.skip_trivia();
+                if self.peek() == Some(b':') {
+                    self.pos += 1;
+                } else {
+                    self.report(self.pos, "expected ':'");
+                }
+            }
+            self.value(depth);
+
+            self.skip_trivia();
+            match self.peek() {
+                Some(b',') => {
+                    let comma = self.pos;
+                    self.pos += 1;
+                    self.skip_trivia();
+                    if self.peek() == Some(closer) {
+                        self.report(comma, "trailing comma");
+                    }
+                }
+                Some(byte) if byte == closer => {
+                    self.pos += 1;
+                    return;
+                }
+                None => {
+                    self.report(open, never_closed);
+                    return;
+                }
+                Some(byte @ (b'}' | b']')) if self.closes_outer(byte) => {
+                    self.report(self.pos, expected_separator);
+                    return;
+                }
+                // Most likely a missing comma; the next item is parsed as usual
+                Some(_) => self.report(self.pos, expected_separator),
+            }
+
+            // Nothing could be made of the input; step over it so the loop ends
+            if self.pos == item_start {
+                self.skip_token();
+            }
+        }
+    }
+}
+
+fn line_column_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start = if line <= 1 {
+        0
+    } else {
+        text.match_indices('\n')
+            .nth(line - 2)
+            .map(|(i, _)| i + 1)
+            .unwrap_or(text.len())
+    };
+    text[line_start..]
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map(|(i, _)| line_start + i)
+        .unwrap_or(text.len())
+}
+
+// Every syntax problem in `text` (at most `max_errors`), in document order,
+// each as byte offset and message
+fn find_errors(text: &str, max_errors: usize) -> Vec<(usize, String)> {
+    let error = match serde_json::from_str::<serde::de::IgnoredAny>(text) {
+        Ok(_) => return Vec::new(),
+        Err(e) => e,
+    };
+
+    let mut linter = Linter {
+        text,
+        bytes: text.as_bytes(),
+        pos: 0,
+        closers: Vec::new(),
+        errors: Vec::new(),
+        max_errors: max_errors.max(1),
+    };
+    linter.skip_trivia();
+    if linter.peek().is_none() {
+        linter.report(linter.pos, "empty input");
+    } else {
+        linter.value(0);
+        linter.skip_trivia();
+        if linter.peek().is_some() {
+            linter.report(linter.pos, "trailing characters");
+        }
+    }
+
+    // Anything serde_json rejects beyond syntax (such as its nesting limit)
+    // still shows up, at the position serde_json reports
+    if linter.errors.is_empty() {
+        let offset = line_column_offset(text, error.line(), error.column());
+        linter.errors.push((offset, error.to_string()));
+    }
+    linter.errors.sort_by_key(|(pos, _)| *pos);
+    linter.errors
+}
+
+pub fn lint_json(text: &str, max_errors: usize) -> Vec<serde_json::Value> {
+    find_errors(text, max_errors)
+        .into_iter()
+        .map(|(pos, message)| {
+            let (line, column, snippet, marker) = error_location(text, pos);
+            serde_json::json!({
+                "line": line,
+                "column": column,
+                "message": message,
+                "snippet": snippet,
+                "marker": marker
+            })
+        })
+        .collect()
+}
+
+pub fn lint_report(text: &str) -> String {
+    let errors = lint_json(text, DEFAULT_MAX_ERRORS);
+    if errors.is_empty() {
+        return "✅ Valid JSON - no problems found".to_string();
+    }
+
+    let mut report = if errors.len() >= DEFAULT_MAX_ERRORS {
+        format!("❌ JSON Validation - first {} problems\n", errors.len())
+    } else if errors.len() == 1 {
+        "❌ JSON Validation - 1 problem\n".to_string()
+    } else {
+        format!("❌ JSON Validation - {} problems\n", errors.len())
+    };
+    for (index, error) in errors.iter().enumerate() {
+        report.push_str(&format!(
+            "\n{}. Line {} column {}: {}\n{}\n{}\n",
+            index + 1,
+            error["line"],
+            error["column"],
+            error["message"].as_str().unwrap_or_default(),
+            error["snippet"].as_str().unwrap_or_default(),
+            error["marker"].as_str().unwrap_or_default()
+        ));
+    }
+    report
+}
+
+pub struct JsonLintFormatter;
+
+impl Formatter for JsonLintFormatter {
+    fn id(&self) -> &'static str {
+        "json-lint"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["json-validate"]
+    }
+
+    fn display_name(&self) -> &'static str {
+        "JSON Validate (all errors)"
+    }
+
+    // The report describes the input rather than replacing its formatted form
+    fn stores_output(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        Ok(lint_report(input))
+    }
+}
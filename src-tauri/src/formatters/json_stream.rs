@@ -0,0 +1,555 @@
+// Pretty-prints JSON straight from the token stream without building a
+// serde_json::Value, so only the input and the output are held in memory.
+// Strings and numbers are copied verbatim rather than re-serialized; otherwise
+// the layout matches formatters::to_string_pretty, including sort_keys and
+// line_width, and Mongo Extended JSON wrappers stay on one line as they do in
+// mongo::pretty_print.
+use std::borrow::Cow;
+
+// How often (in input bytes) progress is reported
+const PROGRESS_INTERVAL: usize = 1024 * 1024;
+// Characters of the offending line shown either side of a syntax error
+const ERROR_CONTEXT_CHARS: usize = 40;
+// Nesting beyond this is never kept on one line
+const MAX_COMPACT_DEPTH: usize = 64;
+// First keys of the Extended JSON wrappers mongo::recognize knows
+const EXTENDED_KEYS: &[&str] = &[
+    "\"$oid\"",
+    "\"$date\"",
+    "\"$numberLong\"",
+    "\"$numberInt\"",
+    "\"$numberDouble\"",
+    "\"$numberDecimal\"",
+    "\"$binary\"",
+    "\"$type\"",
+    "\"$timestamp\"",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    FirstValueOrEnd,
+    FirstKeyOrEnd,
+    Key,
+    Colon,
+    CommaOrEnd,
+    Done,
+}
+
+// An object whose members are held back until it closes, so they can be
+// written in key order
+struct SortedObject {
+    // Output up to and including the object's `{`
+    before: String,
+    // Key text and the member as written, from its line break on
+    members: Vec<(String, String)>,
+    key: String,
+}
+
+// What keys are ordered by: the unescaped text, as in a serde_json::Map
+fn key_order(key: &str) -> Cow<'_, str> {
+    if key.contains('\\') {
+        Cow::Owned(serde_json::from_str(key).unwrap_or_default())
+    } else {
+        Cow::Borrowed(&key[1..key.len() - 1])
+    }
+}
+
+fn sort_members(members: &mut [(String, String)]) {
+    members.sort_by(|(a, _), (b, _)| key_order(a).cmp(&key_order(b)));
+}
+
+// Writes single values on one line (`[1, 2]`, `{"a": 1}`), giving up with
+// None as soon as the line would pass `limit` bytes or the input turns out
+// malformed; the main loop then handles it and reports any error
+struct Compact<'a> {
+    text: &'a str,
+    sort_keys: bool,
+    limit: usize,
+    written: usize,
+}
+
+impl Compact<'_> {
+    fn push(&mut self, out: &mut String, piece: &str) -> Option<()> {
+        self.written += piece.len();
+        (self.written <= self.limit).then(|| out.push_str(piece))
+    }
+
+    // Returns the position just past the value
+    fn value(&mut self, pos: usize, depth: usize, out: &mut String) -> Option<usize> {
+        let bytes = self.text.as_bytes();
+        let pos = skip_whitespace(bytes, pos);
+        if depth > MAX_COMPACT_DEPTH {
+            return None;
+        }
+        let end = match *bytes.get(pos)? {
+            b'[' => return self.array(pos, depth, out),
+            b'{' => return self.object(pos, depth, out),
+            b'"' => scan_string(bytes, pos).ok()?,
+            b'-' | b'0'..=b'9' => scan_number(bytes, pos).ok()?,
+            _ => {
+                let literal = ["true", "false", "null"]
+                    .into_iter()
+                    .find(|literal| bytes[pos..].starts_with(literal.as_bytes()))?;
+                pos + literal.len()
+            }
+        };
+        self.push(out, &self.text[pos..end])?;
+        Some(end)
+    }
+
+    fn array(&mut self, pos: usize, depth: usize, out: &mut String) -> Option<usize> {
+        let bytes = self.text.as_bytes();
+        self.push(out, "[")?;
+        let mut pos = skip_whitespace(bytes, pos + 1);
+        if bytes.get(pos) == Some(&b']') {
+            self.push(out, "]")?;
+            return Some(pos + 1);
+        }
+        loop {
+            pos = skip_whitespace(bytes, self.value(pos, depth + 1, out)?);
+            match *bytes.get(pos)? {
+                b',' => self.push(out, ", ")?,
+                b']' => {
+                    self.push(out, "]")?;
+                    return Some(pos + 1);
+                }
+                _ => return None,
+            }
+            pos += 1;
+        }
+    }
+
+    fn object(&mut self, pos: usize, depth: usize, out: &mut String) -> Option<usize> {
+        let bytes = self.text.as_bytes();
+        let mut members: Vec<(String, String)> = Vec::new();
+        let mut pos = skip_whitespace(bytes, pos + 1);
+        if bytes.get(pos) != Some(&b'}') {
+            loop {
+                if bytes.get(pos) != Some(&b'"') {
+                    return None;
+                }
+                let key_end = scan_string(bytes, pos).ok()?;
+                let key = &self.text[pos..key_end];
+                let mut member = String::new();
+                self.push(&mut member, key)?;
+                self.push(&mut member, ": ")?;
+                pos = skip_whitespace(bytes, key_end);
+                if bytes.get(pos) != Some(&b':') {
+                    return None;
+                }
+                pos = skip_whitespace(bytes, self.value(pos + 1, depth + 1, &mut member)?);
+                members.push((key.to_string(), member));
+                match *bytes.get(pos)? {
+                    b',' => pos = skip_whitespace(bytes, pos + 1),
+                    b'}' => break,
+                    _ => return None,
+                }
+                // Counts the ", " that will separate the members
+                self.push(&mut String::new(), ", ")?;
+            }
+        }
+        if self.sort_keys {
+            sort_members(&mut members);
+        }
+        self.push(out, "{")?;
+        for (index, (_, member)) in members.iter().enumerate() {
+            if index > 0 {
+                out.push_str(", ");
+            }
+            out.push_str(member);
+        }
+        self.push(out, "}")?;
+        Some(pos + 1)
+    }
+}
+
+// The container opening at `start` written on one line, when it's an Extended
+// JSON wrapper or fits in `line_width` from where the output line stands.
+// Returns the line and the position just past the container.
+fn packed(text: &str, start: usize, out: &str, sort_keys: bool, line_width: usize) -> Option<(String, usize)> {
+    let bytes = text.as_bytes();
+    let key_start = skip_whitespace(bytes, start + 1);
+    let maybe_extended =
+        bytes[start] == b'{' && EXTENDED_KEYS.iter().any(|key| bytes[key_start..].starts_with(key.as_bytes()));
+    if maybe_extended {
+        let mut compact = Compact {
+            text,
+            sort_keys: false,
+            limit: usize::MAX,
+            written: 0,
+        };
+        let mut line = String::new();
+        let end = compact.value(start, 0, &mut line)?;
+        let value: serde_json::Value = serde_json::from_str(&line).ok()?;
+        if super::mongo::recognize(&value).is_some() {
+            return Some((value.to_string(), end));
+        }
+    }
+
+    if line_width == 0 {
+        return None;
+    }
+    let column = out[out.rfind('\n').map(|i| i + 1).unwrap_or(0)..].chars().count();
+    // One column is kept for a following comma, as in to_string_pretty
+    let budget = line_width.checked_sub(column + 1)?;
+    let mut compact = Compact {
+        text,
+        sort_keys,
+        // Characters take up to four bytes each, so this only rules out lines
+        // that are certainly too wide; the exact check follows
+        limit: budget.saturating_mul(4),
+        written: 0,
+    };
+    let mut line = String::new();
+    let end = compact.value(start, 0, &mut line)?;
+    (line.chars().count() <= budget).then_some((line, end))
+}
+
+fn newline(out: &mut String, depth: usize) {
+    out.push('\n');
+    out.push_str(&super::indent(depth));
+}
+
+fn after_value(stack: &[bool]) -> Expect {
+    if stack.is_empty() {
+        Expect::Done
+    } else {
+        Expect::CommaOrEnd
+    }
+}
+
+pub(super) fn skip_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && matches!(bytes[pos], b' ' | b'\t' | b'\n' | b'\r') {
+        pos += 1;
+    }
+    pos
+}
+
+// Returns the position just past the closing quote
+pub(super) fn scan_string(bytes: &[u8], start: usize) -> Result<usize, (usize, &'static str)> {
+    let mut pos = start + 1;
+    loop {
+        match bytes.get(pos) {
+            None => return Err((pos, "EOF while parsing a string")),
+            Some(b'"') => return Ok(pos + 1),
+            Some(b'\\') => match bytes.get(pos + 1) {
+                Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => pos += 2,
+                Some(b'u') => {
+                    let hex = bytes.get(pos + 2..pos + 6).ok_or((pos, "EOF while parsing a string"))?;
+                    if !hex.iter().all(|b| b.is_ascii_hexdigit()) {
+                        return Err((pos, "invalid unicode escape"));
+                    }
+                    pos += 6;
+                }
+                _ => return Err((pos, "invalid escape")),
+            },
+            Some(&b) if b < 0x20 => return Err((pos, "control character found while parsing a string")),
+            Some(_) => pos += 1,
+        }
+    }
+}
+
+fn scan_digits(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+        pos += 1;
+    }
+    pos
+}
+
+// -?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?
+pub(super) fn scan_number(bytes: &[u8], start: usize) -> Result<usize, (usize, &'static str)> {
+    let mut pos = start;
+    if bytes[pos] == b'-' {
+        pos += 1;
+    }
+    match bytes.get(pos) {
+        Some(b'0') => pos += 1,
+        Some(b'1'..=b'9') => pos = scan_digits(bytes, pos),
+        _ => return Err((pos, "invalid number")),
+    }
+    if bytes.get(pos) == Some(&b'.') {
+        if !bytes.get(pos + 1).is_some_and(|b| b.is_ascii_digit()) {
+            return Err((pos + 1, "invalid number"));
+        }
+        pos = scan_digits(bytes, pos + 1);
+    }
+    if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+        pos += 1;
+        if matches!(bytes.get(pos), Some(b'+' | b'-')) {
+            pos += 1;
+        }
+        if !bytes.get(pos).is_some_and(|b| b.is_ascii_digit()) {
+            return Err((pos, "invalid number"));
+        }
+        pos = scan_digits(bytes, pos);
+    }
+    Ok(pos)
+}
+
+// 1-based line and column of `pos`, the part of its line around it, and a
+// marker pointing at the column within that snippet
+pub(super) fn error_location(text: &str, pos: usize) -> (usize, usize, String, String) {
+    let pos = pos.min(text.len());
+    let before = &text.as_bytes()[..pos];
+    let line = before.iter().filter(|b| **b == b'\n').count() + 1;
+    let line_start = before.iter().rposition(|b| *b == b'\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = text[line_start..].find('\n').map(|i| line_start + i).unwrap_or(text.len());
+    let column = text.get(line_start..pos).map(|s| s.chars().count()).unwrap_or(0);
+
+    // Single-line files can be enormous, so only show a window around the error
+    let skip = column.saturating_sub(ERROR_CONTEXT_CHARS);
+    let snippet: String = text[line_start..line_end]
+        .trim_end_matches('\r')
+        .chars()
+        .skip(skip)
+        .take(ERROR_CONTEXT_CHARS * 2)
+        .collect();
+    let marker = format!("{}^", "-".repeat(column - skip));
+    (line, column + 1, snippet, marker)
+}
+
+fn syntax_error(text: &str, pos: usize, message: &str) -> String {
+    let (line, column, snippet, marker) = error_location(text, pos);
+    format!(
+        "❌ JSON Syntax Error\n\n\
+        There's a syntax error in your JSON at line {} column {}.\n\n\
+        Error location:\n\
+        {}\n\
+        {}\n\n\
+        Detailed error: {}",
+        line, column, snippet, marker, message
+    )
+}
+
+pub fn pretty_print(
+    text: &str,
+    progress: &mut dyn FnMut(usize) -> Result<(), String>,
+) -> Result<String, String> {
+    let bytes = text.as_bytes();
+    let fail = |(pos, message): (usize, &'static str)| syntax_error(text, pos, message);
+    let options = super::options();
+
+    // Pretty output is usually around 1.5x the size of minified input
+    let mut out = String::with_capacity(text.len() + text.len() / 2);
+    // true for objects, false for arrays
+    let mut stack: Vec<bool> = Vec::new();
+    // Open objects when sorting keys; `out` then holds the current member
+    let mut sorted: Vec<SortedObject> = Vec::new();
+    let mut expect = Expect::Value;
+    let mut pos = 0;
+    let mut next_report = PROGRESS_INTERVAL;
+
+    loop {
+        pos = skip_whitespace(bytes, pos);
+        if pos >= bytes.len() {
+            break;
+        }
+        if pos >= next_report {
+            progress(pos)?;
+            next_report = pos + PROGRESS_INTERVAL;
+        }
+
+        let byte = bytes[pos];
+        let closer = match stack.last() {
+            Some(true) => b'}',
+            _ => b']',
+        };
+
+        match expect {
+            Expect::Done => return Err(fail((pos, "trailing characters"))),
+            Expect::FirstKeyOrEnd | Expect::FirstValueOrEnd if byte == closer => {
+                // Empty containers stay on one line: {} and []
+                if byte == b'}' && options.sort_keys {
+                    out = sorted.pop().map(|object| object.before).unwrap_or_default();
+                }
+                out.push(byte as char);
+                stack.pop();
+                pos += 1;
+                expect = after_value(&stack);
+            }
+            Expect::FirstKeyOrEnd | Expect::Key => {
+                if byte != b'"' {
+                    return Err(fail((pos, "key must be a string")));
+                }
+                let end = scan_string(bytes, pos).map_err(fail)?;
+                if let Some(object) = sorted.last_mut() {
+                    if !object.key.is_empty() {
+                        object.members.push((std::mem::take(&mut object.key), std::mem::take(&mut out)));
+                    }
+                    object.key = text[pos..end].to_string();
+                }
+                newline(&mut out, stack.len());
+                out.push_str(&text[pos..end]);
+                pos = end;
+                expect = Expect::Colon;
+            }
+            Expect::Colon => {
+                if byte != b':' {
+                    return Err(fail((pos, "expected ':'")));
+                }
+                out.push_str(": ");
+                pos += 1;
+                expect = Expect::Value;
+            }
+            Expect::CommaOrEnd => {
+                if byte == b',' {
+                    pos += 1;
+                    if stack.last() == Some(&true) {
+                        // Sorted members get their commas once they're in order
+                        if !options.sort_keys {
+                            out.push(',');
+                        }
+                        expect = Expect::Key;
+                    } else {
+                        out.push(',');
+                        newline(&mut out, stack.len());
+                        expect = Expect::Value;
+                    }
+                } else if byte == closer {
+                    stack.pop();
+                    if let Some(mut object) = sorted.pop_if(|_| byte == b'}') {
+                        object.members.push((object.key, std::mem::take(&mut out)));
+                        sort_members(&mut object.members);
+                        out = object.before;
+                        for (index, (_, member)) in object.members.iter().enumerate() {
+                            if index > 0 {
+                                out.push(',');
+                            }
+                            out.push_str(member);
+                        }
+                    }
+                    newline(&mut out, stack.len());
+                    out.push(byte as char);
+                    pos += 1;
+                    expect = after_value(&stack);
+                } else {
+                    return Err(fail((pos, "expected ',' or closing bracket")));
+                }
+            }
+            Expect::Value | Expect::FirstValueOrEnd => {
+                if expect == Expect::FirstValueOrEnd {
+                    newline(&mut out, stack.len());
+                }
+                match byte {
+                    b'{' | b'[' => match packed(text, pos, &out, options.sort_keys, options.line_width) {
+                        Some((line, end)) => {
+                            out.push_str(&line);
+                            pos = end;
+                        }
+                        None => {
+                            out.push(byte as char);
+                            stack.push(byte == b'{');
+                            if byte == b'{' && options.sort_keys {
+                                sorted.push(SortedObject {
+                                    before: std::mem::take(&mut out),
+                                    members: Vec::new(),
+                                    key: String::new(),
+                                });
+                            }
+                            pos += 1;
+                            expect = if byte == b'{' { Expect::FirstKeyOrEnd } else { Expect::FirstValueOrEnd };
+                            continue;
+                        }
+                    },
+                    b'"' => {
+                        let end = scan_string(bytes, pos).map_err(fail)?;
+                        out.push_str(&text[pos..end]);
+                        pos = end;
+                    }
+                    b'-' | b'0'..=b'9' => {
+                        let end = scan_number(bytes, pos).map_err(fail)?;
+                        out.push_str(&text[pos..end]);
+                        pos = end;
+                    }
+                    _ => {
+                        let literal = ["true", "false", "null"]
+                            .into_iter()
+                            .find(|literal| bytes[pos..].starts_with(literal.as_bytes()))
+                            .ok_or_else(|| fail((pos, "expected value")))?;
+                        out.push_str(literal);
+                        pos += literal.len();
+                    }
+                }
+                expect = after_value(&stack);
+            }
+        }
+    }
+
+    if expect != Expect::Done {
+        return Err(fail((pos, "EOF while parsing JSON")));
+    }
+    progress(text.len())?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::{mongo, to_string_pretty, with_options, FormatOptions};
+
+    const SAMPLE: &str = r#"{"b": [1, 2, {"z": null, "a": "xA"}], "a": {"d": true, "c": []}, "\u0061a": {},
+        "long": ["aaaaaaaaaaaaaaaaaaaa", "bbbbbbbbbbbbbbbbbbbbbbbb", "cccccccccccccccccccccccccc"]}"#;
+
+    fn streamed(text: &str, options: FormatOptions) -> String {
+        with_options(options, || pretty_print(text, &mut |_| Ok(()))).unwrap()
+    }
+
+    fn tree(text: &str, options: FormatOptions) -> String {
+        let value: serde_json::Value = serde_json::from_str(text).unwrap();
+        with_options(options, || to_string_pretty(&value)).unwrap()
+    }
+
+    #[test]
+    fn matches_the_tree_printer() {
+        for line_width in [0, 20, 40, 80] {
+            let options = FormatOptions {
+                line_width,
+                ..FormatOptions::DEFAULT
+            };
+            // Escapes are copied verbatim but sorted by what they stand for
+            let streamed = streamed(SAMPLE, options).replace("\\u0061", "a");
+            assert_eq!(streamed, tree(SAMPLE, options), "line_width {}", line_width);
+        }
+    }
+
+    #[test]
+    fn keeps_input_order_without_sort_keys() {
+        let options = FormatOptions {
+            sort_keys: false,
+            ..FormatOptions::DEFAULT
+        };
+        let formatted = streamed(r#"{"b": 1, "a": {"d": 2, "c": 3}}"#, options);
+        assert!(formatted.find("\"b\"").unwrap() < formatted.find("\"a\"").unwrap());
+        assert!(formatted.find("\"d\"").unwrap() < formatted.find("\"c\"").unwrap());
+    }
+
+    #[test]
+    fn extended_json_wrappers_stay_on_one_line() {
+        let text = r#"{"_id": {"$oid": "5f0c9a1e2b3c4d5e6f708192"}, "n": {"$numberLong": "7"}, "x": {"$other": 1}}"#;
+        let value: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(streamed(text, FormatOptions::DEFAULT), mongo::pretty_print(&value));
+    }
+
+    #[test]
+    fn malformed_input_is_still_reported() {
+        let options = FormatOptions {
+            line_width: 80,
+            ..FormatOptions::DEFAULT
+        };
+        let error = with_options(options, || pretty_print(r#"{"a": [1 2]}"#, &mut |_| Ok(()))).unwrap_err();
+        assert!(error.contains("expected ',' or closing bracket"), "{}", error);
+    }
+
+    #[test]
+    fn progress_error_stops_the_printer() {
+        let text = format!("[{}0]", "0,".repeat(PROGRESS_INTERVAL));
+        let mut reports = 0;
+        let result = pretty_print(&text, &mut |_| {
+            reports += 1;
+            Err("Job cancelled".to_string())
+        });
+        assert_eq!(result.unwrap_err(), "Job cancelled");
+        assert_eq!(reports, 1);
+    }
+}
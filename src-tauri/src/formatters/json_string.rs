@@ -0,0 +1,71 @@
+use super::Formatter;
+
+// Raw text (SQL, a script, a multi-line message) as one JSON string literal,
+// quotes included, ready to paste into a payload
+pub fn escape_json_string(text: &str) -> Result<String, String> {
+    serde_json::to_string(text).map_err(|e| format!("Failed to escape text: {}", e))
+}
+
+// The inverse: a JSON string literal back to raw text. The surrounding quotes
+// may be left off, and a trailing comma copied along with the field is ignored.
+pub fn unescape_json_string(text: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(String::new());
+    }
+    let text = text.strip_suffix(',').map(str::trim_end).unwrap_or(text);
+    let literal = if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        text.to_string()
+    } else {
+        format!("\"{}\"", text)
+    };
+    serde_json::from_str::<String>(&literal).map_err(|e| {
+        // Columns count the quote added above when the input had none
+        let column = if literal.len() > text.len() {
+            e.column().saturating_sub(1)
+        } else {
+            e.column()
+        };
+        format!("Invalid JSON string at column {}: {}", column.max(1), e)
+    })
+}
+
+pub struct JsonStringEscaper;
+
+impl Formatter for JsonStringEscaper {
+    fn id(&self) -> &'static str {
+        "json-escape"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Text to JSON String"
+    }
+
+    fn stores_output(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        escape_json_string(input)
+    }
+}
+
+pub struct JsonStringUnescaper;
+
+impl Formatter for JsonStringUnescaper {
+    fn id(&self) -> &'static str {
+        "json-unescape"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "JSON String to Text"
+    }
+
+    fn stores_output(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        unescape_json_string(input)
+    }
+}
@@ -0,0 +1,390 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use super::Formatter;
+
+pub fn parse_jwt(token: &str) -> Result<String, String> {
+    let token = token.trim();
+
+    if token.is_empty() {
+        return Err("Empty JWT token".to_string());
+    }
+
+    // Split JWT token into parts
+    let parts: Vec<&str> = token.split('.').collect();
+
+    if parts.len() != 3 {
+        return Err("Invalid JWT format. Expected 3 parts separated by dots.".to_string());
+    }
+
+    let mut result = serde_json::Map::new();
+
+    // Decode header
+    match decode_jwt_part(parts[0]) {
+        Ok(header) => {
+            result.insert("header".to_string(), header);
+        }
+        Err(e) => return Err(format!("Failed to decode JWT header: {}", e)),
+    }
+
+    // Decode payload
+    match decode_jwt_part(parts[1]) {
+        Ok(payload) => {
+            result.insert("payload".to_string(), payload);
+        }
+        Err(e) => return Err(format!("Failed to decode JWT payload: {}", e)),
+    }
+
+    // Claim checks with the defaults; validate_jwt takes expected values
+    result.insert(
+        "validation".to_string(),
+        claims_report(
+            &result["header"],
+            &result["payload"],
+            parts[2],
+            &Expectations::default(),
+        ),
+    );
+
+    // Add signature info (we can't decode it without the secret)
+    result.insert(
+        "signature".to_string(),
+        serde_json::Value::String(format!("Signature (base64): {}", parts[2])),
+    );
+
+    // Add token parts for reference
+    let mut token_parts = serde_json::Map::new();
+    token_parts.insert(
+        "header".to_string(),
+        serde_json::Value::String(parts[0].to_string()),
+    );
+    token_parts.insert(
+        "payload".to_string(),
+        serde_json::Value::String(parts[1].to_string()),
+    );
+    token_parts.insert(
+        "signature".to_string(),
+        serde_json::Value::String(parts[2].to_string()),
+    );
+    result.insert(
+        "token_parts".to_string(),
+        serde_json::Value::Object(token_parts),
+    );
+
+    // Convert to pretty JSON
+    match super::to_string_pretty(&result) {
+        Ok(formatted) => Ok(formatted),
+        Err(e) => Err(format!("Failed to format JWT output: {}", e)),
+    }
+}
+
+pub fn decode_jwt_part(encoded: &str) -> Result<serde_json::Value, String> {
+    // Add padding if needed (JWT base64 encoding omits padding)
+    let mut padded = encoded.to_string();
+    while !padded.len().is_multiple_of(4) {
+        padded.push('=');
+    }
+
+    // Replace URL-safe characters
+    let standard_base64 = padded.replace('-', "+").replace('_', "/");
+
+    // Decode base64
+    match STANDARD.decode(&standard_base64) {
+        Ok(decoded_bytes) => {
+            // Convert to string
+            match String::from_utf8(decoded_bytes) {
+                Ok(decoded_string) => {
+                    // Parse as JSON
+                    match serde_json::from_str(&decoded_string) {
+                        Ok(json_value) => Ok(json_value),
+                        Err(e) => Err(format!("Invalid JSON in JWT part: {}", e)),
+                    }
+                }
+                Err(e) => Err(format!("Invalid UTF-8 in JWT part: {}", e)),
+            }
+        }
+        Err(e) => Err(format!("Invalid base64 encoding: {}", e)),
+    }
+}
+
+// Tokens living longer than this are flagged unless the caller sets a limit
+pub const MAX_LIFETIME_HOURS: i64 = 24;
+// Leeway for clock differences between issuer and reader, as most libraries allow
+const CLOCK_SKEW_SECONDS: i64 = 60;
+
+pub struct Expectations {
+    pub audience: Option<String>,
+    pub issuer: Option<String>,
+    pub max_lifetime_hours: i64,
+}
+
+impl Default for Expectations {
+    fn default() -> Self {
+        Expectations {
+            audience: None,
+            issuer: None,
+            max_lifetime_hours: MAX_LIFETIME_HOURS,
+        }
+    }
+}
+
+// "3h 12m", "2d 4h", "45s": the two largest units
+pub fn human_duration(seconds: i64) -> String {
+    let seconds = seconds.unsigned_abs();
+    let units = [(86_400, "d"), (3_600, "h"), (60, "m"), (1, "s")];
+    let parts: Vec<String> = units
+        .iter()
+        .scan(seconds, |left, (size, unit)| {
+            let count = *left / size;
+            *left %= size;
+            Some((count, unit))
+        })
+        .skip_while(|(count, _)| *count == 0)
+        .take(2)
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, unit)| format!("{}{}", count, unit))
+        .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+fn numeric_claim(payload: &serde_json::Value, claim: &str) -> Option<i64> {
+    // Some issuers write fractional seconds
+    payload.get(claim)?.as_f64().map(|seconds| seconds as i64)
+}
+
+fn check(name: &str, status: &str, message: String) -> serde_json::Value {
+    serde_json::json!({ "check": name, "status": status, "message": message })
+}
+
+// Expands exp/iat/nbf/auth_time into UTC times relative to now, and checks the
+// algorithm, signature presence, time window, lifetime and, when expected
+// values are given, aud and iss. `valid` is false if any check failed; warnings
+// don't count against it.
+pub fn claims_report(
+    header: &serde_json::Value,
+    payload: &serde_json::Value,
+    signature: &str,
+    expected: &Expectations,
+) -> serde_json::Value {
+    let now = chrono::Utc::now().timestamp();
+    let mut timestamps = serde_json::Map::new();
+    for claim in ["exp", "nbf", "iat", "auth_time"] {
+        let Some(value) = numeric_claim(payload, claim) else {
+            continue;
+        };
+        let distance = human_duration(value - now);
+        let relative = match (claim, value <= now) {
+            ("exp", true) => format!("expired {} ago", distance),
+            ("exp", false) => format!("expires in {}", distance),
+            ("nbf", true) => format!("valid since {} ago", distance),
+            ("nbf", false) => format!("not valid for another {}", distance),
+            (_, true) => format!("{} ago", distance),
+            (_, false) => format!("{} in the future", distance),
+        };
+        let utc = chrono::DateTime::from_timestamp(value, 0).map(|time| time.to_rfc3339());
+        timestamps.insert(
+            claim.to_string(),
+            serde_json::json!({ "value": value, "utc": utc, "relative": relative }),
+        );
+    }
+
+    let mut checks = Vec::new();
+    match header.get("alg").and_then(|alg| alg.as_str()) {
+        Some(alg) if alg.eq_ignore_ascii_case("none") => checks.push(check(
+            "alg",
+            "fail",
+            "alg is \"none\": the token is unsigned and must be rejected".to_string(),
+        )),
+        Some(alg) => checks.push(check("alg", "pass", format!("Signed with {}", alg))),
+        None => checks.push(check("alg", "fail", "The header has no alg".to_string())),
+    }
+    let unsigned = header["alg"]
+        .as_str()
+        .is_some_and(|alg| alg.eq_ignore_ascii_case("none"));
+    if signature.is_empty() && !unsigned {
+        checks.push(check(
+            "signature",
+            "fail",
+            "The signature part is empty".to_string(),
+        ));
+    }
+
+    let exp = numeric_claim(payload, "exp");
+    let iat = numeric_claim(payload, "iat");
+    match exp {
+        None => checks.push(check(
+            "exp",
+            "warn",
+            "No exp claim: the token never expires".to_string(),
+        )),
+        Some(exp) if now >= exp + CLOCK_SKEW_SECONDS => checks.push(check(
+            "exp",
+            "fail",
+            format!("Expired {} ago", human_duration(now - exp)),
+        )),
+        Some(exp) => checks.push(check(
+            "exp",
+            "pass",
+            format!("Expires in {}", human_duration(exp - now)),
+        )),
+    }
+    if let Some(nbf) = numeric_claim(payload, "nbf") {
+        if now + CLOCK_SKEW_SECONDS < nbf {
+            checks.push(check(
+                "nbf",
+                "fail",
+                format!("Not valid for another {}", human_duration(nbf - now)),
+            ));
+        } else {
+            checks.push(check("nbf", "pass", "Already valid".to_string()));
+        }
+    }
+    if let Some(iat) = iat.filter(|iat| *iat > now + CLOCK_SKEW_SECONDS) {
+        checks.push(check(
+            "iat",
+            "warn",
+            format!(
+                "Issued {} in the future; are the clocks in sync?",
+                human_duration(iat - now)
+            ),
+        ));
+    }
+    if let (Some(exp), Some(iat)) = (exp, iat) {
+        let lifetime = exp - iat;
+        if lifetime < 0 {
+            checks.push(check("lifetime", "fail", "exp is before iat".to_string()));
+        } else if lifetime > expected.max_lifetime_hours * 3_600 {
+            checks.push(check(
+                "lifetime",
+                "warn",
+                format!(
+                    "Lifetime of {} is longer than {}h",
+                    human_duration(lifetime),
+                    expected.max_lifetime_hours
+                ),
+            ));
+        } else {
+            checks.push(check(
+                "lifetime",
+                "pass",
+                format!("Lifetime of {}", human_duration(lifetime)),
+            ));
+        }
+    }
+
+    if let Some(audience) = &expected.audience {
+        // aud is a string or an array of strings
+        let audiences: Vec<&str> = match &payload["aud"] {
+            serde_json::Value::String(aud) => vec![aud.as_str()],
+            serde_json::Value::Array(items) => {
+                items.iter().filter_map(|aud| aud.as_str()).collect()
+            }
+            _ => Vec::new(),
+        };
+        if audiences.is_empty() {
+            checks.push(check(
+                "aud",
+                "fail",
+                format!("No aud claim; expected {}", audience),
+            ));
+        } else if audiences.contains(&audience.as_str()) {
+            checks.push(check(
+                "aud",
+                "pass",
+                format!("Audience includes {}", audience),
+            ));
+        } else {
+            checks.push(check(
+                "aud",
+                "fail",
+                format!("Audience is {}, not {}", audiences.join(", "), audience),
+            ));
+        }
+    }
+    if let Some(issuer) = &expected.issuer {
+        match payload["iss"].as_str() {
+            Some(iss) if iss == issuer => {
+                checks.push(check("iss", "pass", format!("Issued by {}", iss)))
+            }
+            // A frequent near miss with OIDC issuers
+            Some(iss) if iss.trim_end_matches('/') == issuer.trim_end_matches('/') => {
+                checks.push(check(
+                    "iss",
+                    "fail",
+                    format!(
+                        "Issuer {} differs from {} only by a trailing slash",
+                        iss, issuer
+                    ),
+                ))
+            }
+            Some(iss) => checks.push(check(
+                "iss",
+                "fail",
+                format!("Issuer is {}, not {}", iss, issuer),
+            )),
+            None => checks.push(check(
+                "iss",
+                "fail",
+                format!("No iss claim; expected {}", issuer),
+            )),
+        }
+    }
+
+    let valid = checks.iter().all(|check| check["status"] != "fail");
+    serde_json::json!({
+        "valid": valid,
+        "checked_at": chrono::Utc::now().to_rfc3339(),
+        "timestamps": timestamps,
+        "checks": checks
+    })
+}
+
+// Decodes `token` and checks its claims against `expected`; the signature
+// itself isn't verified, as that needs the key
+pub fn validate_jwt(token: &str, expected: &Expectations) -> Result<serde_json::Value, String> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    if parts.len() != 3 {
+        return Err("Invalid JWT format. Expected 3 parts separated by dots.".to_string());
+    }
+    let header =
+        decode_jwt_part(parts[0]).map_err(|e| format!("Failed to decode JWT header: {}", e))?;
+    let payload =
+        decode_jwt_part(parts[1]).map_err(|e| format!("Failed to decode JWT payload: {}", e))?;
+    let mut report = claims_report(&header, &payload, parts[2], expected);
+    report["header"] = header;
+    report["payload"] = payload;
+    Ok(report)
+}
+
+pub struct JwtFormatter;
+
+impl Formatter for JwtFormatter {
+    fn id(&self) -> &'static str {
+        "jwt"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "JWT Parser"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        super::layout_options_schema(super::PRETTY_OPTIONS)
+    }
+
+    // Expiry status is computed against the current time
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        parse_jwt(input)
+    }
+}
@@ -0,0 +1,548 @@
+// Minifiers for JSON, XML, HTML, CSS and JavaScript. JSON and XML are checked
+// first, so broken input fails with the formatters' located errors. The HTML,
+// CSS and JavaScript passes are conservative: they drop comments and
+// whitespace that can't change meaning and otherwise copy the source, so
+// strings, regex literals, templates and <pre> blocks come through intact. The
+// JavaScript pass keeps line breaks where automatic semicolon insertion could
+// depend on them; nothing is renamed.
+use quick_xml::events::Event;
+
+use super::Formatter;
+
+pub fn minify_json(text: &str) -> Result<String, String> {
+    serde_json::from_str::<serde::de::IgnoredAny>(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut out = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            out.push(c);
+        } else if !c.is_whitespace() {
+            out.push(c);
+        }
+    }
+    Ok(out)
+}
+
+// Drops comments and the whitespace between tags; text content is trimmed the
+// same way the XML formatter trims it
+pub fn minify_xml(text: &str) -> Result<String, String> {
+    super::xml::format_xml(text)?;
+    let mut reader = quick_xml::Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut writer = quick_xml::Writer::new(Vec::with_capacity(text.len()));
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Eof => break,
+            Event::Comment(_) => {}
+            event => writer.write_event(event).map_err(|e| e.to_string())?,
+        }
+    }
+    String::from_utf8(writer.into_inner()).map_err(|e| e.to_string())
+}
+
+// Copies a quoted string starting at `start` (the opening quote); returns the
+// index after the closing quote
+fn copy_string(chars: &[char], start: usize, out: &mut String, multiline: bool) -> Result<usize, String> {
+    let quote = chars[start];
+    out.push(quote);
+    let mut i = start + 1;
+    while i < chars.len() {
+        let c = chars[i];
+        out.push(c);
+        match c {
+            '\\' => {
+                if let Some(&escaped) = chars.get(i + 1) {
+                    out.push(escaped);
+                }
+                i += 2;
+                continue;
+            }
+            '\n' if !multiline => break,
+            c if c == quote => return Ok(i + 1),
+            _ => {}
+        }
+        i += 1;
+    }
+    Err(format!("Unterminated string starting at character {}", start + 1))
+}
+
+fn find(chars: &[char], from: usize, pattern: &str) -> Option<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    (from..chars.len().saturating_sub(pattern.len() - 1)).find(|&i| chars[i..i + pattern.len()] == pattern[..])
+}
+
+pub fn minify_css(text: &str) -> Result<String, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut pending_space = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let end = find(&chars, i + 2, "*/").ok_or_else(|| "Unterminated comment".to_string())?;
+            i = end + 2;
+            pending_space = true;
+            continue;
+        }
+        if c.is_whitespace() {
+            pending_space = true;
+            i += 1;
+            continue;
+        }
+        if pending_space {
+            // Space stays between words ("solid red", descendant selectors,
+            // "a :hover") but not next to punctuation that separates anyway
+            let separated = out
+                .chars()
+                .last()
+                .is_none_or(|previous| matches!(previous, '{' | '}' | ';' | ',' | '>' | ':' | '('))
+                || matches!(c, '{' | '}' | ';' | ',' | '>' | ')');
+            if !separated {
+                out.push(' ');
+            }
+            pending_space = false;
+        }
+        match c {
+            '"' | '\'' => i = copy_string(&chars, i, &mut out, false)?,
+            '}' => {
+                // The last declaration in a block needs no semicolon
+                if out.ends_with(';') {
+                    out.pop();
+                }
+                out.push(c);
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$' || c == '\\' || !c.is_ascii()
+}
+
+// Whether a `/` here starts a regex literal rather than a division, judged by
+// the token before it
+fn regex_allowed(out: &str) -> bool {
+    let trimmed = out.trim_end();
+    let Some(last) = trimmed.chars().last() else {
+        return true;
+    };
+    if is_word(last) {
+        let word: String = trimmed.chars().rev().take_while(|&c| is_word(c)).collect();
+        let word: String = word.chars().rev().collect();
+        return matches!(
+            word.as_str(),
+            "return"
+                | "typeof"
+                | "instanceof"
+                | "in"
+                | "of"
+                | "new"
+                | "delete"
+                | "void"
+                | "throw"
+                | "case"
+                | "do"
+                | "else"
+                | "yield"
+                | "await"
+        );
+    }
+    !matches!(last, ')' | ']')
+}
+
+fn copy_regex(chars: &[char], start: usize, out: &mut String) -> Result<usize, String> {
+    out.push('/');
+    let mut i = start + 1;
+    let mut in_class = false;
+    while i < chars.len() {
+        let c = chars[i];
+        out.push(c);
+        match c {
+            '\\' => {
+                if let Some(&escaped) = chars.get(i + 1) {
+                    out.push(escaped);
+                }
+                i += 2;
+                continue;
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => return Ok(i + 1),
+            '\n' => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    Err(format!(
+        "Unterminated regular expression starting at character {}",
+        start + 1
+    ))
+}
+
+// Template literals are copied as written, `${...}` expressions included
+fn copy_template(chars: &[char], start: usize, out: &mut String) -> Result<usize, String> {
+    out.push('`');
+    let mut i = start + 1;
+    let mut depth = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        out.push(c);
+        match c {
+            '\\' => {
+                if let Some(&escaped) = chars.get(i + 1) {
+                    out.push(escaped);
+                }
+                i += 2;
+                continue;
+            }
+            '`' if depth == 0 => return Ok(i + 1),
+            '$' if depth == 0 && chars.get(i + 1) == Some(&'{') => {
+                out.push('{');
+                depth = 1;
+                i += 2;
+                continue;
+            }
+            '{' if depth > 0 => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    Err(format!(
+        "Unterminated template literal starting at character {}",
+        start + 1
+    ))
+}
+
+// What goes between two tokens that had whitespace between them
+fn js_separator(out: &mut String, line_break: bool, next: char) {
+    let Some(previous) = out.chars().last() else {
+        return;
+    };
+    // A line break after an operator or opener, or before a closer, can't end
+    // a statement. `+` and `-` are left out: `a++` may end one.
+    let continues = matches!(
+        previous,
+        '{' | '(' | '[' | ',' | ';' | ':' | '=' | '*' | '&' | '|' | '?' | '<' | '>' | '!' | '~' | '^' | '%'
+    ) || matches!(next, '}' | ')' | ']' | ',' | ';' | '.' | '?' | ':');
+    if line_break && !continues {
+        out.push('\n');
+        return;
+    }
+    let joins = (is_word(previous) && is_word(next))
+        || (previous == next && matches!(next, '+' | '-' | '/'))
+        || (previous.is_ascii_digit() && next == '.');
+    if joins {
+        out.push(' ');
+    }
+}
+
+pub fn minify_js(text: &str) -> Result<String, String> {
+    let chars: Vec<char> = text.trim_start_matches('\u{feff}').chars().collect();
+    let mut out = String::with_capacity(text.len());
+    // Whitespace since the last token: None, or whether it held a line break
+    let mut gap: Option<bool> = None;
+    let mut i = 0;
+
+    // A #! line stays first and on its own line
+    if chars.starts_with(&['#', '!']) {
+        let end = chars.iter().position(|&c| c == '\n').unwrap_or(chars.len());
+        out.extend(&chars[..end]);
+        i = end;
+    }
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            gap = Some(gap.unwrap_or(false));
+            continue;
+        }
+        if c == '/' && next == Some('*') {
+            let end = find(&chars, i + 2, "*/").ok_or_else(|| "Unterminated comment".to_string())?;
+            let line_break = chars[i..end].contains(&'\n');
+            gap = Some(gap == Some(true) || line_break);
+            i = end + 2;
+            continue;
+        }
+        if c.is_whitespace() {
+            let line_break = matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}');
+            gap = Some(gap == Some(true) || line_break);
+            i += 1;
+            continue;
+        }
+        if let Some(line_break) = gap.take() {
+            js_separator(&mut out, line_break, c);
+        }
+        i = match c {
+            '"' | '\'' => copy_string(&chars, i, &mut out, false)?,
+            '`' => copy_template(&chars, i, &mut out)?,
+            '/' if regex_allowed(&out) => copy_regex(&chars, i, &mut out)?,
+            _ => {
+                out.push(c);
+                i + 1
+            }
+        };
+    }
+    Ok(out)
+}
+
+// Elements whose surrounding whitespace doesn't render
+const BLOCK_ELEMENTS: &[&str] = &[
+    "!doctype",
+    "address",
+    "article",
+    "aside",
+    "base",
+    "blockquote",
+    "body",
+    "br",
+    "caption",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "meta",
+    "nav",
+    "noscript",
+    "ol",
+    "optgroup",
+    "option",
+    "p",
+    "pre",
+    "script",
+    "section",
+    "select",
+    "style",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "ul",
+];
+// Elements whose content is copied rather than collapsed
+const RAW_ELEMENTS: &[&str] = &["script", "style", "pre", "textarea"];
+
+fn tag_name(chars: &[char], start: usize) -> String {
+    chars[start..]
+        .iter()
+        .skip_while(|&&c| c == '<' || c == '/')
+        .take_while(|&&c| c.is_alphanumeric() || c == '-' || c == ':' || c == '!')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+// Copies a tag with its whitespace outside attribute values collapsed;
+// returns the index after `>`
+fn copy_tag(chars: &[char], start: usize, out: &mut String) -> Result<usize, String> {
+    let mut i = start;
+    let mut quote: Option<char> = None;
+    let mut pending_space = false;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match quote {
+            Some(open) => {
+                out.push(c);
+                if c == open {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => pending_space = true,
+            None => {
+                if pending_space && c != '>' {
+                    out.push(' ');
+                }
+                pending_space = false;
+                out.push(c);
+                match c {
+                    '"' | '\'' => quote = Some(c),
+                    '>' => return Ok(i),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Err(format!("Unterminated tag starting at character {}", start + 1))
+}
+
+fn minify_raw_content(element: &str, opening_tag: &str, content: &str) -> String {
+    let script_type = opening_tag.to_ascii_lowercase();
+    let javascript = !script_type.contains("type=")
+        || ["javascript", "module", "ecmascript"]
+            .iter()
+            .any(|kind| script_type.contains(kind));
+    // Inline code that fails to minify is kept as written
+    let minified = match element {
+        "script" if javascript => minify_js(content).ok(),
+        "style" => minify_css(content).ok(),
+        _ => None,
+    };
+    minified.unwrap_or_else(|| content.to_string())
+}
+
+pub fn minify_html(text: &str) -> Result<String, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut pending_space = false;
+    // Whether the last thing written was a block-level tag
+    let mut after_block = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '<' && chars[i..].starts_with(&['<', '!', '-', '-']) {
+            let end = find(&chars, i + 4, "-->").ok_or_else(|| "Unterminated comment".to_string())?;
+            // Conditional comments are markup for old IE, not commentary
+            let comment: String = chars[i..end + 3].iter().collect();
+            if comment.starts_with("<!--[if") || comment.starts_with("<!--<![endif") {
+                out.push_str(&comment);
+            }
+            i = end + 3;
+            continue;
+        }
+        let is_tag = c == '<' && next.is_some_and(|next| next.is_alphabetic() || matches!(next, '/' | '!' | '?'));
+        if is_tag {
+            let name = tag_name(&chars, i);
+            let block = BLOCK_ELEMENTS.contains(&name.as_str()) || next == Some('?');
+            if pending_space && !block && !after_block {
+                out.push(' ');
+            }
+            pending_space = false;
+            let tag_start = out.len();
+            i = copy_tag(&chars, i, &mut out)?;
+            after_block = block;
+
+            let closing = next == Some('/');
+            if !closing && RAW_ELEMENTS.contains(&name.as_str()) && !out.ends_with("/>") {
+                let closing_tag: Vec<char> = format!("</{}", name).chars().collect();
+                let end = (i..chars.len())
+                    .find(|&j| {
+                        chars.len() - j >= closing_tag.len()
+                            && chars[j..j + closing_tag.len()]
+                                .iter()
+                                .zip(&closing_tag)
+                                .all(|(a, b)| a.to_ascii_lowercase() == *b)
+                    })
+                    .unwrap_or(chars.len());
+                let content: String = chars[i..end].iter().collect();
+                let opening_tag = out[tag_start..].to_string();
+                out.push_str(&minify_raw_content(&name, &opening_tag, &content));
+                i = end;
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            pending_space = true;
+        } else {
+            if pending_space && !after_block {
+                out.push(' ');
+            }
+            pending_space = false;
+            after_block = false;
+            out.push(c);
+        }
+        i += 1;
+    }
+    Ok(out)
+}
+
+pub type MinifyFn = fn(&str) -> Result<String, String>;
+
+// (language, formatter id, display name, output kind, minifier)
+pub const LANGUAGES: &[(&str, &str, &str, &str, MinifyFn)] = &[
+    ("json", "json-minify", "Minify JSON", "json", minify_json),
+    ("xml", "xml-minify", "Minify XML", "xml", minify_xml),
+    ("html", "html-minify", "Minify HTML", "html", minify_html),
+    ("css", "css-minify", "Minify CSS", "css", minify_css),
+    ("js", "js-minify", "Minify JavaScript", "javascript", minify_js),
+];
+
+pub fn minify(language: &str, text: &str) -> Result<String, String> {
+    let language = match language {
+        "javascript" => "js",
+        other => other,
+    };
+    let (_, _, _, _, minify) = LANGUAGES
+        .iter()
+        .find(|(name, ..)| *name == language)
+        .ok_or_else(|| format!("Unknown language {}; use json, xml, html, css or js", language))?;
+    minify(text)
+}
+
+// One formatter per entry of LANGUAGES
+pub struct Minifier(usize);
+
+impl Minifier {
+    pub fn all() -> impl Iterator<Item = Minifier> {
+        (0..LANGUAGES.len()).map(Minifier)
+    }
+}
+
+impl Formatter for Minifier {
+    fn id(&self) -> &'static str {
+        LANGUAGES[self.0].1
+    }
+
+    fn display_name(&self) -> &'static str {
+        LANGUAGES[self.0].2
+    }
+
+    fn output_kind(&self) -> &'static str {
+        LANGUAGES[self.0].3
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        if input.trim().is_empty() {
+            return Err("Nothing to minify".to_string());
+        }
+        (LANGUAGES[self.0].4)(input)
+    }
+}
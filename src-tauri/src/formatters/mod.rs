@@ -0,0 +1,406 @@
+pub mod autodetect;
+pub mod base64_codec;
+pub mod json;
+pub mod json_lint;
+pub mod json_stream;
+pub mod json_string;
+pub mod jwt;
+pub mod minify;
+pub mod mongo;
+pub mod summary;
+pub mod xml;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cell::Cell;
+use std::sync::RwLock;
+
+pub const DEFAULT_INDENT_WIDTH: usize = 2;
+pub const MAX_INDENT_WIDTH: usize = 16;
+const MAX_LINE_WIDTH: usize = 1000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteStyle {
+    Double,
+    Single,
+}
+
+// Layout choices for pretty-printed output. Each formatter honours the ones
+// its options_schema lists and ignores the rest.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FormatOptions {
+    // Spaces per nesting level, or the width a tab counts as
+    pub indent_width: usize,
+    pub use_tabs: bool,
+    // JSON object keys in alphabetical order instead of input order
+    pub sort_keys: bool,
+    // Arrays and objects that fit on one line within this many columns stay
+    // on it; 0 puts every element on its own line
+    pub line_width: usize,
+    // Quotes around XML attribute values
+    pub quote_style: QuoteStyle,
+}
+
+impl FormatOptions {
+    pub const DEFAULT: FormatOptions = FormatOptions {
+        indent_width: DEFAULT_INDENT_WIDTH,
+        use_tabs: false,
+        sort_keys: true,
+        line_width: 0,
+        quote_style: QuoteStyle::Double,
+    };
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.indent_width > MAX_INDENT_WIDTH {
+            return Err(format!("Indent width must be at most {}", MAX_INDENT_WIDTH));
+        }
+        if self.line_width > MAX_LINE_WIDTH {
+            return Err(format!("Line width must be at most {}", MAX_LINE_WIDTH));
+        }
+        Ok(())
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions::DEFAULT
+    }
+}
+
+// The defaults come from the settings store. A single formatter run can
+// override them on its own thread with `with_options`, so formatters (and
+// plugins) read options the same way whether or not a call passed any.
+static DEFAULT_OPTIONS: RwLock<FormatOptions> = RwLock::new(FormatOptions::DEFAULT);
+
+thread_local! {
+    static CALL_OPTIONS: Cell<Option<FormatOptions>> = const { Cell::new(None) };
+}
+
+pub fn set_default_options(options: FormatOptions) {
+    if let Ok(mut defaults) = DEFAULT_OPTIONS.write() {
+        *defaults = options;
+    }
+}
+
+pub fn options() -> FormatOptions {
+    CALL_OPTIONS
+        .with(Cell::get)
+        .unwrap_or_else(|| DEFAULT_OPTIONS.read().map(|defaults| *defaults).unwrap_or_default())
+}
+
+// Runs `work` with `options` in effect for every formatter it calls
+pub fn with_options<T>(options: FormatOptions, work: impl FnOnce() -> T) -> T {
+    struct Restore(Option<FormatOptions>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CALL_OPTIONS.with(|call| call.set(self.0));
+        }
+    }
+    let _restore = Restore(CALL_OPTIONS.with(|call| call.replace(Some(options))));
+    work()
+}
+
+pub fn indent(depth: usize) -> String {
+    let options = options();
+    if options.use_tabs {
+        "\t".repeat(depth)
+    } else {
+        " ".repeat(depth * options.indent_width)
+    }
+}
+
+// Columns taken by `depth` levels of indentation
+fn indent_columns(depth: usize, options: &FormatOptions) -> usize {
+    depth * options.indent_width
+}
+
+// serde_json::to_string_pretty with the configured indent and line width
+pub fn to_string_pretty<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+    let options = options();
+    if options.line_width > 0 {
+        let value = serde_json::to_value(value)?;
+        let mut out = String::new();
+        write_packed(&value, 0, 0, &options, &mut out);
+        return Ok(out);
+    }
+    let indent = indent(1);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value.serialize(&mut serializer)?;
+    // The serializer only ever writes valid UTF-8
+    Ok(String::from_utf8(out).expect("serde_json output is UTF-8"))
+}
+
+fn scalar(value: &Value) -> String {
+    // Serializing a Value can't fail
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+// Columns `value` takes written on one line (`[1, 2]`, `{"a": 1}`), or None
+// as soon as it exceeds `budget`, so large containers are never fully measured
+fn single_line_width(value: &Value, budget: usize) -> Option<usize> {
+    let width = match value {
+        Value::Array(items) => {
+            let mut width = 2 + items.len().saturating_sub(1) * 2;
+            for item in items {
+                width += single_line_width(item, budget.checked_sub(width)?)?;
+            }
+            width
+        }
+        Value::Object(map) => {
+            let mut width = 2 + map.len().saturating_sub(1) * 2;
+            for (key, item) in map {
+                width += scalar(&Value::from(key.as_str())).chars().count() + 2;
+                width += single_line_width(item, budget.checked_sub(width)?)?;
+            }
+            width
+        }
+        _ => scalar(value).chars().count(),
+    };
+    (width <= budget).then_some(width)
+}
+
+fn write_single_line(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_single_line(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push('{');
+            for (index, (key, item)) in map.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                out.push_str(&scalar(&Value::from(key.as_str())));
+                out.push_str(": ");
+                write_single_line(item, out);
+            }
+            out.push('}');
+        }
+        _ => out.push_str(&scalar(value)),
+    }
+}
+
+// Pretty-printing that keeps containers on one line when they fit in the
+// line width. `column` is where `value` starts; one column is kept for the
+// comma that may follow it.
+fn write_packed(value: &Value, depth: usize, column: usize, options: &FormatOptions, out: &mut String) {
+    let empty = match value {
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => true,
+    };
+    let budget = options.line_width.saturating_sub(column + 1);
+    if empty || single_line_width(value, budget).is_some() {
+        write_single_line(value, out);
+        return;
+    }
+    let child_column = indent_columns(depth + 1, options);
+    let (open, close) = if value.is_array() { ('[', ']') } else { ('{', '}') };
+    out.push(open);
+    let mut first = true;
+    let mut write_child = |key: Option<&str>, item: &Value, out: &mut String| {
+        if !first {
+            out.push(',');
+        }
+        first = false;
+        out.push('\n');
+        out.push_str(&indent(depth + 1));
+        let mut column = child_column;
+        if let Some(key) = key {
+            let key = scalar(&Value::from(key));
+            column += key.chars().count() + 2;
+            out.push_str(&key);
+            out.push_str(": ");
+        }
+        write_packed(item, depth + 1, column, options, out);
+    };
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| write_child(None, item, out)),
+        Value::Object(map) => map.iter().for_each(|(key, item)| write_child(Some(key), item, out)),
+        _ => {}
+    }
+    out.push('\n');
+    out.push_str(&indent(depth));
+    out.push(close);
+}
+
+// Schema for the layout options a formatter honours, for options_schema
+pub fn layout_options_schema(names: &[&str]) -> Value {
+    let properties: serde_json::Map<String, Value> = names
+        .iter()
+        .filter_map(|name| {
+            let schema = match *name {
+                "indent_width" => json!({ "type": "integer", "minimum": 0, "maximum": MAX_INDENT_WIDTH }),
+                "use_tabs" => json!({ "type": "boolean", "description": "Indent with tabs instead of spaces" }),
+                "sort_keys" => json!({
+                    "type": "boolean",
+                    "description": "Sort object keys; off keeps the input order"
+                }),
+                "line_width" => json!({
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": MAX_LINE_WIDTH,
+                    "description": "Keep arrays and objects that fit within this many columns on one line; 0 is off"
+                }),
+                "quote_style" => json!({ "type": "string", "enum": ["double", "single"] }),
+                _ => return None,
+            };
+            Some((name.to_string(), schema))
+        })
+        .collect();
+    json!({ "type": "object", "properties": properties })
+}
+
+// Options for formatters whose output goes through to_string_pretty
+pub const PRETTY_OPTIONS: &[&str] = &["indent_width", "use_tabs", "line_width"];
+
+// A single entry in the format menu. Implementations are stateless; anything
+// that needs storage access lives in the command layer instead.
+pub trait Formatter: Send + Sync {
+    fn id(&self) -> &'static str;
+
+    // Additional format_type values accepted for this formatter
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn display_name(&self) -> &'static str;
+
+    fn input_kind(&self) -> &'static str {
+        "text"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "text"
+    }
+
+    // JSON Schema describing accepted options; empty object when there are none
+    fn options_schema(&self) -> serde_json::Value {
+        serde_json::json!({ "type": "object", "properties": {} })
+    }
+
+    // Whether the result should replace the stored formatted content
+    fn stores_output(&self) -> bool {
+        true
+    }
+
+    // Whether the output depends only on the input and options. Formatters that
+    // read the clock (expiry checks, "now"-relative dates) return false so the
+    // format cache never serves a stale result.
+    fn cacheable(&self) -> bool {
+        true
+    }
+
+    fn format(&self, input: &str) -> Result<String, String>;
+
+    // `progress` receives the number of input bytes processed so far. An error
+    // from it (a cancelled job) stops the formatter, which returns that error.
+    // Formatters that can report while they work override this; the default
+    // reports once the whole input is done.
+    fn format_with_progress(
+        &self,
+        input: &str,
+        progress: &mut dyn FnMut(usize) -> Result<(), String>,
+    ) -> Result<String, String> {
+        let result = self.format(input);
+        progress(input.len())?;
+        result
+    }
+}
+
+pub struct FormatterRegistry {
+    formatters: Vec<Box<dyn Formatter>>,
+}
+
+impl FormatterRegistry {
+    pub fn new() -> Self {
+        FormatterRegistry { formatters: Vec::new() }
+    }
+
+    pub fn with_builtins() -> Self {
+        let mut registry = FormatterRegistry::new();
+        registry.register(Box::new(json::JsonFormatter));
+        registry.register(Box::new(json::Json5Formatter));
+        registry.register(Box::new(json_lint::JsonLintFormatter));
+        registry.register(Box::new(xml::XmlFormatter));
+        registry.register(Box::new(jwt::JwtFormatter));
+        registry.register(Box::new(summary::JsonSummaryFormatter));
+        registry.register(Box::new(mongo::MongoJsonFormatter));
+        registry.register(Box::new(base64_codec::Base64Encoder));
+        registry.register(Box::new(base64_codec::Base64Decoder));
+        registry.register(Box::new(json_string::JsonStringEscaper));
+        registry.register(Box::new(json_string::JsonStringUnescaper));
+        for minifier in minify::Minifier::all() {
+            registry.register(Box::new(minifier));
+        }
+        registry.register(Box::new(crate::x509::X509Formatter));
+        registry.register(Box::new(crate::saml::SamlFormatter));
+        registry.register(Box::new(crate::email::EmailFormatter));
+        registry.register(Box::new(crate::asn1::Asn1Formatter));
+        registry.register(Box::new(crate::yaml::YamlFormatter));
+        registry.register(Box::new(crate::patch::PatchFormatter));
+        registry.register(Box::new(crate::json_unwrap::JsonUnwrapFormatter));
+        registry.register(Box::new(crate::webauthn::WebAuthnFormatter));
+        registry.register(Box::new(crate::ics::IcsFormatter));
+        registry.register(Box::new(crate::ics::IcsGeneratorFormatter));
+        registry.register(Box::new(crate::vcard::VcardFormatter));
+        registry.register(Box::new(crate::vcard::VcardGeneratorFormatter));
+        registry
+    }
+
+    pub fn register(&mut self, formatter: Box<dyn Formatter>) {
+        // Later registrations replace earlier ones with the same id
+        self.formatters.retain(|existing| existing.id() != formatter.id());
+        self.formatters.push(formatter);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn Formatter> {
+        self.formatters
+            .iter()
+            .find(|f| f.id() == id || f.aliases().contains(&id))
+            .map(|f| f.as_ref())
+    }
+
+    // Option schemas carry the current defaults
+    pub fn describe(&self) -> Vec<serde_json::Value> {
+        let defaults = serde_json::to_value(options()).unwrap_or_default();
+        self.formatters
+            .iter()
+            .map(|f| {
+                let mut options_schema = f.options_schema();
+                if let Some(Value::Object(properties)) = options_schema.get_mut("properties") {
+                    for (name, schema) in properties.iter_mut() {
+                        if let (Some(default), Value::Object(schema)) = (defaults.get(name), schema) {
+                            schema.insert("default".into(), default.clone());
+                        }
+                    }
+                }
+                serde_json::json!({
+                    "id": f.id(),
+                    "aliases": f.aliases(),
+                    "display_name": f.display_name(),
+                    "input_kind": f.input_kind(),
+                    "output_kind": f.output_kind(),
+                    "options_schema": options_schema
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for FormatterRegistry {
+    fn default() -> Self {
+        FormatterRegistry::new()
+    }
+}
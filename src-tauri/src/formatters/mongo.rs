@@ -0,0 +1,267 @@
+// MongoDB Extended JSON (what mongoexport and the drivers' canonical mode
+// write): wrappers such as {"$oid": ...}, {"$date": ...} and
+// {"$numberLong": ...} that stand in for BSON types. The JSON formatter keeps
+// them on one line, the summarizer shows their resolved values, and the
+// mongo-json formatter converts a document (or a mongoexport file with one
+// document per line) to plain JSON.
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use super::{indent, Formatter};
+
+pub enum Extended {
+    ObjectId(String),
+    Date(DateTime<Utc>),
+    Long(i64),
+    Int(i32),
+    // Kept as text: also "Infinity", "-Infinity" and "NaN"
+    Double(String),
+    Decimal(String),
+    Binary { base64: String, subtype: String },
+    Timestamp { t: u32, i: u32 },
+}
+
+fn number_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn parse_date(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    match value {
+        // Relaxed mode: ISO 8601; canonical mode: milliseconds as $numberLong
+        serde_json::Value::String(text) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|date| date.with_timezone(&Utc)),
+        serde_json::Value::Number(number) => DateTime::from_timestamp_millis(number.as_i64()?),
+        serde_json::Value::Object(object) if object.len() == 1 => {
+            DateTime::from_timestamp_millis(number_text(object.get("$numberLong")?)?.parse().ok()?)
+        }
+        _ => None,
+    }
+}
+
+// The BSON value an Extended JSON wrapper stands for, or None when `value` is
+// an ordinary object
+pub fn recognize(value: &serde_json::Value) -> Option<Extended> {
+    let object = value.as_object()?;
+    // Legacy binary: {"$binary": "<base64>", "$type": "<hex>"}
+    if object.len() == 2 {
+        let base64 = object.get("$binary")?.as_str()?;
+        let subtype = object.get("$type")?.as_str()?;
+        return Some(Extended::Binary {
+            base64: base64.to_string(),
+            subtype: subtype.to_string(),
+        });
+    }
+    if object.len() != 1 {
+        return None;
+    }
+    let (key, inner) = object.iter().next()?;
+    match key.as_str() {
+        "$oid" => {
+            let hex = inner.as_str()?;
+            (hex.len() == 24 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| Extended::ObjectId(hex.to_string()))
+        }
+        "$date" => parse_date(inner).map(Extended::Date),
+        "$numberLong" => number_text(inner)?.parse().ok().map(Extended::Long),
+        "$numberInt" => number_text(inner)?.parse().ok().map(Extended::Int),
+        "$numberDouble" => {
+            let text = number_text(inner)?;
+            text.parse::<f64>().ok().map(|_| Extended::Double(text))
+        }
+        "$numberDecimal" => number_text(inner).map(Extended::Decimal),
+        "$binary" => Some(Extended::Binary {
+            base64: inner.get("base64")?.as_str()?.to_string(),
+            subtype: inner.get("subType")?.as_str()?.to_string(),
+        }),
+        "$timestamp" => Some(Extended::Timestamp {
+            t: u32::try_from(inner.get("t")?.as_u64()?).ok()?,
+            i: u32::try_from(inner.get("i")?.as_u64()?).ok()?,
+        }),
+        _ => None,
+    }
+}
+
+fn date_text(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+impl Extended {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Extended::ObjectId(_) => "ObjectId",
+            Extended::Date(_) => "Date",
+            Extended::Long(_) => "Int64",
+            Extended::Int(_) => "Int32",
+            Extended::Double(_) => "Double",
+            Extended::Decimal(_) => "Decimal128",
+            Extended::Binary { .. } => "Binary",
+            Extended::Timestamp { .. } => "Timestamp",
+        }
+    }
+
+    // The value as a person would read it; an ObjectId also shows when it was
+    // generated, from the timestamp in its first four bytes
+    pub fn describe(&self) -> String {
+        match self {
+            Extended::ObjectId(hex) => {
+                let created = u32::from_str_radix(&hex[..8], 16)
+                    .ok()
+                    .and_then(|seconds| DateTime::from_timestamp(seconds.into(), 0));
+                match created {
+                    Some(created) => format!("{} (created {})", hex, date_text(&created)),
+                    None => hex.clone(),
+                }
+            }
+            Extended::Date(date) => date_text(date),
+            Extended::Long(number) => number.to_string(),
+            Extended::Int(number) => number.to_string(),
+            Extended::Double(text) | Extended::Decimal(text) => text.clone(),
+            Extended::Binary { base64, subtype } => {
+                let length = base64::engine::general_purpose::STANDARD
+                    .decode(base64)
+                    .map(|bytes| bytes.len())
+                    .unwrap_or(0);
+                format!("{} bytes, subtype {}", length, subtype)
+            }
+            Extended::Timestamp { t, i } => match DateTime::from_timestamp((*t).into(), 0) {
+                Some(time) => format!("{} (increment {})", date_text(&time), i),
+                None => format!("{} (increment {})", t, i),
+            },
+        }
+    }
+
+    // Plain JSON equivalent: ids, dates, decimals and binary data become strings
+    // and numbers become numbers (non-finite doubles stay strings)
+    pub fn to_plain(&self) -> serde_json::Value {
+        match self {
+            Extended::ObjectId(hex) => hex.clone().into(),
+            Extended::Date(date) => date_text(date).into(),
+            Extended::Long(number) => (*number).into(),
+            Extended::Int(number) => (*number).into(),
+            Extended::Double(text) => text
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(serde_json::Value::Number)
+                .unwrap_or_else(|| text.clone().into()),
+            Extended::Decimal(text) => text.clone().into(),
+            Extended::Binary { base64, .. } => base64.clone().into(),
+            Extended::Timestamp { t, i } => serde_json::json!({ "t": t, "i": i }),
+        }
+    }
+}
+
+pub fn contains_extended(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Object(object) => recognize(value).is_some() || object.values().any(contains_extended),
+        serde_json::Value::Array(items) => items.iter().any(contains_extended),
+        _ => false,
+    }
+}
+
+pub fn to_plain_json(value: serde_json::Value) -> serde_json::Value {
+    if let Some(extended) = recognize(&value) {
+        return extended.to_plain();
+    }
+    match value {
+        serde_json::Value::Object(object) => object
+            .into_iter()
+            .map(|(key, value)| (key, to_plain_json(value)))
+            .collect(),
+        serde_json::Value::Array(items) => items.into_iter().map(to_plain_json).collect(),
+        other => other,
+    }
+}
+
+// Same layout as to_string_pretty, except that Extended JSON wrappers stay on
+// one line: `"_id": {"$oid": "..."}` instead of three
+pub fn pretty_print(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_pretty(value, 0, &mut out);
+    out
+}
+
+fn write_pretty(value: &serde_json::Value, depth: usize, out: &mut String) {
+    if recognize(value).is_some() {
+        out.push_str(&value.to_string());
+        return;
+    }
+    match value {
+        serde_json::Value::Object(object) if !object.is_empty() => {
+            out.push('{');
+            for (position, (key, value)) in object.iter().enumerate() {
+                out.push_str(if position == 0 { "\n" } else { ",\n" });
+                out.push_str(&indent(depth + 1));
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push_str(": ");
+                write_pretty(value, depth + 1, out);
+            }
+            out.push('\n');
+            out.push_str(&indent(depth));
+            out.push('}');
+        }
+        serde_json::Value::Array(items) if !items.is_empty() => {
+            out.push('[');
+            for (position, item) in items.iter().enumerate() {
+                out.push_str(if position == 0 { "\n" } else { ",\n" });
+                out.push_str(&indent(depth + 1));
+                write_pretty(item, depth + 1, out);
+            }
+            out.push('\n');
+            out.push_str(&indent(depth));
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+// A single document, or mongoexport's default output of one document per line
+// (returned as an array)
+fn parse_export(text: &str) -> Result<serde_json::Value, String> {
+    let mut documents = serde_json::Deserializer::from_str(text)
+        .into_iter::<serde_json::Value>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid Extended JSON: {}", e))?;
+    match documents.len() {
+        0 => Err("Empty JSON input".to_string()),
+        1 => Ok(documents.remove(0)),
+        _ => Ok(documents.into()),
+    }
+}
+
+pub fn convert_to_plain_json(text: &str) -> Result<String, String> {
+    let parsed = parse_export(text)?;
+    super::to_string_pretty(&to_plain_json(parsed)).map_err(|e| format!("Failed to format JSON: {}", e))
+}
+
+pub struct MongoJsonFormatter;
+
+impl Formatter for MongoJsonFormatter {
+    fn id(&self) -> &'static str {
+        "mongo-json"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ejson", "extended-json"]
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Mongo Extended JSON to Plain JSON"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        super::layout_options_schema(&["indent_width", "use_tabs"])
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        convert_to_plain_json(input)
+    }
+}
@@ -0,0 +1,401 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::json::parse_json_relaxed;
+use super::mongo;
+use super::Formatter;
+use crate::json_path::{self, Segment};
+
+// Paths listed in the summary's size section
+const SUMMARY_SIZE_PATHS: usize = 10;
+// Distinct `[*]` field patterns tracked; keeps pathological documents bounded
+const MAX_FIELD_PATTERNS: usize = 50_000;
+
+pub fn summarize_json(text: &str) -> Result<String, String> {
+    let trimmed = text.trim();
+
+    if trimmed.is_empty() {
+        return Err("Empty JSON input".to_string());
+    }
+
+    // Parse the JSON to validate it (JSONC/JSON5 input is accepted too)
+    let parsed_value = parse_json_relaxed(trimmed)?;
+
+    // Generate summary
+    let summary = generate_json_summary(&parsed_value, "root", 0);
+
+    // Format as a readable summary
+    let mut result = String::new();
+    result.push_str("JSON Structure Summary:\n");
+    result.push_str("======================\n\n");
+    result.push_str(&summary);
+
+    // Add statistics
+    let stats = calculate_json_stats(&parsed_value);
+    result.push_str("\n\nStatistics:\n");
+    result.push_str("-----------\n");
+    result.push_str(&format!("Total objects: {}\n", stats.objects));
+    result.push_str(&format!("Total arrays: {}\n", stats.arrays));
+    result.push_str(&format!("Total primitive values: {}\n", stats.primitives));
+    result.push_str(&format!("Maximum depth: {}\n", stats.max_depth));
+    result.push_str(&format!("Total keys: {}\n", stats.total_keys));
+
+    let sizes = analyze_json_size(&parsed_value, SUMMARY_SIZE_PATHS);
+    result.push_str("\nSize (as compact JSON):\n");
+    result.push_str("----------------------\n");
+    result.push_str(&format!(
+        "Total: {}\n",
+        crate::units::human_readable_bytes(sizes["total_bytes"].as_f64().unwrap_or_default())
+    ));
+    for (title, section) in [
+        ("By top-level key", "top_level"),
+        ("Largest paths", "largest_paths"),
+    ] {
+        let entries = sizes[section].as_array().cloned().unwrap_or_default();
+        if entries.is_empty() {
+            continue;
+        }
+        result.push_str(&format!("{}:\n", title));
+        for entry in entries.iter().take(SUMMARY_SIZE_PATHS) {
+            result.push_str(&format!(
+                "  {} - {} ({:.1}%)\n",
+                entry["path"].as_str().unwrap_or_default(),
+                crate::units::human_readable_bytes(entry["bytes"].as_f64().unwrap_or_default()),
+                entry["percent"].as_f64().unwrap_or_default()
+            ));
+        }
+    }
+
+    Ok(result)
+}
+
+fn generate_json_summary(value: &serde_json::Value, key: &str, depth: usize) -> String {
+    let indent = "  ".repeat(depth);
+
+    // Extended JSON wrappers read as the value they stand for
+    if let Some(extended) = mongo::recognize(value) {
+        return format!(
+            "{}🍃 {}: {} - {}\n",
+            indent,
+            key,
+            extended.type_name(),
+            extended.describe()
+        );
+    }
+
+    match value {
+        serde_json::Value::Object(obj) => {
+            let mut summary = String::new();
+            if depth == 0 {
+                summary.push_str(&format!(
+                    "{}📁 {} (Object with {} keys)\n",
+                    indent,
+                    key,
+                    obj.len()
+                ));
+            } else {
+                summary.push_str(&format!(
+                    "{}📁 {}: Object ({} keys)\n",
+                    indent,
+                    key,
+                    obj.len()
+                ));
+            }
+
+            for (k, v) in obj.iter() {
+                summary.push_str(&generate_json_summary(v, k, depth + 1));
+            }
+            summary
+        }
+        serde_json::Value::Array(arr) => {
+            let mut summary = String::new();
+            summary.push_str(&format!(
+                "{}📋 {}: Array ({} items)\n",
+                indent,
+                key,
+                arr.len()
+            ));
+
+            if !arr.is_empty() {
+                // Show type of first element and if all elements are the same type
+                let first_type = get_value_type(&arr[0]);
+                let all_same_type = arr.iter().all(|v| get_value_type(v) == first_type);
+
+                if all_same_type {
+                    summary.push_str(&format!("{}   └─ All items are: {}\n", indent, first_type));
+                } else {
+                    summary.push_str(&format!("{}   └─ Mixed types: ", indent));
+                    let mut types = std::collections::HashSet::new();
+                    for item in arr.iter() {
+                        types.insert(get_value_type(item));
+                    }
+                    let type_list: Vec<String> = types.into_iter().collect();
+                    summary.push_str(&type_list.join(", "));
+                    summary.push('\n');
+                }
+
+                // If it's an array of objects, show the structure of the first object
+                if let serde_json::Value::Object(_) = &arr[0] {
+                    summary.push_str(&format!("{}   └─ First item structure:\n", indent));
+                    summary.push_str(&generate_json_summary(&arr[0], "item", depth + 2));
+                }
+            }
+            summary
+        }
+        serde_json::Value::String(s) => {
+            let preview = if s.len() > 50 {
+                format!("{}...", &s[..47])
+            } else {
+                s.clone()
+            };
+            format!(
+                "{}📝 {}: String ({} chars) - \"{}\"\n",
+                indent,
+                key,
+                s.len(),
+                preview
+            )
+        }
+        serde_json::Value::Number(n) => {
+            format!("{}🔢 {}: Number - {}\n", indent, key, n)
+        }
+        serde_json::Value::Bool(b) => {
+            format!("{}✅ {}: Boolean - {}\n", indent, key, b)
+        }
+        serde_json::Value::Null => {
+            format!("{}❌ {}: null\n", indent, key)
+        }
+    }
+}
+
+fn get_value_type(value: &serde_json::Value) -> String {
+    if let Some(extended) = mongo::recognize(value) {
+        return extended.type_name().to_string();
+    }
+    match value {
+        serde_json::Value::Object(_) => "Object".to_string(),
+        serde_json::Value::Array(_) => "Array".to_string(),
+        serde_json::Value::String(_) => "String".to_string(),
+        serde_json::Value::Number(_) => "Number".to_string(),
+        serde_json::Value::Bool(_) => "Boolean".to_string(),
+        serde_json::Value::Null => "null".to_string(),
+    }
+}
+
+struct JsonStats {
+    objects: usize,
+    arrays: usize,
+    primitives: usize,
+    max_depth: usize,
+    total_keys: usize,
+}
+
+fn calculate_json_stats(value: &serde_json::Value) -> JsonStats {
+    let mut stats = JsonStats {
+        objects: 0,
+        arrays: 0,
+        primitives: 0,
+        max_depth: 0,
+        total_keys: 0,
+    };
+
+    calculate_stats_recursive(value, &mut stats, 0);
+    stats
+}
+
+fn calculate_stats_recursive(value: &serde_json::Value, stats: &mut JsonStats, depth: usize) {
+    stats.max_depth = stats.max_depth.max(depth);
+
+    match value {
+        _ if mongo::recognize(value).is_some() => {
+            stats.primitives += 1;
+        }
+        serde_json::Value::Object(obj) => {
+            stats.objects += 1;
+            stats.total_keys += obj.len();
+            for v in obj.values() {
+                calculate_stats_recursive(v, stats, depth + 1);
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            stats.arrays += 1;
+            for v in arr.iter() {
+                calculate_stats_recursive(v, stats, depth + 1);
+            }
+        }
+        _ => {
+            stats.primitives += 1;
+        }
+    }
+}
+
+// Length of `text` as a JSON string literal, quotes and escapes included
+fn string_size(text: &str) -> usize {
+    2 + text
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+            c if (c as u32) < 0x20 => 6,
+            c => c.len_utf8(),
+        })
+        .sum::<usize>()
+}
+
+struct SizeWalk {
+    top: usize,
+    // Min-heap of the `top` largest subtrees seen so far
+    largest: BinaryHeap<Reverse<(usize, String)>>,
+    // Bytes and occurrences per path with array indices folded into [*]
+    fields: HashMap<String, (usize, usize)>,
+    path: Vec<Segment>,
+    pattern: String,
+}
+
+impl SizeWalk {
+    fn walk(&mut self, value: &serde_json::Value) -> usize {
+        match value {
+            serde_json::Value::Object(object) => {
+                let mut total = 2 + object.len().saturating_sub(1);
+                for (key, child) in object {
+                    let segment = Segment::Key(key.clone());
+                    let mark = self.pattern.len();
+                    self.pattern
+                        .push_str(&json_path::format_path(std::slice::from_ref(&segment))[1..]);
+                    self.path.push(segment);
+                    total += string_size(key) + 1 + self.walk_child(child);
+                    self.path.pop();
+                    self.pattern.truncate(mark);
+                }
+                total
+            }
+            serde_json::Value::Array(items) => {
+                let mut total = 2 + items.len().saturating_sub(1);
+                let mark = self.pattern.len();
+                self.pattern.push_str("[*]");
+                for (index, item) in items.iter().enumerate() {
+                    self.path.push(Segment::Index(index));
+                    total += self.walk_child(item);
+                    self.path.pop();
+                }
+                self.pattern.truncate(mark);
+                total
+            }
+            serde_json::Value::String(text) => string_size(text),
+            serde_json::Value::Number(number) => number.to_string().len(),
+            serde_json::Value::Bool(true) => 4,
+            serde_json::Value::Bool(false) => 5,
+            serde_json::Value::Null => 4,
+        }
+    }
+
+    fn walk_child(&mut self, value: &serde_json::Value) -> usize {
+        let size = self.walk(value);
+        // The path is only formatted for subtrees that make the list
+        let smallest = self
+            .largest
+            .peek()
+            .map(|Reverse((size, _))| *size)
+            .unwrap_or(0);
+        if self.largest.len() < self.top || size > smallest {
+            self.largest
+                .push(Reverse((size, json_path::format_path(&self.path))));
+            if self.largest.len() > self.top {
+                self.largest.pop();
+            }
+        }
+        if let Some(entry) = self.fields.get_mut(&self.pattern) {
+            entry.0 += size;
+            entry.1 += 1;
+        } else if self.fields.len() < MAX_FIELD_PATTERNS {
+            self.fields.insert(self.pattern.clone(), (size, 1));
+        }
+        size
+    }
+}
+
+// Where the bytes of a JSON document go, measured as compact JSON: per
+// top-level key, the `top` largest subtrees at any depth, and the `top`
+// heaviest fields with array indices folded together ($.items[*].thumbnail
+// adds up that field across every item).
+pub fn analyze_json_size(value: &serde_json::Value, top: usize) -> serde_json::Value {
+    let mut walk = SizeWalk {
+        top,
+        largest: BinaryHeap::new(),
+        fields: HashMap::new(),
+        path: Vec::new(),
+        pattern: "$".to_string(),
+    };
+    let total = walk.walk(value);
+    let percent = |bytes: usize| {
+        if total == 0 {
+            0.0
+        } else {
+            bytes as f64 * 100.0 / total as f64
+        }
+    };
+
+    let mut top_level: Vec<(String, usize)> = match value {
+        serde_json::Value::Object(object) => object
+            .keys()
+            .map(|key| {
+                let path = json_path::format_path(&[Segment::Key(key.clone())]);
+                let bytes = walk
+                    .fields
+                    .get(&path)
+                    .map(|(bytes, _)| *bytes)
+                    .unwrap_or_default();
+                (path, bytes)
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    top_level.sort_by_key(|(_, bytes)| Reverse(*bytes));
+
+    let largest: Vec<(usize, String)> = walk
+        .largest
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(entry)| entry)
+        .collect();
+    let mut fields: Vec<(String, (usize, usize))> = walk
+        .fields
+        .into_iter()
+        .filter(|(pattern, _)| pattern.contains("[*]"))
+        .collect();
+    fields.sort_by_key(|(_, (count, _))| Reverse(*count));
+    fields.truncate(top);
+
+    serde_json::json!({
+        "total_bytes": total,
+        "top_level": top_level
+            .iter()
+            .map(|(path, bytes)| serde_json::json!({ "path": path, "bytes": bytes, "percent": percent(*bytes) }))
+            .collect::<Vec<_>>(),
+        "largest_paths": largest
+            .iter()
+            .map(|(bytes, path)| serde_json::json!({ "path": path, "bytes": bytes, "percent": percent(*bytes) }))
+            .collect::<Vec<_>>(),
+        "heaviest_fields": fields
+            .iter()
+            .map(|(pattern, (bytes, count))| {
+                serde_json::json!({ "path": pattern, "bytes": bytes, "count": count, "percent": percent(*bytes) })
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+pub struct JsonSummaryFormatter;
+
+impl Formatter for JsonSummaryFormatter {
+    fn id(&self) -> &'static str {
+        "json-summary"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "JSON Summarizer"
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        summarize_json(input)
+    }
+}
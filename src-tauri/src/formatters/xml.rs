@@ -0,0 +1,381 @@
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::ResolveResult;
+use quick_xml::NsReader;
+use std::borrow::Cow;
+
+use super::{Formatter, QuoteStyle};
+
+// Report roughly every 64K bytes while reading the input
+const PROGRESS_INTERVAL: usize = 64 * 1024;
+
+// What was written last decides whether the next token starts a new line:
+// text and empty elements (`<a></a>`) stay on the start tag's line
+#[derive(PartialEq)]
+enum Last {
+    Nothing,
+    Start,
+    Text,
+    Markup,
+}
+
+fn raw(bytes: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(bytes)
+}
+
+// 1-based line and column of a byte offset, plus the line itself for the snippet
+fn locate(text: &str, offset: usize) -> (usize, usize, &str) {
+    let offset = crate::text_buffer::grapheme_floor(text, offset.min(text.len()));
+    let line_start = text[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = text[offset..].find('\n').map(|i| offset + i).unwrap_or(text.len());
+    let line = text[..offset].matches('\n').count() + 1;
+    let column = text[line_start..offset].chars().count() + 1;
+    (line, column, text[line_start..line_end].trim_end_matches('\r'))
+}
+
+fn syntax_error(text: &str, offset: usize, message: &str) -> String {
+    let (line, column, error_line) = locate(text, offset);
+    let marker = format!("{}^", "-".repeat(column - 1));
+    format!(
+        "❌ XML Syntax Error\n\n\
+        There's a syntax error in your XML at line {} column {}.\n\n\
+        Error location:\n\
+        {}\n\
+        {}\n\n\
+        Detailed error: {}",
+        line, column, error_line, marker, message
+    )
+}
+
+// Names declared with <!ENTITY ...> in the internal DTD subset
+fn declared_entities(doctype: &str) -> Vec<String> {
+    doctype
+        .split("<!ENTITY")
+        .skip(1)
+        .filter_map(|declaration| {
+            let name = declaration.split_whitespace().next()?;
+            // Parameter entities (`<!ENTITY % name ...>`) can't appear in content
+            (name != "%").then(|| name.to_string())
+        })
+        .collect()
+}
+
+// Checks the entity and character references in escaped text. Predefined and
+// numeric references are built in; anything else must be declared in the DTD.
+fn check_references(escaped: &str, entities: &[String]) -> Result<(), String> {
+    quick_xml::escape::unescape_with(escaped, |entity| {
+        entities.iter().any(|declared| declared == entity).then_some("")
+    })
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+fn new_line(formatted: &mut String, depth: usize) {
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+    formatted.push_str(&super::indent(depth));
+}
+
+fn check_prefix(result: &ResolveResult, name: &[u8]) -> Result<(), String> {
+    match result {
+        ResolveResult::Unknown(prefix) => Err(format!(
+            "Namespace prefix '{}' in <{}> is not declared",
+            raw(prefix),
+            raw(name)
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Start or empty tag, namespace-checked. Attributes keep their escaping and
+// get the configured quotes, or the other kind when the value contains them;
+// a tag whose attributes were spread over several lines gets one attribute
+// per line.
+fn write_tag(
+    reader: &NsReader<&[u8]>,
+    element: &BytesStart,
+    depth: usize,
+    entities: &[String],
+    formatted: &mut String,
+) -> Result<(), String> {
+    let (result, _) = reader.resolve_element(element.name());
+    check_prefix(&result, element.name().as_ref())?;
+
+    let mut attributes = Vec::new();
+    for attribute in element.attributes() {
+        let Attribute { key, value } = attribute.map_err(|e| e.to_string())?;
+        let (result, _) = reader.resolve_attribute(key);
+        check_prefix(&result, key.as_ref())?;
+        let value = raw(&value);
+        check_references(&value, entities)?;
+        let quote = match super::options().quote_style {
+            QuoteStyle::Double if value.contains('"') => '\'',
+            QuoteStyle::Double => '"',
+            QuoteStyle::Single if value.contains('\'') => '"',
+            QuoteStyle::Single => '\'',
+        };
+        attributes.push(format!("{}={}{}{}", raw(key.as_ref()), quote, value, quote));
+    }
+
+    formatted.push('<');
+    formatted.push_str(&raw(element.name().as_ref()));
+    let multiline = attributes.len() > 1 && element.attributes_raw().contains(&b'\n');
+    for attribute in attributes {
+        if multiline {
+            formatted.push('\n');
+            formatted.push_str(&super::indent(depth + 1));
+        } else {
+            formatted.push(' ');
+        }
+        formatted.push_str(&attribute);
+    }
+    Ok(())
+}
+
+fn is_whitespace(content: &[u8]) -> bool {
+    content.iter().all(|byte| matches!(byte, b' ' | b'\t' | b'\r' | b'\n'))
+}
+
+// Whether each element, in document order, directly holds text or CDATA. In
+// such mixed content the whitespace between children is significant, so the
+// element is written as it stands rather than re-indented. Syntax errors end
+// the scan early; the formatting pass reports them.
+fn mixed_elements(text: &str) -> Vec<bool> {
+    let mut reader = NsReader::from_str(text);
+    let mut mixed = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(_)) => {
+                open.push(mixed.len());
+                mixed.push(false);
+            }
+            Ok(Event::End(_)) => {
+                open.pop();
+            }
+            Ok(Event::Text(content)) if is_whitespace(&content) => {}
+            Ok(Event::Text(_) | Event::CData(_)) => {
+                if let Some(&element) = open.last() {
+                    mixed[element] = true;
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+    mixed
+}
+
+pub fn format_xml(text: &str) -> Result<String, String> {
+    format_xml_with_progress(text, &mut |_| Ok(()))
+}
+
+pub fn format_xml_with_progress(
+    text: &str,
+    progress: &mut dyn FnMut(usize) -> Result<(), String>,
+) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Err("Empty XML input".to_string());
+    }
+
+    let mixed = mixed_elements(text);
+    let mut reader = NsReader::from_str(text);
+
+    let mut formatted = String::new();
+    // Open elements with the offset of their start tag, for unclosed-tag errors
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut entities: Vec<String> = Vec::new();
+    let mut last = Last::Nothing;
+    let mut elements = 0;
+    let mut has_root = false;
+    // Depth of the mixed-content element being copied without re-indenting
+    let mut inline_from: Option<usize> = None;
+    let mut next_report = PROGRESS_INTERVAL;
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        if position >= next_report {
+            progress(position)?;
+            next_report = position + PROGRESS_INTERVAL;
+        }
+
+        let event = match reader.read_event() {
+            Ok(event) => event,
+            Err(e) => return Err(syntax_error(text, reader.error_position() as usize, &e.to_string())),
+        };
+        let depth = open.len();
+        let inline = inline_from.is_some();
+        // Inside mixed content markup is copied in place; elsewhere it goes on
+        // its own line
+        let break_line = |formatted: &mut String| {
+            if !inline {
+                new_line(formatted, depth);
+            }
+        };
+
+        if depth == 0 {
+            match &event {
+                Event::Start(_) | Event::Empty(_) if has_root => {
+                    return Err(syntax_error(text, position, "Only one root element is allowed"));
+                }
+                Event::Start(_) | Event::Empty(_) => has_root = true,
+                Event::Text(content) if !is_whitespace(content) => {
+                    return Err(syntax_error(text, position, "Text is not allowed outside the root element"));
+                }
+                Event::CData(_) => {
+                    return Err(syntax_error(text, position, "CDATA is not allowed outside the root element"));
+                }
+                _ => {}
+            }
+        }
+
+        let written = match &event {
+            Event::Start(element) => {
+                break_line(&mut formatted);
+                write_tag(&reader, element, depth, &entities, &mut formatted).map(|()| {
+                    formatted.push('>');
+                    open.push((raw(element.name().as_ref()).into_owned(), position));
+                    if inline_from.is_none() && mixed.get(elements).copied().unwrap_or(false) {
+                        inline_from = Some(open.len());
+                    }
+                    elements += 1;
+                    Last::Start
+                })
+            }
+            Event::Empty(element) => {
+                break_line(&mut formatted);
+                write_tag(&reader, element, depth, &entities, &mut formatted).map(|()| {
+                    formatted.push_str("/>");
+                    Last::Markup
+                })
+            }
+            Event::End(element) => {
+                // The reader has already matched the name against the start tag
+                open.pop();
+                if last == Last::Markup && !inline {
+                    new_line(&mut formatted, depth.saturating_sub(1));
+                }
+                formatted.push_str(&format!("</{}>", raw(element.name().as_ref())));
+                if inline_from == Some(depth) {
+                    inline_from = None;
+                }
+                Ok(Last::Markup)
+            }
+            // Whitespace between elements is only layout; inside mixed content
+            // it's kept like any other text
+            Event::Text(content) if !inline && is_whitespace(content) => continue,
+            Event::Text(content) => {
+                let content = raw(content);
+                check_references(&content, &entities).map(|_| {
+                    if last != Last::Start && last != Last::Text {
+                        break_line(&mut formatted);
+                    }
+                    formatted.push_str(&content);
+                    Last::Text
+                })
+            }
+            Event::CData(content) => {
+                if last != Last::Start && last != Last::Text {
+                    break_line(&mut formatted);
+                }
+                formatted.push_str(&format!("<![CDATA[{}]]>", raw(content)));
+                Ok(Last::Text)
+            }
+            Event::Comment(content) => {
+                break_line(&mut formatted);
+                formatted.push_str(&format!("<!--{}-->", raw(content)));
+                Ok(Last::Markup)
+            }
+            Event::Decl(declaration) => {
+                break_line(&mut formatted);
+                formatted.push_str(&format!("<?{}?>", raw(declaration)));
+                Ok(Last::Markup)
+            }
+            Event::PI(instruction) => {
+                break_line(&mut formatted);
+                formatted.push_str(&format!("<?{}?>", raw(instruction)));
+                Ok(Last::Markup)
+            }
+            Event::DocType(doctype) => {
+                let doctype = raw(doctype);
+                entities = declared_entities(&doctype);
+                break_line(&mut formatted);
+                formatted.push_str(&format!("<!DOCTYPE {}>", doctype));
+                Ok(Last::Markup)
+            }
+            Event::Eof => break,
+        };
+        last = written.map_err(|message| syntax_error(text, position, &message))?;
+    }
+
+    if let Some((name, position)) = open.pop() {
+        return Err(syntax_error(text, position, &format!("<{}> is never closed", name)));
+    }
+    if !has_root {
+        return Err(syntax_error(text, text.len(), "The document has no root element"));
+    }
+
+    progress(text.len())?;
+    Ok(formatted)
+}
+
+pub struct XmlFormatter;
+
+impl Formatter for XmlFormatter {
+    fn id(&self) -> &'static str {
+        "xml"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "XML Format"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "xml"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        super::layout_options_schema(&["indent_width", "use_tabs", "quote_style"])
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        format_xml(input)
+    }
+
+    fn format_with_progress(
+        &self,
+        input: &str,
+        progress: &mut dyn FnMut(usize) -> Result<(), String>,
+    ) -> Result<String, String> {
+        format_xml_with_progress(input, progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn element_content_is_reindented() {
+        let formatted = format_xml("<a>\n<b>1</b>   <c/></a>").unwrap();
+        assert_eq!(formatted, format!("<a>\n{0}<b>1</b>\n{0}<c/>\n</a>", super::super::indent(1)));
+    }
+
+    #[test]
+    fn mixed_content_keeps_its_whitespace() {
+        let input = "<p>Hello <b>big</b> <i>world</i>\n  again </p>";
+        assert_eq!(format_xml(input).unwrap(), input);
+        assert_eq!(format_xml("<pre>  two  spaces  </pre>").unwrap(), "<pre>  two  spaces  </pre>");
+    }
+
+    #[test]
+    fn requires_exactly_one_root() {
+        assert!(format_xml("<a/><b/>").unwrap_err().contains("Only one root element"));
+        assert!(format_xml("<a/>trailing").unwrap_err().contains("outside the root element"));
+        assert!(format_xml("<?xml version=\"1.0\"?><!-- only a comment -->")
+            .unwrap_err()
+            .contains("no root element"));
+        assert!(format_xml("<?xml version=\"1.0\"?>\n<a/>\n<!-- after -->\n").is_ok());
+    }
+}
@@ -0,0 +1,203 @@
+// Offline IP enrichment from MaxMind-format (.mmdb) databases such as
+// GeoLite2-City/Country and GeoLite2-ASN, configured in settings. Nothing is
+// sent over the network: the databases are opened from disk, memory-mapped
+// and kept open between lookups.
+use maxminddb::{Mmap, Reader};
+use regex::Regex;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+type Database = Arc<Reader<Mmap>>;
+
+// Opened databases by path; reopened when the file changes on disk
+fn open(path: &str) -> Result<Database, String> {
+    static OPEN: OnceLock<Mutex<HashMap<String, (std::time::SystemTime, Database)>>> = OnceLock::new();
+    let modified = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let mut open = OPEN.get_or_init(Default::default).lock().map_err(|e| e.to_string())?;
+    if let Some((opened, database)) = open.get(path) {
+        if *opened == modified {
+            return Ok(database.clone());
+        }
+    }
+    let database = Arc::new(Reader::open_mmap(path).map_err(|e| format!("Failed to open {}: {}", path, e))?);
+    open.insert(path.to_string(), (modified, database.clone()));
+    Ok(database)
+}
+
+fn ipv4_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b")
+            .expect("valid IPv4 pattern")
+    })
+}
+
+// Candidates only; each is confirmed by parsing
+fn ipv6_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"[0-9A-Fa-f]{0,4}(?::[0-9A-Fa-f]{0,4}){2,7}").expect("valid IPv6 pattern"))
+}
+
+// Distinct addresses in order of first appearance, with how often each occurs
+pub fn extract_ips(text: &str) -> Vec<(IpAddr, usize)> {
+    let mut found: Vec<(usize, IpAddr)> = ipv4_pattern()
+        .find_iter(text)
+        .filter_map(|m| m.as_str().parse().ok().map(|ip| (m.start(), ip)))
+        .collect();
+    found.extend(ipv6_pattern().find_iter(text).filter_map(|m| {
+        // Timestamps like 12:30:45 also fit the pattern but don't parse
+        // and ::ffff:1.2.3.4 is left to the IPv4 match
+        let candidate = m.as_str();
+        (candidate.matches(':').count() >= 2 && !text[m.end()..].starts_with('.'))
+            .then(|| candidate.parse::<std::net::Ipv6Addr>().ok())
+            .flatten()
+            .map(|ip| (m.start(), IpAddr::V6(ip)))
+    }));
+    found.sort_by_key(|(start, _)| *start);
+
+    let mut counts: Vec<(IpAddr, usize)> = Vec::new();
+    let mut index: HashMap<IpAddr, usize> = HashMap::new();
+    for (_, ip) in found {
+        match index.get(&ip) {
+            Some(position) => counts[*position].1 += 1,
+            None => {
+                index.insert(ip, counts.len());
+                counts.push((ip, 1));
+            }
+        }
+    }
+    counts
+}
+
+// Addresses no public database will have an answer for
+fn special_range(ip: &IpAddr) -> Option<&'static str> {
+    match ip {
+        IpAddr::V4(ip) if ip.is_private() => Some("private"),
+        IpAddr::V4(ip) if ip.is_loopback() => Some("loopback"),
+        IpAddr::V4(ip) if ip.is_link_local() => Some("link-local"),
+        IpAddr::V4(ip) if ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64 => Some("carrier-grade NAT"),
+        IpAddr::V4(ip) if ip.is_multicast() => Some("multicast"),
+        IpAddr::V4(ip) if ip.is_unspecified() || ip.is_broadcast() || ip.is_documentation() => Some("reserved"),
+        IpAddr::V6(ip) if ip.is_loopback() => Some("loopback"),
+        IpAddr::V6(ip) if ip.is_unspecified() => Some("reserved"),
+        IpAddr::V6(ip) if ip.is_multicast() => Some("multicast"),
+        IpAddr::V6(ip) if (ip.segments()[0] & 0xfe00) == 0xfc00 => Some("private"),
+        IpAddr::V6(ip) if (ip.segments()[0] & 0xffc0) == 0xfe80 => Some("link-local"),
+        _ => None,
+    }
+}
+
+fn english_name(record: &serde_json::Value, field: &str) -> Option<String> {
+    record[field]["names"]["en"].as_str().map(str::to_string)
+}
+
+fn lookup(ip: IpAddr, count: usize, geo: Option<&Database>, asn: Option<&Database>) -> serde_json::Value {
+    let mut result = serde_json::json!({ "ip": ip.to_string(), "count": count });
+    if let Some(range) = special_range(&ip) {
+        result["range"] = range.into();
+        return result;
+    }
+    if let Some(geo) = geo {
+        match geo.lookup::<serde_json::Value>(ip) {
+            Ok(record) => {
+                result["country_code"] = record["country"]["iso_code"].clone();
+                result["country"] = english_name(&record, "country").into();
+                result["continent"] = english_name(&record, "continent").into();
+                result["city"] = english_name(&record, "city").into();
+                result["region"] = record["subdivisions"][0]["names"]["en"].clone();
+                result["latitude"] = record["location"]["latitude"].clone();
+                result["longitude"] = record["location"]["longitude"].clone();
+                result["time_zone"] = record["location"]["time_zone"].clone();
+            }
+            Err(maxminddb::MaxMindDBError::AddressNotFoundError(_)) => result["country"] = serde_json::Value::Null,
+            Err(e) => result["error"] = format!("Lookup failed: {}", e).into(),
+        }
+    }
+    if let Some(asn) = asn {
+        if let Ok(record) = asn.lookup::<serde_json::Value>(ip) {
+            result["asn"] = record["autonomous_system_number"].clone();
+            result["as_organization"] = record["autonomous_system_organization"].clone();
+        }
+    }
+    result
+}
+
+fn lookup_blocking(
+    app: &AppHandle,
+    ips: Option<Vec<String>>,
+    text: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let settings = crate::settings::current(app);
+    let configured = |path: Option<String>| path.filter(|path| !path.is_empty());
+    let (geo_path, asn_path) = (configured(settings.geoip_database), configured(settings.asn_database));
+    if geo_path.is_none() && asn_path.is_none() {
+        return Err("No IP database configured; set a GeoIP (.mmdb) and/or ASN database in settings".to_string());
+    }
+    let geo = geo_path.as_deref().map(open).transpose()?;
+    let asn = asn_path.as_deref().map(open).transpose()?;
+
+    let addresses = match ips.filter(|ips| !ips.is_empty()) {
+        Some(ips) => ips
+            .iter()
+            .map(|ip| {
+                ip.trim()
+                    .parse::<IpAddr>()
+                    .map(|ip| (ip, 1))
+                    .map_err(|_| format!("Invalid IP address: {}", ip))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => {
+            let text = text.filter(|text| !text.is_empty());
+            let snapshot = match text {
+                Some(_) => None,
+                None => Some(
+                    app.state::<AppState>()
+                        .inner()
+                        .lock()
+                        .map_err(|e| e.to_string())?
+                        .snapshot_raw()?,
+                ),
+            };
+            let text = snapshot
+                .as_ref()
+                .map_or(text.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+            extract_ips(text)
+        }
+    };
+
+    let results: Vec<serde_json::Value> = addresses
+        .into_iter()
+        .map(|(ip, count)| lookup(ip, count, geo.as_ref(), asn.as_ref()))
+        .collect();
+    let metadata = |database: &Option<Database>| {
+        database.as_ref().map(|database| {
+            serde_json::json!({
+                "type": database.metadata.database_type,
+                "built": chrono::DateTime::from_timestamp(database.metadata.build_epoch as i64, 0)
+                    .map(|built| built.date_naive().to_string())
+            })
+        })
+    };
+    Ok(serde_json::json!({
+        "addresses": results,
+        "databases": { "geoip": metadata(&geo), "asn": metadata(&asn) }
+    }))
+}
+
+// Country, city and ASN for each of `ips`, or for every address found in
+// `text` (the active document's raw content when both are empty), using the
+// databases from settings. Private and reserved ranges are labelled instead.
+#[tauri::command]
+pub async fn lookup_ip_addresses(
+    app: AppHandle,
+    ips: Option<Vec<String>>,
+    text: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("lookup_ip_addresses", move || lookup_blocking(&app, ips, text)).await
+}
@@ -0,0 +1,431 @@
+// Repository plumbing lookups: converting git remote URLs between their SSH
+// and HTTPS forms, reading `.git/config` / `.gitmodules` / `.gitconfig` into
+// JSON, and listing the hosts of an OpenSSH client config with the options
+// each one ends up with.
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+// SSH options that accumulate instead of first-value-wins
+const MULTI_VALUE_SSH_OPTIONS: &[&str] = &[
+    "identityfile",
+    "certificatefile",
+    "localforward",
+    "remoteforward",
+    "dynamicforward",
+    "sendenv",
+    "setenv",
+];
+
+struct Remote {
+    host: String,
+    port: Option<u16>,
+    user: Option<String>,
+    path: String,
+}
+
+// scp-like `git@host:owner/repo.git`, `ssh://`, `git+ssh://`, `git://`,
+// `http(s)://`, or a bare `host/owner/repo`
+fn parse_remote(url: &str) -> Result<Remote, String> {
+    let url = url.trim();
+    if url.contains("://") {
+        let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+        // Ports and users of http(s) URLs (tokens, usually) don't carry over to SSH
+        let ssh = parsed.scheme().contains("ssh");
+        return Ok(Remote {
+            host: parsed
+                .host_str()
+                .ok_or_else(|| "The URL has no host".to_string())?
+                .to_string(),
+            port: parsed.port().filter(|_| ssh),
+            user: Some(parsed.username().to_string()).filter(|user| ssh && !user.is_empty()),
+            path: parsed.path().trim_start_matches('/').to_string(),
+        });
+    }
+    // scp-like syntax: [user@]host:path, where the host has no slash
+    if let Some((authority, path)) = url.split_once(':') {
+        if !authority.contains('/') && !path.starts_with("//") {
+            let (user, host) = match authority.rsplit_once('@') {
+                Some((user, host)) => (Some(user.to_string()), host),
+                None => (None, authority),
+            };
+            return Ok(Remote {
+                host: host.to_string(),
+                port: None,
+                user,
+                path: path.trim_start_matches('/').to_string(),
+            });
+        }
+    }
+    match url.split_once('/') {
+        Some((host, path)) if host.contains('.') => Ok(Remote {
+            host: host.to_string(),
+            port: None,
+            user: None,
+            path: path.to_string(),
+        }),
+        _ => Err(format!("Not a git remote URL: {}", url)),
+    }
+}
+
+pub fn convert_remote(url: &str) -> Result<Value, String> {
+    let remote = parse_remote(url)?;
+    let path = remote.path.trim_end_matches('/');
+    let repo_path = path.strip_suffix(".git").unwrap_or(path);
+    let user = remote.user.as_deref().unwrap_or("git");
+    // A non-default SSH port needs the ssh:// form
+    let ssh = match remote.port.filter(|port| *port != 22) {
+        Some(port) => format!("ssh://{}@{}:{}/{}.git", user, remote.host, port, repo_path),
+        None => format!("{}@{}:{}.git", user, remote.host, repo_path),
+    };
+    let (owner, name) = match repo_path.rsplit_once('/') {
+        Some((owner, name)) => (Some(owner), name),
+        None => (None, repo_path),
+    };
+    Ok(json!({
+        "input": url.trim(),
+        "host": remote.host,
+        "owner": owner,
+        "repository": name,
+        "ssh": ssh,
+        "https": format!("https://{}/{}.git", remote.host, repo_path),
+        "web": format!("https://{}/{}", remote.host, repo_path)
+    }))
+}
+
+// A git config value: quotes removed, escapes resolved, trailing comments cut
+fn config_value(raw: &str) -> String {
+    let mut value = String::new();
+    let mut in_quotes = false;
+    let mut chars = raw.trim().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(other) => value.push(other),
+                None => {}
+            },
+            '#' | ';' if !in_quotes => break,
+            _ => value.push(c),
+        }
+    }
+    value.trim_end().to_string()
+}
+
+fn insert_value(section: &mut Map<String, Value>, key: String, value: Value) {
+    // Repeated keys (remote.*.fetch, url.*.insteadOf) become arrays
+    match section.get_mut(&key) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+        None => {
+            section.insert(key, value);
+        }
+    }
+}
+
+// `[section]` and `[section "subsection"]` blocks as nested objects. Keys are
+// lowercased like git does; a key without `=` is boolean true.
+pub fn parse_config(text: &str) -> Result<Value, String> {
+    let mut root = Map::new();
+    let mut current: Option<(String, Option<String>)> = None;
+    let mut logical = String::new();
+    let mut start_line = 0;
+    for (index, line) in text.lines().enumerate() {
+        if logical.is_empty() {
+            start_line = index + 1;
+        }
+        // A trailing backslash continues the value on the next line
+        if let Some(continued) = line.strip_suffix('\\') {
+            logical.push_str(continued);
+            continue;
+        }
+        logical.push_str(line);
+        let entry = std::mem::take(&mut logical);
+        let entry = entry.trim();
+        if entry.is_empty() || entry.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(header) = entry.strip_prefix('[') {
+            let header = header
+                .split_once(']')
+                .map(|(header, _)| header)
+                .ok_or_else(|| format!("Line {}: unterminated section header", start_line))?;
+            current = Some(match header.split_once(char::is_whitespace) {
+                Some((name, subsection)) => (
+                    name.to_ascii_lowercase(),
+                    Some(subsection.trim().trim_matches('"').to_string()),
+                ),
+                // [section.subsection] is the legacy spelling
+                None => match header.split_once('.') {
+                    Some((name, subsection)) => (name.to_ascii_lowercase(), Some(subsection.to_string())),
+                    None => (header.to_ascii_lowercase(), None),
+                },
+            });
+            continue;
+        }
+        let Some((section, subsection)) = &current else {
+            return Err(format!("Line {}: a setting before any [section]", start_line));
+        };
+        let (key, value) = match entry.split_once('=') {
+            Some((key, value)) => (key.trim().to_ascii_lowercase(), Value::String(config_value(value))),
+            None => (entry.to_ascii_lowercase(), Value::Bool(true)),
+        };
+        let section = root
+            .entry(section.clone())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| format!("Line {}: {} is both a value and a section", start_line, section))?;
+        let target = match subsection {
+            Some(subsection) => section
+                .entry(subsection.clone())
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .ok_or_else(|| format!("Line {}: {} is both a value and a subsection", start_line, subsection))?,
+            None => section,
+        };
+        insert_value(target, key, value);
+    }
+
+    // .gitmodules reads best as a list with each URL's other forms
+    let mut result = json!({ "config": root });
+    if let Some(Value::Object(submodules)) = result["config"].get("submodule").cloned() {
+        let listed: Vec<Value> = submodules
+            .iter()
+            .map(|(name, settings)| {
+                let url = settings["url"].as_str();
+                json!({
+                    "name": name,
+                    "path": settings["path"],
+                    "url": url,
+                    "branch": settings["branch"],
+                    "remote": url.and_then(|url| convert_remote(url).ok())
+                })
+            })
+            .collect();
+        result["submodules"] = listed.into();
+    }
+    if let Some(Value::Object(remotes)) = result["config"].get("remote").cloned() {
+        let listed: Vec<Value> = remotes
+            .iter()
+            .map(|(name, settings)| {
+                let url = settings["url"].as_str();
+                json!({
+                    "name": name,
+                    "url": url,
+                    "fetch": settings["fetch"],
+                    "remote": url.and_then(|url| convert_remote(url).ok())
+                })
+            })
+            .collect();
+        result["remotes"] = listed.into();
+    }
+    Ok(result)
+}
+
+// ssh_config patterns: `*` and `?` wildcards, matched case-insensitively
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let (mut star, mut resume) = (None, 0);
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some(p);
+            resume = t;
+            p += 1;
+        } else if let Some(star) = star {
+            p = star + 1;
+            resume += 1;
+            t = resume;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+// A Host line's patterns against a name; any negated match excludes it
+fn host_matches(patterns: &[String], name: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) if wildcard_match(negated, name) => return false,
+            Some(_) => {}
+            None => matched |= wildcard_match(pattern, name),
+        }
+    }
+    matched
+}
+
+struct SshBlock {
+    // None for options before the first Host line, which apply to every host
+    patterns: Option<Vec<String>>,
+    is_match: bool,
+    line: usize,
+    options: Vec<(String, String, String)>,
+}
+
+fn parse_ssh_blocks(text: &str) -> (Vec<SshBlock>, Vec<String>) {
+    let mut blocks = vec![SshBlock {
+        patterns: None,
+        is_match: false,
+        line: 0,
+        options: Vec::new(),
+    }];
+    let mut notes = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // `Key value`, `Key=value` or `Key = value`
+        let (key, value) = match line.find(|c: char| c.is_whitespace() || c == '=') {
+            Some(split) => (
+                &line[..split],
+                line[split..].trim_start_matches(|c: char| c.is_whitespace() || c == '='),
+            ),
+            None => (line, ""),
+        };
+        let value = value.trim().trim_matches('"');
+        match key.to_ascii_lowercase().as_str() {
+            "host" => blocks.push(SshBlock {
+                patterns: Some(value.split_whitespace().map(str::to_string).collect()),
+                is_match: false,
+                line: index + 1,
+                options: Vec::new(),
+            }),
+            "match" => {
+                notes.push(format!(
+                    "Line {}: Match {} isn't evaluated; its options are listed but not applied",
+                    index + 1,
+                    value
+                ));
+                blocks.push(SshBlock {
+                    patterns: Some(vec![format!("Match {}", value)]),
+                    is_match: true,
+                    line: index + 1,
+                    options: Vec::new(),
+                });
+            }
+            "include" => notes.push(format!("Line {}: Include {} isn't followed", index + 1, value)),
+            lower => {
+                if let Some(block) = blocks.last_mut() {
+                    block
+                        .options
+                        .push((lower.to_string(), key.to_string(), value.to_string()));
+                }
+            }
+        }
+    }
+    (blocks, notes)
+}
+
+// The options ssh would use for `name`: blocks apply in file order and the
+// first value of an option wins, except for the ones that accumulate
+fn resolve_host(blocks: &[SshBlock], name: &str) -> Map<String, Value> {
+    let mut resolved = Map::new();
+    for block in blocks {
+        if block.is_match {
+            continue;
+        }
+        if let Some(patterns) = &block.patterns {
+            if !host_matches(patterns, name) {
+                continue;
+            }
+        }
+        for (lower, key, value) in &block.options {
+            if MULTI_VALUE_SSH_OPTIONS.contains(&lower.as_str()) {
+                let values = resolved.entry(key.clone()).or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(values) = values {
+                    values.push(value.clone().into());
+                }
+            } else if !resolved.keys().any(|existing| existing.eq_ignore_ascii_case(key)) {
+                resolved.insert(key.clone(), value.clone().into());
+            }
+        }
+    }
+    // %h in HostName stands for the name being resolved
+    if let Some((_, Value::String(host_name))) = resolved
+        .iter_mut()
+        .find(|(key, _)| key.eq_ignore_ascii_case("hostname"))
+    {
+        *host_name = host_name.replace("%h", name);
+    }
+    resolved
+}
+
+pub fn parse_ssh_config(text: &str, host: Option<&str>) -> Value {
+    let (blocks, notes) = parse_ssh_blocks(text);
+    // Concrete aliases (no wildcards or negation) are the hosts one can ssh to
+    let mut aliases: Vec<&str> = Vec::new();
+    for block in blocks.iter().filter(|block| !block.is_match) {
+        for pattern in block.patterns.iter().flatten() {
+            if !pattern.contains(['*', '?', '!']) && !aliases.contains(&pattern.as_str()) {
+                aliases.push(pattern);
+            }
+        }
+    }
+    let hosts: Vec<Value> = aliases
+        .iter()
+        .map(|alias| json!({ "host": alias, "options": resolve_host(&blocks, alias) }))
+        .collect();
+    let listed: Vec<Value> = blocks
+        .iter()
+        .filter(|block| block.patterns.is_some() || !block.options.is_empty())
+        .map(|block| {
+            json!({
+                "patterns": block.patterns,
+                "line": block.line,
+                "options": block.options.iter().map(|(_, key, value)| json!({ "option": key, "value": value })).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut result = json!({ "hosts": hosts, "blocks": listed, "notes": notes });
+    if let Some(host) = host.filter(|host| !host.trim().is_empty()) {
+        result["resolved"] = json!({ "host": host.trim(), "options": resolve_host(&blocks, host.trim()) });
+    }
+    result
+}
+
+fn config_text(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    match text.filter(|text| !text.is_empty()) {
+        Some(text) => Ok(text),
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            Ok(storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string())
+        }
+    }
+}
+
+// The SSH, HTTPS and web forms of a git remote URL, with its host, owner and
+// repository name
+#[tauri::command]
+pub fn convert_git_remote_url(url: String) -> Result<Value, String> {
+    convert_remote(&url)
+}
+
+// A git config file (.git/config, .gitmodules, ~/.gitconfig) as JSON from
+// `text` or the active document's raw content; remotes and submodules are
+// also listed with their URLs converted
+#[tauri::command]
+pub fn parse_git_config(app: AppHandle, text: Option<String>) -> Result<Value, String> {
+    parse_config(&config_text(&app, text)?)
+}
+
+// Hosts of an ssh_config (`text` or the active document) with the options
+// each resolves to; `host` also resolves one name, wildcards included
+#[tauri::command]
+pub fn parse_ssh_client_config(app: AppHandle, text: Option<String>, host: Option<String>) -> Result<Value, String> {
+    Ok(parse_ssh_config(&config_text(&app, text)?, host.as_deref()))
+}
@@ -0,0 +1,291 @@
+// Highlight tokens for a window of lines, so the frontend can color huge
+// documents without tokenizing the full text in JS. Lines are tokenized on
+// their own; constructs spanning lines (XML comments, YAML block scalars) are
+// only colored on the lines that show their delimiters.
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::autodetect;
+use crate::formatters::FormatterRegistry;
+use crate::outline;
+use crate::text_buffer;
+use crate::AppState;
+
+const MAX_LINES: usize = 2_000;
+// Very long lines (minified JSON) are only tokenized up to this many bytes
+const MAX_LINE_BYTES: usize = 16 * 1024;
+
+type Token = (&'static str, usize, usize);
+
+fn scan_quoted(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() && bytes[i] != quote {
+        if bytes[i] == b'\\' {
+            i += 1;
+        }
+        i += 1;
+    }
+    (i + 1).min(bytes.len())
+}
+
+fn scan_while(bytes: &[u8], start: usize, accept: impl Fn(u8) -> bool) -> usize {
+    let mut i = start;
+    while i < bytes.len() && accept(bytes[i]) {
+        i += 1;
+    }
+    i
+}
+
+fn is_number_byte(b: u8) -> bool {
+    b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+' | b'e' | b'E')
+}
+
+fn tokenize_json(line: &str) -> Vec<Token> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let end = match b {
+            b'"' => {
+                let end = scan_quoted(bytes, i);
+                let next = scan_while(bytes, end, |c| c == b' ' || c == b'\t');
+                let kind = if bytes.get(next) == Some(&b':') { "key" } else { "string" };
+                tokens.push((kind, i, end));
+                end
+            }
+            b'{' | b'}' | b'[' | b']' | b',' | b':' => {
+                tokens.push(("punctuation", i, i + 1));
+                i + 1
+            }
+            b'-' | b'0'..=b'9' => {
+                let end = scan_while(bytes, i, is_number_byte);
+                tokens.push(("number", i, end));
+                end
+            }
+            b'a'..=b'z' => {
+                let end = scan_while(bytes, i, |c| c.is_ascii_alphabetic());
+                if matches!(&line[i..end], "true" | "false" | "null") {
+                    tokens.push(("keyword", i, end));
+                }
+                end
+            }
+            _ => i + 1,
+        };
+        i = end.max(i + 1);
+    }
+    tokens
+}
+
+fn tokenize_xml(line: &str) -> Vec<Token> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"<!--") {
+            let end = line[i..].find("-->").map(|p| i + p + 3).unwrap_or(bytes.len());
+            tokens.push(("comment", i, end));
+            i = end;
+            continue;
+        }
+        if bytes[i] != b'<' {
+            i += 1;
+            continue;
+        }
+        // Tag: punctuation, name, then attributes up to '>'
+        let name_start = scan_while(bytes, i + 1, |c| matches!(c, b'/' | b'?' | b'!'));
+        tokens.push(("punctuation", i, name_start));
+        let name_end = scan_while(bytes, name_start, |c| !c.is_ascii_whitespace() && c != b'>' && c != b'/');
+        if name_end > name_start {
+            tokens.push(("tag", name_start, name_end));
+        }
+        i = name_end;
+        while i < bytes.len() && bytes[i] != b'>' {
+            match bytes[i] {
+                b'"' | b'\'' => {
+                    let end = scan_quoted(bytes, i);
+                    tokens.push(("string", i, end));
+                    i = end;
+                }
+                b'=' | b'/' | b'?' => {
+                    tokens.push(("punctuation", i, i + 1));
+                    i += 1;
+                }
+                c if c.is_ascii_whitespace() => i += 1,
+                _ => {
+                    let end = scan_while(bytes, i, |c| !c.is_ascii_whitespace() && !matches!(c, b'=' | b'>' | b'/'));
+                    tokens.push(("attribute", i, end));
+                    i = end;
+                }
+            }
+        }
+        if i < bytes.len() {
+            tokens.push(("punctuation", i, i + 1));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+fn tokenize_yaml(line: &str) -> Vec<Token> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = scan_while(bytes, 0, |c| c == b' ');
+    if line[i..].starts_with('#') {
+        return vec![("comment", i, bytes.len())];
+    }
+    if line[i..].starts_with("---") || line[i..].starts_with("...") {
+        return vec![("punctuation", i, i + 3)];
+    }
+    while line[i..].starts_with("- ") {
+        tokens.push(("punctuation", i, i + 1));
+        i = scan_while(bytes, i + 1, |c| c == b' ');
+    }
+    // `key:` prefix
+    if let Some(colon) = line[i..].find(": ").or_else(|| line[i..].strip_suffix(':').map(|k| k.len())) {
+        let key = &line[i..i + colon];
+        if !key.is_empty() && !key.contains('#') && !key.starts_with('"') && !key.starts_with('\'') {
+            tokens.push(("key", i, i + colon));
+            tokens.push(("punctuation", i + colon, i + colon + 1));
+            i = scan_while(bytes, i + colon + 1, |c| c == b' ');
+        }
+    }
+    if i >= bytes.len() {
+        return tokens;
+    }
+    // Scalar value with an optional trailing comment
+    let comment = line[i..].find(" #").map(|p| i + p + 1);
+    let value_end = comment.unwrap_or(bytes.len());
+    let value = line[i..value_end].trim_end();
+    let kind = match bytes[i] {
+        b'"' | b'\'' => "string",
+        b'&' | b'*' => "anchor",
+        b'|' | b'>' | b'[' | b'{' => "punctuation",
+        _ if matches!(value, "true" | "false" | "null" | "~" | "yes" | "no") => "keyword",
+        _ if value.parse::<f64>().is_ok() => "number",
+        _ => "string",
+    };
+    tokens.push((kind, i, i + value.len()));
+    if let Some(comment) = comment {
+        tokens.push(("comment", comment, bytes.len()));
+    }
+    tokens
+}
+
+const LOG_LEVELS: &[(&str, &str)] = &[
+    ("FATAL", "error"),
+    ("ERROR", "error"),
+    ("WARNING", "warning"),
+    ("WARN", "warning"),
+    ("INFO", "info"),
+    ("DEBUG", "debug"),
+    ("TRACE", "debug"),
+];
+
+fn tokenize_log(line: &str) -> Vec<Token> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let end = match b {
+            b'"' => {
+                let end = scan_quoted(bytes, i);
+                tokens.push(("string", i, end));
+                end
+            }
+            b'0'..=b'9' => {
+                // Dates, times and plain numbers all start with a digit
+                let end = scan_while(bytes, i, |c| c.is_ascii_digit() || matches!(c, b'-' | b':' | b'.' | b'T' | b'Z' | b'+' | b','));
+                let run = &line[i..end];
+                let kind = if run.contains(':') || run.matches('-').count() == 2 { "timestamp" } else { "number" };
+                tokens.push((kind, i, end));
+                end
+            }
+            c if c.is_ascii_alphabetic() => {
+                let end = scan_while(bytes, i, |c| c.is_ascii_alphabetic());
+                let word = &line[i..end];
+                if let Some((_, level)) = LOG_LEVELS.iter().find(|(name, _)| word.eq_ignore_ascii_case(name)) {
+                    tokens.push((*level, i, end));
+                }
+                end
+            }
+            _ => i + 1,
+        };
+        i = end.max(i + 1);
+    }
+    tokens
+}
+
+fn highlight_lines_blocking(
+    app: &AppHandle,
+    content_type: String,
+    start_line: usize,
+    line_count: usize,
+    format: Option<String>,
+) -> Result<serde_json::Value, String> {
+    // Only the buffer handle is cloned, so the storage lock is released before
+    // any tokenizing
+    let (content, format_type) = {
+        let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let document = storage.active();
+        let content = match content_type.as_str() {
+            "raw" => document.raw_content.clone(),
+            "formatted" => document.formatted_content.clone(),
+            _ => return Err("Invalid content type".to_string()),
+        }
+        .ok_or_else(|| "No content stored".to_string())?;
+        (content, document.format_type.clone())
+    };
+
+    let format = match format.as_deref() {
+        Some("log") | Some("logs") => "log",
+        _ => {
+            let sample_end = text_buffer::grapheme_floor(&content, autodetect::SAMPLE_BYTES);
+            let registry = app.state::<FormatterRegistry>();
+            // Anything that isn't structured gets the log tokenizer
+            outline::resolve_format(format, format_type.as_deref(), &content[..sample_end], &registry)
+                .unwrap_or("log")
+        }
+    };
+    let tokenize: fn(&str) -> Vec<Token> = match format {
+        "json" => tokenize_json,
+        "xml" => tokenize_xml,
+        "yaml" => tokenize_yaml,
+        _ => tokenize_log,
+    };
+
+    let total_lines = content.line_count();
+    let end_line = start_line.saturating_add(line_count.min(MAX_LINES)).min(total_lines);
+    let lines: Vec<serde_json::Value> = (start_line..end_line)
+        .filter_map(|index| content.line(index).map(|line| (index, line)))
+        .map(|(index, line)| {
+            let visible = &line[..text_buffer::grapheme_floor(line, MAX_LINE_BYTES)];
+            let tokens: Vec<serde_json::Value> = tokenize(visible)
+                .into_iter()
+                .map(|(kind, start, end)| serde_json::json!({ "kind": kind, "start": start, "end": end }))
+                .collect();
+            serde_json::json!({ "line": index, "tokens": tokens })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "format": format,
+        "start_line": start_line,
+        "total_lines": total_lines,
+        "lines": lines,
+        "next_line": end_line
+    }))
+}
+
+// Token offsets are byte ranges within each line, as returned by get_content_lines
+#[tauri::command]
+pub async fn get_highlight_tokens(
+    app: AppHandle,
+    content_type: String,
+    start_line: usize,
+    line_count: usize,
+    format: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("get_highlight_tokens", move || highlight_lines_blocking(&app, content_type, start_line, line_count, format)).await
+}
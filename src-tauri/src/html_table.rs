@@ -0,0 +1,501 @@
+// Renders a JSON array of objects, or simple XML with repeated elements, as
+// an HTML <table> for pasting into wikis and emails. Columns come in the
+// order they first appear (JSON keys in document order) unless the caller
+// lists them. Nested objects become dotted columns (`address.city`) and
+// every cell is escaped here, so the result can be pasted as-is. Borders and
+// padding are inline styles because mail clients drop <style> blocks.
+use quick_xml::events::{BytesStart, Event};
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::indent;
+use crate::json_tree::Node;
+use crate::undo::Slot;
+use crate::AppState;
+
+const CELL_STYLE: &str = "border: 1px solid #d0d7de; padding: 4px 8px;";
+const TABLE_STYLE: &str = "border-collapse: collapse;";
+// Guard against maliciously deep XML
+const MAX_XML_DEPTH: usize = 64;
+
+struct Cell {
+    text: String,
+    numeric: bool,
+}
+
+type Row = Vec<(String, Cell)>;
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// Nested values written back as compact JSON, keys in document order
+fn compact(node: &Node, out: &mut String) {
+    match node {
+        Node::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                compact(item, out);
+            }
+            out.push(']');
+        }
+        Node::Object(entries) => {
+            out.push('{');
+            for (index, (key, value)) in entries.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::from(key.as_str()).to_string());
+                out.push(':');
+                compact(value, out);
+            }
+            out.push('}');
+        }
+        Node::String(text) => out.push_str(&serde_json::Value::from(text.as_str()).to_string()),
+        scalar => out.push_str(&scalar.scalar_text().unwrap_or_default()),
+    }
+}
+
+fn json_cell(node: &Node, null_text: &str) -> Cell {
+    let text = match node {
+        Node::Null => null_text.to_string(),
+        Node::String(text) => text.clone(),
+        // A list of scalars reads better as "a, b, c" than as JSON
+        Node::Array(items)
+            if items
+                .iter()
+                .all(|item| !matches!(item, Node::Array(_) | Node::Object(_))) =>
+        {
+            items
+                .iter()
+                .map(|item| match item {
+                    Node::Null => null_text.to_string(),
+                    scalar => scalar.scalar_text().unwrap_or_default(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+        Node::Array(_) | Node::Object(_) => {
+            let mut out = String::new();
+            compact(node, &mut out);
+            out
+        }
+        scalar => scalar.scalar_text().unwrap_or_default(),
+    };
+    Cell {
+        text,
+        numeric: matches!(node, Node::Number(_)),
+    }
+}
+
+fn json_row(prefix: &str, entries: &[(String, Node)], flatten: bool, null_text: &str, row: &mut Row) {
+    for (key, value) in entries {
+        let column = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Node::Object(nested) if flatten && !nested.is_empty() => json_row(&column, nested, flatten, null_text, row),
+            value => row.push((column, json_cell(value, null_text))),
+        }
+    }
+}
+
+// Rows from a JSON array (or an object holding exactly one array, as API
+// responses often do), with the key the rows came from
+fn json_rows(text: &str, flatten: bool, null_text: &str) -> Result<(Vec<Row>, Option<String>), String> {
+    let tree: Node = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let (items, source) = match &tree {
+        Node::Array(items) => (items.as_slice(), None),
+        Node::Object(entries) => {
+            let arrays: Vec<&(String, Node)> = entries
+                .iter()
+                .filter(|(_, value)| matches!(value, Node::Array(_)))
+                .collect();
+            match arrays.as_slice() {
+                [(key, Node::Array(items))] => (items.as_slice(), Some(key.clone())),
+                _ => (std::slice::from_ref(&tree), None),
+            }
+        }
+        _ => return Err("Expected a JSON array of objects".to_string()),
+    };
+    let rows = items
+        .iter()
+        .map(|item| {
+            let mut row = Row::new();
+            match item {
+                Node::Object(entries) => json_row("", entries, flatten, null_text, &mut row),
+                // Scalars (or lists) in the array become a single "value" column
+                other => row.push(("value".to_string(), json_cell(other, null_text))),
+            }
+            row
+        })
+        .collect();
+    Ok((rows, source))
+}
+
+#[derive(Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+}
+
+fn element_from(start: &BytesStart) -> Result<Element, String> {
+    let mut element = Element {
+        name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+        ..Element::default()
+    };
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| format!("Invalid XML attribute: {}", e))?;
+        let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+        // Namespace declarations aren't data
+        if attribute.key.as_ref().starts_with(b"xmlns") {
+            continue;
+        }
+        let value = attribute.unescape_value().map_err(|e| e.to_string())?.into_owned();
+        element.attributes.push((key, value));
+    }
+    Ok(element)
+}
+
+fn parse_xml(text: &str) -> Result<Element, String> {
+    let mut reader = quick_xml::Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid XML at byte {}: {}", reader.error_position(), e))?;
+        let finished = match event {
+            Event::Start(start) => {
+                if stack.len() >= MAX_XML_DEPTH {
+                    return Err("XML nesting too deep".to_string());
+                }
+                stack.push(element_from(&start)?);
+                None
+            }
+            Event::Empty(start) => Some(element_from(&start)?),
+            Event::Text(content) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&content.unescape().map_err(|e| e.to_string())?);
+                }
+                None
+            }
+            Event::CData(content) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&String::from_utf8_lossy(&content));
+                }
+                None
+            }
+            Event::End(_) => stack.pop(),
+            Event::Eof => break,
+            _ => None,
+        };
+        if let Some(element) = finished {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None if root.is_none() => root = Some(element),
+                None => return Err("XML has more than one root element".to_string()),
+            }
+        }
+    }
+    root.ok_or_else(|| "No XML element found".to_string())
+}
+
+fn xml_row(prefix: &str, element: &Element, row: &mut Row) {
+    let column = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", prefix, name)
+        }
+    };
+    for (key, value) in &element.attributes {
+        // `@id` when a child element is also called `id`
+        let key = if element.children.iter().any(|child| child.name == *key) {
+            format!("@{}", key)
+        } else {
+            key.clone()
+        };
+        push_cell(row, column(&key), value.clone());
+    }
+    for child in &element.children {
+        if child.children.is_empty() && child.attributes.is_empty() {
+            push_cell(row, column(&child.name), child.text.clone());
+        } else {
+            xml_row(&column(&child.name), child, row);
+        }
+    }
+}
+
+// Repeated child elements share a column, their values joined
+fn push_cell(row: &mut Row, column: String, text: String) {
+    match row.iter_mut().find(|(name, _)| *name == column) {
+        Some((_, cell)) => {
+            cell.text.push_str(", ");
+            cell.text.push_str(&text);
+            cell.numeric = false;
+        }
+        None => {
+            let numeric = !text.is_empty() && text.trim().parse::<f64>().is_ok_and(f64::is_finite);
+            row.push((column, Cell { text, numeric }));
+        }
+    }
+}
+
+// Rows are the children of the root, stepping down through wrappers that hold
+// a single element (<response><items><item/>...</items></response>)
+fn xml_rows(text: &str) -> Result<(Vec<Row>, Option<String>), String> {
+    let mut container = parse_xml(text)?;
+    let mut path = vec![container.name.clone()];
+    while container.children.len() == 1 && !container.children[0].children.is_empty() {
+        let only = container.children.remove(0);
+        // A lone record with leaf children is the row itself, not a wrapper
+        if only.children.iter().all(|child| child.children.is_empty()) {
+            container.children.push(only);
+            break;
+        }
+        container = only;
+        path.push(container.name.clone());
+    }
+    let rows = container
+        .children
+        .iter()
+        .map(|element| {
+            let mut row = Row::new();
+            if element.children.is_empty() && element.attributes.is_empty() {
+                push_cell(&mut row, element.name.clone(), element.text.clone());
+            } else {
+                xml_row("", element, &mut row);
+                if !element.text.trim().is_empty() {
+                    push_cell(&mut row, "text".to_string(), element.text.clone());
+                }
+            }
+            row
+        })
+        .collect();
+    Ok((rows, Some(path.join("/"))))
+}
+
+// Cell text escaped, with line breaks kept as <br>
+fn cell_html(text: &str) -> String {
+    escape_html(text).replace("\r\n", "\n").replace('\n', "<br>")
+}
+
+struct TableOptions {
+    columns: Option<Vec<String>>,
+    header: bool,
+    styled: bool,
+    caption: Option<String>,
+}
+
+// The table markup, with the columns used and any requested columns that no
+// row has
+fn render(rows: &[Row], options: &TableOptions) -> (String, Vec<String>, Vec<String>) {
+    let mut columns: Vec<String> = Vec::new();
+    for (name, _) in rows.iter().flatten() {
+        if !columns.contains(name) {
+            columns.push(name.clone());
+        }
+    }
+    let mut missing = Vec::new();
+    if let Some(requested) = &options.columns {
+        for column in requested {
+            if !columns.contains(column) {
+                missing.push(column.clone());
+            }
+        }
+        columns = requested.clone();
+    }
+    // Right-aligned when every filled cell in the column is a number
+    let numeric: Vec<bool> = columns
+        .iter()
+        .map(|column| {
+            let mut cells = rows
+                .iter()
+                .filter_map(|row| row.iter().find(|(name, _)| name == column).map(|(_, cell)| cell))
+                .filter(|cell| !cell.text.is_empty())
+                .peekable();
+            cells.peek().is_some() && cells.all(|cell| cell.numeric)
+        })
+        .collect();
+    let style = |align: &str| {
+        if options.styled {
+            format!(" style=\"{} text-align: {};\"", CELL_STYLE, align)
+        } else {
+            String::new()
+        }
+    };
+
+    let mut html = String::new();
+    if options.styled {
+        html.push_str(&format!("<table style=\"{}\">\n", TABLE_STYLE));
+    } else {
+        html.push_str("<table>\n");
+    }
+    if let Some(caption) = options.caption.as_deref().filter(|caption| !caption.trim().is_empty()) {
+        html.push_str(&format!("{}<caption>{}</caption>\n", indent(1), cell_html(caption)));
+    }
+    if options.header {
+        html.push_str(&format!("{}<thead>\n{}<tr>\n", indent(1), indent(2)));
+        for (column, numeric) in columns.iter().zip(&numeric) {
+            let align = if *numeric { "right" } else { "left" };
+            html.push_str(&format!(
+                "{}<th{}>{}</th>\n",
+                indent(3),
+                style(align),
+                cell_html(column)
+            ));
+        }
+        html.push_str(&format!("{}</tr>\n{}</thead>\n", indent(2), indent(1)));
+    }
+    html.push_str(&format!("{}<tbody>\n", indent(1)));
+    for row in rows {
+        html.push_str(&format!("{}<tr>\n", indent(2)));
+        for (column, numeric) in columns.iter().zip(&numeric) {
+            let text = row
+                .iter()
+                .find(|(name, _)| name == column)
+                .map_or("", |(_, cell)| cell.text.as_str());
+            let align = if *numeric { "right" } else { "left" };
+            html.push_str(&format!("{}<td{}>{}</td>\n", indent(3), style(align), cell_html(text)));
+        }
+        html.push_str(&format!("{}</tr>\n", indent(2)));
+    }
+    html.push_str(&format!("{}</tbody>\n</table>\n", indent(1)));
+    (html, columns, missing)
+}
+
+fn is_xml(text: &str) -> bool {
+    text.trim_start().trim_start_matches('\u{feff}').starts_with('<')
+}
+
+// `input` is "json", "xml" or None to tell from the first character
+fn html_table(
+    text: &str,
+    input: Option<&str>,
+    options: &TableOptions,
+    flatten: bool,
+    null_text: &str,
+) -> Result<serde_json::Value, String> {
+    let xml = match input {
+        Some("json") => false,
+        Some("xml") => true,
+        Some(other) => return Err(format!("Unknown input {}; use json or xml", other)),
+        None => is_xml(text),
+    };
+    let (rows, source) = if xml {
+        xml_rows(text)?
+    } else {
+        json_rows(text, flatten, null_text)?
+    };
+    if rows.is_empty() {
+        return Err("No rows to put in a table".to_string());
+    }
+    let (html, columns, missing) = render(&rows, options);
+    let mut warnings = Vec::new();
+    if !missing.is_empty() {
+        warnings.push(format!("No row has column {}; it is left empty", missing.join(", ")));
+    }
+    Ok(serde_json::json!({
+        "html": html,
+        "input": if xml { "xml" } else { "json" },
+        "source": source,
+        "rows": rows.len(),
+        "columns": columns,
+        "warnings": warnings
+    }))
+}
+
+// Renders `text` (or the active document) as an HTML table. `columns` picks
+// and orders the columns; `flatten` (on by default) splits nested objects
+// into dotted columns instead of showing them as JSON; `styled` (on by
+// default) adds inline borders and padding. With `apply` the document is
+// replaced by the table.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn render_html_table(
+    app: AppHandle,
+    text: Option<String>,
+    input: Option<String>,
+    columns: Option<Vec<String>>,
+    header: Option<bool>,
+    flatten: Option<bool>,
+    styled: Option<bool>,
+    caption: Option<String>,
+    null_text: Option<String>,
+    apply: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("render_html_table", move || {
+        let apply = apply.unwrap_or(false);
+        let from_document = text.as_deref().is_none_or(str::is_empty);
+        if apply && !from_document {
+            return Err("Only the stored content can be replaced by the table".to_string());
+        }
+        let content = match text.filter(|text| !text.is_empty()) {
+            Some(text) => text,
+            None => {
+                let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                storage
+                    .active()
+                    .raw_content
+                    .as_deref()
+                    .ok_or_else(|| "No content stored".to_string())?
+                    .to_string()
+            }
+        };
+        let columns = columns
+            .map(|columns| {
+                columns
+                    .into_iter()
+                    .map(|column| column.trim().to_string())
+                    .filter(|column| !column.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|columns| !columns.is_empty());
+        let options = TableOptions {
+            columns,
+            header: header.unwrap_or(true),
+            styled: styled.unwrap_or(true),
+            caption,
+        };
+        let input = input
+            .map(|input| input.trim().to_ascii_lowercase())
+            .filter(|input| !input.is_empty());
+
+        let mut result = html_table(
+            &content,
+            input.as_deref(),
+            &options,
+            flatten.unwrap_or(true),
+            null_text.as_deref().unwrap_or(""),
+        )?;
+        if apply {
+            let html = result["html"].as_str().unwrap_or_default().to_string();
+            let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            let document = storage.active_mut();
+            document.edit(Slot::Raw, html);
+            document.formatted_content = None;
+        }
+        result["applied"] = serde_json::json!(apply);
+        Ok(result)
+    })
+    .await
+}
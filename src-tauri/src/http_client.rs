@@ -0,0 +1,546 @@
+// A small HTTP client for API calls, with named environments and a request
+// history. An environment holds a base URL, variables and default headers;
+// `{{variable}}` placeholders in the URL, headers and body are resolved here
+// in the backend, so switching dev/staging/prod is a matter of picking another
+// environment. Environments live in `http_environments.json` and the last
+// requests in `http_history.json`. History keeps requests as written, before
+// substitution, so secrets held in variables never end up in it.
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_data;
+use crate::http_collections::{check_assertions, Assertion};
+use crate::AppState;
+
+const ENVIRONMENTS_FILE: &str = "http_environments.json";
+const HISTORY_FILE: &str = "http_history.json";
+const MAX_HISTORY: usize = 200;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_RESPONSE_BYTES: u64 = 256 * 1024 * 1024;
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+// Header values kept out of the history unless they only reference variables
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "cookie", "proxy-authorization", "x-api-key"];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpEnvironment {
+    pub name: String,
+    #[serde(default)]
+    pub base_url: String,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct HistoryEntry {
+    id: u64,
+    sent_at: String,
+    environment: Option<String>,
+    method: String,
+    // As written, with placeholders
+    url: String,
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    // After substitution and joining with the base URL
+    resolved_url: String,
+    status: Option<u16>,
+    elapsed_ms: u64,
+    response_bytes: usize,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct History {
+    next_id: u64,
+    entries: Vec<HistoryEntry>,
+}
+
+pub struct HttpEnvironments(Mutex<Vec<HttpEnvironment>>);
+
+pub struct HttpHistory(Mutex<History>);
+
+// Errors name the file itself, as environments and history share the helpers
+fn save<T: Serialize + ?Sized>(app: &AppHandle, file: &str, value: &T) -> Result<(), String> {
+    app_data::save(app, file, value, file)
+}
+
+fn read<T: DeserializeOwned + Default>(app: &AppHandle, file: &str) -> T {
+    app_data::load(app, file).unwrap_or_default()
+}
+
+// Called from setup; missing or unreadable files start empty
+pub fn load(app: &AppHandle) {
+    app.manage(HttpEnvironments(Mutex::new(read(app, ENVIRONMENTS_FILE))));
+    app.manage(HttpHistory(Mutex::new(read(app, HISTORY_FILE))));
+}
+
+// Replaces every `{{name}}` with the variable's value; `what` names the field
+// for the error when a variable isn't defined
+fn substitute(text: &str, variables: &BTreeMap<String, String>, what: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find("}}")
+            .ok_or_else(|| format!("Unclosed {{{{ in {}", what))?;
+        let name = rest[open + 2..open + close].trim();
+        let value = variables
+            .get(name)
+            .ok_or_else(|| format!("Variable {{{{{}}}}} in {} isn't defined", name, what))?;
+        out.push_str(value);
+        rest = &rest[open + close + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+// Relative URLs ("/users/1", "users/1") are joined with the base URL
+fn resolve_url(url: &str, base_url: Option<&str>) -> String {
+    let url = url.trim();
+    match base_url.map(str::trim).filter(|base| !base.is_empty()) {
+        Some(base) if !url.contains("://") => {
+            format!("{}/{}", base.trim_end_matches('/'), url.trim_start_matches('/'))
+        }
+        _ => url.to_string(),
+    }
+}
+
+fn history_headers(headers: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let sensitive = SENSITIVE_HEADERS.contains(&name.to_ascii_lowercase().as_str());
+            let value = if sensitive && !value.contains("{{") {
+                crate::clipboard_history::mask(value)
+            } else {
+                value.clone()
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+fn record(app: &AppHandle, mut entry: HistoryEntry) -> Result<u64, String> {
+    let history = app.state::<HttpHistory>();
+    let mut history = history.0.lock().map_err(|e| e.to_string())?;
+    history.next_id += 1;
+    entry.id = history.next_id;
+    history.entries.insert(0, entry);
+    history.entries.truncate(MAX_HISTORY);
+    save(app, HISTORY_FILE, &*history)?;
+    Ok(history.next_id)
+}
+
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+// A request as written, before `{{variable}}` substitution
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+// One sent request and what came back
+pub struct Exchange {
+    pub history_id: u64,
+    pub method: String,
+    pub resolved_url: String,
+    pub elapsed_ms: u64,
+    pub response: Result<Response, String>,
+}
+
+pub(crate) fn send(method: &str, url: &str, headers: &BTreeMap<String, String>, body: Option<String>) -> Result<Response, String> {
+    let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|e| format!("Invalid method: {}", e))?;
+    let mut request = crate::fetch::http_client()?
+        .request(method, crate::fetch::parse_http_url(url)?)
+        .timeout(REQUEST_TIMEOUT);
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+    let response = request.send().map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status().as_u16();
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let mut body = Vec::new();
+    response
+        .take(MAX_RESPONSE_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Failed to read the response: {}", e))?;
+    if body.len() as u64 > MAX_RESPONSE_BYTES {
+        return Err(format!("Response is over the {} byte limit", MAX_RESPONSE_BYTES));
+    }
+    Ok(Response {
+        status,
+        headers,
+        content_type,
+        body,
+    })
+}
+
+// Resolves `request` against `environment` and sends it, adding it to the
+// history. Errors before sending (an unknown variable, a bad method) are
+// returned as Err; a failed request is an Exchange holding the error.
+pub fn perform(app: &AppHandle, request: &HttpRequest, environment: Option<&str>) -> Result<Exchange, String> {
+    let method = request.method.trim().to_ascii_uppercase();
+    if !METHODS.contains(&method.as_str()) {
+        return Err(format!("Method must be one of {}", METHODS.join(", ")));
+    }
+    let environment = match environment {
+        Some(name) => Some(
+            app.state::<HttpEnvironments>()
+                .0
+                .lock()
+                .map_err(|e| e.to_string())?
+                .iter()
+                .find(|environment| environment.name == name)
+                .cloned()
+                .ok_or_else(|| format!("Environment {} not found", name))?,
+        ),
+        None => None,
+    };
+
+    let empty = BTreeMap::new();
+    let variables = environment
+        .as_ref()
+        .map(|environment| &environment.variables)
+        .unwrap_or(&empty);
+    let base_url = environment
+        .as_ref()
+        .map(|environment| substitute(&environment.base_url, variables, "the base URL"))
+        .transpose()?;
+    let resolved_url = resolve_url(&substitute(&request.url, variables, "the URL")?, base_url.as_deref());
+    // Request headers override the environment's defaults
+    let mut resolved_headers = BTreeMap::new();
+    let defaults = environment
+        .as_ref()
+        .map(|environment| &environment.headers)
+        .unwrap_or(&empty);
+    for (name, value) in defaults.iter().chain(request.headers.iter()) {
+        let what = format!("header {}", name);
+        resolved_headers.insert(name.clone(), substitute(value, variables, &what)?);
+    }
+    let resolved_body = request
+        .body
+        .as_deref()
+        .filter(|body| !body.is_empty())
+        .map(|body| substitute(body, variables, "the body"))
+        .transpose()?;
+
+    let started = Instant::now();
+    let response = send(&method, &resolved_url, &resolved_headers, resolved_body);
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    let history_id = record(
+        app,
+        HistoryEntry {
+            id: 0,
+            sent_at: chrono::Utc::now().to_rfc3339(),
+            environment: environment.as_ref().map(|environment| environment.name.clone()),
+            method: method.clone(),
+            url: request.url.clone(),
+            headers: history_headers(&request.headers),
+            body: request.body.clone(),
+            resolved_url: resolved_url.clone(),
+            status: response.as_ref().ok().map(|response| response.status),
+            elapsed_ms,
+            response_bytes: response
+                .as_ref()
+                .map(|response| response.body.len())
+                .unwrap_or_default(),
+            error: response.as_ref().err().cloned(),
+        },
+    )?;
+    Ok(Exchange {
+        history_id,
+        method,
+        resolved_url,
+        elapsed_ms,
+        response,
+    })
+}
+
+fn send_http_request_blocking(
+    app: &AppHandle,
+    request: HttpRequest,
+    environment: Option<String>,
+    assertions: Vec<Assertion>,
+    into_document: bool,
+) -> Result<serde_json::Value, String> {
+    let exchange = perform(app, &request, environment.as_deref())?;
+    let response = exchange.response.as_ref().map_err(|e| e.clone())?;
+
+    let (text, encoding, _) = crate::charset::decode_to_utf8(&response.body, None)?;
+    let mut reply = serde_json::json!({
+        "history_id": exchange.history_id,
+        "url": exchange.resolved_url,
+        "status": response.status,
+        "headers": response.headers,
+        "content_type": response.content_type,
+        "elapsed_ms": exchange.elapsed_ms,
+        "bytes": response.body.len(),
+        "encoding": encoding
+    });
+    if !assertions.is_empty() {
+        let results = check_assertions(&assertions, &exchange);
+        reply["passed"] = results.iter().all(|result| result["passed"] == true).into();
+        reply["assertions"] = results.into();
+    }
+    if into_document {
+        let json = response
+            .content_type
+            .as_deref()
+            .is_some_and(|content_type| content_type.contains("json"));
+        let document_id = {
+            let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            let id = storage.insert_document(format!("{} {}", exchange.method, exchange.resolved_url));
+            let document = storage.active_mut();
+            document.raw_content = Some(text.into());
+            document.format_type = json.then(|| "json".to_string());
+            id
+        };
+        crate::tray::refresh(app);
+        reply["document_id"] = document_id.into();
+    } else {
+        reply["body"] = text.into();
+    }
+    Ok(reply)
+}
+
+// Sends a request, optionally within `environment`: its variables fill in
+// `{{name}}` placeholders in `url`, `headers` and `body`, a relative `url` is
+// joined with its base URL, and its default headers are sent unless `headers`
+// overrides them. Every request, failed ones included, is added to the history.
+// `assertions` are checked against the response and reported per assertion.
+// With `into_document` the response body opens as a new document instead of
+// being returned.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn send_http_request(
+    app: AppHandle,
+    method: String,
+    url: String,
+    headers: Option<BTreeMap<String, String>>,
+    body: Option<String>,
+    environment: Option<String>,
+    assertions: Option<Vec<Assertion>>,
+    into_document: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("send_http_request", move || {
+        send_http_request_blocking(
+            &app,
+            HttpRequest {
+                method,
+                url,
+                headers: headers.unwrap_or_default(),
+                body,
+            },
+            environment.filter(|name| !name.trim().is_empty()),
+            assertions.unwrap_or_default(),
+            into_document.unwrap_or(false),
+        )
+    })
+    .await
+}
+
+// Creates environment `name` or replaces the one with that name
+#[tauri::command]
+pub fn save_http_environment(
+    app: AppHandle,
+    name: String,
+    base_url: Option<String>,
+    variables: Option<BTreeMap<String, String>>,
+    headers: Option<BTreeMap<String, String>>,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("An environment needs a name".to_string());
+    }
+    let environments = app.state::<HttpEnvironments>();
+    let mut environments = environments.0.lock().map_err(|e| e.to_string())?;
+    environments.retain(|environment| environment.name != name);
+    environments.push(HttpEnvironment {
+        name,
+        base_url: base_url.unwrap_or_default().trim().to_string(),
+        variables: variables.unwrap_or_default(),
+        headers: headers.unwrap_or_default(),
+    });
+    environments.sort_by_key(|environment| environment.name.to_lowercase());
+    save(&app, ENVIRONMENTS_FILE, &*environments)
+}
+
+fn secret_variable(value: &str) -> bool {
+    crate::clipboard_history::detect_secret(value).is_some()
+}
+
+pub(crate) fn secret_header(name: &str, value: &str) -> bool {
+    SENSITIVE_HEADERS.contains(&name.to_ascii_lowercase().as_str()) && !value.contains("{{")
+}
+
+// The environments, for config bundles. Without `include_secrets`, variables
+// that look like credentials and sensitive headers with literal values are
+// left out; returns how many values were withheld.
+pub(crate) fn shareable_environments(
+    app: &AppHandle,
+    include_secrets: bool,
+) -> Result<(Vec<HttpEnvironment>, usize), String> {
+    let mut environments = app
+        .state::<HttpEnvironments>()
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let mut withheld = 0;
+    if !include_secrets {
+        for environment in &mut environments {
+            let before = environment.variables.len() + environment.headers.len();
+            environment.variables.retain(|_, value| !secret_variable(value));
+            environment.headers.retain(|name, value| !secret_header(name, value));
+            withheld += before - environment.variables.len() - environment.headers.len();
+        }
+    }
+    Ok((environments, withheld))
+}
+
+// Imports environments from a config bundle. Merging folds each one into the
+// local environment of the same name, so variables and headers kept only
+// locally survive; `overwrite` replaces the list, carrying over the local
+// secrets of environments that stay when `keep_secrets` is set.
+pub(crate) fn import_environments(
+    app: &AppHandle,
+    imported: Vec<HttpEnvironment>,
+    overwrite: bool,
+    keep_secrets: bool,
+) -> Result<serde_json::Value, String> {
+    let environments = app.state::<HttpEnvironments>();
+    let mut environments = environments.0.lock().map_err(|e| e.to_string())?;
+    let previous = if overwrite {
+        std::mem::take(&mut *environments)
+    } else {
+        Vec::new()
+    };
+    let (mut added, mut merged) = (0, 0);
+    for mut environment in imported {
+        environment.name = environment.name.trim().to_string();
+        if environment.name.is_empty() {
+            continue;
+        }
+        match environments.iter_mut().find(|own| own.name == environment.name) {
+            Some(own) => {
+                if !environment.base_url.trim().is_empty() {
+                    own.base_url = environment.base_url.trim().to_string();
+                }
+                own.variables.extend(environment.variables);
+                own.headers.extend(environment.headers);
+                merged += 1;
+            }
+            None => {
+                environment.base_url = environment.base_url.trim().to_string();
+                let local = previous.iter().find(|own| own.name == environment.name);
+                if let Some(local) = local.filter(|_| keep_secrets) {
+                    for (name, value) in local.variables.iter().filter(|(_, value)| secret_variable(value)) {
+                        environment
+                            .variables
+                            .entry(name.clone())
+                            .or_insert_with(|| value.clone());
+                    }
+                    for (name, value) in local.headers.iter().filter(|(name, value)| secret_header(name, value)) {
+                        environment.headers.entry(name.clone()).or_insert_with(|| value.clone());
+                    }
+                }
+                environments.push(environment);
+                added += 1;
+            }
+        }
+    }
+    let removed = previous
+        .iter()
+        .filter(|own| !environments.iter().any(|environment| environment.name == own.name))
+        .count();
+    environments.sort_by_key(|environment| environment.name.to_lowercase());
+    save(app, ENVIRONMENTS_FILE, &*environments)?;
+    Ok(serde_json::json!({ "added": added, "merged": merged, "removed": removed }))
+}
+
+#[tauri::command]
+pub fn list_http_environments(environments: State<HttpEnvironments>) -> Result<Vec<HttpEnvironment>, String> {
+    Ok(environments.0.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub fn delete_http_environment(app: AppHandle, name: String) -> Result<bool, String> {
+    let environments = app.state::<HttpEnvironments>();
+    let mut environments = environments.0.lock().map_err(|e| e.to_string())?;
+    let before = environments.len();
+    environments.retain(|environment| environment.name != name);
+    if environments.len() == before {
+        return Ok(false);
+    }
+    save(&app, ENVIRONMENTS_FILE, &*environments)?;
+    Ok(true)
+}
+
+// Most recent first; `query` matches the URL (as written or resolved) or method
+#[tauri::command]
+pub fn list_http_history(
+    history: State<HttpHistory>,
+    query: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let query = query.unwrap_or_default().to_lowercase();
+    let history = history.0.lock().map_err(|e| e.to_string())?;
+    Ok(history
+        .entries
+        .iter()
+        .filter(|entry| {
+            query.is_empty()
+                || entry.url.to_lowercase().contains(&query)
+                || entry.resolved_url.to_lowercase().contains(&query)
+                || entry.method.eq_ignore_ascii_case(&query)
+        })
+        .take(limit.unwrap_or(MAX_HISTORY))
+        .map(|entry| serde_json::to_value(entry).unwrap_or_default())
+        .collect())
+}
+
+// Removes entry `id`, or the whole history when `id` is omitted
+#[tauri::command]
+pub fn clear_http_history(app: AppHandle, id: Option<u64>) -> Result<usize, String> {
+    let history = app.state::<HttpHistory>();
+    let mut history = history.0.lock().map_err(|e| e.to_string())?;
+    let before = history.entries.len();
+    match id {
+        Some(id) => history.entries.retain(|entry| entry.id != id),
+        None => history.entries.clear(),
+    }
+    let removed = before - history.entries.len();
+    if removed > 0 {
+        save(&app, HISTORY_FILE, &*history)?;
+    }
+    Ok(removed)
+}
@@ -0,0 +1,397 @@
+// Saved collections of HTTP requests with simple assertions on each response
+// (status code, a JSON path's value or presence, response time), run one after
+// another as smoke tests. Collections live in `http_collections.json`; requests
+// are stored as written, so the same collection runs against any environment.
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::http_client::{secret_header, Exchange, HttpRequest};
+use crate::jobs::{spawn_job, CancelToken};
+use crate::json_path;
+
+pub const COLLECTION_PROGRESS_EVENT: &str = "collection://progress";
+const COLLECTIONS_FILE: &str = "http_collections.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Assertion {
+    Status { equals: u16 },
+    JsonPathEquals { path: String, value: serde_json::Value },
+    JsonPathExists { path: String },
+    MaxTime { ms: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CollectionRequest {
+    pub name: String,
+    #[serde(flatten)]
+    pub request: HttpRequest,
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpCollection {
+    pub name: String,
+    pub requests: Vec<CollectionRequest>,
+}
+
+pub struct HttpCollections(Mutex<Vec<HttpCollection>>);
+
+pub fn load(app: &AppHandle) {
+    let collections: Vec<HttpCollection> = crate::app_data::load(app, COLLECTIONS_FILE).unwrap_or_default();
+    app.manage(HttpCollections(Mutex::new(collections)));
+}
+
+fn describe(assertion: &Assertion) -> String {
+    match assertion {
+        Assertion::Status { equals } => format!("status == {}", equals),
+        Assertion::JsonPathEquals { path, value } => format!("{} == {}", path, value),
+        Assertion::JsonPathExists { path } => format!("{} exists", path),
+        Assertion::MaxTime { ms } => format!("time < {} ms", ms),
+    }
+}
+
+fn check(assertion: &Assertion, exchange: &Exchange, json: Option<&serde_json::Value>) -> (bool, serde_json::Value) {
+    let Ok(response) = &exchange.response else {
+        return (false, serde_json::Value::Null);
+    };
+    let lookup = |path: &str| {
+        let segments = json_path::parse_path(path).ok()?;
+        json_path::get(json?, &segments)
+    };
+    match assertion {
+        Assertion::Status { equals } => (response.status == *equals, response.status.into()),
+        Assertion::JsonPathEquals { path, value } => match lookup(path) {
+            Some(actual) => (actual == value, actual.clone()),
+            None => (false, serde_json::Value::Null),
+        },
+        Assertion::JsonPathExists { path } => (lookup(path).is_some(), lookup(path).cloned().into()),
+        Assertion::MaxTime { ms } => (exchange.elapsed_ms < *ms, exchange.elapsed_ms.into()),
+    }
+}
+
+// One `{assertion, passed, actual}` entry per assertion, plus an `error` when
+// the request itself failed or a JSON path assertion met a non-JSON body
+pub fn check_assertions(assertions: &[Assertion], exchange: &Exchange) -> Vec<serde_json::Value> {
+    let json = exchange
+        .response
+        .as_ref()
+        .ok()
+        .and_then(|response| serde_json::from_slice::<serde_json::Value>(&response.body).ok());
+    assertions
+        .iter()
+        .map(|assertion| {
+            let (passed, actual) = check(assertion, exchange, json.as_ref());
+            let error = match (&exchange.response, assertion) {
+                (Err(error), _) => Some(error.clone()),
+                (Ok(_), Assertion::JsonPathEquals { path, .. } | Assertion::JsonPathExists { path }) => {
+                    match json_path::parse_path(path) {
+                        Err(e) => Some(format!("Invalid path: {}", e)),
+                        Ok(_) if json.is_none() => Some("The response isn't JSON".to_string()),
+                        Ok(_) => None,
+                    }
+                }
+                _ => None,
+            };
+            serde_json::json!({
+                "assertion": describe(assertion),
+                "passed": passed,
+                "actual": actual,
+                "error": error
+            })
+        })
+        .collect()
+}
+
+fn run_collection_blocking(
+    app: &AppHandle,
+    token: &CancelToken,
+    name: String,
+    environment: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let collection = app
+        .state::<HttpCollections>()
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .find(|collection| collection.name == name)
+        .cloned()
+        .ok_or_else(|| format!("Collection {} not found", name))?;
+
+    let total = collection.requests.len();
+    let mut results = Vec::with_capacity(total);
+    let (mut passed_requests, mut assertions_passed, mut assertions_failed) = (0, 0, 0);
+    for (index, saved) in collection.requests.iter().enumerate() {
+        token.check()?;
+        let mut result = serde_json::json!({ "name": saved.name, "method": saved.request.method });
+        // A request that can't be sent (an undefined variable, say) fails every
+        // assertion but doesn't stop the run
+        let passed = match crate::http_client::perform(app, &saved.request, environment.as_deref()) {
+            Ok(exchange) => {
+                let checks = check_assertions(&saved.assertions, &exchange);
+                let passed_count = checks.iter().filter(|check| check["passed"] == true).count();
+                assertions_passed += passed_count;
+                assertions_failed += checks.len() - passed_count;
+                result["url"] = exchange.resolved_url.into();
+                result["elapsed_ms"] = exchange.elapsed_ms.into();
+                result["status"] = exchange.response.as_ref().ok().map(|response| response.status).into();
+                result["error"] = exchange.response.as_ref().err().cloned().into();
+                result["assertions"] = checks.into();
+                exchange.response.is_ok() && passed_count == saved.assertions.len()
+            }
+            Err(error) => {
+                assertions_failed += saved.assertions.len();
+                result["error"] = error.into();
+                false
+            }
+        };
+        if passed {
+            passed_requests += 1;
+        }
+        result["passed"] = passed.into();
+        let _ = app.emit(
+            COLLECTION_PROGRESS_EVENT,
+            serde_json::json!({
+                "collection": name,
+                "index": index + 1,
+                "total": total,
+                "name": saved.name,
+                "passed": passed
+            }),
+        );
+        results.push(result);
+    }
+
+    Ok(serde_json::json!({
+        "collection": name,
+        "environment": environment,
+        "passed": passed_requests == total,
+        "total": total,
+        "passed_requests": passed_requests,
+        "failed_requests": total - passed_requests,
+        "assertions_passed": assertions_passed,
+        "assertions_failed": assertions_failed,
+        "results": results
+    }))
+}
+
+// Sends every request of collection `name` in order, within `environment`, and
+// reports pass/fail per request and per assertion. A request passes when it
+// got a response and all its assertions hold. Runs as a cancellable job.
+#[tauri::command]
+pub fn run_http_collection(app: AppHandle, name: String, environment: Option<String>) -> Result<u64, String> {
+    spawn_job(&app, "http-collection", move |app, token| {
+        run_collection_blocking(app, token, name, environment.filter(|name| !name.trim().is_empty()))
+    })
+}
+
+// Creates collection `name` or replaces the one with that name
+#[tauri::command]
+pub fn save_http_collection(app: AppHandle, name: String, requests: Vec<CollectionRequest>) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("A collection needs a name".to_string());
+    }
+    let collections = app.state::<HttpCollections>();
+    let mut collections = collections.0.lock().map_err(|e| e.to_string())?;
+    collections.retain(|collection| collection.name != name);
+    collections.push(HttpCollection { name, requests });
+    collections.sort_by_key(|collection| collection.name.to_lowercase());
+    crate::app_data::save(&app, COLLECTIONS_FILE, &*collections, COLLECTIONS_FILE)
+}
+
+#[tauri::command]
+pub fn list_http_collections(collections: State<HttpCollections>) -> Result<Vec<HttpCollection>, String> {
+    Ok(collections.0.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub fn delete_http_collection(app: AppHandle, name: String) -> Result<bool, String> {
+    let collections = app.state::<HttpCollections>();
+    let mut collections = collections.0.lock().map_err(|e| e.to_string())?;
+    let before = collections.len();
+    collections.retain(|collection| collection.name != name);
+    if collections.len() == before {
+        return Ok(false);
+    }
+    crate::app_data::save(&app, COLLECTIONS_FILE, &*collections, COLLECTIONS_FILE)?;
+    Ok(true)
+}
+
+// Drops sensitive headers with literal values from every request, for a bundle
+// exported without secrets; returns how many were dropped
+fn withhold_secrets(collections: &mut [HttpCollection]) -> usize {
+    let mut withheld = 0;
+    for saved in collections
+        .iter_mut()
+        .flat_map(|collection| collection.requests.iter_mut())
+    {
+        let before = saved.request.headers.len();
+        saved.request.headers.retain(|name, value| !secret_header(name, value));
+        withheld += before - saved.request.headers.len();
+    }
+    withheld
+}
+
+// Copies the sensitive headers of `own`'s requests into the same-named
+// requests of `collection` where it doesn't set them
+fn keep_local_secrets(collection: &mut HttpCollection, own: &HttpCollection) {
+    for saved in &mut collection.requests {
+        let Some(own_request) = own.requests.iter().find(|own_request| own_request.name == saved.name) else {
+            continue;
+        };
+        for (name, value) in &own_request.request.headers {
+            if secret_header(name, value) {
+                saved
+                    .request
+                    .headers
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+    }
+}
+
+// Folds `imported` into `collections`, each replacing the local collection of
+// the same name; `overwrite` replaces the whole list. With `keep_secrets` a
+// replaced request keeps the sensitive headers it had locally.
+fn merge(
+    collections: &mut Vec<HttpCollection>,
+    imported: Vec<HttpCollection>,
+    overwrite: bool,
+    keep_secrets: bool,
+) -> serde_json::Value {
+    let local = if overwrite {
+        std::mem::take(collections)
+    } else {
+        collections.clone()
+    };
+    let (mut added, mut replaced) = (0, 0);
+    for mut collection in imported {
+        collection.name = collection.name.trim().to_string();
+        if collection.name.is_empty() {
+            continue;
+        }
+        let own = local.iter().find(|own| own.name == collection.name);
+        if let Some(own) = own.filter(|_| keep_secrets) {
+            keep_local_secrets(&mut collection, own);
+        }
+        match collections.iter_mut().find(|own| own.name == collection.name) {
+            Some(own) => {
+                *own = collection;
+                replaced += 1;
+            }
+            None => {
+                collections.push(collection);
+                added += 1;
+            }
+        }
+    }
+    collections.sort_by_key(|collection| collection.name.to_lowercase());
+    let removed = local
+        .iter()
+        .filter(|own| !collections.iter().any(|collection| collection.name == own.name))
+        .count();
+    serde_json::json!({ "added": added, "replaced": replaced, "removed": removed })
+}
+
+// The collections, for config bundles. Without `include_secrets`, sensitive
+// headers with literal values are left out; returns how many were withheld.
+pub(crate) fn shareable(app: &AppHandle, include_secrets: bool) -> Result<(Vec<HttpCollection>, usize), String> {
+    let mut collections = app
+        .state::<HttpCollections>()
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let withheld = if include_secrets {
+        0
+    } else {
+        withhold_secrets(&mut collections)
+    };
+    Ok((collections, withheld))
+}
+
+// Imports collections from a config bundle, merging or overwriting as `merge`
+// describes
+pub(crate) fn import(
+    app: &AppHandle,
+    imported: Vec<HttpCollection>,
+    overwrite: bool,
+    keep_secrets: bool,
+) -> Result<serde_json::Value, String> {
+    let collections = app.state::<HttpCollections>();
+    let mut collections = collections.0.lock().map_err(|e| e.to_string())?;
+    let result = merge(&mut collections, imported, overwrite, keep_secrets);
+    crate::app_data::save(app, COLLECTIONS_FILE, &*collections, COLLECTIONS_FILE)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn collection(name: &str, headers: &[(&str, &str)]) -> HttpCollection {
+        HttpCollection {
+            name: name.to_string(),
+            requests: vec![CollectionRequest {
+                name: "health".to_string(),
+                request: HttpRequest {
+                    method: "GET".to_string(),
+                    url: "{{base}}/health".to_string(),
+                    headers: headers
+                        .iter()
+                        .map(|(name, value)| (name.to_string(), value.to_string()))
+                        .collect::<BTreeMap<_, _>>(),
+                    body: None,
+                },
+                assertions: vec![Assertion::Status { equals: 200 }],
+            }],
+        }
+    }
+
+    // Exports through JSON the way a config bundle carries them
+    fn round_trip(collections: &[HttpCollection]) -> Vec<HttpCollection> {
+        serde_json::from_str(&serde_json::to_string(collections).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn collections_survive_a_round_trip() {
+        let exported = vec![collection("smoke", &[("Accept", "application/json")])];
+        let mut local = Vec::new();
+        let result = merge(&mut local, round_trip(&exported), false, true);
+        assert_eq!(result, serde_json::json!({ "added": 1, "replaced": 0, "removed": 0 }));
+        assert_eq!(
+            serde_json::to_value(&local).unwrap(),
+            serde_json::to_value(&exported).unwrap()
+        );
+    }
+
+    #[test]
+    fn withheld_headers_are_kept_locally_on_import() {
+        let local_copy = collection("smoke", &[("Authorization", "Bearer abc"), ("Accept", "text/plain")]);
+        let mut exported = vec![local_copy.clone()];
+        assert_eq!(withhold_secrets(&mut exported), 1);
+        exported[0].requests[0]
+            .request
+            .headers
+            .insert("Accept".to_string(), "application/json".to_string());
+
+        let mut local = vec![local_copy, collection("other", &[])];
+        let result = merge(&mut local, round_trip(&exported), true, true);
+        assert_eq!(result, serde_json::json!({ "added": 1, "replaced": 0, "removed": 1 }));
+        let headers = &local[0].requests[0].request.headers;
+        assert_eq!(headers["Authorization"], "Bearer abc");
+        assert_eq!(headers["Accept"], "application/json");
+    }
+
+    #[test]
+    fn templated_credentials_are_exported() {
+        let mut exported = vec![collection("smoke", &[("Authorization", "Bearer {{token}}")])];
+        assert_eq!(withhold_secrets(&mut exported), 0);
+    }
+}
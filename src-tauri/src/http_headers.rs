@@ -0,0 +1,350 @@
+// Explains a pasted block of HTTP headers (from devtools, `curl -v` or a raw
+// request/response): caching directives, cookies, transport and content
+// security policies, CORS and content negotiation, with warnings for the
+// usual misconfigurations and a list of security headers a response lacks.
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::jwt::human_duration;
+use crate::AppState;
+
+// A year, the HSTS max-age browsers' preload lists ask for
+const HSTS_PRELOAD_MIN_AGE: i64 = 31_536_000;
+
+const SECURITY_HEADERS: &[(&str, &str)] = &[
+    ("strict-transport-security", "forces HTTPS on later visits"),
+    (
+        "content-security-policy",
+        "restricts where scripts, styles and frames may load from",
+    ),
+    ("x-content-type-options", "stops MIME sniffing (nosniff)"),
+    ("referrer-policy", "limits what the Referer header leaks"),
+    ("permissions-policy", "turns off browser features the site doesn't use"),
+];
+
+// The start line (status or request line) and the fields in order. Folded
+// lines are joined, `curl -v` prefixes ("< ", "> ") are removed and HTTP/2
+// pseudo-headers (":status") are kept with their colon.
+pub fn parse_header_block(text: &str) -> (Option<String>, Vec<(String, String)>) {
+    let mut start_line = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for raw in text.lines() {
+        let line = raw
+            .strip_prefix("< ")
+            .or_else(|| raw.strip_prefix("> "))
+            .unwrap_or(raw)
+            .trim_end();
+        if line.trim().is_empty() || line.starts_with('*') {
+            continue;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if headers.is_empty() && start_line.is_none() && is_start_line(line) {
+            start_line = Some(line.to_string());
+            continue;
+        }
+        let split = match line.strip_prefix(':') {
+            Some(rest) => rest.split_once(':').map(|(name, value)| (format!(":{}", name), value)),
+            None => line.split_once(':').map(|(name, value)| (name.to_string(), value)),
+        };
+        if let Some((name, value)) = split {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (start_line, headers)
+}
+
+fn is_start_line(line: &str) -> bool {
+    line.starts_with("HTTP/")
+        || line
+            .split_whitespace()
+            .nth(2)
+            .is_some_and(|version| version.starts_with("HTTP/"))
+}
+
+// Comma-separated `name[=value]` items, commas inside quotes kept
+pub fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in value.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            ',' if !in_quotes => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn directive(item: &str) -> (String, Option<String>) {
+    match item.split_once('=') {
+        Some((name, value)) => (
+            name.trim().to_ascii_lowercase(),
+            Some(value.trim().trim_matches('"').to_string()),
+        ),
+        None => (item.trim().to_ascii_lowercase(), None),
+    }
+}
+
+fn seconds(value: Option<&str>) -> Option<i64> {
+    value.and_then(|value| value.trim().parse().ok())
+}
+
+fn cache_control(value: &str, warnings: &mut Vec<String>) -> Value {
+    let directives: Vec<(String, Option<String>)> = split_list(value).iter().map(|item| directive(item)).collect();
+    let has = |name: &str| directives.iter().any(|(directive, _)| directive == name);
+    let explained: Vec<Value> = directives
+        .iter()
+        .map(|(name, value)| {
+            let age = seconds(value.as_deref());
+            let meaning = match name.as_str() {
+                "max-age" => format!("fresh for {}", age.map(human_duration).unwrap_or_else(|| "?".into())),
+                "s-maxage" => format!(
+                    "shared caches keep it fresh for {}",
+                    age.map(human_duration).unwrap_or_else(|| "?".into())
+                ),
+                "no-cache" => "may be stored, but must be revalidated before every use".to_string(),
+                "no-store" => "must not be stored by any cache".to_string(),
+                "private" => "only the browser may cache it, not shared caches or CDNs".to_string(),
+                "public" => "any cache may store it, even for authenticated requests".to_string(),
+                "must-revalidate" => "once stale, must be revalidated rather than served".to_string(),
+                "proxy-revalidate" => "like must-revalidate, for shared caches only".to_string(),
+                "immutable" => "won't change while fresh; skip revalidation on reload".to_string(),
+                "no-transform" => "intermediaries must not alter the body".to_string(),
+                "stale-while-revalidate" => format!(
+                    "may be served stale for {} while revalidating in the background",
+                    age.map(human_duration).unwrap_or_else(|| "?".into())
+                ),
+                "stale-if-error" => format!(
+                    "may be served stale for {} if the origin errors",
+                    age.map(human_duration).unwrap_or_else(|| "?".into())
+                ),
+                "max-stale" => "the client accepts stale responses".to_string(),
+                "min-fresh" => "the client wants responses fresh for at least this long".to_string(),
+                "only-if-cached" => "the client only wants a cached response".to_string(),
+                _ => "unknown directive".to_string(),
+            };
+            json!({ "directive": name, "value": value, "meaning": meaning })
+        })
+        .collect();
+    if has("no-store") && (has("max-age") || has("public")) {
+        warnings.push("Cache-Control: no-store overrides max-age/public; the response is never cached".to_string());
+    }
+    if has("public") && has("private") {
+        warnings.push("Cache-Control: both public and private are set".to_string());
+    }
+    if directives
+        .iter()
+        .any(|(name, value)| name == "max-age" && seconds(value.as_deref()).is_none())
+    {
+        warnings.push("Cache-Control: max-age needs a number of seconds".to_string());
+    }
+    json!({ "directives": explained })
+}
+
+fn set_cookie(value: &str, warnings: &mut Vec<String>) -> Value {
+    let cookie = crate::cookies::parse_set_cookie(value);
+    for warning in crate::cookies::warnings(&cookie) {
+        warnings.push(format!("Set-Cookie {}: {}", cookie.name, warning));
+    }
+    crate::cookies::describe_set_cookie(&cookie, chrono::Utc::now())
+}
+
+fn strict_transport_security(value: &str, warnings: &mut Vec<String>) -> Value {
+    let directives: Vec<(String, Option<String>)> = value.split(';').map(directive).collect();
+    let max_age = directives
+        .iter()
+        .find(|(name, _)| name == "max-age")
+        .and_then(|(_, value)| seconds(value.as_deref()));
+    let include_subdomains = directives.iter().any(|(name, _)| name == "includesubdomains");
+    let preload = directives.iter().any(|(name, _)| name == "preload");
+    match max_age {
+        None => warnings.push("Strict-Transport-Security: max-age is required".to_string()),
+        Some(0) => warnings.push("Strict-Transport-Security: max-age=0 removes the policy".to_string()),
+        Some(age) if preload && (age < HSTS_PRELOAD_MIN_AGE || !include_subdomains) => warnings.push(
+            "Strict-Transport-Security: preload needs max-age of at least a year and includeSubDomains".to_string(),
+        ),
+        _ => {}
+    }
+    json!({
+        "max_age": max_age,
+        "duration": max_age.map(human_duration),
+        "include_subdomains": include_subdomains,
+        "preload": preload
+    })
+}
+
+fn content_security_policy(value: &str, warnings: &mut Vec<String>) -> Value {
+    let report = crate::csp::analyze(value);
+    for finding in report["findings"].as_array().into_iter().flatten() {
+        if matches!(finding["severity"].as_str(), Some("high" | "medium")) {
+            warnings.push(format!(
+                "Content-Security-Policy {}: {}",
+                finding["directive"].as_str().unwrap_or(""),
+                finding["message"].as_str().unwrap_or("")
+            ));
+        }
+    }
+    report
+}
+
+// Accept-style lists ordered by preference (q-value, then position)
+fn negotiation(value: &str) -> Value {
+    let mut items: Vec<(String, f64, Vec<String>)> = split_list(value)
+        .iter()
+        .map(|item| {
+            let mut parts = item.split(';').map(str::trim);
+            let name = parts.next().unwrap_or("").to_string();
+            let mut quality = 1.0;
+            let mut parameters = Vec::new();
+            for parameter in parts {
+                match parameter.strip_prefix("q=") {
+                    Some(q) => quality = q.parse().unwrap_or(0.0),
+                    None => parameters.push(parameter.to_string()),
+                }
+            }
+            (name, quality, parameters)
+        })
+        .collect();
+    items.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let preferences: Vec<Value> = items
+        .into_iter()
+        .map(|(value, quality, parameters)| {
+            json!({
+                "value": value,
+                "q": quality,
+                "parameters": parameters,
+                "refused": quality == 0.0
+            })
+        })
+        .collect();
+    json!({ "preferences": preferences })
+}
+
+fn access_control(headers: &[(String, String)], warnings: &mut Vec<String>) {
+    let get = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    };
+    let origin = get("access-control-allow-origin");
+    let credentials = get("access-control-allow-credentials") == Some("true");
+    if origin == Some("*") && credentials {
+        warnings.push("CORS: browsers reject Allow-Origin * together with Allow-Credentials true".to_string());
+    }
+    if origin.is_some_and(|origin| origin != "*")
+        && !get("vary").is_some_and(|vary| vary.to_ascii_lowercase().contains("origin"))
+    {
+        warnings.push(
+            "CORS: a specific Allow-Origin without Vary: Origin lets caches serve it to other origins".to_string(),
+        );
+    }
+}
+
+fn explain(name: &str, value: &str, warnings: &mut Vec<String>) -> Option<Value> {
+    let explanation = match name {
+        "cache-control" => cache_control(value, warnings),
+        "set-cookie" => set_cookie(value, warnings),
+        "strict-transport-security" => strict_transport_security(value, warnings),
+        "content-security-policy" | "content-security-policy-report-only" => content_security_policy(value, warnings),
+        "accept" | "accept-language" | "accept-encoding" | "accept-charset" | "te" => negotiation(value),
+        "vary" => json!({ "varies_on": split_list(value) }),
+        "x-content-type-options" => {
+            if !value.eq_ignore_ascii_case("nosniff") {
+                warnings.push("X-Content-Type-Options: the only valid value is nosniff".to_string());
+            }
+            json!({ "nosniff": value.eq_ignore_ascii_case("nosniff") })
+        }
+        "x-frame-options" => json!({ "meaning": match value.to_ascii_uppercase().as_str() {
+            "DENY" => "may not be framed",
+            "SAMEORIGIN" => "may only be framed by the same origin",
+            _ => "unrecognized; use CSP frame-ancestors instead",
+        }}),
+        "server" | "x-powered-by" | "x-aspnet-version" => {
+            warnings.push(format!("{}: discloses server software ({})", name, value));
+            return None;
+        }
+        _ => return None,
+    };
+    Some(explanation)
+}
+
+fn analyze(text: &str) -> Result<Value, String> {
+    let (start_line, headers) = parse_header_block(text);
+    if headers.is_empty() {
+        return Err("No headers found; expected lines like `Name: value`".to_string());
+    }
+    let has = |name: &str| headers.iter().any(|(key, _)| key.eq_ignore_ascii_case(name));
+    let is_response = match &start_line {
+        Some(line) => line.starts_with("HTTP/"),
+        None => has(":status") || has("set-cookie") || has("server") || (has("cache-control") && !has("accept")),
+    };
+
+    let mut warnings = Vec::new();
+    let explained: Vec<Value> = headers
+        .iter()
+        .map(|(name, value)| {
+            let lower = name.to_ascii_lowercase();
+            json!({
+                "name": name,
+                "value": value,
+                "details": explain(&lower, value, &mut warnings)
+            })
+        })
+        .collect();
+    access_control(&headers, &mut warnings);
+
+    let missing: Vec<Value> = if is_response {
+        SECURITY_HEADERS
+            .iter()
+            .filter(|(name, _)| !has(name))
+            .map(|(name, purpose)| json!({ "header": name, "purpose": purpose }))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Ok(json!({
+        "start_line": start_line,
+        "kind": if is_response { "response" } else { "request" },
+        "headers": explained,
+        "warnings": warnings,
+        "missing_security_headers": missing
+    }))
+}
+
+fn header_text(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    match text.filter(|text| !text.is_empty()) {
+        Some(text) => Ok(text),
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            Ok(storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string())
+        }
+    }
+}
+
+// Explains a raw header block (`text`, or the active document's raw content
+// when it's empty); responses also get the security headers they lack
+#[tauri::command]
+pub async fn analyze_http_headers(app: AppHandle, text: Option<String>) -> Result<Value, String> {
+    crate::run_blocking("analyze_http_headers", move || analyze(&header_text(&app, text)?)).await
+}
@@ -0,0 +1,1908 @@
+// iCalendar (RFC 5545) content decoded into JSON: calendar properties, time
+// zones, and each event with its times, attendees and alarms. Recurring
+// events list their next occurrences, expanded from RRULE, RDATE and EXDATE.
+// Times with a TZID are resolved to UTC through the calendar's own VTIMEZONE
+// definitions, since there's no time zone database to fall back on; a TZID
+// without a definition is reported and its times stay local. The generator
+// at the end goes the other way, writing a calendar from the same JSON.
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use serde_json::{json, Map, Value};
+
+use crate::formatters::Formatter;
+
+pub const DEFAULT_OCCURRENCES: usize = 10;
+pub const MAX_OCCURRENCES: usize = 1000;
+// Recurrence periods walked before giving up on finding more occurrences
+const MAX_PERIODS: usize = 50_000;
+// Components nested deeper than this are rejected
+const MAX_DEPTH: usize = 16;
+
+const WEEKDAYS: &[(&str, Weekday)] = &[
+    ("MO", Weekday::Mon),
+    ("TU", Weekday::Tue),
+    ("WE", Weekday::Wed),
+    ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri),
+    ("SA", Weekday::Sat),
+    ("SU", Weekday::Sun),
+];
+
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub children: Vec<Component>,
+}
+
+impl Component {
+    pub fn get(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|property| property.name == name)
+    }
+
+    pub fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Property> {
+        self.properties.iter().filter(move |property| property.name == name)
+    }
+
+    // A TEXT value with its escapes undone
+    pub fn text(&self, name: &str) -> Option<String> {
+        self.get(name).map(|property| unescape_text(&property.value))
+    }
+}
+
+// Joins folded lines: a line starting with a space or tab continues the last
+pub fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+pub fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+// `NAME;PARAM=value;PARAM="quoted:value":property value`
+pub fn parse_content_line(line: &str) -> Result<Property, String> {
+    let mut name = String::new();
+    let mut params = Vec::new();
+    let mut chars = line.char_indices().peekable();
+    while let Some((_, c)) = chars.peek().copied() {
+        if c == ';' || c == ':' {
+            break;
+        }
+        name.push(c);
+        chars.next();
+    }
+    loop {
+        match chars.next() {
+            Some((index, ':')) => {
+                return Ok(Property {
+                    name: name.trim().to_ascii_uppercase(),
+                    params,
+                    value: line[index + 1..].to_string(),
+                })
+            }
+            Some((_, ';')) => {
+                let mut key = String::new();
+                let mut value = String::new();
+                let mut in_value = false;
+                let mut quoted = false;
+                while let Some((_, c)) = chars.peek().copied() {
+                    match c {
+                        '"' if in_value => quoted = !quoted,
+                        ';' | ':' if !quoted => break,
+                        '=' if !in_value => in_value = true,
+                        c if in_value => value.push(c),
+                        c => key.push(c),
+                    }
+                    chars.next();
+                }
+                params.push((key.trim().to_ascii_uppercase(), value));
+            }
+            _ => return Err(format!("No ':' in content line: {}", line)),
+        }
+    }
+}
+
+// The top-level components (usually one VCALENDAR or VCARD after another)
+pub fn parse_components(text: &str) -> Result<Vec<Component>, String> {
+    let mut stack: Vec<Component> = Vec::new();
+    let mut top = Vec::new();
+    for (index, line) in unfold(text).iter().enumerate() {
+        let property = parse_content_line(line).map_err(|e| format!("Line {}: {}", index + 1, e))?;
+        match property.name.as_str() {
+            "BEGIN" => {
+                if stack.len() >= MAX_DEPTH {
+                    return Err("Components are nested too deep".to_string());
+                }
+                stack.push(Component {
+                    name: property.value.trim().to_ascii_uppercase(),
+                    properties: Vec::new(),
+                    children: Vec::new(),
+                });
+            }
+            "END" => {
+                let component = stack
+                    .pop()
+                    .ok_or_else(|| format!("Line {}: END without BEGIN", index + 1))?;
+                let name = property.value.trim().to_ascii_uppercase();
+                if component.name != name {
+                    return Err(format!(
+                        "Line {}: END:{} closes BEGIN:{}",
+                        index + 1,
+                        name,
+                        component.name
+                    ));
+                }
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(component),
+                    None => top.push(component),
+                }
+            }
+            _ => match stack.last_mut() {
+                Some(component) => component.properties.push(property),
+                None => {
+                    return Err(format!(
+                        "Line {}: {} outside of any component",
+                        index + 1,
+                        property.name
+                    ))
+                }
+            },
+        }
+    }
+    if let Some(open) = stack.last() {
+        return Err(format!("BEGIN:{} is never closed", open.name));
+    }
+    Ok(top)
+}
+
+#[derive(Clone, PartialEq)]
+enum When {
+    Date(NaiveDate),
+    Floating(NaiveDateTime),
+    Utc(NaiveDateTime),
+    Zoned(NaiveDateTime, String),
+}
+
+impl When {
+    fn naive(&self) -> NaiveDateTime {
+        match self {
+            When::Date(date) => date.and_time(NaiveTime::MIN),
+            When::Floating(time) | When::Utc(time) | When::Zoned(time, _) => *time,
+        }
+    }
+
+    // The same kind of time (date, UTC, same zone) at `time`
+    fn at(&self, time: NaiveDateTime) -> When {
+        match self {
+            When::Date(_) => When::Date(time.date()),
+            When::Floating(_) => When::Floating(time),
+            When::Utc(_) => When::Utc(time),
+            When::Zoned(_, tzid) => When::Zoned(time, tzid.clone()),
+        }
+    }
+}
+
+fn parse_date_time(value: &str, tzid: Option<&str>, date_only: bool) -> Result<When, String> {
+    let value = value.trim();
+    if date_only || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(When::Date)
+            .map_err(|_| format!("Invalid date {}", value));
+    }
+    let (local, utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(local) => (local, true),
+        None => (value, false),
+    };
+    let time =
+        NaiveDateTime::parse_from_str(local, "%Y%m%dT%H%M%S").map_err(|_| format!("Invalid date-time {}", value))?;
+    Ok(match (utc, tzid) {
+        (true, _) => When::Utc(time),
+        (false, Some(tzid)) => When::Zoned(time, tzid.to_string()),
+        (false, None) => When::Floating(time),
+    })
+}
+
+fn parse_when(property: &Property) -> Result<When, String> {
+    parse_date_time(
+        &property.value,
+        property.param("TZID"),
+        property.param("VALUE") == Some("DATE"),
+    )
+}
+
+// Every value of a list property such as EXDATE or RDATE
+fn parse_when_list(property: &Property) -> Vec<When> {
+    property
+        .value
+        .split(',')
+        .filter_map(|value| {
+            parse_date_time(value, property.param("TZID"), property.param("VALUE") == Some("DATE")).ok()
+        })
+        .collect()
+}
+
+// "+0130" / "-0500" / "+013045" as seconds east of UTC
+fn parse_utc_offset(value: &str) -> Option<i32> {
+    let value = value.trim();
+    let (sign, digits) = match value.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if !(digits.len() == 4 || digits.len() == 6) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..4].parse().ok()?;
+    let seconds: i32 = digits
+        .get(4..)
+        .filter(|s| !s.is_empty())
+        .map_or(Ok(0), str::parse)
+        .ok()?;
+    Some(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+    format!("{}{:02}:{:02}", sign, seconds / 3600, seconds % 3600 / 60)
+}
+
+// "P1W", "PT1H30M", "-P1DT2H": RFC 5545 durations
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::try_weeks(n)?,
+                    ('D', false) => Duration::try_days(n)?,
+                    ('H', true) => Duration::try_hours(n)?,
+                    ('M', true) => Duration::try_minutes(n)?,
+                    ('S', true) => Duration::try_seconds(n)?,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+fn describe_duration(duration: Duration) -> String {
+    crate::formatters::jwt::human_duration(duration.num_seconds())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Clone)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<When>,
+    // Weekday with an optional ordinal: 2MO is the second Monday, -1FR the last Friday
+    by_day: Vec<(Option<i32>, Weekday)>,
+    by_month_day: Vec<i32>,
+    by_month: Vec<u32>,
+    by_set_pos: Vec<i32>,
+    week_start: Weekday,
+    // Parts that aren't applied when expanding (BYHOUR, BYWEEKNO, ...)
+    ignored: Vec<String>,
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    WEEKDAYS.iter().find(|(name, _)| *name == code).map(|(_, day)| *day)
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    WEEKDAYS
+        .iter()
+        .find(|(_, d)| *d == day)
+        .map(|(name, _)| *name)
+        .unwrap_or("")
+}
+
+fn int_list<T: std::str::FromStr>(value: &str, what: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .parse::<T>()
+                .map_err(|_| format!("Invalid {} value {}", what, item))
+        })
+        .collect()
+}
+
+fn parse_rule(text: &str) -> Result<Rule, String> {
+    let mut rule = Rule {
+        frequency: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        by_day: Vec::new(),
+        by_month_day: Vec::new(),
+        by_month: Vec::new(),
+        by_set_pos: Vec::new(),
+        week_start: Weekday::Mon,
+        ignored: Vec::new(),
+    };
+    let mut frequency = None;
+    for part in text.trim().split(';').filter(|part| !part.is_empty()) {
+        let (key, value) = part
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid RRULE part {}", part))?;
+        match key.trim().to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match value.trim().to_ascii_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    other => return Err(format!("FREQ={} isn't expanded; only DAILY to YEARLY are", other)),
+                })
+            }
+            "INTERVAL" => {
+                rule.interval = value
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or("INTERVAL must be a positive number")?
+            }
+            "COUNT" => rule.count = Some(value.trim().parse().map_err(|_| "COUNT must be a number")?),
+            "UNTIL" => rule.until = Some(parse_date_time(value, None, false)?),
+            "BYDAY" => {
+                for item in value.split(',') {
+                    let item = item.trim().to_ascii_uppercase();
+                    if item.len() < 2 || !item.is_ascii() {
+                        return Err(format!("Invalid BYDAY value {}", item));
+                    }
+                    let (ordinal, code) = item.split_at(item.len() - 2);
+                    let day = weekday(code).ok_or_else(|| format!("Invalid BYDAY value {}", item))?;
+                    let ordinal = match ordinal {
+                        "" => None,
+                        ordinal => Some(
+                            ordinal
+                                .trim_start_matches('+')
+                                .parse::<i32>()
+                                .map_err(|_| format!("Invalid BYDAY value {}", item))?,
+                        ),
+                    };
+                    rule.by_day.push((ordinal, day));
+                }
+            }
+            "BYMONTHDAY" => rule.by_month_day = int_list(value, "BYMONTHDAY")?,
+            "BYMONTH" => rule.by_month = int_list(value, "BYMONTH")?,
+            "BYSETPOS" => rule.by_set_pos = int_list(value, "BYSETPOS")?,
+            "WKST" => rule.week_start = weekday(&value.trim().to_ascii_uppercase()).ok_or("Invalid WKST value")?,
+            other => rule.ignored.push(other.to_string()),
+        }
+    }
+    rule.frequency = frequency.ok_or("RRULE has no FREQ")?;
+    Ok(rule)
+}
+
+fn ordinal_word(n: i32) -> String {
+    match n {
+        -1 => "last".to_string(),
+        -2 => "second to last".to_string(),
+        n => {
+            let suffix = match (n % 10, n % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            format!("{}{}", n, suffix)
+        }
+    }
+}
+
+// "every 2 weeks on MO, WE; 10 times"
+fn summarize_rule(rule: &Rule) -> String {
+    let unit = match rule.frequency {
+        Frequency::Daily => "day",
+        Frequency::Weekly => "week",
+        Frequency::Monthly => "month",
+        Frequency::Yearly => "year",
+    };
+    let mut summary = if rule.interval == 1 {
+        format!("every {}", unit)
+    } else {
+        format!("every {} {}s", rule.interval, unit)
+    };
+    if !rule.by_month.is_empty() {
+        let months: Vec<String> = rule.by_month.iter().map(u32::to_string).collect();
+        summary.push_str(&format!(" in month {}", months.join(", ")));
+    }
+    if !rule.by_day.is_empty() {
+        let days: Vec<String> = rule
+            .by_day
+            .iter()
+            .map(|(ordinal, day)| match ordinal {
+                Some(n) => format!("{} {}", ordinal_word(*n), weekday_code(*day)),
+                None => weekday_code(*day).to_string(),
+            })
+            .collect();
+        summary.push_str(&format!(" on {}", days.join(", ")));
+    }
+    if !rule.by_month_day.is_empty() {
+        let days: Vec<String> = rule.by_month_day.iter().map(|day| ordinal_word(*day)).collect();
+        summary.push_str(&format!(" on the {}", days.join(", ")));
+    }
+    if !rule.by_set_pos.is_empty() {
+        let positions: Vec<String> = rule.by_set_pos.iter().map(|pos| ordinal_word(*pos)).collect();
+        summary.push_str(&format!(" (the {} of those)", positions.join(", ")));
+    }
+    match (&rule.count, &rule.until) {
+        (Some(count), _) => summary.push_str(&format!("; {} times", count)),
+        (None, Some(until)) => summary.push_str(&format!("; until {}", until.naive().format("%Y-%m-%d %H:%M"))),
+        (None, None) => {}
+    }
+    summary
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|next| next.pred_opt())
+        .map_or(28, |last| last.day())
+}
+
+// Days `first..=last` that fall on `day`, narrowed to the nth (from the end
+// when negative) when `ordinal` is given
+fn weekdays_between(first: NaiveDate, last: NaiveDate, day: Weekday, ordinal: Option<i32>) -> Vec<NaiveDate> {
+    let offset = (7 + day.num_days_from_monday() - first.weekday().num_days_from_monday()) % 7;
+    let matches: Vec<NaiveDate> = first
+        .iter_days()
+        .skip(offset as usize)
+        .step_by(7)
+        .take_while(|date| *date <= last)
+        .collect();
+    match ordinal {
+        None => matches,
+        Some(n) if n > 0 => matches.get(n as usize - 1).copied().into_iter().collect(),
+        Some(n) => matches
+            .len()
+            .checked_sub(n.unsigned_abs() as usize)
+            .map(|i| matches[i])
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn resolve_month_day(year: i32, month: u32, day: i32) -> Option<NaiveDate> {
+    let length = days_in_month(year, month) as i32;
+    let day = if day < 0 { length + 1 + day } else { day };
+    if (1..=length).contains(&day) {
+        NaiveDate::from_ymd_opt(year, month, day as u32)
+    } else {
+        None
+    }
+}
+
+// Candidate days in one month of a MONTHLY or YEARLY rule
+fn month_candidates(rule: &Rule, year: i32, month: u32, start: NaiveDate) -> Vec<NaiveDate> {
+    let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+        return Vec::new();
+    };
+    let last = first + Duration::days(days_in_month(year, month) as i64 - 1);
+    let by_month_day: Vec<NaiveDate> = rule
+        .by_month_day
+        .iter()
+        .filter_map(|day| resolve_month_day(year, month, *day))
+        .collect();
+    let by_day: Vec<NaiveDate> = rule
+        .by_day
+        .iter()
+        .flat_map(|(ordinal, day)| weekdays_between(first, last, *day, *ordinal))
+        .collect();
+    match (rule.by_month_day.is_empty(), rule.by_day.is_empty()) {
+        (false, false) => by_month_day.into_iter().filter(|date| by_day.contains(date)).collect(),
+        (false, true) => by_month_day,
+        (true, false) => by_day,
+        (true, true) => resolve_month_day(year, month, start.day() as i32).into_iter().collect(),
+    }
+}
+
+// Candidate days of the `period`th period after `start`, before BYSETPOS
+fn period_candidates(rule: &Rule, start: NaiveDate, period: i64) -> Option<Vec<NaiveDate>> {
+    let step = period * rule.interval as i64;
+    let month_allowed = |date: &NaiveDate| rule.by_month.is_empty() || rule.by_month.contains(&date.month());
+    Some(match rule.frequency {
+        Frequency::Daily => {
+            let date = start.checked_add_signed(Duration::try_days(step)?)?;
+            let day_ok = rule.by_day.is_empty() || rule.by_day.iter().any(|(_, day)| *day == date.weekday());
+            let month_day_ok = rule.by_month_day.is_empty()
+                || rule
+                    .by_month_day
+                    .iter()
+                    .any(|day| resolve_month_day(date.year(), date.month(), *day) == Some(date));
+            if day_ok && month_day_ok && month_allowed(&date) {
+                vec![date]
+            } else {
+                Vec::new()
+            }
+        }
+        Frequency::Weekly => {
+            let back = (7 + start.weekday().num_days_from_monday() - rule.week_start.num_days_from_monday()) % 7;
+            let week = start
+                .checked_sub_signed(Duration::days(back as i64))?
+                .checked_add_signed(Duration::try_weeks(step)?)?;
+            let days: Vec<Weekday> = if rule.by_day.is_empty() {
+                vec![start.weekday()]
+            } else {
+                rule.by_day.iter().map(|(_, day)| *day).collect()
+            };
+            week.iter_days()
+                .take(7)
+                .filter(|date| days.contains(&date.weekday()) && month_allowed(date))
+                .collect()
+        }
+        Frequency::Monthly => {
+            let index = start.year() as i64 * 12 + start.month0() as i64 + step;
+            let (year, month) = (
+                i32::try_from(index.div_euclid(12)).ok()?,
+                index.rem_euclid(12) as u32 + 1,
+            );
+            if !rule.by_month.is_empty() && !rule.by_month.contains(&month) {
+                Vec::new()
+            } else {
+                month_candidates(rule, year, month, start)
+            }
+        }
+        Frequency::Yearly => {
+            let year = i32::try_from(start.year() as i64 + step).ok()?;
+            if rule.by_month.is_empty() && !rule.by_day.is_empty() && rule.by_month_day.is_empty() {
+                // BYDAY across the whole year, e.g. 20MO is the 20th Monday
+                let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let last = NaiveDate::from_ymd_opt(year, 12, 31)?;
+                rule.by_day
+                    .iter()
+                    .flat_map(|(ordinal, day)| weekdays_between(first, last, *day, *ordinal))
+                    .collect()
+            } else {
+                let months = if rule.by_month.is_empty() {
+                    vec![start.month()]
+                } else {
+                    rule.by_month.clone()
+                };
+                months
+                    .iter()
+                    .filter(|month| (1..=12).contains(*month))
+                    .flat_map(|month| month_candidates(rule, year, *month, start))
+                    .collect()
+            }
+        }
+    })
+}
+
+// Calls `visit` with each start of `rule` from `start` in order, DTSTART
+// first, until it returns false or the rule runs out. `offset` is the UTC
+// offset of the local times, to compare them with a UTC UNTIL
+fn expand_rule(rule: &Rule, start: NaiveDateTime, offset: i32, mut visit: impl FnMut(NaiveDateTime) -> bool) {
+    if !visit(start) {
+        return;
+    }
+    let mut emitted = 1;
+    let until = rule.until.as_ref().map(|until| match until {
+        When::Date(date) => date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN)),
+        When::Utc(time) => time
+            .checked_add_signed(Duration::seconds(offset as i64))
+            .unwrap_or(*time),
+        other => other.naive(),
+    });
+    for period in 0..MAX_PERIODS as i64 {
+        let Some(mut dates) = period_candidates(rule, start.date(), period) else {
+            return;
+        };
+        dates.sort();
+        dates.dedup();
+        if !rule.by_set_pos.is_empty() {
+            let all = dates;
+            dates = rule
+                .by_set_pos
+                .iter()
+                .filter_map(|pos| match *pos {
+                    pos if pos > 0 => all.get(pos as usize - 1).copied(),
+                    pos => all.len().checked_sub(pos.unsigned_abs() as usize).map(|i| all[i]),
+                })
+                .collect();
+            dates.sort();
+            dates.dedup();
+        }
+        for date in dates {
+            let time = date.and_time(start.time());
+            if time <= start {
+                continue;
+            }
+            if until.is_some_and(|until| time > until) || rule.count.is_some_and(|count| emitted >= count) {
+                return;
+            }
+            emitted += 1;
+            if !visit(time) {
+                return;
+            }
+        }
+    }
+}
+
+struct Observance {
+    daylight: bool,
+    name: Option<String>,
+    start: NaiveDateTime,
+    offset_from: i32,
+    offset_to: i32,
+    rule: Option<Rule>,
+    rrule: Option<String>,
+    rdates: Vec<NaiveDateTime>,
+}
+
+struct Zone {
+    tzid: String,
+    observances: Vec<Observance>,
+}
+
+impl Zone {
+    fn from_component(component: &Component) -> Option<Zone> {
+        let tzid = component.get("TZID")?.value.trim().to_string();
+        let observances = component
+            .children
+            .iter()
+            .filter(|child| child.name == "STANDARD" || child.name == "DAYLIGHT")
+            .filter_map(|child| {
+                Some(Observance {
+                    daylight: child.name == "DAYLIGHT",
+                    name: child.text("TZNAME"),
+                    start: parse_when(child.get("DTSTART")?).ok()?.naive(),
+                    offset_from: parse_utc_offset(&child.get("TZOFFSETFROM")?.value)?,
+                    offset_to: parse_utc_offset(&child.get("TZOFFSETTO")?.value)?,
+                    rule: child.get("RRULE").and_then(|rule| parse_rule(&rule.value).ok()),
+                    rrule: child.get("RRULE").map(|rule| rule.value.clone()),
+                    rdates: child
+                        .all("RDATE")
+                        .flat_map(parse_when_list)
+                        .map(|when| when.naive())
+                        .collect(),
+                })
+            })
+            .collect();
+        Some(Zone { tzid, observances })
+    }
+
+    // The observance in effect at local time `local`: the one whose latest
+    // onset at or before it is the most recent
+    fn observance_at(&self, local: NaiveDateTime) -> Option<&Observance> {
+        let mut best: Option<(NaiveDateTime, &Observance)> = None;
+        for observance in &self.observances {
+            let mut latest = None;
+            let mut consider = |onset: NaiveDateTime| {
+                if onset <= local {
+                    latest = Some(onset);
+                    true
+                } else {
+                    false
+                }
+            };
+            match &observance.rule {
+                Some(rule) => expand_rule(rule, observance.start, observance.offset_from, &mut consider),
+                None => {
+                    consider(observance.start);
+                }
+            }
+            for rdate in &observance.rdates {
+                if *rdate <= local && latest.is_none_or(|latest| *rdate > latest) {
+                    latest = Some(*rdate);
+                }
+            }
+            if let Some(onset) = latest {
+                if best.is_none_or(|(best, _)| onset > best) {
+                    best = Some((onset, observance));
+                }
+            }
+        }
+        best.map(|(_, observance)| observance)
+    }
+
+    // UTC offset at `local`; before the first onset, the earliest
+    // observance's offset_from applies
+    fn offset_at(&self, local: NaiveDateTime) -> Option<(i32, Option<String>)> {
+        match self.observance_at(local) {
+            Some(observance) => Some((observance.offset_to, observance.name.clone())),
+            None => self
+                .observances
+                .iter()
+                .min_by_key(|observance| observance.start)
+                .map(|observance| (observance.offset_from, None)),
+        }
+    }
+
+    fn describe(&self) -> Value {
+        let observances: Vec<Value> = self
+            .observances
+            .iter()
+            .map(|observance| {
+                json!({
+                    "kind": if observance.daylight { "daylight" } else { "standard" },
+                    "name": observance.name,
+                    "starts": observance.start.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    "offset_from": format_offset(observance.offset_from),
+                    "offset_to": format_offset(observance.offset_to),
+                    "rule": observance.rule.as_ref().map(summarize_rule),
+                    "rrule": observance.rrule
+                })
+            })
+            .collect();
+        json!({ "tzid": self.tzid, "observances": observances })
+    }
+}
+
+struct Resolver<'a> {
+    zones: &'a [Zone],
+    // TZIDs used without a VTIMEZONE definition
+    unresolved: Vec<String>,
+}
+
+impl Resolver<'_> {
+    fn offset(&mut self, when: &When) -> Option<(i32, Option<String>)> {
+        match when {
+            When::Utc(_) => Some((0, None)),
+            When::Zoned(time, tzid) => {
+                if let Some(zone) = self.zones.iter().find(|zone| zone.tzid == *tzid) {
+                    return zone.offset_at(*time);
+                }
+                if matches!(tzid.as_str(), "UTC" | "GMT" | "Etc/UTC" | "Etc/GMT" | "Z") {
+                    return Some((0, None));
+                }
+                if !self.unresolved.contains(tzid) {
+                    self.unresolved.push(tzid.clone());
+                }
+                None
+            }
+            When::Date(_) | When::Floating(_) => None,
+        }
+    }
+
+    fn utc(&mut self, when: &When) -> Option<NaiveDateTime> {
+        let (offset, _) = self.offset(when)?;
+        when.naive().checked_sub_signed(Duration::seconds(offset as i64))
+    }
+
+    fn describe(&mut self, when: &When) -> Value {
+        let local = when.naive();
+        match when {
+            When::Date(date) => json!({ "date": date.format("%Y-%m-%d").to_string() }),
+            When::Floating(_) => json!({ "local": local.format("%Y-%m-%dT%H:%M:%S").to_string(), "floating": true }),
+            When::Utc(_) => json!({ "utc": local.format("%Y-%m-%dT%H:%M:%SZ").to_string() }),
+            When::Zoned(_, tzid) => {
+                let mut description = json!({
+                    "local": local.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    "tzid": tzid,
+                });
+                if let Some((offset, name)) = self.offset(when) {
+                    description["offset"] = json!(format_offset(offset));
+                    description["zone_name"] = json!(name);
+                    if let Some(utc) = local.checked_sub_signed(Duration::seconds(offset as i64)) {
+                        description["utc"] = json!(utc.format("%Y-%m-%dT%H:%M:%SZ").to_string());
+                    }
+                } else {
+                    description["resolved"] = json!(false);
+                }
+                description
+            }
+        }
+    }
+}
+
+fn describe_person(property: &Property) -> Value {
+    let address = property.value.trim();
+    let email = address
+        .strip_prefix("mailto:")
+        .or_else(|| address.strip_prefix("MAILTO:"))
+        .unwrap_or(address);
+    let mut person = json!({ "email": email });
+    for (param, key) in [
+        ("CN", "name"),
+        ("ROLE", "role"),
+        ("PARTSTAT", "status"),
+        ("RSVP", "rsvp"),
+        ("CUTYPE", "type"),
+    ] {
+        if let Some(value) = property.param(param) {
+            person[key] = json!(value);
+        }
+    }
+    person
+}
+
+fn describe_alarm(alarm: &Component) -> Value {
+    let trigger = alarm.get("TRIGGER").map(|trigger| {
+        let readable = parse_duration(&trigger.value).map(|duration| {
+            let direction = if duration < Duration::zero() { "before" } else { "after" };
+            let relation = match trigger.param("RELATED") {
+                Some("END") => "end",
+                _ => "start",
+            };
+            format!("{} {} {}", describe_duration(duration.abs()), direction, relation)
+        });
+        json!({ "value": trigger.value, "readable": readable })
+    });
+    json!({
+        "action": alarm.text("ACTION"),
+        "trigger": trigger,
+        "description": alarm.text("DESCRIPTION"),
+    })
+}
+
+// Next `limit` occurrences starting at or after `from` (UTC), as
+// [start, end] pairs in the event's own kind of time
+#[allow(clippy::too_many_arguments)]
+fn next_occurrences(
+    start: &When,
+    length: Option<Duration>,
+    rule: Option<&Rule>,
+    rdates: &[When],
+    exdates: &[When],
+    from: NaiveDateTime,
+    limit: usize,
+    resolver: &mut Resolver,
+) -> (Vec<(When, Option<When>)>, bool) {
+    // UTC exclusions moved into the start's zone so they compare as local times
+    let exdates: Vec<When> = exdates
+        .iter()
+        .map(|exdate| match (exdate, start) {
+            (When::Utc(time), When::Zoned(..)) => resolver
+                .offset(&start.at(*time))
+                .and_then(|(offset, _)| time.checked_add_signed(Duration::seconds(offset as i64)))
+                .map_or_else(|| exdate.clone(), When::Floating),
+            _ => exdate.clone(),
+        })
+        .collect();
+    let excluded = |time: NaiveDateTime| {
+        exdates.iter().any(|exdate| match exdate {
+            When::Date(date) => *date == time.date(),
+            other => other.naive() == time,
+        })
+    };
+    // Offset in effect around a UTC UNTIL, which can differ from the start's
+    // across a daylight saving change
+    let until = match rule.and_then(|rule| rule.until.as_ref()) {
+        Some(When::Utc(until)) => start.at(*until),
+        _ => start.clone(),
+    };
+    let offset = resolver.offset(&until).map_or(0, |(offset, _)| offset);
+    let mut starts: Vec<NaiveDateTime> = Vec::new();
+    let mut more = false;
+    // Floating and unresolved times are compared with `from` as local times
+    let upcoming = |time: NaiveDateTime, resolver: &mut Resolver| resolver.utc(&start.at(time)).unwrap_or(time) >= from;
+    let mut visit = |time: NaiveDateTime| {
+        if excluded(time) || !upcoming(time, resolver) {
+            return true;
+        }
+        if starts.len() == limit {
+            more = true;
+            return false;
+        }
+        starts.push(time);
+        true
+    };
+    match rule {
+        Some(rule) => expand_rule(rule, start.naive(), offset, &mut visit),
+        None => {
+            visit(start.naive());
+        }
+    }
+    for rdate in rdates {
+        let time = rdate.naive();
+        if !excluded(time) && !starts.contains(&time) && upcoming(time, resolver) {
+            starts.push(time);
+        }
+    }
+    starts.sort();
+    more |= starts.len() > limit;
+    starts.truncate(limit);
+    let occurrences = starts
+        .into_iter()
+        .map(|time| {
+            let end = length
+                .and_then(|length| time.checked_add_signed(length))
+                .map(|end| start.at(end));
+            (start.at(time), end)
+        })
+        .collect();
+    (occurrences, more)
+}
+
+fn describe_event(event: &Component, from: NaiveDateTime, limit: usize, resolver: &mut Resolver) -> Value {
+    let mut description = Map::new();
+    let mut warnings: Vec<String> = Vec::new();
+    for (name, key) in [
+        ("UID", "uid"),
+        ("SUMMARY", "summary"),
+        ("DESCRIPTION", "description"),
+        ("LOCATION", "location"),
+        ("STATUS", "status"),
+        ("TRANSP", "transparency"),
+        ("CLASS", "class"),
+        ("URL", "url"),
+        ("SEQUENCE", "sequence"),
+    ] {
+        if let Some(text) = event.text(name) {
+            description.insert(key.into(), json!(text));
+        }
+    }
+    let categories: Vec<String> = event
+        .all("CATEGORIES")
+        .flat_map(|property| {
+            property
+                .value
+                .split(',')
+                .map(|category| unescape_text(category.trim()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    if !categories.is_empty() {
+        description.insert("categories".into(), json!(categories));
+    }
+    if let Some(organizer) = event.get("ORGANIZER") {
+        description.insert("organizer".into(), describe_person(organizer));
+    }
+    let attendees: Vec<Value> = event.all("ATTENDEE").map(describe_person).collect();
+    if !attendees.is_empty() {
+        description.insert("attendees".into(), json!(attendees));
+    }
+    for (name, key) in [
+        ("DTSTAMP", "stamp"),
+        ("CREATED", "created"),
+        ("LAST-MODIFIED", "last_modified"),
+        ("RECURRENCE-ID", "recurrence_id"),
+    ] {
+        if let Some(when) = event.get(name).and_then(|property| parse_when(property).ok()) {
+            description.insert(key.into(), resolver.describe(&when));
+        }
+    }
+
+    let start = match event.get("DTSTART").map(parse_when) {
+        Some(Ok(start)) => start,
+        Some(Err(error)) => {
+            warnings.push(error);
+            description.insert("warnings".into(), json!(warnings));
+            return Value::Object(description);
+        }
+        None => {
+            warnings.push("No DTSTART".to_string());
+            description.insert("warnings".into(), json!(warnings));
+            return Value::Object(description);
+        }
+    };
+    let all_day = matches!(start, When::Date(_));
+    description.insert("all_day".into(), json!(all_day));
+    description.insert("start".into(), resolver.describe(&start));
+    let end = match event.get("DTEND").map(parse_when) {
+        Some(Ok(end)) => Some(end),
+        Some(Err(error)) => {
+            warnings.push(error);
+            None
+        }
+        None => None,
+    };
+    let length = match (&end, event.get("DURATION")) {
+        (Some(end), _) => match (resolver.utc(end), resolver.utc(&start)) {
+            (Some(end_utc), Some(start_utc)) => Some(end_utc - start_utc),
+            _ => Some(end.naive() - start.naive()),
+        },
+        (None, Some(duration)) => {
+            let parsed = parse_duration(&duration.value);
+            if parsed.is_none() {
+                warnings.push(format!("Invalid DURATION {}", duration.value));
+            }
+            parsed
+        }
+        // An all-day event without an end takes the day
+        (None, None) if all_day => Some(Duration::days(1)),
+        (None, None) => None,
+    };
+    if let Some(end) = &end {
+        description.insert("end".into(), resolver.describe(end));
+    }
+    if let Some(length) = length {
+        if length < Duration::zero() {
+            warnings.push("The event ends before it starts".to_string());
+        }
+        description.insert("duration".into(), json!(describe_duration(length)));
+    }
+
+    let rule = match event.get("RRULE").map(|rule| (rule, parse_rule(&rule.value))) {
+        Some((property, Ok(rule))) => {
+            let mut summary = json!({ "raw": property.value, "summary": summarize_rule(&rule) });
+            if !rule.ignored.is_empty() {
+                summary["ignored_parts"] = json!(rule.ignored);
+                warnings.push(format!(
+                    "RRULE parts not applied to occurrences: {}",
+                    rule.ignored.join(", ")
+                ));
+            }
+            description.insert("recurrence".into(), summary);
+            Some(rule)
+        }
+        Some((property, Err(error))) => {
+            warnings.push(format!("RRULE {}: {}", property.value, error));
+            None
+        }
+        None => None,
+    };
+    let rdates: Vec<When> = event.all("RDATE").flat_map(parse_when_list).collect();
+    let exdates: Vec<When> = event.all("EXDATE").flat_map(parse_when_list).collect();
+    if !exdates.is_empty() {
+        let excluded: Vec<Value> = exdates.iter().map(|exdate| resolver.describe(exdate)).collect();
+        description.insert("excluded".into(), json!(excluded));
+    }
+    if rule.is_some() || !rdates.is_empty() {
+        let (occurrences, more) =
+            next_occurrences(&start, length, rule.as_ref(), &rdates, &exdates, from, limit, resolver);
+        let occurrences: Vec<Value> = occurrences
+            .iter()
+            .map(|(start, end)| {
+                let mut occurrence = json!({ "start": resolver.describe(start) });
+                if let Some(end) = end {
+                    occurrence["end"] = resolver.describe(end);
+                }
+                occurrence
+            })
+            .collect();
+        description.insert("next_occurrences".into(), json!(occurrences));
+        description.insert("more_occurrences".into(), json!(more));
+    }
+
+    let alarms: Vec<Value> = event
+        .children
+        .iter()
+        .filter(|child| child.name == "VALARM")
+        .map(describe_alarm)
+        .collect();
+    if !alarms.is_empty() {
+        description.insert("alarms".into(), json!(alarms));
+    }
+    if !warnings.is_empty() {
+        description.insert("warnings".into(), json!(warnings));
+    }
+    Value::Object(description)
+}
+
+// Calendar, time zones and events of `text`, with up to `limit` upcoming
+// occurrences per recurring event counted from `from` (UTC)
+pub fn describe_calendar(text: &str, from: NaiveDateTime, limit: usize) -> Result<Value, String> {
+    let components = parse_components(text)?;
+    let calendars: Vec<&Component> = components
+        .iter()
+        .filter(|component| component.name == "VCALENDAR")
+        .collect();
+    if calendars.is_empty() {
+        return Err("No BEGIN:VCALENDAR found".to_string());
+    }
+    let limit = limit.min(MAX_OCCURRENCES);
+
+    let zones: Vec<Zone> = calendars
+        .iter()
+        .flat_map(|calendar| calendar.children.iter())
+        .filter(|child| child.name == "VTIMEZONE")
+        .filter_map(Zone::from_component)
+        .collect();
+    let mut resolver = Resolver {
+        zones: &zones,
+        unresolved: Vec::new(),
+    };
+
+    let mut calendar_info = Map::new();
+    let mut events = Vec::new();
+    let mut other: std::collections::BTreeMap<String, usize> = std::collections::BTreeMap::new();
+    for calendar in &calendars {
+        for (name, key) in [
+            ("PRODID", "product"),
+            ("VERSION", "version"),
+            ("METHOD", "method"),
+            ("CALSCALE", "scale"),
+            ("X-WR-CALNAME", "name"),
+            ("X-WR-TIMEZONE", "timezone"),
+        ] {
+            if let Some(text) = calendar.text(name) {
+                calendar_info.entry(key).or_insert(json!(text));
+            }
+        }
+        for child in &calendar.children {
+            match child.name.as_str() {
+                "VEVENT" => events.push(describe_event(child, from, limit, &mut resolver)),
+                "VTIMEZONE" => {}
+                name => *other.entry(name.to_string()).or_default() += 1,
+            }
+        }
+    }
+
+    let mut warnings: Vec<String> = Vec::new();
+    for tzid in &resolver.unresolved {
+        warnings.push(format!(
+            "Time zone {} has no VTIMEZONE in the file; its times are left local",
+            tzid
+        ));
+    }
+    Ok(json!({
+        "calendar": calendar_info,
+        "occurrences_from": from.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        "time_zones": zones.iter().map(Zone::describe).collect::<Vec<_>>(),
+        "events": events,
+        "other_components": other,
+        "warnings": warnings
+    }))
+}
+
+fn parse_from(text: &str) -> Result<NaiveDateTime, String> {
+    let text = text.trim();
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Ok(time.naive_utc());
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN))
+        .map_err(|_| format!("Invalid start time {}; use RFC 3339 or YYYY-MM-DD", text))
+}
+
+pub fn parse_ics(text: &str) -> Result<String, String> {
+    let description = describe_calendar(text, Utc::now().naive_utc(), DEFAULT_OCCURRENCES)?;
+    crate::formatters::to_string_pretty(&description).map_err(|e| format!("Failed to format calendar output: {}", e))
+}
+
+// The calendar in `text` (or the active document's raw content) with
+// `occurrences` upcoming instances of each recurring event (10 by default,
+// at most 1000) counted from `from` (now by default)
+#[tauri::command]
+pub async fn describe_ics(
+    app: tauri::AppHandle,
+    text: Option<String>,
+    occurrences: Option<usize>,
+    from: Option<String>,
+) -> Result<Value, String> {
+    use tauri::Manager;
+    crate::run_blocking("describe_ics", move || {
+        let from = match from.as_deref().filter(|from| !from.trim().is_empty()) {
+            Some(from) => parse_from(from)?,
+            None => Utc::now().naive_utc(),
+        };
+        let text = text.filter(|text| !text.is_empty());
+        let snapshot = match text {
+            Some(_) => None,
+            None => Some(
+                app.state::<crate::AppState>()
+                    .inner()
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .snapshot_raw()?,
+            ),
+        };
+        let content = snapshot
+            .as_ref()
+            .map_or(text.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+        describe_calendar(content, from, occurrences.unwrap_or(DEFAULT_OCCURRENCES))
+    })
+    .await
+}
+
+pub struct IcsFormatter;
+
+impl Formatter for IcsFormatter {
+    fn id(&self) -> &'static str {
+        "ics"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "iCalendar (ICS) Parser"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        crate::formatters::layout_options_schema(crate::formatters::PRETTY_OPTIONS)
+    }
+
+    // Upcoming occurrences are expanded from the current time
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        parse_ics(input)
+    }
+}
+
+// Content lines for the generators here and in vcard.rs, folded at 75 octets
+// with CRLF endings as RFC 5545 and RFC 6350 require
+#[derive(Default)]
+pub struct ContentWriter {
+    out: String,
+}
+
+impl ContentWriter {
+    pub fn line(&mut self, name: &str, params: &[(&str, String)], value: &str) {
+        let mut line = name.to_string();
+        for (key, param) in params {
+            line.push(';');
+            line.push_str(key);
+            line.push('=');
+            line.push_str(&param_value(param));
+        }
+        line.push(':');
+        line.push_str(value);
+        let mut rest = line.as_str();
+        let mut limit = 75;
+        while rest.len() > limit {
+            let mut cut = limit;
+            while !rest.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            self.out.push_str(&rest[..cut]);
+            self.out.push_str("\r\n ");
+            rest = &rest[cut..];
+            // The leading space of a continuation counts towards its 75
+            limit = 74;
+        }
+        self.out.push_str(rest);
+        self.out.push_str("\r\n");
+    }
+
+    // A TEXT property, escaped
+    pub fn text(&mut self, name: &str, value: &str) {
+        self.line(name, &[], &escape_text(value));
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+// Parameter values containing ; : or , are quoted; a DQUOTE can't appear at all
+pub fn param_value(value: &str) -> String {
+    let value: String = value.chars().filter(|c| *c != '"' && !c.is_control()).collect();
+    if value.contains([';', ':', ',']) {
+        format!("\"{}\"", value)
+    } else {
+        value
+    }
+}
+
+pub fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// The first of `keys` present with a scalar value, as text
+pub fn string_field(object: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| match object.get(*key)? {
+        Value::String(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    })
+}
+
+// Strings, or arrays of them, as a list
+pub fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(text) => Some(text.clone()),
+                Value::Number(number) => Some(number.to_string()),
+                _ => None,
+            })
+            .collect(),
+        Some(Value::String(text)) => vec![text.clone()],
+        _ => Vec::new(),
+    }
+}
+
+// Version 4 UUID for components described without a UID
+pub fn random_uid() -> String {
+    let bytes: [u8; 16] = rand::random();
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-4{}-{:x}{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[13..16],
+        8 + (bytes[8] & 0x3),
+        &hex[17..20],
+        &hex[20..32]
+    )
+}
+
+// "2025-03-01", "2025-03-01T09:00[:00]", "2025-03-01T14:00:00Z", RFC 3339
+// with an offset (kept as UTC), or the basic iCalendar forms. Local times
+// take `tzid` when there is one and float otherwise
+fn text_when(text: &str, tzid: Option<&str>) -> Result<When, String> {
+    let text = text.trim();
+    for format in ["%Y-%m-%d", "%Y%m%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(text, format) {
+            return Ok(When::Date(date));
+        }
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Ok(When::Utc(time.naive_utc()));
+    }
+    let (local, utc) = match text.strip_suffix(['Z', 'z']) {
+        Some(local) => (local, true),
+        None => (text, false),
+    };
+    let time = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y%m%dT%H%M%S",
+        "%Y%m%dT%H%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(local, format).ok())
+    .ok_or_else(|| {
+        format!(
+            "Invalid time {}; use YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS, optionally with Z",
+            text
+        )
+    })?;
+    Ok(match (utc, tzid) {
+        (true, _) => When::Utc(time),
+        (false, Some(tzid)) if tzid.eq_ignore_ascii_case("UTC") => When::Utc(time),
+        (false, Some(tzid)) => When::Zoned(time, tzid.to_string()),
+        (false, None) => When::Floating(time),
+    })
+}
+
+// A time as text (see `text_when`) or in the parser's {"date"}, {"utc"} or
+// {"local", "tzid"} shape. `all_day` keeps only the date.
+fn json_when(value: &Value, tzid: Option<&str>, all_day: bool) -> Result<When, String> {
+    let when = match value {
+        Value::String(text) => text_when(text, tzid)?,
+        Value::Object(object) => {
+            let text = |key: &str| object.get(key).and_then(Value::as_str);
+            match (text("local"), text("tzid"), text("utc"), text("date")) {
+                (Some(local), Some(tzid), _, _) => text_when(local, Some(tzid))?,
+                (_, _, Some(utc), _) => text_when(utc, None)?,
+                (Some(local), None, _, _) => text_when(local, tzid)?,
+                (_, _, _, Some(date)) => text_when(date, None)?,
+                _ => return Err("A time object needs date, utc or local".to_string()),
+            }
+        }
+        _ => return Err(format!("Invalid time {}", value)),
+    };
+    Ok(match when {
+        When::Date(_) => when,
+        other if all_day => When::Date(other.naive().date()),
+        other => other,
+    })
+}
+
+fn when_value(when: &When) -> (Vec<(&'static str, String)>, String) {
+    match when {
+        When::Date(date) => (vec![("VALUE", "DATE".to_string())], date.format("%Y%m%d").to_string()),
+        When::Floating(time) => (Vec::new(), time.format("%Y%m%dT%H%M%S").to_string()),
+        When::Utc(time) => (Vec::new(), time.format("%Y%m%dT%H%M%SZ").to_string()),
+        When::Zoned(time, tzid) => (vec![("TZID", tzid.clone())], time.format("%Y%m%dT%H%M%S").to_string()),
+    }
+}
+
+fn write_when(writer: &mut ContentWriter, name: &str, when: &When) {
+    let (params, value) = when_value(when);
+    writer.line(name, &params, &value);
+}
+
+fn iso_duration(duration: Duration) -> String {
+    let sign = if duration < Duration::zero() { "-" } else { "" };
+    let seconds = duration.num_seconds().unsigned_abs();
+    let (days, hours, minutes, seconds) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+    );
+    let mut out = format!("{}P", sign);
+    if days > 0 {
+        out.push_str(&format!("{}D", days));
+    }
+    if hours + minutes + seconds > 0 || days == 0 {
+        out.push('T');
+        for (count, unit) in [(hours, 'H'), (minutes, 'M'), (seconds, 'S')] {
+            if count > 0 {
+                out.push_str(&format!("{}{}", count, unit));
+            }
+        }
+        if out.ends_with('T') {
+            out.push_str("0S");
+        }
+    }
+    out
+}
+
+// An RFC 5545 duration, or the parser's readable "1d 2h" form
+fn text_duration(text: &str) -> Option<Duration> {
+    if let Some(duration) = parse_duration(text) {
+        return Some(duration);
+    }
+    let mut total = Duration::zero();
+    for part in text.split_whitespace() {
+        let split = part.find(|c: char| !c.is_ascii_digit()).filter(|index| *index > 0)?;
+        let count: i64 = part[..split].parse().ok()?;
+        total += match &part[split..] {
+            "w" => Duration::try_weeks(count)?,
+            "d" => Duration::try_days(count)?,
+            "h" => Duration::try_hours(count)?,
+            "m" => Duration::try_minutes(count)?,
+            "s" => Duration::try_seconds(count)?,
+            _ => return None,
+        };
+    }
+    (!text.trim().is_empty()).then_some(total)
+}
+
+// RRULE text from a string ("FREQ=WEEKLY;COUNT=3"), the parser's
+// {"raw": ...}, or an object of parts such as {"freq": "weekly", "by_day":
+// ["MO", "WE"], "until": "2025-06-30"}. The result is checked by parsing it.
+fn json_rule(value: &Value) -> Result<String, String> {
+    let text = match value {
+        Value::String(text) => text.trim().trim_start_matches("RRULE:").to_string(),
+        Value::Object(object) if object.contains_key("raw") => object["raw"]
+            .as_str()
+            .ok_or("recurrence.raw must be a string")?
+            .trim()
+            .to_string(),
+        Value::Object(object) => {
+            let mut parts = Vec::new();
+            for (key, value) in object {
+                let key = key.replace('_', "").to_ascii_uppercase();
+                let value = match (key.as_str(), value) {
+                    ("UNTIL", Value::String(until)) => {
+                        let (_, until) = when_value(&text_when(until, None)?);
+                        until
+                    }
+                    (_, Value::Array(_)) => string_list(Some(value)).join(","),
+                    (_, Value::String(text)) => text.clone(),
+                    (_, Value::Number(number)) => number.to_string(),
+                    _ => return Err(format!("Invalid recurrence part {}", key)),
+                };
+                parts.push((key, value.to_ascii_uppercase()));
+            }
+            // FREQ leads by convention
+            parts.sort_by_key(|(key, _)| key != "FREQ");
+            parts
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(";")
+        }
+        _ => return Err("The recurrence rule must be a string or an object".to_string()),
+    };
+    parse_rule(&text).map_err(|e| format!("Invalid recurrence rule {}: {}", text, e))?;
+    Ok(text)
+}
+
+// An address ("jane@example.com") or the parser's {"email", "name", "role",
+// "status", "rsvp", "type"}
+fn write_person(writer: &mut ContentWriter, name: &str, value: &Value) -> Result<(), String> {
+    let empty = Map::new();
+    let (address, object) = match value {
+        Value::String(address) => (address.trim().to_string(), &empty),
+        Value::Object(object) => (
+            string_field(object, &["email", "address"]).ok_or_else(|| format!("{} needs an email", name))?,
+            object,
+        ),
+        _ => return Err(format!("{} must be an email address or an object", name)),
+    };
+    let mut params = Vec::new();
+    if let Some(common_name) = string_field(object, &["name"]) {
+        params.push(("CN", common_name));
+    }
+    for (key, param) in [
+        ("role", "ROLE"),
+        ("status", "PARTSTAT"),
+        ("rsvp", "RSVP"),
+        ("type", "CUTYPE"),
+    ] {
+        if let Some(value) = string_field(object, &[key]) {
+            params.push((param, value.to_ascii_uppercase()));
+        }
+    }
+    let address = if address.contains(':') {
+        address
+    } else {
+        format!("mailto:{}", address)
+    };
+    writer.line(name, &params, &address);
+    Ok(())
+}
+
+fn write_alarm(writer: &mut ContentWriter, alarm: &Value, summary: Option<&str>) -> Result<(), String> {
+    let alarm = alarm.as_object().ok_or("Each alarm must be an object")?;
+    let action = string_field(alarm, &["action"])
+        .unwrap_or_else(|| "DISPLAY".to_string())
+        .to_ascii_uppercase();
+    let trigger = match alarm.get("trigger") {
+        Some(Value::String(trigger)) => trigger.trim().to_string(),
+        Some(Value::Object(trigger)) => string_field(trigger, &["value"]).ok_or("trigger.value is missing")?,
+        _ => return Err("Each alarm needs a trigger such as -PT15M".to_string()),
+    };
+    writer.line("BEGIN", &[], "VALARM");
+    writer.line("ACTION", &[], &action);
+    match (parse_duration(&trigger), text_when(&trigger, None)) {
+        (Some(_), _) => {
+            let params = match string_field(alarm, &["related"]) {
+                Some(related) if related.eq_ignore_ascii_case("end") => vec![("RELATED", "END".to_string())],
+                _ => Vec::new(),
+            };
+            writer.line("TRIGGER", &params, &trigger);
+        }
+        (None, Ok(When::Utc(time))) => writer.line(
+            "TRIGGER",
+            &[("VALUE", "DATE-TIME".to_string())],
+            &time.format("%Y%m%dT%H%M%SZ").to_string(),
+        ),
+        _ => {
+            return Err(format!(
+                "Invalid alarm trigger {}; use a duration like -PT15M or a UTC time",
+                trigger
+            ))
+        }
+    }
+    let description = string_field(alarm, &["description"]).or_else(|| summary.map(str::to_string));
+    match description {
+        Some(description) => writer.text("DESCRIPTION", &description),
+        // DISPLAY and EMAIL alarms must have one
+        None if action != "AUDIO" => writer.text("DESCRIPTION", "Reminder"),
+        None => {}
+    }
+    writer.line("END", &[], "VALARM");
+    Ok(())
+}
+
+fn write_event(writer: &mut ContentWriter, event: &Value, now: NaiveDateTime) -> Result<(), String> {
+    let event = event.as_object().ok_or("Each event must be an object")?;
+    let tzid = string_field(event, &["tzid", "timezone"]);
+    let all_day = event.get("all_day").and_then(Value::as_bool).unwrap_or(false);
+    let time = |key: &str, all_day: bool| -> Result<Option<When>, String> {
+        match event.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => json_when(value, tzid.as_deref(), all_day)
+                .map(Some)
+                .map_err(|e| format!("{}: {}", key, e)),
+        }
+    };
+
+    writer.line("BEGIN", &[], "VEVENT");
+    let uid = string_field(event, &["uid"]).unwrap_or_else(|| format!("{}@devmate", random_uid()));
+    writer.text("UID", &uid);
+    match time("stamp", false)? {
+        Some(stamp) => write_when(writer, "DTSTAMP", &stamp),
+        None => writer.line("DTSTAMP", &[], &now.format("%Y%m%dT%H%M%SZ").to_string()),
+    }
+    let start = time("start", all_day)?.ok_or("start is missing")?;
+    write_when(writer, "DTSTART", &start);
+    match (time("end", matches!(start, When::Date(_)))?, event.get("duration")) {
+        (Some(end), _) => {
+            if end.naive() < start.naive() && std::mem::discriminant(&end) == std::mem::discriminant(&start) {
+                return Err("end is before start".to_string());
+            }
+            write_when(writer, "DTEND", &end);
+        }
+        (None, Some(Value::String(duration))) => {
+            let duration = text_duration(duration)
+                .ok_or_else(|| format!("Invalid duration {}; use an ISO 8601 duration like PT1H30M", duration))?;
+            writer.line("DURATION", &[], &iso_duration(duration));
+        }
+        (None, Some(other)) if !other.is_null() => return Err("duration must be a string like PT1H".to_string()),
+        _ => {}
+    }
+    if let Some(recurrence_id) = time("recurrence_id", false)? {
+        write_when(writer, "RECURRENCE-ID", &recurrence_id);
+    }
+    for key in ["created", "last_modified"] {
+        if let Some(when) = time(key, false)? {
+            write_when(writer, &key.replace('_', "-").to_ascii_uppercase(), &when);
+        }
+    }
+
+    let summary = string_field(event, &["summary", "title"]);
+    if let Some(summary) = &summary {
+        writer.text("SUMMARY", summary);
+    }
+    for (key, name) in [("description", "DESCRIPTION"), ("location", "LOCATION")] {
+        if let Some(text) = string_field(event, &[key]) {
+            writer.text(name, &text);
+        }
+    }
+    for (key, name) in [("status", "STATUS"), ("transparency", "TRANSP"), ("class", "CLASS")] {
+        if let Some(text) = string_field(event, &[key]) {
+            writer.line(name, &[], &text.to_ascii_uppercase());
+        }
+    }
+    if let Some(url) = string_field(event, &["url"]) {
+        writer.line("URL", &[], &url);
+    }
+    if let Some(sequence) = string_field(event, &["sequence"]) {
+        let sequence: u32 = sequence.parse().map_err(|_| "sequence must be a whole number")?;
+        writer.line("SEQUENCE", &[], &sequence.to_string());
+    }
+    let categories = string_list(event.get("categories"));
+    if !categories.is_empty() {
+        let categories: Vec<String> = categories.iter().map(|category| escape_text(category)).collect();
+        writer.line("CATEGORIES", &[], &categories.join(","));
+    }
+
+    if let Some(rule) = event.get("rrule").or_else(|| event.get("recurrence")) {
+        writer.line("RRULE", &[], &json_rule(rule)?);
+    }
+    for (keys, name) in [(&["exdates", "excluded"][..], "EXDATE"), (&["rdates"][..], "RDATE")] {
+        let Some(values) = keys.iter().find_map(|key| event.get(*key)) else {
+            continue;
+        };
+        let values = match values {
+            Value::Array(values) => values.as_slice(),
+            single => std::slice::from_ref(single),
+        };
+        for value in values {
+            // Exclusions line up with the start, so a date-only event excludes dates
+            let when = json_when(value, tzid.as_deref(), matches!(start, When::Date(_)))
+                .map_err(|e| format!("{}: {}", keys[0], e))?;
+            write_when(writer, name, &when);
+        }
+    }
+
+    if let Some(organizer) = event.get("organizer").filter(|organizer| !organizer.is_null()) {
+        write_person(writer, "ORGANIZER", organizer)?;
+    }
+    if let Some(attendees) = event.get("attendees") {
+        let attendees = attendees.as_array().ok_or("attendees must be an array")?;
+        for attendee in attendees {
+            write_person(writer, "ATTENDEE", attendee)?;
+        }
+    }
+    write_extra_properties(writer, event)?;
+    if let Some(alarms) = event.get("alarms") {
+        let alarms = alarms.as_array().ok_or("alarms must be an array")?;
+        for alarm in alarms {
+            write_alarm(writer, alarm, summary.as_deref())?;
+        }
+    }
+    writer.line("END", &[], "VEVENT");
+    Ok(())
+}
+
+// {"properties": {"X-APP-ID": "42"}}: extra TEXT properties, a list value
+// giving one line each
+pub fn write_extra_properties(writer: &mut ContentWriter, object: &Map<String, Value>) -> Result<(), String> {
+    let Some(properties) = object.get("properties") else {
+        return Ok(());
+    };
+    let properties = properties.as_object().ok_or("properties must be an object")?;
+    for (name, value) in properties {
+        let name = name.trim().to_ascii_uppercase();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid property name {}", name));
+        }
+        for value in string_list(Some(value)) {
+            writer.text(&name, &value);
+        }
+    }
+    Ok(())
+}
+
+fn write_zone(writer: &mut ContentWriter, zone: &Value) -> Result<(), String> {
+    let zone = zone.as_object().ok_or("Each time zone must be an object")?;
+    let tzid = string_field(zone, &["tzid"]).ok_or("A time zone needs a tzid")?;
+    let observances = zone
+        .get("observances")
+        .and_then(Value::as_array)
+        .filter(|observances| !observances.is_empty())
+        .ok_or("A time zone needs observances")?;
+    writer.line("BEGIN", &[], "VTIMEZONE");
+    writer.text("TZID", &tzid);
+    for observance in observances {
+        let observance = observance
+            .as_object()
+            .ok_or_else(|| "Each observance must be an object".to_string())?;
+        let kind = match string_field(observance, &["kind"])
+            .as_deref()
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("daylight") => "DAYLIGHT",
+            Some("standard") | None => "STANDARD",
+            Some(other) => return Err(format!("Unknown observance kind {}", other)),
+        };
+        let starts = observance
+            .get("starts")
+            .ok_or_else(|| "An observance needs starts".to_string())
+            .and_then(|starts| json_when(starts, None, false))?;
+        let offset = |key: &str| -> Result<String, String> {
+            string_field(observance, &[key])
+                .and_then(|offset| parse_utc_offset(&offset.replace(':', "")))
+                .map(|seconds| {
+                    let sign = if seconds < 0 { '-' } else { '+' };
+                    let seconds = seconds.abs();
+                    match seconds % 60 {
+                        0 => format!("{}{:02}{:02}", sign, seconds / 3600, seconds % 3600 / 60),
+                        rest => format!("{}{:02}{:02}{:02}", sign, seconds / 3600, seconds % 3600 / 60, rest),
+                    }
+                })
+                .ok_or_else(|| format!("{} must be an offset like +01:00", key))
+        };
+        writer.line("BEGIN", &[], kind);
+        writer.line("DTSTART", &[], &starts.naive().format("%Y%m%dT%H%M%S").to_string());
+        writer.line("TZOFFSETFROM", &[], &offset("offset_from")?);
+        writer.line("TZOFFSETTO", &[], &offset("offset_to")?);
+        if let Some(rule) = observance.get("rrule") {
+            writer.line("RRULE", &[], &json_rule(rule)?);
+        }
+        if let Some(name) = string_field(observance, &["name"]) {
+            writer.text("TZNAME", &name);
+        }
+        writer.line("END", &[], kind);
+    }
+    writer.line("END", &[], "VTIMEZONE");
+    Ok(())
+}
+
+fn json_list<'a>(object: &'a Map<String, Value>, key: &str) -> Result<&'a [Value], String> {
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(&[]),
+        Some(Value::Array(items)) => Ok(items),
+        Some(_) => Err(format!("{} must be an array", key)),
+    }
+}
+
+// iCalendar text from a JSON description: {"calendar": {...}, "time_zones":
+// [...], "events": [...]} as the parser produces it (calendar keys may also
+// sit at the top level), a bare array of events, or a single event
+pub fn generate_ics(input: &str) -> Result<String, String> {
+    let description: Value = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let empty = Map::new();
+    let (calendar, events, zones): (&Map<String, Value>, &[Value], &[Value]) = match &description {
+        Value::Array(events) => (&empty, events, &[]),
+        Value::Object(object)
+            if object.contains_key("events")
+                || object.contains_key("calendar")
+                || object.contains_key("time_zones") =>
+        {
+            (object, json_list(object, "events")?, json_list(object, "time_zones")?)
+        }
+        Value::Object(_) => (&empty, std::slice::from_ref(&description), &[]),
+        _ => return Err("Describe the calendar as a JSON object or an array of events".to_string()),
+    };
+    if events.is_empty() && zones.is_empty() {
+        return Err("No events to write".to_string());
+    }
+    let settings = calendar.get("calendar").and_then(Value::as_object).unwrap_or(calendar);
+    let setting = |keys: &[&str]| string_field(settings, keys).or_else(|| string_field(calendar, keys));
+
+    let mut writer = ContentWriter::default();
+    writer.line("BEGIN", &[], "VCALENDAR");
+    writer.line("VERSION", &[], "2.0");
+    writer.text(
+        "PRODID",
+        &setting(&["product", "prodid"]).unwrap_or_else(|| "-//devmate//devmate//EN".to_string()),
+    );
+    writer.line(
+        "CALSCALE",
+        &[],
+        &setting(&["scale"]).unwrap_or_else(|| "GREGORIAN".to_string()),
+    );
+    if let Some(method) = setting(&["method"]) {
+        writer.line("METHOD", &[], &method.to_ascii_uppercase());
+    }
+    if let Some(name) = setting(&["name"]) {
+        writer.text("X-WR-CALNAME", &name);
+    }
+    if let Some(timezone) = setting(&["timezone"]) {
+        writer.text("X-WR-TIMEZONE", &timezone);
+    }
+    for (index, zone) in zones.iter().enumerate() {
+        write_zone(&mut writer, zone).map_err(|e| {
+            let tzid = zone.get("tzid").and_then(Value::as_str).map(str::to_string);
+            format!("Time zone {}: {}", tzid.unwrap_or_else(|| (index + 1).to_string()), e)
+        })?;
+    }
+    let now = Utc::now().naive_utc();
+    for (index, event) in events.iter().enumerate() {
+        write_event(&mut writer, event, now).map_err(|e| format!("Event {}: {}", index + 1, e))?;
+    }
+    writer.line("END", &[], "VCALENDAR");
+    Ok(writer.finish())
+}
+
+pub struct IcsGeneratorFormatter;
+
+impl Formatter for IcsGeneratorFormatter {
+    fn id(&self) -> &'static str {
+        "json-to-ics"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "JSON to iCalendar (ICS)"
+    }
+
+    fn input_kind(&self) -> &'static str {
+        "json"
+    }
+
+    // Events without a DTSTAMP are stamped with the current time
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        generate_ics(input)
+    }
+}
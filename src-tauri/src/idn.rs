@@ -0,0 +1,68 @@
+use std::collections::BTreeSet;
+use unicode_script::{Script, UnicodeScript};
+
+// Non-Latin letters that render (near-)identically to ASCII letters
+const LATIN_LOOKALIKES: &[(char, char)] = &[
+    ('а', 'a'), ('е', 'e'), ('о', 'o'), ('р', 'p'), ('с', 'c'), ('у', 'y'), ('х', 'x'),
+    ('і', 'i'), ('ј', 'j'), ('ѕ', 's'), ('һ', 'h'), ('ԁ', 'd'), ('ԛ', 'q'), ('ԝ', 'w'),
+    ('α', 'a'), ('ο', 'o'), ('ν', 'v'), ('ι', 'i'), ('κ', 'k'), ('τ', 't'), ('ρ', 'p'),
+];
+
+fn label_scripts(label: &str) -> BTreeSet<String> {
+    label
+        .chars()
+        .map(|c| c.script())
+        .filter(|s| *s != Script::Common && *s != Script::Inherited)
+        .map(|s| s.full_name().to_string())
+        .collect()
+}
+
+fn homograph_warnings(unicode_domain: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for label in unicode_domain.split('.') {
+        let scripts = label_scripts(label);
+        if scripts.len() > 1 {
+            warnings.push(format!(
+                "Label '{}' mixes scripts: {}",
+                label,
+                scripts.into_iter().collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        let lookalikes: Vec<String> = label
+            .chars()
+            .filter_map(|c| {
+                LATIN_LOOKALIKES
+                    .iter()
+                    .find(|(lookalike, _)| *lookalike == c)
+                    .map(|(lookalike, latin)| format!("'{}' (U+{:04X}) looks like '{}'", lookalike, *lookalike as u32, latin))
+            })
+            .collect();
+        if !lookalikes.is_empty() {
+            warnings.push(format!("Label '{}' contains Latin lookalikes: {}", label, lookalikes.join(", ")));
+        }
+    }
+    warnings
+}
+
+#[tauri::command]
+pub fn convert_idn(domain: String) -> Result<serde_json::Value, String> {
+    let domain = domain.trim();
+    if domain.is_empty() {
+        return Err("Empty domain name".to_string());
+    }
+
+    let ascii = idna::domain_to_ascii(domain).map_err(|e| format!("Invalid domain name: {:?}", e))?;
+    let (unicode, result) = idna::domain_to_unicode(&ascii);
+    result.map_err(|e| format!("Invalid punycode in domain: {:?}", e))?;
+
+    let warnings = homograph_warnings(&unicode);
+    Ok(serde_json::json!({
+        "input": domain,
+        "ascii": ascii,
+        "unicode": unicode,
+        "is_idn": ascii.split('.').any(|label| label.starts_with("xn--")),
+        "suspicious": !warnings.is_empty(),
+        "warnings": warnings
+    }))
+}
@@ -0,0 +1,124 @@
+// Long-running work runs as a job: the starting command returns an id straight
+// away, the outcome arrives as a `job://finished` event, and `cancel_job` asks
+// the work to stop at its next checkpoint.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::responses::{JobFinished, JobInfo};
+
+pub const JOB_FINISHED_EVENT: &str = "job://finished";
+
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    // Checkpoint for job loops: bails out with `?` once cancellation was requested
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err("Job cancelled".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+struct JobEntry {
+    kind: String,
+    token: CancelToken,
+    started: Instant,
+}
+
+#[derive(Default)]
+pub struct JobRegistry {
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<u64, JobEntry>>,
+}
+
+pub fn spawn_job<F>(app: &AppHandle, kind: &str, work: F) -> Result<u64, String>
+where
+    F: FnOnce(&AppHandle, &CancelToken) -> Result<serde_json::Value, String> + Send + 'static,
+{
+    let registry = app.state::<JobRegistry>();
+    let id = registry.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let token = CancelToken::default();
+    registry.jobs.lock().map_err(|e| e.to_string())?.insert(
+        id,
+        JobEntry {
+            kind: kind.to_string(),
+            token: token.clone(),
+            started: Instant::now(),
+        },
+    );
+
+    let app = app.clone();
+    let job_kind = kind.to_string();
+    // Jobs share the task queue's concurrency limit with blocking commands
+    tauri::async_runtime::spawn(crate::tasks::queue().run(kind, move || {
+        // Cancelled while still queued: don't start the work at all
+        let outcome = token.check().and_then(|_| work(&app, &token));
+        if let Ok(mut jobs) = app.state::<JobRegistry>().jobs.lock() {
+            jobs.remove(&id);
+        }
+
+        let (status, result, error) = match outcome {
+            Ok(result) => ("completed", Some(result), None),
+            Err(_) if token.is_cancelled() => ("cancelled", None, None),
+            Err(error) => ("failed", None, Some(error)),
+        };
+        let payload = JobFinished {
+            id,
+            kind: job_kind,
+            status: status.to_string(),
+            result,
+            error,
+        };
+        let _ = app.emit(JOB_FINISHED_EVENT, payload);
+        Ok(())
+    }));
+
+    Ok(id)
+}
+
+// Returns false when the job is unknown, usually because it already finished
+pub fn cancel(registry: &JobRegistry, id: u64) -> Result<bool, String> {
+    let jobs = registry.jobs.lock().map_err(|e| e.to_string())?;
+    match jobs.get(&id) {
+        Some(job) => {
+            job.token.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub fn cancel_job(id: u64, registry: State<JobRegistry>) -> Result<bool, String> {
+    cancel(&registry, id)
+}
+
+#[tauri::command]
+pub fn list_jobs(registry: State<JobRegistry>) -> Result<Vec<JobInfo>, String> {
+    let jobs = registry.jobs.lock().map_err(|e| e.to_string())?;
+    let mut listed: Vec<JobInfo> = jobs
+        .iter()
+        .map(|(id, job)| JobInfo {
+            id: *id,
+            kind: job.kind.clone(),
+            elapsed_ms: job.started.elapsed().as_millis() as u64,
+            cancelling: job.token.is_cancelled(),
+        })
+        .collect();
+    listed.sort_by_key(|job| job.id);
+    Ok(listed)
+}
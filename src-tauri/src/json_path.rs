@@ -0,0 +1,82 @@
+// Minimal JSONPath subset: `$`, `.key`, `['key']`, `[index]` (also accepts bare `a.b[0]`)
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+pub fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let chars: Vec<char> = path.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' => i += 1,
+            '[' => {
+                let close = chars[i..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .map(|p| p + i)
+                    .ok_or_else(|| format!("Unclosed '[' in path: {}", path))?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let inner = inner.trim();
+                if let Some(quoted) = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+                {
+                    segments.push(Segment::Key(quoted.to_string()));
+                } else {
+                    let index = inner
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid array index '{}' in path", inner))?;
+                    segments.push(Segment::Index(index));
+                }
+                i = close + 1;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                segments.push(Segment::Key(chars[start..i].iter().collect()));
+            }
+        }
+    }
+
+    Ok(segments)
+}
+
+// Inverse of parse_path; keys that wouldn't survive the bare form are bracket-quoted
+pub fn format_path(segments: &[Segment]) -> String {
+    let mut path = String::from("$");
+    for segment in segments {
+        match segment {
+            Segment::Key(key) if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') => {
+                path.push('.');
+                path.push_str(key);
+            }
+            Segment::Key(key) if key.contains('\'') => path.push_str(&format!("[\"{}\"]", key)),
+            Segment::Key(key) => path.push_str(&format!("['{}']", key)),
+            Segment::Index(index) => path.push_str(&format!("[{}]", index)),
+        }
+    }
+    path
+}
+
+pub fn get<'a>(value: &'a serde_json::Value, segments: &[Segment]) -> Option<&'a serde_json::Value> {
+    segments.iter().try_fold(value, |current, segment| match segment {
+        Segment::Key(key) => current.get(key.as_str()),
+        Segment::Index(index) => current.get(*index),
+    })
+}
+
+pub fn get_mut<'a>(value: &'a mut serde_json::Value, segments: &[Segment]) -> Option<&'a mut serde_json::Value> {
+    segments.iter().try_fold(value, |current, segment| match segment {
+        Segment::Key(key) => current.get_mut(key.as_str()),
+        Segment::Index(index) => current.get_mut(*index),
+    })
+}
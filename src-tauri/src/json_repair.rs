@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+struct Repairer {
+    chars: Vec<char>,
+    out: String,
+    fixes: BTreeMap<&'static str, usize>,
+}
+
+impl Repairer {
+    fn new(text: &str) -> Self {
+        Repairer {
+            chars: text.chars().collect(),
+            out: String::with_capacity(text.len()),
+            fixes: BTreeMap::new(),
+        }
+    }
+
+    fn fix(&mut self, description: &'static str) {
+        *self.fixes.entry(description).or_insert(0) += 1;
+    }
+
+    // Index just past a comment starting at `i`, if there is one
+    fn comment_end(&self, i: usize) -> Option<usize> {
+        match (self.chars.get(i), self.chars.get(i + 1)) {
+            (Some('/'), Some('/')) | (Some('#'), _) => {
+                let mut j = i;
+                while j < self.chars.len() && self.chars[j] != '\n' {
+                    j += 1;
+                }
+                Some(j)
+            }
+            (Some('/'), Some('*')) => {
+                let mut j = i + 2;
+                while j + 1 < self.chars.len() && !(self.chars[j] == '*' && self.chars[j + 1] == '/') {
+                    j += 1;
+                }
+                Some((j + 2).min(self.chars.len()))
+            }
+            _ => None,
+        }
+    }
+
+    // Next character that isn't whitespace or part of a comment
+    fn next_significant(&self, mut i: usize) -> Option<char> {
+        while i < self.chars.len() {
+            if self.chars[i].is_whitespace() {
+                i += 1;
+            } else if let Some(end) = self.comment_end(i) {
+                i = end;
+            } else {
+                return Some(self.chars[i]);
+            }
+        }
+        None
+    }
+
+    fn copy_double_quoted(&mut self, mut i: usize) -> usize {
+        self.out.push('"');
+        i += 1;
+        while i < self.chars.len() {
+            let c = self.chars[i];
+            match c {
+                '\\' => {
+                    self.out.push(c);
+                    if let Some(next) = self.chars.get(i + 1) {
+                        self.out.push(*next);
+                    }
+                    i += 2;
+                    continue;
+                }
+                '"' => {
+                    self.out.push('"');
+                    return i + 1;
+                }
+                '\n' => {
+                    self.out.push_str("\\n");
+                    self.fix("Escaped raw newlines inside strings");
+                }
+                _ => self.out.push(c),
+            }
+            i += 1;
+        }
+        self.out.push('"');
+        self.fix("Closed unterminated string");
+        i
+    }
+
+    fn convert_single_quoted(&mut self, mut i: usize) -> usize {
+        self.fix("Converted single-quoted strings");
+        self.out.push('"');
+        i += 1;
+        while i < self.chars.len() {
+            let c = self.chars[i];
+            match c {
+                '\\' if self.chars.get(i + 1) == Some(&'\'') => {
+                    self.out.push('\'');
+                    i += 2;
+                    continue;
+                }
+                '\\' => {
+                    self.out.push(c);
+                    if let Some(next) = self.chars.get(i + 1) {
+                        self.out.push(*next);
+                    }
+                    i += 2;
+                    continue;
+                }
+                '"' => self.out.push_str("\\\""),
+                '\'' => {
+                    self.out.push('"');
+                    return i + 1;
+                }
+                _ => self.out.push(c),
+            }
+            i += 1;
+        }
+        self.out.push('"');
+        i
+    }
+
+    fn convert_word(&mut self, start: usize) -> usize {
+        let mut end = start;
+        while end < self.chars.len() && (self.chars[end].is_alphanumeric() || self.chars[end] == '_' || self.chars[end] == '$') {
+            end += 1;
+        }
+        let word: String = self.chars[start..end].iter().collect();
+
+        if self.next_significant(end) == Some(':') {
+            self.out.push('"');
+            self.out.push_str(&word);
+            self.out.push('"');
+            self.fix("Quoted unquoted keys");
+            return end;
+        }
+
+        match word.as_str() {
+            "true" | "false" | "null" => self.out.push_str(&word),
+            "True" => {
+                self.out.push_str("true");
+                self.fix("Replaced Python literals (True/False/None)");
+            }
+            "False" => {
+                self.out.push_str("false");
+                self.fix("Replaced Python literals (True/False/None)");
+            }
+            "None" => {
+                self.out.push_str("null");
+                self.fix("Replaced Python literals (True/False/None)");
+            }
+            "undefined" | "NaN" | "Infinity" => {
+                self.out.push_str("null");
+                self.fix("Replaced undefined/NaN/Infinity with null");
+            }
+            _ => {
+                self.out.push('"');
+                self.out.push_str(&word);
+                self.out.push('"');
+                self.fix("Quoted bare words");
+            }
+        }
+        end
+    }
+
+    fn run(mut self) -> (String, Vec<String>) {
+        let mut i = 0;
+        while i < self.chars.len() {
+            let c = self.chars[i];
+            if let Some(end) = self.comment_end(i) {
+                self.fix("Removed comments");
+                i = end;
+                continue;
+            }
+            match c {
+                '"' => i = self.copy_double_quoted(i),
+                '\'' => i = self.convert_single_quoted(i),
+                ',' => {
+                    match self.next_significant(i + 1) {
+                        Some('}') | Some(']') | None => self.fix("Removed trailing commas"),
+                        _ => self.out.push(','),
+                    }
+                    i += 1;
+                }
+                c if c.is_ascii_digit() || c == '-' || c == '+' => {
+                    let start = i;
+                    while i < self.chars.len() && (self.chars[i].is_ascii_alphanumeric() || matches!(self.chars[i], '.' | '-' | '+')) {
+                        i += 1;
+                    }
+                    let number: String = self.chars[start..i].iter().collect();
+                    self.out.push_str(number.trim_start_matches('+'));
+                }
+                c if c.is_alphabetic() || c == '_' || c == '$' => i = self.convert_word(i),
+                _ => {
+                    self.out.push(c);
+                    i += 1;
+                }
+            }
+        }
+
+        let fixes = self
+            .fixes
+            .iter()
+            .map(|(fix, count)| format!("{} ({})", fix, count))
+            .collect();
+        (self.out, fixes)
+    }
+}
+
+pub fn repair(text: &str) -> Result<(String, Vec<String>), String> {
+    if text.trim().is_empty() {
+        return Err("Empty JSON input".to_string());
+    }
+
+    let (repaired, fixes) = Repairer::new(text).run();
+    let value: serde_json::Value = serde_json::from_str(&repaired)
+        .map_err(|e| format!("Could not repair JSON automatically: {}", e))?;
+    let pretty = crate::formatters::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to format JSON: {}", e))?;
+    Ok((pretty, fixes))
+}
+
+fn repair_json_blocking(text: String, state: &AppState) -> Result<serde_json::Value, String> {
+    // Stored content is repaired from a snapshot, without copying it or
+    // holding the lock while the repair runs
+    let snapshot = if text.is_empty() {
+        Some(state.lock().map_err(|e| e.to_string())?.snapshot_raw()?)
+    } else {
+        None
+    };
+
+    let (repaired, fixes) = repair(snapshot.as_ref().map_or(text.as_str(), |snapshot| snapshot.text.as_str()))?;
+
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let document = match &snapshot {
+        Some(snapshot) => storage.snapshot_document(snapshot)?,
+        None => storage.active_mut(),
+    };
+    document.edit(Slot::Formatted, repaired.clone());
+
+    Ok(serde_json::json!({
+        "repaired": repaired,
+        "fixes": fixes
+    }))
+}
+
+#[tauri::command]
+pub async fn repair_json(app: AppHandle, text: String) -> Result<serde_json::Value, String> {
+    crate::run_blocking("repair_json", move || repair_json_blocking(text, app.state::<AppState>().inner())).await
+}
@@ -0,0 +1,155 @@
+use serde::Deserialize;
+use std::cmp::Ordering;
+use tauri::{AppHandle, Manager};
+
+use crate::json_path::{self, Segment};
+use crate::undo::Slot;
+use crate::AppState;
+
+#[derive(Deserialize)]
+pub struct SortKey {
+    pub path: String,
+    #[serde(default)]
+    pub descending: bool,
+    // "string" | "numeric" | "date" | "natural"
+    #[serde(default = "default_comparator")]
+    pub comparator: String,
+}
+
+fn default_comparator() -> String {
+    "string".to_string()
+}
+
+fn parse_date(value: &str) -> Option<i64> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.timestamp_millis());
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc2822(value) {
+        return Some(dt.timestamp_millis());
+    }
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        return Some(dt.and_utc().timestamp_millis());
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis())
+}
+
+fn as_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// Missing values always sort last regardless of direction
+fn compare_values(a: Option<&serde_json::Value>, b: Option<&serde_json::Value>, key: &SortKey) -> Ordering {
+    let (a, b) = match (a.filter(|v| !v.is_null()), b.filter(|v| !v.is_null())) {
+        (None, None) => return Ordering::Equal,
+        (None, Some(_)) => return Ordering::Greater,
+        (Some(_), None) => return Ordering::Less,
+        (Some(a), Some(b)) => (a, b),
+    };
+
+    let ordering = match key.comparator.as_str() {
+        "numeric" => {
+            let a_num = a.as_f64().or_else(|| as_text(a).trim().parse().ok());
+            let b_num = b.as_f64().or_else(|| as_text(b).trim().parse().ok());
+            // Non-numbers sort before numbers; total_cmp gives NaN a fixed place
+            match (a_num, b_num) {
+                (Some(a_num), Some(b_num)) => a_num.total_cmp(&b_num),
+                (a_num, b_num) => a_num.is_some().cmp(&b_num.is_some()),
+            }
+        }
+        "date" => parse_date(&as_text(a)).cmp(&parse_date(&as_text(b))),
+        "natural" => crate::lines::natural_cmp(&as_text(a), &as_text(b)),
+        _ => as_text(a).cmp(&as_text(b)),
+    };
+
+    if key.descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+pub fn sort_array(value: &mut serde_json::Value, array_path: &str, keys: &[SortKey]) -> Result<usize, String> {
+    if keys.is_empty() {
+        return Err("At least one sort key is required".to_string());
+    }
+    for key in keys {
+        if !matches!(key.comparator.as_str(), "string" | "numeric" | "date" | "natural") {
+            return Err(format!("Unknown comparator: {}", key.comparator));
+        }
+    }
+
+    let target_path = json_path::parse_path(array_path)?;
+    let key_paths: Vec<Vec<Segment>> = keys
+        .iter()
+        .map(|k| json_path::parse_path(&k.path))
+        .collect::<Result<_, _>>()?;
+
+    let array = json_path::get_mut(value, &target_path)
+        .ok_or_else(|| format!("Path not found: {}", array_path))?
+        .as_array_mut()
+        .ok_or_else(|| format!("Value at {} is not an array", array_path))?;
+
+    // sort_by is stable, so equal elements keep their original relative order
+    array.sort_by(|a, b| {
+        keys.iter()
+            .zip(key_paths.iter())
+            .map(|(key, path)| compare_values(json_path::get(a, path), json_path::get(b, path), key))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    Ok(array.len())
+}
+
+fn sort_json_array_blocking(
+    array_path: Option<String>,
+    keys: Vec<SortKey>,
+    state: &AppState,
+) -> Result<String, String> {
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let content = &snapshot.text;
+
+    let mut value = crate::formatters::json::parse_json_relaxed(content)?;
+    sort_array(&mut value, array_path.as_deref().unwrap_or("$"), &keys)?;
+
+    let result = crate::formatters::to_string_pretty(&value).map_err(|e| format!("Failed to format JSON: {}", e))?;
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.snapshot_document(&snapshot)?.edit(Slot::Formatted, result.clone());
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn sort_json_array(
+    app: AppHandle,
+    array_path: Option<String>,
+    keys: Vec<SortKey>,
+) -> Result<String, String> {
+    crate::run_blocking("sort_json_array", move || {
+        sort_json_array_blocking(array_path, keys, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn numeric_sort_places_nan_consistently() {
+        let mut value = json!([{"n": "NaN"}, {"n": 3}, {"n": "x"}, {"n": "1.5"}, {"n": "NaN"}, {"n": null}]);
+        let key = SortKey {
+            path: "n".to_string(),
+            descending: false,
+            comparator: "numeric".to_string(),
+        };
+        sort_array(&mut value, "$", &[key]).unwrap();
+        assert_eq!(value, json!([{"n": "x"}, {"n": "1.5"}, {"n": 3}, {"n": "NaN"}, {"n": "NaN"}, {"n": null}]));
+    }
+}
@@ -0,0 +1,250 @@
+// Backend tree for the virtualized JSON viewer. Stored JSON is parsed once and
+// kept; the UI then asks for one node's children at a time, so a 100MB
+// document never has to be materialized in the webview.
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::json_path::{self, Segment};
+use crate::AppState;
+
+const DEFAULT_PAGE_SIZE: usize = 200;
+const MAX_PAGE_SIZE: usize = 5_000;
+const PREVIEW_CHARS: usize = 120;
+
+// Unlike serde_json::Value (sorted maps), objects keep their document order
+pub enum Node {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+struct NodeVisitor;
+
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Node, E> {
+        Ok(Node::Null)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Node, E> {
+        Ok(Node::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Node, E> {
+        Ok(Node::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Node, E> {
+        Ok(Node::Number(value.into()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Node, E> {
+        Ok(serde_json::Number::from_f64(value).map(Node::Number).unwrap_or(Node::Null))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Node, E> {
+        Ok(Node::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Node, E> {
+        Ok(Node::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Node::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Node::Object(entries))
+    }
+}
+
+impl Node {
+    fn kind(&self) -> &'static str {
+        match self {
+            Node::Null => "null",
+            Node::Bool(_) => "boolean",
+            Node::Number(_) => "number",
+            Node::String(_) => "string",
+            Node::Array(_) => "array",
+            Node::Object(_) => "object",
+        }
+    }
+
+    fn child_count(&self) -> usize {
+        match self {
+            Node::Array(items) => items.len(),
+            Node::Object(entries) => entries.len(),
+            _ => 0,
+        }
+    }
+
+    fn child(&self, segment: &Segment) -> Option<&Node> {
+        match (self, segment) {
+            (Node::Array(items), Segment::Index(index)) => items.get(*index),
+            (Node::Object(entries), Segment::Key(key)) => {
+                entries.iter().find(|(name, _)| name == key).map(|(_, node)| node)
+            }
+            // Bare numeric segments parse as keys; let them index arrays too
+            (Node::Array(items), Segment::Key(key)) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn scalar_text(&self) -> Option<String> {
+        match self {
+            Node::Null => Some("null".to_string()),
+            Node::Bool(value) => Some(value.to_string()),
+            Node::Number(value) => Some(value.to_string()),
+            Node::String(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn preview(&self) -> String {
+        match self {
+            Node::Array(items) => format!("[{} items]", items.len()),
+            Node::Object(entries) => format!("{{{} keys}}", entries.len()),
+            Node::String(value) => {
+                let mut preview: String = value.chars().take(PREVIEW_CHARS).collect();
+                if preview.len() < value.len() {
+                    preview.push('…');
+                }
+                format!("\"{}\"", preview)
+            }
+            scalar => scalar.scalar_text().unwrap_or_default(),
+        }
+    }
+}
+
+// The most recently parsed tree, tagged with the buffer it came from
+#[derive(Default)]
+pub struct JsonTreeCache(Mutex<Option<(u64, Arc<Node>)>>);
+
+fn load_tree(app: &AppHandle, content_type: &str) -> Result<Arc<Node>, String> {
+    let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let content = match content_type {
+        "raw" => storage.active().raw_content.as_ref(),
+        "formatted" => storage.active().formatted_content.as_ref(),
+        _ => return Err("Invalid content type".to_string()),
+    }
+    .ok_or_else(|| "No content stored".to_string())?;
+
+    let cache = app.state::<JsonTreeCache>();
+    let mut cached = cache.0.lock().map_err(|e| e.to_string())?;
+    if let Some((id, tree)) = cached.as_ref() {
+        if *id == content.id() {
+            return Ok(tree.clone());
+        }
+    }
+    // Drop the old tree before building the next one so both never coexist
+    *cached = None;
+    let tree: Node = serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let tree = Arc::new(tree);
+    *cached = Some((content.id(), tree.clone()));
+    Ok(tree)
+}
+
+fn get_json_node_blocking(
+    app: &AppHandle,
+    path: Option<String>,
+    offset: usize,
+    limit: Option<usize>,
+    content_type: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let tree = load_tree(app, content_type.as_deref().unwrap_or("raw"))?;
+    let segments = json_path::parse_path(path.as_deref().unwrap_or("$"))?;
+    let node = segments
+        .iter()
+        .try_fold(tree.as_ref(), |node, segment| node.child(segment))
+        .ok_or_else(|| format!("No node at path {}", json_path::format_path(&segments)))?;
+
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let child_entry = |segment: Segment, child: &Node| {
+        let mut child_path = segments.clone();
+        let key = match &segment {
+            Segment::Key(key) => serde_json::Value::from(key.as_str()),
+            Segment::Index(index) => serde_json::Value::from(*index),
+        };
+        child_path.push(segment);
+        serde_json::json!({
+            "key": key,
+            "path": json_path::format_path(&child_path),
+            "kind": child.kind(),
+            "child_count": child.child_count(),
+            "preview": child.preview()
+        })
+    };
+    let children: Vec<serde_json::Value> = match node {
+        Node::Array(items) => items
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(limit)
+            .map(|(i, child)| child_entry(Segment::Index(i), child))
+            .collect(),
+        Node::Object(entries) => entries
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(key, child)| child_entry(Segment::Key(key.clone()), child))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let next_offset = offset + children.len();
+    Ok(serde_json::json!({
+        "path": json_path::format_path(&segments),
+        "kind": node.kind(),
+        "child_count": node.child_count(),
+        // Full value for scalars; containers are browsed through `children`
+        "value": node.scalar_text(),
+        "offset": offset,
+        "children": children,
+        "has_more": next_offset < node.child_count(),
+        "next_offset": next_offset
+    }))
+}
+
+// Path uses the json_path syntax (`$.items[3].name`); omitted means the root
+#[tauri::command]
+pub async fn get_json_node(
+    app: AppHandle,
+    path: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    content_type: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("get_json_node", move || get_json_node_blocking(&app, path, offset.unwrap_or(0), limit, content_type)).await
+}
+
+// Frees the parsed tree once the viewer is closed
+#[tauri::command]
+pub fn release_json_tree(cache: State<JsonTreeCache>) -> Result<(), String> {
+    *cache.0.lock().map_err(|e| e.to_string())? = None;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// Unwraps JSON that was serialized into a string, possibly several times over:
+// `"{\"a\":1}"` or `"\"{\\\"a\\\":1}\""`, as log pipelines produce when a
+// payload is logged as a message field. Escaped JSON without the surrounding
+// quotes (`{\"a\":1}`, e.g. copied out of a log line) counts as one layer too.
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::Formatter;
+use crate::undo::Slot;
+use crate::AppState;
+
+// Stops runaway inputs; real data rarely goes beyond three or four layers
+const MAX_LAYERS: usize = 32;
+
+fn looks_escaped(text: &str) -> bool {
+    ["{\\\"", "[\\\"", "[{\\\""]
+        .iter()
+        .any(|prefix| text.starts_with(prefix))
+}
+
+// The innermost JSON text and the number of string layers removed, or None
+// when `text` isn't stringified JSON
+pub fn unwrap_stringified(text: &str) -> Option<(String, usize)> {
+    let trimmed = text.trim();
+    let mut current = if looks_escaped(trimmed) {
+        format!("\"{}\"", trimmed)
+    } else {
+        trimmed.to_string()
+    };
+
+    let mut layers = 0;
+    while layers < MAX_LAYERS {
+        let Ok(serde_json::Value::String(inner)) = serde_json::from_str::<serde_json::Value>(&current) else {
+            break;
+        };
+        let inner = inner.trim();
+        // Only strings that hold JSON again are a layer; a plain string is data
+        let holds_json = (inner.starts_with('{') || inner.starts_with('[') || inner.starts_with('"'))
+            && serde_json::from_str::<serde::de::IgnoredAny>(inner).is_ok();
+        if !holds_json {
+            break;
+        }
+        current = inner.to_string();
+        layers += 1;
+    }
+    (layers > 0).then_some((current, layers))
+}
+
+fn unwrap_pretty(text: &str) -> Result<(String, usize), String> {
+    let (inner, layers) = unwrap_stringified(text).ok_or_else(|| "Content isn't stringified JSON".to_string())?;
+    let value: serde_json::Value = serde_json::from_str(&inner).map_err(|e| e.to_string())?;
+    let pretty = crate::formatters::to_string_pretty(&value).map_err(|e| format!("Failed to format JSON: {}", e))?;
+    Ok((pretty, layers))
+}
+
+pub struct JsonUnwrapFormatter;
+
+impl Formatter for JsonUnwrapFormatter {
+    fn id(&self) -> &'static str {
+        "json-unwrap"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["stringified-json"]
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Unwrap Stringified JSON"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        crate::formatters::layout_options_schema(crate::formatters::PRETTY_OPTIONS)
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        unwrap_pretty(input).map(|(pretty, _)| pretty)
+    }
+}
+
+// Unwraps `text`, or the active document's raw content when it's empty, and
+// reports how many layers were removed. With `apply` the pretty-printed result
+// replaces the raw content as an undoable edit.
+#[tauri::command]
+pub async fn unwrap_json(
+    app: AppHandle,
+    text: Option<String>,
+    apply: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("unwrap_json", move || {
+        let apply = apply.unwrap_or(false);
+        let from_document = text.as_deref().is_none_or(str::is_empty);
+        if apply && !from_document {
+            return Err("Only the stored content can be unwrapped in place".to_string());
+        }
+        let content = match text.filter(|text| !text.is_empty()) {
+            Some(text) => text,
+            None => {
+                let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                storage
+                    .active()
+                    .raw_content
+                    .as_deref()
+                    .ok_or_else(|| "No content stored".to_string())?
+                    .to_string()
+            }
+        };
+
+        let (pretty, layers) = unwrap_pretty(&content)?;
+        if apply {
+            let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            let document = storage.active_mut();
+            document.edit(Slot::Raw, pretty.clone());
+            document.formatted_content = None;
+            document.format_type = Some("json".to_string());
+        }
+        Ok(serde_json::json!({
+            "content": pretty,
+            "layers": layers,
+            "applied": apply
+        }))
+    })
+    .await
+}
@@ -0,0 +1,278 @@
+// Peeks at a Kafka topic without joining a consumer group: lists the cluster's
+// topics, and reads the last N messages of one partition (or of every partition,
+// merged by timestamp). Payloads are decoded like Redis values; with a schema
+// registry URL, Confluent-framed Avro payloads (magic byte 0, 4-byte schema id)
+// are decoded against the registered schema. The messages can be loaded into a
+// new document as a JSON array for the formatter, summary and tree.
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Headers, Message};
+use rdkafka::{Offset, TopicPartitionList};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::redis_client::decode_bytes;
+use crate::AppState;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+// Overall limit for a peek, however many partitions are read
+const PEEK_DEADLINE: Duration = Duration::from_secs(20);
+const DEFAULT_COUNT: i64 = 20;
+const MAX_COUNT: i64 = 1000;
+
+// `config` carries extra librdkafka properties, e.g. security.protocol,
+// sasl.mechanism, sasl.username and sasl.password for secured clusters
+fn consumer(brokers: &str, config: Option<HashMap<String, String>>) -> Result<BaseConsumer, String> {
+    let mut client_config = ClientConfig::new();
+    client_config
+        .set("bootstrap.servers", brokers)
+        // Offsets are assigned explicitly and never committed, so the group is
+        // only a formality
+        .set("group.id", "devmate-peek")
+        .set("enable.auto.commit", "false")
+        .set("enable.partition.eof", "true")
+        .set("socket.timeout.ms", TIMEOUT.as_millis().to_string());
+    for (key, value) in config.unwrap_or_default() {
+        client_config.set(key, value);
+    }
+    client_config
+        .create()
+        .map_err(|e| format!("Failed to create Kafka client: {}", e))
+}
+
+fn kafka_error(e: KafkaError) -> String {
+    format!("Kafka error: {}", e)
+}
+
+// Confluent schema registry lookups, cached per schema id for one peek
+struct AvroDecoder {
+    registry_url: String,
+    schemas: HashMap<u32, Result<apache_avro::Schema, String>>,
+}
+
+impl AvroDecoder {
+    fn schema(&mut self, id: u32) -> Result<&apache_avro::Schema, String> {
+        let registry_url = &self.registry_url;
+        self.schemas
+            .entry(id)
+            .or_insert_with(|| {
+                let schema = crate::avro::registry_schema(registry_url, id)?;
+                apache_avro::Schema::parse_str(&schema).map_err(|e| format!("Invalid Avro schema {}: {}", id, e))
+            })
+            .as_ref()
+            .map_err(|e| e.clone())
+    }
+
+    // None when the payload isn't in the registry's wire format
+    fn decode(&mut self, payload: &[u8]) -> Option<Result<serde_json::Value, String>> {
+        if payload.len() < 5 || payload[0] != 0 {
+            return None;
+        }
+        let id = u32::from_be_bytes([payload[1], payload[2], payload[3], payload[4]]);
+        Some(self.schema(id).and_then(|schema| {
+            let value = apache_avro::from_avro_datum(schema, &mut &payload[5..], None)
+                .map_err(|e| format!("Payload doesn't match Avro schema {}: {}", id, e))?;
+            serde_json::Value::try_from(value).map_err(|e| format!("Failed to convert Avro value: {}", e))
+        }))
+    }
+}
+
+fn message_json<M: Message>(message: &M, avro: &mut Option<AvroDecoder>) -> serde_json::Value {
+    let value = match message.payload() {
+        None => serde_json::Value::Null,
+        Some(payload) => match avro.as_mut().and_then(|avro| avro.decode(payload)) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => serde_json::json!({ "error": error, "raw": decode_bytes(payload) }),
+            None => decode_bytes(payload),
+        },
+    };
+    let headers: serde_json::Map<String, serde_json::Value> = message
+        .headers()
+        .map(|headers| {
+            headers
+                .iter()
+                .map(|header| {
+                    (
+                        header.key.to_string(),
+                        header.value.map(decode_bytes).unwrap_or_default(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    serde_json::json!({
+        "partition": message.partition(),
+        "offset": message.offset(),
+        "timestamp_ms": message.timestamp().to_millis(),
+        "key": message.key().map(decode_bytes),
+        "headers": headers,
+        "value": value
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn peek_blocking(
+    app: &AppHandle,
+    brokers: &str,
+    topic: &str,
+    partition: Option<i32>,
+    count: i64,
+    schema_registry_url: Option<String>,
+    config: Option<HashMap<String, String>>,
+    into_document: bool,
+) -> Result<serde_json::Value, String> {
+    let consumer = consumer(brokers, config)?;
+    let metadata = consumer.fetch_metadata(Some(topic), TIMEOUT).map_err(kafka_error)?;
+    let partitions: Vec<i32> = metadata
+        .topics()
+        .iter()
+        .find(|found| found.name() == topic && found.error().is_none())
+        .map(|found| found.partitions().iter().map(|p| p.id()).collect())
+        .ok_or_else(|| format!("Topic {} doesn't exist", topic))?;
+    let partitions = match partition {
+        Some(partition) if partitions.contains(&partition) => vec![partition],
+        Some(partition) => return Err(format!("Topic {} has no partition {}", topic, partition)),
+        None => partitions,
+    };
+
+    // Start `count` messages before the end of each partition
+    let mut assignment = TopicPartitionList::new();
+    let mut pending = 0;
+    for &partition in &partitions {
+        let (low, high) = consumer
+            .fetch_watermarks(topic, partition, TIMEOUT)
+            .map_err(kafka_error)?;
+        if high > low {
+            assignment
+                .add_partition_offset(topic, partition, Offset::Offset((high - count).max(low)))
+                .map_err(kafka_error)?;
+            pending += 1;
+        }
+    }
+
+    let mut avro = schema_registry_url.map(|registry_url| AvroDecoder {
+        registry_url,
+        schemas: HashMap::new(),
+    });
+    let mut messages = Vec::new();
+    let mut timed_out = false;
+    if pending > 0 {
+        consumer.assign(&assignment).map_err(kafka_error)?;
+        let deadline = Instant::now() + PEEK_DEADLINE;
+        // Each partition signals EOF once it has been read to the end
+        while pending > 0 {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                timed_out = true;
+                break;
+            };
+            match consumer.poll(remaining.min(TIMEOUT)) {
+                Some(Ok(message)) => messages.push(message_json(&message, &mut avro)),
+                Some(Err(KafkaError::PartitionEOF(_))) => pending -= 1,
+                Some(Err(e)) => return Err(kafka_error(e)),
+                None => {
+                    timed_out = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    // Oldest first; across partitions only the latest `count` are kept
+    messages.sort_by_key(|message| message["timestamp_ms"].as_i64().unwrap_or_default());
+    let excess = messages.len().saturating_sub(count as usize);
+    messages.drain(..excess);
+
+    let mut reply = serde_json::json!({
+        "topic": topic,
+        "partitions": partitions,
+        "count": messages.len(),
+        "timed_out": timed_out
+    });
+    if into_document {
+        let json = crate::formatters::to_string_pretty(&messages)
+            .map_err(|e| format!("Failed to serialize messages: {}", e))?;
+        let document_id = {
+            let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            let name = match partition {
+                Some(partition) => format!("kafka: {}[{}]", topic, partition),
+                None => format!("kafka: {}", topic),
+            };
+            let id = storage.insert_document(name);
+            let document = storage.active_mut();
+            document.raw_content = Some(json.into());
+            document.format_type = Some("json".to_string());
+            id
+        };
+        crate::tray::refresh(app);
+        reply["document_id"] = document_id.into();
+    }
+    reply["messages"] = messages.into();
+    Ok(reply)
+}
+
+// The cluster's brokers and topics with their partition counts. Internal
+// topics (names starting with "__") are left out unless asked for.
+#[tauri::command]
+pub async fn kafka_list_topics(
+    brokers: String,
+    config: Option<HashMap<String, String>>,
+    include_internal: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("kafka_list_topics", move || {
+        let consumer = consumer(&brokers, config)?;
+        let metadata = consumer.fetch_metadata(None, TIMEOUT).map_err(kafka_error)?;
+        let include_internal = include_internal.unwrap_or(false);
+        let mut topics: Vec<serde_json::Value> = metadata
+            .topics()
+            .iter()
+            .filter(|topic| include_internal || !topic.name().starts_with("__"))
+            .map(|topic| {
+                serde_json::json!({
+                    "name": topic.name(),
+                    "partitions": topic.partitions().len(),
+                    "error": topic.error().map(|e| format!("{:?}", e))
+                })
+            })
+            .collect();
+        topics.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+        let brokers: Vec<serde_json::Value> = metadata
+            .brokers()
+            .iter()
+            .map(|broker| serde_json::json!({ "id": broker.id(), "host": broker.host(), "port": broker.port() }))
+            .collect();
+        Ok(serde_json::json!({ "brokers": brokers, "topics": topics }))
+    })
+    .await
+}
+
+// Reads the last `count` messages (20 by default, at most 1000) of `topic`,
+// from `partition` or from all of them. Committed offsets are never touched.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn kafka_peek(
+    app: AppHandle,
+    brokers: String,
+    topic: String,
+    partition: Option<i32>,
+    count: Option<i64>,
+    schema_registry_url: Option<String>,
+    config: Option<HashMap<String, String>>,
+    into_document: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let count = count.unwrap_or(DEFAULT_COUNT).clamp(1, MAX_COUNT);
+    crate::run_blocking("kafka_peek", move || {
+        peek_blocking(
+            &app,
+            &brokers,
+            &topic,
+            partition,
+            count,
+            schema_registry_url.filter(|url| !url.trim().is_empty()),
+            config,
+            into_document.unwrap_or(false),
+        )
+    })
+    .await
+}
@@ -1,537 +1,597 @@
-use base64;
-use base64::engine::general_purpose::STANDARD;
-use base64::Engine;
+use std::collections::BTreeMap;
 use std::sync::Mutex;
-use tauri::State;
-
+use tauri::{AppHandle, Manager, State};
+
+use formatters::FormatterRegistry;
+use progress::ProgressReporter;
+use responses::{ChunkHeader, ChunkResponse, ContentInfo, FileLoadResult, FormatJobResult, LinesResponse};
+use text_buffer::TextBuffer;
+
+mod ansi;
+mod app_data;
+mod archive;
+mod asn1;
+mod avro;
+mod batch;
+mod byte_editor;
+mod cbor;
+mod charset;
+mod checksum;
+pub mod cli;
+mod clipboard;
+mod clipboard_history;
+mod columns;
+mod compression;
+mod config_bundle;
+mod cookies;
+mod csp;
+mod data_uri;
+mod deep_link;
+mod detect;
+mod docker;
+mod documents;
+mod email;
+mod environment;
+mod export;
+mod fetch;
+mod format_cache;
+mod formatters;
+mod geoip;
+mod git_config;
+mod highlight;
+mod history;
+mod html_table;
+mod http_client;
+mod http_collections;
+mod http_headers;
+mod ics;
+mod idn;
+mod jobs;
+mod json_path;
+mod json_tree;
+mod json_repair;
+mod json_sort;
+mod json_unwrap;
+mod kafka;
+mod lines;
+mod live;
+mod local_api;
+mod locale_format;
+mod log_timeline;
+mod lorem;
+mod multipart;
+mod oauth;
+mod open_files;
+mod openapi;
+mod outline;
+mod parquet_file;
+mod patch;
+mod pem;
+mod permissions;
+mod phone;
+mod plugins;
+mod progress;
+mod qr;
+mod quick_action;
+mod recent_files;
+mod records;
+mod redact;
+mod redis_client;
+mod replace;
+mod robots;
+pub mod responses;
+mod saml;
+mod schedules;
+mod scripting;
+mod search;
+mod semver;
+mod session;
+mod settings;
+mod share;
+mod slug;
+mod snippets;
+mod spreadsheet;
+mod sql_client;
+mod sql_fixtures;
+mod stdin;
+mod tasks;
+mod template;
+mod text_buffer;
+mod tls;
+mod tray;
+mod undo;
+mod units;
+mod url_compare;
+mod user_agent;
+mod validators;
+mod vcard;
+mod watch;
+mod webauthn;
+mod whitespace;
+mod x509;
+mod yaml;
 
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[tauri::command]
-fn format_text(text: String, format_type: String, state: State<AppState>) -> Result<String, String> {
-    // If text is empty, try to get raw content from storage
-    let content_to_format = if text.is_empty() {
-        let storage = state.lock().map_err(|e| e.to_string())?;
-        storage.raw_content.clone().unwrap_or_default()
-    } else {
-        text
-    };
-    
-    let result = match format_type.as_str() {
-        "json" => format_json(&content_to_format),
-        "xml" => format_xml(&content_to_format),
-        "jwt" => parse_jwt(&content_to_format),
-        "json-summary" => summarize_json(&content_to_format),
-        "encode" => encode_base64(&content_to_format),
-        "decode" => decode_base64(&content_to_format),
-        _ => Err("Unknown format type".to_string()),
-    };
-    
-    // Store formatted content in backend for chunked loading
-    if let Ok(ref formatted) = result {
-        if format_type != "encode" && format_type != "decode" {
-            let mut storage = state.lock().map_err(|e| e.to_string())?;
-            storage.formatted_content = Some(formatted.clone());
-        }
-    }
-    
-    result
+// Run CPU-heavy command work on the blocking pool so the IPC handler and window
+// stay responsive. `kind` names the task in list_tasks; work beyond the
+// concurrency limit waits its turn in the task queue.
+pub async fn run_blocking<T, F>(kind: &str, work: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tasks::queue().run(kind, work).await
 }
 
-
-fn format_json(text: &str) -> Result<String, String> {
-    match serde_json::from_str::<serde_json::Value>(text) {
-        Ok(parsed) => {
-            match serde_json::to_string_pretty(&parsed) {
-                Ok(formatted) => Ok(formatted),
-                Err(e) => Err(format!("Failed to format JSON: {}", e))
-            }
-        },
-        Err(e) => {
-            // Get line and column from serde_json::Error
-            let line = e.line();
-            let column = e.column();
-            let lines: Vec<&str> = text.lines().collect();
-            let error_line = lines.get(line.saturating_sub(1)).unwrap_or(&"");
-
-            // Check for common error patterns and provide user-friendly messages
-            let error_str = e.to_string();
-            let user_friendly_error = if error_str.contains("trailing characters") {
-                // This is likely JSONL/NDJSON format (multiple JSON objects on separate lines)
-                let total_lines = lines.len();
-                format!(
-                    "❌ Invalid JSON Format - Multiple JSON Objects Detected\n\n\
-                    Your file contains {} lines with separate JSON objects (JSONL/NDJSON format).\n\
-                    Standard JSON requires a single object or array.\n\n\
-                    To fix this, you have two options:\n\n\
-                    1. **Convert to JSON Array**: Wrap all objects in square brackets and separate with commas:\n\
-                       [\n         {{first object}},\n         {{second object}}\n       ]\n\n\
-                    2. **Process as JSONL**: Each line is a separate JSON object (not supported in JSON formatter)\n\n\
-                    Error details:\n\
-                    - Line {}: Found additional JSON object after the first one\n\
-                    - Total objects detected: {} lines\n\
-                    - First object ends at character position in line {}",
-                    total_lines, line, total_lines, line
-                )
-            } else if error_str.contains("expected") {
-                // Missing comma, bracket, etc.
-                let mut marker = String::new();
-                for _ in 0..column {
-                    marker.push('-');
-                }
-                marker.push('^');
-                
-                format!(
-                    "❌ JSON Syntax Error\n\n\
-                    There's a syntax error in your JSON at line {} column {}.\n\n\
-                    Error location:\n\
-                    {}\n\
-                    {}\n\n\
-                    Common fixes:\n\
-                    • Add missing comma between properties\n\
-                    • Check for unclosed brackets {{ }} or [ ]\n\
-                    • Ensure strings are properly quoted\n\
-                    • Remove trailing commas\n\n\
-                    Detailed error: {}",
-                    line, column + 1, error_line, marker, e
-                )
-            } else {
-                // Generic JSON error with helpful context and precise error location
-                let mut marker = String::new();
-                for _ in 0..column {
-                    marker.push('-');
-                }
-                marker.push('^');
-                
-                format!(
-                    "❌ JSON Parse Error\n\n\
-                    Invalid JSON format detected at line {} column {}.\n\n\
-                    Error location:\n\
-                    {}\n\
-                    {}\n\n\
-                    Please check:\n\
-                    • All strings are enclosed in double quotes\n\
-                    • Properties are separated by commas\n\
-                    • Objects are enclosed in {{ }}\n\
-                    • Arrays are enclosed in [ ]\n\
-                    • No trailing commas\n\n\
-                    Detailed error: {}\n\n\
-                    Raw parse error:\n\
-                    Parse error on line {} column {}:\n\
-                    {}\n\
-                    {}\n\
-                    {}",
-                    line, column + 1, error_line, marker, e,
-                    line, column + 1, error_line, marker, e
-                )
-            };
-            
-            Err(user_friendly_error)
-        }
-    }
+// run_formatter_with the default options from the settings, for the commands
+// that format on their own (clipboard, watch, stdin and the like)
+fn run_formatter(app: &AppHandle, text: String, format_type: &str) -> Result<(TextBuffer, bool), String> {
+    let document_id = active_document_id(app)?;
+    let options = settings::current(app).format_options();
+    run_formatter_with(app, &jobs::CancelToken::default(), document_id, text, format_type, options)
 }
 
-fn format_xml(text: &str) -> Result<String, String> {
-    let trimmed = text.trim();
-    if trimmed.is_empty() {
-        return Err("Empty XML input".to_string());
-    }
-
-    // Basic XML validation
-    if !trimmed.starts_with('<') || !trimmed.ends_with('>') {
-        return Err("Invalid XML: Must start with '<' and end with '>'".to_string());
-    }
+fn active_document_id(app: &AppHandle) -> Result<u64, String> {
+    Ok(app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?.active_id)
+}
 
-    // Simple XML formatting with proper depth handling
-    let mut formatted = String::new();
-    let mut depth: i32 = 0;
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    let mut last_was_text = false; // Track if the last content added was text
-
-    while i < chars.len() {
-        if chars[i] == '<' {
-            // Find the end of the tag
-            let mut tag_end = i;
-            while tag_end < chars.len() && chars[tag_end] != '>' {
-                tag_end += 1;
-            }
+// Shared by format_text and start_format_job. Returns the formatted text and
+// whether the formatter wants it kept as the stored formatted content.
+// Formatters that report progress stop early once `token` is cancelled.
+fn run_formatter_with(
+    app: &AppHandle,
+    token: &jobs::CancelToken,
+    document_id: u64,
+    text: String,
+    format_type: &str,
+    options: formatters::FormatOptions,
+) -> Result<(TextBuffer, bool), String> {
+    let registry = app.state::<FormatterRegistry>();
+    let formatter = registry
+        .get(format_type)
+        .ok_or_else(|| "Unknown format type".to_string())?;
+
+    // If text is empty, format document `document_id`'s raw content. Only the
+    // buffer handle is cloned, so the storage lock is released before any real
+    // work starts.
+    let stored;
+    let content_to_format: &str = if text.is_empty() {
+        let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        stored = storage
+            .document(document_id)
+            .ok_or_else(|| "The document was closed before it could be formatted".to_string())?
+            .raw_content
+            .clone();
+        stored.as_deref().unwrap_or_default()
+    } else {
+        &text
+    };
 
-            if tag_end >= chars.len() {
-                return Err("Invalid XML: Unclosed tag found".to_string());
+    let mut reporter = ProgressReporter::new(
+        app,
+        progress::FORMAT_PROGRESS_EVENT,
+        content_to_format.len() as u64,
+        serde_json::json!({ "format_type": format_type }),
+    );
+    let options_key = serde_json::to_value(options).map_err(|e| e.to_string())?;
+    let cache_key = formatter
+        .cacheable()
+        .then(|| format_cache::CacheKey::new(content_to_format, format_type, &options_key));
+    let cached = match &cache_key {
+        Some(key) => app
+            .state::<AppState>()
+            .inner()
+            .lock()
+            .map_err(|e| e.to_string())?
+            .format_cache
+            .get(key),
+        None => None,
+    };
+    let result = match cached {
+        Some(output) => {
+            reporter.update(content_to_format.len() as u64);
+            Ok(output)
+        }
+        None => {
+            let result = formatters::with_options(options, || {
+                formatter.format_with_progress(content_to_format, &mut |processed| {
+                    token.check()?;
+                    reporter.update(processed as u64);
+                    Ok(())
+                })
+            })
+            .map(TextBuffer::from);
+            if let (Ok(output), Some(key)) = (&result, cache_key) {
+                let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                storage.format_cache.insert(key, output.clone());
             }
+            result
+        }
+    };
+    history::record(app, format_type, content_to_format, result.as_deref());
+    Ok((result?, formatter.stores_output()))
+}
 
-            // Extract tag content
-            let tag_content: String = chars[i + 1..tag_end].iter().collect();
-            let is_closing_tag = tag_content.starts_with('/');
-            let is_self_closing = tag_content.ends_with('/');
-
-            if is_closing_tag {
-                // Always decrease depth for closing tags first
-                depth -= 1;
-
-                // Only add newline and indentation if the last content was NOT text
-                if !last_was_text {
-                    formatted.push('\n');
-                    formatted.push_str(&"  ".repeat(depth.max(0) as usize));
-                }
-                // Reset the text flag
-                last_was_text = false;
-            } else {
-                // Opening tag or self-closing tag - add newline and indentation
-                if !formatted.is_empty() {
-                    formatted.push('\n');
-                }
-
-                formatted.push_str(&"  ".repeat(depth.max(0) as usize));
-
-                // Only increase depth for non-self-closing opening tags
-                if !is_self_closing {
-                    depth += 1;
-                }
+// The formatted text is shared with storage and the cache, and serialized for
+// the reply straight from that shared buffer
+#[tauri::command]
+async fn format_text(
+    app: AppHandle,
+    text: String,
+    format_type: String,
+    options: Option<serde_json::Value>,
+) -> Result<TextBuffer, String> {
+    // The result belongs to the document that was active when the call was
+    // made, even if another one is switched to while the format runs
+    let document_id = active_document_id(&app)?;
+    run_blocking("format_text", move || {
+        let format_type = settings::resolve_format(&app, format_type);
+        let options = settings::resolve_format_options(&app, options)?;
+        let token = jobs::CancelToken::default();
+        let (formatted, store) = run_formatter_with(&app, &token, document_id, text, &format_type, options)?;
+
+        // Store formatted content in backend for chunked loading
+        if store {
+            store_formatted(&app, document_id, formatted.clone(), format_type)?;
+        }
+        Ok(formatted)
+    })
+    .await
+}
 
-                // Reset the text flag for new tags
-                last_was_text = false;
-            }
+fn store_formatted(app: &AppHandle, document_id: u64, formatted: TextBuffer, format_type: String) -> Result<(), String> {
+    let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let document = storage
+        .documents
+        .get_mut(&document_id)
+        .ok_or_else(|| "The document was closed while it was being formatted".to_string())?;
+    document.formatted_content = Some(formatted);
+    document.format_type = Some(format_type);
+    Ok(())
+}
 
-            // Add the complete tag
-            for j in i..=tag_end {
-                formatted.push(chars[j]);
-            }
+// Job variant of format_text for huge inputs: returns a job id immediately and
+// reports through `job://finished`. Formatters that report progress stop at
+// their next report once the job is cancelled; the rest run to the end and the
+// result is discarded instead of stored.
+#[tauri::command]
+fn start_format_job(
+    app: AppHandle,
+    text: String,
+    format_type: String,
+    options: Option<serde_json::Value>,
+) -> Result<u64, String> {
+    // Checked up front so bad options fail the call rather than the job
+    let options = settings::resolve_format_options(&app, options)?;
+    let document_id = active_document_id(&app)?;
+    jobs::spawn_job(&app, "format", move |app, token| {
+        token.check()?;
+        let format_type = settings::resolve_format(app, format_type);
+        let (formatted, store) = run_formatter_with(app, token, document_id, text, &format_type, options)?;
+        token.check()?;
+
+        let formatted_length = formatted.len();
+        if store {
+            store_formatted(app, document_id, formatted, format_type.clone())?;
+        }
+        let result = FormatJobResult {
+            format_type,
+            formatted_length,
+            stored: store,
+        };
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
 
-            i = tag_end + 1;
-        } else if !chars[i].is_whitespace() {
-            // Handle text content - collect until next tag
-            let text_start = i;
+#[tauri::command]
+fn list_formatters(registry: State<FormatterRegistry>) -> Vec<serde_json::Value> {
+    registry.describe()
+}
 
-            while i < chars.len() && chars[i] != '<' {
-                i += 1;
+// Ranked guesses for pasted text, or the active document's raw content when
+// `text` is empty. Stored content is only sampled, so huge files stay cheap.
+#[tauri::command]
+async fn detect_format(app: AppHandle, text: Option<String>) -> Result<serde_json::Value, String> {
+    run_blocking("detect_format", move || {
+        let (sample, complete) = match text {
+            Some(text) if !text.is_empty() => (text, true),
+            _ => {
+                let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                let content = storage.active().raw_content.as_deref().unwrap_or_default();
+                let end = text_buffer::grapheme_floor(content, formatters::autodetect::SAMPLE_BYTES);
+                (content[..end].to_string(), end == content.len())
             }
+        };
 
-            // Add the text content (trimmed)
-            let text_content: String = chars[text_start..i]
-                .iter()
-                .collect::<String>()
-                .trim()
-                .to_string();
-            if !text_content.is_empty() {
-                formatted.push_str(&text_content);
-                last_was_text = true; // Mark that we just added text content
-            }
-        } else {
-            // Skip whitespace between tags
-            i += 1;
-        }
-    }
-
-    // Final validation: depth should be 0 if XML is properly balanced
-    if depth != 0 {
-        return Err("Invalid XML: Unbalanced tags detected".to_string());
-    }
-
-    Ok(formatted)
+        let registry = app.state::<FormatterRegistry>();
+        let guesses = formatters::autodetect::guess_formats(&sample, complete, &registry);
+        Ok(serde_json::json!({
+            "best": guesses.first().map(|g| g.to_json()),
+            "candidates": guesses.iter().map(|g| g.to_json()).collect::<Vec<_>>(),
+            "sampled": !complete
+        }))
+    })
+    .await
 }
 
-fn parse_jwt(token: &str) -> Result<String, String> {
-    let token = token.trim();
+// Claim report for a JWT: readable exp/iat/nbf, alg and lifetime checks, and
+// aud/iss compared with the expected values when given. Uses the active
+// document's raw content when `token` is empty.
+#[tauri::command]
+fn validate_jwt(
+    token: Option<String>,
+    expected_audience: Option<String>,
+    expected_issuer: Option<String>,
+    max_lifetime_hours: Option<i64>,
+    state: State<AppState>,
+) -> Result<serde_json::Value, String> {
+    let token = match token.filter(|token| !token.trim().is_empty()) {
+        Some(token) => token,
+        None => state
+            .lock()
+            .map_err(|e| e.to_string())?
+            .active()
+            .raw_content
+            .as_deref()
+            .unwrap_or_default()
+            .to_string(),
+    };
+    let expected = formatters::jwt::Expectations {
+        audience: expected_audience.filter(|aud| !aud.is_empty()),
+        issuer: expected_issuer.filter(|iss| !iss.is_empty()),
+        max_lifetime_hours: max_lifetime_hours.unwrap_or(formatters::jwt::MAX_LIFETIME_HOURS),
+    };
+    formatters::jwt::validate_jwt(&token, &expected)
+}
 
-    if token.is_empty() {
-        return Err("Empty JWT token".to_string());
-    }
+// Every JSON syntax problem in `text` (or the active document's raw content)
+// in one pass, each with line, column and a caret snippet
+#[tauri::command]
+async fn lint_json(
+    app: AppHandle,
+    text: Option<String>,
+    max_errors: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    run_blocking("lint_json", move || {
+        // Stored content is read from a snapshot rather than copied under the lock
+        let text = text.filter(|text| !text.is_empty());
+        let snapshot = match text {
+            Some(_) => None,
+            None => Some(
+                app.state::<AppState>()
+                    .inner()
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .snapshot_raw()?,
+            ),
+        };
+        let text = snapshot
+            .as_ref()
+            .map_or(text.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+        let max_errors = max_errors.unwrap_or(formatters::json_lint::DEFAULT_MAX_ERRORS);
+        let errors = formatters::json_lint::lint_json(text, max_errors);
+        Ok(serde_json::json!({
+            "valid": errors.is_empty(),
+            "truncated": errors.len() >= max_errors,
+            "errors": errors
+        }))
+    })
+    .await
+}
 
-    // Split JWT token into parts
-    let parts: Vec<&str> = token.split('.').collect();
+// Minifies `text` (or the active document's raw content) as `language` (json,
+// xml, html, css or js) and reports the size before and after
+#[tauri::command]
+async fn minify_content(
+    app: AppHandle,
+    text: Option<String>,
+    language: String,
+) -> Result<serde_json::Value, String> {
+    run_blocking("minify_content", move || {
+        // Stored content is read from a snapshot rather than copied under the lock
+        let text = text.filter(|text| !text.is_empty());
+        let snapshot = match text {
+            Some(_) => None,
+            None => Some(
+                app.state::<AppState>()
+                    .inner()
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .snapshot_raw()?,
+            ),
+        };
+        let text = snapshot
+            .as_ref()
+            .map_or(text.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+        let minified = formatters::minify::minify(&language.to_ascii_lowercase(), text)?;
+        let (before, after) = (text.len(), minified.len());
+        Ok(serde_json::json!({
+            "content": minified,
+            "original_bytes": before,
+            "minified_bytes": after,
+            "saved_bytes": before.saturating_sub(after),
+            "saved_percent": if before == 0 { 0.0 } else { (before as f64 - after as f64) / before as f64 * 100.0 }
+        }))
+    })
+    .await
+}
 
-    if parts.len() != 3 {
-        return Err("Invalid JWT format. Expected 3 parts separated by dots.".to_string());
-    }
+// Where a JSON document's bytes go: size per top-level key, the `top` largest
+// subtrees (20 by default) and the heaviest fields across array items. Uses the
+// active document's raw content when `text` is empty.
+#[tauri::command]
+async fn analyze_json_size(
+    app: AppHandle,
+    text: Option<String>,
+    top: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    run_blocking("analyze_json_size", move || {
+        // Stored content is read from a snapshot rather than copied under the lock
+        let text = text.filter(|text| !text.trim().is_empty());
+        let snapshot = match text {
+            Some(_) => None,
+            None => Some(
+                app.state::<AppState>()
+                    .inner()
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .snapshot_raw()?,
+            ),
+        };
+        let text = snapshot
+            .as_ref()
+            .map_or(text.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+        let value = formatters::json::parse_json_relaxed(text.trim())?;
+        Ok(formatters::summary::analyze_json_size(&value, top.unwrap_or(20).clamp(1, 500)))
+    })
+    .await
+}
 
-    let mut result = serde_json::Map::new();
+// One open document (a tab in the UI)
+#[derive(Default)]
+pub struct Document {
+    name: String,
+    // Last formatter applied and opaque UI state (scroll positions etc.), kept for session restore
+    format_type: Option<String>,
+    view_state: serde_json::Value,
+    raw_content: Option<TextBuffer>,
+    formatted_content: Option<TextBuffer>,
+    binary_content: Option<Vec<u8>>,
+    undo_stack: Vec<undo::Revision>,
+    redo_stack: Vec<undo::Revision>,
+}
 
-    // Decode header
-    match decode_jwt_part(parts[0]) {
-        Ok(header) => {
-            result.insert("header".to_string(), header);
+impl Document {
+    fn named(name: String) -> Self {
+        Document {
+            name,
+            ..Document::default()
         }
-        Err(e) => return Err(format!("Failed to decode JWT header: {}", e)),
     }
 
-    // Decode payload
-    match decode_jwt_part(parts[1]) {
-        Ok(payload) => {
-            result.insert("payload".to_string(), payload);
+    fn info(&self) -> ContentInfo {
+        ContentInfo {
+            name: self.name.clone(),
+            format_type: self.format_type.clone(),
+            view_state: self.view_state.clone(),
+            has_raw: self.raw_content.is_some(),
+            has_formatted: self.formatted_content.is_some(),
+            has_binary: self.binary_content.is_some(),
+            raw_memory_mapped: self.raw_content.as_ref().is_some_and(|c| c.is_mapped()),
+            raw_compressed: self.raw_content.as_ref().is_some_and(|c| c.is_compressed()),
+            formatted_compressed: self.formatted_content.as_ref().is_some_and(|c| c.is_compressed()),
+            resident_bytes: [&self.raw_content, &self.formatted_content]
+                .iter()
+                .filter_map(|c| c.as_ref())
+                .map(|c| c.resident_len())
+                .sum::<usize>()
+                + self.binary_content.as_ref().map(|b| b.len()).unwrap_or(0),
+            raw_length: self.raw_content.as_ref().map(|s| s.len()).unwrap_or(0),
+            formatted_length: self.formatted_content.as_ref().map(|s| s.len()).unwrap_or(0),
+            binary_length: self.binary_content.as_ref().map(|b| b.len()).unwrap_or(0),
+            undo_steps: self.undo_stack.len(),
+            redo_steps: self.redo_stack.len(),
         }
-        Err(e) => return Err(format!("Failed to decode JWT payload: {}", e)),
-    }
-
-    // Add signature info (we can't decode it without the secret)
-    result.insert(
-        "signature".to_string(),
-        serde_json::Value::String(format!("Signature (base64): {}", parts[2])),
-    );
-
-    // Add token parts for reference
-    let mut token_parts = serde_json::Map::new();
-    token_parts.insert(
-        "header".to_string(),
-        serde_json::Value::String(parts[0].to_string()),
-    );
-    token_parts.insert(
-        "payload".to_string(),
-        serde_json::Value::String(parts[1].to_string()),
-    );
-    token_parts.insert(
-        "signature".to_string(),
-        serde_json::Value::String(parts[2].to_string()),
-    );
-    result.insert(
-        "token_parts".to_string(),
-        serde_json::Value::Object(token_parts),
-    );
-
-    // Convert to pretty JSON
-    match serde_json::to_string_pretty(&result) {
-        Ok(formatted) => Ok(formatted),
-        Err(e) => Err(format!("Failed to format JWT output: {}", e)),
     }
 }
 
-fn decode_jwt_part(encoded: &str) -> Result<serde_json::Value, String> {
-    // Add padding if needed (JWT base64 encoding omits padding)
-    let mut padded = encoded.to_string();
-    while padded.len() % 4 != 0 {
-        padded.push('=');
-    }
+// Content storage for managing large files. There is always at least one
+// document, and commands that don't take a document id work on the active one.
+pub struct ContentStorage {
+    documents: BTreeMap<u64, Document>,
+    active_id: u64,
+    next_id: u64,
+    format_cache: format_cache::FormatCache,
+}
 
-    // Replace URL-safe characters
-    let standard_base64 = padded.replace('-', "+").replace('_', "/");
-
-    // Decode base64
-    match STANDARD.decode(&standard_base64) {
-        Ok(decoded_bytes) => {
-            // Convert to string
-            match String::from_utf8(decoded_bytes) {
-                Ok(decoded_string) => {
-                    // Parse as JSON
-                    match serde_json::from_str(&decoded_string) {
-                        Ok(json_value) => Ok(json_value),
-                        Err(e) => Err(format!("Invalid JSON in JWT part: {}", e)),
-                    }
-                }
-                Err(e) => Err(format!("Invalid UTF-8 in JWT part: {}", e)),
-            }
+impl Default for ContentStorage {
+    fn default() -> Self {
+        let mut documents = BTreeMap::new();
+        documents.insert(1, Document::named("Untitled 1".to_string()));
+        ContentStorage {
+            documents,
+            active_id: 1,
+            next_id: 2,
+            format_cache: format_cache::FormatCache::default(),
         }
-        Err(e) => Err(format!("Invalid base64 encoding: {}", e)),
     }
 }
 
-fn encode_base64(text: &str) -> Result<String, String> {
-    if text.is_empty() {
-        return Ok(String::new()); // Return empty string instead of error
+impl ContentStorage {
+    pub fn active(&self) -> &Document {
+        &self.documents[&self.active_id]
     }
-    Ok(STANDARD.encode(text.as_bytes()))
-}
 
-fn decode_base64(text: &str) -> Result<String, String> {
-    let text = text.trim();
-    if text.is_empty() {
-        return Ok(String::new()); // Return empty string instead of error
+    pub fn active_mut(&mut self) -> &mut Document {
+        self.documents
+            .get_mut(&self.active_id)
+            .expect("active document always exists")
     }
-    match STANDARD.decode(text) {
-        Ok(decoded_bytes) => match String::from_utf8(decoded_bytes) {
-            Ok(decoded_string) => Ok(decoded_string),
-            Err(e) => Err(format!("Invalid UTF-8 in decoded data: {}", e)),
-        },
-        Err(e) => Err(format!("Invalid base64 encoding: {}", e)),
-    }
-}
-
-fn summarize_json(text: &str) -> Result<String, String> {
-    let trimmed = text.trim();
 
-    if trimmed.is_empty() {
-        return Err("Empty JSON input".to_string());
+    pub fn document(&self, id: u64) -> Option<&Document> {
+        self.documents.get(&id)
     }
 
-    // Parse the JSON to validate it
-    let parsed_value: serde_json::Value = match serde_json::from_str(trimmed) {
-        Ok(value) => value,
-        Err(e) => return Err(format!("Invalid JSON: {}", e)),
-    };
-
-    // Generate summary
-    let summary = generate_json_summary(&parsed_value, "root", 0);
-
-    // Format as a readable summary
-    let mut result = String::new();
-    result.push_str("JSON Structure Summary:\n");
-    result.push_str("======================\n\n");
-    result.push_str(&summary);
-
-    // Add statistics
-    let stats = calculate_json_stats(&parsed_value);
-    result.push_str("\n\nStatistics:\n");
-    result.push_str("-----------\n");
-    result.push_str(&format!("Total objects: {}\n", stats.objects));
-    result.push_str(&format!("Total arrays: {}\n", stats.arrays));
-    result.push_str(&format!("Total primitive values: {}\n", stats.primitives));
-    result.push_str(&format!("Maximum depth: {}\n", stats.max_depth));
-    result.push_str(&format!("Total keys: {}\n", stats.total_keys));
-
-    Ok(result)
-}
-
-fn generate_json_summary(value: &serde_json::Value, key: &str, depth: usize) -> String {
-    let indent = "  ".repeat(depth);
-
-    match value {
-        serde_json::Value::Object(obj) => {
-            let mut summary = String::new();
-            if depth == 0 {
-                summary.push_str(&format!(
-                    "{}📁 {} (Object with {} keys)\n",
-                    indent,
-                    key,
-                    obj.len()
-                ));
-            } else {
-                summary.push_str(&format!(
-                    "{}📁 {}: Object ({} keys)\n",
-                    indent,
-                    key,
-                    obj.len()
-                ));
-            }
+    pub fn document_mut(&mut self, id: u64) -> Option<&mut Document> {
+        self.documents.get_mut(&id)
+    }
 
-            for (k, v) in obj.iter() {
-                summary.push_str(&generate_json_summary(v, k, depth + 1));
-            }
-            summary
-        }
-        serde_json::Value::Array(arr) => {
-            let mut summary = String::new();
-            summary.push_str(&format!(
-                "{}📋 {}: Array ({} items)\n",
-                indent,
-                key,
-                arr.len()
-            ));
-
-            if !arr.is_empty() {
-                // Show type of first element and if all elements are the same type
-                let first_type = get_value_type(&arr[0]);
-                let all_same_type = arr.iter().all(|v| get_value_type(v) == first_type);
-
-                if all_same_type {
-                    summary.push_str(&format!("{}   └─ All items are: {}\n", indent, first_type));
-                } else {
-                    summary.push_str(&format!("{}   └─ Mixed types: ", indent));
-                    let mut types = std::collections::HashSet::new();
-                    for item in arr.iter() {
-                        types.insert(get_value_type(item));
-                    }
-                    let type_list: Vec<String> = types.into_iter().collect();
-                    summary.push_str(&type_list.join(", "));
-                    summary.push('\n');
-                }
+    // Takes a handle on the active document's raw text, so a transform can drop
+    // the lock before doing any real work
+    pub fn snapshot_raw(&self) -> Result<ContentSnapshot, String> {
+        let text = self
+            .active()
+            .raw_content
+            .clone()
+            .ok_or_else(|| "No content stored".to_string())?;
+        Ok(ContentSnapshot {
+            document_id: self.active_id,
+            text,
+        })
+    }
 
-                // If it's an array of objects, show the structure of the first object
-                if let serde_json::Value::Object(_) = &arr[0] {
-                    summary.push_str(&format!("{}   └─ First item structure:\n", indent));
-                    summary.push_str(&generate_json_summary(&arr[0], "item", depth + 2));
-                }
-            }
-            summary
-        }
-        serde_json::Value::String(s) => {
-            let preview = if s.len() > 50 {
-                format!("{}...", &s[..47])
-            } else {
-                s.clone()
-            };
-            format!(
-                "{}📝 {}: String ({} chars) - \"{}\"\n",
-                indent,
-                key,
-                s.len(),
-                preview
-            )
-        }
-        serde_json::Value::Number(n) => {
-            format!("{}🔢 {}: Number - {}\n", indent, key, n)
-        }
-        serde_json::Value::Bool(b) => {
-            format!("{}✅ {}: Boolean - {}\n", indent, key, b)
-        }
-        serde_json::Value::Null => {
-            format!("{}❌ {}: null\n", indent, key)
+    // The document a snapshot came from, for storing the transform's result.
+    // Fails if the document was closed or its raw text replaced in the meantime,
+    // so a stale result never overwrites newer content.
+    pub fn snapshot_document(&mut self, snapshot: &ContentSnapshot) -> Result<&mut Document, String> {
+        let document = self
+            .documents
+            .get_mut(&snapshot.document_id)
+            .ok_or_else(|| "The document was closed while it was being transformed".to_string())?;
+        if document.raw_content.as_ref().map(TextBuffer::id) != Some(snapshot.text.id()) {
+            return Err("The content changed while it was being transformed; run it again".to_string());
         }
+        Ok(document)
     }
-}
 
-fn get_value_type(value: &serde_json::Value) -> String {
-    match value {
-        serde_json::Value::Object(_) => "Object".to_string(),
-        serde_json::Value::Array(_) => "Array".to_string(),
-        serde_json::Value::String(_) => "String".to_string(),
-        serde_json::Value::Number(_) => "Number".to_string(),
-        serde_json::Value::Bool(_) => "Boolean".to_string(),
-        serde_json::Value::Null => "null".to_string(),
+    // Adds an empty document, makes it active and returns its id
+    pub fn insert_document(&mut self, name: String) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.documents.insert(id, Document::named(name));
+        self.active_id = id;
+        id
     }
-}
 
-struct JsonStats {
-    objects: usize,
-    arrays: usize,
-    primitives: usize,
-    max_depth: usize,
-    total_keys: usize,
-}
-
-fn calculate_json_stats(value: &serde_json::Value) -> JsonStats {
-    let mut stats = JsonStats {
-        objects: 0,
-        arrays: 0,
-        primitives: 0,
-        max_depth: 0,
-        total_keys: 0,
-    };
-
-    calculate_stats_recursive(value, &mut stats, 0);
-    stats
-}
-
-fn calculate_stats_recursive(value: &serde_json::Value, stats: &mut JsonStats, depth: usize) {
-    stats.max_depth = stats.max_depth.max(depth);
-
-    match value {
-        serde_json::Value::Object(obj) => {
-            stats.objects += 1;
-            stats.total_keys += obj.len();
-            for v in obj.values() {
-                calculate_stats_recursive(v, stats, depth + 1);
+    // Drops the inflated copies whole-text work left on compressed documents
+    pub fn release_inflated(&mut self) {
+        for document in self.documents.values_mut() {
+            let contents = [&mut document.raw_content, &mut document.formatted_content];
+            for content in contents.into_iter().flatten() {
+                content.release_inflated();
             }
         }
-        serde_json::Value::Array(arr) => {
-            stats.arrays += 1;
-            for v in arr.iter() {
-                calculate_stats_recursive(v, stats, depth + 1);
-            }
-        }
-        _ => {
-            stats.primitives += 1;
-        }
     }
 }
 
-// Content storage for managing large files
-#[derive(Default)]
-pub struct ContentStorage {
-    raw_content: Option<String>,
-    formatted_content: Option<String>,
+// Raw text shared with storage, taken by ContentStorage::snapshot_raw
+pub struct ContentSnapshot {
+    document_id: u64,
+    pub text: TextBuffer,
 }
 
 pub type AppState = Mutex<ContentStorage>;
@@ -539,141 +599,647 @@ pub type AppState = Mutex<ContentStorage>;
 #[tauri::command]
 fn store_raw_content(content: String, state: State<AppState>) -> Result<(), String> {
     let mut storage = state.lock().map_err(|e| e.to_string())?;
-    storage.raw_content = Some(content);
-    storage.formatted_content = None; // Clear formatted content when new raw content is set
+    let document = storage.active_mut();
+    document.raw_content = Some(content.into());
+    document.formatted_content = None; // Clear formatted content when new raw content is set
+    document.binary_content = None;
+    document.clear_history();
     Ok(())
 }
 
 #[tauri::command]
 fn store_formatted_content(content: String, state: State<AppState>) -> Result<(), String> {
     let mut storage = state.lock().map_err(|e| e.to_string())?;
-    storage.formatted_content = Some(content);
+    storage.active_mut().formatted_content = Some(content.into());
     Ok(())
 }
 
+// Bytes inflated on each side of a compressed chunk read
+const GRAPHEME_CONTEXT: usize = 256;
+
+// Shared by get_content_chunk and get_content_chunk_bytes
+fn read_chunk(storage: &ContentStorage, content_type: &str, start: usize, chunk_size: usize) -> Result<(ChunkHeader, String), String> {
+    let content = match content_type {
+        "raw" => storage.active().raw_content.as_ref(),
+        "formatted" => storage.active().formatted_content.as_ref(),
+        _ => return Err("Invalid content type".to_string()),
+    }
+    .ok_or_else(|| "No content stored".to_string())?;
+
+    let total_length = content.len();
+    if start >= total_length {
+        let header = ChunkHeader {
+            has_more: false,
+            total_length,
+            start: total_length,
+            end: total_length,
+            next_start: total_length,
+        };
+        return Ok((header, String::new()));
+    }
+
+    // Compressed content is only inflated around the requested range; the
+    // margins keep enough context to find grapheme boundaries at either end
+    let (window_start, window) = content.window(
+        start.saturating_sub(GRAPHEME_CONTEXT),
+        start.saturating_add(chunk_size).saturating_add(GRAPHEME_CONTEXT),
+    );
+
+    // Clamp both ends to grapheme boundaries; the actual byte range is reported
+    // back so the caller continues from `next_start` rather than start + chunk_size
+    let local_start = text_buffer::grapheme_floor(&window, start - window_start);
+    let mut local_end = text_buffer::grapheme_floor(&window, start.saturating_add(chunk_size) - window_start);
+    if local_end <= local_start {
+        // chunk_size is smaller than a single grapheme; return that grapheme whole
+        local_end = text_buffer::next_grapheme_boundary(&window, local_start);
+    }
+
+    let end = window_start + local_end;
+    let header = ChunkHeader {
+        has_more: end < total_length,
+        total_length,
+        start: window_start + local_start,
+        end,
+        next_start: end,
+    };
+    Ok((header, window[local_start..local_end].to_string()))
+}
+
 #[tauri::command]
 fn get_content_chunk(
+    app: AppHandle,
     content_type: String, // "raw" or "formatted"
     start: usize,
-    chunk_size: usize,
+    chunk_size: Option<usize>, // defaults to the chunk_size setting
     state: State<AppState>
-) -> Result<serde_json::Value, String> {
+) -> Result<ChunkResponse, String> {
+    let chunk_size = chunk_size.unwrap_or_else(|| settings::current(&app).chunk_size);
     let storage = state.lock().map_err(|e| e.to_string())?;
-    
-    let content = match content_type.as_str() {
-        "raw" => storage.raw_content.as_ref(),
-        "formatted" => storage.formatted_content.as_ref(),
-        _ => return Err("Invalid content type".to_string()),
-    };
-    
-    match content {
-        Some(content_str) => {
-            let total_length = content_str.len();
-            let end = std::cmp::min(start + chunk_size, total_length);
-            
-            if start >= total_length {
-                return Ok(serde_json::json!({
-                    "chunk": "",
-                    "has_more": false,
-                    "total_length": total_length,
-                    "next_start": total_length
-                }));
+    let (header, chunk) = read_chunk(&storage, &content_type, start, chunk_size)?;
+    Ok(ChunkResponse { chunk, header })
+}
+
+// Binary variant of get_content_chunk for scrolling through big documents: the
+// text travels as raw UTF-8 instead of an escaped JSON string. The body is a
+// little-endian u32 header length, the ChunkHeader as JSON, then the chunk bytes.
+#[tauri::command]
+fn get_content_chunk_bytes(
+    app: AppHandle,
+    content_type: String, // "raw" or "formatted"
+    start: usize,
+    chunk_size: Option<usize>, // defaults to the chunk_size setting
+    state: State<AppState>
+) -> Result<tauri::ipc::Response, String> {
+    let chunk_size = chunk_size.unwrap_or_else(|| settings::current(&app).chunk_size);
+    let storage = state.lock().map_err(|e| e.to_string())?;
+    let (header, chunk) = read_chunk(&storage, &content_type, start, chunk_size)?;
+    drop(storage);
+
+    let header = serde_json::to_vec(&header).map_err(|e| e.to_string())?;
+    let mut body = Vec::with_capacity(4 + header.len() + chunk.len());
+    body.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    body.extend_from_slice(&header);
+    body.extend_from_slice(chunk.as_bytes());
+    Ok(tauri::ipc::Response::new(body))
+}
+
+// Line-based counterpart of get_content_chunk for virtual scrolling and go-to-line.
+// Lines are 0-based and returned without their line endings. The first call on
+// a buffer builds its line index, so the work runs off the main thread on a
+// clone of the buffer, without holding the storage lock.
+#[tauri::command]
+async fn get_content_lines(
+    app: AppHandle,
+    content_type: String, // "raw" or "formatted"
+    start_line: usize,
+    line_count: usize,
+) -> Result<LinesResponse, String> {
+    run_blocking("get_content_lines", move || {
+        let content = {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            match content_type.as_str() {
+                "raw" => storage.active().raw_content.clone(),
+                "formatted" => storage.active().formatted_content.clone(),
+                _ => return Err("Invalid content type".to_string()),
             }
-            
-            let chunk = &content_str[start..end];
-            let has_more = end < total_length;
-            
-            Ok(serde_json::json!({
-                "chunk": chunk,
-                "has_more": has_more,
-                "total_length": total_length,
-                "next_start": end
-            }))
-        },
-        None => Err("No content stored".to_string()),
-    }
+            .ok_or_else(|| "No content stored".to_string())?
+        };
+
+        let total_lines = content.line_count();
+        let end_line = std::cmp::min(start_line.saturating_add(line_count), total_lines);
+        let lines = (start_line..end_line)
+            .filter_map(|i| content.line(i))
+            .map(str::to_string)
+            .collect();
+
+        Ok(LinesResponse {
+            lines,
+            start_line,
+            total_lines,
+            has_more: end_line < total_lines,
+            next_line: end_line,
+            start_offset: content.line_start_offset(start_line.min(total_lines)).unwrap_or(content.len()),
+        })
+    })
+    .await
 }
 
 #[tauri::command]
-fn get_content_info(state: State<AppState>) -> Result<serde_json::Value, String> {
+fn get_content_info(state: State<AppState>) -> Result<ContentInfo, String> {
     let storage = state.lock().map_err(|e| e.to_string())?;
-    
-    let raw_length = storage.raw_content.as_ref().map(|s| s.len()).unwrap_or(0);
-    let formatted_length = storage.formatted_content.as_ref().map(|s| s.len()).unwrap_or(0);
-    
-    Ok(serde_json::json!({
-        "has_raw": storage.raw_content.is_some(),
-        "has_formatted": storage.formatted_content.is_some(),
-        "raw_length": raw_length,
-        "formatted_length": formatted_length
-    }))
+    Ok(storage.active().info())
+}
+
+// Keeps large stored text zstd-compressed in memory. Applies to content stored
+// from now on and repacks what every open document already holds.
+#[tauri::command]
+async fn set_content_compression(app: AppHandle, enabled: bool) -> Result<(), String> {
+    run_blocking("set_content_compression", move || {
+        text_buffer::set_compression_enabled(enabled);
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        for document in storage.documents.values_mut() {
+            document.raw_content = document.raw_content.take().map(TextBuffer::repack);
+            document.formatted_content = document.formatted_content.take().map(TextBuffer::repack);
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+fn get_content_compression() -> bool {
+    text_buffer::compression_enabled()
 }
 
 #[tauri::command]
 fn clear_content(state: State<AppState>) -> Result<(), String> {
     let mut storage = state.lock().map_err(|e| e.to_string())?;
-    storage.raw_content = None;
-    storage.formatted_content = None;
+    let document = storage.active_mut();
+    document.raw_content = None;
+    document.formatted_content = None;
+    document.binary_content = None;
+    document.clear_history();
     Ok(())
 }
 
-#[tauri::command]
-fn read_large_file_streaming(file_path: String, state: State<AppState>) -> Result<serde_json::Value, String> {
-    use std::fs::File;
-    use std::io::{BufReader, Read};
-    
-    let file = File::open(&file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+const READ_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+// Decodes a file or pipe chunk by chunk, so memory holds the decoded text and
+// one read buffer rather than the whole input twice. `size_hint` pre-sizes the
+// output; pass 0 when the length isn't known. `progress` gets the number of
+// input bytes decoded so far.
+fn decode_streaming(
+    mut reader: impl std::io::Read,
+    size_hint: u64,
+    token: &jobs::CancelToken,
+    mut progress: impl FnMut(u64),
+) -> Result<(TextBuffer, &'static str), String> {
+    let mut buffer = vec![0u8; READ_CHUNK_SIZE];
+    // Pipes hand over whatever is ready, so fill the first chunk before sniffing the encoding
+    let mut read = 0;
+    while read < buffer.len() {
+        token.check()?;
+        match reader.read(&mut buffer[read..]).map_err(|e| format!("Failed to read file: {}", e))? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    let encoding = charset::detect_encoding(&buffer[..read]).encoding;
+    // The decoder sniffs and strips a BOM
+    let mut decoder = encoding.new_decoder();
+    let mut content = String::with_capacity(size_hint as usize);
+
+    let mut chunk_len = read;
+    let mut processed = 0u64;
+    loop {
+        token.check()?;
+        let last = chunk_len == 0;
+        let needed = decoder
+            .max_utf8_buffer_length(chunk_len)
+            .ok_or_else(|| "File is too large to decode".to_string())?;
+        content.reserve(needed);
+        // Capacity was reserved above, so the whole chunk is always consumed
+        let _ = decoder.decode_to_string(&buffer[..chunk_len], &mut content, last);
+        if last {
+            break;
+        }
+        processed += chunk_len as u64;
+        progress(processed);
+        chunk_len = reader.read(&mut buffer).map_err(|e| format!("Failed to read file: {}", e))?;
+    }
+    Ok((content.into(), decoder.encoding().name()))
+}
+
+fn load_file_blocking(app: &AppHandle, token: &jobs::CancelToken, file_path: String) -> Result<FileLoadResult, String> {
+    let mut file = std::fs::File::open(&file_path).map_err(|e| format!("Failed to open file: {}", e))?;
     let metadata = file.metadata().map_err(|e| format!("Failed to get file metadata: {}", e))?;
     let file_size = metadata.len();
     
-    // For files larger than 100MB, we'll read them in streaming mode
-    if file_size > 100 * 1024 * 1024 {
-        // Read file in chunks and store in backend
-        let mut reader = BufReader::new(file);
-        let mut content = String::new();
-        
-        // Read the entire file (we have enough memory in Rust backend)
-        reader.read_to_string(&mut content).map_err(|e| format!("Failed to read file: {}", e))?;
-        
-        // Store in backend
-        let mut storage = state.lock().map_err(|e| e.to_string())?;
-        storage.raw_content = Some(content);
-        storage.formatted_content = None;
-        
-        Ok(serde_json::json!({
-            "success": true,
-            "file_size": file_size,
-            "use_streaming": true,
-            "message": "Large file loaded successfully using streaming mode"
-        }))
-    } else {
+    // Files above the large_file_threshold setting are loaded into the backend instead of the webview.
+    // Compressed files always are: the webview can't decompress them.
+    if file_size <= settings::current(app).large_file_threshold && compression::sniff_file(&mut file)?.is_none() {
         // For smaller files, let frontend handle normally
-        Ok(serde_json::json!({
-            "success": true,
-            "file_size": file_size,
-            "use_streaming": false,
-            "message": "File size is manageable, frontend can handle normally"
-        }))
+        return Ok(FileLoadResult {
+            success: true,
+            file_size,
+            use_streaming: false,
+            encoding: None,
+            memory_mapped: None,
+            compression: None,
+            uncompressed_size: None,
+            message: "File size is manageable, frontend can handle normally".to_string(),
+        });
     }
+    
+    // The file lands in whichever document was active when loading started
+    let document_id = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?.active_id;
+    let result = load_file_into_document(app, token, document_id, file, file_size, &file_path, true)?;
+    recent_files::record(app, std::path::Path::new(&file_path), file_size, None);
+    Ok(result)
+}
+
+// Reads a file of any size into one document's raw content, memory-mapping
+// UTF-8 (unless `allow_map` is false) and decoding other encodings as it goes.
+// gzip and zstd files are decompressed on the way in.
+fn load_file_into_document(
+    app: &AppHandle,
+    token: &jobs::CancelToken,
+    document_id: u64,
+    mut file: std::fs::File,
+    file_size: u64,
+    file_path: &str,
+    allow_map: bool,
+) -> Result<FileLoadResult, String> {
+    let mut reporter = ProgressReporter::new(
+        app,
+        progress::FILE_READ_PROGRESS_EVENT,
+        file_size,
+        serde_json::json!({ "file_path": file_path }),
+    );
+    
+    let codec = compression::sniff_file(&mut file)?;
+    let mut uncompressed_size = None;
+    let (content, encoding) = match codec {
+        // Progress follows the compressed bytes read, the only known total
+        Some(codec) => {
+            let compressed = progress::ProgressReader::new(file, Some(&mut reporter));
+            let mut decompressed = progress::ProgressReader::new(compression::decoder(compressed, codec)?, None);
+            let decoded = decode_streaming(&mut decompressed, file_size, token, |_| {})?;
+            uncompressed_size = Some(decompressed.bytes_read());
+            decoded
+        }
+        None if !allow_map => decode_streaming(file, file_size, token, |processed| reporter.update(processed))?,
+        None => match text_buffer::map_utf8_file(&mut file, token, &mut reporter)? {
+            // UTF-8 files are spilled to a private temp file and mapped instead of
+            // being copied into RAM
+            Some(mapped) => (mapped, "UTF-8"),
+            // Non-UTF-8 files (UTF-16, Latin-1, Shift-JIS, ...) are decoded as they're read
+            None => decode_streaming(file, file_size, token, |processed| reporter.update(processed))?,
+        },
+    };
+    token.check()?;
+    let memory_mapped = content.is_mapped();
+    
+    let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let document = storage
+        .documents
+        .get_mut(&document_id)
+        .ok_or_else(|| "The document was closed while the file was loading".to_string())?;
+    document.raw_content = Some(content);
+    document.formatted_content = None;
+    document.binary_content = None;
+    document.clear_history();
+    
+    Ok(FileLoadResult {
+        success: true,
+        file_size,
+        use_streaming: true,
+        encoding: Some(encoding.to_string()),
+        memory_mapped: Some(memory_mapped),
+        compression: codec.map(str::to_string),
+        uncompressed_size,
+        message: match codec {
+            Some(codec) => format!("{} file decompressed and loaded using streaming mode", codec),
+            None => "Large file loaded successfully using streaming mode".to_string(),
+        },
+    })
+}
+
+#[tauri::command]
+async fn read_large_file_streaming(
+    app: AppHandle,
+    file_path: String,
+) -> Result<FileLoadResult, String> {
+    run_blocking("read_large_file_streaming", move || load_file_blocking(&app, &jobs::CancelToken::default(), file_path)).await
+}
+
+// Cancellable variant of read_large_file_streaming; the FileLoadResult arrives
+// as the `result` of the `job://finished` event
+#[tauri::command]
+fn start_file_load(app: AppHandle, file_path: String) -> Result<u64, String> {
+    jobs::spawn_job(&app, "file-load", move |app, token| {
+        let result = load_file_blocking(app, token, file_path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let stdin_format = stdin::requested(&std::env::args().collect::<Vec<_>>());
+    let mut builder = tauri::Builder::default();
+    // A piped launch keeps its own window: handing its arguments to a running
+    // instance would leave stdin behind
+    if stdin_format.is_none() {
+        // Must come first: a second launch hands its files to this instance and exits
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            open_files::open_paths(app, open_files::file_arguments(argv, std::path::Path::new(&cwd)));
+        }));
+    }
+    builder
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(quick_action::plugin())
         .manage(AppState::default())
+        .manage(jobs::JobRegistry::default())
+        .manage(live::LiveTransform::default())
+        .manage(json_tree::JsonTreeCache::default())
+        .manage(clipboard_history::ClipboardHistory::default())
+        .manage(quick_action::QuickAction::default())
+        .manage(watch::FileWatches::default())
+        .manage(local_api::LocalApi::default())
+        .manage(stdin::StdinResult::default())
+        .manage(sql_client::SqlResults::default())
+        .manage(redis_client::RedisSession::default())
+        .setup(move |app| {
+            let data_dir = app.path().app_data_dir()?;
+            let mut registry = FormatterRegistry::with_builtins();
+            plugins::load_plugins(&mut registry, &data_dir.join("plugins"));
+            app.manage(registry);
+            // History is optional; the app still works if the database can't be opened
+            match history::HistoryDb::open(&data_dir.join("history.sqlite3")) {
+                Ok(db) => {
+                    app.manage(db);
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+            settings::load(app.handle());
+            recent_files::load(app.handle());
+            snippets::load(app.handle());
+            share::load(app.handle());
+            sql_client::load(app.handle());
+            http_client::load(app.handle());
+            http_collections::load(app.handle());
+            session::restore_session(app.handle());
+            // Whole-text work inflates compressed documents; once nothing is
+            // running, pack them down again. A busy lock means some command is
+            // still using the text, and the next idle moment will catch it.
+            let handle = app.handle().clone();
+            tasks::queue().on_idle(move || {
+                if let Ok(mut storage) = handle.state::<AppState>().inner().try_lock() {
+                    storage.release_inflated();
+                }
+            });
+            // Another app may already own the shortcut; it can be changed later
+            if let Err(e) = quick_action::register(app.handle()) {
+                eprintln!("{}", e);
+            }
+            tray::create(app.handle())?;
+            deep_link::register(app.handle());
+            if let Ok(cwd) = std::env::current_dir() {
+                open_files::open_paths(app.handle(), open_files::file_arguments(std::env::args(), &cwd));
+            }
+            if let Some(format_type) = stdin_format {
+                stdin::load(app.handle(), format_type);
+            }
+            Ok(())
+        })
+        .on_window_event(tray::on_window_event)
         .invoke_handler(tauri::generate_handler![
             greet,
             format_text,
+            start_format_job,
+            list_formatters,
+            detect_format,
+            validate_jwt,
+            analyze_json_size,
+            lint_json,
+            minify_content,
             store_raw_content,
             store_formatted_content,
             get_content_chunk,
+            get_content_chunk_bytes,
+            get_content_lines,
             get_content_info,
+            set_content_compression,
+            get_content_compression,
             clear_content,
-            read_large_file_streaming
+            read_large_file_streaming,
+            start_file_load,
+            tls::inspect_tls_certificate,
+            detect::detect_content_type,
+            checksum::compute_file_checksum,
+            checksum::compute_checksum,
+            compression::compress_content,
+            compression::decompress_content,
+            archive::list_archive_entries,
+            archive::extract_archive_entry,
+            data_uri::image_to_data_uri,
+            data_uri::decode_data_uri,
+            qr::generate_qr_code,
+            qr::decode_qr_code,
+            lines::transform_lines,
+            replace::regex_replace,
+            charset::detect_file_encoding,
+            charset::transcode_to_utf8,
+            pem::parse_pem_bundle,
+            pem::decode_pem_block,
+            idn::convert_idn,
+            slug::slugify_lines,
+            permissions::convert_file_mode,
+            units::convert_data_size,
+            units::estimate_transfer_time,
+            json_repair::repair_json,
+            yaml::list_yaml_documents,
+            yaml::select_yaml_document,
+            template::render_template,
+            whitespace::analyze_whitespace,
+            whitespace::normalize_whitespace,
+            json_sort::sort_json_array,
+            columns::align_columns,
+            scripting::run_script,
+            scripting::save_script,
+            scripting::list_scripts,
+            scripting::delete_script,
+            jobs::cancel_job,
+            jobs::list_jobs,
+            search::search_content,
+            documents::create_document,
+            documents::list_documents,
+            documents::switch_document,
+            documents::rename_document,
+            documents::close_document,
+            history::list_history,
+            history::search_history,
+            history::rerun_history,
+            history::purge_history,
+            session::update_document_view,
+            session::get_session_settings,
+            session::set_session_settings,
+            session::save_session,
+            undo::undo_content,
+            undo::redo_content,
+            export::save_content_to_file,
+            format_cache::get_format_cache_info,
+            format_cache::set_format_cache_limit,
+            format_cache::clear_format_cache,
+            records::validate_ndjson,
+            records::filter_lines,
+            records::profile_csv,
+            json_tree::get_json_node,
+            json_tree::release_json_tree,
+            outline::compute_outline,
+            highlight::get_highlight_tokens,
+            tasks::list_tasks,
+            tasks::set_task_concurrency,
+            clipboard::format_from_clipboard,
+            clipboard::copy_result_to_clipboard,
+            clipboard_history::set_clipboard_watch,
+            clipboard_history::list_clipboard_history,
+            clipboard_history::load_clipboard_entry,
+            clipboard_history::delete_clipboard_entry,
+            clipboard_history::clear_clipboard_history,
+            quick_action::get_quick_action_payload,
+            quick_action::get_quick_action_shortcut,
+            quick_action::set_quick_action_shortcut,
+            quick_action::open_quick_action,
+            quick_action::close_quick_action,
+            open_files::open_dropped_files,
+            fetch::load_from_url,
+            fetch::start_url_load,
+            watch::watch_file,
+            watch::unwatch_file,
+            watch::list_file_watches,
+            local_api::start_local_api,
+            local_api::stop_local_api,
+            local_api::get_local_api_status,
+            settings::get_settings,
+            settings::update_settings,
+            settings::reset_settings,
+            recent_files::list_recent_files,
+            recent_files::add_recent_file,
+            recent_files::pin_recent_file,
+            recent_files::remove_recent_file,
+            recent_files::clear_recent_files,
+            recent_files::reopen_recent_file,
+            snippets::save_snippet,
+            snippets::search_snippets,
+            snippets::list_snippet_tags,
+            snippets::load_snippet,
+            snippets::delete_snippet,
+            snippets::export_snippets,
+            snippets::import_snippets,
+            share::share_content,
+            share::get_share_config,
+            share::set_share_config,
+            stdin::get_stdin_result,
+            sql_client::save_db_connection,
+            sql_client::list_db_connections,
+            sql_client::delete_db_connection,
+            sql_client::test_db_connection,
+            sql_client::run_sql_query,
+            sql_client::get_sql_result_page,
+            redis_client::redis_connect,
+            redis_client::redis_disconnect,
+            redis_client::redis_status,
+            redis_client::redis_scan_keys,
+            redis_client::redis_get_value,
+            kafka::kafka_list_topics,
+            kafka::kafka_peek,
+            environment::get_environment,
+            docker::docker_info,
+            docker::docker_list_containers,
+            docker::docker_list_images,
+            docker::docker_inspect,
+            docker::docker_container_logs,
+            patch::apply_patch,
+            redact::redact_content,
+            json_unwrap::unwrap_json,
+            batch::batch_format,
+            url_compare::compare_urls,
+            lorem::generate_lorem,
+            lorem::generate_bulk_text,
+            http_client::send_http_request,
+            http_client::save_http_environment,
+            http_client::list_http_environments,
+            http_client::delete_http_environment,
+            http_client::list_http_history,
+            http_client::clear_http_history,
+            http_collections::save_http_collection,
+            http_collections::list_http_collections,
+            http_collections::delete_http_collection,
+            http_collections::run_http_collection,
+            openapi::list_openapi_operations,
+            openapi::scaffold_openapi_request,
+            avro::decode_avro,
+            parquet_file::inspect_parquet,
+            parquet_file::read_parquet_rows,
+            spreadsheet::list_spreadsheet_sheets,
+            spreadsheet::import_spreadsheet_sheet,
+            robots::analyze_robots,
+            robots::analyze_sitemap,
+            http_headers::analyze_http_headers,
+            cookies::parse_cookies,
+            cookies::build_cookies,
+            csp::evaluate_csp,
+            user_agent::parse_user_agents,
+            geoip::lookup_ip_addresses,
+            log_timeline::analyze_log_timeline,
+            ansi::convert_ansi,
+            git_config::convert_git_remote_url,
+            git_config::parse_git_config,
+            git_config::parse_ssh_client_config,
+            semver::parse_semver,
+            semver::compare_semver,
+            semver::check_semver_range,
+            schedules::summarize_schedules,
+            schedules::explain_schedule,
+            byte_editor::read_bytes,
+            byte_editor::write_bytes,
+            byte_editor::export_bytes,
+            multipart::decompose_multipart,
+            live::live_transform,
+            locale_format::format_for_locales,
+            locale_format::parse_locale_number,
+            phone::parse_phone_number,
+            phone::normalize_phone_numbers,
+            validators::validate_check_digits,
+            validators::validate_check_digits_bulk,
+            oauth::build_oauth_authorization_url,
+            oauth::decode_oauth_redirect,
+            oauth::exchange_oauth_code,
+            sql_fixtures::convert_query_output,
+            ics::describe_ics,
+            html_table::render_html_table,
+            config_bundle::export_config_bundle,
+            config_bundle::import_config_bundle
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            tauri::RunEvent::Exit => {
+                if let Err(e) = session::save_session_to_disk(app) {
+                    eprintln!("Failed to save session: {}", e);
+                }
+            }
+            // macOS delivers "Open with" and double-clicked files as an event, not arguments
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            tauri::RunEvent::Opened { urls } => {
+                let paths = urls.iter().filter_map(|url| url.to_file_path().ok()).collect();
+                open_files::open_paths(app, paths);
+            }
+            _ => {}
+        });
 }
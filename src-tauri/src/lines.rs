@@ -0,0 +1,208 @@
+use rand::seq::SliceRandom;
+use rayon::slice::ParallelSliceMut;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+// Compare strings treating embedded digit runs as numbers ("file2" < "file10")
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut x_num = String::new();
+                while let Some(c) = a_chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+                    x_num.push(c);
+                    a_chars.next();
+                }
+                let mut y_num = String::new();
+                while let Some(c) = b_chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+                    y_num.push(c);
+                    b_chars.next();
+                }
+                let x_trim = x_num.trim_start_matches('0');
+                let y_trim = y_num.trim_start_matches('0');
+                let ordering = x_trim
+                    .len()
+                    .cmp(&y_trim.len())
+                    .then_with(|| x_trim.cmp(y_trim));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn numeric_key(line: &str) -> f64 {
+    line.trim()
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .next()
+        .and_then(|token| token.parse::<f64>().ok())
+        .unwrap_or(f64::INFINITY)
+}
+
+// Operations work on lines without their endings; the result is joined with
+// the input's line ending (CRLF when its first line ends that way) and keeps
+// its trailing newline, if it had one
+pub fn apply_line_operation(text: &str, operation: &str, mode: Option<&str>) -> Result<String, String> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let line_ending = match text.find('\n') {
+        Some(end) if text[..end].ends_with('\r') => "\r\n",
+        _ => "\n",
+    };
+
+    let result = match operation {
+        "sort" => {
+            // "reverse" is a descending lexical sort; any mode can be made
+            // descending with a "-reverse" suffix ("numeric-reverse")
+            let (order, descending) = match mode.unwrap_or("lexical") {
+                "reverse" => ("lexical", true),
+                mode => match mode.strip_suffix("-reverse") {
+                    Some(order) => (order, true),
+                    None => (mode, false),
+                },
+            };
+            let compare: fn(&&str, &&str) -> Ordering = match order {
+                "lexical" => |a, b| a.cmp(b),
+                "natural" => |a, b| natural_cmp(a, b),
+                // total_cmp keeps NaN lines in one place instead of breaking the sort
+                "numeric" => |a, b| numeric_key(a).total_cmp(&numeric_key(b)),
+                _ => return Err(format!("Unknown sort mode: {}", mode.unwrap_or_default())),
+            };
+            // Parallel stable sorts; equal lines keep their original order either way
+            if descending {
+                lines.par_sort_by(|a, b| compare(b, a));
+            } else {
+                lines.par_sort_by(compare);
+            }
+            lines.join(line_ending)
+        }
+        "dedupe" => {
+            let mut seen = HashSet::new();
+            match mode.unwrap_or("first") {
+                "first" => lines.retain(|line| seen.insert(*line)),
+                "last" => {
+                    lines.reverse();
+                    lines.retain(|line| seen.insert(*line));
+                    lines.reverse();
+                }
+                other => return Err(format!("Unknown dedupe mode: {}", other)),
+            }
+            lines.join(line_ending)
+        }
+        "shuffle" => {
+            lines.shuffle(&mut rand::thread_rng());
+            lines.join(line_ending)
+        }
+        "number" => {
+            let width = lines.len().to_string().len();
+            lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| format!("{:>width$}  {}", i + 1, line, width = width))
+                .collect::<Vec<_>>()
+                .join(line_ending)
+        }
+        "trim-trailing" => lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join(line_ending),
+        "remove-blank" => lines
+            .into_iter()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(line_ending),
+        _ => return Err(format!("Unknown line operation: {}", operation)),
+    };
+
+    if text.ends_with('\n') && !result.is_empty() {
+        Ok(result + line_ending)
+    } else {
+        Ok(result)
+    }
+}
+
+fn transform_lines_blocking(
+    operation: String,
+    mode: Option<String>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let content = &snapshot.text;
+
+    let input_lines = content.lines().count();
+    let result = apply_line_operation(content, &operation, mode.as_deref())?;
+    let output_lines = result.lines().count();
+    let output_length = result.len();
+
+    // Keep the result in storage so the UI can page through it in chunks
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.snapshot_document(&snapshot)?.edit(Slot::Formatted, result);
+
+    Ok(serde_json::json!({
+        "operation": operation,
+        "input_lines": input_lines,
+        "output_lines": output_lines,
+        "formatted_length": output_length
+    }))
+}
+
+#[tauri::command]
+pub async fn transform_lines(
+    app: AppHandle,
+    operation: String,
+    mode: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("transform_lines", move || {
+        transform_lines_blocking(operation, mode, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_sort_handles_nan() {
+        let sorted = apply_line_operation("10\nNaN\n2\nabc\n-1", "sort", Some("numeric")).unwrap();
+        assert_eq!(sorted, "-1\n2\n10\nabc\nNaN");
+    }
+
+    #[test]
+    fn reverse_sorts_descending() {
+        assert_eq!(apply_line_operation("b\nc\na\nb", "sort", Some("reverse")).unwrap(), "c\nb\nb\na");
+        assert_eq!(
+            apply_line_operation("file2\nfile10\nfile1", "sort", Some("natural-reverse")).unwrap(),
+            "file10\nfile2\nfile1"
+        );
+        assert_eq!(apply_line_operation("3\n-1\n20", "sort", Some("numeric-reverse")).unwrap(), "20\n3\n-1");
+        assert!(apply_line_operation("a", "sort", Some("bogus-reverse")).is_err());
+    }
+
+    #[test]
+    fn line_endings_are_preserved() {
+        assert_eq!(apply_line_operation("b\r\na\r\n", "sort", None).unwrap(), "a\r\nb\r\n");
+        assert_eq!(apply_line_operation("b\na", "sort", None).unwrap(), "a\nb");
+        assert_eq!(apply_line_operation("x  \n\n", "trim-trailing", None).unwrap(), "x\n\n");
+        assert_eq!(apply_line_operation("\n\n", "remove-blank", None).unwrap(), "");
+    }
+}
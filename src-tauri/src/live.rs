@@ -0,0 +1,167 @@
+// Two-pane live mode: the frontend calls `live_transform` (debounced) on every
+// input change with an increasing revision. Small inputs are transformed inline
+// and answered directly; inputs above the live threshold run as a job whose
+// result arrives via `job://finished`. A newer revision cancels the job still
+// running for an older one, and results that were overtaken are marked stale
+// so the output pane never goes backwards. Nothing is stored or recorded in
+// history; the one-shot format_text is still how a result is committed.
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::{FormatOptions, FormatterRegistry};
+use crate::jobs::{CancelToken, JobRegistry};
+use crate::progress::{self, ProgressReporter};
+use crate::text_buffer::TextBuffer;
+use crate::AppState;
+
+#[derive(Default)]
+struct LiveRevision {
+    latest: u64,
+    // Job transforming `latest`, if it was too large to run inline
+    job: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct LiveTransform(Mutex<LiveRevision>);
+
+impl LiveTransform {
+    fn is_current(&self, revision: u64) -> bool {
+        self.0.lock().map(|live| live.latest == revision).unwrap_or(false)
+    }
+}
+
+// Formats `text` with the shared format cache. Errors are part of the result
+// rather than a failure, since half-typed input is the normal case here.
+fn transform(
+    app: &AppHandle,
+    text: &str,
+    format_type: &str,
+    options: FormatOptions,
+    job: Option<(&mut ProgressReporter, &CancelToken)>,
+) -> Result<Value, String> {
+    let registry = app.state::<FormatterRegistry>();
+    let formatter = registry
+        .get(format_type)
+        .ok_or_else(|| "Unknown format type".to_string())?;
+    let options_key = serde_json::to_value(options).map_err(|e| e.to_string())?;
+    let cache_key = crate::format_cache::CacheKey::new(text, format_type, &options_key);
+    let cached = app
+        .state::<AppState>()
+        .inner()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .format_cache
+        .get(&cache_key);
+    let result = match cached {
+        Some(output) => Ok(output),
+        None => {
+            let result = crate::formatters::with_options(options, || match job {
+                Some((reporter, token)) => formatter.format_with_progress(text, &mut |processed| {
+                    token.check()?;
+                    reporter.update(processed as u64);
+                    Ok(())
+                }),
+                None => formatter.format(text),
+            })
+            .map(TextBuffer::from);
+            if let Ok(output) = &result {
+                let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                storage.format_cache.insert(cache_key, output.clone());
+            }
+            result
+        }
+    };
+    Ok(match result {
+        Ok(output) => json!({ "ok": true, "output": output.as_str() }),
+        Err(error) => json!({ "ok": false, "error": error }),
+    })
+}
+
+// Runs the transform for one input revision. Returns `mode: "inline"` with
+// the outcome, or `mode: "job"` with the job id when the input is over
+// `live_transform_threshold`. `stale: true` means a later revision came in
+// while this one ran, and its result should be dropped.
+#[tauri::command]
+pub async fn live_transform(
+    app: AppHandle,
+    text: String,
+    format_type: String,
+    options: Option<Value>,
+    revision: u64,
+) -> Result<Value, String> {
+    let format_type = crate::settings::resolve_format(&app, format_type);
+    let options = crate::settings::resolve_format_options(&app, options)?;
+    let previous_job = {
+        let live = app.state::<LiveTransform>();
+        let mut live = live.0.lock().map_err(|e| e.to_string())?;
+        if revision < live.latest {
+            return Ok(json!({ "revision": revision, "stale": true }));
+        }
+        live.latest = revision;
+        live.job.take()
+    };
+    if let Some(id) = previous_job {
+        crate::jobs::cancel(&app.state::<JobRegistry>(), id)?;
+    }
+
+    if text.len() as u64 <= crate::settings::current(&app).live_transform_threshold {
+        let started = std::time::Instant::now();
+        let outcome = crate::run_blocking("live_transform", {
+            let app = app.clone();
+            let format_type = format_type.clone();
+            move || transform(&app, &text, &format_type, options, None)
+        })
+        .await?;
+        let mut result = json!({
+            "mode": "inline",
+            "revision": revision,
+            "format_type": format_type,
+            "elapsed_ms": started.elapsed().as_millis() as u64,
+            "stale": !app.state::<LiveTransform>().is_current(revision)
+        });
+        if let (Value::Object(result), Value::Object(outcome)) = (&mut result, outcome) {
+            result.extend(outcome);
+        }
+        return Ok(result);
+    }
+
+    let job_format_type = format_type.clone();
+    let id = crate::jobs::spawn_job(&app, "live_transform", move |app, token: &CancelToken| {
+        token.check()?;
+        let mut reporter = ProgressReporter::new(
+            app,
+            progress::FORMAT_PROGRESS_EVENT,
+            text.len() as u64,
+            json!({ "format_type": job_format_type, "revision": revision }),
+        );
+        let mut outcome = transform(app, &text, &job_format_type, options, Some((&mut reporter, token)))?;
+        // Formatters can't be interrupted, so a superseded run ends up here
+        token.check()?;
+        if let Value::Object(outcome) = &mut outcome {
+            outcome.insert("revision".into(), revision.into());
+            outcome.insert("format_type".into(), job_format_type.into());
+            outcome.insert(
+                "stale".into(),
+                (!app.state::<LiveTransform>().is_current(revision)).into(),
+            );
+        }
+        Ok(outcome)
+    })?;
+
+    let live = app.state::<LiveTransform>();
+    let mut live = live.0.lock().map_err(|e| e.to_string())?;
+    if live.latest == revision {
+        live.job = Some(id);
+    } else {
+        // Overtaken while the job was being queued
+        drop(live);
+        crate::jobs::cancel(&app.state::<JobRegistry>(), id)?;
+    }
+    Ok(json!({
+        "mode": "job",
+        "revision": revision,
+        "format_type": format_type,
+        "job_id": id
+    }))
+}
@@ -0,0 +1,220 @@
+// Opt-in HTTP server on 127.0.0.1 so editors and scripts on the same machine
+// can use devmate's formatters:
+//
+//   curl -H "Authorization: Bearer $TOKEN" --data-binary @in.json http://127.0.0.1:PORT/format/json
+//   curl -H "Authorization: Bearer $TOKEN" -d "$JWT" http://127.0.0.1:PORT/decode/jwt
+//
+// Routes: POST /format/<formatter>, POST /decode/<formatter>, POST /detect and
+// GET /formats. Every request needs the token handed out by start_local_api,
+// which changes on each start. No CORS headers are sent and the Host header
+// must name the loopback address, so web pages can't call in.
+use rand::RngCore;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::formatters::{autodetect, FormatterRegistry};
+use crate::text_buffer;
+
+const MAX_BODY_BYTES: u64 = 64 * 1024 * 1024;
+const TOKEN_HEADER: &str = "X-Devmate-Token";
+
+struct RunningServer {
+    port: u16,
+    token: String,
+    server: Arc<Server>,
+}
+
+#[derive(Default)]
+pub struct LocalApi(Mutex<Option<RunningServer>>);
+
+impl Drop for RunningServer {
+    // Ends the accept loop; requests already being handled still get answered
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+fn status(running: Option<&RunningServer>) -> serde_json::Value {
+    match running {
+        Some(running) => serde_json::json!({
+            "running": true,
+            "port": running.port,
+            "url": format!("http://127.0.0.1:{}", running.port),
+            "token": running.token
+        }),
+        None => serde_json::json!({ "running": false }),
+    }
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Compares in constant time so the token can't be guessed byte by byte
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+fn authorized(request: &Request, token: &str) -> bool {
+    let given = header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| header(request, TOKEN_HEADER));
+    given.is_some_and(|given| token_matches(given.trim(), token))
+}
+
+// Rejects DNS-rebinding requests that reach the port under another host name
+fn loopback_host(request: &Request, port: u16) -> bool {
+    header(request, "Host").is_some_and(|host| {
+        ["127.0.0.1", "localhost", "[::1]"]
+            .iter()
+            .any(|name| host.eq_ignore_ascii_case(&format!("{}:{}", name, port)))
+    })
+}
+
+fn respond(request: Request, status_code: u16, content_type: &str, body: String) {
+    let content_type =
+        Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()).expect("content type is a valid header");
+    let _ = request.respond(
+        Response::from_string(body)
+            .with_status_code(status_code)
+            .with_header(content_type),
+    );
+}
+
+fn respond_error(request: Request, status_code: u16, message: String) {
+    let body = serde_json::json!({ "error": message }).to_string();
+    respond(request, status_code, "application/json", body);
+}
+
+fn read_body(request: &mut Request) -> Result<String, (u16, String)> {
+    if request.body_length().is_some_and(|length| length as u64 > MAX_BODY_BYTES) {
+        return Err((413, format!("Request body is over the {} byte limit", MAX_BODY_BYTES)));
+    }
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| (400, format!("Failed to read request body: {}", e)))?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err((413, format!("Request body is over the {} byte limit", MAX_BODY_BYTES)));
+    }
+    String::from_utf8(body).map_err(|_| (400, "Request body must be UTF-8 text".to_string()))
+}
+
+fn handle(app: &AppHandle, mut request: Request, port: u16, token: &str) {
+    if !loopback_host(&request, port) {
+        return respond_error(request, 403, "Requests must be addressed to the loopback host".to_string());
+    }
+    if !authorized(&request, token) {
+        return respond_error(request, 401, "Missing or wrong API token".to_string());
+    }
+
+    let path = request.url().split('?').next().unwrap_or_default().to_string();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let method = request.method().clone();
+    let registry = app.state::<FormatterRegistry>();
+    match (method, segments.as_slice()) {
+        (Method::Get, ["formats"]) => {
+            let body = serde_json::Value::from(registry.describe()).to_string();
+            respond(request, 200, "application/json", body)
+        }
+        (Method::Post, ["format" | "decode", formatter_id]) => {
+            let Some(formatter) = registry.get(formatter_id) else {
+                return respond_error(request, 404, format!("Unknown formatter {}", formatter_id));
+            };
+            let input = match read_body(&mut request) {
+                Ok(input) => input,
+                Err((status_code, message)) => return respond_error(request, status_code, message),
+            };
+            let result = formatter.format(&input);
+            crate::history::record(app, formatter_id, &input, result.as_deref());
+            match result {
+                Ok(output) => respond(request, 200, "text/plain; charset=utf-8", output),
+                Err(error) => respond_error(request, 422, error),
+            }
+        }
+        (Method::Post, ["detect"]) => {
+            let input = match read_body(&mut request) {
+                Ok(input) => input,
+                Err((status_code, message)) => return respond_error(request, status_code, message),
+            };
+            let sample_end = text_buffer::grapheme_floor(&input, autodetect::SAMPLE_BYTES);
+            let guesses: Vec<serde_json::Value> =
+                autodetect::guess_formats(&input[..sample_end], sample_end == input.len(), &registry)
+                    .iter()
+                    .map(|guess| guess.to_json())
+                    .collect();
+            respond(request, 200, "application/json", serde_json::Value::from(guesses).to_string())
+        }
+        (_, ["formats" | "detect"] | ["format" | "decode", _]) => {
+            respond_error(request, 405, "Method not allowed".to_string())
+        }
+        _ => respond_error(request, 404, format!("No route for {}", path)),
+    }
+}
+
+fn serve(app: AppHandle, server: Arc<Server>, port: u16, token: String) {
+    std::thread::spawn(move || {
+        // Ends when the server is unblocked by stop_local_api or a restart
+        for request in server.incoming_requests() {
+            let app = app.clone();
+            let token = token.clone();
+            // Formatting runs on the task queue so API calls share the app's concurrency limit
+            tauri::async_runtime::spawn(crate::tasks::queue().run("local_api", move || {
+                handle(&app, request, port, &token);
+                Ok(())
+            }));
+        }
+    });
+}
+
+// Starts (or restarts) the server on `port`, or a free port when omitted, and
+// returns its URL and a fresh token
+#[tauri::command]
+pub fn start_local_api(app: AppHandle, port: Option<u16>, api: State<LocalApi>) -> Result<serde_json::Value, String> {
+    let mut running = api.0.lock().map_err(|e| e.to_string())?;
+    // Stop the previous server first; its token stops working immediately
+    running.take();
+
+    let server = Server::http(("127.0.0.1", port.unwrap_or(0)))
+        .map_err(|e| format!("Failed to start the local API: {}", e))?;
+    let port = server
+        .server_addr()
+        .to_ip()
+        .map(|address| address.port())
+        .ok_or_else(|| "The local API isn't listening on a TCP port".to_string())?;
+    let server = Arc::new(server);
+    let token = new_token();
+    serve(app, server.clone(), port, token.clone());
+
+    let started = running.insert(RunningServer { port, token, server });
+    Ok(status(Some(started)))
+}
+
+#[tauri::command]
+pub fn stop_local_api(api: State<LocalApi>) -> Result<bool, String> {
+    Ok(api.0.lock().map_err(|e| e.to_string())?.take().is_some())
+}
+
+#[tauri::command]
+pub fn get_local_api_status(api: State<LocalApi>) -> Result<serde_json::Value, String> {
+    Ok(status(api.0.lock().map_err(|e| e.to_string())?.as_ref()))
+}
@@ -0,0 +1,985 @@
+// Locale-aware formatting of numbers, currency amounts, percentages and dates,
+// and the reverse: reading a locale-formatted number back to a canonical
+// value. The conventions are a built-in table of CLDR data for common locales
+// (separators, grouping, symbol placement, short date/time patterns), enough
+// to see why "1.234,56" and "1,234.56" disagree without a browser console per
+// locale. Numbers are handled as decimal strings, so nothing is lost to f64.
+//
+// This is not full CLDR: only the locales in LOCALES are known, with their
+// default numbering system (Latin digits) and short date/time patterns. Any
+// other locale, including another region of a supported language, is rejected
+// with the supported list rather than approximated by a neighbour.
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde_json::{json, Map, Value};
+
+const NBSP: &str = "\u{a0}";
+const NNBSP: &str = "\u{202f}";
+// Most decimals shown for plain numbers, as in ICU's default #,##0.###
+const DEFAULT_MAX_FRACTION: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Grouping {
+    Standard,
+    // 12,34,567: groups of two above the first three digits
+    Indian,
+    // Standard, but four-digit integers stay ungrouped (1000, 10.000)
+    MinimumTwo,
+}
+
+struct Locale {
+    tag: &'static str,
+    name: &'static str,
+    decimal: &'static str,
+    group: &'static str,
+    grouping: Grouping,
+    minus: &'static str,
+    currency: &'static str,
+    currency_after: bool,
+    // A no-break space between amount and symbol
+    currency_space: bool,
+    percent_before: bool,
+    percent_space: &'static str,
+    short_date: &'static str,
+    short_time: &'static str,
+    am_pm: [&'static str; 2],
+    date_time_separator: &'static str,
+}
+
+const EN_US: Locale = Locale {
+    tag: "en-US",
+    name: "English (United States)",
+    decimal: ".",
+    group: ",",
+    grouping: Grouping::Standard,
+    minus: "-",
+    currency: "USD",
+    currency_after: false,
+    currency_space: false,
+    percent_before: false,
+    percent_space: "",
+    short_date: "M/d/yy",
+    short_time: "h:mm a",
+    am_pm: ["AM", "PM"],
+    date_time_separator: ", ",
+};
+
+const DE_DE: Locale = Locale {
+    tag: "de-DE",
+    name: "German (Germany)",
+    decimal: ",",
+    group: ".",
+    currency: "EUR",
+    currency_after: true,
+    currency_space: true,
+    percent_space: NBSP,
+    short_date: "dd.MM.yy",
+    short_time: "HH:mm",
+    ..EN_US
+};
+
+const LOCALES: &[Locale] = &[
+    EN_US,
+    Locale {
+        tag: "en-GB",
+        name: "English (United Kingdom)",
+        currency: "GBP",
+        short_date: "dd/MM/y",
+        short_time: "HH:mm",
+        ..EN_US
+    },
+    Locale {
+        tag: "en-IN",
+        name: "English (India)",
+        grouping: Grouping::Indian,
+        currency: "INR",
+        short_date: "dd/MM/yy",
+        am_pm: ["am", "pm"],
+        ..EN_US
+    },
+    Locale {
+        tag: "en-CA",
+        name: "English (Canada)",
+        currency: "CAD",
+        short_date: "y-MM-dd",
+        am_pm: ["a.m.", "p.m."],
+        ..EN_US
+    },
+    DE_DE,
+    Locale {
+        tag: "de-AT",
+        name: "German (Austria)",
+        group: NBSP,
+        currency_after: false,
+        ..DE_DE
+    },
+    Locale {
+        tag: "de-CH",
+        name: "German (Switzerland)",
+        decimal: ".",
+        group: "\u{2019}",
+        currency: "CHF",
+        currency_after: false,
+        percent_space: "",
+        ..DE_DE
+    },
+    Locale {
+        tag: "fr-FR",
+        name: "French (France)",
+        group: NNBSP,
+        percent_space: NNBSP,
+        short_date: "dd/MM/y",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "fr-CA",
+        name: "French (Canada)",
+        group: NBSP,
+        currency: "CAD",
+        short_date: "y-MM-dd",
+        short_time: "HH 'h' mm",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "fr-CH",
+        name: "French (Switzerland)",
+        group: NNBSP,
+        currency: "CHF",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "es-ES",
+        name: "Spanish (Spain)",
+        grouping: Grouping::MinimumTwo,
+        short_date: "d/M/yy",
+        short_time: "H:mm",
+        ..DE_DE
+    },
+    Locale {
+        tag: "es-MX",
+        name: "Spanish (Mexico)",
+        currency: "MXN",
+        percent_space: NBSP,
+        short_date: "dd/MM/yy",
+        short_time: "H:mm",
+        am_pm: ["a.m.", "p.m."],
+        ..EN_US
+    },
+    Locale {
+        tag: "it-IT",
+        name: "Italian (Italy)",
+        percent_space: "",
+        short_date: "dd/MM/yy",
+        ..DE_DE
+    },
+    Locale {
+        tag: "pt-BR",
+        name: "Portuguese (Brazil)",
+        currency: "BRL",
+        currency_after: false,
+        percent_space: "",
+        short_date: "dd/MM/y",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "pt-PT",
+        name: "Portuguese (Portugal)",
+        group: NBSP,
+        grouping: Grouping::MinimumTwo,
+        percent_space: "",
+        short_date: "dd/MM/yy",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "nl-NL",
+        name: "Dutch (Netherlands)",
+        currency_after: false,
+        percent_space: "",
+        short_date: "dd-MM-y",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "sv-SE",
+        name: "Swedish (Sweden)",
+        group: NBSP,
+        minus: "\u{2212}",
+        currency: "SEK",
+        short_date: "y-MM-dd",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "nb-NO",
+        name: "Norwegian Bokmål (Norway)",
+        group: NBSP,
+        minus: "\u{2212}",
+        currency: "NOK",
+        short_date: "dd.MM.y",
+        date_time_separator: ", ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "da-DK",
+        name: "Danish (Denmark)",
+        currency: "DKK",
+        short_date: "dd.MM.y",
+        short_time: "HH.mm",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "fi-FI",
+        name: "Finnish (Finland)",
+        group: NBSP,
+        minus: "\u{2212}",
+        short_date: "d.M.y",
+        short_time: "H.mm",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "pl-PL",
+        name: "Polish (Poland)",
+        group: NBSP,
+        grouping: Grouping::MinimumTwo,
+        currency: "PLN",
+        percent_space: "",
+        short_date: "d.MM.y",
+        ..DE_DE
+    },
+    Locale {
+        tag: "cs-CZ",
+        name: "Czech (Czechia)",
+        group: NBSP,
+        currency: "CZK",
+        short_date: "dd.MM.yy",
+        short_time: "H:mm",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "ru-RU",
+        name: "Russian (Russia)",
+        group: NBSP,
+        currency: "RUB",
+        short_date: "dd.MM.y",
+        ..DE_DE
+    },
+    Locale {
+        tag: "tr-TR",
+        name: "Turkish (Türkiye)",
+        currency: "TRY",
+        currency_after: false,
+        currency_space: false,
+        percent_before: true,
+        percent_space: "",
+        short_date: "d.MM.y",
+        date_time_separator: " ",
+        ..DE_DE
+    },
+    Locale {
+        tag: "ja-JP",
+        name: "Japanese (Japan)",
+        currency: "JPY",
+        short_date: "y/MM/dd",
+        short_time: "H:mm",
+        date_time_separator: " ",
+        ..EN_US
+    },
+    Locale {
+        tag: "zh-CN",
+        name: "Chinese (China)",
+        currency: "CNY",
+        short_date: "y/M/d",
+        short_time: "HH:mm",
+        date_time_separator: " ",
+        ..EN_US
+    },
+    Locale {
+        tag: "ko-KR",
+        name: "Korean (South Korea)",
+        currency: "KRW",
+        short_date: "yy. M. d.",
+        short_time: "a h:mm",
+        am_pm: ["오전", "오후"],
+        date_time_separator: " ",
+        ..EN_US
+    },
+    Locale {
+        tag: "hi-IN",
+        name: "Hindi (India)",
+        grouping: Grouping::Indian,
+        currency: "INR",
+        short_date: "d/M/yy",
+        am_pm: ["am", "pm"],
+        ..EN_US
+    },
+];
+
+// ISO code, symbol and the digits after the decimal point
+const CURRENCIES: &[(&str, &str, usize)] = &[
+    ("USD", "$", 2),
+    ("EUR", "€", 2),
+    ("GBP", "£", 2),
+    ("JPY", "¥", 0),
+    ("CNY", "¥", 2),
+    ("INR", "₹", 2),
+    ("KRW", "₩", 0),
+    ("CHF", "CHF", 2),
+    ("SEK", "kr", 2),
+    ("NOK", "kr", 2),
+    ("DKK", "kr.", 2),
+    ("PLN", "zł", 2),
+    ("CZK", "Kč", 2),
+    ("RUB", "₽", 2),
+    ("TRY", "₺", 2),
+    ("BRL", "R$", 2),
+    ("MXN", "$", 2),
+    ("CAD", "$", 2),
+    ("AUD", "$", 2),
+];
+
+// "de", "de_DE" and "DE-de" all find de-DE; a bare language takes its first locale
+fn find_locale(tag: &str) -> Result<&'static Locale, String> {
+    let wanted = tag.trim().replace('_', "-");
+    LOCALES
+        .iter()
+        .find(|locale| locale.tag.eq_ignore_ascii_case(&wanted))
+        .or_else(|| {
+            LOCALES.iter().find(|locale| {
+                locale
+                    .tag
+                    .split('-')
+                    .next()
+                    .is_some_and(|language| language.eq_ignore_ascii_case(&wanted))
+            })
+        })
+        .ok_or_else(|| {
+            let tags: Vec<&str> = LOCALES.iter().map(|locale| locale.tag).collect();
+            format!(
+                "Unsupported locale {}; supported locales are {}",
+                tag.trim(),
+                tags.join(", ")
+            )
+        })
+}
+
+fn selected_locales(tags: Option<Vec<String>>) -> Result<Vec<&'static Locale>, String> {
+    match tags.filter(|tags| !tags.is_empty()) {
+        Some(tags) => tags.iter().map(|tag| find_locale(tag)).collect(),
+        None => Ok(LOCALES.iter().collect()),
+    }
+}
+
+// A decimal number as digit strings: `integer` without leading zeros (but at
+// least "0"), `fraction` possibly empty
+#[derive(Clone)]
+struct Decimal {
+    negative: bool,
+    integer: String,
+    fraction: String,
+}
+
+impl Decimal {
+    // Plain or exponent notation: -1234.5, 1.2e3
+    fn parse(text: &str) -> Result<Decimal, String> {
+        let invalid = || format!("Not a number: {}", text.trim());
+        let trimmed = text.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(index) => (
+                &unsigned[..index],
+                unsigned[index + 1..].parse::<i32>().map_err(|_| invalid())?,
+            ),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if (integer.is_empty() && fraction.is_empty())
+            || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let mut decimal = Decimal {
+            negative,
+            integer: integer.to_string(),
+            fraction: fraction.to_string(),
+        };
+        decimal.shift(exponent);
+        decimal.normalize();
+        Ok(decimal)
+    }
+
+    // Multiplies by 10^places
+    fn shift(&mut self, places: i32) {
+        let mut digits = format!("{}{}", self.integer, self.fraction);
+        let mut point = self.integer.len() as i64 + places as i64;
+        if point < 0 {
+            digits.insert_str(0, &"0".repeat(point.unsigned_abs() as usize));
+            point = 0;
+        }
+        let point = point as usize;
+        if point > digits.len() {
+            digits.push_str(&"0".repeat(point - digits.len()));
+        }
+        self.fraction = digits.split_off(point);
+        self.integer = digits;
+        self.normalize();
+    }
+
+    fn normalize(&mut self) {
+        let integer = self.integer.trim_start_matches('0');
+        self.integer = if integer.is_empty() {
+            "0".to_string()
+        } else {
+            integer.to_string()
+        };
+        self.fraction.truncate(self.fraction.trim_end_matches('0').len());
+        if self.is_zero() {
+            self.negative = false;
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.integer == "0" && self.fraction.bytes().all(|b| b == b'0')
+    }
+
+    // Rounds half to even (ICU's default) to at most `max` decimals, then
+    // pads with zeros to at least `min`
+    fn round(&mut self, max: usize, min: usize) {
+        if self.fraction.len() > max {
+            let rest = self.fraction.split_off(max);
+            let mut digits: Vec<u8> = format!("{}{}", self.integer, self.fraction).into_bytes();
+            let first = rest.as_bytes()[0];
+            let odd = digits.last().is_some_and(|digit| (digit - b'0') % 2 == 1);
+            let round_up = first > b'5' || (first == b'5' && (rest[1..].bytes().any(|b| b != b'0') || odd));
+            if round_up {
+                let mut index = digits.len();
+                loop {
+                    if index == 0 {
+                        digits.insert(0, b'1');
+                        break;
+                    }
+                    index -= 1;
+                    if digits[index] == b'9' {
+                        digits[index] = b'0';
+                    } else {
+                        digits[index] += 1;
+                        break;
+                    }
+                }
+            }
+            let point = digits.len() - max;
+            let digits = String::from_utf8(digits).unwrap_or_default();
+            self.integer = digits[..point].to_string();
+            self.fraction = digits[point..].to_string();
+        }
+        let integer = self.integer.trim_start_matches('0');
+        self.integer = if integer.is_empty() {
+            "0".to_string()
+        } else {
+            integer.to_string()
+        };
+        self.fraction.truncate(
+            self.fraction
+                .trim_end_matches('0')
+                .len()
+                .max(min.min(self.fraction.len())),
+        );
+        if self.fraction.len() < min {
+            self.fraction.push_str(&"0".repeat(min - self.fraction.len()));
+        }
+        if self.is_zero() {
+            self.negative = false;
+        }
+    }
+
+    fn canonical(&self) -> String {
+        let mut text = String::new();
+        if self.negative {
+            text.push('-');
+        }
+        text.push_str(&self.integer);
+        if !self.fraction.is_empty() {
+            text.push('.');
+            text.push_str(&self.fraction);
+        }
+        text
+    }
+}
+
+fn group_integer(integer: &str, locale: &Locale) -> String {
+    if integer.len() <= 3 || (locale.grouping == Grouping::MinimumTwo && integer.len() < 5) {
+        return integer.to_string();
+    }
+    let mut groups = Vec::new();
+    let mut end = integer.len();
+    let mut size = 3;
+    while end > size {
+        groups.push(&integer[end - size..end]);
+        end -= size;
+        if locale.grouping == Grouping::Indian {
+            size = 2;
+        }
+    }
+    groups.push(&integer[..end]);
+    groups.reverse();
+    groups.join(locale.group)
+}
+
+// The unsigned digits with the locale's separators
+fn digits_for(decimal: &Decimal, locale: &Locale) -> String {
+    let mut text = group_integer(&decimal.integer, locale);
+    if !decimal.fraction.is_empty() {
+        text.push_str(locale.decimal);
+        text.push_str(&decimal.fraction);
+    }
+    text
+}
+
+fn format_number(decimal: &Decimal, locale: &Locale, max_fraction: usize) -> String {
+    let mut decimal = decimal.clone();
+    decimal.round(max_fraction, 0);
+    let sign = if decimal.negative { locale.minus } else { "" };
+    format!("{}{}", sign, digits_for(&decimal, locale))
+}
+
+fn format_percent(decimal: &Decimal, locale: &Locale, max_fraction: usize) -> String {
+    let mut decimal = decimal.clone();
+    decimal.shift(2);
+    decimal.round(max_fraction, 0);
+    let sign = if decimal.negative { locale.minus } else { "" };
+    let digits = digits_for(&decimal, locale);
+    if locale.percent_before {
+        format!("{}%{}{}", sign, locale.percent_space, digits)
+    } else {
+        format!("{}{}{}%", sign, digits, locale.percent_space)
+    }
+}
+
+// The symbol where it can't be mistaken for another currency in this locale
+// ("$" is only USD where the local currency isn't a dollar too), else the code
+fn currency_symbol(code: &str, locale: &Locale) -> &'static str {
+    let Some(&(iso, symbol, _)) = CURRENCIES.iter().find(|(iso, _, _)| *iso == code) else {
+        return "";
+    };
+    let shared = CURRENCIES
+        .iter()
+        .any(|(other, other_symbol, _)| *other != iso && *other_symbol == symbol);
+    if !shared || iso == locale.currency || (iso == "USD" && locale.currency != "CAD" && locale.currency != "MXN") {
+        symbol
+    } else {
+        iso
+    }
+}
+
+fn format_currency(decimal: &Decimal, locale: &Locale, code: &str, fraction: Option<usize>) -> Result<String, String> {
+    let code = code.trim().to_ascii_uppercase();
+    let digits_after = match CURRENCIES.iter().find(|(iso, _, _)| *iso == code) {
+        Some((_, _, digits)) => *digits,
+        None if code.len() == 3 && code.bytes().all(|b| b.is_ascii_uppercase()) => 2,
+        None => return Err(format!("Not a currency code: {}", code)),
+    };
+    let symbol = match currency_symbol(&code, locale) {
+        "" => code.as_str(),
+        symbol => symbol,
+    };
+    let digits_after = fraction.unwrap_or(digits_after);
+    let mut decimal = decimal.clone();
+    decimal.round(digits_after, digits_after);
+    let sign = if decimal.negative { locale.minus } else { "" };
+    let digits = digits_for(&decimal, locale);
+    // Letter symbols (CHF, USD) are always kept apart from the digits
+    let space = if locale.currency_space || symbol.chars().last().is_some_and(char::is_alphabetic) {
+        NBSP
+    } else {
+        ""
+    };
+    Ok(if locale.currency_after {
+        format!("{}{}{}{}", sign, digits, space, symbol)
+    } else {
+        format!("{}{}{}{}", sign, symbol, space, digits)
+    })
+}
+
+// CLDR-style patterns: y yy M MM d dd H HH h hh mm ss a, and 'quoted' text
+fn render_pattern(pattern: &str, date: Option<NaiveDate>, time: Option<NaiveTime>, locale: &Locale) -> String {
+    let mut out = String::new();
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        if c == '\'' {
+            let end = chars[index + 1..]
+                .iter()
+                .position(|c| *c == '\'')
+                .map(|offset| index + 1 + offset)
+                .unwrap_or(chars.len());
+            out.extend(&chars[index + 1..end]);
+            index = end + 1;
+            continue;
+        }
+        let run = chars[index..].iter().take_while(|other| **other == c).count();
+        index += run;
+        let field = match (c, date, time) {
+            ('y', Some(date), _) if run == 2 => format!("{:02}", date.year().rem_euclid(100)),
+            ('y', Some(date), _) => date.year().to_string(),
+            ('M', Some(date), _) => format!("{:0width$}", date.month(), width = run),
+            ('d', Some(date), _) => format!("{:0width$}", date.day(), width = run),
+            ('H', _, Some(time)) => format!("{:0width$}", time.hour(), width = run),
+            ('h', _, Some(time)) => format!("{:0width$}", time.hour12().1, width = run),
+            ('m', _, Some(time)) => format!("{:0width$}", time.minute(), width = run),
+            ('s', _, Some(time)) => format!("{:0width$}", time.second(), width = run),
+            ('a', _, Some(time)) => locale.am_pm[time.hour12().0 as usize].to_string(),
+            _ => c.to_string().repeat(run),
+        };
+        out.push_str(&field);
+    }
+    out
+}
+
+// ISO 8601 / RFC 3339 (kept in its own offset), a bare date, or a Unix
+// timestamp in seconds or milliseconds (UTC)
+fn parse_date_time(text: &str) -> Result<(NaiveDate, Option<NaiveTime>), String> {
+    let text = text.trim();
+    if let Ok(date_time) = chrono::DateTime::parse_from_rfc3339(text) {
+        let local = date_time.naive_local();
+        return Ok((local.date(), Some(local.time())));
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(text, format) {
+            return Ok((date_time.date(), Some(date_time.time())));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Ok((date, None));
+    }
+    if let Ok(timestamp) = text.parse::<i64>() {
+        // Anything past the year 5000 in seconds is taken as milliseconds
+        let millis = if timestamp.abs() > 100_000_000_000 {
+            timestamp
+        } else {
+            timestamp * 1000
+        };
+        if let Some(date_time) = chrono::DateTime::from_timestamp_millis(millis) {
+            let utc = date_time.naive_utc();
+            return Ok((utc.date(), Some(utc.time())));
+        }
+    }
+    Err(format!("Not a date: {}; use ISO 8601 or a Unix timestamp", text))
+}
+
+fn conventions(locale: &Locale) -> Value {
+    json!({
+        "locale": locale.tag,
+        "name": locale.name,
+        "decimal_separator": locale.decimal,
+        "group_separator": locale.group,
+        "grouping": match locale.grouping {
+            Grouping::Standard => "3",
+            Grouping::Indian => "3;2",
+            Grouping::MinimumTwo => "3, from 5 digits",
+        }
+    })
+}
+
+// `value` formatted under each of `locales` (all supported ones by default).
+// `kind` is "number" (also giving currency and percent forms) or "date";
+// without it, anything that isn't a plain number is treated as a date.
+// `currency` defaults to each locale's own currency.
+#[tauri::command]
+pub fn format_for_locales(
+    value: String,
+    kind: Option<String>,
+    locales: Option<Vec<String>>,
+    currency: Option<String>,
+    fraction_digits: Option<usize>,
+) -> Result<Value, String> {
+    let locales = selected_locales(locales)?;
+    let kind = match kind.as_deref() {
+        Some(kind @ ("number" | "date")) => kind.to_string(),
+        Some(other) => return Err(format!("Unknown kind {}; use number or date", other)),
+        None if Decimal::parse(&value).is_ok() => "number".to_string(),
+        None => "date".to_string(),
+    };
+
+    let mut results = Vec::new();
+    if kind == "number" {
+        let decimal = Decimal::parse(&value)?;
+        let max_fraction = fraction_digits.unwrap_or(DEFAULT_MAX_FRACTION);
+        for locale in locales {
+            let code = currency.as_deref().unwrap_or(locale.currency);
+            let mut result = conventions(locale);
+            if let Value::Object(result) = &mut result {
+                result.insert("number".into(), format_number(&decimal, locale, max_fraction).into());
+                result.insert(
+                    "currency".into(),
+                    format_currency(&decimal, locale, code, fraction_digits)?.into(),
+                );
+                result.insert(
+                    "percent".into(),
+                    format_percent(&decimal, locale, fraction_digits.unwrap_or(0)).into(),
+                );
+            }
+            results.push(result);
+        }
+        return Ok(json!({ "kind": kind, "value": decimal.canonical(), "results": results }));
+    }
+
+    let (date, time) = parse_date_time(&value)?;
+    for locale in locales {
+        let mut result = conventions(locale);
+        let date_text = render_pattern(locale.short_date, Some(date), time, locale);
+        let time_text = time.map(|time| render_pattern(locale.short_time, Some(date), Some(time), locale));
+        if let Value::Object(result) = &mut result {
+            result.insert("date_pattern".into(), locale.short_date.into());
+            result.insert("time_pattern".into(), locale.short_time.into());
+            result.insert(
+                "date_time".into(),
+                match &time_text {
+                    Some(time_text) => format!("{}{}{}", date_text, locale.date_time_separator, time_text),
+                    None => date_text.clone(),
+                }
+                .into(),
+            );
+            result.insert("date".into(), date_text.into());
+            result.insert("time".into(), time_text.into());
+        }
+        results.push(result);
+    }
+    Ok(json!({
+        "kind": kind,
+        "value": match time {
+            Some(time) => date.and_time(time).format("%Y-%m-%dT%H:%M:%S").to_string(),
+            None => date.format("%Y-%m-%d").to_string(),
+        },
+        "results": results
+    }))
+}
+
+// Arabic-Indic, Persian, Devanagari and full-width digits as ASCII, and the
+// Arabic decimal and thousands separators as "." and ","
+fn ascii_digits(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            let zero = match c {
+                '\u{66b}' => return '.',
+                '\u{66c}' => return ',',
+                '\u{660}'..='\u{669}' => 0x660,
+                '\u{6f0}'..='\u{6f9}' => 0x6f0,
+                '\u{966}'..='\u{96f}' => 0x966,
+                '\u{ff10}'..='\u{ff19}' => 0xff10,
+                _ => return c,
+            };
+            char::from(b'0' + (c as u32 - zero) as u8)
+        })
+        .collect()
+}
+
+fn is_space(c: char) -> bool {
+    c.is_whitespace() || c == '\u{a0}' || c == '\u{202f}'
+}
+
+struct Parsed {
+    decimal: Decimal,
+    percent: bool,
+    currency: Option<String>,
+    grouping_valid: bool,
+}
+
+// Reads `text` under one locale's conventions. Currency symbols or codes and
+// a percent sign may surround the number; `(1.00)` is negative.
+fn parse_with(text: &str, locale: &Locale) -> Result<Parsed, String> {
+    let mut body = ascii_digits(text.trim());
+    let mut negative = false;
+    if body.starts_with('(') && body.ends_with(')') {
+        negative = true;
+        body = body[1..body.len() - 1].to_string();
+    }
+
+    let mut currency = None;
+    let mut percent = false;
+    // Longest symbols first so "R$" wins over "$"
+    let mut symbols: Vec<(&str, &str)> = CURRENCIES.iter().map(|(iso, symbol, _)| (*iso, *symbol)).collect();
+    symbols.extend(CURRENCIES.iter().map(|(iso, _, _)| (*iso, *iso)));
+    symbols.sort_by_key(|(_, symbol)| std::cmp::Reverse(symbol.len()));
+    loop {
+        let trimmed = body.trim_matches(is_space).to_string();
+        body = trimmed;
+        let mut stripped = false;
+        for sign in ["-", "\u{2212}", "+"] {
+            if let Some(rest) = body.strip_prefix(sign).or_else(|| body.strip_suffix(sign)) {
+                negative |= sign != "+";
+                body = rest.to_string();
+                stripped = true;
+            }
+        }
+        if let Some(rest) = body.strip_prefix('%').or_else(|| body.strip_suffix('%')) {
+            percent = true;
+            body = rest.to_string();
+            stripped = true;
+        }
+        if currency.is_none() {
+            for (iso, symbol) in &symbols {
+                if let Some(rest) = body.strip_prefix(symbol).or_else(|| body.strip_suffix(symbol)) {
+                    // A symbol shared by several currencies means the locale's own
+                    let shared = symbols.iter().filter(|(_, other)| other == symbol).count() > 1;
+                    currency = Some(if shared && *symbol != *iso {
+                        symbols
+                            .iter()
+                            .find(|(other_iso, other)| other == symbol && *other_iso == locale.currency)
+                            .map(|(other_iso, _)| *other_iso)
+                            .unwrap_or(*iso)
+                    } else {
+                        *iso
+                    });
+                    body = rest.to_string();
+                    stripped = true;
+                    break;
+                }
+            }
+        }
+        if !stripped {
+            break;
+        }
+    }
+    if body.is_empty() || !body.starts_with(|c: char| c.is_ascii_digit() || locale.decimal.starts_with(c)) {
+        return Err("No number found".to_string());
+    }
+
+    // Space-like group separators also accept any other space; the Swiss
+    // apostrophe also accepts a plain one
+    let is_group = |c: char| {
+        locale.group.starts_with(c)
+            || (locale.group.starts_with(is_space) && is_space(c))
+            || (locale.group == "\u{2019}" && c == '\'')
+    };
+    let (integer_part, fraction) = match body.split_once(locale.decimal) {
+        Some((integer, fraction)) => (integer.to_string(), fraction.to_string()),
+        None => (body.clone(), String::new()),
+    };
+    if let Some(c) = fraction.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(format!("Unexpected '{}' after the decimal separator", c));
+    }
+    let mut groups: Vec<String> = vec![String::new()];
+    for c in integer_part.chars() {
+        if c.is_ascii_digit() {
+            let last = groups.len() - 1;
+            groups[last].push(c);
+        } else if is_group(c) {
+            groups.push(String::new());
+        } else {
+            return Err(format!("Unexpected '{}' in the number", c));
+        }
+    }
+    // Every group after the first has the locale's size; the first is 1-3
+    let grouping_valid = groups.len() == 1
+        || (!groups[0].is_empty()
+            && groups[0].len() <= 3
+            && groups[1..].iter().enumerate().all(|(index, group)| {
+                let last = index == groups.len() - 2;
+                match locale.grouping {
+                    Grouping::Indian if !last => group.len() == 2,
+                    _ => group.len() == 3,
+                }
+            }));
+    let decimal = Decimal::parse(&format!(
+        "{}{}.{}",
+        if negative { "-" } else { "" },
+        groups.concat(),
+        fraction
+    ))?;
+    Ok(Parsed {
+        decimal,
+        percent,
+        currency: currency.map(str::to_string),
+        grouping_valid,
+    })
+}
+
+fn describe_parsed(parsed: &Parsed) -> Map<String, Value> {
+    let mut value = parsed.decimal.clone();
+    if parsed.percent {
+        value.shift(-2);
+    }
+    let mut result = Map::new();
+    result.insert("value".into(), value.canonical().into());
+    result.insert("number".into(), value.canonical().parse::<f64>().ok().into());
+    result.insert("percent".into(), parsed.percent.into());
+    result.insert("currency".into(), parsed.currency.clone().into());
+    result.insert("grouping_valid".into(), parsed.grouping_valid.into());
+    result
+}
+
+// Reads a locale-formatted number back to a canonical value ("1.234,56" ->
+// "1234.56"). With a `locale` that locale's conventions are applied; without
+// one every supported locale is tried and the distinct readings are listed,
+// which is how "1,234" turns out to be 1234 or 1.234 depending on who wrote it.
+#[tauri::command]
+pub fn parse_locale_number(text: String, locale: Option<String>) -> Result<Value, String> {
+    if text.trim().is_empty() {
+        return Err("No number given".to_string());
+    }
+    if let Some(tag) = locale.filter(|tag| !tag.trim().is_empty()) {
+        let locale = find_locale(&tag)?;
+        let parsed = parse_with(&text, locale)?;
+        let mut result = describe_parsed(&parsed);
+        result.insert("input".into(), text.into());
+        result.insert("locale".into(), locale.tag.into());
+        return Ok(Value::Object(result));
+    }
+
+    // Readings by value; a misgrouped reading only counts when nothing else parses
+    let mut readings: Vec<(Map<String, Value>, Vec<&str>)> = Vec::new();
+    for locale in LOCALES {
+        let Ok(parsed) = parse_with(&text, locale) else {
+            continue;
+        };
+        let reading = describe_parsed(&parsed);
+        match readings
+            .iter_mut()
+            .find(|(existing, _)| existing["value"] == reading["value"] && existing["percent"] == reading["percent"])
+        {
+            Some((existing, locales)) => {
+                locales.push(locale.tag);
+                if reading["grouping_valid"] == true {
+                    existing.insert("grouping_valid".into(), true.into());
+                }
+            }
+            None => readings.push((reading, vec![locale.tag])),
+        }
+    }
+    if readings.iter().any(|(reading, _)| reading["grouping_valid"] == true) {
+        readings.retain(|(reading, _)| reading["grouping_valid"] == true);
+    }
+    if readings.is_empty() {
+        return Err(format!(
+            "{} doesn't read as a number in any supported locale",
+            text.trim()
+        ));
+    }
+    let interpretations: Vec<Value> = readings
+        .into_iter()
+        .map(|(mut reading, locales)| {
+            reading.insert("locales".into(), locales.into());
+            Value::Object(reading)
+        })
+        .collect();
+    Ok(json!({
+        "input": text,
+        "ambiguous": interpretations.len() > 1,
+        "interpretations": interpretations
+    }))
+}
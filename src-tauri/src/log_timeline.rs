@@ -0,0 +1,276 @@
+// Timeline of a log in the active document: timestamps are pulled from each
+// line, then the covered range, an events-per-bucket histogram and the quiet
+// periods longer than a threshold are reported. The timestamp layout is
+// picked from a sample of the first lines and then applied to every line, so
+// multi-GB logs are scanned once with a single pattern.
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+use crate::jobs::{spawn_job, CancelToken};
+use crate::AppState;
+
+const DEFAULT_GAP_SECONDS: u64 = 300;
+const DEFAULT_BUCKET_SECONDS: u64 = 60;
+// Histograms are coarsened to stay under this many buckets
+const MAX_BUCKETS: u64 = 5_000;
+const MAX_REPORTED_GAPS: usize = 100;
+const SAMPLE_LINES: usize = 1_000;
+// Timestamps sit near the start of a line; only this many bytes are searched
+const SEARCH_PREFIX: usize = 256;
+const CANCEL_CHECK_LINES: usize = 100_000;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    // 2024-05-01T12:00:00.123Z, 2024-05-01 12:00:00,123 +02:00
+    Iso,
+    // 01/May/2024:12:00:00 +0000 (Apache/nginx access logs)
+    Clf,
+    // May  1 12:00:00 (syslog, no year)
+    Syslog,
+    // 1714564800 or 1714564800123
+    Epoch,
+}
+
+impl Layout {
+    const ALL: [Layout; 4] = [Layout::Iso, Layout::Clf, Layout::Syslog, Layout::Epoch];
+
+    fn name(self) -> &'static str {
+        match self {
+            Layout::Iso => "iso8601",
+            Layout::Clf => "common-log",
+            Layout::Syslog => "syslog",
+            Layout::Epoch => "epoch",
+        }
+    }
+
+    fn pattern(self) -> &'static Regex {
+        static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+        let patterns = PATTERNS.get_or_init(|| {
+            [
+                r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d{1,9})?(?:\s?(?:Z|[+-]\d{2}:?\d{2}))?",
+                r"\d{2}/[A-Z][a-z]{2}/\d{4}:\d{2}:\d{2}:\d{2}(?: [+-]\d{4})?",
+                r"\b[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}\b",
+                r"\b1[0-9]{9}(?:[0-9]{3})?(?:\.\d+)?\b",
+            ]
+            .iter()
+            .map(|pattern| Regex::new(pattern).expect("valid timestamp pattern"))
+            .collect()
+        });
+        &patterns[self as usize]
+    }
+
+    // Milliseconds since the epoch; timestamps without an offset count as UTC
+    fn parse(self, text: &str, year: i32) -> Option<i64> {
+        match self {
+            Layout::Iso => {
+                let normalized = text.replacen(' ', "T", 1).replace(',', ".");
+                if let Ok(datetime) = DateTime::parse_from_rfc3339(&normalized) {
+                    return Some(datetime.timestamp_millis());
+                }
+                for format in ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%dT%H:%M:%S%.f %z"] {
+                    if let Ok(datetime) = DateTime::parse_from_str(&normalized, format) {
+                        return Some(datetime.timestamp_millis());
+                    }
+                }
+                NaiveDateTime::parse_from_str(&normalized, "%Y-%m-%dT%H:%M:%S%.f")
+                    .ok()
+                    .map(|naive| naive.and_utc().timestamp_millis())
+            }
+            Layout::Clf => {
+                let with_offset = if text.len() > 20 {
+                    text.to_string()
+                } else {
+                    format!("{} +0000", text)
+                };
+                DateTime::parse_from_str(&with_offset, "%d/%b/%Y:%H:%M:%S %z")
+                    .ok()
+                    .map(|datetime| datetime.timestamp_millis())
+            }
+            Layout::Syslog => {
+                let naive = NaiveDateTime::parse_from_str(&format!("{} {}", year, text), "%Y %b %e %H:%M:%S").ok()?;
+                Some(naive.and_utc().timestamp_millis())
+            }
+            Layout::Epoch => {
+                let value: f64 = text.parse().ok()?;
+                // 13 digits are milliseconds
+                let millis = if text.split('.').next()?.len() > 10 {
+                    value
+                } else {
+                    value * 1000.0
+                };
+                Some(millis as i64)
+            }
+        }
+    }
+}
+
+fn search_prefix(line: &str) -> &str {
+    if line.len() <= SEARCH_PREFIX {
+        return line;
+    }
+    let mut end = SEARCH_PREFIX;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    &line[..end]
+}
+
+fn find(layout: Layout, line: &str, year: i32) -> Option<i64> {
+    let found = layout.pattern().find(search_prefix(line))?;
+    layout.parse(found.as_str(), year)
+}
+
+// The layout matching the most sampled lines
+fn detect_layout(text: &str, year: i32) -> Option<Layout> {
+    let sample: Vec<&str> = text.lines().take(SAMPLE_LINES).collect();
+    Layout::ALL
+        .into_iter()
+        .map(|layout| {
+            let hits = sample.iter().filter(|line| find(layout, line, year).is_some()).count();
+            (layout, hits)
+        })
+        .filter(|(_, hits)| *hits > 0)
+        // Earlier layouts win ties; epoch numbers are the least specific
+        .max_by_key(|(layout, hits)| (*hits, std::cmp::Reverse(*layout as usize)))
+        .map(|(layout, _)| layout)
+}
+
+fn iso(millis: i64) -> String {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .map(|datetime| datetime.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_default()
+}
+
+// The bucket size actually used: the requested one, coarsened to a round
+// size when the span would need too many buckets
+fn bucket_size(requested: u64, span_seconds: u64) -> u64 {
+    const ROUND: [u64; 9] = [60, 300, 900, 1_800, 3_600, 10_800, 21_600, 43_200, 86_400];
+    if span_seconds / requested.max(1) < MAX_BUCKETS {
+        return requested.max(1);
+    }
+    ROUND
+        .into_iter()
+        .find(|size| *size >= requested && span_seconds / size < MAX_BUCKETS)
+        .unwrap_or_else(|| (span_seconds / MAX_BUCKETS + 1).div_ceil(86_400) * 86_400)
+}
+
+fn timeline_blocking(
+    app: &AppHandle,
+    token: &CancelToken,
+    gap_seconds: u64,
+    bucket_seconds: u64,
+) -> Result<serde_json::Value, String> {
+    // Only the buffer handle is cloned; the lock is released before scanning
+    let stored = {
+        let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        storage
+            .active()
+            .raw_content
+            .clone()
+            .ok_or_else(|| "No content stored".to_string())?
+    };
+    let text = stored.as_str();
+    // Syslog lines carry no year; assume the current one
+    let year = Utc::now().year();
+    let layout = detect_layout(text, year).ok_or_else(|| "No timestamps found in the first lines".to_string())?;
+
+    // (line number, milliseconds) for every line with a timestamp
+    let mut events: Vec<(usize, i64)> = Vec::new();
+    let mut lines = 0;
+    for (index, line) in text.lines().enumerate() {
+        if index % CANCEL_CHECK_LINES == 0 {
+            token.check()?;
+        }
+        lines += 1;
+        if let Some(millis) = find(layout, line, year) {
+            events.push((index + 1, millis));
+        }
+    }
+    if events.is_empty() {
+        return Err("No timestamps found".to_string());
+    }
+
+    let mut out_of_order = 0;
+    let mut gaps: Vec<(i64, usize, i64, usize, i64)> = Vec::new();
+    for pair in events.windows(2) {
+        let ((previous_line, previous), (line, current)) = (pair[0], pair[1]);
+        let delta = current - previous;
+        if delta < 0 {
+            out_of_order += 1;
+        } else if delta > gap_seconds as i64 * 1000 {
+            gaps.push((delta, previous_line, previous, line, current));
+        }
+    }
+    let gap_count = gaps.len();
+    gaps.sort_by_key(|gap| std::cmp::Reverse(gap.0));
+    gaps.truncate(MAX_REPORTED_GAPS);
+    token.check()?;
+
+    let first = events.iter().map(|(_, millis)| *millis).min().unwrap_or(0);
+    let last = events.iter().map(|(_, millis)| *millis).max().unwrap_or(0);
+    let span_seconds = ((last - first) / 1000) as u64;
+    let bucket = bucket_size(bucket_seconds, span_seconds);
+    let bucket_millis = bucket as i64 * 1000;
+    let origin = first - first.rem_euclid(bucket_millis);
+    let mut counts = vec![0usize; ((last - origin) / bucket_millis + 1) as usize];
+    for (_, millis) in &events {
+        counts[((millis - origin) / bucket_millis) as usize] += 1;
+    }
+    let peak = counts.iter().enumerate().max_by_key(|(_, count)| **count).map(
+        |(index, count)| serde_json::json!({ "start": iso(origin + index as i64 * bucket_millis), "count": count }),
+    );
+    let empty_buckets = counts.iter().filter(|count| **count == 0).count();
+    let histogram: Vec<serde_json::Value> = counts
+        .iter()
+        .enumerate()
+        .map(
+            |(index, count)| serde_json::json!({ "start": iso(origin + index as i64 * bucket_millis), "count": count }),
+        )
+        .collect();
+
+    Ok(serde_json::json!({
+        "layout": layout.name(),
+        "assumed_year": (layout == Layout::Syslog).then_some(year),
+        "lines": lines,
+        "lines_with_timestamp": events.len(),
+        "first": iso(first),
+        "last": iso(last),
+        "span_seconds": span_seconds,
+        "span": crate::formatters::jwt::human_duration(span_seconds as i64),
+        "out_of_order": out_of_order,
+        "bucket_seconds": bucket,
+        "histogram": histogram,
+        "empty_buckets": empty_buckets,
+        "peak": peak,
+        "gap_threshold_seconds": gap_seconds,
+        "gap_count": gap_count,
+        "gaps": gaps.iter().map(|(delta, from_line, from, to_line, to)| serde_json::json!({
+            "from": iso(*from),
+            "to": iso(*to),
+            "seconds": delta / 1000,
+            "duration": crate::formatters::jwt::human_duration(delta / 1000),
+            "from_line": from_line,
+            "to_line": to_line
+        })).collect::<Vec<_>>()
+    }))
+}
+
+// Scans the active document as a job (logs can be GBs); the report arrives
+// through `job://finished`. Gaps are quiet periods over `gap_seconds`
+// (default 5 minutes), longest first; the histogram uses `bucket_seconds`
+// (default a minute), coarsened for long spans.
+#[tauri::command]
+pub fn analyze_log_timeline(
+    app: AppHandle,
+    gap_seconds: Option<u64>,
+    bucket_seconds: Option<u64>,
+) -> Result<u64, String> {
+    let gap_seconds = gap_seconds.unwrap_or(DEFAULT_GAP_SECONDS);
+    let bucket_seconds = bucket_seconds.unwrap_or(DEFAULT_BUCKET_SECONDS).max(1);
+    spawn_job(&app, "log-timeline", move |app, token| {
+        timeline_blocking(app, token, gap_seconds, bucket_seconds)
+    })
+}
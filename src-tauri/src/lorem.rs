@@ -0,0 +1,346 @@
+// Placeholder text: lorem ipsum by words, sentences or paragraphs (optionally
+// as HTML), and bulk text of a requested size made by repeating a template,
+// for trying out large-file behavior. Bulk output goes straight into a new
+// document, so a 50MB test file never has to pass through the webview.
+use rand::seq::SliceRandom;
+use rand::Rng;
+use tauri::{AppHandle, Manager};
+
+use crate::jobs::{spawn_job, CancelToken};
+use crate::progress::ProgressReporter;
+use crate::AppState;
+
+pub const BULK_PROGRESS_EVENT: &str = "bulk://progress";
+const MAX_COUNT: usize = 10_000;
+const MAX_BULK_BYTES: f64 = 2_000_000_000.0;
+
+const OPENING: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit";
+const WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+    "ex",
+    "ea",
+    "commodo",
+    "consequat",
+    "duis",
+    "aute",
+    "irure",
+    "in",
+    "reprehenderit",
+    "voluptate",
+    "velit",
+    "esse",
+    "cillum",
+    "eu",
+    "fugiat",
+    "nulla",
+    "pariatur",
+    "excepteur",
+    "sint",
+    "occaecat",
+    "cupidatat",
+    "non",
+    "proident",
+    "sunt",
+    "culpa",
+    "qui",
+    "officia",
+    "deserunt",
+    "mollit",
+    "anim",
+    "id",
+    "est",
+    "laborum",
+    "perspiciatis",
+    "unde",
+    "omnis",
+    "iste",
+    "natus",
+    "error",
+    "voluptatem",
+    "accusantium",
+    "doloremque",
+    "laudantium",
+    "totam",
+    "rem",
+    "aperiam",
+    "eaque",
+    "ipsa",
+    "quae",
+    "ab",
+    "illo",
+    "inventore",
+    "veritatis",
+    "quasi",
+    "architecto",
+    "beatae",
+    "vitae",
+    "dicta",
+    "explicabo",
+];
+
+fn random_words(rng: &mut impl Rng, count: usize) -> Vec<&'static str> {
+    (0..count)
+        .map(|_| *WORDS.choose(rng).expect("word list isn't empty"))
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn sentence(rng: &mut impl Rng) -> String {
+    let count = rng.gen_range(6..=14);
+    let mut words = random_words(rng, count).join(" ");
+    // Longer sentences get a comma somewhere in the middle
+    if words.len() > 60 {
+        if let Some(space) = words[20..].find(' ') {
+            words.insert(20 + space, ',');
+        }
+    }
+    format!("{}.", capitalize(&words))
+}
+
+fn paragraph(rng: &mut impl Rng) -> String {
+    (0..rng.gen_range(3..=7))
+        .map(|_| sentence(rng))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// `unit` is "words", "sentences" or "paragraphs". With `start_with_lorem` the
+// text opens with the classic "Lorem ipsum dolor sit amet...".
+fn lorem(unit: &str, count: usize, start_with_lorem: bool) -> Result<Vec<String>, String> {
+    let mut rng = rand::thread_rng();
+    let mut parts: Vec<String> = match unit {
+        "words" => random_words(&mut rng, count).into_iter().map(str::to_string).collect(),
+        "sentences" => (0..count).map(|_| sentence(&mut rng)).collect(),
+        "paragraphs" => (0..count).map(|_| paragraph(&mut rng)).collect(),
+        _ => return Err(format!("Unknown unit {}; use words, sentences or paragraphs", unit)),
+    };
+    if start_with_lorem {
+        match unit {
+            "words" => {
+                let opening: Vec<String> = OPENING
+                    .replace(',', "")
+                    .split(' ')
+                    .map(|word| word.to_lowercase())
+                    .collect();
+                for (part, word) in parts.iter_mut().zip(opening) {
+                    *part = word;
+                }
+                if let Some(first) = parts.first_mut() {
+                    *first = capitalize(first);
+                }
+            }
+            _ => {
+                if let Some(first) = parts.first_mut() {
+                    // Replaces the first sentence of the first paragraph
+                    let rest = first.split_once(". ").map(|(_, rest)| rest).unwrap_or_default();
+                    *first = if rest.is_empty() {
+                        format!("{}.", OPENING)
+                    } else {
+                        format!("{}. {}", OPENING, rest)
+                    };
+                }
+            }
+        }
+    }
+    Ok(parts)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+// Placeholder text as a string; `html` wraps paragraphs in <p> elements (words
+// and sentences go into a single paragraph)
+#[tauri::command]
+pub fn generate_lorem(
+    unit: Option<String>,
+    count: Option<usize>,
+    html: Option<bool>,
+    start_with_lorem: Option<bool>,
+) -> Result<String, String> {
+    let unit = unit.unwrap_or_else(|| "paragraphs".to_string());
+    let count = count.unwrap_or(3).clamp(1, MAX_COUNT);
+    let parts = lorem(&unit, count, start_with_lorem.unwrap_or(true))?;
+    let text = match unit.as_str() {
+        "paragraphs" if html.unwrap_or(false) => parts
+            .iter()
+            .map(|part| format!("<p>{}</p>", escape_html(part)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "paragraphs" => parts.join("\n\n"),
+        _ if html.unwrap_or(false) => format!("<p>{}</p>", escape_html(&parts.join(" "))),
+        _ => parts.join(" "),
+    };
+    Ok(text)
+}
+
+// Fills in one repetition of the bulk template. Placeholders: {{index}} (0-based),
+// {{uuid}}, {{int}} (0-999999), {{float}}, {{bool}}, {{word}}, {{sentence}} and
+// {{timestamp}} (one second apart per repetition, ending now).
+fn render_item(template: &str, index: u64, start: i64, rng: &mut impl Rng) -> String {
+    if !template.contains("{{") {
+        return template.to_string();
+    }
+    let mut out = String::with_capacity(template.len() + 32);
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find("}}") else {
+            rest = &rest[open..];
+            break;
+        };
+        let name = rest[open + 2..open + close].trim();
+        match name {
+            "index" => out.push_str(&index.to_string()),
+            "uuid" => {
+                let bytes: [u8; 16] = rng.gen();
+                let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+                // Version 4, RFC 4122 variant
+                out.push_str(&format!(
+                    "{}-{}-4{}-{:x}{}-{}",
+                    &hex[0..8],
+                    &hex[8..12],
+                    &hex[13..16],
+                    8 + (bytes[8] & 0x3),
+                    &hex[17..20],
+                    &hex[20..32]
+                ));
+            }
+            "int" => out.push_str(&rng.gen_range(0..1_000_000).to_string()),
+            "float" => out.push_str(&format!("{:.4}", rng.gen::<f64>() * 1000.0)),
+            "bool" => out.push_str(if rng.gen() { "true" } else { "false" }),
+            "word" => out.push_str(WORDS.choose(rng).expect("word list isn't empty")),
+            "sentence" => out.push_str(&sentence(rng)),
+            "timestamp" => {
+                let time = chrono::DateTime::from_timestamp(start + index as i64, 0).unwrap_or_default();
+                out.push_str(&time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+            }
+            // Unknown names are kept as written
+            _ => out.push_str(&rest[open..open + close + 2]),
+        }
+        rest = &rest[open + close + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn generate_bulk_blocking(
+    app: &AppHandle,
+    token: &CancelToken,
+    template: String,
+    size: String,
+    separator: String,
+) -> Result<serde_json::Value, String> {
+    if template.is_empty() {
+        return Err("Empty template".to_string());
+    }
+    let target = crate::units::parse_size(&size)?;
+    if !(1.0..=MAX_BULK_BYTES).contains(&target) {
+        return Err(format!(
+            "Size must be between 1 byte and {}",
+            crate::units::human_readable_bytes(MAX_BULK_BYTES)
+        ));
+    }
+    let target = target as usize;
+
+    let mut rng = rand::thread_rng();
+    let mut content = String::with_capacity(target + template.len() + separator.len());
+    let mut reporter = ProgressReporter::new(
+        app,
+        BULK_PROGRESS_EVENT,
+        target as u64,
+        serde_json::json!({ "size": size }),
+    );
+    // Rough item count so the timestamps end at about the current time
+    let estimated_items = (target / (template.len() + separator.len()).max(1)) as i64;
+    let start = chrono::Utc::now().timestamp() - estimated_items;
+    let mut index = 0;
+    while content.len() < target {
+        // Checking every item would dominate the cost for tiny templates
+        if index % 4096 == 0 {
+            token.check()?;
+            reporter.update(content.len() as u64);
+        }
+        if index > 0 {
+            content.push_str(&separator);
+        }
+        content.push_str(&render_item(&template, index, start, &mut rng));
+        index += 1;
+    }
+    // Line-based output ends with a newline like any text file
+    if separator.ends_with('\n') {
+        content.push_str(&separator);
+    }
+    reporter.update(target as u64);
+
+    let length = content.len();
+    let document_id = {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let id = storage.insert_document(format!("bulk: {}", size.trim()));
+        storage.active_mut().raw_content = Some(content.into());
+        id
+    };
+    crate::tray::refresh(app);
+    Ok(serde_json::json!({
+        "document_id": document_id,
+        "items": index,
+        "length": length
+    }))
+}
+
+// Repeats `template` (one NDJSON line, a CSV row, ...) until the output reaches
+// `size` ("50MB", "1.5 GiB"), joined by `separator` (a newline by default), and
+// opens the result as a new document. Runs as a cancellable job.
+#[tauri::command]
+pub fn generate_bulk_text(
+    app: AppHandle,
+    template: String,
+    size: String,
+    separator: Option<String>,
+) -> Result<u64, String> {
+    spawn_job(&app, "bulk-text", move |app, token| {
+        generate_bulk_blocking(
+            app,
+            token,
+            template,
+            size,
+            separator.unwrap_or_else(|| "\n".to_string()),
+        )
+    })
+}
@@ -2,5 +2,11 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // `devmate fmt json < file` and friends run headless and exit. On Windows
+    // release builds stdout only reaches a pipe or redirect, not the console.
+    let args: Vec<String> = std::env::args().collect();
+    if devmate_lib::cli::is_cli_invocation(&args) {
+        std::process::exit(devmate_lib::cli::run(&args));
+    }
     devmate_lib::run();
 }
@@ -0,0 +1,274 @@
+// multipart/form-data request bodies (captured uploads, HAR entries, curl
+// traces) split into their parts: each field or file with its headers, size
+// and decoded content. Text content is routed to the formatter its
+// Content-Type names, or to the best autodetected one for plain fields;
+// binary files are identified by magic bytes and included as base64 when
+// small. Field values that are data URIs are decoded as well.
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::email::{
+    decode_base64_lenient, decode_charset, decode_quoted_printable, header, parameter, parse_parameters,
+};
+use crate::formatters::{autodetect, FormatterRegistry};
+use crate::AppState;
+
+// Binary files up to this size are included as base64
+const MAX_INLINE_BINARY: usize = 64 * 1024;
+// Nested multipart/mixed (RFC 2388 multi-file fields) beyond this isn't parsed
+const MAX_DEPTH: usize = 4;
+// Where a `boundary=` parameter is looked for when none is given
+const HEADER_SEARCH_BYTES: usize = 16 * 1024;
+
+// The boundary from a Content-Type header in front of the body, else from the
+// first delimiter line
+fn detect_boundary(text: &str) -> Option<String> {
+    let head = &text[..crate::text_buffer::grapheme_floor(text, HEADER_SEARCH_BYTES)];
+    for line in head.lines() {
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-type") {
+                let (_, parameters) = parse_parameters(value);
+                if let Some(boundary) = parameter(&parameters, "boundary") {
+                    return Some(boundary.to_string());
+                }
+            }
+        }
+    }
+    head.lines()
+        .map(str::trim_end)
+        .find(|line| line.starts_with("--") && line.len() > 2 && !line.contains(' '))
+        .map(|line| line[2..].trim_end_matches("--").to_string())
+}
+
+fn is_textual(content_type: &str) -> bool {
+    content_type.starts_with("text/")
+        || matches!(
+            content_type,
+            "application/json" | "application/xml" | "application/x-www-form-urlencoded" | "application/javascript"
+        )
+        || content_type.ends_with("+json")
+        || content_type.ends_with("+xml")
+}
+
+// The formatter a declared Content-Type maps to
+fn formatter_for(content_type: &str) -> Option<&'static str> {
+    match content_type {
+        "application/json" => Some("json"),
+        "application/xml" | "text/xml" => Some("xml"),
+        "application/yaml" | "application/x-yaml" | "text/yaml" => Some("yaml"),
+        _ if content_type.ends_with("+json") => Some("json"),
+        _ if content_type.ends_with("+xml") => Some("xml"),
+        _ => None,
+    }
+}
+
+fn format_text(text: &str, content_type: Option<&str>, registry: &FormatterRegistry, part: &mut Map<String, Value>) {
+    // A plain field (no Content-Type) goes to whatever it looks like
+    let formatter_id = match content_type {
+        Some(content_type) => formatter_for(content_type),
+        None => autodetect::guess_formats(text, true, registry)
+            .first()
+            .filter(|guess| guess.confidence >= 0.9)
+            .and_then(|guess| guess.formatter_id),
+    };
+    if let Some(formatter) = formatter_id.and_then(|id| registry.get(id)) {
+        if let Ok(formatted) = formatter.format(text) {
+            part.insert("formatted_as".into(), formatter.id().into());
+            part.insert("body".into(), formatted.into());
+            return;
+        }
+    }
+    if text.starts_with("data:") {
+        if let Ok((media_type, is_base64, bytes)) = crate::data_uri::parse_data_uri(text) {
+            part.insert(
+                "data_uri".into(),
+                json!({
+                    "declared_mime": media_type,
+                    "is_base64": is_base64,
+                    "size": bytes.len(),
+                    "detected": crate::detect::sniff_magic(&bytes).map(|detection| detection.mime)
+                }),
+            );
+        }
+    }
+    part.insert("formatted_as".into(), "text".into());
+    part.insert("body".into(), text.into());
+}
+
+fn parse_part(text: &str, index: usize, depth: usize, registry: &FormatterRegistry) -> Value {
+    let (headers, body) = crate::email::split_message(text);
+    let (disposition, disposition_parameters) = match header(&headers, "Content-Disposition") {
+        Some(value) => {
+            let (disposition, parameters) = parse_parameters(value);
+            (Some(disposition), parameters)
+        }
+        None => (None, Vec::new()),
+    };
+    let (content_type, type_parameters) = match header(&headers, "Content-Type") {
+        Some(value) => {
+            let (content_type, parameters) = parse_parameters(value);
+            (Some(content_type), parameters)
+        }
+        None => (None, Vec::new()),
+    };
+    let name = parameter(&disposition_parameters, "name");
+    let filename = parameter(&disposition_parameters, "filename");
+
+    let mut part = Map::new();
+    part.insert("index".into(), index.into());
+    part.insert("name".into(), name.into());
+    if let Some(filename) = filename {
+        part.insert("filename".into(), filename.into());
+    }
+    part.insert("kind".into(), if filename.is_some() { "file" } else { "field" }.into());
+    part.insert("content_type".into(), content_type.clone().into());
+    if disposition
+        .as_deref()
+        .is_some_and(|disposition| disposition != "form-data")
+    {
+        part.insert("disposition".into(), disposition.clone().into());
+    }
+    part.insert(
+        "headers".into(),
+        headers
+            .iter()
+            .map(|(name, value)| json!({ "name": name, "value": value }))
+            .collect::<Vec<_>>()
+            .into(),
+    );
+
+    if let Some(content_type) = content_type
+        .as_deref()
+        .filter(|content_type| content_type.starts_with("multipart/"))
+    {
+        match parameter(&type_parameters, "boundary") {
+            Some(boundary) if depth < MAX_DEPTH => {
+                let parts: Vec<Value> = crate::email::split_multipart(body, boundary)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, child)| parse_part(child, index, depth + 1, registry))
+                    .collect();
+                part.insert("parts".into(), parts.into());
+            }
+            Some(_) => {}
+            None => {
+                part.insert(
+                    "error".into(),
+                    format!("{} part without a boundary", content_type).into(),
+                );
+            }
+        }
+        return Value::Object(part);
+    }
+
+    let bytes = match header(&headers, "Content-Transfer-Encoding").map(|value| value.trim().to_ascii_lowercase()) {
+        Some(encoding) if encoding == "base64" => decode_base64_lenient(body).unwrap_or_else(|e| {
+            part.insert("error".into(), e.into());
+            body.as_bytes().to_vec()
+        }),
+        Some(encoding) if encoding == "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.as_bytes().to_vec(),
+    };
+    part.insert("size".into(), bytes.len().into());
+
+    // Files without a declared type are judged by their bytes
+    let textual = match content_type.as_deref() {
+        Some(content_type) => is_textual(content_type),
+        None => filename.is_none() || std::str::from_utf8(&bytes).is_ok(),
+    };
+    if textual {
+        let text = decode_charset(&bytes, parameter(&type_parameters, "charset"));
+        format_text(&text, content_type.as_deref(), registry, &mut part);
+    } else {
+        if let Some(detection) = crate::detect::sniff_magic(&bytes) {
+            part.insert(
+                "detected".into(),
+                json!({ "kind": detection.kind, "mime": detection.mime }),
+            );
+        }
+        part.insert("formatted_as".into(), "base64".into());
+        part.insert(
+            "body".into(),
+            (bytes.len() <= MAX_INLINE_BINARY)
+                .then(|| STANDARD.encode(&bytes))
+                .into(),
+        );
+    }
+    Value::Object(part)
+}
+
+pub fn parse_multipart(text: &str, boundary: Option<&str>, registry: &FormatterRegistry) -> Result<Value, String> {
+    let boundary = match boundary.map(|boundary| boundary.trim().trim_matches('"')) {
+        Some(boundary) if !boundary.is_empty() => boundary.to_string(),
+        _ => detect_boundary(text).ok_or_else(|| "No boundary given or found in the body".to_string())?,
+    };
+    let sections = crate::email::split_multipart(text, &boundary);
+    if sections.is_empty() {
+        return Err(format!("No parts delimited by --{} found", boundary));
+    }
+    let parts: Vec<Value> = sections
+        .into_iter()
+        .enumerate()
+        .map(|(index, section)| parse_part(section, index, 0, registry))
+        .collect();
+
+    // Plain fields as a name -> value object, repeated names as arrays
+    let mut fields = Map::new();
+    for part in parts
+        .iter()
+        .filter(|part| part["kind"] == "field" && part["parts"].is_null())
+    {
+        let (Some(name), Some(value)) = (part["name"].as_str(), part["body"].as_str()) else {
+            continue;
+        };
+        match fields.get_mut(name) {
+            Some(Value::Array(values)) => values.push(value.into()),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value.into()]),
+            None => {
+                fields.insert(name.to_string(), value.into());
+            }
+        }
+    }
+    let files = parts.iter().filter(|part| part["kind"] == "file").count();
+    let total_size: u64 = parts.iter().filter_map(|part| part["size"].as_u64()).sum();
+    Ok(json!({
+        "boundary": boundary,
+        "part_count": parts.len(),
+        "file_count": files,
+        "total_size": total_size,
+        "fields": fields,
+        "parts": parts
+    }))
+}
+
+fn decompose_blocking(app: &AppHandle, text: Option<String>, boundary: Option<String>) -> Result<Value, String> {
+    let text = text.filter(|text| !text.is_empty());
+    let snapshot = match text {
+        Some(_) => None,
+        None => Some(
+            app.state::<AppState>()
+                .inner()
+                .lock()
+                .map_err(|e| e.to_string())?
+                .snapshot_raw()?,
+        ),
+    };
+    let text = snapshot
+        .as_ref()
+        .map_or(text.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+    parse_multipart(text, boundary.as_deref(), &app.state::<FormatterRegistry>())
+}
+
+// Parts of a multipart/form-data body in `text` (or the active document's raw
+// content). `boundary` is read from a Content-Type header or the first
+// delimiter line when not given, so a whole captured request works too.
+#[tauri::command]
+pub async fn decompose_multipart(
+    app: AppHandle,
+    text: Option<String>,
+    boundary: Option<String>,
+) -> Result<Value, String> {
+    crate::run_blocking("decompose_multipart", move || decompose_blocking(&app, text, boundary)).await
+}
@@ -0,0 +1,376 @@
+// Walks an OAuth 2.0 / OpenID Connect authorization code flow end to end:
+// build the authorization URL (with a PKCE verifier and S256 challenge, state
+// and nonce generated when not given), decode what comes back on the redirect
+// URI, and exchange the code at the token endpoint. Tokens in either place are
+// handed to the JWT claim checks, with the ID token's audience, issuer and
+// nonce compared with what the flow expects.
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use rand::RngCore;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::formatters::jwt::{self, Expectations};
+
+const TOKEN_FIELDS: &[&str] = &["id_token", "access_token", "refresh_token"];
+
+// 32 random bytes as base64url: 43 characters, within PKCE's 43-128
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// RFC 7636: 43-128 characters of [A-Za-z0-9-._~]
+fn check_code_verifier(verifier: &str) -> Result<(), String> {
+    if !(43..=128).contains(&verifier.len()) {
+        return Err(format!(
+            "A PKCE code verifier has 43 to 128 characters, not {}",
+            verifier.len()
+        ));
+    }
+    match verifier
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')))
+    {
+        Some(c) => Err(format!("'{}' isn't allowed in a PKCE code verifier", c)),
+        None => Ok(()),
+    }
+}
+
+fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+fn encode_component(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn form_encode(pairs: &[(&str, &str)]) -> String {
+    pairs
+        .iter()
+        .map(|(name, value)| format!("{}={}", encode_component(name), encode_component(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// Parameters of a form-encoded string, such as a query or a fragment
+fn form_decode(text: &str) -> Vec<(String, String)> {
+    // The url crate does the decoding; the host is never contacted
+    match reqwest::Url::parse(&format!("http://params.invalid/?{}", text)) {
+        Ok(url) => url
+            .query_pairs()
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn looks_like_jwt(token: &str) -> bool {
+    token.starts_with("eyJ") && token.split('.').count() == 3
+}
+
+// Claim reports for the tokens in a token response or redirect. The ID token
+// is checked against `client_id` as audience and the expected issuer and
+// nonce; access and refresh tokens are only decoded when they're JWTs.
+fn describe_tokens(
+    params: &Map<String, Value>,
+    client_id: Option<&str>,
+    issuer: Option<&str>,
+    nonce: Option<&str>,
+) -> Value {
+    let mut tokens = Map::new();
+    for field in TOKEN_FIELDS {
+        let Some(token) = params.get(*field).and_then(Value::as_str) else {
+            continue;
+        };
+        let id_token = *field == "id_token";
+        if !looks_like_jwt(token) {
+            tokens.insert((*field).into(), json!({ "jwt": false, "length": token.len() }));
+            continue;
+        }
+        let expected = Expectations {
+            audience: client_id.filter(|_| id_token).map(str::to_string),
+            issuer: issuer.filter(|_| id_token).map(str::to_string),
+            ..Expectations::default()
+        };
+        let mut report = match jwt::validate_jwt(token, &expected) {
+            Ok(report) => report,
+            Err(error) => json!({ "error": error }),
+        };
+        report["jwt"] = json!(true);
+        if let (true, Some(nonce)) = (id_token, nonce) {
+            report["nonce_matches"] = json!(report["payload"]["nonce"].as_str() == Some(nonce));
+        }
+        tokens.insert((*field).into(), report);
+    }
+    if let Some(expires_in) = params.get("expires_in").and_then(|value| match value {
+        Value::String(text) => text.parse::<i64>().ok(),
+        value => value.as_i64(),
+    }) {
+        let expires_at = chrono::Duration::try_seconds(expires_in)
+            .and_then(|lifetime| chrono::Utc::now().checked_add_signed(lifetime));
+        tokens.insert("expires_at".into(), json!(expires_at.map(|at| at.to_rfc3339())));
+        tokens.insert("expires_in_readable".into(), json!(jwt::human_duration(expires_in)));
+    }
+    Value::Object(tokens)
+}
+
+// An OAuth error response (RFC 6749 §4.1.2.1 / §5.2), if `params` is one
+fn oauth_error(params: &Map<String, Value>) -> Option<Value> {
+    let error = params.get("error")?;
+    Some(json!({
+        "error": error,
+        "description": params.get("error_description"),
+        "uri": params.get("error_uri"),
+    }))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// Builds the URL to open in a browser to start the flow. `pkce` (on by
+// default) adds an S256 challenge for `code_verifier` or a fresh one; `state`
+// is generated when not given, and so is `nonce` when the scope asks for
+// `openid`. Keep the returned verifier, state and nonce for the next steps.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub fn build_oauth_authorization_url(
+    authorization_endpoint: String,
+    client_id: String,
+    redirect_uri: String,
+    scope: Option<String>,
+    response_type: Option<String>,
+    state: Option<String>,
+    nonce: Option<String>,
+    pkce: Option<bool>,
+    code_verifier: Option<String>,
+    extra_params: Option<BTreeMap<String, String>>,
+) -> Result<Value, String> {
+    let mut url = crate::fetch::parse_http_url(&authorization_endpoint)?;
+    if client_id.trim().is_empty() {
+        return Err("A client ID is required".to_string());
+    }
+    let scope = non_empty(scope);
+    let response_type = non_empty(response_type).unwrap_or_else(|| "code".to_string());
+    let state = non_empty(state).unwrap_or_else(random_token);
+    let openid = scope
+        .as_deref()
+        .is_some_and(|scope| scope.split_whitespace().any(|scope| scope == "openid"));
+    let nonce = non_empty(nonce).or_else(|| openid.then(random_token));
+    let code_verifier = if pkce.unwrap_or(true) {
+        let verifier = non_empty(code_verifier).unwrap_or_else(random_token);
+        check_code_verifier(&verifier)?;
+        Some(verifier)
+    } else {
+        None
+    };
+    let challenge = code_verifier.as_deref().map(code_challenge);
+
+    let mut params: Vec<(String, String)> = vec![
+        ("response_type".into(), response_type),
+        ("client_id".into(), client_id.trim().to_string()),
+        ("redirect_uri".into(), redirect_uri.trim().to_string()),
+    ];
+    if let Some(scope) = &scope {
+        params.push(("scope".into(), scope.clone()));
+    }
+    params.push(("state".into(), state.clone()));
+    if let Some(nonce) = &nonce {
+        params.push(("nonce".into(), nonce.clone()));
+    }
+    if let Some(challenge) = &challenge {
+        params.push(("code_challenge".into(), challenge.clone()));
+        params.push(("code_challenge_method".into(), "S256".into()));
+    }
+    for (name, value) in extra_params.unwrap_or_default() {
+        match params.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(param) => param.1 = value,
+            None => params.push((name, value)),
+        }
+    }
+    url.query_pairs_mut().extend_pairs(params.iter());
+
+    Ok(json!({
+        "url": url.to_string(),
+        "state": state,
+        "nonce": nonce,
+        "code_verifier": code_verifier,
+        "code_challenge": challenge,
+        "code_challenge_method": challenge.as_ref().map(|_| "S256"),
+        "params": params.into_iter().collect::<BTreeMap<_, _>>()
+    }))
+}
+
+// Decodes the redirect the authorization server sent back: a full URL, or
+// just its query or fragment. Reports the code or the error, whether `state`
+// matches `expected_state`, and claim checks for any tokens (implicit and
+// hybrid flows return them in the fragment).
+#[tauri::command]
+pub fn decode_oauth_redirect(
+    redirect: String,
+    expected_state: Option<String>,
+    client_id: Option<String>,
+    issuer: Option<String>,
+    nonce: Option<String>,
+) -> Result<Value, String> {
+    let redirect = redirect.trim();
+    if redirect.is_empty() {
+        return Err("Paste the redirect URL, or its query or fragment".to_string());
+    }
+    let (query, fragment) = match reqwest::Url::parse(redirect) {
+        Ok(url) => (
+            url.query().unwrap_or("").to_string(),
+            url.fragment().unwrap_or("").to_string(),
+        ),
+        Err(_) => match redirect.strip_prefix('#') {
+            Some(fragment) => (String::new(), fragment.to_string()),
+            None => (redirect.trim_start_matches('?').to_string(), String::new()),
+        },
+    };
+
+    let mut params = Map::new();
+    let mut sources = Map::new();
+    for (source, text) in [("query", &query), ("fragment", &fragment)] {
+        for (name, value) in form_decode(text) {
+            sources.insert(name.clone(), json!(source));
+            params.insert(name, json!(value));
+        }
+    }
+    if params.is_empty() {
+        return Err("No parameters in the query or fragment".to_string());
+    }
+
+    let mut warnings: Vec<String> = Vec::new();
+    let state = params.get("state").and_then(Value::as_str);
+    let state_matches = match (non_empty(expected_state), state) {
+        (Some(expected), Some(state)) => Some(expected == state),
+        (Some(_), None) => Some(false),
+        (None, _) => None,
+    };
+    if state_matches == Some(false) {
+        warnings.push("state doesn't match the one sent; the response may not belong to this flow".to_string());
+    }
+    if params.contains_key("access_token") && sources.get("access_token") == Some(&json!("query")) {
+        warnings.push("An access token in the query string leaks into logs and Referer headers".to_string());
+    }
+
+    let nonce = non_empty(nonce);
+    let tokens = describe_tokens(
+        &params,
+        non_empty(client_id).as_deref(),
+        non_empty(issuer).as_deref(),
+        nonce.as_deref(),
+    );
+    if tokens["id_token"]["nonce_matches"] == false {
+        warnings.push("The ID token's nonce doesn't match the one sent".to_string());
+    }
+    Ok(json!({
+        "code": params.get("code"),
+        "state": state,
+        "state_matches": state_matches,
+        "error": oauth_error(&params),
+        "tokens": tokens,
+        "params": params,
+        "sources": sources,
+        "warnings": warnings
+    }))
+}
+
+// Exchanges an authorization code at `token_endpoint`. The client
+// authenticates with `client_secret` via HTTP Basic (the default) or in the
+// form body (`client_auth: "post"`); public clients send only `client_id`
+// and the PKCE `code_verifier`. The request is left out of the HTTP history,
+// since it carries the code and secret.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn exchange_oauth_code(
+    token_endpoint: String,
+    client_id: String,
+    code: String,
+    redirect_uri: String,
+    code_verifier: Option<String>,
+    client_secret: Option<String>,
+    client_auth: Option<String>,
+    issuer: Option<String>,
+    nonce: Option<String>,
+) -> Result<Value, String> {
+    crate::run_blocking("exchange_oauth_code", move || {
+        let client_id = client_id.trim().to_string();
+        let code = code.trim().to_string();
+        if code.is_empty() {
+            return Err("An authorization code is required".to_string());
+        }
+        let code_verifier = non_empty(code_verifier);
+        let client_secret = client_secret.filter(|secret| !secret.is_empty());
+        let basic = match non_empty(client_auth).as_deref() {
+            None | Some("basic") => true,
+            Some("post") => false,
+            Some(other) => return Err(format!("Client authentication must be basic or post, not {}", other)),
+        };
+
+        let mut form: Vec<(&str, &str)> = vec![
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", redirect_uri.trim()),
+        ];
+        if let Some(verifier) = &code_verifier {
+            form.push(("code_verifier", verifier));
+        }
+        let mut headers = BTreeMap::new();
+        headers.insert(
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        );
+        headers.insert("Accept".to_string(), "application/json".to_string());
+        match (&client_secret, basic) {
+            (Some(secret), true) => {
+                // RFC 6749 §2.3.1: both parts form-encoded before base64
+                let credentials = format!("{}:{}", encode_component(&client_id), encode_component(secret));
+                headers.insert(
+                    "Authorization".to_string(),
+                    format!("Basic {}", STANDARD.encode(credentials)),
+                );
+            }
+            (Some(secret), false) => {
+                form.push(("client_id", &client_id));
+                form.push(("client_secret", secret));
+            }
+            (None, _) => form.push(("client_id", &client_id)),
+        }
+
+        let started = std::time::Instant::now();
+        let response = crate::http_client::send("POST", token_endpoint.trim(), &headers, Some(form_encode(&form)))?;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        let body = String::from_utf8_lossy(&response.body).into_owned();
+        let mut reply = json!({
+            "status": response.status,
+            "ok": (200..300).contains(&response.status),
+            "elapsed_ms": elapsed_ms,
+        });
+        match serde_json::from_str::<Value>(&body) {
+            Ok(Value::Object(params)) => {
+                reply["error"] = json!(oauth_error(&params));
+                reply["tokens"] = describe_tokens(
+                    &params,
+                    Some(&client_id),
+                    non_empty(issuer).as_deref(),
+                    non_empty(nonce).as_deref(),
+                );
+                reply["response"] = Value::Object(params);
+            }
+            _ => reply["body"] = json!(body),
+        }
+        Ok(reply)
+    })
+    .await
+}
@@ -0,0 +1,219 @@
+// Files handed to devmate by the OS (launch arguments, a second launch routed
+// through the single-instance plugin, the macOS open-file event) or dropped on
+// the window. Each file gets its own document; OS-opened files are announced
+// through `file://opened`.
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::formatters::{autodetect, FormatterRegistry};
+use crate::jobs::CancelToken;
+use crate::responses::{OpenFileResult, OpenedFile};
+use crate::{charset, text_buffer, AppState};
+
+pub const FILE_OPENED_EVENT: &str = "file://opened";
+
+// Extensions whose format is known without looking at the content
+fn format_for_extension(path: &Path) -> Option<(&'static str, Option<&'static str>)> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let format = match extension.as_str() {
+        "json" | "har" | "geojson" | "webmanifest" => ("json", Some("json")),
+        "json5" | "jsonc" => ("json5", Some("json5")),
+        "ndjson" | "jsonl" => ("ndjson", None),
+        "xml" | "xsd" | "svg" | "plist" => ("xml", Some("xml")),
+        "yaml" | "yml" => ("yaml", Some("yaml")),
+        "pem" | "crt" | "cer" => ("x509", Some("x509")),
+        "jwt" => ("jwt", Some("jwt")),
+        _ => return None,
+    };
+    Some(format)
+}
+
+fn detect(app: &AppHandle, document_id: u64, path: &Path) -> (String, Option<String>) {
+    if let Some((format, formatter_id)) = format_for_extension(path) {
+        return (format.to_string(), formatter_id.map(str::to_string));
+    }
+    let Ok(storage) = app.state::<AppState>().inner().lock() else {
+        return ("text".to_string(), None);
+    };
+    let content = storage
+        .document(document_id)
+        .and_then(|document| document.raw_content.as_deref())
+        .unwrap_or_default();
+    let sample_end = text_buffer::grapheme_floor(content, autodetect::SAMPLE_BYTES);
+    let registry = app.state::<FormatterRegistry>();
+    autodetect::guess_formats(&content[..sample_end], sample_end == content.len(), &registry)
+        .first()
+        .map(|guess| (guess.format.to_string(), guess.formatter_id.map(str::to_string)))
+        .unwrap_or_else(|| ("text".to_string(), None))
+}
+
+// Reads `path` into an existing document's raw content, replacing what was
+// there. Returns the file size, the encoding it was decoded from and whether
+// it's memory-mapped. Files another program keeps rewriting should be read
+// with `allow_map` off: truncating a mapped file faults the reader.
+pub fn load_path_into_document(
+    app: &AppHandle,
+    token: &CancelToken,
+    document_id: u64,
+    path: &Path,
+    allow_map: bool,
+) -> Result<(u64, String, bool), String> {
+    let file_path = path.to_string_lossy().into_owned();
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", file_path, e))?;
+    let metadata = file.metadata().map_err(|e| format!("Failed to get file metadata: {}", e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", file_path));
+    }
+    let file_size = metadata.len();
+
+    // Compressed files of any size are decompressed while streaming
+    if file_size > crate::settings::current(app).large_file_threshold
+        || crate::compression::sniff_file(&mut file)?.is_some()
+    {
+        let result = crate::load_file_into_document(app, token, document_id, file, file_size, &file_path, allow_map)?;
+        return Ok((file_size, result.encoding.unwrap_or_default(), result.memory_mapped.unwrap_or(false)));
+    }
+    // Small files are read into memory so later edits on disk can't affect the map
+    drop(file);
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    let (content, encoding, _) = charset::decode_to_utf8(&bytes, None)?;
+    let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let document = storage
+        .documents
+        .get_mut(&document_id)
+        .ok_or_else(|| "The document was closed while the file was loading".to_string())?;
+    document.raw_content = Some(content.into());
+    document.formatted_content = None;
+    document.binary_content = None;
+    document.clear_history();
+    Ok((file_size, encoding.to_string(), false))
+}
+
+// Loads `path` into a new document, which becomes the active one. `allow_map`
+// is passed on to load_path_into_document.
+pub fn open_in_new_document(
+    app: &AppHandle,
+    token: &CancelToken,
+    path: &Path,
+    allow_map: bool,
+) -> Result<OpenedFile, String> {
+    let file_path = path.to_string_lossy().into_owned();
+    if !path.is_file() {
+        return Err(format!("{} is not a file", file_path));
+    }
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file_path.clone());
+
+    let document_id = app
+        .state::<AppState>()
+        .inner()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert_document(name.clone());
+
+    let (file_size, encoding, memory_mapped) = match load_path_into_document(app, token, document_id, path, allow_map) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            // Don't leave an empty tab behind for a file that couldn't be read
+            if let Ok(mut storage) = app.state::<AppState>().inner().lock() {
+                if storage.documents.len() > 1 {
+                    storage.documents.remove(&document_id);
+                    if storage.active_id == document_id {
+                        storage.active_id = *storage.documents.keys().next_back().expect("documents remain");
+                    }
+                }
+            }
+            return Err(e);
+        }
+    };
+
+    let (detected_format, formatter_id) = detect(app, document_id, path);
+    crate::recent_files::record(app, path, file_size, Some(detected_format.clone()));
+    crate::tray::refresh(app);
+    Ok(OpenedFile {
+        id: document_id,
+        name,
+        file_path,
+        file_size,
+        encoding,
+        memory_mapped,
+        detected_format,
+        formatter_id,
+    })
+}
+
+// Launch arguments that name existing files; flags and the program path are skipped
+pub fn file_arguments(args: impl IntoIterator<Item = String>, cwd: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == crate::stdin::FORMAT_FLAG {
+            // Its value is a formatter id, not a file
+            args.next();
+        } else if !arg.starts_with('-') {
+            let path = cwd.join(arg);
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+fn open_result(file_path: String, outcome: Result<OpenedFile, String>) -> OpenFileResult {
+    match outcome {
+        Ok(file) => OpenFileResult {
+            file_path,
+            file: Some(file),
+            error: None,
+        },
+        Err(error) => OpenFileResult {
+            file_path,
+            file: None,
+            error: Some(error),
+        },
+    }
+}
+
+// Opens each path on the task queue and reports it through `file://opened`
+pub fn open_paths(app: &AppHandle, paths: Vec<PathBuf>) {
+    if paths.is_empty() {
+        return;
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    for path in paths {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let worker = app.clone();
+            let file_path = path.to_string_lossy().into_owned();
+            let outcome = crate::run_blocking("open_file", move || {
+                open_in_new_document(&worker, &CancelToken::default(), &path, true)
+            })
+            .await;
+            let _ = app.emit(FILE_OPENED_EVENT, open_result(file_path, outcome));
+        });
+    }
+}
+
+// Files dropped on the window: each gets its own document, in the order given,
+// and the last one that loads becomes active. A file that fails doesn't stop
+// the rest; its entry carries the error instead.
+#[tauri::command]
+pub async fn open_dropped_files(app: AppHandle, paths: Vec<String>) -> Result<Vec<OpenFileResult>, String> {
+    crate::run_blocking("open_dropped_files", move || {
+        let token = CancelToken::default();
+        Ok(paths
+            .into_iter()
+            .map(|file_path| {
+                let outcome = open_in_new_document(&app, &token, Path::new(&file_path), true);
+                open_result(file_path, outcome)
+            })
+            .collect())
+    })
+    .await
+}
@@ -0,0 +1,546 @@
+// OpenAPI 3.x and Swagger 2.0 specs (JSON or YAML): lists the operations and
+// turns one into a ready-to-send HTTP client request, with the server URL,
+// path and query parameters, auth headers and an example body built from the
+// request schema. Parameters without an example become `{{name}}` placeholders
+// that an environment can fill in.
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager};
+
+use crate::http_client::HttpRequest;
+use crate::AppState;
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+// Deep enough for real payloads; recursive schemas stop here
+const MAX_EXAMPLE_DEPTH: usize = 10;
+
+fn parse_spec(text: &str) -> Result<serde_json::Value, String> {
+    let trimmed = text.trim_start();
+    let spec: serde_json::Value = if trimmed.starts_with('{') {
+        serde_json::from_str(trimmed).map_err(|e| format!("Invalid JSON: {}", e))?
+    } else {
+        serde_yaml::from_str(trimmed).map_err(|e| format!("Invalid YAML: {}", e))?
+    };
+    if spec.get("openapi").is_none() && spec.get("swagger").is_none() {
+        return Err("Not an OpenAPI or Swagger document (no openapi or swagger field)".to_string());
+    }
+    Ok(spec)
+}
+
+// Follows a local `$ref` ("#/components/schemas/User"); external refs aren't
+// fetched, so they resolve to nothing
+fn resolve<'a>(spec: &'a serde_json::Value, value: &'a serde_json::Value) -> Option<&'a serde_json::Value> {
+    let mut current = value;
+    // Refs pointing at refs, bounded in case they loop
+    for _ in 0..16 {
+        match current.get("$ref").and_then(|reference| reference.as_str()) {
+            Some(reference) => current = spec.pointer(reference.strip_prefix('#')?)?,
+            None => return Some(current),
+        }
+    }
+    None
+}
+
+fn example_string(format: Option<&str>) -> &'static str {
+    match format {
+        Some("date-time") => "2024-01-01T12:00:00Z",
+        Some("date") => "2024-01-01",
+        Some("time") => "12:00:00",
+        Some("email") => "user@example.com",
+        Some("uuid") => "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+        Some("uri") | Some("url") => "https://example.com",
+        Some("hostname") => "example.com",
+        Some("ipv4") => "192.0.2.1",
+        Some("ipv6") => "2001:db8::1",
+        Some("byte") => "ZXhhbXBsZQ==",
+        Some("password") => "********",
+        _ => "string",
+    }
+}
+
+// An example value for `schema`: its own example, default or first enum value
+// when there is one, otherwise one built from the types
+fn example(spec: &serde_json::Value, schema: &serde_json::Value, depth: usize) -> serde_json::Value {
+    let Some(schema) = resolve(spec, schema) else {
+        return serde_json::Value::Null;
+    };
+    if depth > MAX_EXAMPLE_DEPTH {
+        return serde_json::Value::Null;
+    }
+    if let Some(value) = schema
+        .get("example")
+        .or_else(|| schema.get("const"))
+        .or_else(|| schema.get("default"))
+    {
+        return value.clone();
+    }
+    let first = |key: &str| {
+        schema
+            .get(key)
+            .and_then(|list| list.as_array())
+            .and_then(|list| list.first())
+    };
+    if let Some(value) = first("examples").or_else(|| first("enum")) {
+        return value.clone();
+    }
+    if let Some(variant) = first("oneOf").or_else(|| first("anyOf")) {
+        return example(spec, variant, depth + 1);
+    }
+    if let Some(parts) = schema.get("allOf").and_then(|parts| parts.as_array()) {
+        let mut merged = serde_json::Map::new();
+        for part in parts {
+            match example(spec, part, depth + 1) {
+                serde_json::Value::Object(object) => merged.extend(object),
+                other if parts.len() == 1 => return other,
+                _ => {}
+            }
+        }
+        return serde_json::Value::Object(merged);
+    }
+
+    // OpenAPI 3.1 allows a list of types, e.g. ["string", "null"]
+    let kind = match schema.get("type") {
+        Some(serde_json::Value::Array(types)) => types
+            .iter()
+            .filter_map(|kind| kind.as_str())
+            .find(|kind| *kind != "null"),
+        Some(kind) => kind.as_str(),
+        None if schema.get("properties").is_some() => Some("object"),
+        None if schema.get("items").is_some() => Some("array"),
+        None => None,
+    };
+    match kind {
+        Some("object") => {
+            let mut object = serde_json::Map::new();
+            if let Some(properties) = schema.get("properties").and_then(|properties| properties.as_object()) {
+                for (name, property) in properties {
+                    // Read-only fields belong in responses, not requests
+                    let read_only = resolve(spec, property)
+                        .and_then(|property| property.get("readOnly"))
+                        .and_then(|flag| flag.as_bool())
+                        .unwrap_or(false);
+                    if !read_only {
+                        object.insert(name.clone(), example(spec, property, depth + 1));
+                    }
+                }
+            } else if let Some(values) = schema.get("additionalProperties").filter(|values| values.is_object()) {
+                object.insert("key".to_string(), example(spec, values, depth + 1));
+            }
+            serde_json::Value::Object(object)
+        }
+        Some("array") => match schema.get("items") {
+            Some(items) => serde_json::Value::Array(vec![example(spec, items, depth + 1)]),
+            None => serde_json::Value::Array(Vec::new()),
+        },
+        Some("string") => example_string(schema.get("format").and_then(|format| format.as_str())).into(),
+        Some("integer") => 0.into(),
+        Some("number") => 0.0.into(),
+        Some("boolean") => true.into(),
+        _ => serde_json::Value::Null,
+    }
+}
+
+struct Operation<'a> {
+    method: &'static str,
+    path: &'a str,
+    item: &'a serde_json::Value,
+    operation: &'a serde_json::Value,
+}
+
+fn operations(spec: &serde_json::Value) -> Vec<Operation<'_>> {
+    let mut found = Vec::new();
+    let Some(paths) = spec.get("paths").and_then(|paths| paths.as_object()) else {
+        return found;
+    };
+    for (path, item) in paths {
+        let Some(item) = resolve(spec, item) else {
+            continue;
+        };
+        for method in METHODS {
+            if let Some(operation) = item.get(*method).filter(|operation| operation.is_object()) {
+                found.push(Operation {
+                    method,
+                    path,
+                    item,
+                    operation,
+                });
+            }
+        }
+    }
+    found
+}
+
+fn operation_label(operation: &Operation) -> String {
+    format!("{} {}", operation.method.to_uppercase(), operation.path)
+}
+
+// Path-level parameters apply unless the operation redefines the same one
+fn parameters<'a>(spec: &'a serde_json::Value, operation: &Operation<'a>) -> Vec<&'a serde_json::Value> {
+    let list = |value: &'a serde_json::Value| {
+        value
+            .get("parameters")
+            .and_then(|parameters| parameters.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(move |parameter| resolve(spec, parameter))
+    };
+    let key = |parameter: &serde_json::Value| (parameter.get("name").cloned(), parameter.get("in").cloned());
+    let own: Vec<&serde_json::Value> = list(operation.operation).collect();
+    let mut merged: Vec<&serde_json::Value> = list(operation.item)
+        .filter(|inherited| !own.iter().any(|parameter| key(parameter) == key(inherited)))
+        .collect();
+    merged.extend(own);
+    merged
+}
+
+fn location(parameter: &serde_json::Value) -> Option<&str> {
+    parameter.get("in").and_then(|location| location.as_str())
+}
+
+fn base_url(spec: &serde_json::Value) -> String {
+    if let Some(server) = spec.pointer("/servers/0") {
+        let mut url = server.get("url").and_then(|url| url.as_str()).unwrap_or("").to_string();
+        if let Some(variables) = server.get("variables").and_then(|variables| variables.as_object()) {
+            for (name, variable) in variables {
+                let default = variable
+                    .get("default")
+                    .and_then(|default| default.as_str())
+                    .unwrap_or("");
+                url = url.replace(&format!("{{{}}}", name), default);
+            }
+        }
+        return url.trim_end_matches('/').to_string();
+    }
+    // Swagger 2.0
+    let base_path = spec.get("basePath").and_then(|path| path.as_str()).unwrap_or("");
+    match spec.get("host").and_then(|host| host.as_str()) {
+        Some(host) => {
+            let scheme = spec
+                .pointer("/schemes/0")
+                .and_then(|scheme| scheme.as_str())
+                .unwrap_or("https");
+            format!("{}://{}{}", scheme, host, base_path.trim_end_matches('/'))
+        }
+        None => base_path.trim_end_matches('/').to_string(),
+    }
+}
+
+fn encode_component(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn scalar_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+// The parameter's example as text, or a `{{name}}` placeholder (recorded in
+// `variables`) when the spec gives none
+fn parameter_value(
+    spec: &serde_json::Value,
+    parameter: &serde_json::Value,
+    name: &str,
+    variables: &mut Vec<String>,
+) -> (String, bool) {
+    let given = parameter
+        .get("example")
+        .or_else(|| {
+            parameter
+                .get("examples")
+                .and_then(|examples| examples.as_object())
+                .and_then(|examples| examples.values().next())
+                .and_then(|example| resolve(spec, example))
+                .and_then(|example| example.get("value"))
+        })
+        .or_else(|| parameter.pointer("/schema/example"))
+        .or_else(|| parameter.pointer("/schema/default"))
+        .or_else(|| parameter.pointer("/schema/enum/0"))
+        // Swagger 2.0 keeps these on the parameter itself
+        .or_else(|| parameter.get("default"))
+        .or_else(|| parameter.pointer("/enum/0"));
+    match given {
+        Some(value) => (scalar_text(value), false),
+        None => {
+            if !variables.iter().any(|variable| variable == name) {
+                variables.push(name.to_string());
+            }
+            (format!("{{{{{}}}}}", name), true)
+        }
+    }
+}
+
+// Headers for the first security requirement that applies (operation-level
+// requirements override the document's)
+fn security_headers(
+    spec: &serde_json::Value,
+    operation: &serde_json::Value,
+    headers: &mut BTreeMap<String, String>,
+    query: &mut Vec<String>,
+    variables: &mut Vec<String>,
+) {
+    let requirement = operation
+        .get("security")
+        .or_else(|| spec.get("security"))
+        .and_then(|security| security.as_array())
+        .and_then(|requirements| requirements.first())
+        .and_then(|requirement| requirement.as_object());
+    let Some(requirement) = requirement else {
+        return;
+    };
+    for scheme_name in requirement.keys() {
+        let scheme = spec
+            .pointer(&format!("/components/securitySchemes/{}", scheme_name))
+            .or_else(|| spec.pointer(&format!("/securityDefinitions/{}", scheme_name)))
+            .and_then(|scheme| resolve(spec, scheme));
+        let Some(scheme) = scheme else {
+            continue;
+        };
+        let field = |key: &str| scheme.get(key).and_then(|value| value.as_str()).unwrap_or("");
+        let mut placeholder = |name: &str| {
+            if !variables.iter().any(|variable| variable == name) {
+                variables.push(name.to_string());
+            }
+            format!("{{{{{}}}}}", name)
+        };
+        match (field("type"), field("scheme").to_ascii_lowercase().as_str()) {
+            ("http", "bearer") | ("oauth2", _) | ("openIdConnect", _) => {
+                headers.insert("Authorization".to_string(), format!("Bearer {}", placeholder("token")));
+            }
+            ("http", "basic") | ("basic", _) => {
+                headers.insert(
+                    "Authorization".to_string(),
+                    format!("Basic {}", placeholder("basic_credentials")),
+                );
+            }
+            ("apiKey", _) => {
+                let name = field("name");
+                let value = placeholder("api_key");
+                match field("in") {
+                    "header" => {
+                        headers.insert(name.to_string(), value);
+                    }
+                    "query" => query.push(format!("{}={}", encode_component(name), value)),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn form_encode(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(object) => object
+            .iter()
+            .map(|(name, value)| format!("{}={}", encode_component(name), encode_component(&scalar_text(value))))
+            .collect::<Vec<_>>()
+            .join("&"),
+        other => scalar_text(other),
+    }
+}
+
+// The body's content type and example text
+fn request_body(
+    spec: &serde_json::Value,
+    operation: &serde_json::Value,
+    parameters: &[&serde_json::Value],
+) -> Result<Option<(String, String)>, String> {
+    let (content_type, value) = if let Some(body) = operation.get("requestBody").and_then(|body| resolve(spec, body)) {
+        let Some(content) = body.get("content").and_then(|content| content.as_object()) else {
+            return Ok(None);
+        };
+        let Some((content_type, media)) = content
+            .iter()
+            .find(|(content_type, _)| content_type.contains("json"))
+            .or_else(|| content.iter().next())
+        else {
+            return Ok(None);
+        };
+        let value = media
+            .get("example")
+            .cloned()
+            .or_else(|| {
+                media
+                    .get("examples")
+                    .and_then(|examples| examples.as_object())
+                    .and_then(|examples| examples.values().next())
+                    .and_then(|example| resolve(spec, example))
+                    .and_then(|example| example.get("value"))
+                    .cloned()
+            })
+            .or_else(|| media.get("schema").map(|schema| example(spec, schema, 0)))
+            .unwrap_or(serde_json::Value::Null);
+        (content_type.clone(), value)
+    } else if let Some(body) = parameters.iter().find(|parameter| location(parameter) == Some("body")) {
+        // Swagger 2.0
+        let content_type = operation
+            .pointer("/consumes/0")
+            .or_else(|| spec.pointer("/consumes/0"))
+            .and_then(|content_type| content_type.as_str())
+            .unwrap_or("application/json");
+        let value = body
+            .get("schema")
+            .map(|schema| example(spec, schema, 0))
+            .unwrap_or_default();
+        (content_type.to_string(), value)
+    } else {
+        let fields: serde_json::Map<String, serde_json::Value> = parameters
+            .iter()
+            .filter(|parameter| location(parameter) == Some("formData"))
+            .filter_map(|parameter| {
+                let name = parameter.get("name")?.as_str()?;
+                Some((name.to_string(), example(spec, parameter, 0)))
+            })
+            .collect();
+        if fields.is_empty() {
+            return Ok(None);
+        }
+        ("application/x-www-form-urlencoded".to_string(), fields.into())
+    };
+
+    let text = if content_type.contains("json") {
+        crate::formatters::to_string_pretty(&value).map_err(|e| format!("Failed to format the example: {}", e))?
+    } else if content_type == "application/x-www-form-urlencoded" {
+        form_encode(&value)
+    } else {
+        scalar_text(&value)
+    };
+    Ok(Some((content_type, text)))
+}
+
+fn scaffold(spec: &serde_json::Value, selector: &str) -> Result<serde_json::Value, String> {
+    let selector = selector.trim();
+    let all = operations(spec);
+    let operation = all
+        .iter()
+        .find(|operation| operation.operation.get("operationId").and_then(|id| id.as_str()) == Some(selector))
+        .or_else(|| {
+            all.iter()
+                .find(|operation| operation_label(operation).eq_ignore_ascii_case(selector))
+        })
+        .ok_or_else(|| format!("Operation {} not found", selector))?;
+
+    let parameters = parameters(spec, operation);
+    let mut variables = Vec::new();
+    let mut headers = BTreeMap::new();
+    let mut query = Vec::new();
+    let mut path = operation.path.to_string();
+    for parameter in &parameters {
+        let Some(name) = parameter.get("name").and_then(|name| name.as_str()) else {
+            continue;
+        };
+        let required = parameter
+            .get("required")
+            .and_then(|flag| flag.as_bool())
+            .unwrap_or(false);
+        match location(parameter) {
+            Some("path") => {
+                let (value, placeholder) = parameter_value(spec, parameter, name, &mut variables);
+                let value = if placeholder { value } else { encode_component(&value) };
+                path = path.replace(&format!("{{{}}}", name), &value);
+            }
+            // Optional query parameters and headers are left out to keep the
+            // request minimal
+            Some("query") if required => {
+                let (value, placeholder) = parameter_value(spec, parameter, name, &mut variables);
+                let value = if placeholder { value } else { encode_component(&value) };
+                query.push(format!("{}={}", encode_component(name), value));
+            }
+            Some("header") if required => {
+                let (value, _) = parameter_value(spec, parameter, name, &mut variables);
+                headers.insert(name.to_string(), value);
+            }
+            _ => {}
+        }
+    }
+    security_headers(spec, operation.operation, &mut headers, &mut query, &mut variables);
+
+    let body = request_body(spec, operation.operation, &parameters)?;
+    if let Some((content_type, _)) = &body {
+        headers.insert("Content-Type".to_string(), content_type.clone());
+    }
+    let mut url = format!("{}{}", base_url(spec), path);
+    if !query.is_empty() {
+        url = format!("{}?{}", url, query.join("&"));
+    }
+    let request = HttpRequest {
+        method: operation.method.to_uppercase(),
+        url,
+        headers,
+        body: body.map(|(_, text)| text),
+    };
+    Ok(serde_json::json!({
+        "operation": operation_label(operation),
+        "operation_id": operation.operation.get("operationId"),
+        "summary": operation.operation.get("summary"),
+        "request": request,
+        // Placeholders the environment has to define before sending
+        "variables": variables
+    }))
+}
+
+fn spec_text(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    match text.filter(|text| !text.is_empty()) {
+        Some(text) => Ok(text),
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            Ok(storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string())
+        }
+    }
+}
+
+// The spec's title, version, base URL and operations (`text`, or the active
+// document's raw content when it's empty)
+#[tauri::command]
+pub async fn list_openapi_operations(app: AppHandle, text: Option<String>) -> Result<serde_json::Value, String> {
+    crate::run_blocking("list_openapi_operations", move || {
+        let spec = parse_spec(&spec_text(&app, text)?)?;
+        let operations: Vec<serde_json::Value> = operations(&spec)
+            .iter()
+            .map(|operation| {
+                serde_json::json!({
+                    "operation": operation_label(operation),
+                    "operation_id": operation.operation.get("operationId"),
+                    "method": operation.method.to_uppercase(),
+                    "path": operation.path,
+                    "summary": operation.operation.get("summary"),
+                    "tags": operation.operation.get("tags"),
+                    "deprecated": operation.operation.get("deprecated").and_then(|flag| flag.as_bool()).unwrap_or(false)
+                })
+            })
+            .collect();
+        Ok(serde_json::json!({
+            "title": spec.pointer("/info/title"),
+            "version": spec.pointer("/info/version"),
+            "base_url": base_url(&spec),
+            "operations": operations
+        }))
+    })
+    .await
+}
+
+// A request for `operation` (an operationId or "METHOD /path") in the shape
+// send_http_request and collections take
+#[tauri::command]
+pub async fn scaffold_openapi_request(
+    app: AppHandle,
+    text: Option<String>,
+    operation: String,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("scaffold_openapi_request", move || {
+        let spec = parse_spec(&spec_text(&app, text)?)?;
+        scaffold(&spec, &operation)
+    })
+    .await
+}
@@ -0,0 +1,374 @@
+// Folding ranges and an outline for stored JSON/XML/YAML. Each format is handled
+// by a single pass over the bytes rather than a full parse, so this also works on
+// documents too large (or too broken) to parse. Lines are 0-based, matching
+// get_content_lines.
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::autodetect;
+use crate::formatters::FormatterRegistry;
+use crate::text_buffer;
+use crate::AppState;
+
+// Deeper structure still folds, but isn't listed in the outline
+const MAX_OUTLINE_DEPTH: usize = 6;
+const MAX_OUTLINE_ENTRIES: usize = 10_000;
+const MAX_FOLD_RANGES: usize = 100_000;
+const MAX_NAME_LEN: usize = 120;
+
+struct Outline {
+    folds: Vec<serde_json::Value>,
+    symbols: Vec<serde_json::Value>,
+    truncated: bool,
+}
+
+impl Outline {
+    fn new() -> Self {
+        Outline {
+            folds: Vec::new(),
+            symbols: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    fn fold(&mut self, start_line: usize, end_line: usize, kind: &str) {
+        if end_line <= start_line {
+            return;
+        }
+        if self.folds.len() == MAX_FOLD_RANGES {
+            self.truncated = true;
+            return;
+        }
+        self.folds.push(serde_json::json!({
+            "start_line": start_line,
+            "end_line": end_line,
+            "kind": kind
+        }));
+    }
+
+    fn symbol(&mut self, name: &str, kind: &str, depth: usize, start_line: usize, end_line: usize) {
+        if depth > MAX_OUTLINE_DEPTH {
+            return;
+        }
+        if self.symbols.len() == MAX_OUTLINE_ENTRIES {
+            self.truncated = true;
+            return;
+        }
+        let name: String = name.chars().take(MAX_NAME_LEN).collect();
+        self.symbols.push(serde_json::json!({
+            "name": name,
+            "kind": kind,
+            "depth": depth,
+            "start_line": start_line,
+            "end_line": end_line
+        }));
+    }
+}
+
+struct JsonFrame {
+    name: String,
+    is_array: bool,
+    start_line: usize,
+    items: usize,
+}
+
+fn outline_json(text: &str) -> Outline {
+    let mut outline = Outline::new();
+    let bytes = text.as_bytes();
+    let mut stack: Vec<JsonFrame> = Vec::new();
+    let mut line = 0;
+    let mut last_string: Option<(usize, usize)> = None;
+    let mut pending_key: Option<String> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => line += 1,
+            b'"' => {
+                let start = i + 1;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    match bytes[i] {
+                        b'\\' => i += 1,
+                        b'\n' => line += 1,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                last_string = Some((start, i.min(bytes.len())));
+            }
+            b':' => {
+                pending_key = last_string
+                    .take()
+                    .map(|(start, end)| String::from_utf8_lossy(&bytes[start..end]).into_owned());
+            }
+            b',' => {
+                if let Some(frame) = stack.last_mut() {
+                    frame.items += 1;
+                }
+                last_string = None;
+                pending_key = None;
+            }
+            open @ (b'{' | b'[') => {
+                let name = match (pending_key.take(), stack.last()) {
+                    (Some(key), _) => key,
+                    (None, Some(parent)) if parent.is_array => format!("[{}]", parent.items),
+                    _ => "$".to_string(),
+                };
+                stack.push(JsonFrame {
+                    name,
+                    is_array: open == b'[',
+                    start_line: line,
+                    items: 0,
+                });
+                last_string = None;
+            }
+            b'}' | b']' => {
+                if let Some(frame) = stack.pop() {
+                    let kind = if frame.is_array { "array" } else { "object" };
+                    outline.fold(frame.start_line, line, kind);
+                    if !stack.is_empty() {
+                        outline.symbol(&frame.name, kind, stack.len() - 1, frame.start_line, line);
+                    }
+                }
+                last_string = None;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    outline
+}
+
+fn outline_xml(text: &str) -> Outline {
+    let mut outline = Outline::new();
+    let bytes = text.as_bytes();
+    let mut stack: Vec<(String, usize)> = Vec::new();
+    let mut line = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\n' {
+            line += 1;
+            i += 1;
+            continue;
+        }
+        if bytes[i] != b'<' {
+            i += 1;
+            continue;
+        }
+        let start_line = line;
+        let rest = &bytes[i..];
+        if rest.starts_with(b"<!--") {
+            skip_to(bytes, &mut i, &mut line, b"-->");
+            outline.fold(start_line, line, "comment");
+        } else if rest.starts_with(b"<![CDATA[") {
+            skip_to(bytes, &mut i, &mut line, b"]]>");
+            outline.fold(start_line, line, "cdata");
+        } else if rest.starts_with(b"<?") {
+            skip_to(bytes, &mut i, &mut line, b"?>");
+        } else if rest.starts_with(b"<!") {
+            skip_to(bytes, &mut i, &mut line, b">");
+        } else if rest.starts_with(b"</") {
+            let name_start = i + 2;
+            skip_to(bytes, &mut i, &mut line, b">");
+            let name = tag_name(&bytes[name_start..i]);
+            // Tolerate unclosed children (HTML-ish input) by unwinding to the match
+            if let Some(position) = stack.iter().rposition(|(open, _)| *open == name) {
+                while stack.len() > position {
+                    let (open, open_line) = stack.pop().expect("checked length");
+                    outline.fold(open_line, line, "element");
+                    outline.symbol(&open, "element", stack.len(), open_line, line);
+                }
+            }
+        } else {
+            let name_start = i + 1;
+            // Attribute values may contain '>', so skip quoted runs
+            let mut quote = None;
+            while i < bytes.len() {
+                match (bytes[i], quote) {
+                    (b'\n', _) => line += 1,
+                    (b'"' | b'\'', None) => quote = Some(bytes[i]),
+                    (c, Some(q)) if c == q => quote = None,
+                    (b'>', None) => break,
+                    _ => {}
+                }
+                i += 1;
+            }
+            let self_closing = i > 0 && bytes.get(i - 1) == Some(&b'/');
+            let name = tag_name(&bytes[name_start..i.min(bytes.len())]);
+            if !self_closing && !name.is_empty() {
+                stack.push((name, start_line));
+            }
+            i += 1;
+        }
+    }
+    outline
+}
+
+// Advances past `terminator`, counting newlines on the way
+fn skip_to(bytes: &[u8], i: &mut usize, line: &mut usize, terminator: &[u8]) {
+    while *i < bytes.len() {
+        if bytes[*i..].starts_with(terminator) {
+            *i += terminator.len();
+            return;
+        }
+        if bytes[*i] == b'\n' {
+            *line += 1;
+        }
+        *i += 1;
+    }
+}
+
+fn tag_name(tag: &[u8]) -> String {
+    let end = tag
+        .iter()
+        .position(|b| b.is_ascii_whitespace() || *b == b'>' || *b == b'/')
+        .unwrap_or(tag.len());
+    String::from_utf8_lossy(&tag[..end]).into_owned()
+}
+
+fn outline_yaml(text: &str) -> Outline {
+    let mut outline = Outline::new();
+    // (indent, start line, last content line, key)
+    let mut stack: Vec<(usize, usize, usize, String)> = Vec::new();
+
+    let close = |outline: &mut Outline, stack: &mut Vec<(usize, usize, usize, String)>, indent: Option<usize>| {
+        while let Some((open_indent, start, end, key)) = stack.last().cloned() {
+            if indent.is_some_and(|indent| indent > open_indent) {
+                break;
+            }
+            stack.pop();
+            // Like JSON, only keys holding nested content make the outline
+            if end > start {
+                outline.fold(start, end, "block");
+                outline.symbol(&key, "key", stack.len(), start, end);
+            }
+        }
+    };
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let trimmed = raw_line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed == "---" || trimmed == "..." {
+            close(&mut outline, &mut stack, None);
+            continue;
+        }
+        let mut indent = raw_line.len() - trimmed.len();
+        // "- key: value" opens a mapping indented past the dash
+        let mut content = trimmed;
+        while let Some(rest) = content.strip_prefix("- ") {
+            indent += 2;
+            content = rest.trim_start();
+        }
+
+        close(&mut outline, &mut stack, Some(indent));
+        for frame in stack.iter_mut() {
+            frame.2 = index;
+        }
+
+        if let Some(key) = yaml_key(content) {
+            stack.push((indent, index, index, key));
+        }
+    }
+    close(&mut outline, &mut stack, None);
+    outline
+}
+
+// The key of a `key:` line, ignoring colons inside quotes or URLs
+fn yaml_key(line: &str) -> Option<String> {
+    let (key, rest) = if let Some(quoted) = line.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        (&quoted[..end], &quoted[end + 1..])
+    } else if let Some(quoted) = line.strip_prefix('\'') {
+        let end = quoted.find('\'')?;
+        (&quoted[..end], &quoted[end + 1..])
+    } else {
+        let end = line.find(": ").or_else(|| line.strip_suffix(':').map(|k| k.len()))?;
+        (&line[..end], &line[end..])
+    };
+    (rest.starts_with(':') && !key.is_empty()).then(|| key.trim().to_string())
+}
+
+// Maps a format or formatter id to the scanner that understands its structure
+fn structure_of(format: &str) -> Option<&'static str> {
+    match format {
+        "json" | "json5" | "jsonc" | "ndjson" | "json-summary" => Some("json"),
+        "xml" | "html" | "saml" => Some("xml"),
+        "yaml" | "yml" => Some("yaml"),
+        _ => None,
+    }
+}
+
+pub fn resolve_format(
+    format: Option<String>,
+    document_format: Option<&str>,
+    sample: &str,
+    registry: &FormatterRegistry,
+) -> Result<&'static str, String> {
+    if let Some(format) = format.as_deref().filter(|f| !f.is_empty() && *f != "auto") {
+        return structure_of(format).ok_or_else(|| format!("Outline isn't supported for {}", format));
+    }
+    // The document's last formatter is only a hint; anything else is guessed
+    if let Some(format) = document_format.and_then(structure_of) {
+        return Ok(format);
+    }
+    autodetect::guess_formats(sample, false, registry)
+        .iter()
+        .find_map(|guess| structure_of(guess.format))
+        .ok_or_else(|| "Couldn't tell whether the content is JSON, XML or YAML".to_string())
+}
+
+fn compute_outline_blocking(
+    app: &AppHandle,
+    content_type: Option<String>,
+    format: Option<String>,
+) -> Result<serde_json::Value, String> {
+    // Only the buffer handle is cloned, so the storage lock is released before
+    // the scan
+    let content_type = content_type.unwrap_or_else(|| "formatted".to_string());
+    let (content, format_type) = {
+        let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let document = storage.active();
+        let content = match content_type.as_str() {
+            "raw" => document.raw_content.clone(),
+            "formatted" => document.formatted_content.clone(),
+            _ => return Err("Invalid content type".to_string()),
+        }
+        .ok_or_else(|| "No content stored".to_string())?;
+        (content, document.format_type.clone())
+    };
+
+    let sample_end = text_buffer::grapheme_floor(&content, autodetect::SAMPLE_BYTES);
+    let registry = app.state::<FormatterRegistry>();
+    let format = resolve_format(format, format_type.as_deref(), &content[..sample_end], &registry)?;
+    let mut outline = match format {
+        "json" => outline_json(&content),
+        "xml" => outline_xml(&content),
+        _ => outline_yaml(&content),
+    };
+    // Scanners emit symbols as they close; list them in document order
+    outline
+        .symbols
+        .sort_by_key(|symbol| (symbol["start_line"].as_u64(), symbol["depth"].as_u64()));
+
+    Ok(serde_json::json!({
+        "content_type": content_type,
+        "format": format,
+        "folding_ranges": outline.folds,
+        "outline": outline.symbols,
+        "truncated": outline.truncated
+    }))
+}
+
+// `format` is json, xml or yaml; when omitted it comes from the document's last
+// formatter or is guessed from the content
+#[tauri::command]
+pub async fn compute_outline(
+    app: AppHandle,
+    content_type: Option<String>,
+    format: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("compute_outline", move || compute_outline_blocking(&app, content_type, format)).await
+}
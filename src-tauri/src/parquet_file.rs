@@ -0,0 +1,258 @@
+// A quick look into local Parquet files: the schema, file and row-group
+// metadata (sizes, compression, encodings, statistics per column chunk), and
+// one page of rows at a time loaded into a new document as a JSON array or
+// CSV. Row groups before the requested page are skipped using their row
+// counts, so paging deep into a large file doesn't decode what comes before.
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::schema::types::Type;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::columns::quote_field;
+use crate::AppState;
+
+const DEFAULT_PAGE_ROWS: usize = 1000;
+const MAX_PAGE_ROWS: usize = 1_000_000;
+
+fn open(file_path: &str) -> Result<SerializedFileReader<std::fs::File>, String> {
+    let file = std::fs::File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+    SerializedFileReader::new(file).map_err(|e| format!("Not a readable Parquet file: {}", e))
+}
+
+fn inspect_blocking(file_path: &str) -> Result<serde_json::Value, String> {
+    let reader = open(file_path)?;
+    let metadata = reader.metadata();
+    let file_metadata = metadata.file_metadata();
+
+    let mut printed = Vec::new();
+    parquet::schema::printer::print_schema(&mut printed, file_metadata.schema());
+    let columns: Vec<serde_json::Value> = file_metadata
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|column| {
+            serde_json::json!({
+                "path": column.path().string(),
+                "physical_type": column.physical_type().to_string(),
+                "logical_type": column.logical_type().map(|logical| format!("{:?}", logical)),
+                "converted_type": column.converted_type().to_string(),
+                "optional": column.max_def_level() > 0,
+                "repeated": column.max_rep_level() > 0
+            })
+        })
+        .collect();
+
+    let mut first_row = 0;
+    let row_groups: Vec<serde_json::Value> = metadata
+        .row_groups()
+        .iter()
+        .enumerate()
+        .map(|(index, group)| {
+            let chunks: Vec<serde_json::Value> = group
+                .columns()
+                .iter()
+                .map(|chunk| {
+                    serde_json::json!({
+                        "path": chunk.column_path().string(),
+                        "compression": chunk.compression().to_string(),
+                        "encodings": chunk.encodings().iter().map(|encoding| encoding.to_string()).collect::<Vec<_>>(),
+                        "compressed_bytes": chunk.compressed_size(),
+                        "uncompressed_bytes": chunk.uncompressed_size(),
+                        "statistics": chunk.statistics().map(|statistics| statistics.to_string())
+                    })
+                })
+                .collect();
+            let summary = serde_json::json!({
+                "index": index,
+                "first_row": first_row,
+                "rows": group.num_rows(),
+                "uncompressed_bytes": group.total_byte_size(),
+                "compressed_bytes": group.compressed_size(),
+                "columns": chunks
+            });
+            first_row += group.num_rows();
+            summary
+        })
+        .collect();
+
+    let key_value_metadata: serde_json::Map<String, serde_json::Value> = file_metadata
+        .key_value_metadata()
+        .map(|entries| {
+            entries
+                .iter()
+                .map(|entry| (entry.key.clone(), entry.value.clone().into()))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(serde_json::json!({
+        "rows": file_metadata.num_rows(),
+        "version": file_metadata.version(),
+        "created_by": file_metadata.created_by(),
+        "schema": String::from_utf8_lossy(&printed),
+        "columns": columns,
+        "row_groups": row_groups,
+        "key_value_metadata": key_value_metadata,
+        "file_bytes": std::fs::metadata(file_path).map(|m| m.len()).unwrap_or_default()
+    }))
+}
+
+// A projection onto the named top-level columns, in the order given
+fn projection(schema: &Type, columns: &[String]) -> Result<Type, String> {
+    let fields = columns
+        .iter()
+        .map(|name| {
+            schema
+                .get_fields()
+                .iter()
+                .find(|field| field.name() == name)
+                .map(Arc::clone)
+                .ok_or_else(|| format!("No column named {}", name))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Type::group_type_builder(schema.name())
+        .with_fields(fields)
+        .build()
+        .map_err(|e| format!("Invalid column selection: {}", e))
+}
+
+fn csv_cell(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(text) => quote_field(text, ','),
+        other => quote_field(&other.to_string(), ','),
+    }
+}
+
+fn read_rows_blocking(
+    app: &AppHandle,
+    file_path: String,
+    offset: usize,
+    limit: usize,
+    columns: Option<Vec<String>>,
+    csv: bool,
+) -> Result<serde_json::Value, String> {
+    let reader = open(&file_path)?;
+    let metadata = reader.metadata();
+    let schema = metadata.file_metadata().schema();
+    let projection = match &columns {
+        Some(columns) => Some(projection(schema, columns)?),
+        None => None,
+    };
+    let header: Vec<String> = match &projection {
+        Some(projection) => projection
+            .get_fields()
+            .iter()
+            .map(|field| field.name().to_string())
+            .collect(),
+        None => schema
+            .get_fields()
+            .iter()
+            .map(|field| field.name().to_string())
+            .collect(),
+    };
+
+    let total_rows = metadata.file_metadata().num_rows().max(0) as usize;
+    let mut rows = Vec::new();
+    let mut group_start = 0;
+    for index in 0..metadata.num_row_groups() {
+        if rows.len() >= limit {
+            break;
+        }
+        let group_rows = metadata.row_group(index).num_rows().max(0) as usize;
+        if group_start + group_rows <= offset {
+            group_start += group_rows;
+            continue;
+        }
+        let skip = offset.saturating_sub(group_start);
+        let row_group = reader
+            .get_row_group(index)
+            .map_err(|e| format!("Failed to read row group {}: {}", index, e))?;
+        let iter = row_group
+            .get_row_iter(projection.clone())
+            .map_err(|e| format!("Failed to read row group {}: {}", index, e))?;
+        for row in iter.skip(skip).take(limit - rows.len()) {
+            let row = row.map_err(|e| format!("Failed to read a row in row group {}: {}", index, e))?;
+            rows.push(row.to_json_value());
+        }
+        group_start += group_rows;
+    }
+
+    let count = rows.len();
+    let content = if csv {
+        let mut lines = vec![header
+            .iter()
+            .map(|name| quote_field(name, ','))
+            .collect::<Vec<_>>()
+            .join(",")];
+        lines.extend(rows.iter().map(|row| {
+            header
+                .iter()
+                .map(|name| csv_cell(&row[name.as_str()]))
+                .collect::<Vec<_>>()
+                .join(",")
+        }));
+        lines.join("\n") + "\n"
+    } else {
+        crate::formatters::to_string_pretty(&serde_json::Value::Array(rows))
+            .map_err(|e| format!("Failed to format JSON: {}", e))?
+    };
+
+    let file_name = Path::new(&file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file_path.clone());
+    let document_id = {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let id = storage.insert_document(format!("{} rows {}-{}", file_name, offset + 1, offset + count));
+        let document = storage.active_mut();
+        document.raw_content = Some(content.into());
+        document.format_type = Some(if csv { "csv" } else { "json" }.to_string());
+        id
+    };
+    crate::tray::refresh(app);
+    Ok(serde_json::json!({
+        "document_id": document_id,
+        "offset": offset,
+        "rows": count,
+        "total_rows": total_rows,
+        "has_more": offset + count < total_rows,
+        "columns": header
+    }))
+}
+
+// Schema and metadata of the Parquet file at `file_path`, without reading rows
+#[tauri::command]
+pub async fn inspect_parquet(file_path: String) -> Result<serde_json::Value, String> {
+    crate::run_blocking("inspect_parquet", move || inspect_blocking(&file_path)).await
+}
+
+// Loads `limit` rows starting at row `offset` (0-based) into a new document,
+// as a JSON array or, with `format` "csv", as CSV. `columns` limits the output
+// to those top-level columns.
+#[tauri::command]
+pub async fn read_parquet_rows(
+    app: AppHandle,
+    file_path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    columns: Option<Vec<String>>,
+    format: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let csv = match format.as_deref().unwrap_or("json") {
+        "json" => false,
+        "csv" => true,
+        other => return Err(format!("Unknown format {}; use json or csv", other)),
+    };
+    crate::run_blocking("read_parquet_rows", move || {
+        read_rows_blocking(
+            &app,
+            file_path,
+            offset.unwrap_or(0),
+            limit.unwrap_or(DEFAULT_PAGE_ROWS).clamp(1, MAX_PAGE_ROWS),
+            columns.filter(|columns| !columns.is_empty()),
+            csv,
+        )
+    })
+    .await
+}
@@ -0,0 +1,390 @@
+// Unified diffs (`git diff`, `diff -u`, format-patch mails): the "diff"
+// formatter turns one into a per-file hunk model with old and new line numbers
+// for side-by-side rendering, and apply_patch applies a file's hunks to a
+// document. Like `patch`, a hunk whose context moved is found nearby and the
+// offset reported; if any hunk can't be placed nothing is changed.
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::Formatter;
+use crate::undo::Slot;
+use crate::AppState;
+
+#[derive(Serialize, Clone)]
+pub struct PatchLine {
+    // "context", "add" or "delete"
+    pub kind: &'static str,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+    // Followed by "\ No newline at end of file"
+    pub no_newline: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    // Text after the closing @@, usually the enclosing function
+    pub section: String,
+    pub lines: Vec<PatchLine>,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct FilePatch {
+    // None for /dev/null, i.e. an added or deleted file
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    // "modified", "added", "deleted" or "renamed"
+    pub status: &'static str,
+    pub binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+// "a/src/main.rs\t2024-01-01 ..." -> "src/main.rs"; None for /dev/null
+fn header_path(text: &str) -> Option<String> {
+    let path = text.split('\t').next().unwrap_or_default().trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path.trim_matches('"');
+    Some(
+        path.strip_prefix("a/")
+            .or_else(|| path.strip_prefix("b/"))
+            .unwrap_or(path)
+            .to_string(),
+    )
+}
+
+// "-12,5" -> (12, 5); a missing count means one line
+fn parse_range(text: &str) -> Option<(usize, usize)> {
+    match text.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((text.parse().ok()?, 1)),
+    }
+}
+
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize, usize, String)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let (old_start, old_lines) = parse_range(old)?;
+    let (new_start, new_lines) = parse_range(new)?;
+    Some((old_start, old_lines, new_start, new_lines, section.trim().to_string()))
+}
+
+fn finish(file: Option<FilePatch>, files: &mut Vec<FilePatch>) {
+    if let Some(mut file) = file {
+        if file.status.is_empty() {
+            file.status = match (&file.old_path, &file.new_path) {
+                (None, Some(_)) => "added",
+                (Some(_), None) => "deleted",
+                (Some(old), Some(new)) if old != new => "renamed",
+                _ => "modified",
+            };
+        }
+        files.push(file);
+    }
+}
+
+pub fn parse_patch(text: &str) -> Result<Vec<FilePatch>, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut files = Vec::new();
+    let mut current: Option<FilePatch> = None;
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            finish(current.take(), &mut files);
+            let (old, new) = rest.split_once(" b/").unwrap_or((rest, rest));
+            current = Some(FilePatch {
+                old_path: header_path(old),
+                new_path: Some(new.to_string()),
+                ..FilePatch::default()
+            });
+        } else if line.starts_with("--- ") && lines.get(index).is_some_and(|next| next.starts_with("+++ ")) {
+            // Plain `diff -u` output has no "diff --git" line; a second file
+            // starts with its own ---/+++ pair
+            if current.as_ref().is_none_or(|file| !file.hunks.is_empty()) {
+                finish(current.take(), &mut files);
+                current = Some(FilePatch::default());
+            }
+            let file = current.as_mut().expect("file patch was just set");
+            file.old_path = header_path(&line[4..]);
+            file.new_path = header_path(&lines[index][4..]);
+            index += 1;
+        } else if let Some(file) = current.as_mut().filter(|_| !line.starts_with("@@ ")) {
+            if line.starts_with("new file mode") {
+                file.status = "added";
+                file.old_path = None;
+            } else if line.starts_with("deleted file mode") {
+                file.status = "deleted";
+                file.new_path = None;
+            } else if let Some(path) = line.strip_prefix("rename from ") {
+                file.status = "renamed";
+                file.old_path = Some(path.to_string());
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                file.new_path = Some(path.to_string());
+            } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+                file.binary = true;
+            }
+        } else if let Some((old_start, old_lines, new_start, new_lines, section)) = parse_hunk_header(line) {
+            let file = current
+                .as_mut()
+                .ok_or_else(|| format!("Line {}: hunk without a file header", index))?;
+            let (mut old_left, mut new_left) = (old_lines, new_lines);
+            let (mut old_line, mut new_line) = (old_start, new_start);
+            let mut hunk_lines: Vec<PatchLine> = Vec::new();
+            while old_left > 0 || new_left > 0 || lines.get(index).is_some_and(|next| next.starts_with('\\')) {
+                let Some(body) = lines.get(index) else {
+                    return Err(format!("Hunk at line {} ends early", index));
+                };
+                index += 1;
+                let (marker, text) = match body.get(..1) {
+                    Some(marker) => (marker, &body[1..]),
+                    None => (if body.is_empty() { "" } else { "?" }, *body),
+                };
+                let (kind, old_number, new_number) = match marker {
+                    // Some editors strip the trailing space of empty context lines
+                    " " | "" if old_left > 0 && new_left > 0 => {
+                        old_left -= 1;
+                        new_left -= 1;
+                        ("context", Some(old_line), Some(new_line))
+                    }
+                    "-" if old_left > 0 => {
+                        old_left -= 1;
+                        ("delete", Some(old_line), None)
+                    }
+                    "+" if new_left > 0 => {
+                        new_left -= 1;
+                        ("add", None, Some(new_line))
+                    }
+                    "\\" => {
+                        if let Some(previous) = hunk_lines.last_mut() {
+                            previous.no_newline = true;
+                        }
+                        continue;
+                    }
+                    _ => return Err(format!("Line {}: unexpected line inside a hunk: {}", index, body)),
+                };
+                old_line += usize::from(old_number.is_some());
+                new_line += usize::from(new_number.is_some());
+                hunk_lines.push(PatchLine {
+                    kind,
+                    old_line: old_number,
+                    new_line: new_number,
+                    text: text.to_string(),
+                    no_newline: false,
+                });
+            }
+            file.additions += hunk_lines.iter().filter(|line| line.kind == "add").count();
+            file.deletions += hunk_lines.iter().filter(|line| line.kind == "delete").count();
+            file.hunks.push(Hunk {
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                section,
+                lines: hunk_lines,
+            });
+        }
+    }
+    finish(current, &mut files);
+    if files.is_empty() {
+        return Err("No unified diff found".to_string());
+    }
+    Ok(files)
+}
+
+pub fn format_patch(text: &str) -> Result<String, String> {
+    let files = parse_patch(text)?;
+    let additions: usize = files.iter().map(|file| file.additions).sum();
+    let deletions: usize = files.iter().map(|file| file.deletions).sum();
+    let model = serde_json::json!({
+        "file_count": files.len(),
+        "additions": additions,
+        "deletions": deletions,
+        "files": files
+    });
+    crate::formatters::to_string_pretty(&model).map_err(|e| format!("Failed to serialize the diff: {}", e))
+}
+
+fn line_text(line: &str) -> &str {
+    line.trim_end_matches('\n').trim_end_matches('\r')
+}
+
+// Applies every hunk of `file` to `content`, each placed at its stated line or
+// the nearest position where its old lines match. Returns the new content and
+// each hunk's offset.
+fn apply_file(content: &str, file: &FilePatch, reverse: bool) -> Result<(String, Vec<i64>), String> {
+    let source: Vec<&str> = content.split_inclusive('\n').collect();
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut out = String::with_capacity(content.len());
+    let mut cursor = 0;
+    let mut offsets = Vec::new();
+    let mut failed = Vec::new();
+
+    for (number, hunk) in file.hunks.iter().enumerate() {
+        let (removed, added) = if reverse { ("add", "delete") } else { ("delete", "add") };
+        let old: Vec<&PatchLine> = hunk.lines.iter().filter(|line| line.kind != added).collect();
+        let new: Vec<&PatchLine> = hunk.lines.iter().filter(|line| line.kind != removed).collect();
+        let (old_start, old_lines) = if reverse {
+            (hunk.new_start, hunk.new_lines)
+        } else {
+            (hunk.old_start, hunk.old_lines)
+        };
+        // A hunk that only adds lines gives the line they follow
+        let expected = if old_lines == 0 {
+            old_start
+        } else {
+            old_start.saturating_sub(1)
+        };
+
+        let matches_at = |position: usize| {
+            position + old.len() <= source.len()
+                && old
+                    .iter()
+                    .zip(&source[position..])
+                    .all(|(line, existing)| line.text == line_text(existing))
+        };
+        let last = source.len().saturating_sub(old.len());
+        let position = (0..=source.len())
+            .flat_map(|distance| [expected.checked_add(distance), expected.checked_sub(distance)])
+            .flatten()
+            .filter(|position| *position >= cursor && *position <= last)
+            .find(|position| matches_at(*position));
+        let Some(position) = position else {
+            failed.push(number + 1);
+            continue;
+        };
+
+        for line in &source[cursor..position] {
+            out.push_str(line);
+        }
+        for line in &new {
+            out.push_str(&line.text);
+            if !line.no_newline {
+                out.push_str(newline);
+            }
+        }
+        cursor = position + old.len();
+        offsets.push(position as i64 - expected as i64);
+    }
+    if !failed.is_empty() {
+        let numbers: Vec<String> = failed.iter().map(usize::to_string).collect();
+        return Err(format!(
+            "Hunk {} of {} doesn't match the content; nothing was changed",
+            numbers.join(", "),
+            file.path()
+        ));
+    }
+    for line in &source[cursor..] {
+        out.push_str(line);
+    }
+    Ok((out, offsets))
+}
+
+// The file to apply: the one named by `file`, the only text file in the patch,
+// or the one whose name matches the document's
+fn pick_file<'a>(files: &'a [FilePatch], file: Option<&str>, document_name: &str) -> Result<&'a FilePatch, String> {
+    let candidates: Vec<&FilePatch> = files.iter().filter(|patch| !patch.binary).collect();
+    let by_name = |name: &str| {
+        candidates
+            .iter()
+            .copied()
+            .find(|patch| patch.path() == name || patch.path().ends_with(&format!("/{}", name)))
+    };
+    if let Some(file) = file {
+        return by_name(file).ok_or_else(|| format!("The patch doesn't change {}", file));
+    }
+    match candidates.as_slice() {
+        [] => Err("The patch only changes binary files".to_string()),
+        [only] => Ok(*only),
+        _ => by_name(document_name).ok_or_else(|| {
+            let paths: Vec<&str> = candidates.iter().map(|patch| patch.path()).collect();
+            format!("The patch changes several files; pick one of {}", paths.join(", "))
+        }),
+    }
+}
+
+// Applies `patch` (or its hunks for `file`) to a document's raw content, the
+// active one unless `document_id` is given, as an undoable edit. `reverse`
+// undoes a patch that was already applied.
+#[tauri::command]
+pub async fn apply_patch(
+    app: AppHandle,
+    patch: String,
+    file: Option<String>,
+    document_id: Option<u64>,
+    reverse: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("apply_patch", move || {
+        let files = parse_patch(&patch)?;
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let document_id = document_id.unwrap_or(storage.active_id);
+        let document = storage
+            .documents
+            .get_mut(&document_id)
+            .ok_or_else(|| format!("Document {} not found", document_id))?;
+        let target = pick_file(&files, file.as_deref(), &document.name)?;
+        let content = document.raw_content.as_deref().unwrap_or_default();
+        let (patched, offsets) = apply_file(content, target, reverse.unwrap_or(false))?;
+
+        let length = patched.len();
+        document.edit(Slot::Raw, patched);
+        document.formatted_content = None;
+        Ok(serde_json::json!({
+            "document_id": document_id,
+            "file": target.path(),
+            "hunks": offsets.len(),
+            "offsets": offsets,
+            "additions": target.additions,
+            "deletions": target.deletions,
+            "length": length
+        }))
+    })
+    .await
+}
+
+pub struct PatchFormatter;
+
+impl Formatter for PatchFormatter {
+    fn id(&self) -> &'static str {
+        "diff"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["patch", "unified-diff"]
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Unified Diff / Patch"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        crate::formatters::layout_options_schema(crate::formatters::PRETTY_OPTIONS)
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        format_patch(input)
+    }
+}
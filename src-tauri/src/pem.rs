@@ -0,0 +1,149 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use tauri::{AppHandle, Manager};
+
+use crate::{asn1, x509, AppState};
+
+pub struct PemBlock {
+    pub label: String,
+    pub der: Vec<u8>,
+    pub text: String,
+}
+
+pub fn split_pem_blocks(text: &str) -> Result<Vec<PemBlock>, String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(String, String, String)> = None;
+
+    for line in text.lines().map(str::trim) {
+        if let Some(label) = line.strip_prefix("-----BEGIN ").and_then(|l| l.strip_suffix("-----")) {
+            current = Some((label.to_string(), String::new(), format!("{}\n", line)));
+        } else if let Some(label) = line.strip_prefix("-----END ").and_then(|l| l.strip_suffix("-----")) {
+            let (begin_label, body, mut block_text) = current
+                .take()
+                .ok_or_else(|| format!("Found END {} without matching BEGIN", label))?;
+            if begin_label != label {
+                return Err(format!("Mismatched PEM block: BEGIN {} closed by END {}", begin_label, label));
+            }
+            block_text.push_str(line);
+            block_text.push('\n');
+            let der = STANDARD
+                .decode(body.as_bytes())
+                .map_err(|e| format!("Invalid base64 in {} block #{}: {}", label, blocks.len(), e))?;
+            blocks.push(PemBlock { label: begin_label, der, text: block_text });
+        } else if let Some((_, body, block_text)) = current.as_mut() {
+            // Skip RFC 1421 headers such as "Proc-Type:" in encrypted keys
+            if !line.contains(':') {
+                body.push_str(line);
+            }
+            block_text.push_str(line);
+            block_text.push('\n');
+        }
+    }
+
+    if let Some((label, _, _)) = current {
+        return Err(format!("Unterminated PEM block: BEGIN {}", label));
+    }
+    Ok(blocks)
+}
+
+fn describe_block(block: &PemBlock) -> serde_json::Value {
+    let mut summary = serde_json::json!({
+        "label": block.label,
+        "der_size": block.der.len()
+    });
+
+    if block.label == "CERTIFICATE" || block.label == "TRUSTED CERTIFICATE" {
+        if let Ok(cert) = x509::describe_certificate(&block.der) {
+            summary["subject"] = cert["subject"].clone();
+            summary["issuer"] = cert["issuer"].clone();
+            summary["not_after"] = cert["not_after"].clone();
+        }
+    }
+    summary
+}
+
+fn bundle_text(text: Option<String>, state: &AppState) -> Result<String, String> {
+    match text {
+        Some(text) if !text.is_empty() => Ok(text),
+        _ => {
+            let storage = state.lock().map_err(|e| e.to_string())?;
+            storage
+                .active()
+                .raw_content
+                .as_deref()
+                .map(str::to_string)
+                .ok_or_else(|| "No content stored".to_string())
+        }
+    }
+}
+
+fn parse_pem_bundle_blocking(
+    text: Option<String>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let text = bundle_text(text, state)?;
+    let blocks = split_pem_blocks(&text)?;
+    if blocks.is_empty() {
+        return Err("No PEM blocks found".to_string());
+    }
+
+    let described: Vec<serde_json::Value> = blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| {
+            let mut summary = describe_block(block);
+            summary["index"] = serde_json::json!(index);
+            summary
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "count": described.len(),
+        "blocks": described
+    }))
+}
+
+#[tauri::command]
+pub async fn parse_pem_bundle(
+    app: AppHandle,
+    text: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("parse_pem_bundle", move || {
+        parse_pem_bundle_blocking(text, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+// Decode one block of a bundle with the decoder matching its label
+fn decode_pem_block_blocking(
+    index: usize,
+    text: Option<String>,
+    state: &AppState,
+) -> Result<String, String> {
+    let text = bundle_text(text, state)?;
+    let blocks = split_pem_blocks(&text)?;
+    let block = blocks
+        .get(index)
+        .ok_or_else(|| format!("PEM block #{} not found ({} blocks in bundle)", index, blocks.len()))?;
+
+    let result = match block.label.as_str() {
+        "CERTIFICATE" | "TRUSTED CERTIFICATE" => x509::decode_x509(&block.text)?,
+        _ => asn1::dump_asn1(&block.text)?,
+    };
+
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.active_mut().formatted_content = Some(result.clone().into());
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn decode_pem_block(
+    app: AppHandle,
+    index: usize,
+    text: Option<String>,
+) -> Result<String, String> {
+    crate::run_blocking("decode_pem_block", move || {
+        decode_pem_block_blocking(index, text, app.state::<AppState>().inner())
+    })
+    .await
+}
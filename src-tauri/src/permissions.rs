@@ -0,0 +1,100 @@
+const CLASSES: [&str; 3] = ["owner", "group", "other"];
+
+fn parse_octal(mode: &str) -> Result<u32, String> {
+    let digits = mode.trim_start_matches("0o");
+    if digits.is_empty() || digits.len() > 5 || !digits.chars().all(|c| ('0'..='7').contains(&c)) {
+        return Err(format!("Invalid octal mode: {}", mode));
+    }
+    u32::from_str_radix(digits, 8)
+        .map(|m| m & 0o7777)
+        .map_err(|e| format!("Invalid octal mode: {}", e))
+}
+
+fn parse_symbolic(mode: &str) -> Result<u32, String> {
+    // Allow an `ls -l` style leading file-type character
+    let all: Vec<char> = mode.chars().collect();
+    let chars = match all.len() {
+        9 => &all[..],
+        10 => &all[1..],
+        _ => return Err(format!("Invalid symbolic mode: {}", mode)),
+    };
+
+    let mut bits = 0u32;
+    for (class, triple) in chars.chunks(3).enumerate() {
+        let shift = 6 - class as u32 * 3;
+        if triple[0] == 'r' {
+            bits |= 0o4 << shift;
+        } else if triple[0] != '-' {
+            return Err(format!("Invalid read flag '{}' in {}", triple[0], mode));
+        }
+        if triple[1] == 'w' {
+            bits |= 0o2 << shift;
+        } else if triple[1] != '-' {
+            return Err(format!("Invalid write flag '{}' in {}", triple[1], mode));
+        }
+
+        // The execute slot also carries setuid/setgid/sticky (lowercase = with execute)
+        let special = [0o4000, 0o2000, 0o1000][class];
+        let special_char = ['s', 's', 't'][class];
+        match triple[2] {
+            'x' => bits |= 0o1 << shift,
+            '-' => {}
+            c if c == special_char => bits |= special | (0o1 << shift),
+            c if c == special_char.to_ascii_uppercase() => bits |= special,
+            c => return Err(format!("Invalid execute flag '{}' in {}", c, mode)),
+        }
+    }
+    Ok(bits)
+}
+
+fn to_symbolic(mode: u32) -> String {
+    let mut out = String::new();
+    for class in 0..3 {
+        let shift = 6 - class * 3;
+        let perms = (mode >> shift) & 0o7;
+        out.push(if perms & 0o4 != 0 { 'r' } else { '-' });
+        out.push(if perms & 0o2 != 0 { 'w' } else { '-' });
+        let special = mode & [0o4000, 0o2000, 0o1000][class as usize] != 0;
+        let special_char = ['s', 's', 't'][class as usize];
+        out.push(match (special, perms & 0o1 != 0) {
+            (true, true) => special_char,
+            (true, false) => special_char.to_ascii_uppercase(),
+            (false, true) => 'x',
+            (false, false) => '-',
+        });
+    }
+    out
+}
+
+#[tauri::command]
+pub fn convert_file_mode(mode: String) -> Result<serde_json::Value, String> {
+    let mode = mode.trim();
+    let bits = if mode.chars().all(|c| c.is_ascii_digit()) || mode.starts_with("0o") {
+        parse_octal(mode)?
+    } else {
+        parse_symbolic(mode)?
+    };
+
+    let mut breakdown = serde_json::Map::new();
+    for (class, name) in CLASSES.iter().enumerate() {
+        let perms = (bits >> (6 - class * 3)) & 0o7;
+        breakdown.insert(
+            name.to_string(),
+            serde_json::json!({
+                "read": perms & 0o4 != 0,
+                "write": perms & 0o2 != 0,
+                "execute": perms & 0o1 != 0
+            }),
+        );
+    }
+
+    Ok(serde_json::json!({
+        "octal": format!("{:04o}", bits),
+        "symbolic": to_symbolic(bits),
+        "chmod_command": format!("chmod {:o} <file>", bits),
+        "setuid": bits & 0o4000 != 0,
+        "setgid": bits & 0o2000 != 0,
+        "sticky": bits & 0o1000 != 0,
+        "permissions": breakdown
+    }))
+}
@@ -0,0 +1,752 @@
+// Phone number parsing in the spirit of libphonenumber: a number written in
+// any common style ("+44 20 7946 0958", "(415) 555-0100 ext. 12", "0049 30
+// 1234567") is split into calling code and national number, checked against
+// per-country number patterns for validity and type, and normalized to E.164.
+// Patterns cover the countries listed in REGIONS; other calling codes are
+// recognised and length-checked only, so their numbers are "possible" at best.
+use regex::Regex;
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+// E.164 allows 15 digits including the calling code
+const MAX_E164_DIGITS: usize = 15;
+const MIN_NATIONAL_DIGITS: usize = 4;
+
+struct Region {
+    code: &'static str,
+    calling_code: &'static str,
+    // Dialled before the national number within the country, dropped in E.164
+    trunk_prefix: Option<&'static str>,
+    // Number types in match order, as full-match patterns on the national number
+    types: &'static [(&'static str, &'static str)],
+    // Digit groups for display by national number prefix; the last group
+    // takes whatever digits remain
+    groups: &'static [(&'static str, &'static [usize])],
+}
+
+const NANP_TYPES: &[(&str, &str)] = &[
+    ("toll_free", r"8(?:00|33|44|55|66|77|88)[2-9]\d{6}"),
+    ("premium_rate", r"900[2-9]\d{6}"),
+    // North American numbers don't reveal whether they're mobile
+    ("fixed_line_or_mobile", r"[2-9]\d{2}[2-9]\d{6}"),
+];
+
+const REGIONS: &[Region] = &[
+    Region {
+        code: "US",
+        calling_code: "1",
+        trunk_prefix: Some("1"),
+        types: NANP_TYPES,
+        groups: &[("", &[3, 3, 4])],
+    },
+    Region {
+        code: "CA",
+        calling_code: "1",
+        trunk_prefix: Some("1"),
+        types: NANP_TYPES,
+        groups: &[("", &[3, 3, 4])],
+    },
+    Region {
+        code: "GB",
+        calling_code: "44",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"7(?:[1-57-9]\d{8}|624\d{6})"),
+            ("toll_free", r"80(?:0\d{6,7}|8\d{7})"),
+            ("premium_rate", r"9[018]\d{8}"),
+            ("shared_cost", r"8(?:4[2-5]|7[0-3])\d{7}"),
+            ("voip", r"56\d{8}"),
+            ("uan", r"(?:3\d|55)\d{8}"),
+            ("fixed_line", r"1\d{8,9}|2\d{9}"),
+        ],
+        groups: &[
+            ("2", &[2, 4, 4]),
+            ("7", &[4, 6]),
+            ("8", &[3, 3, 4]),
+            ("9", &[3, 3, 4]),
+            ("", &[4, 6]),
+        ],
+    },
+    Region {
+        code: "DE",
+        calling_code: "49",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"1(?:5[0-25-9]\d{8}|6[023]\d{7,8}|7\d{8,9})"),
+            ("toll_free", r"800\d{7,12}"),
+            ("premium_rate", r"900[135]\d{6}"),
+            ("fixed_line", r"[2-9]\d{5,12}"),
+        ],
+        groups: &[
+            ("1", &[3, 8]),
+            ("30", &[2, 8]),
+            ("40", &[2, 8]),
+            ("69", &[2, 8]),
+            ("89", &[2, 8]),
+            ("800", &[3, 8]),
+        ],
+    },
+    Region {
+        code: "FR",
+        calling_code: "33",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"[67]\d{8}"),
+            ("toll_free", r"80[0-5]\d{6}"),
+            ("premium_rate", r"89[1-37-9]\d{6}"),
+            ("shared_cost", r"8(?:1[01]|2[0156]|84)\d{6}"),
+            ("voip", r"9\d{8}"),
+            ("fixed_line", r"[1-5]\d{8}"),
+        ],
+        groups: &[("", &[1, 2, 2, 2, 2])],
+    },
+    Region {
+        code: "ES",
+        calling_code: "34",
+        trunk_prefix: None,
+        types: &[
+            ("mobile", r"6\d{8}|7[1-4]\d{7}"),
+            ("toll_free", r"[89]00\d{6}"),
+            ("premium_rate", r"80[367]\d{6}"),
+            ("fixed_line", r"[89][1-8]\d{7}"),
+        ],
+        groups: &[("", &[3, 3, 3])],
+    },
+    Region {
+        code: "IT",
+        calling_code: "39",
+        // Italian landlines keep their leading 0 even in E.164
+        trunk_prefix: None,
+        types: &[
+            ("mobile", r"3\d{8,9}"),
+            ("toll_free", r"80(?:0\d{3}|3)\d{3}"),
+            ("premium_rate", r"89[2-5]\d{3,6}"),
+            ("fixed_line", r"0\d{5,10}"),
+        ],
+        groups: &[
+            ("3", &[3, 3, 4]),
+            ("02", &[2, 4, 4]),
+            ("06", &[2, 4, 4]),
+            ("0", &[3, 7]),
+        ],
+    },
+    Region {
+        code: "NL",
+        calling_code: "31",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"6[1-58]\d{7}"),
+            ("toll_free", r"800\d{4,7}"),
+            ("premium_rate", r"90[069]\d{4,7}"),
+            ("voip", r"85\d{7}"),
+            ("fixed_line", r"[1-57]\d{8}"),
+        ],
+        groups: &[("6", &[1, 8]), ("", &[2, 3, 4])],
+    },
+    Region {
+        code: "CH",
+        calling_code: "41",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"7[5-9]\d{7}"),
+            ("toll_free", r"800\d{6}"),
+            ("premium_rate", r"90[016]\d{6}"),
+            ("fixed_line", r"[2-9]\d{8}"),
+        ],
+        groups: &[("", &[2, 3, 2, 2])],
+    },
+    Region {
+        code: "SE",
+        calling_code: "46",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"7[02369]\d{7}"),
+            ("toll_free", r"20\d{4,7}"),
+            ("premium_rate", r"900\d{4,7}"),
+            ("fixed_line", r"[1-9]\d{6,8}"),
+        ],
+        groups: &[("7", &[2, 3, 2, 2]), ("8", &[1, 3, 3, 2])],
+    },
+    Region {
+        code: "PL",
+        calling_code: "48",
+        trunk_prefix: None,
+        types: &[
+            ("mobile", r"(?:45|5[0137]|6[069]|7[2389]|88)\d{7}"),
+            ("toll_free", r"800\d{6}"),
+            ("premium_rate", r"70[01346-8]\d{6}"),
+            ("fixed_line", r"[1-9]\d{8}"),
+        ],
+        groups: &[("", &[3, 3, 3])],
+    },
+    Region {
+        code: "IE",
+        calling_code: "353",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"8[35-9]\d{7}"),
+            ("toll_free", r"1800\d{6}"),
+            ("premium_rate", r"15\d{8}"),
+            ("fixed_line", r"[1-9]\d{6,8}"),
+        ],
+        groups: &[("8", &[2, 3, 4]), ("1", &[1, 3, 4])],
+    },
+    Region {
+        code: "BR",
+        calling_code: "55",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("toll_free", r"800\d{6,7}"),
+            ("mobile", r"[1-9]{2}9\d{8}"),
+            ("fixed_line", r"[1-9]{2}[2-5]\d{7}"),
+        ],
+        groups: &[("800", &[3, 3, 4]), ("", &[2, 4, 4])],
+    },
+    Region {
+        code: "MX",
+        calling_code: "52",
+        trunk_prefix: None,
+        types: &[
+            ("toll_free", r"800\d{7}"),
+            ("premium_rate", r"900\d{7}"),
+            ("fixed_line_or_mobile", r"[2-9]\d{9}"),
+        ],
+        groups: &[
+            ("55", &[2, 4, 4]),
+            ("33", &[2, 4, 4]),
+            ("81", &[2, 4, 4]),
+            ("", &[3, 3, 4]),
+        ],
+    },
+    Region {
+        code: "IN",
+        calling_code: "91",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"[6-9]\d{9}"),
+            ("toll_free", r"1800\d{6,7}"),
+            ("fixed_line", r"(?:1[1-9]|[2-5]\d)\d{8}"),
+        ],
+        groups: &[("1800", &[4, 3, 4]), ("", &[5, 5])],
+    },
+    Region {
+        code: "CN",
+        calling_code: "86",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"1[3-9]\d{9}"),
+            ("toll_free", r"(?:400|800)\d{7}"),
+            ("fixed_line", r"(?:10|2\d)\d{8}|[3-9]\d{9,10}"),
+        ],
+        groups: &[("1", &[3, 4, 4]), ("2", &[2, 4, 4]), ("", &[3, 4, 4])],
+    },
+    Region {
+        code: "JP",
+        calling_code: "81",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"[7-9]0\d{8}"),
+            ("toll_free", r"120\d{6}|800\d{7}"),
+            ("premium_rate", r"990\d{6}"),
+            ("fixed_line", r"[1-9]\d{8}"),
+        ],
+        groups: &[
+            ("120", &[3, 3, 3]),
+            ("3", &[1, 4, 4]),
+            ("6", &[1, 4, 4]),
+            ("", &[2, 4, 4]),
+        ],
+    },
+    Region {
+        code: "SG",
+        calling_code: "65",
+        trunk_prefix: None,
+        types: &[
+            ("mobile", r"[89]\d{7}"),
+            ("fixed_line", r"6\d{7}"),
+            ("toll_free", r"1800\d{7}"),
+        ],
+        groups: &[("1800", &[4, 3, 4]), ("", &[4, 4])],
+    },
+    Region {
+        code: "AU",
+        calling_code: "61",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"4\d{8}"),
+            ("toll_free", r"180(?:0\d{6}|2\d{3})"),
+            ("shared_cost", r"13(?:00\d{6}|\d{4})"),
+            ("premium_rate", r"190[0-26]\d{6}"),
+            ("fixed_line", r"[2378]\d{8}"),
+        ],
+        groups: &[("4", &[3, 3, 3]), ("1", &[4, 3, 3]), ("", &[1, 4, 4])],
+    },
+    Region {
+        code: "NZ",
+        calling_code: "64",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"2\d{7,9}"),
+            ("toll_free", r"80[08]\d{6,7}"),
+            ("premium_rate", r"90\d{6,7}"),
+            ("fixed_line", r"[3-9]\d{7}"),
+        ],
+        groups: &[("2", &[2, 3, 4]), ("", &[1, 3, 4])],
+    },
+    Region {
+        code: "ZA",
+        calling_code: "27",
+        trunk_prefix: Some("0"),
+        types: &[
+            ("mobile", r"(?:6\d|7[0-46-9]|8[1-4])\d{7}"),
+            ("toll_free", r"80\d{7}"),
+            ("premium_rate", r"86\d{7}"),
+            ("fixed_line", r"[1-5]\d{8}"),
+        ],
+        groups: &[("", &[2, 3, 4])],
+    },
+];
+
+// Calling codes without number patterns: recognised, length-checked only
+const OTHER_CALLING_CODES: &[(&str, &str)] = &[
+    ("7", "RU"),
+    ("20", "EG"),
+    ("30", "GR"),
+    ("32", "BE"),
+    ("36", "HU"),
+    ("40", "RO"),
+    ("43", "AT"),
+    ("45", "DK"),
+    ("47", "NO"),
+    ("51", "PE"),
+    ("54", "AR"),
+    ("56", "CL"),
+    ("57", "CO"),
+    ("58", "VE"),
+    ("60", "MY"),
+    ("62", "ID"),
+    ("63", "PH"),
+    ("66", "TH"),
+    ("82", "KR"),
+    ("84", "VN"),
+    ("90", "TR"),
+    ("92", "PK"),
+    ("98", "IR"),
+    ("212", "MA"),
+    ("234", "NG"),
+    ("254", "KE"),
+    ("351", "PT"),
+    ("358", "FI"),
+    ("420", "CZ"),
+    ("852", "HK"),
+    ("880", "BD"),
+    ("886", "TW"),
+    ("966", "SA"),
+    ("971", "AE"),
+    ("972", "IL"),
+];
+
+// Canadian area codes; the rest of +1 is reported as US
+const CANADIAN_AREA_CODES: &[&str] = &[
+    "204", "226", "236", "249", "250", "263", "289", "306", "343", "354", "365", "367", "368", "382", "403", "416",
+    "418", "428", "431", "437", "438", "450", "468", "474", "506", "514", "519", "548", "579", "581", "584", "587",
+    "604", "613", "639", "647", "672", "683", "705", "709", "742", "753", "778", "780", "782", "807", "819", "825",
+    "867", "873", "879", "902", "905",
+];
+
+// Compiled type patterns, indexed like REGIONS
+fn type_patterns() -> &'static [Vec<(&'static str, Regex)>] {
+    static PATTERNS: OnceLock<Vec<Vec<(&'static str, Regex)>>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        REGIONS
+            .iter()
+            .map(|region| {
+                region
+                    .types
+                    .iter()
+                    .map(|(kind, pattern)| {
+                        let anchored = format!("^(?:{})$", pattern);
+                        (*kind, Regex::new(&anchored).expect("valid phone number pattern"))
+                    })
+                    .collect()
+            })
+            .collect()
+    })
+}
+
+fn find_region(code: &str) -> Option<usize> {
+    REGIONS
+        .iter()
+        .position(|region| region.code.eq_ignore_ascii_case(code.trim()))
+}
+
+fn number_type(region: usize, national: &str) -> Option<&'static str> {
+    type_patterns()[region]
+        .iter()
+        .find(|(_, pattern)| pattern.is_match(national))
+        .map(|(kind, _)| *kind)
+}
+
+// Letters on a phone keypad (1-800-FLOWERS)
+fn keypad_digit(c: char) -> Option<char> {
+    let digit = match c.to_ascii_uppercase() {
+        'A'..='C' => '2',
+        'D'..='F' => '3',
+        'G'..='I' => '4',
+        'J'..='L' => '5',
+        'M'..='O' => '6',
+        'P'..='S' => '7',
+        'T'..='V' => '8',
+        'W'..='Z' => '9',
+        _ => return None,
+    };
+    Some(digit)
+}
+
+// The number part and the extension, split at "ext", "x" or "#"
+fn split_extension(text: &str) -> (&str, Option<String>) {
+    let lower = text.to_ascii_lowercase();
+    for marker in ["extension", "ext.", "ext", "x", "#"] {
+        if let Some(index) = lower.rfind(marker) {
+            let extension: String = text[index + marker.len()..]
+                .trim_start_matches([' ', '.', ':'])
+                .to_string();
+            if !extension.is_empty() && extension.chars().all(|c| c.is_ascii_digit()) && index > 0 {
+                return (&text[..index], Some(extension));
+            }
+        }
+    }
+    (text, None)
+}
+
+struct PhoneNumber {
+    calling_code: String,
+    national: String,
+    // Index into REGIONS, or the code from OTHER_CALLING_CODES
+    region: Option<usize>,
+    other_region: Option<&'static str>,
+    extension: Option<String>,
+    kind: Option<&'static str>,
+}
+
+impl PhoneNumber {
+    fn region_code(&self) -> Option<&'static str> {
+        match self.region {
+            Some(index) if REGIONS[index].calling_code == "1" => {
+                let canadian = CANADIAN_AREA_CODES.iter().any(|code| self.national.starts_with(code));
+                Some(if canadian { "CA" } else { "US" })
+            }
+            Some(index) => Some(REGIONS[index].code),
+            None => self.other_region,
+        }
+    }
+
+    fn possible(&self) -> bool {
+        let digits = self.calling_code.len() + self.national.len();
+        self.national.len() >= MIN_NATIONAL_DIGITS && digits <= MAX_E164_DIGITS
+    }
+
+    // Valid means matching one of the region's patterns; without patterns
+    // validity can't be told
+    fn valid(&self) -> Option<bool> {
+        self.region.map(|_| self.kind.is_some())
+    }
+
+    fn e164(&self) -> String {
+        format!("+{}{}", self.calling_code, self.national)
+    }
+
+    fn grouped(&self) -> String {
+        let Some(region) = self.region.map(|index| &REGIONS[index]) else {
+            return self.national.clone();
+        };
+        let Some((_, sizes)) = region
+            .groups
+            .iter()
+            .find(|(prefix, _)| self.national.starts_with(prefix))
+        else {
+            return self.national.clone();
+        };
+        let mut groups = Vec::new();
+        let mut rest = self.national.as_str();
+        for (index, size) in sizes.iter().enumerate() {
+            if rest.is_empty() {
+                break;
+            }
+            let take = if index == sizes.len() - 1 {
+                rest.len()
+            } else {
+                (*size).min(rest.len())
+            };
+            groups.push(&rest[..take]);
+            rest = &rest[take..];
+        }
+        groups.join(" ")
+    }
+
+    fn international(&self) -> String {
+        let mut text = format!("+{} {}", self.calling_code, self.grouped());
+        if let Some(extension) = &self.extension {
+            text.push_str(&format!(" ext. {}", extension));
+        }
+        text
+    }
+
+    fn national_format(&self) -> String {
+        let region = self.region.map(|index| &REGIONS[index]);
+        let mut text = if region.is_some_and(|region| region.calling_code == "1") && self.national.len() == 10 {
+            format!(
+                "({}) {}-{}",
+                &self.national[..3],
+                &self.national[3..6],
+                &self.national[6..]
+            )
+        } else {
+            let trunk = region
+                .and_then(|region| region.trunk_prefix)
+                .filter(|trunk| *trunk != "1")
+                .unwrap_or("");
+            format!("{}{}", trunk, self.grouped())
+        };
+        if let Some(extension) = &self.extension {
+            text.push_str(&format!(" ext. {}", extension));
+        }
+        text
+    }
+
+    fn to_json(&self, input: &str) -> Value {
+        json!({
+            "input": input,
+            "e164": self.possible().then(|| self.e164()),
+            "international": self.international(),
+            "national": self.national_format(),
+            "calling_code": self.calling_code,
+            "national_number": self.national,
+            "extension": self.extension,
+            "region": self.region_code(),
+            "possible": self.possible(),
+            "valid": self.valid(),
+            "type": self.kind.unwrap_or("unknown")
+        })
+    }
+}
+
+// International numbers start with + or an exit code (00, or 011 in North
+// America); anything else is read as a national number of `default_region`
+fn parse(text: &str, default_region: Option<&str>) -> Result<PhoneNumber, String> {
+    let (number, extension) = split_extension(text.trim());
+    let mut digits = String::new();
+    let mut plus = false;
+    let mut letters = 0;
+    for c in number.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            '+' if digits.is_empty() => plus = true,
+            ' ' | '-' | '.' | '(' | ')' | '/' | '\u{a0}' => {}
+            _ => match keypad_digit(c) {
+                Some(digit) if !digits.is_empty() => {
+                    digits.push(digit);
+                    letters += 1;
+                }
+                _ => return Err(format!("Unexpected '{}' in phone number", c)),
+            },
+        }
+    }
+    if digits.len() < MIN_NATIONAL_DIGITS {
+        return Err("Too few digits for a phone number".to_string());
+    }
+    if letters > 0 && plus {
+        return Err("Letters only appear in national numbers".to_string());
+    }
+
+    let default = match default_region.map(str::trim).filter(|code| !code.is_empty()) {
+        Some(code) => Some(find_region(code).ok_or_else(|| {
+            let codes: Vec<&str> = REGIONS.iter().map(|region| region.code).collect();
+            format!("Unknown region {}; supported: {}", code, codes.join(", "))
+        })?),
+        None => None,
+    };
+
+    let international = if plus {
+        Some(digits.as_str())
+    } else if let Some(rest) = digits.strip_prefix("00") {
+        Some(rest)
+    } else if default.is_some_and(|index| REGIONS[index].calling_code == "1") {
+        digits.strip_prefix("011")
+    } else {
+        None
+    };
+
+    let mut number = match international {
+        Some(international) => {
+            // Calling codes are prefix-free, so the first match is the one
+            let (calling_code, region, other_region) = (1..=3)
+                .filter_map(|length| {
+                    let code = international.get(..length)?;
+                    let region = REGIONS.iter().position(|region| region.calling_code == code);
+                    let other = OTHER_CALLING_CODES
+                        .iter()
+                        .find(|(calling_code, _)| *calling_code == code)
+                        .map(|(_, region)| *region);
+                    (region.is_some() || other.is_some()).then_some((code, region, other))
+                })
+                .next()
+                .ok_or_else(|| format!("Unknown calling code in +{}", international))?;
+            PhoneNumber {
+                calling_code: calling_code.to_string(),
+                national: international[calling_code.len()..].to_string(),
+                region,
+                other_region,
+                extension,
+                kind: None,
+            }
+        }
+        None => {
+            let index = default
+                .ok_or_else(|| "A region is needed for numbers without a + or international prefix".to_string())?;
+            let region = &REGIONS[index];
+            let mut national = digits.as_str();
+            // The trunk prefix is dropped, and so is a calling code typed
+            // without its + ("44 7911 123456" with region GB)
+            if let Some(rest) = region.trunk_prefix.and_then(|trunk| national.strip_prefix(trunk)) {
+                if region.calling_code != "1" || national.len() == 11 {
+                    national = rest;
+                }
+            } else if let Some(rest) = national.strip_prefix(region.calling_code) {
+                if number_type(index, national).is_none() && number_type(index, rest).is_some() {
+                    national = rest;
+                }
+            }
+            PhoneNumber {
+                calling_code: region.calling_code.to_string(),
+                national: national.to_string(),
+                region: Some(index),
+                other_region: None,
+                extension,
+                kind: None,
+            }
+        }
+    };
+
+    if let Some(index) = number.region {
+        number.kind = number_type(index, &number.national);
+    }
+    Ok(number)
+}
+
+// Parses one phone number. `default_region` (an ISO country code such as
+// "GB") is how numbers without + or an exit code are read.
+#[tauri::command]
+pub fn parse_phone_number(number: String, default_region: Option<String>) -> Result<Value, String> {
+    let parsed = parse(&number, default_region.as_deref())?;
+    Ok(parsed.to_json(&number))
+}
+
+// Runs of digits with the usual separators, optionally led by + or (
+fn candidate_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"[+(]?\d[\d \-.()]{5,}\d").expect("valid phone candidate pattern"))
+}
+
+fn date_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^(?:\d{4}[-/.]\d{1,2}[-/.]\d{1,2}|\d{1,2}[-/.]\d{1,2}[-/.]\d{2,4})\b").expect("valid date pattern")
+    })
+}
+
+// Valid phone numbers in `text` with their byte ranges. Runs glued to letters
+// or continuing as a longer dotted/dashed number (versions, IPs, IDs) and
+// date-shaped runs are skipped.
+fn find_numbers(text: &str, default_region: Option<&str>) -> Vec<(usize, usize, PhoneNumber)> {
+    let mut found = Vec::new();
+    for candidate in candidate_pattern().find_iter(text) {
+        let mut before = text[..candidate.start()].chars().rev();
+        let mut after = text[candidate.end()..].chars();
+        let glued = |next: Option<char>, beyond: Option<char>| match next {
+            Some(c) if c.is_alphanumeric() || c == '_' => true,
+            Some('.' | '-') => beyond.is_some_and(|c| c.is_ascii_digit()),
+            _ => false,
+        };
+        if glued(before.next(), before.next()) || glued(after.next(), after.next()) {
+            continue;
+        }
+        let token = candidate.as_str();
+        if date_pattern().is_match(token.trim_start_matches(['(', '+'])) {
+            continue;
+        }
+        if let Ok(number) = parse(token, default_region) {
+            if number.valid() == Some(true) {
+                found.push((candidate.start(), candidate.end(), number));
+            }
+        }
+    }
+    found
+}
+
+// Every valid phone number in `text` (or the active document's raw content),
+// normalized to E.164. With `apply` the numbers are replaced in the stored
+// content as an undoable edit.
+#[tauri::command]
+pub async fn normalize_phone_numbers(
+    app: AppHandle,
+    text: Option<String>,
+    default_region: Option<String>,
+    apply: Option<bool>,
+) -> Result<Value, String> {
+    crate::run_blocking("normalize_phone_numbers", move || {
+        let apply = apply.unwrap_or(false);
+        let from_document = text.as_deref().is_none_or(str::is_empty);
+        if apply && !from_document {
+            return Err("Only the stored content can be normalized in place".to_string());
+        }
+        let text = text.filter(|text| !text.is_empty());
+        let snapshot = match text {
+            Some(_) => None,
+            None => Some(
+                app.state::<AppState>()
+                    .inner()
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .snapshot_raw()?,
+            ),
+        };
+        let content = snapshot
+            .as_ref()
+            .map_or(text.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+
+        let numbers = find_numbers(content, default_region.as_deref());
+        let mut normalized = String::with_capacity(content.len());
+        let mut last = 0;
+        let mut matches = Vec::new();
+        for (start, end, number) in &numbers {
+            normalized.push_str(&content[last..*start]);
+            normalized.push_str(&number.e164());
+            last = *end;
+            let mut entry = number.to_json(&content[*start..*end]);
+            entry["offset"] = (*start).into();
+            matches.push(entry);
+        }
+        normalized.push_str(&content[last..]);
+
+        let applied = apply && !numbers.is_empty();
+        // `apply` is only accepted for stored content, so there is always a snapshot here
+        if let (true, Some(snapshot)) = (applied, &snapshot) {
+            let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            let document = storage.snapshot_document(snapshot)?;
+            document.edit(Slot::Raw, normalized.clone());
+            document.formatted_content = None;
+        }
+        Ok(json!({
+            "content": normalized,
+            "count": matches.len(),
+            "numbers": matches,
+            "applied": applied
+        }))
+    })
+    .await
+}
@@ -0,0 +1,192 @@
+// WebAssembly formatter plugins.
+//
+// Each `*.wasm` file in the plugins directory becomes a formatter. A module must export:
+//   memory                                  - its linear memory
+//   alloc(len: i32) -> i32                  - reserve `len` bytes for the input
+//   transform(ptr: i32, len: i32) -> i64    - returns (out_ptr << 32) | out_len
+// Output is UTF-8; output starting with "error:" is reported as a formatting error.
+// An optional `<name>.json` next to the module supplies `id`, `display_name` and `output_kind`.
+// Ids that match an already registered formatter are rejected rather than replacing it.
+//
+// Plugins are untrusted: each call gets a memory cap and a wall-clock deadline enforced
+// through epoch interruption, so a runaway module traps instead of hanging the worker.
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+use wasmtime::{Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+
+use crate::formatters::{Formatter, FormatterRegistry};
+
+// Largest linear memory a plugin may grow to
+const MAX_PLUGIN_MEMORY: usize = 1024 * 1024 * 1024;
+// The engine epoch advances once per tick; a call traps after PLUGIN_DEADLINE_TICKS of them
+const EPOCH_TICK: Duration = Duration::from_millis(100);
+const PLUGIN_DEADLINE_TICKS: u64 = 300;
+
+#[derive(Deserialize, Default)]
+struct PluginManifest {
+    id: Option<String>,
+    display_name: Option<String>,
+    output_kind: Option<String>,
+}
+
+pub struct WasmFormatter {
+    // Leaked once at load time so the Formatter trait can hand out &'static str
+    id: &'static str,
+    display_name: &'static str,
+    output_kind: &'static str,
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmFormatter {
+    fn load(engine: &Engine, path: &Path) -> Result<Self, String> {
+        let module = Module::from_file(engine, path)
+            .map_err(|e| format!("Failed to compile plugin {}: {}", path.display(), e))?;
+
+        let manifest: PluginManifest = std::fs::read_to_string(path.with_extension("json"))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "plugin".to_string());
+
+        let id = manifest.id.unwrap_or_else(|| format!("plugin:{}", stem));
+        let display_name = manifest.display_name.unwrap_or_else(|| stem.clone());
+        let output_kind = manifest.output_kind.unwrap_or_else(|| "text".to_string());
+
+        Ok(WasmFormatter {
+            id: Box::leak(id.into_boxed_str()),
+            display_name: Box::leak(display_name.into_boxed_str()),
+            output_kind: Box::leak(output_kind.into_boxed_str()),
+            engine: engine.clone(),
+            module,
+        })
+    }
+
+    fn run(&self, input: &str) -> Result<String, String> {
+        // A fresh store per call keeps plugins stateless and isolated from each other
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_PLUGIN_MEMORY).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_epoch_deadline(PLUGIN_DEADLINE_TICKS);
+        let linker: Linker<StoreLimits> = Linker::new(&self.engine);
+        let instance: Instance = linker
+            .instantiate(&mut store, &self.module)
+            .map_err(|e| call_error("Failed to instantiate plugin", e))?;
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| "Plugin does not export 'memory'".to_string())?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|e| format!("Plugin does not export 'alloc': {}", e))?;
+        let transform = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "transform")
+            .map_err(|e| format!("Plugin does not export 'transform': {}", e))?;
+
+        let input_len = i32::try_from(input.len()).map_err(|_| "Input too large for plugin".to_string())?;
+        let input_ptr = alloc
+            .call(&mut store, input_len)
+            .map_err(|e| call_error("Plugin alloc failed", e))?;
+        memory
+            .write(&mut store, input_ptr as usize, input.as_bytes())
+            .map_err(|e| format!("Failed to write plugin input: {}", e))?;
+
+        let packed = transform
+            .call(&mut store, (input_ptr, input_len))
+            .map_err(|e| call_error("Plugin transform failed", e))?;
+        let out_ptr = (packed as u64 >> 32) as usize;
+        let out_len = (packed as u64 & 0xffff_ffff) as usize;
+
+        let mut output = vec![0u8; out_len];
+        memory
+            .read(&store, out_ptr, &mut output)
+            .map_err(|e| format!("Failed to read plugin output: {}", e))?;
+        let output = String::from_utf8(output).map_err(|e| format!("Plugin returned invalid UTF-8: {}", e))?;
+
+        match output.strip_prefix("error:") {
+            Some(message) => Err(message.trim().to_string()),
+            None => Ok(output),
+        }
+    }
+}
+
+fn call_error(context: &str, error: wasmtime::Error) -> String {
+    if error.downcast_ref::<Trap>() == Some(&Trap::Interrupt) {
+        let seconds = (EPOCH_TICK * PLUGIN_DEADLINE_TICKS as u32).as_secs();
+        return format!("{}: plugin did not finish within {} seconds", context, seconds);
+    }
+    format!("{}: {}", context, error)
+}
+
+impl Formatter for WasmFormatter {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn display_name(&self) -> &'static str {
+        self.display_name
+    }
+
+    fn output_kind(&self) -> &'static str {
+        self.output_kind
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        self.run(input)
+    }
+}
+
+// Returns the number of plugins loaded; broken plugins are logged and skipped
+// so one bad module can't prevent the app from starting.
+pub fn load_plugins(registry: &mut FormatterRegistry, dir: &Path) -> usize {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = match Engine::new(&config) {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("Failed to create plugin engine: {}", e);
+            return 0;
+        }
+    };
+    let mut loaded = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("wasm") {
+            continue;
+        }
+        match WasmFormatter::load(&engine, &path) {
+            Ok(formatter) if registry.get(formatter.id()).is_some() => {
+                eprintln!(
+                    "Skipping plugin {}: id '{}' is already used by another formatter",
+                    path.display(),
+                    formatter.id()
+                );
+            }
+            Ok(formatter) => {
+                registry.register(Box::new(formatter));
+                loaded += 1;
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    if loaded > 0 {
+        std::thread::Builder::new()
+            .name("plugin-epoch".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(EPOCH_TICK);
+                engine.increment_epoch();
+            })
+            .ok();
+    }
+    loaded
+}
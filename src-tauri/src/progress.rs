@@ -0,0 +1,80 @@
+use tauri::{AppHandle, Emitter};
+
+pub const FORMAT_PROGRESS_EVENT: &str = "format://progress";
+pub const FILE_READ_PROGRESS_EVENT: &str = "file://read-progress";
+pub const FILE_WRITE_PROGRESS_EVENT: &str = "file://write-progress";
+pub const SEARCH_PROGRESS_EVENT: &str = "search://progress";
+pub const URL_LOAD_PROGRESS_EVENT: &str = "url://load-progress";
+
+// Emits `{ ...context, processed_bytes, total_bytes, percent }` each time the
+// whole-number percentage changes, so huge inputs don't flood the IPC channel
+pub struct ProgressReporter {
+    app: AppHandle,
+    event: &'static str,
+    context: serde_json::Value,
+    total_bytes: u64,
+    last_percent: Option<u64>,
+}
+
+impl ProgressReporter {
+    pub fn new(app: &AppHandle, event: &'static str, total_bytes: u64, context: serde_json::Value) -> Self {
+        ProgressReporter {
+            app: app.clone(),
+            event,
+            context,
+            total_bytes,
+            last_percent: None,
+        }
+    }
+
+    pub fn update(&mut self, processed_bytes: u64) {
+        let percent = (processed_bytes.min(self.total_bytes) * 100)
+            .checked_div(self.total_bytes)
+            .unwrap_or(100);
+        if self.last_percent == Some(percent) {
+            return;
+        }
+        self.last_percent = Some(percent);
+
+        let mut payload = self.context.clone();
+        if let Some(fields) = payload.as_object_mut() {
+            fields.insert("processed_bytes".to_string(), processed_bytes.into());
+            fields.insert("total_bytes".to_string(), self.total_bytes.into());
+            fields.insert("percent".to_string(), percent.into());
+        }
+        let _ = self.app.emit(self.event, payload);
+    }
+}
+
+// Counts the bytes read through it, reporting them as progress when given a
+// reporter; for inputs whose size is only known before a decoding step
+pub struct ProgressReader<'a, R> {
+    inner: R,
+    reporter: Option<&'a mut ProgressReporter>,
+    read: u64,
+}
+
+impl<'a, R: std::io::Read> ProgressReader<'a, R> {
+    pub fn new(inner: R, reporter: Option<&'a mut ProgressReporter>) -> Self {
+        ProgressReader {
+            inner,
+            reporter,
+            read: 0,
+        }
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.read
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        if let Some(reporter) = self.reporter.as_mut() {
+            reporter.update(self.read);
+        }
+        Ok(n)
+    }
+}
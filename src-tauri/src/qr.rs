@@ -0,0 +1,112 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use qrcode::render::svg;
+use qrcode::{EcLevel, QrCode};
+use std::io::Cursor;
+
+fn parse_ec_level(level: Option<&str>) -> Result<EcLevel, String> {
+    match level.unwrap_or("M").to_uppercase().as_str() {
+        "L" => Ok(EcLevel::L),
+        "M" => Ok(EcLevel::M),
+        "Q" => Ok(EcLevel::Q),
+        "H" => Ok(EcLevel::H),
+        other => Err(format!("Unknown error correction level: {}", other)),
+    }
+}
+
+fn generate_qr_code_blocking(
+    text: String,
+    format: String,
+    size: Option<u32>,
+    error_correction: Option<String>,
+) -> Result<serde_json::Value, String> {
+    if text.is_empty() {
+        return Err("Empty QR code content".to_string());
+    }
+
+    let level = parse_ec_level(error_correction.as_deref())?;
+    let code = QrCode::with_error_correction_level(text.as_bytes(), level)
+        .map_err(|e| format!("Failed to encode QR code: {}", e))?;
+    let size = size.unwrap_or(256);
+
+    let (mime, data) = match format.as_str() {
+        "svg" => {
+            let image = code
+                .render::<svg::Color>()
+                .min_dimensions(size, size)
+                .build();
+            ("image/svg+xml", image.into_bytes())
+        }
+        "png" => {
+            let image = code
+                .render::<image::Luma<u8>>()
+                .min_dimensions(size, size)
+                .build();
+            let mut bytes = Vec::new();
+            image::DynamicImage::ImageLuma8(image)
+                .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+                .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+            ("image/png", bytes)
+        }
+        _ => return Err(format!("Unknown QR code output format: {}", format)),
+    };
+
+    Ok(serde_json::json!({
+        "format": format,
+        "mime": mime,
+        "version": format!("{:?}", code.version()),
+        "modules": code.width(),
+        "base64": STANDARD.encode(&data)
+    }))
+}
+
+#[tauri::command]
+pub async fn generate_qr_code(
+    text: String,
+    format: String,
+    size: Option<u32>,
+    error_correction: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("generate_qr_code", move || {
+        generate_qr_code_blocking(text, format, size, error_correction)
+    })
+    .await
+}
+
+fn decode_qr_code_blocking(file_path: String) -> Result<serde_json::Value, String> {
+    let image = image::open(&file_path)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .to_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32).0[0],
+    );
+
+    let mut codes = Vec::new();
+    for grid in prepared.detect_grids() {
+        let (meta, content) = grid
+            .decode()
+            .map_err(|e| format!("Failed to decode QR code: {}", e))?;
+        codes.push(serde_json::json!({
+            "content": content,
+            "version": meta.version.0,
+            "ecc_level": meta.ecc_level
+        }));
+    }
+
+    if codes.is_empty() {
+        return Err("No QR code found in image".to_string());
+    }
+
+    Ok(serde_json::json!({
+        "count": codes.len(),
+        "codes": codes
+    }))
+}
+
+#[tauri::command]
+pub async fn decode_qr_code(file_path: String) -> Result<serde_json::Value, String> {
+    crate::run_blocking("decode_qr_code", move || decode_qr_code_blocking(file_path)).await
+}
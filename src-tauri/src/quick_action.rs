@@ -0,0 +1,140 @@
+// Global shortcut that opens a small always-on-top window with whatever is on
+// the clipboard and a guess at its format. The window loads the regular
+// frontend with a `#quick` route and reads its payload with get_quick_action_payload.
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::formatters::{autodetect, FormatterRegistry};
+use crate::text_buffer;
+
+pub const QUICK_WINDOW_LABEL: &str = "quick-action";
+pub const QUICK_ACTION_OPENED_EVENT: &str = "quick-action://opened";
+pub const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+
+pub struct QuickAction {
+    shortcut: Mutex<String>,
+    // Clipboard snapshot taken when the window was last opened
+    payload: Mutex<serde_json::Value>,
+}
+
+impl Default for QuickAction {
+    fn default() -> Self {
+        QuickAction {
+            shortcut: Mutex::new(DEFAULT_SHORTCUT.to_string()),
+            payload: Mutex::new(serde_json::Value::Null),
+        }
+    }
+}
+
+fn parse_shortcut(shortcut: &str) -> Result<Shortcut, String> {
+    shortcut
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut {}: {}", shortcut, e))
+}
+
+// Plugin setup for run(); any registered shortcut opens the quick window
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                if let Err(e) = open_quick_window(app) {
+                    eprintln!("Failed to open quick action window: {}", e);
+                }
+            }
+        })
+        .build()
+}
+
+// Registers the configured shortcut; called once from setup
+pub fn register(app: &AppHandle) -> Result<(), String> {
+    let shortcut = app.state::<QuickAction>().shortcut.lock().map_err(|e| e.to_string())?.clone();
+    app.global_shortcut()
+        .register(parse_shortcut(&shortcut)?)
+        .map_err(|e| format!("Failed to register {}: {}", shortcut, e))
+}
+
+fn clipboard_payload(app: &AppHandle) -> serde_json::Value {
+    let text = app.clipboard().read_text().unwrap_or_default();
+    let sample_end = text_buffer::grapheme_floor(&text, autodetect::SAMPLE_BYTES);
+    let complete = sample_end == text.len();
+    let registry = app.state::<FormatterRegistry>();
+    let guesses = autodetect::guess_formats(&text[..sample_end], complete, &registry);
+    serde_json::json!({
+        "length": text.len(),
+        "text": text,
+        "suggestion": guesses.first().map(|g| g.to_json()),
+        "candidates": guesses.iter().map(|g| g.to_json()).collect::<Vec<_>>()
+    })
+}
+
+pub fn open_quick_window(app: &AppHandle) -> Result<(), String> {
+    let payload = clipboard_payload(app);
+    *app.state::<QuickAction>().payload.lock().map_err(|e| e.to_string())? = payload.clone();
+
+    match app.get_webview_window(QUICK_WINDOW_LABEL) {
+        Some(window) => {
+            window.show().map_err(|e| e.to_string())?;
+            window.set_focus().map_err(|e| e.to_string())?;
+            // An already loaded window won't ask again, so push the new snapshot
+            window
+                .emit(QUICK_ACTION_OPENED_EVENT, payload)
+                .map_err(|e| e.to_string())?;
+        }
+        None => {
+            WebviewWindowBuilder::new(app, QUICK_WINDOW_LABEL, WebviewUrl::App("index.html#quick".into()))
+                .title("devmate")
+                .inner_size(520.0, 360.0)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .center()
+                .build()
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_quick_action_payload(quick: State<QuickAction>) -> Result<serde_json::Value, String> {
+    Ok(quick.payload.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub fn get_quick_action_shortcut(quick: State<QuickAction>) -> Result<String, String> {
+    Ok(quick.shortcut.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// Swaps the shortcut; the old one stays registered if the new one is rejected
+#[tauri::command]
+pub fn set_quick_action_shortcut(app: AppHandle, shortcut: String) -> Result<String, String> {
+    let new_shortcut = parse_shortcut(&shortcut)?;
+    let quick = app.state::<QuickAction>();
+    let mut current = quick.shortcut.lock().map_err(|e| e.to_string())?;
+    let old_shortcut = parse_shortcut(&current)?;
+    if new_shortcut == old_shortcut {
+        return Ok(current.clone());
+    }
+
+    let global = app.global_shortcut();
+    global
+        .register(new_shortcut)
+        .map_err(|e| format!("Failed to register {}: {}", shortcut, e))?;
+    let _ = global.unregister(old_shortcut);
+    *current = shortcut;
+    Ok(current.clone())
+}
+
+#[tauri::command]
+pub fn open_quick_action(app: AppHandle) -> Result<(), String> {
+    open_quick_window(&app)
+}
+
+#[tauri::command]
+pub fn close_quick_action(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_WINDOW_LABEL) {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
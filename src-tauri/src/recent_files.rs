@@ -0,0 +1,159 @@
+// Recently opened files, kept in `recent_files.json`. Pinned entries stay at
+// the top and are never dropped; the rest are capped at MAX_UNPINNED, newest
+// first. Files opened through the backend are recorded automatically; files the
+// frontend reads itself are added with add_recent_file.
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_data;
+use crate::jobs::CancelToken;
+use crate::open_files;
+use crate::responses::OpenedFile;
+
+const RECENT_FILES_FILE: &str = "recent_files.json";
+const MAX_UNPINNED: usize = 30;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RecentFile {
+    pub path: String,
+    pub size: u64,
+    pub detected_format: Option<String>,
+    // RFC 3339
+    pub last_opened: String,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Default)]
+pub struct RecentFiles(Mutex<Vec<RecentFile>>);
+
+fn save(app: &AppHandle, entries: &[RecentFile]) -> Result<(), String> {
+    app_data::save(app, RECENT_FILES_FILE, entries, "recent files")
+}
+
+// Pinned first, then most recently opened; unpinned entries past the cap are dropped
+fn normalize(entries: &mut Vec<RecentFile>) {
+    entries.sort_by(|a, b| b.pinned.cmp(&a.pinned).then_with(|| b.last_opened.cmp(&a.last_opened)));
+    let mut unpinned = 0;
+    entries.retain(|entry| {
+        if entry.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= MAX_UNPINNED
+    });
+}
+
+// Applies `change` to the list and saves it
+fn update<T>(app: &AppHandle, change: impl FnOnce(&mut Vec<RecentFile>) -> T) -> Result<T, String> {
+    let recent = app.state::<RecentFiles>();
+    let mut entries = recent.0.lock().map_err(|e| e.to_string())?;
+    let result = change(&mut entries);
+    normalize(&mut entries);
+    save(app, &entries)?;
+    Ok(result)
+}
+
+// Canonical form so the same file opened two ways is listed once
+fn entry_path(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Best effort: failing to remember a file must never fail opening it
+pub fn record(app: &AppHandle, path: &Path, size: u64, detected_format: Option<String>) {
+    if app.try_state::<RecentFiles>().is_none() {
+        return;
+    }
+    let path = entry_path(path);
+    let outcome = update(app, |entries| {
+        let pinned = entries.iter().any(|entry| entry.path == path && entry.pinned);
+        entries.retain(|entry| entry.path != path);
+        entries.push(RecentFile {
+            path,
+            size,
+            detected_format,
+            last_opened: chrono::Utc::now().to_rfc3339(),
+            pinned,
+        });
+    });
+    if let Err(e) = outcome {
+        eprintln!("{}", e);
+    }
+}
+
+// Called from setup; a missing or unreadable file starts an empty list
+pub fn load(app: &AppHandle) {
+    let mut entries: Vec<RecentFile> = app_data::load(app, RECENT_FILES_FILE)
+        .unwrap_or_default();
+    normalize(&mut entries);
+    app.manage(RecentFiles(Mutex::new(entries)));
+}
+
+// Entries include an `exists` flag so the UI can grey out moved or deleted files
+#[tauri::command]
+pub fn list_recent_files(recent: State<RecentFiles>) -> Result<Vec<serde_json::Value>, String> {
+    let entries = recent.0.lock().map_err(|e| e.to_string())?;
+    Ok(entries
+        .iter()
+        .map(|entry| {
+            let mut value = serde_json::to_value(entry).unwrap_or_default();
+            value["exists"] = Path::new(&entry.path).is_file().into();
+            value
+        })
+        .collect())
+}
+
+// For files the frontend read itself (below the large-file threshold)
+#[tauri::command]
+pub fn add_recent_file(app: AppHandle, path: String, detected_format: Option<String>) -> Result<(), String> {
+    let metadata = std::fs::metadata(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    record(&app, Path::new(&path), metadata.len(), detected_format);
+    Ok(())
+}
+
+// Returns false when the path isn't in the list
+#[tauri::command]
+pub fn pin_recent_file(app: AppHandle, path: String, pinned: bool) -> Result<bool, String> {
+    update(&app, |entries| match entries.iter_mut().find(|entry| entry.path == path) {
+        Some(entry) => {
+            entry.pinned = pinned;
+            true
+        }
+        None => false,
+    })
+}
+
+#[tauri::command]
+pub fn remove_recent_file(app: AppHandle, path: String) -> Result<bool, String> {
+    update(&app, |entries| {
+        let before = entries.len();
+        entries.retain(|entry| entry.path != path);
+        entries.len() != before
+    })
+}
+
+// Pinned entries survive unless `include_pinned` is set
+#[tauri::command]
+pub fn clear_recent_files(app: AppHandle, include_pinned: Option<bool>) -> Result<usize, String> {
+    let include_pinned = include_pinned.unwrap_or(false);
+    update(&app, |entries| {
+        let before = entries.len();
+        entries.retain(|entry| entry.pinned && !include_pinned);
+        before - entries.len()
+    })
+}
+
+// Opens the entry in a new document through the same loader as OS-opened
+// files, so large files are streamed or memory-mapped
+#[tauri::command]
+pub async fn reopen_recent_file(app: AppHandle, path: String) -> Result<OpenedFile, String> {
+    crate::run_blocking("reopen_recent_file", move || {
+        open_files::open_in_new_document(&app, &CancelToken::default(), Path::new(&path), true)
+    })
+    .await
+}
@@ -0,0 +1,299 @@
+// Per-record work on line-based content (NDJSON, logs, CSV), spread across
+// cores with rayon. Results are always collected in input order, so output
+// doesn't depend on how the work was scheduled. Each pass works on a snapshot
+// of the content, so other commands aren't held up while it runs.
+use rayon::prelude::*;
+use regex::RegexBuilder;
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager};
+
+use crate::columns;
+use crate::undo::Slot;
+use crate::AppState;
+
+const DEFAULT_MAX_ERRORS: usize = 100;
+// Rows profiled per task; small enough to balance, large enough to amortise merging
+const PROFILE_CHUNK_ROWS: usize = 4096;
+// Distinct values are tracked up to this many per column
+const MAX_DISTINCT: usize = 10_000;
+
+fn validate_ndjson_blocking(max_errors: Option<usize>, state: &AppState) -> Result<serde_json::Value, String> {
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let lines: Vec<&str> = snapshot.text.lines().collect();
+
+    // None for blank lines, Some(Ok) for valid records, Some(Err) with the error otherwise
+    let outcomes: Vec<Option<Result<(), serde_json::Error>>> = lines
+        .par_iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                None
+            } else {
+                Some(serde_json::from_str::<serde::de::IgnoredAny>(line).map(|_| ()))
+            }
+        })
+        .collect();
+
+    let max_errors = max_errors.unwrap_or(DEFAULT_MAX_ERRORS);
+    let mut records = 0;
+    let mut invalid = 0;
+    let mut errors = Vec::new();
+    for (index, outcome) in outcomes.into_iter().enumerate() {
+        match outcome {
+            None => {}
+            Some(Ok(())) => records += 1,
+            Some(Err(e)) => {
+                records += 1;
+                invalid += 1;
+                if errors.len() < max_errors {
+                    errors.push(serde_json::json!({
+                        "line": index + 1,
+                        "column": e.column(),
+                        "message": e.to_string()
+                    }));
+                }
+            }
+        }
+    }
+
+    Ok(serde_json::json!({
+        "total_lines": lines.len(),
+        "record_count": records,
+        "valid_count": records - invalid,
+        "invalid_count": invalid,
+        "blank_lines": lines.len() - records,
+        "errors": errors,
+        "errors_truncated": invalid > errors.len()
+    }))
+}
+
+#[tauri::command]
+pub async fn validate_ndjson(app: AppHandle, max_errors: Option<usize>) -> Result<serde_json::Value, String> {
+    crate::run_blocking("validate_ndjson", move || validate_ndjson_blocking(max_errors, app.state::<AppState>().inner())).await
+}
+
+// grep-style filter of the raw content into the formatted slot
+fn filter_lines_blocking(
+    pattern: String,
+    is_regex: bool,
+    case_sensitive: bool,
+    invert: bool,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    if pattern.is_empty() {
+        return Err("Empty filter pattern".to_string());
+    }
+    let pattern = if is_regex { pattern } else { regex::escape(&pattern) };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid regex: {}", e))?;
+
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let lines: Vec<&str> = snapshot.text.lines().collect();
+    let kept: Vec<&str> = lines
+        .par_iter()
+        .filter(|line| regex.is_match(line) != invert)
+        .copied()
+        .collect();
+
+    let total_lines = lines.len();
+    let matched_lines = kept.len();
+    let result = kept.join("\n");
+    let formatted_length = result.len();
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.snapshot_document(&snapshot)?.edit(Slot::Formatted, result);
+
+    Ok(serde_json::json!({
+        "total_lines": total_lines,
+        "matched_lines": matched_lines,
+        "formatted_length": formatted_length
+    }))
+}
+
+#[tauri::command]
+pub async fn filter_lines(
+    app: AppHandle,
+    pattern: String,
+    is_regex: bool,
+    case_sensitive: bool,
+    invert: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("filter_lines", move || {
+        filter_lines_blocking(
+            pattern,
+            is_regex,
+            case_sensitive,
+            invert.unwrap_or(false),
+            app.state::<AppState>().inner(),
+        )
+    })
+    .await
+}
+
+#[derive(Default)]
+struct ColumnProfile {
+    filled: usize,
+    empty: usize,
+    integers: usize,
+    numbers: usize,
+    booleans: usize,
+    min: Option<f64>,
+    max: Option<f64>,
+    max_width: usize,
+    distinct: HashSet<String>,
+    distinct_capped: bool,
+}
+
+impl ColumnProfile {
+    fn add(&mut self, field: &str) {
+        let value = field.trim_matches('"');
+        if value.is_empty() {
+            self.empty += 1;
+            return;
+        }
+        self.filled += 1;
+        self.max_width = self.max_width.max(value.chars().count());
+        if value.parse::<i64>().is_ok() {
+            self.integers += 1;
+        }
+        if let Ok(number) = value.parse::<f64>() {
+            self.numbers += 1;
+            self.min = Some(self.min.map_or(number, |m| m.min(number)));
+            self.max = Some(self.max.map_or(number, |m| m.max(number)));
+        }
+        if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+            self.booleans += 1;
+        }
+        if !self.distinct_capped {
+            self.distinct.insert(value.to_string());
+            self.distinct_capped = self.distinct.len() >= MAX_DISTINCT;
+        }
+    }
+
+    // Every statistic is order-independent, so merging chunk profiles gives the
+    // same result however rayon split the rows
+    fn merge(&mut self, other: ColumnProfile) {
+        self.filled += other.filled;
+        self.empty += other.empty;
+        self.integers += other.integers;
+        self.numbers += other.numbers;
+        self.booleans += other.booleans;
+        self.min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.max_width = self.max_width.max(other.max_width);
+        for value in other.distinct {
+            if self.distinct.len() >= MAX_DISTINCT {
+                break;
+            }
+            self.distinct.insert(value);
+        }
+        self.distinct_capped |= other.distinct_capped || self.distinct.len() >= MAX_DISTINCT;
+    }
+
+    fn inferred_type(&self) -> &'static str {
+        match self.filled {
+            0 => "empty",
+            n if self.integers == n => "integer",
+            n if self.numbers == n => "number",
+            n if self.booleans == n => "boolean",
+            _ => "text",
+        }
+    }
+
+    fn to_json(&self, name: String) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "type": self.inferred_type(),
+            "filled": self.filled,
+            "empty": self.empty,
+            "distinct": self.distinct.len(),
+            "distinct_capped": self.distinct_capped,
+            "min": self.min,
+            "max": self.max,
+            "max_width": self.max_width
+        })
+    }
+}
+
+fn merge_profiles(mut left: Vec<ColumnProfile>, right: Vec<ColumnProfile>) -> Vec<ColumnProfile> {
+    for (i, profile) in right.into_iter().enumerate() {
+        match left.get_mut(i) {
+            Some(existing) => existing.merge(profile),
+            None => left.push(profile),
+        }
+    }
+    left
+}
+
+fn profile_csv_blocking(
+    delimiter: Option<String>,
+    has_header: bool,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let content = &snapshot.text;
+    let delimiter = match delimiter.as_deref() {
+        None | Some("") | Some("auto") => columns::detect_delimiter(content),
+        Some("\\t") | Some("tab") => '\t',
+        Some(d) => d.chars().next().unwrap_or(','),
+    };
+
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    let (header, rows) = match (has_header, lines.split_first()) {
+        (true, Some((first, rest))) => (columns::split_fields(first, delimiter), rest),
+        _ => (Vec::new(), &lines[..]),
+    };
+
+    let profiles = rows
+        .par_chunks(PROFILE_CHUNK_ROWS)
+        .map(|chunk| {
+            let mut profiles: Vec<ColumnProfile> = Vec::new();
+            for row in chunk {
+                for (i, field) in columns::split_fields(row, delimiter).iter().enumerate() {
+                    if profiles.len() <= i {
+                        profiles.resize_with(i + 1, ColumnProfile::default);
+                    }
+                    profiles[i].add(field);
+                }
+            }
+            profiles
+        })
+        .reduce(Vec::new, merge_profiles);
+
+    let column_count = profiles.len().max(header.len());
+    let empty = ColumnProfile::default();
+    let columns: Vec<serde_json::Value> = (0..column_count)
+        .map(|i| {
+            let name = header
+                .get(i)
+                .map(|h| h.trim_matches('"').to_string())
+                .unwrap_or_else(|| format!("column_{}", i + 1));
+            profiles.get(i).unwrap_or(&empty).to_json(name)
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "delimiter": delimiter.to_string(),
+        "row_count": rows.len(),
+        "column_count": column_count,
+        "columns": columns
+    }))
+}
+
+#[tauri::command]
+pub async fn profile_csv(
+    app: AppHandle,
+    delimiter: Option<String>,
+    has_header: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("profile_csv", move || {
+        profile_csv_blocking(delimiter, has_header.unwrap_or(true), app.state::<AppState>().inner())
+    })
+    .await
+}
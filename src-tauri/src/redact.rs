@@ -0,0 +1,269 @@
+// Masks personal data and credentials before content is shared: emails, card
+// numbers (Luhn-checked), bearer tokens, JWTs, AWS keys, API tokens and private
+// key blocks, plus any field names or JSON paths the caller lists. JSON content
+// is redacted value by value and pretty-printed again; anything else is
+// redacted as text, where `name: value` / `name=value` pairs stand in for fields.
+use regex::{Captures, Regex, RegexBuilder};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+use crate::json_path;
+use crate::undo::Slot;
+use crate::AppState;
+
+// Each pattern redacts its `secret` group when it has one (keeping the label
+// around it, e.g. "Bearer "), else the whole match
+fn patterns() -> &'static [(&'static str, Regex)] {
+    static PATTERNS: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (
+                "private-key",
+                r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+            ),
+            ("jwt", r"\beyJ[A-Za-z0-9_-]{5,}\.eyJ[A-Za-z0-9_-]{5,}\.[A-Za-z0-9_-]*"),
+            ("bearer-token", r"(?i)\bbearer\s+(?P<secret>[A-Za-z0-9._~+/-]{16,}=*)"),
+            ("aws-access-key", r"\b(?:AKIA|ASIA)[A-Z0-9]{16}\b"),
+            (
+                "aws-secret-key",
+                r#"(?i)aws_?secret_?access_?key["']?\s*[:=]\s*["']?(?P<secret>[A-Za-z0-9/+=]{40})"#,
+            ),
+            (
+                "api-token",
+                r"\b(?:ghp|gho|ghu|ghs|ghr)_[A-Za-z0-9]{36}\b|\bgithub_pat_[A-Za-z0-9_]{40,}|\bxox[abprs]-[A-Za-z0-9-]{10,}|\bsk-[A-Za-z0-9_-]{20,}",
+            ),
+            ("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b"),
+            // Card numbers start with 2-6 (Mastercard, Visa, Amex, Discover, ...)
+            ("credit-card", r"\b[2-6](?:[ -]?\d){14,18}\b"),
+        ]
+        .into_iter()
+        .map(|(kind, pattern)| (kind, Regex::new(pattern).expect("valid redaction pattern")))
+        .collect()
+    })
+}
+
+pub const KINDS: &[&str] = &[
+    "private-key",
+    "jwt",
+    "bearer-token",
+    "aws-access-key",
+    "aws-secret-key",
+    "api-token",
+    "email",
+    "credit-card",
+];
+
+fn luhn_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    (15..=19).contains(&digits.len()) && crate::validators::luhn_valid(&digits)
+}
+
+fn placeholder(kind: &str) -> String {
+    format!("[REDACTED:{}]", kind)
+}
+
+struct Redactor {
+    kinds: Vec<&'static str>,
+    // Case-insensitive field names, e.g. "password" or "ssn"
+    fields: Vec<String>,
+    // `name: value` / `name=value` in plain text, for the listed fields
+    field_pattern: Option<Regex>,
+    counts: BTreeMap<String, usize>,
+}
+
+impl Redactor {
+    fn new(kinds: Vec<&'static str>, fields: Vec<String>) -> Result<Self, String> {
+        let field_pattern = if fields.is_empty() {
+            None
+        } else {
+            let names: Vec<String> = fields.iter().map(|field| regex::escape(field)).collect();
+            let pattern = format!(
+                r#"(?P<label>\b(?:{})\b["']?\s*[:=]\s*)(?P<value>"[^"]*"|'[^']*'|[^\s,;&]+)"#,
+                names.join("|")
+            );
+            Some(
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| format!("Invalid field name: {}", e))?,
+            )
+        };
+        Ok(Redactor {
+            kinds,
+            fields: fields.iter().map(|field| field.to_lowercase()).collect(),
+            field_pattern,
+            counts: BTreeMap::new(),
+        })
+    }
+
+    fn count(&mut self, kind: &str) {
+        *self.counts.entry(kind.to_string()).or_default() += 1;
+    }
+
+    fn redact_text(&mut self, text: &str) -> String {
+        let mut text = text.to_string();
+        if let Some(field_pattern) = self.field_pattern.clone() {
+            let mut hits = 0;
+            text = field_pattern
+                .replace_all(&text, |captures: &Captures| {
+                    hits += 1;
+                    format!("{}{}", &captures["label"], placeholder("field"))
+                })
+                .into_owned();
+            if hits > 0 {
+                *self.counts.entry("field".to_string()).or_default() += hits;
+            }
+        }
+        for (kind, regex) in patterns() {
+            if !self.kinds.contains(kind) {
+                continue;
+            }
+            let mut hits = 0;
+            let replaced = regex.replace_all(&text, |captures: &Captures| {
+                let whole = captures.get(0).expect("match has group 0");
+                if *kind == "credit-card" && !luhn_valid(whole.as_str()) {
+                    return whole.as_str().to_string();
+                }
+                hits += 1;
+                match captures.name("secret") {
+                    Some(secret) => format!(
+                        "{}{}{}",
+                        &whole.as_str()[..secret.start() - whole.start()],
+                        placeholder(kind),
+                        &whole.as_str()[secret.end() - whole.start()..]
+                    ),
+                    None => placeholder(kind),
+                }
+            });
+            if hits > 0 {
+                text = replaced.into_owned();
+                *self.counts.entry(kind.to_string()).or_default() += hits;
+            }
+        }
+        text
+    }
+
+    fn redact_json(&mut self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (key, child) in object.iter_mut() {
+                    if self.fields.contains(&key.to_lowercase()) {
+                        *child = placeholder("field").into();
+                        self.count("field");
+                    } else {
+                        self.redact_json(child);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|item| self.redact_json(item)),
+            serde_json::Value::String(text) => {
+                let redacted = self.redact_text(text);
+                *text = redacted;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn redact(
+    content: &str,
+    kinds: Vec<&'static str>,
+    fields: Vec<String>,
+    paths: &[String],
+) -> Result<(String, BTreeMap<String, usize>, bool), String> {
+    let mut redactor = Redactor::new(kinds, fields)?;
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(mut value) if value.is_object() || value.is_array() => {
+            for path in paths {
+                let segments = json_path::parse_path(path)?;
+                if let Some(target) = json_path::get_mut(&mut value, &segments) {
+                    *target = placeholder("path").into();
+                    redactor.count("path");
+                }
+            }
+            redactor.redact_json(&mut value);
+            let redacted = crate::formatters::to_string_pretty(&value).map_err(|e| e.to_string())?;
+            Ok((redacted, redactor.counts, true))
+        }
+        _ => {
+            if !paths.is_empty() {
+                return Err("JSON paths can only be redacted in JSON content".to_string());
+            }
+            let redacted = redactor.redact_text(content);
+            Ok((redacted, redactor.counts, false))
+        }
+    }
+}
+
+// Redacts `text`, or the active document's raw content when it's empty. `kinds`
+// limits the built-in detectors (all of KINDS by default); `fields` and `paths`
+// add caller-specific data. With `apply` the result replaces the document's raw
+// content as an undoable edit; either way it's returned with counts per kind.
+#[tauri::command]
+pub async fn redact_content(
+    app: AppHandle,
+    text: Option<String>,
+    kinds: Option<Vec<String>>,
+    fields: Option<Vec<String>>,
+    paths: Option<Vec<String>>,
+    apply: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("redact_content", move || {
+        let kinds: Vec<&'static str> = match kinds {
+            Some(kinds) => kinds
+                .iter()
+                .map(|kind| {
+                    KINDS
+                        .iter()
+                        .copied()
+                        .find(|known| known == kind)
+                        .ok_or_else(|| format!("Unknown redaction kind {}; use {}", kind, KINDS.join(", ")))
+                })
+                .collect::<Result<_, _>>()?,
+            None => KINDS.to_vec(),
+        };
+        let fields: Vec<String> = fields
+            .unwrap_or_default()
+            .into_iter()
+            .map(|field| field.trim().to_string())
+            .filter(|field| !field.is_empty())
+            .collect();
+        let paths = paths.unwrap_or_default();
+        let apply = apply.unwrap_or(false);
+
+        let from_document = text.as_deref().is_none_or(str::is_empty);
+        if apply && !from_document {
+            return Err("Only the stored content can be redacted in place".to_string());
+        }
+        let content = match text.filter(|text| !text.is_empty()) {
+            Some(text) => text,
+            None => {
+                let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                storage
+                    .active()
+                    .raw_content
+                    .as_deref()
+                    .ok_or_else(|| "No content stored".to_string())?
+                    .to_string()
+            }
+        };
+
+        let (redacted, counts, json) = redact(&content, kinds, fields, &paths)?;
+        let total: usize = counts.values().sum();
+        if apply && total > 0 {
+            let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            let document = storage.active_mut();
+            document.edit(Slot::Raw, redacted.clone());
+            document.formatted_content = None;
+        }
+        Ok(serde_json::json!({
+            "content": redacted,
+            "counts": counts,
+            "total": total,
+            "json": json,
+            "applied": apply && total > 0
+        }))
+    })
+    .await
+}
@@ -0,0 +1,367 @@
+// Redis inspector: one connection at a time, key scanning with type and TTL,
+// and value fetching for every core type plus RedisJSON. Values are decoded for
+// display: UTF-8 text as-is (JSON parsed), binary as MessagePack when it
+// decodes, base64 otherwise. A value can be loaded into a new document, where
+// its format is detected and the matching formatter runs on it.
+use base64::Engine;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::formatters::{autodetect, FormatterRegistry};
+use crate::{text_buffer, AppState};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_SCAN_COUNT: u64 = 200;
+// Collections are cut off after this many elements
+const DEFAULT_MAX_ITEMS: usize = 1000;
+
+struct Session {
+    // Connection URL with the password removed, for display
+    url: String,
+    connection: redis::Connection,
+}
+
+#[derive(Default)]
+pub struct RedisSession(Mutex<Option<Session>>);
+
+fn redacted(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            if parsed.password().is_some() {
+                let _ = parsed.set_password(Some("***"));
+            }
+            parsed.to_string()
+        }
+        Err(_) => "redis".to_string(),
+    }
+}
+
+fn redis_error(e: redis::RedisError) -> String {
+    format!("Redis error: {}", e)
+}
+
+// Text when it's UTF-8 (parsed if it's a JSON object or array), MessagePack
+// when it decodes as such, otherwise base64
+pub fn decode_bytes(bytes: &[u8]) -> serde_json::Value {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        let trimmed = text.trim_start();
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
+                return value;
+            }
+        }
+        return text.into();
+    }
+    if let Ok(value) = rmp_serde::from_slice::<serde_json::Value>(bytes) {
+        return serde_json::json!({ "msgpack": value });
+    }
+    serde_json::json!({ "base64": base64::engine::general_purpose::STANDARD.encode(bytes) })
+}
+
+fn with_session<T>(
+    session: &RedisSession,
+    work: impl FnOnce(&mut redis::Connection) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut session = session.0.lock().map_err(|e| e.to_string())?;
+    let session = session.as_mut().ok_or_else(|| "Not connected to Redis".to_string())?;
+    work(&mut session.connection)
+}
+
+// Iterates a *SCAN command (HSCAN, SSCAN) until `max_items` elements are seen
+fn scan_collection(
+    connection: &mut redis::Connection,
+    command: &str,
+    key: &str,
+    max_items: usize,
+) -> Result<(Vec<Vec<u8>>, bool), String> {
+    let mut items = Vec::new();
+    let mut cursor: u64 = 0;
+    loop {
+        let (next, batch): (u64, Vec<Vec<u8>>) = redis::cmd(command)
+            .arg(key)
+            .arg(cursor)
+            .arg("COUNT")
+            .arg(DEFAULT_SCAN_COUNT)
+            .query(connection)
+            .map_err(redis_error)?;
+        items.extend(batch);
+        cursor = next;
+        if cursor == 0 {
+            return Ok((items, false));
+        }
+        if items.len() >= max_items {
+            return Ok((items, true));
+        }
+    }
+}
+
+// The decoded value, the collection's full length and whether it was cut off
+fn fetch_value(
+    connection: &mut redis::Connection,
+    key: &str,
+    key_type: &str,
+    max_items: usize,
+) -> Result<(serde_json::Value, u64, bool), String> {
+    let limit = max_items as isize - 1;
+    match key_type {
+        "string" => {
+            let bytes: Vec<u8> = redis::cmd("GET").arg(key).query(connection).map_err(redis_error)?;
+            Ok((decode_bytes(&bytes), bytes.len() as u64, false))
+        }
+        "hash" => {
+            let length: u64 = redis::cmd("HLEN").arg(key).query(connection).map_err(redis_error)?;
+            // HSCAN returns field, value, field, value, ...
+            let (items, truncated) = scan_collection(connection, "HSCAN", key, max_items * 2)?;
+            let map: serde_json::Map<String, serde_json::Value> = items
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [field, value] => Some((String::from_utf8_lossy(field).into_owned(), decode_bytes(value))),
+                    _ => None,
+                })
+                .collect();
+            Ok((map.into(), length, truncated))
+        }
+        "list" => {
+            let length: u64 = redis::cmd("LLEN").arg(key).query(connection).map_err(redis_error)?;
+            let items: Vec<Vec<u8>> = redis::cmd("LRANGE")
+                .arg(key)
+                .arg(0)
+                .arg(limit)
+                .query(connection)
+                .map_err(redis_error)?;
+            let values: Vec<serde_json::Value> = items.iter().map(|item| decode_bytes(item)).collect();
+            Ok((values.into(), length, length > max_items as u64))
+        }
+        "set" => {
+            let length: u64 = redis::cmd("SCARD").arg(key).query(connection).map_err(redis_error)?;
+            let (items, truncated) = scan_collection(connection, "SSCAN", key, max_items)?;
+            let values: Vec<serde_json::Value> = items.iter().map(|item| decode_bytes(item)).collect();
+            Ok((values.into(), length, truncated))
+        }
+        "zset" => {
+            let length: u64 = redis::cmd("ZCARD").arg(key).query(connection).map_err(redis_error)?;
+            let items: Vec<(Vec<u8>, f64)> = redis::cmd("ZRANGE")
+                .arg(key)
+                .arg(0)
+                .arg(limit)
+                .arg("WITHSCORES")
+                .query(connection)
+                .map_err(redis_error)?;
+            let values: Vec<serde_json::Value> = items
+                .iter()
+                .map(|(member, score)| serde_json::json!({ "member": decode_bytes(member), "score": score }))
+                .collect();
+            Ok((values.into(), length, length > max_items as u64))
+        }
+        "stream" => {
+            let length: u64 = redis::cmd("XLEN").arg(key).query(connection).map_err(redis_error)?;
+            // Newest entries first; each is [id, [field, value, ...]]
+            let entries: Vec<(String, Vec<Vec<u8>>)> = redis::cmd("XREVRANGE")
+                .arg(key)
+                .arg("+")
+                .arg("-")
+                .arg("COUNT")
+                .arg(max_items)
+                .query(connection)
+                .map_err(redis_error)?;
+            let values: Vec<serde_json::Value> = entries
+                .iter()
+                .map(|(id, fields)| {
+                    let fields: serde_json::Map<String, serde_json::Value> = fields
+                        .chunks(2)
+                        .filter_map(|pair| match pair {
+                            [field, value] => Some((String::from_utf8_lossy(field).into_owned(), decode_bytes(value))),
+                            _ => None,
+                        })
+                        .collect();
+                    serde_json::json!({ "id": id, "fields": fields })
+                })
+                .collect();
+            Ok((values.into(), length, length > max_items as u64))
+        }
+        "ReJSON-RL" => {
+            let text: String = redis::cmd("JSON.GET").arg(key).query(connection).map_err(redis_error)?;
+            let value = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text.clone()));
+            Ok((value, text.len() as u64, false))
+        }
+        other => Err(format!("Values of type {} can't be displayed", other)),
+    }
+}
+
+// Puts the value in a new document and formats it with whatever formatter its
+// content is detected as
+fn load_into_document(app: &AppHandle, key: &str, value: &serde_json::Value) -> Result<serde_json::Value, String> {
+    let content = match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => crate::formatters::to_string_pretty(other).map_err(|e| e.to_string())?,
+    };
+    let document_id = {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let id = storage.insert_document(format!("redis: {}", key));
+        storage.active_mut().raw_content = Some(content.clone().into());
+        id
+    };
+    crate::tray::refresh(app);
+
+    let sample_end = text_buffer::grapheme_floor(&content, autodetect::SAMPLE_BYTES);
+    let guess = {
+        let registry = app.state::<FormatterRegistry>();
+        autodetect::guess_formats(&content[..sample_end], sample_end == content.len(), &registry)
+            .into_iter()
+            .next()
+    };
+    let formatter_id = guess.as_ref().and_then(|guess| guess.formatter_id);
+    let mut formatted_length = None;
+    if let Some(formatter_id) = formatter_id {
+        // A value that only looks like a format is still loaded, just unformatted
+        if let Ok((formatted, store)) = crate::run_formatter(app, content, formatter_id) {
+            formatted_length = Some(formatted.len());
+            if store {
+                let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                if let Some(document) = storage.documents.get_mut(&document_id) {
+                    document.formatted_content = Some(formatted);
+                    document.format_type = Some(formatter_id.to_string());
+                }
+            }
+        }
+    }
+    Ok(serde_json::json!({
+        "document_id": document_id,
+        "detected_format": guess.map(|guess| guess.format),
+        "formatter_id": formatter_id,
+        "formatted_length": formatted_length
+    }))
+}
+
+// Connects to `url` (redis:// or rediss:// for TLS, credentials and database
+// number included), replacing any current connection
+#[tauri::command]
+pub async fn redis_connect(app: AppHandle, url: String) -> Result<serde_json::Value, String> {
+    crate::run_blocking("redis_connect", move || {
+        let client = redis::Client::open(url.as_str()).map_err(redis_error)?;
+        let mut connection = client.get_connection_with_timeout(TIMEOUT).map_err(redis_error)?;
+        connection.set_read_timeout(Some(TIMEOUT)).map_err(redis_error)?;
+        connection.set_write_timeout(Some(TIMEOUT)).map_err(redis_error)?;
+
+        let info: String = redis::cmd("INFO")
+            .arg("server")
+            .query(&mut connection)
+            .map_err(redis_error)?;
+        let version = info
+            .lines()
+            .find_map(|line| line.strip_prefix("redis_version:"))
+            .map(|version| version.trim().to_string());
+        let key_count: u64 = redis::cmd("DBSIZE").query(&mut connection).map_err(redis_error)?;
+
+        let url = redacted(&url);
+        *app.state::<RedisSession>().0.lock().map_err(|e| e.to_string())? = Some(Session {
+            url: url.clone(),
+            connection,
+        });
+        Ok(serde_json::json!({ "url": url, "server_version": version, "key_count": key_count }))
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn redis_disconnect(session: State<RedisSession>) -> Result<bool, String> {
+    Ok(session.0.lock().map_err(|e| e.to_string())?.take().is_some())
+}
+
+#[tauri::command]
+pub fn redis_status(session: State<RedisSession>) -> Result<serde_json::Value, String> {
+    let session = session.0.lock().map_err(|e| e.to_string())?;
+    Ok(match session.as_ref() {
+        Some(session) => serde_json::json!({ "connected": true, "url": session.url }),
+        None => serde_json::json!({ "connected": false }),
+    })
+}
+
+// One SCAN step over keys matching `pattern` (glob syntax, `*` by default).
+// Pass the returned cursor back to continue; 0 means the scan is complete.
+#[tauri::command]
+pub async fn redis_scan_keys(
+    app: AppHandle,
+    pattern: Option<String>,
+    cursor: Option<u64>,
+    count: Option<u64>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("redis_scan_keys", move || {
+        with_session(&app.state::<RedisSession>(), |connection| {
+            let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor.unwrap_or(0))
+                .arg("MATCH")
+                .arg(pattern.as_deref().unwrap_or("*"))
+                .arg("COUNT")
+                .arg(count.unwrap_or(DEFAULT_SCAN_COUNT))
+                .query(connection)
+                .map_err(redis_error)?;
+
+            let mut pipeline = redis::pipe();
+            for key in &keys {
+                pipeline.cmd("TYPE").arg(key).cmd("PTTL").arg(key);
+            }
+            let details: Vec<redis::Value> = if keys.is_empty() {
+                Vec::new()
+            } else {
+                pipeline.query(connection).map_err(redis_error)?
+            };
+            let entries: Vec<serde_json::Value> = keys
+                .iter()
+                .zip(details.chunks(2))
+                .map(|(key, detail)| {
+                    let key_type: Option<String> = redis::from_redis_value(&detail[0]).ok();
+                    // -1: no expiry, -2: the key vanished since SCAN saw it
+                    let ttl_ms: Option<i64> = detail.get(1).and_then(|ttl| redis::from_redis_value(ttl).ok());
+                    serde_json::json!({
+                        "key": key,
+                        "type": key_type,
+                        "ttl_ms": ttl_ms.filter(|ttl| *ttl >= 0)
+                    })
+                })
+                .collect();
+            Ok(serde_json::json!({ "cursor": next_cursor, "keys": entries }))
+        })
+    })
+    .await
+}
+
+// Fetches `key` with its type and TTL. Collections stop after `max_items`
+// elements (1000 by default). With `into_document` the value also opens in a
+// new document, formatted according to its detected format.
+#[tauri::command]
+pub async fn redis_get_value(
+    app: AppHandle,
+    key: String,
+    max_items: Option<usize>,
+    into_document: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("redis_get_value", move || {
+        let max_items = max_items.unwrap_or(DEFAULT_MAX_ITEMS).max(1);
+        let (key_type, ttl_ms, (value, length, truncated)) =
+            with_session(&app.state::<RedisSession>(), |connection| {
+                let key_type: String = redis::cmd("TYPE").arg(&key).query(connection).map_err(redis_error)?;
+                if key_type == "none" {
+                    return Err(format!("Key {} doesn't exist", key));
+                }
+                let ttl_ms: i64 = redis::cmd("PTTL").arg(&key).query(connection).map_err(redis_error)?;
+                let fetched = fetch_value(connection, &key, &key_type, max_items)?;
+                Ok((key_type, ttl_ms, fetched))
+            })?;
+
+        let mut reply = serde_json::json!({
+            "key": key,
+            "type": key_type,
+            "ttl_ms": (ttl_ms >= 0).then_some(ttl_ms),
+            "length": length,
+            "truncated": truncated,
+            "value": value
+        });
+        if into_document.unwrap_or(false) {
+            reply["document"] = load_into_document(&app, &key, &reply["value"])?;
+        }
+        Ok(reply)
+    })
+    .await
+}
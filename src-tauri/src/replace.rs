@@ -0,0 +1,149 @@
+use regex::Regex;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+const DEFAULT_PREVIEW_COUNT: usize = 20;
+
+// Everything a replace reports, gathered in one pass over the matches
+struct ReplacePass {
+    total_matches: usize,
+    preview: Vec<serde_json::Value>,
+    // Only built when the replacement is applied
+    updated: Option<String>,
+}
+
+// Counts every match, previews the first `preview_count` changes and, when
+// `apply` is set, builds the replaced text, all from the same match results.
+// `limit` 0 replaces every match; `$1`/`${name}` references are expanded.
+fn replace_pass(
+    regex: &Regex,
+    text: &str,
+    replacement: &str,
+    limit: usize,
+    preview_count: usize,
+    apply: bool,
+) -> ReplacePass {
+    let max_preview = if limit == 0 { preview_count } else { limit.min(preview_count) };
+    let mut total_matches = 0;
+    let mut preview = Vec::new();
+    let mut updated = apply.then(|| String::with_capacity(text.len()));
+    let mut copied_to = 0;
+    // Line numbers are counted forward from the previous preview item, so the
+    // text before each match is only scanned once
+    let mut line = 1;
+    let mut line_counted_to = 0;
+
+    for caps in regex.captures_iter(text) {
+        let whole = caps.get(0).expect("capture group 0 always exists");
+        let replacing = updated.is_some() && (limit == 0 || total_matches < limit);
+        let previewing = preview.len() < max_preview;
+        total_matches += 1;
+        if !replacing && !previewing {
+            continue;
+        }
+
+        let mut replaced = String::new();
+        caps.expand(replacement, &mut replaced);
+        if previewing {
+            line += text[line_counted_to..whole.start()].matches('\n').count();
+            line_counted_to = whole.start();
+            preview.push(serde_json::json!({
+                "line": line,
+                "start": whole.start(),
+                "end": whole.end(),
+                "before": whole.as_str(),
+                "after": replaced
+            }));
+        }
+        if let Some(updated) = updated.as_mut().filter(|_| replacing) {
+            updated.push_str(&text[copied_to..whole.start()]);
+            updated.push_str(&replaced);
+            copied_to = whole.end();
+        }
+    }
+    if let Some(updated) = updated.as_mut() {
+        updated.push_str(&text[copied_to..]);
+    }
+
+    ReplacePass {
+        total_matches,
+        preview,
+        updated,
+    }
+}
+
+fn regex_replace_blocking(
+    pattern: String,
+    replacement: String,
+    limit: Option<usize>,
+    preview_count: Option<usize>,
+    apply: bool,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let regex = Regex::new(&pattern).map_err(|e| format!("Invalid regex: {}", e))?;
+    let limit = limit.unwrap_or(0);
+    let preview_count = preview_count.unwrap_or(DEFAULT_PREVIEW_COUNT);
+
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let content = &snapshot.text;
+
+    let pass = replace_pass(&regex, content, &replacement, limit, preview_count, apply);
+    let total_matches = pass.total_matches;
+    let replaced_count = if limit == 0 { total_matches } else { total_matches.min(limit) };
+
+    if let Some(updated) = pass.updated {
+        let mut storage = state.lock().map_err(|e| e.to_string())?;
+        let document = storage.snapshot_document(&snapshot)?;
+        document.edit_raw(updated);
+    }
+
+    Ok(serde_json::json!({
+        "total_matches": total_matches,
+        "replaced_count": replaced_count,
+        "applied": apply,
+        "preview": pass.preview
+    }))
+}
+
+#[tauri::command]
+pub async fn regex_replace(
+    app: AppHandle,
+    pattern: String,
+    replacement: String,
+    limit: Option<usize>,
+    preview_count: Option<usize>,
+    apply: bool,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("regex_replace", move || {
+        regex_replace_blocking(pattern, replacement, limit, preview_count, apply, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_pass_matches_replacen() {
+        let regex = Regex::new(r"(\w)(\d)").unwrap();
+        let text = "a1 b2\n\nc3 d4\ne5";
+        let pass = replace_pass(&regex, text, "$2$1", 3, 10, true);
+        assert_eq!(pass.total_matches, 5);
+        assert_eq!(pass.updated.as_deref(), Some(regex.replacen(text, 3, "$2$1").as_ref()));
+        let lines: Vec<_> = pass.preview.iter().map(|item| item["line"].as_u64().unwrap()).collect();
+        assert_eq!(lines, [1, 1, 3]);
+        assert_eq!(pass.preview[2]["after"], "3c");
+    }
+
+    #[test]
+    fn preview_only_counts_every_match() {
+        let regex = Regex::new("x").unwrap();
+        let pass = replace_pass(&regex, "x\nx\nx\nx", "y", 0, 2, false);
+        assert_eq!(pass.total_matches, 4);
+        assert!(pass.updated.is_none());
+        let lines: Vec<_> = pass.preview.iter().map(|item| item["line"].as_u64().unwrap()).collect();
+        assert_eq!(lines, [1, 2]);
+    }
+}
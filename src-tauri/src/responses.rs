@@ -0,0 +1,169 @@
+// Return types of the core content commands. They serialize to the same JSON
+// the frontend already reads, and are public so other front doors (tests, a
+// CLI, an HTTP API) can share them instead of re-spelling field names.
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChunkHeader {
+    pub has_more: bool,
+    pub total_length: usize,
+    pub start: usize,
+    pub end: usize,
+    // Where the next request should start; may differ from start + chunk_size
+    pub next_start: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChunkResponse {
+    pub chunk: String,
+    #[serde(flatten)]
+    pub header: ChunkHeader,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LinesResponse {
+    pub lines: Vec<String>,
+    pub start_line: usize,
+    pub total_lines: usize,
+    pub has_more: bool,
+    pub next_line: usize,
+    pub start_offset: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ContentInfo {
+    pub name: String,
+    pub format_type: Option<String>,
+    pub view_state: serde_json::Value,
+    pub has_raw: bool,
+    pub has_formatted: bool,
+    pub has_binary: bool,
+    pub raw_memory_mapped: bool,
+    pub raw_compressed: bool,
+    pub formatted_compressed: bool,
+    pub resident_bytes: usize,
+    pub raw_length: usize,
+    pub formatted_length: usize,
+    pub binary_length: usize,
+    pub undo_steps: usize,
+    pub redo_steps: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DocumentInfo {
+    pub id: u64,
+    pub active: bool,
+    #[serde(flatten)]
+    pub info: ContentInfo,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FileLoadResult {
+    pub success: bool,
+    pub file_size: u64,
+    // False when the file is small enough for the frontend to read itself
+    pub use_streaming: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mapped: Option<bool>,
+    // gzip or zstd when the file was decompressed while loading; file_size is
+    // then the compressed size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uncompressed_size: Option<u64>,
+    pub message: String,
+}
+
+// A file opened into its own document by the OS or by dropping it on the window
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OpenedFile {
+    // Document id
+    pub id: u64,
+    pub name: String,
+    pub file_path: String,
+    pub file_size: u64,
+    pub encoding: String,
+    pub memory_mapped: bool,
+    pub detected_format: String,
+    // Formatter to offer for the detected format, if there is one
+    pub formatter_id: Option<String>,
+}
+
+// Outcome for one path of a batch open; exactly one of `file` and `error` is set
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OpenFileResult {
+    pub file_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<OpenedFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// `result` of a finished "format" job
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FormatJobResult {
+    pub format_type: String,
+    pub formatted_length: usize,
+    pub stored: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SearchMatch {
+    pub start: usize,
+    pub end: usize,
+    // 1-based, like an editor gutter
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub snippet: String,
+    pub snippet_match_start: usize,
+    pub snippet_match_end: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SearchResult {
+    pub content_type: String,
+    pub match_count: usize,
+    pub matches: Vec<SearchMatch>,
+    pub has_more: bool,
+    // Pass back as after_offset to fetch the next page
+    pub next_offset: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UndoResult {
+    pub content_type: String,
+    pub length: usize,
+    pub undo_steps: usize,
+    pub redo_steps: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SaveResult {
+    pub file_path: String,
+    pub content_type: String,
+    pub bytes_written: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JobInfo {
+    pub id: u64,
+    pub kind: String,
+    pub elapsed_ms: u64,
+    pub cancelling: bool,
+}
+
+// Payload of the `job://finished` event
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JobFinished {
+    pub id: u64,
+    pub kind: String,
+    // "completed", "cancelled" or "failed"
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
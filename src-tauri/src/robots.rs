@@ -0,0 +1,482 @@
+// robots.txt and sitemap.xml analysis. robots.txt is parsed into groups of
+// user agents with their allow/disallow rules, and URLs can be tested against
+// it with the RFC 9309 matching rules (most specific user-agent group, longest
+// matching path wins, `*` and `$` wildcards). Sitemaps (url sets and sitemap
+// indexes) are summarized: URL counts, lastmod range and spread, change
+// frequencies and problems such as duplicates. Either file comes from `text`,
+// the active document, or is fetched from a site.
+use quick_xml::events::Event;
+use std::collections::{BTreeMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+const MAX_FETCH_BYTES: u64 = 64 * 1024 * 1024;
+// Limits from the sitemaps.org protocol
+const SITEMAP_MAX_URLS: usize = 50_000;
+const SITEMAP_MAX_BYTES: usize = 50 * 1024 * 1024;
+const MAX_LISTED_PROBLEMS: usize = 100;
+
+struct Rule {
+    allow: bool,
+    path: String,
+    line: usize,
+}
+
+#[derive(Default)]
+struct Group {
+    user_agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<String>,
+}
+
+#[derive(Default)]
+struct Robots {
+    groups: Vec<Group>,
+    sitemaps: Vec<String>,
+    warnings: Vec<String>,
+}
+
+fn parse_robots(text: &str) -> Robots {
+    let mut robots = Robots::default();
+    // Whether the current group is still collecting user-agent lines
+    let mut in_agents = false;
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            robots.warnings.push(format!("Line {}: no ':' separator", number));
+            continue;
+        };
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "user-agent" => {
+                if !in_agents {
+                    robots.groups.push(Group::default());
+                    in_agents = true;
+                }
+                if let Some(group) = robots.groups.last_mut() {
+                    group.user_agents.push(value.to_string());
+                }
+            }
+            key @ ("allow" | "disallow") => {
+                in_agents = false;
+                let Some(group) = robots.groups.last_mut() else {
+                    robots
+                        .warnings
+                        .push(format!("Line {}: {} before any user-agent line", number, key));
+                    continue;
+                };
+                // An empty disallow allows everything, like having no rule
+                if !value.is_empty() {
+                    group.rules.push(Rule {
+                        allow: key == "allow",
+                        path: value.to_string(),
+                        line: number,
+                    });
+                }
+            }
+            "crawl-delay" => {
+                in_agents = false;
+                if let Some(group) = robots.groups.last_mut() {
+                    group.crawl_delay = Some(value.to_string());
+                }
+            }
+            // Not part of any group
+            "sitemap" => robots.sitemaps.push(value.to_string()),
+            other => robots
+                .warnings
+                .push(format!("Line {}: unknown directive {}", number, other)),
+        }
+    }
+    robots
+}
+
+// Whether `pattern` (with `*` wildcards and an optional trailing `$`) matches
+// the start of `path`, or all of it when anchored
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let pieces: Vec<&str> = pattern.split('*').collect();
+    let Some(first) = pieces.first() else {
+        return true;
+    };
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    if pieces.len() == 1 {
+        return !anchored || rest.is_empty();
+    }
+    for (index, piece) in pieces.iter().enumerate().skip(1) {
+        let last = index == pieces.len() - 1;
+        if last && anchored {
+            return rest.ends_with(piece);
+        }
+        match rest.find(piece) {
+            Some(position) => rest = &rest[position + piece.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+// The path and query robots rules are matched against
+fn request_path(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        },
+        // Already a path
+        Err(_) if url.starts_with('/') => url.to_string(),
+        Err(_) => format!("/{}", url),
+    }
+}
+
+fn test_url(robots: &Robots, url: &str, user_agent: &str) -> serde_json::Value {
+    let path = request_path(url);
+    // The product token: "Googlebot/2.1 (+http://...)" is matched as "googlebot"
+    let token = user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let agent_matches = |agent: &str| {
+        let agent = agent.to_ascii_lowercase();
+        agent != "*" && !token.is_empty() && (token == agent || token.starts_with(&agent))
+    };
+    // The most specific named agent wins; groups naming it are combined
+    let best_agent = robots
+        .groups
+        .iter()
+        .flat_map(|group| group.user_agents.iter())
+        .filter(|agent| agent_matches(agent))
+        .max_by_key(|agent| agent.len())
+        .map(|agent| agent.to_ascii_lowercase());
+    let groups: Vec<&Group> = match &best_agent {
+        Some(best) => robots
+            .groups
+            .iter()
+            .filter(|group| {
+                group
+                    .user_agents
+                    .iter()
+                    .any(|agent| agent.to_ascii_lowercase() == *best)
+            })
+            .collect(),
+        None => robots
+            .groups
+            .iter()
+            .filter(|group| group.user_agents.iter().any(|agent| agent == "*"))
+            .collect(),
+    };
+
+    // Longest pattern wins; on a tie, allow wins
+    let matched = groups
+        .iter()
+        .flat_map(|group| group.rules.iter())
+        .filter(|rule| pattern_matches(&rule.path, &path))
+        .max_by_key(|rule| (rule.path.len(), rule.allow));
+    let allowed = path == "/robots.txt" || !matches!(matched, Some(rule) if !rule.allow);
+    serde_json::json!({
+        "url": url,
+        "path": path,
+        "allowed": allowed,
+        "group": best_agent.unwrap_or_else(|| if groups.is_empty() { "none" } else { "*" }.to_string()),
+        "rule": matched.map(|rule| serde_json::json!({
+            "kind": if rule.allow { "allow" } else { "disallow" },
+            "path": rule.path,
+            "line": rule.line
+        }))
+    })
+}
+
+fn robots_report(text: &str, urls: &[String], user_agent: &str) -> serde_json::Value {
+    let robots = parse_robots(text);
+    let groups: Vec<serde_json::Value> = robots
+        .groups
+        .iter()
+        .map(|group| {
+            serde_json::json!({
+                "user_agents": group.user_agents,
+                "crawl_delay": group.crawl_delay,
+                "rules": group.rules.iter().map(|rule| serde_json::json!({
+                    "kind": if rule.allow { "allow" } else { "disallow" },
+                    "path": rule.path,
+                    "line": rule.line
+                })).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let tests: Vec<serde_json::Value> = urls.iter().map(|url| test_url(&robots, url, user_agent)).collect();
+    serde_json::json!({
+        "groups": groups,
+        "sitemaps": robots.sitemaps,
+        "warnings": robots.warnings,
+        "user_agent": user_agent,
+        "tests": tests
+    })
+}
+
+// A W3C datetime lastmod ("2024", "2024-05", "2024-05-01", or a full
+// timestamp) as a date
+fn parse_lastmod(text: &str) -> Option<chrono::NaiveDate> {
+    let text = text.trim();
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.date_naive());
+    }
+    let padded = match text.len() {
+        4 => format!("{}-01-01", text),
+        7 => format!("{}-01", text),
+        _ => text.to_string(),
+    };
+    chrono::NaiveDate::parse_from_str(&padded, "%Y-%m-%d").ok()
+}
+
+#[derive(Default)]
+struct Entry {
+    loc: String,
+    lastmod: Option<String>,
+    changefreq: Option<String>,
+    priority: Option<String>,
+}
+
+fn parse_sitemap(text: &str) -> Result<(String, Vec<Entry>), String> {
+    let mut reader = quick_xml::Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut kind = None;
+    let mut entries = Vec::new();
+    let mut current: Option<Entry> = None;
+    let mut field = String::new();
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid XML at byte {}: {}", reader.error_position(), e))?;
+        match event {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+                match name.as_str() {
+                    "urlset" | "sitemapindex" if kind.is_none() => kind = Some(name),
+                    "url" | "sitemap" => current = Some(Entry::default()),
+                    _ => field = name,
+                }
+            }
+            Event::Text(content) => {
+                let value = content.unescape().map_err(|e| e.to_string())?.into_owned();
+                append_field(current.as_mut(), &field, value);
+            }
+            Event::CData(content) => {
+                let value = String::from_utf8_lossy(&content).into_owned();
+                append_field(current.as_mut(), &field, value);
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"url" | b"sitemap" => entries.extend(current.take()),
+                _ => field.clear(),
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let kind = kind.ok_or_else(|| "Not a sitemap: no <urlset> or <sitemapindex> element".to_string())?;
+    Ok((kind, entries))
+}
+
+fn append_field(entry: Option<&mut Entry>, field: &str, value: String) {
+    let Some(entry) = entry else {
+        return;
+    };
+    match field {
+        "loc" => entry.loc.push_str(value.trim()),
+        "lastmod" => entry.lastmod = Some(value),
+        "changefreq" => entry.changefreq = Some(value.to_ascii_lowercase()),
+        "priority" => entry.priority = Some(value),
+        _ => {}
+    }
+}
+
+fn sitemap_report(text: &str) -> Result<serde_json::Value, String> {
+    let (kind, entries) = parse_sitemap(text)?;
+    let mut problems = Vec::new();
+    let mut problem = |message: String| {
+        if problems.len() < MAX_LISTED_PROBLEMS {
+            problems.push(message);
+        }
+    };
+
+    let mut seen = HashSet::new();
+    let mut duplicates = 0;
+    let mut hosts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut dates = Vec::new();
+    let mut invalid_lastmod = 0;
+    let mut changefreq: BTreeMap<String, usize> = BTreeMap::new();
+    let mut per_year: BTreeMap<i32, usize> = BTreeMap::new();
+    let mut priorities = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.loc.is_empty() {
+            problem(format!("Entry {} has no <loc>", index + 1));
+            continue;
+        }
+        if !seen.insert(entry.loc.as_str()) {
+            duplicates += 1;
+            problem(format!("Duplicate URL {}", entry.loc));
+        }
+        match reqwest::Url::parse(&entry.loc) {
+            Ok(url) => *hosts.entry(url.host_str().unwrap_or("").to_string()).or_default() += 1,
+            Err(_) => problem(format!("Invalid URL {}", entry.loc)),
+        }
+        if let Some(lastmod) = &entry.lastmod {
+            match parse_lastmod(lastmod) {
+                Some(date) => {
+                    use chrono::Datelike;
+                    *per_year.entry(date.year()).or_default() += 1;
+                    dates.push(date);
+                }
+                None => {
+                    invalid_lastmod += 1;
+                    problem(format!("Invalid lastmod {} for {}", lastmod, entry.loc));
+                }
+            }
+        }
+        if let Some(frequency) = &entry.changefreq {
+            *changefreq.entry(frequency.clone()).or_default() += 1;
+        }
+        if let Some(priority) = &entry.priority {
+            match priority.trim().parse::<f64>() {
+                Ok(value) if (0.0..=1.0).contains(&value) => priorities.push(value),
+                _ => problem(format!("Invalid priority {} for {}", priority, entry.loc)),
+            }
+        }
+    }
+    if entries.len() > SITEMAP_MAX_URLS {
+        problem(format!(
+            "{} entries, over the limit of {} per sitemap",
+            entries.len(),
+            SITEMAP_MAX_URLS
+        ));
+    }
+    if text.len() > SITEMAP_MAX_BYTES {
+        problem(format!("{} bytes uncompressed, over the 50MB limit", text.len()));
+    }
+    if hosts.len() > 1 {
+        problem(format!("URLs span {} hosts", hosts.len()));
+    }
+
+    dates.sort();
+    let median = dates.get(dates.len() / 2);
+    let locations: Vec<&str> = entries.iter().take(20).map(|entry| entry.loc.as_str()).collect();
+    Ok(serde_json::json!({
+        "kind": kind,
+        "entries": entries.len(),
+        "unique_urls": seen.len(),
+        "duplicates": duplicates,
+        "hosts": hosts,
+        "lastmod": {
+            "with_lastmod": dates.len() + invalid_lastmod,
+            "invalid": invalid_lastmod,
+            "oldest": dates.first().map(|date| date.to_string()),
+            "newest": dates.last().map(|date| date.to_string()),
+            "median": median.map(|date| date.to_string()),
+            "per_year": per_year
+        },
+        "changefreq": changefreq,
+        "priority": {
+            "count": priorities.len(),
+            "average": (!priorities.is_empty()).then(|| priorities.iter().sum::<f64>() / priorities.len() as f64)
+        },
+        // For a sitemap index these are the child sitemaps to look at next
+        "first_urls": locations,
+        "problems": problems
+    }))
+}
+
+fn origin(site: &str) -> String {
+    let site = site.trim().trim_end_matches('/');
+    if site.contains("://") {
+        match reqwest::Url::parse(site) {
+            Ok(url) => url.origin().ascii_serialization(),
+            Err(_) => site.to_string(),
+        }
+    } else {
+        format!("https://{}", site)
+    }
+}
+
+fn content(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    match text.filter(|text| !text.is_empty()) {
+        Some(text) => Ok(text),
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            Ok(storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string())
+        }
+    }
+}
+
+// Groups, rules and sitemaps of a robots.txt taken from `text`, fetched from
+// `site` ("example.com" or any URL on it), or the active document. Each of
+// `urls` is tested for `user_agent` (a full UA string or just its name;
+// "*" by default).
+#[tauri::command]
+pub async fn analyze_robots(
+    app: AppHandle,
+    text: Option<String>,
+    site: Option<String>,
+    urls: Option<Vec<String>>,
+    user_agent: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("analyze_robots", move || {
+        let (source, text) = match site.filter(|site| !site.trim().is_empty()) {
+            Some(site) => {
+                let (url, text) = crate::fetch::fetch_text(&format!("{}/robots.txt", origin(&site)), MAX_FETCH_BYTES)?;
+                (Some(url), text)
+            }
+            None => (None, content(&app, text)?),
+        };
+        let user_agent = user_agent
+            .filter(|agent| !agent.trim().is_empty())
+            .unwrap_or_else(|| "*".to_string());
+        let mut report = robots_report(&text, &urls.unwrap_or_default(), &user_agent);
+        report["source"] = source.into();
+        Ok(report)
+    })
+    .await
+}
+
+// Summary of a sitemap or sitemap index taken from `text`, fetched from `url`
+// (gzipped sitemaps included; a bare domain means its /sitemap.xml), or the
+// active document
+#[tauri::command]
+pub async fn analyze_sitemap(
+    app: AppHandle,
+    text: Option<String>,
+    url: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("analyze_sitemap", move || {
+        let (source, text) = match url.filter(|url| !url.trim().is_empty()) {
+            Some(url) => {
+                let url = if url.contains("://") {
+                    url
+                } else {
+                    format!("{}/sitemap.xml", origin(&url))
+                };
+                let (url, text) = crate::fetch::fetch_text(&url, MAX_FETCH_BYTES)?;
+                (Some(url), text)
+            }
+            None => (None, content(&app, text)?),
+        };
+        let mut report = sitemap_report(&text)?;
+        report["source"] = source.into();
+        Ok(report)
+    })
+    .await
+}
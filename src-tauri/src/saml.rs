@@ -0,0 +1,197 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::io::Read;
+
+use crate::formatters::Formatter;
+
+#[derive(Default)]
+struct SamlSummary {
+    issuers: Vec<String>,
+    name_id: Option<String>,
+    audiences: Vec<String>,
+    not_before: Option<String>,
+    not_on_or_after: Option<String>,
+    status: Option<String>,
+    attributes: Vec<(String, Vec<String>)>,
+}
+
+fn url_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(b) => {
+                        out.push(b);
+                        i += 3;
+                        continue;
+                    }
+                    Err(_) => out.push(b'%'),
+                }
+            }
+            b'+' => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// Accepts a raw SAMLResponse/SAMLRequest value (optionally URL-encoded or as a
+// `SAMLResponse=...` form field) and returns the XML document
+fn decode_saml_payload(text: &str) -> Result<String, String> {
+    let mut value = text.trim().to_string();
+    if let Some((_, rest)) = value.split_once("SAMLResponse=").or_else(|| value.split_once("SAMLRequest=")) {
+        value = rest.split('&').next().unwrap_or("").to_string();
+    }
+    if value.contains('%') {
+        value = url_decode(&value);
+    }
+    if value.trim_start().starts_with('<') {
+        return Ok(value);
+    }
+
+    let compact: String = value.split_whitespace().collect();
+    let bytes = STANDARD
+        .decode(compact.as_bytes())
+        .map_err(|e| format!("Invalid base64 in SAML message: {}", e))?;
+
+    // POST binding is plain base64; Redirect binding is raw-deflated first
+    if let Ok(xml) = std::str::from_utf8(&bytes) {
+        if xml.trim_start().starts_with('<') {
+            return Ok(xml.to_string());
+        }
+    }
+    let mut inflated = String::new();
+    DeflateDecoder::new(&bytes[..])
+        .read_to_string(&mut inflated)
+        .map_err(|e| format!("Failed to inflate SAML message: {}", e))?;
+    Ok(inflated)
+}
+
+fn record_attributes(name: &str, element: &BytesStart, summary: &mut SamlSummary) {
+    for attr in element.attributes().flatten() {
+        let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+        let value = attr.unescape_value().map(|v| v.into_owned()).unwrap_or_default();
+        match (name, key.as_str()) {
+            ("Conditions", "NotBefore") => summary.not_before = Some(value),
+            ("Conditions", "NotOnOrAfter") => summary.not_on_or_after = Some(value),
+            ("StatusCode", "Value") if summary.status.is_none() => summary.status = Some(value),
+            ("Attribute", "Name") => summary.attributes.push((value, Vec::new())),
+            _ => {}
+        }
+    }
+}
+
+fn summarize_saml(xml: &str) -> Result<SamlSummary, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut summary = SamlSummary::default();
+    let mut stack: Vec<String> = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                record_attributes(&name, &e, &mut summary);
+                stack.push(name);
+            }
+            Ok(Event::Empty(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                record_attributes(&name, &e, &mut summary);
+            }
+            Ok(Event::Text(t)) => {
+                let text = t.unescape().map(|v| v.into_owned()).unwrap_or_default();
+                match stack.last().map(|s| s.as_str()) {
+                    Some("Issuer") => summary.issuers.push(text),
+                    Some("NameID") => summary.name_id = Some(text),
+                    Some("Audience") => summary.audiences.push(text),
+                    Some("AttributeValue") => {
+                        if let Some((_, values)) = summary.attributes.last_mut() {
+                            values.push(text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(_)) => {
+                stack.pop();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("Invalid SAML XML at position {}: {}", reader.buffer_position(), e)),
+            _ => {}
+        }
+    }
+
+    Ok(summary)
+}
+
+pub fn decode_saml(text: &str) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Err("Empty SAML input".to_string());
+    }
+
+    let xml = decode_saml_payload(text)?;
+    let summary = summarize_saml(&xml)?;
+    let pretty = crate::formatters::xml::format_xml(&xml)?;
+
+    let mut result = String::new();
+    result.push_str("SAML Message Summary:\n");
+    result.push_str("=====================\n\n");
+    for issuer in summary.issuers.iter() {
+        result.push_str(&format!("Issuer: {}\n", issuer));
+    }
+    if let Some(status) = &summary.status {
+        result.push_str(&format!("Status: {}\n", status));
+    }
+    if let Some(name_id) = &summary.name_id {
+        result.push_str(&format!("Subject NameID: {}\n", name_id));
+    }
+    for audience in summary.audiences.iter() {
+        result.push_str(&format!("Audience: {}\n", audience));
+    }
+    if summary.not_before.is_some() || summary.not_on_or_after.is_some() {
+        result.push_str(&format!(
+            "Conditions: NotBefore={} NotOnOrAfter={}\n",
+            summary.not_before.as_deref().unwrap_or("-"),
+            summary.not_on_or_after.as_deref().unwrap_or("-")
+        ));
+    }
+    if !summary.attributes.is_empty() {
+        result.push_str("\nAttributes:\n");
+        for (name, values) in summary.attributes.iter() {
+            result.push_str(&format!("  • {} = {}\n", name, values.join(", ")));
+        }
+    }
+    result.push_str("\nXML:\n----\n");
+    result.push_str(&pretty);
+
+    Ok(result)
+}
+
+pub struct SamlFormatter;
+
+impl Formatter for SamlFormatter {
+    fn id(&self) -> &'static str {
+        "saml"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "SAML Decoder"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "text"
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        decode_saml(input)
+    }
+}
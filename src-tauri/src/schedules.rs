@@ -0,0 +1,912 @@
+// Scheduled jobs of a host, read from a pasted crontab (user or system format,
+// with its environment lines) or from systemd timer and service units (as
+// `systemctl cat` prints them). Every job gets a plain-English explanation of
+// its schedule and its next runs, and the list is sorted by the next run.
+// Cron expressions and systemd OnCalendar expressions are reduced to the same
+// bit-set schedule, so both are explained and advanced by the same code.
+// Times are computed at a fixed UTC offset; DST transitions aren't modelled.
+use chrono::{Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, TimeZone, Timelike, Utc};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+const DEFAULT_RUNS: usize = 5;
+const MAX_RUNS: usize = 50;
+// Long enough to reach Feb 29 across a skipped leap year (2096 -> 2104)
+const SEARCH_DAYS: u32 = 366 * 8 + 2;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+// The @ shorthands cron accepts in place of the five fields
+const CRON_MACROS: &[(&str, &str)] = &[
+    ("@yearly", "0 0 1 1 *"),
+    ("@annually", "0 0 1 1 *"),
+    ("@monthly", "0 0 1 * *"),
+    ("@weekly", "0 0 * * 0"),
+    ("@daily", "0 0 * * *"),
+    ("@midnight", "0 0 * * *"),
+    ("@hourly", "0 * * * *"),
+];
+
+const CALENDAR_SHORTHANDS: &[(&str, &str)] = &[
+    ("minutely", "*-*-* *:*:00"),
+    ("hourly", "*-*-* *:00:00"),
+    ("daily", "*-*-* 00:00:00"),
+    ("weekly", "Mon *-*-* 00:00:00"),
+    ("monthly", "*-*-01 00:00:00"),
+    ("quarterly", "*-01,04,07,10-01 00:00:00"),
+    ("semiannually", "*-01,07-01 00:00:00"),
+    ("yearly", "*-01-01 00:00:00"),
+    ("annually", "*-01-01 00:00:00"),
+];
+
+// Monotonic timer settings and what their delay counts from
+const MONOTONIC_TIMERS: &[(&str, &str)] = &[
+    ("onbootsec", "boot"),
+    ("onstartupsec", "the service manager started"),
+    ("onactivesec", "the timer was activated"),
+    ("onunitactivesec", "the unit last started"),
+    ("onunitinactivesec", "the unit last finished"),
+];
+
+// Each field is a bit set of the values it allows
+struct Schedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    // Days counted from the end of the month (bit 1 = the last day)
+    last_days: u64,
+    months: u64,
+    weekdays: u64,
+    // (from, to, step) year ranges; empty allows every year
+    years: Vec<(i32, i32, i32)>,
+    // Cron runs when either day field matches if both are restricted
+    day_or: bool,
+}
+
+fn bits(from: u32, to: u32) -> u64 {
+    (from..=to).fold(0, |mask, value| mask | (1 << value))
+}
+
+fn values(mask: u64) -> Vec<u32> {
+    (0..64).filter(|value| mask & (1 << value) != 0).collect()
+}
+
+// The lowest allowed value at or after `from`
+fn next_value(mask: u64, from: u32) -> Option<u32> {
+    if from >= 64 {
+        return None;
+    }
+    let shifted = mask >> from;
+    (shifted != 0).then(|| from + shifted.trailing_zeros())
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(31)
+}
+
+impl Schedule {
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let year = date.year();
+        if !self.years.is_empty()
+            && !self
+                .years
+                .iter()
+                .any(|(from, to, step)| year >= *from && year <= *to && (year - from) % step == 0)
+        {
+            return false;
+        }
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = date.day();
+        let from_end = days_in_month(date) - day + 1;
+        let dom = self.days & (1 << day) != 0 || self.last_days & (1 << from_end) != 0;
+        let dow = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.day_or {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+
+    // The first allowed time of day at or after (hour, minute, second)
+    fn first_time(&self, (hour, minute, second): (u32, u32, u32)) -> Option<(u32, u32, u32)> {
+        let mut h = next_value(self.hours, hour)?;
+        loop {
+            let mut m = next_value(self.minutes, if h == hour { minute } else { 0 });
+            while let Some(found_minute) = m {
+                let from_second = if h == hour && found_minute == minute { second } else { 0 };
+                if let Some(s) = next_value(self.seconds, from_second) {
+                    return Some((h, found_minute, s));
+                }
+                m = next_value(self.minutes, found_minute + 1);
+            }
+            h = next_value(self.hours, h + 1)?;
+        }
+    }
+
+    fn next_from(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut date = from.date();
+        for day in 0..SEARCH_DAYS {
+            if self.day_matches(date) {
+                let start = if day == 0 {
+                    (from.hour(), from.minute(), from.second())
+                } else {
+                    (0, 0, 0)
+                };
+                if let Some((h, m, s)) = self.first_time(start) {
+                    return date.and_hms_opt(h, m, s);
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn next_runs(&self, after: NaiveDateTime, count: usize) -> Vec<NaiveDateTime> {
+        let mut runs = Vec::new();
+        let mut from = after.with_nanosecond(0).unwrap_or(after) + Duration::seconds(1);
+        while runs.len() < count {
+            match self.next_from(from) {
+                Some(run) => {
+                    runs.push(run);
+                    from = run + Duration::seconds(1);
+                }
+                None => break,
+            }
+        }
+        runs
+    }
+
+    fn explain(&self) -> String {
+        let mut parts = Vec::new();
+        let (seconds, minutes, hours) = (values(self.seconds), values(self.minutes), values(self.hours));
+        if seconds.len() == 1 && minutes.len() == 1 && hours.len() <= 6 && self.hours != bits(0, 23) {
+            let times: Vec<String> = hours
+                .iter()
+                .map(|hour| match seconds[0] {
+                    0 => format!("{:02}:{:02}", hour, minutes[0]),
+                    second => format!("{:02}:{:02}:{:02}", hour, minutes[0], second),
+                })
+                .collect();
+            parts.push(format!("at {}", join_and(&times)));
+        } else {
+            if self.seconds != 1 {
+                parts.push(phrase(&seconds, 0, 59, "second", "at"));
+            }
+            if !(self.seconds != 1 && self.minutes == bits(0, 59)) {
+                parts.push(phrase(&minutes, 0, 59, "minute", "at"));
+            }
+            if self.hours != bits(0, 23) {
+                parts.push(phrase(&hours, 0, 23, "hour", "at"));
+            }
+        }
+
+        let dom_restricted = self.days != bits(1, 31) || self.last_days != 0;
+        let dow_restricted = self.weekdays != bits(0, 6);
+        let mut day_parts = Vec::new();
+        if dom_restricted {
+            let mut dom = Vec::new();
+            if self.days != 0 {
+                dom.push(format!(
+                    "{} of the month",
+                    phrase(&values(self.days), 1, 31, "day", "on")
+                ));
+            }
+            for from_end in values(self.last_days) {
+                dom.push(match from_end {
+                    1 => "on the last day of the month".to_string(),
+                    n => format!("on the day {} days before the end of the month", n - 1),
+                });
+            }
+            day_parts.push(dom.join(" and "));
+        }
+        if dow_restricted {
+            day_parts.push(named_phrase(&values(self.weekdays), 0, &WEEKDAYS, "on"));
+        }
+        match day_parts.len() {
+            2 if self.day_or => parts.push(format!("{}, or {}", day_parts[0], day_parts[1])),
+            _ => parts.extend(day_parts),
+        }
+        if self.months != bits(1, 12) {
+            parts.push(named_phrase(&values(self.months), 1, &MONTHS, "in"));
+        }
+        if !self.years.is_empty() {
+            let years: Vec<String> = self
+                .years
+                .iter()
+                .map(|(from, to, step)| match (from == to, *step) {
+                    (true, _) => from.to_string(),
+                    (false, 1) if *to >= 9999 => format!("{} onwards", from),
+                    (false, 1) => format!("{} through {}", from, to),
+                    (false, step) => format!("every {} years from {}", step, from),
+                })
+                .collect();
+            parts.push(format!("in {}", join_and(&years)));
+        }
+        let sentence = parts.join(", ");
+        let mut chars = sentence.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => sentence,
+        }
+    }
+}
+
+fn join_and(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+// The step of an evenly spaced set (`*/15`, `0-20/2`)
+fn step_of(values: &[u32], min: u32, max: u32) -> Option<u32> {
+    if values.len() < 2 {
+        return None;
+    }
+    let step = values[1] - values[0];
+    (step > 1
+        && values.windows(2).all(|pair| pair[1] - pair[0] == step)
+        && (values.len() > 2 || covers(values, step, min, max)))
+    .then_some(step)
+}
+
+// Whether a stepped set reaches both ends of its field
+fn covers(values: &[u32], step: u32, min: u32, max: u32) -> bool {
+    values[0] < min + step && values[values.len() - 1] + step > max
+}
+
+fn is_run(values: &[u32]) -> bool {
+    values.len() > 2 && values.windows(2).all(|pair| pair[1] == pair[0] + 1)
+}
+
+fn phrase(values: &[u32], min: u32, max: u32, unit: &str, preposition: &str) -> String {
+    let labels: Vec<String> = values.iter().map(u32::to_string).collect();
+    let (first, last) = (labels[0].as_str(), labels[labels.len() - 1].as_str());
+    if values.len() as u32 == max - min + 1 {
+        return format!("every {}", unit);
+    }
+    if let Some(step) = step_of(values, min, max) {
+        return match covers(values, step, min, max) {
+            true if values[0] == min => format!("every {} {}s", step, unit),
+            true => format!("every {} {}s from {} {}", step, unit, unit, first),
+            false => format!("every {} {}s from {} {} through {}", step, unit, unit, first, last),
+        };
+    }
+    match labels.as_slice() {
+        [only] => format!("{} {} {}", preposition, unit, only),
+        _ if is_run(values) && preposition == "at" => format!("every {} from {} through {}", unit, first, last),
+        _ if is_run(values) => format!("{} {}s {} through {}", preposition, unit, first, last),
+        _ => format!("{} {}s {}", preposition, unit, join_and(&labels)),
+    }
+}
+
+fn named_phrase(values: &[u32], min: u32, names: &[&str], preposition: &str) -> String {
+    let labels: Vec<String> = values
+        .iter()
+        .map(|value| names[(value - min) as usize].to_string())
+        .collect();
+    if is_run(values) {
+        return format!("{} {} through {}", preposition, labels[0], labels[labels.len() - 1]);
+    }
+    format!("{} {}", preposition, join_and(&labels))
+}
+
+// A number, or a name from `names` (prefixes of three letters or more)
+fn field_value(text: &str, min: u32, max: u32, names: &[&str], name_base: u32) -> Result<u32, String> {
+    let value = match text.parse::<u32>() {
+        Ok(value) => value,
+        Err(_) => {
+            let lower = text.to_ascii_lowercase();
+            names
+                .iter()
+                .position(|name| lower.len() >= 3 && name.to_ascii_lowercase().starts_with(&lower))
+                .map(|index| index as u32 + name_base)
+                .ok_or_else(|| format!("\"{}\" isn't a valid value", text))?
+        }
+    };
+    if value < min || value > max {
+        return Err(format!("{} is outside {}-{}", value, min, max));
+    }
+    Ok(value)
+}
+
+// Comma lists of `*`, values, ranges and `/step` repetitions; cron writes
+// ranges as `a-b`, systemd as `a..b`
+fn parse_field(
+    text: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+    range_separator: &str,
+) -> Result<u64, String> {
+    let mut mask = 0;
+    for item in text.split(',') {
+        let (base, step) = match item.split_once('/') {
+            Some((base, step)) => (
+                base,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("\"{}\" isn't a valid step", step))?,
+            ),
+            None => (item, 1),
+        };
+        let (from, to) = match base {
+            "*" | "?" => (min, max),
+            _ => match base.split_once(range_separator) {
+                Some((from, to)) => (
+                    field_value(from, min, max, names, name_base)?,
+                    field_value(to, min, max, names, name_base)?,
+                ),
+                None => {
+                    let value = field_value(base, min, max, names, name_base)?;
+                    // `5/10` repeats from 5 to the end of the field
+                    (value, if item.contains('/') { max } else { value })
+                }
+            },
+        };
+        if from > to {
+            return Err(format!("The range {} runs backwards", base));
+        }
+        mask |= (from..=to)
+            .step_by(step as usize)
+            .fold(0, |mask, value| mask | (1 << value));
+    }
+    Ok(mask)
+}
+
+// Five cron fields, or six with seconds first
+fn parse_cron(expression: &str) -> Result<Schedule, String> {
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let (seconds, fields) = match fields.len() {
+        5 => (1, &fields[..]),
+        6 => (
+            parse_field(fields[0], 0, 59, &[], 0, "-").map_err(|e| format!("Seconds: {}", e))?,
+            &fields[1..],
+        ),
+        count => return Err(format!("A cron expression has 5 fields, not {}", count)),
+    };
+    let field = |index: usize, name: &str, min: u32, max: u32, names: &[&str], name_base: u32| {
+        parse_field(fields[index], min, max, names, name_base, "-").map_err(|e| format!("{}: {}", name, e))
+    };
+    let mut weekdays = field(4, "Day of week", 0, 7, &WEEKDAYS, 0)?;
+    // 7 is Sunday as well as 0
+    if weekdays & (1 << 7) != 0 {
+        weekdays = (weekdays & !(1 << 7)) | 1;
+    }
+    let restricted = |index: usize| !fields[index].starts_with(['*', '?']);
+    Ok(Schedule {
+        seconds,
+        minutes: field(0, "Minute", 0, 59, &[], 0)?,
+        hours: field(1, "Hour", 0, 23, &[], 0)?,
+        days: field(2, "Day of month", 1, 31, &[], 0)?,
+        last_days: 0,
+        months: field(3, "Month", 1, 12, &MONTHS, 1)?,
+        weekdays,
+        years: Vec::new(),
+        day_or: restricted(2) && restricted(4),
+    })
+}
+
+fn parse_years(text: &str) -> Result<Vec<(i32, i32, i32)>, String> {
+    if text == "*" {
+        return Ok(Vec::new());
+    }
+    let year = |text: &str| -> Result<i32, String> {
+        let value: i32 = text.parse().map_err(|_| format!("\"{}\" isn't a valid year", text))?;
+        Ok(if value < 100 { value + 2000 } else { value })
+    };
+    text.split(',')
+        .map(|item| {
+            let (base, step) = match item.split_once('/') {
+                Some((base, step)) => (base, step.parse::<i32>().ok().filter(|step| *step > 0)),
+                None => (item, Some(1)),
+            };
+            let step = step.ok_or_else(|| format!("\"{}\" has an invalid step", item))?;
+            match base.split_once("..") {
+                Some((from, to)) => Ok((year(from)?, year(to)?, step)),
+                None if base == "*" => Ok((1970, 9999, step)),
+                None if item.contains('/') => Ok((year(base)?, 9999, step)),
+                None => year(base).map(|year| (year, year, 1)),
+            }
+        })
+        .collect()
+}
+
+// A systemd OnCalendar expression: `[weekdays] [date] [time] [timezone]`,
+// or one of the shorthands. Returns the schedule and a timezone if one was
+// named.
+fn parse_calendar(expression: &str) -> Result<(Schedule, Option<String>), String> {
+    let mut tokens: Vec<String> = expression.split_whitespace().map(str::to_string).collect();
+    if let Some(first) = tokens.first() {
+        let lower = first.to_ascii_lowercase();
+        if let Some((_, expanded)) = CALENDAR_SHORTHANDS.iter().find(|(name, _)| *name == lower) {
+            let rest = tokens.split_off(1);
+            tokens = expanded.split_whitespace().map(str::to_string).chain(rest).collect();
+        }
+    }
+    if tokens.is_empty() {
+        return Err("Empty calendar expression".to_string());
+    }
+
+    let (mut weekdays, mut date, mut time, mut timezone) = (None, None, None, None);
+    for token in &tokens {
+        if token.contains(':') {
+            time = Some(token.as_str());
+        } else if token.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            match parse_field(&token.replace('-', ".."), 0, 6, &WEEKDAYS, 0, "..") {
+                Ok(mask) if weekdays.is_none() && date.is_none() && time.is_none() => weekdays = Some(mask),
+                _ => timezone = Some(token.clone()),
+            }
+        } else {
+            date = Some(token.as_str());
+        }
+    }
+
+    let date = date.unwrap_or("*-*-*");
+    let (year_month, day, from_end) = match date.split_once('~') {
+        Some((year_month, day)) => (year_month, day, true),
+        None => date
+            .rsplit_once('-')
+            .map(|(year_month, day)| (year_month, day, false))
+            .ok_or_else(|| format!("\"{}\" isn't a date; use YYYY-MM-DD with * for any", date))?,
+    };
+    let (years, month) = match year_month.split_once('-') {
+        Some((years, month)) => (parse_years(years)?, month),
+        None => (Vec::new(), year_month),
+    };
+    let months = parse_field(month, 1, 12, &[], 0, "..").map_err(|e| format!("Month: {}", e))?;
+    let day_mask = parse_field(day, 1, 31, &[], 0, "..").map_err(|e| format!("Day: {}", e))?;
+
+    let time = time.unwrap_or("00:00:00");
+    let parts: Vec<&str> = time.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return Err(format!("\"{}\" isn't a time; use HH:MM[:SS]", time));
+    }
+    // Fractional seconds are dropped
+    let seconds = parts
+        .get(2)
+        .map(|seconds| seconds.split('.').next().unwrap_or("00"))
+        .unwrap_or("00");
+
+    Ok((
+        Schedule {
+            seconds: parse_field(seconds, 0, 59, &[], 0, "..").map_err(|e| format!("Second: {}", e))?,
+            minutes: parse_field(parts[1], 0, 59, &[], 0, "..").map_err(|e| format!("Minute: {}", e))?,
+            hours: parse_field(parts[0], 0, 23, &[], 0, "..").map_err(|e| format!("Hour: {}", e))?,
+            days: if from_end { 0 } else { day_mask },
+            last_days: if from_end { day_mask } else { 0 },
+            months,
+            weekdays: weekdays.unwrap_or(bits(0, 6)),
+            years,
+            day_or: false,
+        },
+        timezone,
+    ))
+}
+
+// UTC, or a fixed offset like +02:00; named zones would need a tz database
+fn fixed_timezone(name: &str) -> Option<FixedOffset> {
+    match name.to_ascii_uppercase().as_str() {
+        "UTC" | "GMT" | "ETC/UTC" | "Z" => FixedOffset::east_opt(0),
+        _ => {
+            let sign = match name.chars().next()? {
+                '+' => 1,
+                '-' => -1,
+                _ => return None,
+            };
+            let digits: String = name[1..].chars().filter(char::is_ascii_digit).collect();
+            let (hours, minutes) = match digits.len() {
+                1 | 2 => (digits.parse::<i32>().ok()?, 0),
+                4 => (digits[..2].parse::<i32>().ok()?, digits[2..].parse::<i32>().ok()?),
+                _ => return None,
+            };
+            FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        }
+    }
+}
+
+struct Context {
+    offset: FixedOffset,
+    count: usize,
+}
+
+// Fills in the explanation and upcoming runs; the returned key orders jobs
+fn schedule_entry(
+    entry: &mut Map<String, Value>,
+    schedule: &Schedule,
+    timezone: Option<&str>,
+    context: &Context,
+    notes: &mut Vec<String>,
+) -> Option<i64> {
+    let offset = match timezone {
+        Some(name) => fixed_timezone(name).unwrap_or_else(|| {
+            notes.push(format!(
+                "Timezone {} can't be resolved offline; its runs are shown at the default offset",
+                name
+            ));
+            context.offset
+        }),
+        None => context.offset,
+    };
+    let now = Utc::now().with_timezone(&offset).naive_local();
+    let runs: Vec<_> = schedule
+        .next_runs(now, context.count)
+        .into_iter()
+        .filter_map(|run| offset.from_local_datetime(&run).single())
+        .collect();
+    entry.insert("explanation".to_string(), schedule.explain().into());
+    if let Some(timezone) = timezone {
+        entry.insert("timezone".to_string(), timezone.into());
+    }
+    entry.insert("next_run".to_string(), runs.first().map(|run| run.to_rfc3339()).into());
+    entry.insert(
+        "next_runs".to_string(),
+        runs.iter().map(|run| run.to_rfc3339()).collect::<Vec<_>>().into(),
+    );
+    runs.first().map(|run| run.timestamp())
+}
+
+// Splits off the first `count` whitespace-separated fields, keeping the rest
+// of the line (the command) as written
+fn split_fields(line: &str, count: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::new();
+    let mut rest = line.trim_start();
+    for _ in 0..count {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    Some((fields, rest))
+}
+
+fn is_username(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && text
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+fn parse_crontab(text: &str, system: Option<bool>, context: &Context) -> Value {
+    let job_lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    let is_environment = |line: &str| {
+        !line.starts_with(|c: char| c.is_ascii_digit() || c == '*' || c == '@')
+            && line.split_once('=').is_some_and(|(name, _)| {
+                !name.trim().is_empty() && name.trim().chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+    };
+    // /etc/crontab and /etc/cron.d files have a user column; guessed from
+    // `root` showing up where it would be
+    let system = system.unwrap_or_else(|| {
+        job_lines.iter().any(|(_, line)| {
+            let fields = if line.starts_with('@') { 1 } else { 5 };
+            split_fields(line, fields + 1).is_some_and(|(fields, _)| fields.last() == Some(&"root"))
+        })
+    });
+
+    let mut environment = Map::new();
+    let mut timezone: Option<String> = None;
+    let mut notes = Vec::new();
+    let mut jobs: Vec<(Option<i64>, Value)> = Vec::new();
+    for (line_number, line) in job_lines {
+        if is_environment(line) {
+            let (name, value) = line.split_once('=').unwrap_or((line, ""));
+            let (name, value) = (name.trim(), value.trim().trim_matches(['"', '\'']));
+            if name == "CRON_TZ" || name == "TZ" {
+                timezone = Some(value.to_string());
+            }
+            environment.insert(name.to_string(), value.into());
+            continue;
+        }
+        let mut entry = Map::new();
+        entry.insert("source".to_string(), "crontab".into());
+        entry.insert("line".to_string(), line_number.into());
+        let fields = if line.starts_with('@') { 1 } else { 5 };
+        let Some((mut schedule_fields, command)) = split_fields(line, fields + usize::from(system)) else {
+            entry.insert("schedule".to_string(), line.into());
+            entry.insert("error".to_string(), "Not a cron job line".into());
+            jobs.push((None, Value::Object(entry)));
+            continue;
+        };
+        if system {
+            entry.insert("user".to_string(), schedule_fields.pop().into());
+        }
+        let expression = schedule_fields.join(" ");
+        entry.insert("schedule".to_string(), expression.clone().into());
+        entry.insert("command".to_string(), command.into());
+        if system && !entry["user"].as_str().is_some_and(is_username) {
+            notes.push(format!(
+                "Line {}: the user column doesn't look like a user name",
+                line_number
+            ));
+        }
+        if expression == "@reboot" {
+            entry.insert("explanation".to_string(), "At boot".into());
+            jobs.push((None, Value::Object(entry)));
+            continue;
+        }
+        let cron = CRON_MACROS
+            .iter()
+            .find(|(name, _)| *name == expression)
+            .map(|(_, cron)| *cron)
+            .unwrap_or(&expression);
+        let key = match parse_cron(cron) {
+            Ok(schedule) => schedule_entry(&mut entry, &schedule, timezone.as_deref(), context, &mut notes),
+            Err(e) => {
+                entry.insert("error".to_string(), e.into());
+                None
+            }
+        };
+        jobs.push((key, Value::Object(entry)));
+    }
+    notes.dedup();
+    let format = if system { "system crontab" } else { "crontab" };
+    finish(
+        jobs,
+        json!({ "format": format, "environment": environment }),
+        notes,
+        context,
+    )
+}
+
+#[derive(Default)]
+struct Unit {
+    name: Option<String>,
+    description: Option<String>,
+    // (setting, value) pairs of the [Timer] section
+    timer: Vec<(String, String)>,
+    exec_start: Vec<String>,
+    has_timer: bool,
+    has_service: bool,
+}
+
+fn parse_units(text: &str) -> Vec<Unit> {
+    let mut units: Vec<Unit> = vec![Unit::default()];
+    let mut section = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        // `systemctl cat` heads each file with `# /path/to/name.timer`
+        if let Some(path) = line.strip_prefix("# /") {
+            let name = path.rsplit('/').next().unwrap_or(path).to_string();
+            if !name.ends_with(".conf") {
+                units.push(Unit {
+                    name: Some(name),
+                    ..Unit::default()
+                });
+            }
+            continue;
+        }
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|header| header.strip_suffix(']')) {
+            section = header.to_ascii_lowercase();
+            let current = units.last().expect("at least one unit");
+            // Snippets pasted back to back without file headers
+            if section == "unit" && (current.has_timer || current.has_service) {
+                units.push(Unit::default());
+            }
+            let current = units.last_mut().expect("at least one unit");
+            current.has_timer |= section == "timer";
+            current.has_service |= section == "service";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let current = units.last_mut().expect("at least one unit");
+        match section.as_str() {
+            "unit" if key.eq_ignore_ascii_case("description") => current.description = Some(value.to_string()),
+            "timer" => current.timer.push((key.to_string(), value.to_string())),
+            "service" if key.eq_ignore_ascii_case("execstart") => current
+                .exec_start
+                .push(value.trim_start_matches(['-', '@', '+', '!', ':']).to_string()),
+            _ => {}
+        }
+    }
+    units.retain(|unit| unit.has_timer || unit.has_service);
+    units
+}
+
+fn parse_systemd(text: &str, context: &Context) -> Value {
+    let units = parse_units(text);
+    let mut notes = Vec::new();
+    let mut jobs: Vec<(Option<i64>, Value)> = Vec::new();
+    for (index, unit) in units.iter().enumerate().filter(|(_, unit)| unit.has_timer) {
+        let name = unit.name.clone().unwrap_or_else(|| format!("timer {}", index + 1));
+        let setting = |key: &str| {
+            unit.timer
+                .iter()
+                .find(|(setting, _)| setting.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value.clone())
+        };
+        // The activated unit: Unit=, else the service named like the timer
+        let target = setting("Unit").or_else(|| {
+            unit.name
+                .as_deref()
+                .and_then(|name| name.strip_suffix(".timer"))
+                .map(|stem| format!("{}.service", stem))
+        });
+        let service = target
+            .as_deref()
+            .and_then(|target| units.iter().find(|other| other.name.as_deref() == Some(target)))
+            .or_else(|| {
+                units
+                    .get(index + 1)
+                    .filter(|next| next.has_service && next.name.is_none())
+            });
+        let base = json!({
+            "source": "systemd",
+            "unit": name,
+            "description": unit.description.clone().or_else(|| service.and_then(|service| service.description.clone())),
+            "activates": target,
+            "command": service.and_then(|service| service.exec_start.first().cloned()),
+            "persistent": setting("Persistent").map(|value| matches!(value.as_str(), "true" | "yes" | "1" | "on")),
+            "randomized_delay": setting("RandomizedDelaySec")
+        });
+
+        for (key, value) in &unit.timer {
+            let lower = key.to_ascii_lowercase();
+            let mut entry = base.as_object().cloned().unwrap_or_default();
+            if lower == "oncalendar" {
+                entry.insert("schedule".to_string(), value.clone().into());
+                let key = match parse_calendar(value) {
+                    Ok((schedule, timezone)) => {
+                        schedule_entry(&mut entry, &schedule, timezone.as_deref(), context, &mut notes)
+                    }
+                    Err(e) => {
+                        entry.insert("error".to_string(), e.into());
+                        None
+                    }
+                };
+                jobs.push((key, Value::Object(entry)));
+            } else if let Some((_, event)) = MONOTONIC_TIMERS.iter().find(|(name, _)| *name == lower) {
+                entry.insert("schedule".to_string(), format!("{}={}", key, value).into());
+                entry.insert("explanation".to_string(), format!("{} after {}", value, event).into());
+                jobs.push((None, Value::Object(entry)));
+            }
+        }
+    }
+    if jobs.is_empty() {
+        notes.push("No [Timer] section with OnCalendar= or a monotonic setting was found".to_string());
+    }
+    notes.dedup();
+    finish(jobs, json!({ "format": "systemd" }), notes, context)
+}
+
+fn finish(mut jobs: Vec<(Option<i64>, Value)>, mut result: Value, notes: Vec<String>, context: &Context) -> Value {
+    // Jobs without a next run (boot-relative, invalid) go last in file order
+    jobs.sort_by_key(|(key, _)| (key.is_none(), *key));
+    result["utc_offset"] = context.offset.to_string().into();
+    result["now"] = Utc::now().with_timezone(&context.offset).to_rfc3339().into();
+    result["jobs"] = jobs.into_iter().map(|(_, job)| job).collect::<Vec<_>>().into();
+    result["notes"] = notes.into();
+    result
+}
+
+fn context(count: Option<usize>, utc_offset_minutes: Option<i32>) -> Result<Context, String> {
+    let offset = match utc_offset_minutes {
+        Some(minutes) => {
+            FixedOffset::east_opt(minutes * 60).ok_or_else(|| format!("Invalid UTC offset: {}", minutes))?
+        }
+        None => Local::now().offset().fix(),
+    };
+    Ok(Context {
+        offset,
+        count: count.unwrap_or(DEFAULT_RUNS).clamp(1, MAX_RUNS),
+    })
+}
+
+fn schedules_text(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    match text.filter(|text| !text.is_empty()) {
+        Some(text) => Ok(text),
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            Ok(storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string())
+        }
+    }
+}
+
+// Every job in a crontab or in systemd timer/service units (`text`, or the
+// active document's raw content), explained and sorted by next run. `system`
+// forces the user column of /etc/crontab on or off; `count` upcoming runs
+// are listed per job at `utc_offset_minutes` (default: this machine's).
+#[tauri::command]
+pub fn summarize_schedules(
+    app: AppHandle,
+    text: Option<String>,
+    system: Option<bool>,
+    count: Option<usize>,
+    utc_offset_minutes: Option<i32>,
+) -> Result<Value, String> {
+    let text = schedules_text(&app, text)?;
+    let context = context(count, utc_offset_minutes)?;
+    let is_systemd = text.lines().any(|line| {
+        let line = line.trim();
+        line.eq_ignore_ascii_case("[timer]") || line.eq_ignore_ascii_case("[service]")
+    });
+    Ok(if is_systemd {
+        parse_systemd(&text, &context)
+    } else {
+        parse_crontab(&text, system, &context)
+    })
+}
+
+// A single cron expression (5 or 6 fields, or an @ shorthand) or systemd
+// OnCalendar expression, explained with its next runs
+#[tauri::command]
+pub fn explain_schedule(
+    expression: String,
+    count: Option<usize>,
+    utc_offset_minutes: Option<i32>,
+) -> Result<Value, String> {
+    let context = context(count, utc_offset_minutes)?;
+    let expression = expression.trim();
+    let cron = CRON_MACROS
+        .iter()
+        .find(|(name, _)| *name == expression)
+        .map(|(_, cron)| *cron)
+        .unwrap_or(expression);
+    let is_cron = (5..=6).contains(&cron.split_whitespace().count()) && !cron.contains(':');
+    let (schedule, timezone, kind) = if is_cron {
+        (parse_cron(cron)?, None, "cron")
+    } else {
+        let (schedule, timezone) = parse_calendar(expression)?;
+        (schedule, timezone, "systemd")
+    };
+    let mut entry = Map::new();
+    entry.insert("kind".to_string(), kind.into());
+    entry.insert("schedule".to_string(), expression.into());
+    let mut notes = Vec::new();
+    schedule_entry(&mut entry, &schedule, timezone.as_deref(), &context, &mut notes);
+    entry.insert("notes".to_string(), notes.into());
+    Ok(Value::Object(entry))
+}
@@ -0,0 +1,272 @@
+use rhai::{Dynamic, Engine, Scope};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+const SCRIPT_TIME_LIMIT: Duration = Duration::from_secs(10);
+const SCRIPT_MAX_OPERATIONS: u64 = 500_000_000;
+// Caps on individual values keep a runaway script from exhausting memory
+const SCRIPT_MAX_STRING_SIZE: usize = 512 * 1024 * 1024;
+const SCRIPT_MAX_COLLECTION_SIZE: usize = 10_000_000;
+
+fn scripts_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::app_data::dir(app, "scripts")
+}
+
+// A saved script as carried in a config bundle
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SavedScript {
+    pub name: String,
+    pub source: String,
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn script_file(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.rhai", name))
+}
+
+fn script_path(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    if !valid_name(name) {
+        return Err("Script names may only contain letters, digits, '-' and '_'".to_string());
+    }
+    Ok(script_file(&scripts_dir(app)?, name))
+}
+
+fn build_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(SCRIPT_MAX_OPERATIONS);
+    engine.set_max_string_size(SCRIPT_MAX_STRING_SIZE);
+    engine.set_max_array_size(SCRIPT_MAX_COLLECTION_SIZE);
+    engine.set_max_map_size(SCRIPT_MAX_COLLECTION_SIZE);
+
+    let started = Instant::now();
+    engine.on_progress(move |_| {
+        if started.elapsed() > SCRIPT_TIME_LIMIT {
+            Some(Dynamic::from("Script exceeded the time limit"))
+        } else {
+            None
+        }
+    });
+    engine
+}
+
+fn run_script_blocking(
+    app: AppHandle,
+    script: Option<String>,
+    name: Option<String>,
+    state: &AppState,
+) -> Result<String, String> {
+    let source = match (script.filter(|s| !s.trim().is_empty()), name) {
+        (Some(script), _) => script,
+        (None, Some(name)) => std::fs::read_to_string(script_path(&app, &name)?)
+            .map_err(|e| format!("Failed to load script '{}': {}", name, e))?,
+        (None, None) => return Err("No script supplied".to_string()),
+    };
+
+    // Only a handle on the stored text is taken under the lock; rhai gets its
+    // own copy once the lock is released. Scripts may also run with no content.
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw().ok();
+    let input = snapshot
+        .as_ref()
+        .map(|snapshot| snapshot.text.as_str().to_string())
+        .unwrap_or_default();
+
+    // The stored content is available to scripts as `input`; the script's final
+    // expression becomes the output
+    let mut scope = Scope::new();
+    scope.push("input", input);
+
+    let result = build_engine()
+        .eval_with_scope::<Dynamic>(&mut scope, &source)
+        .map_err(|e| format!("Script error: {}", e))?;
+    let output = if result.is_string() {
+        result.into_string().unwrap_or_default()
+    } else if result.is_unit() {
+        String::new()
+    } else {
+        result.to_string()
+    };
+
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let document = match &snapshot {
+        Some(snapshot) => storage.snapshot_document(snapshot)?,
+        None => storage.active_mut(),
+    };
+    document.edit(Slot::Formatted, output.clone());
+    Ok(output)
+}
+
+#[tauri::command]
+pub async fn run_script(
+    app: AppHandle,
+    script: Option<String>,
+    name: Option<String>,
+) -> Result<String, String> {
+    crate::run_blocking("run_script", move || {
+        run_script_blocking(app.clone(), script, name, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn save_script(app: AppHandle, name: String, script: String) -> Result<(), String> {
+    // Compile first so broken scripts aren't saved silently
+    Engine::new()
+        .compile(&script)
+        .map_err(|e| format!("Script error: {}", e))?;
+    std::fs::write(script_path(&app, &name)?, script).map_err(|e| format!("Failed to save script: {}", e))
+}
+
+#[tauri::command]
+pub fn list_scripts(app: AppHandle) -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = std::fs::read_dir(scripts_dir(&app)?)
+        .map_err(|e| format!("Failed to read scripts directory: {}", e))?
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("rhai") {
+                path.file_stem().map(|s| s.to_string_lossy().into_owned())
+            } else {
+                None
+            }
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+#[tauri::command]
+pub fn delete_script(app: AppHandle, name: String) -> Result<(), String> {
+    std::fs::remove_file(script_path(&app, &name)?).map_err(|e| format!("Failed to delete script: {}", e))
+}
+
+// Every saved script in `dir`, sorted by name
+fn read_scripts(dir: &Path) -> Result<Vec<SavedScript>, String> {
+    let mut scripts = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read scripts directory: {}", e))?
+        .flatten()
+    {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("rhai") {
+            continue;
+        }
+        let Some(name) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+            continue;
+        };
+        let source = std::fs::read_to_string(&path).map_err(|e| format!("Failed to load script '{}': {}", name, e))?;
+        scripts.push(SavedScript { name, source });
+    }
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scripts)
+}
+
+// Saves `scripts` into `dir`, replacing scripts of the same name; `overwrite`
+// deletes the other saved scripts first. Scripts with an invalid name or that
+// don't compile are skipped and listed, and are checked before anything is
+// deleted.
+fn write_scripts(dir: &Path, scripts: Vec<SavedScript>, overwrite: bool) -> Result<serde_json::Value, String> {
+    let engine = Engine::new();
+    let (scripts, skipped): (Vec<SavedScript>, Vec<SavedScript>) = scripts
+        .into_iter()
+        .partition(|script| valid_name(&script.name) && engine.compile(&script.source).is_ok());
+
+    let mut removed = 0;
+    if overwrite {
+        for script in read_scripts(dir)? {
+            std::fs::remove_file(script_file(dir, &script.name))
+                .map_err(|e| format!("Failed to delete script '{}': {}", script.name, e))?;
+            removed += 1;
+        }
+    }
+    let (mut added, mut replaced) = (0, 0);
+    for script in scripts {
+        let path = script_file(dir, &script.name);
+        if path.exists() {
+            replaced += 1;
+        } else {
+            added += 1;
+        }
+        std::fs::write(&path, script.source).map_err(|e| format!("Failed to save script '{}': {}", script.name, e))?;
+    }
+    let skipped: Vec<String> = skipped.into_iter().map(|script| script.name).collect();
+    Ok(serde_json::json!({ "added": added, "replaced": replaced, "removed": removed, "skipped": skipped }))
+}
+
+// The saved scripts, for config bundles
+pub(crate) fn shareable(app: &AppHandle) -> Result<Vec<SavedScript>, String> {
+    read_scripts(&scripts_dir(app)?)
+}
+
+// Imports saved scripts from a config bundle, merging or overwriting as
+// `write_scripts` describes
+pub(crate) fn import(app: &AppHandle, scripts: Vec<SavedScript>, overwrite: bool) -> Result<serde_json::Value, String> {
+    write_scripts(&scripts_dir(app)?, scripts, overwrite)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_scripts_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("devmate-scripts-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn script(name: &str, source: &str) -> SavedScript {
+        SavedScript {
+            name: name.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn scripts_survive_a_round_trip() {
+        let (from, to) = (temp_scripts_dir("export"), temp_scripts_dir("import"));
+        write_scripts(
+            &from,
+            vec![script("upper", "input.to_upper()"), script("len", "input.len()")],
+            false,
+        )
+        .unwrap();
+
+        // Through JSON, the way a config bundle carries them
+        let exported = serde_json::to_string(&read_scripts(&from).unwrap()).unwrap();
+        let result = write_scripts(&to, serde_json::from_str(&exported).unwrap(), false).unwrap();
+        assert_eq!(result["added"], 2);
+
+        let imported = read_scripts(&to).unwrap();
+        let names: Vec<&str> = imported.iter().map(|script| script.name.as_str()).collect();
+        assert_eq!(names, ["len", "upper"]);
+        assert_eq!(imported[1].source, "input.to_upper()");
+        for dir in [from, to] {
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn invalid_scripts_are_skipped_on_import() {
+        let dir = temp_scripts_dir("overwrite");
+        write_scripts(&dir, vec![script("keep", "1"), script("old", "2")], false).unwrap();
+
+        let incoming = vec![script("keep", "3"), script("../escape", "4"), script("broken", "let")];
+        let result = write_scripts(&dir, incoming, true).unwrap();
+        assert_eq!(result["removed"], 2);
+        assert_eq!(result["added"], 1);
+        assert_eq!(result["skipped"], serde_json::json!(["../escape", "broken"]));
+
+        let scripts = read_scripts(&dir).unwrap();
+        assert_eq!(scripts.len(), 1);
+        assert_eq!((scripts[0].name.as_str(), scripts[0].source.as_str()), ("keep", "3"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
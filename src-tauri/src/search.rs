@@ -0,0 +1,132 @@
+use regex::RegexBuilder;
+use tauri::{AppHandle, Manager};
+
+use crate::progress::{ProgressReporter, SEARCH_PROGRESS_EVENT};
+use crate::responses::{SearchMatch, SearchResult};
+use crate::text_buffer::{self, TextBuffer};
+use crate::AppState;
+
+const DEFAULT_MAX_RESULTS: usize = 500;
+const MAX_RESULTS_LIMIT: usize = 10_000;
+// Bytes of surrounding text kept on each side of a match in its snippet
+const SNIPPET_CONTEXT: usize = 60;
+
+fn match_entry(content: &TextBuffer, start: usize, end: usize) -> SearchMatch {
+    let line = content.line_of_offset(start);
+    let line_start = content.line_start_offset(line).unwrap_or(0);
+    let line_end = content[line_start..]
+        .find('\n')
+        .map(|i| line_start + i)
+        .unwrap_or(content.len());
+
+    // Long lines (minified JSON, logs) are cut down to a window around the match
+    let snippet_start = text_buffer::grapheme_floor(content, start.saturating_sub(SNIPPET_CONTEXT).max(line_start));
+    let snippet_end = text_buffer::grapheme_floor(content, (end + SNIPPET_CONTEXT).min(line_end)).max(end);
+    let snippet = content[snippet_start..snippet_end].trim_end_matches('\r');
+
+    SearchMatch {
+        start,
+        end,
+        line: line + 1,
+        column: content[line_start..start].chars().count() + 1,
+        text: content[start..end].to_string(),
+        snippet: snippet.to_string(),
+        snippet_match_start: start - snippet_start,
+        snippet_match_end: (end - snippet_start).min(snippet.len()),
+    }
+}
+
+fn search_content_blocking(
+    app: &AppHandle,
+    query: String,
+    is_regex: bool,
+    case_sensitive: bool,
+    max_results: Option<usize>,
+    after_offset: Option<usize>,
+    content_type: Option<String>,
+) -> Result<SearchResult, String> {
+    if query.is_empty() {
+        return Err("Empty search query".to_string());
+    }
+    let pattern = if is_regex { query.clone() } else { regex::escape(&query) };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|e| format!("Invalid regex: {}", e))?;
+    let max_results = max_results.unwrap_or(DEFAULT_MAX_RESULTS).clamp(1, MAX_RESULTS_LIMIT);
+
+    // Only the buffer handle is cloned, so the storage lock is released before
+    // the scan and other commands aren't held up by a long search
+    let content_type = content_type.unwrap_or_else(|| "raw".to_string());
+    let content = {
+        let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        match content_type.as_str() {
+            "raw" => storage.active().raw_content.clone(),
+            "formatted" => storage.active().formatted_content.clone(),
+            _ => return Err("Invalid content type".to_string()),
+        }
+        .ok_or_else(|| "No content stored".to_string())?
+    };
+
+    let mut reporter = ProgressReporter::new(
+        app,
+        SEARCH_PROGRESS_EVENT,
+        content.len() as u64,
+        serde_json::json!({ "query": query }),
+    );
+
+    let mut matches = Vec::new();
+    let mut position = text_buffer::grapheme_floor(&content, after_offset.unwrap_or(0));
+    let mut has_more = false;
+    while let Some(found) = regex.find_at(&content, position) {
+        if matches.len() == max_results {
+            has_more = true;
+            break;
+        }
+        matches.push(match_entry(&content, found.start(), found.end()));
+        reporter.update(found.end() as u64);
+
+        // Step past empty matches so patterns like `^` can't loop forever
+        position = if found.end() > found.start() {
+            found.end()
+        } else if found.end() < content.len() {
+            text_buffer::next_grapheme_boundary(&content, found.end())
+        } else {
+            break;
+        };
+    }
+    reporter.update(content.len() as u64);
+
+    Ok(SearchResult {
+        content_type,
+        match_count: matches.len(),
+        matches,
+        has_more,
+        next_offset: has_more.then_some(position),
+    })
+}
+
+#[tauri::command]
+pub async fn search_content(
+    app: AppHandle,
+    query: String,
+    is_regex: bool,
+    case_sensitive: bool,
+    max_results: Option<usize>,
+    after_offset: Option<usize>,
+    content_type: Option<String>,
+) -> Result<SearchResult, String> {
+    crate::run_blocking("search_content", move || {
+        search_content_blocking(
+            &app,
+            query,
+            is_regex,
+            case_sensitive,
+            max_results,
+            after_offset,
+            content_type,
+        )
+    })
+    .await
+}
@@ -0,0 +1,605 @@
+// Semantic versions (semver 2.0.0) and the range expressions package managers
+// put in front of them. npm and Cargo agree on `^`, `~`, wildcards and
+// comparison operators; they differ on a bare `1.2.3` (exact in npm, caret in
+// Cargo) and Cargo joins comparators with commas where npm uses spaces. Both
+// spellings are accepted; `dialect` only decides what a bare version means.
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::fmt;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+#[derive(Clone, PartialEq, Eq)]
+enum Identifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl Ord for Identifier {
+    // Numeric identifiers sort before alphanumeric ones
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::Alphanumeric(_)) => Ordering::Less,
+            (Identifier::Alphanumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::Alphanumeric(a), Identifier::Alphanumeric(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(value) => write!(f, "{}", value),
+            Identifier::Alphanumeric(value) => f.write_str(value),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Vec<Identifier>,
+    build: String,
+}
+
+impl Version {
+    fn new(major: u64, minor: u64, patch: u64) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+            pre: Vec::new(),
+            build: String::new(),
+        }
+    }
+
+    // The lowest version with this core: x.y.z-0 sorts before every other
+    // prerelease of x.y.z, so `< x.y.z-0` excludes them all
+    fn floor(major: u64, minor: u64, patch: u64) -> Version {
+        Version {
+            pre: vec![Identifier::Numeric(0)],
+            ..Version::new(major, minor, patch)
+        }
+    }
+
+    fn core(&self) -> (u64, u64, u64) {
+        (self.major, self.minor, self.patch)
+    }
+
+    fn describe(&self) -> Value {
+        json!({
+            "version": self.to_string(),
+            "major": self.major,
+            "minor": self.minor,
+            "patch": self.patch,
+            "prerelease": self.pre.iter().map(|identifier| match identifier {
+                Identifier::Numeric(value) => json!(value),
+                Identifier::Alphanumeric(value) => json!(value),
+            }).collect::<Vec<_>>(),
+            "build": (!self.build.is_empty()).then_some(&self.build),
+            "stable": self.pre.is_empty() && self.major > 0
+        })
+    }
+}
+
+// Precedence ignores build metadata; a prerelease sorts before its release
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core()
+            .cmp(&other.core())
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            let pre: Vec<String> = self.pre.iter().map(Identifier::to_string).collect();
+            write!(f, "-{}", pre.join("."))?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+fn numeric_part(part: &str, name: &str) -> Result<u64, String> {
+    if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("The {} version must be a number, not \"{}\"", name, part));
+    }
+    if part.len() > 1 && part.starts_with('0') {
+        return Err(format!("The {} version {} has a leading zero", name, part));
+    }
+    part.parse()
+        .map_err(|_| format!("The {} version {} is too large", name, part))
+}
+
+fn identifiers(text: &str, label: &str) -> Result<Vec<String>, String> {
+    text.split('.')
+        .map(|identifier| {
+            if identifier.is_empty() || !identifier.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+                Err(format!("Invalid {} identifier \"{}\"", label, identifier))
+            } else {
+                Ok(identifier.to_string())
+            }
+        })
+        .collect()
+}
+
+// A full version; a leading `v` or `=` is tolerated as npm does
+pub fn parse_version(text: &str) -> Result<Version, String> {
+    let text = text.trim();
+    let text = text.strip_prefix(['v', 'V', '=']).unwrap_or(text).trim_start();
+    if text.is_empty() {
+        return Err("Empty version".to_string());
+    }
+    let (rest, build) = match text.split_once('+') {
+        Some((rest, build)) => (rest, identifiers(build, "build")?.join(".")),
+        None => (text, String::new()),
+    };
+    let (core, pre) = match rest.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (rest, None),
+    };
+    let parts: Vec<&str> = core.split('.').collect();
+    if parts.len() != 3 {
+        return Err(format!("{} isn't MAJOR.MINOR.PATCH", text));
+    }
+    let mut version = Version::new(
+        numeric_part(parts[0], "major")?,
+        numeric_part(parts[1], "minor")?,
+        numeric_part(parts[2], "patch")?,
+    );
+    version.build = build;
+    if let Some(pre) = pre {
+        for identifier in identifiers(pre, "prerelease")? {
+            version.pre.push(if identifier.bytes().all(|b| b.is_ascii_digit()) {
+                if identifier.len() > 1 && identifier.starts_with('0') {
+                    return Err(format!("The prerelease identifier {} has a leading zero", identifier));
+                }
+                Identifier::Numeric(identifier.parse().map_err(|_| format!("{} is too large", identifier))?)
+            } else {
+                Identifier::Alphanumeric(identifier)
+            });
+        }
+    }
+    Ok(version)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Exact => "=",
+            Op::Greater => ">",
+            Op::GreaterEq => ">=",
+            Op::Less => "<",
+            Op::LessEq => "<=",
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+impl Comparator {
+    fn matches(&self, version: &Version) -> bool {
+        let ordering = version.cmp(&self.version);
+        match self.op {
+            Op::Exact => ordering == Ordering::Equal,
+            Op::Greater => ordering == Ordering::Greater,
+            Op::GreaterEq => ordering != Ordering::Less,
+            Op::Less => ordering == Ordering::Less,
+            Op::LessEq => ordering != Ordering::Greater,
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.op.symbol(), self.version)
+    }
+}
+
+// A version with missing or wildcard parts: `1`, `1.2`, `1.x`, `*`
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<Identifier>,
+}
+
+impl Partial {
+    fn parse(text: &str) -> Result<Partial, String> {
+        let text = text.strip_prefix(['v', 'V']).unwrap_or(text);
+        let text = text.split_once('+').map(|(text, _)| text).unwrap_or(text);
+        let (core, pre) = match text.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (text, None),
+        };
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() > 3 {
+            return Err(format!("{} has more than three parts", text));
+        }
+        let mut numbers = [None; 3];
+        let mut wildcard = false;
+        for (index, part) in parts.iter().enumerate() {
+            if matches!(*part, "x" | "X" | "*" | "") {
+                wildcard = true;
+            } else if wildcard {
+                return Err(format!("{} has a number after a wildcard", text));
+            } else {
+                numbers[index] = Some(numeric_part(part, ["major", "minor", "patch"][index])?);
+            }
+        }
+        let pre = match pre {
+            Some(pre) if numbers[2].is_some() => parse_version(&format!("0.0.0-{}", pre))?.pre,
+            Some(_) => return Err(format!("{} has a prerelease without a full version", text)),
+            None => Vec::new(),
+        };
+        Ok(Partial {
+            major: numbers[0],
+            minor: numbers[1],
+            patch: numbers[2],
+            pre,
+        })
+    }
+
+    fn lower(&self) -> Version {
+        Version {
+            pre: self.pre.clone(),
+            ..Version::new(
+                self.major.unwrap_or(0),
+                self.minor.unwrap_or(0),
+                self.patch.unwrap_or(0),
+            )
+        }
+    }
+
+    // The first version past everything this partial covers (`1.2` -> 1.3.0-0)
+    fn upper(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (None, _, _) => None,
+            (Some(major), None, _) => Some(Version::floor(major + 1, 0, 0)),
+            (Some(major), Some(minor), None) => Some(Version::floor(major, minor + 1, 0)),
+            (Some(_), Some(_), Some(_)) => None,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.patch.is_some()
+    }
+}
+
+fn range_between(lower: Version, upper: Option<Version>) -> Vec<Comparator> {
+    let mut comparators = vec![Comparator {
+        op: Op::GreaterEq,
+        version: lower,
+    }];
+    if let Some(upper) = upper {
+        comparators.push(Comparator {
+            op: Op::Less,
+            version: upper,
+        });
+    }
+    comparators
+}
+
+fn caret(partial: &Partial) -> Vec<Comparator> {
+    // The leftmost non-zero part is the one that may not change
+    let upper = match (partial.major, partial.minor, partial.patch) {
+        (None, _, _) => None,
+        (Some(major), _, _) if major > 0 => Some(Version::floor(major + 1, 0, 0)),
+        (Some(0), None, _) => Some(Version::floor(1, 0, 0)),
+        (Some(0), Some(minor), _) if minor > 0 => Some(Version::floor(0, minor + 1, 0)),
+        (Some(0), Some(0), None) => Some(Version::floor(0, 1, 0)),
+        (Some(0), Some(0), Some(patch)) => Some(Version::floor(0, 0, patch + 1)),
+        _ => partial.upper(),
+    };
+    range_between(partial.lower(), upper)
+}
+
+fn tilde(partial: &Partial) -> Vec<Comparator> {
+    let upper = match (partial.major, partial.minor) {
+        (Some(major), Some(minor)) => Some(Version::floor(major, minor + 1, 0)),
+        _ => partial.upper(),
+    };
+    range_between(partial.lower(), upper)
+}
+
+fn comparator(op: &str, partial: &Partial, dialect: &str) -> Vec<Comparator> {
+    let any = || range_between(Version::new(0, 0, 0), None);
+    match op {
+        "^" => caret(partial),
+        "~" | "~>" => tilde(partial),
+        // Wildcards widen a comparison to the whole span they cover
+        ">" => match partial.upper() {
+            Some(upper) => range_between(upper, None),
+            None if partial.major.is_none() => vec![Comparator {
+                op: Op::Less,
+                version: Version::floor(0, 0, 0),
+            }],
+            None => vec![Comparator {
+                op: Op::Greater,
+                version: partial.lower(),
+            }],
+        },
+        ">=" => range_between(partial.lower(), None),
+        "<" => match partial.major {
+            None => vec![Comparator {
+                op: Op::Less,
+                version: Version::floor(0, 0, 0),
+            }],
+            Some(_) if partial.is_full() => vec![Comparator {
+                op: Op::Less,
+                version: partial.lower(),
+            }],
+            Some(_) => vec![Comparator {
+                op: Op::Less,
+                version: Version {
+                    pre: vec![Identifier::Numeric(0)],
+                    ..partial.lower()
+                },
+            }],
+        },
+        "<=" => match (partial.major, partial.upper()) {
+            (None, _) => any(),
+            (_, Some(upper)) => vec![Comparator {
+                op: Op::Less,
+                version: upper,
+            }],
+            (_, None) => vec![Comparator {
+                op: Op::LessEq,
+                version: partial.lower(),
+            }],
+        },
+        // A bare full version is exact in npm and a caret requirement in Cargo
+        "" if partial.is_full() && dialect == "cargo" => caret(partial),
+        _ if partial.major.is_none() => any(),
+        _ if partial.is_full() => vec![Comparator {
+            op: Op::Exact,
+            version: partial.lower(),
+        }],
+        _ => range_between(partial.lower(), partial.upper()),
+    }
+}
+
+// Alternatives joined by `||`, each a set of comparators that must all hold
+pub struct Range {
+    sets: Vec<Vec<Comparator>>,
+}
+
+fn split_operator(token: &str) -> (&str, &str) {
+    for op in ["~>", ">=", "<=", "^", "~", ">", "<", "="] {
+        if let Some(rest) = token.strip_prefix(op) {
+            return (op, rest.trim_start());
+        }
+    }
+    ("", token)
+}
+
+pub fn parse_range(text: &str, dialect: &str) -> Result<Range, String> {
+    if !matches!(dialect, "npm" | "cargo") {
+        return Err(format!("Unknown range dialect: {}; use npm or cargo", dialect));
+    }
+    let mut sets = Vec::new();
+    for alternative in text.split("||") {
+        // Commas (Cargo) and spaces (npm) both separate comparators; an
+        // operator followed by a space belongs to the next token
+        let mut tokens: Vec<String> = Vec::new();
+        let mut pending_op: Option<String> = None;
+        for token in alternative.split([',', ' ', '\t']).filter(|token| !token.is_empty()) {
+            match pending_op.take() {
+                Some(op) => tokens.push(format!("{}{}", op, token)),
+                None if split_operator(token).1.is_empty() && token != "-" => pending_op = Some(token.to_string()),
+                None => tokens.push(token.to_string()),
+            }
+        }
+        if let Some(op) = pending_op {
+            return Err(format!("Operator {} has no version after it", op));
+        }
+
+        let mut set = Vec::new();
+        let mut index = 0;
+        while index < tokens.len() {
+            // Hyphen ranges: `1.2 - 2.3.4` is >=1.2.0 <=2.3.4
+            if tokens.get(index + 1).map(String::as_str) == Some("-") {
+                let to = tokens
+                    .get(index + 2)
+                    .ok_or_else(|| format!("Hyphen range {} - has no upper end", tokens[index]))?;
+                let (from, to) = (Partial::parse(&tokens[index])?, Partial::parse(to)?);
+                set.push(Comparator {
+                    op: Op::GreaterEq,
+                    version: from.lower(),
+                });
+                set.extend(comparator("<=", &to, dialect));
+                index += 3;
+                continue;
+            }
+            if tokens[index] == "-" {
+                return Err("Hyphen range without a lower end".to_string());
+            }
+            let (op, version) = split_operator(&tokens[index]);
+            set.extend(comparator(op, &Partial::parse(version)?, dialect));
+            index += 1;
+        }
+        if set.is_empty() {
+            // An empty alternative (`""`, `*`) allows any version
+            set.push(Comparator {
+                op: Op::GreaterEq,
+                version: Version::new(0, 0, 0),
+            });
+        }
+        sets.push(set);
+    }
+    Ok(Range { sets })
+}
+
+impl Range {
+    // A prerelease only satisfies a set when one of its comparators names a
+    // prerelease of the same MAJOR.MINOR.PATCH, so `^1.2.0` doesn't pick up
+    // 1.5.0-beta but `>=1.5.0-alpha` does
+    pub fn satisfies(&self, version: &Version) -> bool {
+        self.sets.iter().any(|set| {
+            set.iter().all(|comparator| comparator.matches(version))
+                && (version.pre.is_empty()
+                    || set.iter().any(|comparator| {
+                        !comparator.version.pre.is_empty() && comparator.version.core() == version.core()
+                    }))
+        })
+    }
+
+    fn normalized(&self) -> String {
+        self.sets
+            .iter()
+            .map(|set| set.iter().map(Comparator::to_string).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join(" || ")
+    }
+}
+
+fn difference(a: &Version, b: &Version) -> Option<&'static str> {
+    if a.major != b.major {
+        Some("major")
+    } else if a.minor != b.minor {
+        Some("minor")
+    } else if a.patch != b.patch {
+        Some("patch")
+    } else if a.pre != b.pre {
+        Some("prerelease")
+    } else if a.build != b.build {
+        Some("build")
+    } else {
+        None
+    }
+}
+
+fn versions_text(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    match text.filter(|text| !text.is_empty()) {
+        Some(text) => Ok(text),
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            Ok(storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string())
+        }
+    }
+}
+
+// The parts of a semantic version
+#[tauri::command]
+pub fn parse_semver(version: String) -> Result<Value, String> {
+    Ok(parse_version(&version)?.describe())
+}
+
+// Orders two versions by semver precedence: `ordering` is -1, 0 or 1 for
+// a < b, a == b, a > b, and `difference` names the most significant part
+// that differs
+#[tauri::command]
+pub fn compare_semver(a: String, b: String) -> Result<Value, String> {
+    let (left, right) = (parse_version(&a)?, parse_version(&b)?);
+    let ordering = left.cmp(&right);
+    Ok(json!({
+        "a": left.describe(),
+        "b": right.describe(),
+        "ordering": ordering as i8,
+        "relation": match ordering {
+            Ordering::Less => "<",
+            Ordering::Equal => "==",
+            Ordering::Greater => ">",
+        },
+        "difference": difference(&left, &right)
+    }))
+}
+
+// Checks `versions` (or one version per line of `text`, the active document
+// when both are empty) against an npm- or Cargo-style `range`. The report has
+// the range as plain comparators, each version's verdict, and the highest and
+// lowest versions that satisfy it.
+#[tauri::command]
+pub fn check_semver_range(
+    app: AppHandle,
+    range: String,
+    versions: Option<Vec<String>>,
+    text: Option<String>,
+    dialect: Option<String>,
+) -> Result<Value, String> {
+    let dialect = dialect.unwrap_or_else(|| "npm".to_string());
+    let parsed_range = parse_range(&range, &dialect)?;
+    let versions = match versions.filter(|versions| !versions.is_empty()) {
+        Some(versions) => versions,
+        None => versions_text(&app, text)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect(),
+    };
+
+    let mut satisfying: Vec<Version> = Vec::new();
+    let mut invalid = 0;
+    let results: Vec<Value> = versions
+        .iter()
+        .map(|text| match parse_version(text) {
+            Ok(version) => {
+                let satisfies = parsed_range.satisfies(&version);
+                if satisfies {
+                    satisfying.push(version.clone());
+                }
+                json!({ "version": text, "normalized": version.to_string(), "satisfies": satisfies })
+            }
+            Err(e) => {
+                invalid += 1;
+                json!({ "version": text, "satisfies": false, "error": e })
+            }
+        })
+        .collect();
+
+    Ok(json!({
+        "range": range,
+        "dialect": dialect,
+        "normalized": parsed_range.normalized(),
+        "versions": results,
+        "satisfying": satisfying.len(),
+        "invalid": invalid,
+        "max_satisfying": satisfying.iter().max().map(Version::to_string),
+        "min_satisfying": satisfying.iter().min().map(Version::to_string)
+    }))
+}
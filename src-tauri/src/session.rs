@@ -0,0 +1,206 @@
+// Open documents are written to `session.json` on exit and loaded again on
+// launch. Content counts against a size budget; documents that don't fit are
+// restored empty (name, format and view state only).
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_data;
+use crate::{AppState, ContentStorage, Document};
+
+const SESSION_FILE: &str = "session.json";
+const SESSION_VERSION: u32 = 1;
+const DEFAULT_MAX_BYTES: u64 = 20 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionSettings {
+    pub enabled: bool,
+    pub max_bytes: u64,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        SessionSettings {
+            enabled: true,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedDocument {
+    id: u64,
+    name: String,
+    format_type: Option<String>,
+    #[serde(default)]
+    view_state: serde_json::Value,
+    raw_content: Option<String>,
+    formatted_content: Option<String>,
+    // Set when content was left out because of the size budget
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedSession {
+    version: u32,
+    #[serde(default)]
+    settings: SessionSettings,
+    active_id: u64,
+    #[serde(default)]
+    documents: Vec<SavedDocument>,
+}
+
+fn snapshot(storage: &ContentStorage, settings: &SessionSettings) -> SavedSession {
+    let mut budget = settings.max_bytes;
+    let mut take = |text: Option<&str>| -> (Option<String>, bool) {
+        match text {
+            Some(text) if (text.len() as u64) <= budget => {
+                budget -= text.len() as u64;
+                (Some(text.to_string()), false)
+            }
+            Some(_) => (None, true),
+            None => (None, false),
+        }
+    };
+
+    let documents = if settings.enabled {
+        storage
+            .documents
+            .iter()
+            .map(|(id, document)| {
+                let (raw_content, raw_truncated) = take(document.raw_content.as_deref());
+                let (formatted_content, formatted_truncated) = take(document.formatted_content.as_deref());
+                SavedDocument {
+                    id: *id,
+                    name: document.name.clone(),
+                    format_type: document.format_type.clone(),
+                    view_state: document.view_state.clone(),
+                    raw_content,
+                    formatted_content,
+                    truncated: raw_truncated || formatted_truncated,
+                }
+            })
+            .collect()
+    } else {
+        // Opting out still writes the file so the choice itself is remembered
+        Vec::new()
+    };
+
+    SavedSession {
+        version: SESSION_VERSION,
+        settings: settings.clone(),
+        active_id: storage.active_id,
+        documents,
+    }
+}
+
+pub fn save_session_to_disk(app: &AppHandle) -> Result<(), String> {
+    let settings = app
+        .state::<Mutex<SessionSettings>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let session = {
+        let state = app.state::<AppState>();
+        let storage = state.lock().map_err(|e| e.to_string())?;
+        snapshot(&storage, &settings)
+    };
+
+    app_data::save(app, SESSION_FILE, &session, "session")
+}
+
+// Called from setup; a missing or unreadable session just starts fresh
+pub fn restore_session(app: &AppHandle) {
+    let saved: Option<SavedSession> = app_data::load(app, SESSION_FILE)
+        .filter(|session: &SavedSession| session.version == SESSION_VERSION);
+
+    let Some(saved) = saved else {
+        app.manage(Mutex::new(SessionSettings::default()));
+        return;
+    };
+    app.manage(Mutex::new(saved.settings.clone()));
+    if !saved.settings.enabled || saved.documents.is_empty() {
+        return;
+    }
+
+    let mut documents = BTreeMap::new();
+    for saved_document in saved.documents {
+        documents.insert(
+            saved_document.id,
+            Document {
+                name: saved_document.name,
+                format_type: saved_document.format_type,
+                view_state: saved_document.view_state,
+                raw_content: saved_document.raw_content.map(Into::into),
+                formatted_content: saved_document.formatted_content.map(Into::into),
+                ..Document::default()
+            },
+        );
+    }
+    let next_id = documents.keys().max().copied().unwrap_or(0) + 1;
+    let active_id = if documents.contains_key(&saved.active_id) {
+        saved.active_id
+    } else {
+        *documents.keys().next().expect("documents is not empty")
+    };
+
+    if let Ok(mut storage) = app.state::<AppState>().lock() {
+        *storage = ContentStorage {
+            documents,
+            active_id,
+            next_id,
+            format_cache: Default::default(),
+        };
+    }
+}
+
+// The UI reports the active format and its view state (scroll positions, ...)
+// so they can be restored with the document
+#[tauri::command]
+pub fn update_document_view(
+    id: Option<u64>,
+    format_type: Option<String>,
+    view_state: Option<serde_json::Value>,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let id = id.unwrap_or(storage.active_id);
+    let document = storage
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| format!("Document {} not found", id))?;
+    if format_type.is_some() {
+        document.format_type = format_type;
+    }
+    if let Some(view_state) = view_state {
+        document.view_state = view_state;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_session_settings(settings: State<Mutex<SessionSettings>>) -> Result<SessionSettings, String> {
+    Ok(settings.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub fn set_session_settings(
+    enabled: bool,
+    max_bytes: Option<u64>,
+    settings: State<Mutex<SessionSettings>>,
+) -> Result<SessionSettings, String> {
+    let mut settings = settings.lock().map_err(|e| e.to_string())?;
+    settings.enabled = enabled;
+    if let Some(max_bytes) = max_bytes {
+        settings.max_bytes = max_bytes;
+    }
+    Ok(settings.clone())
+}
+
+// Save immediately instead of waiting for exit
+#[tauri::command]
+pub async fn save_session(app: AppHandle) -> Result<(), String> {
+    crate::run_blocking("save_session", move || save_session_to_disk(&app)).await
+}
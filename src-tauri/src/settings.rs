@@ -0,0 +1,304 @@
+// User preferences, kept in `settings.json` next to the session. Missing or
+// unknown fields fall back to the defaults, so older files keep loading.
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_data;
+use crate::formatters::{FormatOptions, QuoteStyle};
+use crate::history::HistoryDb;
+use crate::AppState;
+
+const SETTINGS_FILE: &str = "settings.json";
+const MIN_CHUNK_SIZE: usize = 1024;
+const MIN_LARGE_FILE_THRESHOLD: u64 = 1024 * 1024;
+const THEMES: &[&str] = &["system", "light", "dark"];
+// Paths on this machine; left out of shared config bundles
+const LOCAL_SETTINGS: &[&str] = &["geoip_database", "asn_database"];
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    // Defaults for the per-call format options (see formatters::FormatOptions)
+    pub indent_width: usize,
+    pub use_tabs: bool,
+    pub sort_keys: bool,
+    pub line_width: usize,
+    pub quote_style: QuoteStyle,
+    // Formatter used when a command is given no format type
+    pub default_format: String,
+    // Characters per chunk when a chunk command is called without a size
+    pub chunk_size: usize,
+    // Files above this many bytes are loaded by the backend instead of the webview
+    pub large_file_threshold: u64,
+    // Live transforms of inputs up to this many bytes run inline on every
+    // change; larger ones become cancellable jobs
+    pub live_transform_threshold: u64,
+    pub theme: String,
+    // History older than this many days is purged; None keeps everything
+    pub history_retention_days: Option<u32>,
+    // Keep each formatted input (up to 1 MB) in history so it can be re-run;
+    // otherwise only a hash and a masked preview are recorded
+    pub history_store_inputs: bool,
+    // MaxMind-format (.mmdb) databases for IP lookups: a City or Country
+    // database, and an ASN database. Lookups are offline and need at least one.
+    pub geoip_database: Option<String>,
+    pub asn_database: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            indent_width: FormatOptions::DEFAULT.indent_width,
+            use_tabs: FormatOptions::DEFAULT.use_tabs,
+            sort_keys: FormatOptions::DEFAULT.sort_keys,
+            line_width: FormatOptions::DEFAULT.line_width,
+            quote_style: FormatOptions::DEFAULT.quote_style,
+            default_format: "json".to_string(),
+            chunk_size: 50_000,
+            large_file_threshold: 100 * 1024 * 1024,
+            live_transform_threshold: 256 * 1024,
+            theme: "system".to_string(),
+            history_retention_days: Some(30),
+            history_store_inputs: false,
+            geoip_database: None,
+            asn_database: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn format_options(&self) -> FormatOptions {
+        FormatOptions {
+            indent_width: self.indent_width,
+            use_tabs: self.use_tabs,
+            sort_keys: self.sort_keys,
+            line_width: self.line_width,
+            quote_style: self.quote_style,
+        }
+    }
+
+    fn validate(&self, app: &AppHandle) -> Result<(), String> {
+        self.format_options().validate()?;
+        if app
+            .state::<crate::formatters::FormatterRegistry>()
+            .get(&self.default_format)
+            .is_none()
+        {
+            return Err(format!("Unknown default format {}", self.default_format));
+        }
+        if self.chunk_size < MIN_CHUNK_SIZE {
+            return Err(format!("Chunk size must be at least {} characters", MIN_CHUNK_SIZE));
+        }
+        if self.large_file_threshold < MIN_LARGE_FILE_THRESHOLD {
+            return Err(format!(
+                "Large file threshold must be at least {} bytes",
+                MIN_LARGE_FILE_THRESHOLD
+            ));
+        }
+        if !THEMES.contains(&self.theme.as_str()) {
+            return Err(format!("Theme must be one of {}", THEMES.join(", ")));
+        }
+        for path in [&self.geoip_database, &self.asn_database].into_iter().flatten().filter(|path| !path.is_empty()) {
+            if !std::path::Path::new(path).is_file() {
+                return Err(format!("Database file not found: {}", path));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn save(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    app_data::write(app, SETTINGS_FILE, &json, "settings")
+}
+
+// Pushes settings into the modules that don't read them on demand
+fn apply(app: &AppHandle, settings: &Settings, previous: Option<&Settings>) {
+    crate::formatters::set_default_options(settings.format_options());
+    if previous.is_some_and(|previous| previous.format_options() != settings.format_options()) {
+        // Cached output was laid out with the old options
+        if let Ok(mut storage) = app.state::<AppState>().inner().lock() {
+            storage.format_cache.clear();
+        }
+    }
+    if let (Some(days), Some(db)) = (settings.history_retention_days, app.try_state::<HistoryDb>()) {
+        if let Err(e) = crate::history::purge(&db, Some(days)) {
+            eprintln!("{}", e);
+        }
+    }
+    if !settings.history_store_inputs {
+        // Also covers databases written before inputs became opt-in
+        if let Some(db) = app.try_state::<HistoryDb>() {
+            if let Err(e) = crate::history::forget_inputs(&db) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+// Snapshot of the current settings; cheap enough to call per operation
+pub fn current(app: &AppHandle) -> Settings {
+    app.try_state::<Mutex<Settings>>()
+        .and_then(|settings| settings.lock().ok().map(|settings| settings.clone()))
+        .unwrap_or_default()
+}
+
+// `format_type`, or the default format when it's empty
+pub fn resolve_format(app: &AppHandle, format_type: String) -> String {
+    if format_type.is_empty() {
+        current(app).default_format
+    } else {
+        format_type
+    }
+}
+
+// The format options for one call: `options` (any subset of the fields)
+// over the defaults from the settings
+pub fn resolve_format_options(app: &AppHandle, options: Option<serde_json::Value>) -> Result<FormatOptions, String> {
+    let defaults = current(app).format_options();
+    let Some(options) = options.filter(|options| !options.is_null()) else {
+        return Ok(defaults);
+    };
+    let serde_json::Value::Object(options) = options else {
+        return Err("Format options must be an object".to_string());
+    };
+    let mut merged = serde_json::to_value(defaults).map_err(|e| e.to_string())?;
+    for (key, value) in options {
+        match merged.get_mut(&key) {
+            Some(field) => *field = value,
+            None => return Err(format!("Unknown format option {}", key)),
+        }
+    }
+    let resolved: FormatOptions =
+        serde_json::from_value(merged).map_err(|e| format!("Invalid format options: {}", e))?;
+    resolved.validate()?;
+    Ok(resolved)
+}
+
+// Called from setup once the formatter registry and history are in place; an
+// unreadable or invalid file is backed up and falls back to the defaults
+pub fn load(app: &AppHandle) {
+    let settings = match app_data::load::<Settings>(app, SETTINGS_FILE) {
+        Some(settings) => match settings.validate(app) {
+            Ok(()) => settings,
+            Err(e) => {
+                eprintln!("Ignoring saved settings: {}", e);
+                app_data::back_up(app, SETTINGS_FILE);
+                Settings::default()
+            }
+        },
+        None => Settings::default(),
+    };
+    apply(app, &settings, None);
+    app.manage(Mutex::new(settings));
+}
+
+// The settings worth sharing with another machine, for config bundles
+pub(crate) fn portable(app: &AppHandle) -> serde_json::Map<String, serde_json::Value> {
+    let mut values = match serde_json::to_value(current(app)) {
+        Ok(serde_json::Value::Object(values)) => values,
+        _ => serde_json::Map::new(),
+    };
+    for key in LOCAL_SETTINGS {
+        values.remove(*key);
+    }
+    values
+}
+
+// Applies the settings from a config bundle and returns the names of those
+// that changed. Unknown fields (from a newer version) are skipped. With
+// `overwrite`, settings the bundle leaves out go back to their defaults; the
+// local ones are kept either way.
+pub(crate) fn import(
+    app: &AppHandle,
+    values: serde_json::Map<String, serde_json::Value>,
+    overwrite: bool,
+) -> Result<Vec<String>, String> {
+    let settings = app.state::<Mutex<Settings>>();
+    let mut stored = settings.lock().map_err(|e| e.to_string())?;
+    let current = serde_json::to_value(&*stored).map_err(|e| e.to_string())?;
+    let mut merged = if overwrite {
+        serde_json::to_value(Settings::default()).map_err(|e| e.to_string())?
+    } else {
+        current.clone()
+    };
+    for key in LOCAL_SETTINGS {
+        merged[*key] = current[*key].clone();
+    }
+    for (key, value) in values {
+        if LOCAL_SETTINGS.contains(&key.as_str()) {
+            continue;
+        }
+        if let Some(field) = merged.get_mut(&key) {
+            *field = value;
+        }
+    }
+    let updated: Settings = serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+    updated.validate(app)?;
+    let updated_value = serde_json::to_value(&updated).map_err(|e| e.to_string())?;
+    let changed: Vec<String> = updated_value
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, value)| current.get(key.as_str()) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    if changed.is_empty() {
+        return Ok(changed);
+    }
+
+    save(app, &updated)?;
+    let previous = std::mem::replace(&mut *stored, updated.clone());
+    drop(stored);
+    apply(app, &updated, Some(&previous));
+    Ok(changed)
+}
+
+#[tauri::command]
+pub fn get_settings(settings: State<Mutex<Settings>>) -> Result<Settings, String> {
+    Ok(settings.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// Merges `changes` (any subset of the settings fields) into the current
+// settings, saves them and returns the result. Invalid values are rejected
+// without changing anything.
+#[tauri::command]
+pub fn update_settings(
+    app: AppHandle,
+    changes: serde_json::Value,
+    settings: State<Mutex<Settings>>,
+) -> Result<Settings, String> {
+    let serde_json::Value::Object(changes) = changes else {
+        return Err("Settings changes must be an object".to_string());
+    };
+    let mut stored = settings.lock().map_err(|e| e.to_string())?;
+    let mut merged = serde_json::to_value(&*stored).map_err(|e| e.to_string())?;
+    for (key, value) in changes {
+        match merged.get_mut(&key) {
+            Some(field) => *field = value,
+            None => return Err(format!("Unknown setting {}", key)),
+        }
+    }
+    let updated: Settings = serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+    updated.validate(&app)?;
+    if updated == *stored {
+        return Ok(updated);
+    }
+
+    save(&app, &updated)?;
+    let previous = std::mem::replace(&mut *stored, updated.clone());
+    drop(stored);
+    apply(&app, &updated, Some(&previous));
+    Ok(updated)
+}
+
+// Restores every setting to its default
+#[tauri::command]
+pub fn reset_settings(app: AppHandle, settings: State<Mutex<Settings>>) -> Result<Settings, String> {
+    let defaults = Settings::default();
+    save(&app, &defaults)?;
+    let previous = std::mem::replace(&mut *settings.lock().map_err(|e| e.to_string())?, defaults.clone());
+    apply(&app, &defaults, Some(&previous));
+    Ok(defaults)
+}
@@ -0,0 +1,308 @@
+// Uploads the active document's content as a GitHub Gist (secret unless asked
+// otherwise) or to a configurable paste endpoint, and returns the link. The
+// paste settings live in `share.json` and the tokens in the OS keyring;
+// listings never echo the tokens back.
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::app_data;
+use crate::AppState;
+
+const SHARE_FILE: &str = "share.json";
+const KEYRING_SERVICE: &str = "devmate";
+const GITHUB_TOKEN_KEY: &str = "share-github-token";
+const PASTE_TOKEN_KEY: &str = "share-paste-token";
+const GIST_API_URL: &str = "https://api.github.com/gists";
+// GitHub rejects larger gist files through the API
+const MAX_GIST_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ShareConfig {
+    // Receives the content as the raw POST body
+    pub paste_url: Option<String>,
+    // JSON field holding the link when the endpoint answers with JSON
+    pub paste_url_field: Option<String>,
+    // Older versions kept the tokens here in plaintext; they're only read so
+    // load can move them into the keyring
+    #[serde(skip_serializing)]
+    github_token: Option<String>,
+    #[serde(skip_serializing)]
+    paste_token: Option<String>,
+}
+
+impl ShareConfig {
+    // Reads the keyring, so call it off the main thread
+    fn summary(&self) -> Result<serde_json::Value, String> {
+        Ok(serde_json::json!({
+            "github_token_set": stored_token(GITHUB_TOKEN_KEY)?.is_some(),
+            "paste_url": self.paste_url,
+            "paste_token_set": stored_token(PASTE_TOKEN_KEY)?.is_some(),
+            "paste_url_field": self.paste_url_field
+        }))
+    }
+}
+
+fn keyring_entry(key: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, key).map_err(|e| format!("Failed to access the credential store: {}", e))
+}
+
+fn stored_token(key: &str) -> Result<Option<String>, String> {
+    match keyring_entry(key)?.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read the saved token: {}", e)),
+    }
+}
+
+// An empty token removes the saved one
+fn store_token(key: &str, token: &str) -> Result<(), String> {
+    if token.is_empty() {
+        return match keyring_entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to remove the saved token: {}", e)),
+        };
+    }
+    keyring_entry(key)?
+        .set_password(token)
+        .map_err(|e| format!("Failed to save the token: {}", e))
+}
+
+fn save(app: &AppHandle, config: &ShareConfig) -> Result<(), String> {
+    app_data::save(app, SHARE_FILE, config, "share settings")
+}
+
+// Called from setup; a missing or unreadable file means nothing is configured.
+// Plaintext tokens from an older `share.json` move to the keyring, and the
+// file is rewritten without them once they're stored.
+pub fn load(app: &AppHandle) {
+    let mut config: ShareConfig = app_data::load(app, SHARE_FILE).unwrap_or_default();
+    let legacy = [
+        (GITHUB_TOKEN_KEY, config.github_token.take()),
+        (PASTE_TOKEN_KEY, config.paste_token.take()),
+    ];
+    if legacy.iter().any(|(_, token)| token.is_some()) {
+        let migrated = legacy
+            .iter()
+            .filter_map(|(key, token)| Some((key, token.as_deref()?)))
+            .try_for_each(|(key, token)| store_token(key, token))
+            .and_then(|()| save(app, &config));
+        if let Err(e) = migrated {
+            eprintln!("Failed to move share tokens to the credential store: {}", e);
+        }
+    }
+    app.manage(Mutex::new(config));
+}
+
+fn extension_for(format_type: Option<&str>) -> &'static str {
+    match format_type {
+        Some("json" | "json5" | "jwt" | "x509" | "json-summary") => "json",
+        Some("xml" | "saml") => "xml",
+        Some("yaml") => "yaml",
+        _ => "txt",
+    }
+}
+
+// The formatted content when there is some, else the raw content, and a file
+// name derived from the document
+fn shared_content(app: &AppHandle, content_type: Option<&str>) -> Result<(String, String), String> {
+    let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+    let document = storage.active();
+    let content = match content_type {
+        Some("raw") => document.raw_content.as_deref(),
+        Some("formatted") => document.formatted_content.as_deref(),
+        Some(other) => return Err(format!("Unknown content type {}", other)),
+        None => document.formatted_content.as_deref().or(document.raw_content.as_deref()),
+    }
+    .filter(|content| !content.is_empty())
+    .ok_or_else(|| "There is no content to share".to_string())?
+    .to_string();
+
+    let stem: String = document
+        .name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let stem = stem.trim_matches('-');
+    let stem = if stem.is_empty() { "devmate" } else { stem };
+    Ok((content, format!("{}.{}", stem, extension_for(document.format_type.as_deref()))))
+}
+
+fn create_gist(token: &str, file_name: &str, content: String, description: &str, public: bool) -> Result<String, String> {
+    if content.len() > MAX_GIST_BYTES {
+        return Err(format!("Gists are limited to {} bytes", MAX_GIST_BYTES));
+    }
+    let body = serde_json::json!({
+        "description": description,
+        "public": public,
+        "files": { file_name: { "content": content } }
+    });
+    let response = crate::fetch::http_client()?
+        .post(GIST_API_URL)
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+    let status = response.status();
+    let text = response.text().map_err(|e| format!("Failed to read GitHub's reply: {}", e))?;
+    let reply: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+    if !status.is_success() {
+        let message = reply["message"].as_str().unwrap_or(status.as_str());
+        return Err(format!("GitHub refused the gist ({}): {}", status.as_u16(), message));
+    }
+    reply["html_url"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "GitHub's reply had no gist URL".to_string())
+}
+
+fn post_paste(config: &ShareConfig, token: Option<&str>, file_name: &str, content: String) -> Result<String, String> {
+    let paste_url = config
+        .paste_url
+        .as_deref()
+        .ok_or_else(|| "No paste endpoint is configured".to_string())?;
+    let mut request = crate::fetch::http_client()?
+        .post(crate::fetch::parse_http_url(paste_url)?)
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .header("X-File-Name", file_name)
+        .body(content);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().map_err(|e| format!("Failed to reach the paste endpoint: {}", e))?;
+    let status = response.status();
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let text = response
+        .text()
+        .map_err(|e| format!("Failed to read the paste endpoint's reply: {}", e))?;
+    if !status.is_success() {
+        return Err(format!("The paste endpoint responded with {}", status));
+    }
+
+    // A JSON reply carries the link in the configured field (or `url`/`link`);
+    // otherwise it's the Location header or a bare URL as the body
+    let field_link = serde_json::from_str::<serde_json::Value>(&text).ok().and_then(|reply| {
+        let fields = [config.paste_url_field.as_deref().unwrap_or("url"), "url", "link"];
+        fields.iter().find_map(|field| reply[*field].as_str().map(str::to_string))
+    });
+    field_link
+        .or(location)
+        .or_else(|| {
+            let body = text.trim();
+            crate::fetch::parse_http_url(body).ok().map(|_| body.to_string())
+        })
+        .ok_or_else(|| "The paste endpoint's reply didn't contain a link".to_string())
+}
+
+fn share_blocking(
+    app: &AppHandle,
+    target: &str,
+    content_type: Option<String>,
+    description: Option<String>,
+    public: bool,
+) -> Result<serde_json::Value, String> {
+    let config = app
+        .state::<Mutex<ShareConfig>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let (content, file_name) = shared_content(app, content_type.as_deref())?;
+    let length = content.len();
+    let url = match target {
+        "gist" => {
+            let token = stored_token(GITHUB_TOKEN_KEY)?
+                .ok_or_else(|| "Set a GitHub token before creating gists".to_string())?;
+            let description = description.unwrap_or_else(|| format!("{} (shared from devmate)", file_name));
+            create_gist(&token, &file_name, content, &description, public)?
+        }
+        "paste" => {
+            let token = stored_token(PASTE_TOKEN_KEY)?;
+            post_paste(&config, token.as_deref(), &file_name, content)?
+        }
+        other => return Err(format!("Unknown share target {}; use gist or paste", other)),
+    };
+    Ok(serde_json::json!({
+        "target": target,
+        "url": url,
+        "file_name": file_name,
+        "length": length,
+        "public": target == "gist" && public
+    }))
+}
+
+// `target` is "gist" or "paste". Shares the formatted content, falling back to
+// the raw content, unless `content_type` picks one. Gists are secret unless
+// `public` is set.
+#[tauri::command]
+pub async fn share_content(
+    app: AppHandle,
+    target: String,
+    content_type: Option<String>,
+    description: Option<String>,
+    public: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("share_content", move || {
+        share_blocking(&app, &target, content_type, description, public.unwrap_or(false))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_share_config(app: AppHandle) -> Result<serde_json::Value, String> {
+    crate::run_blocking("get_share_config", move || {
+        let config = app
+            .state::<Mutex<ShareConfig>>()
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone();
+        config.summary()
+    })
+    .await
+}
+
+// Fields that are omitted keep their value; an empty string clears one
+#[tauri::command]
+pub async fn set_share_config(
+    app: AppHandle,
+    github_token: Option<String>,
+    paste_url: Option<String>,
+    paste_token: Option<String>,
+    paste_url_field: Option<String>,
+) -> Result<serde_json::Value, String> {
+    if let Some(url) = paste_url.as_deref().filter(|url| !url.is_empty()) {
+        crate::fetch::parse_http_url(url)?;
+    }
+    crate::run_blocking("set_share_config", move || {
+        for (key, token) in [(GITHUB_TOKEN_KEY, github_token), (PASTE_TOKEN_KEY, paste_token)] {
+            if let Some(token) = token {
+                store_token(key, token.trim())?;
+            }
+        }
+        let state = app.state::<Mutex<ShareConfig>>();
+        let mut stored = state.lock().map_err(|e| e.to_string())?;
+        let mut updated = stored.clone();
+        for (field, value) in [
+            (&mut updated.paste_url, paste_url),
+            (&mut updated.paste_url_field, paste_url_field),
+        ] {
+            if let Some(value) = value {
+                let value = value.trim().to_string();
+                *field = (!value.is_empty()).then_some(value);
+            }
+        }
+        save(&app, &updated)?;
+        *stored = updated;
+        let config = stored.clone();
+        drop(stored);
+        config.summary()
+    })
+    .await
+}
@@ -0,0 +1,139 @@
+use deunicode::deunicode;
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+// Transliterate to ASCII and split into lowercase alphanumeric words
+fn words(text: &str) -> Vec<String> {
+    let ascii = deunicode(text);
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            // Split camelCase boundaries so "fooBar" becomes ["foo", "bar"]
+            if c.is_ascii_uppercase() && prev_lower && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+            current.push(c.to_ascii_lowercase());
+        } else {
+            prev_lower = false;
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+pub fn sanitize(text: &str, style: &str, separator: &str, max_length: Option<usize>) -> Result<String, String> {
+    let words = words(text);
+    let mut result = match style {
+        "slug" => words.join(separator),
+        "snake" => words.join("_"),
+        "kebab" => words.join("-"),
+        "constant" => words.join("_").to_ascii_uppercase(),
+        "camel" => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) })
+            .collect(),
+        "pascal" => words.iter().map(|w| capitalize(w)).collect(),
+        _ => return Err(format!("Unknown identifier style: {}", style)),
+    };
+
+    // Identifiers can't start with a digit in most languages
+    if style != "slug" && style != "kebab" && result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+
+    if let Some(max) = max_length.filter(|m| *m > 0) {
+        if result.len() > max {
+            // A multi-byte separator may straddle `max`
+            result.truncate(result.floor_char_boundary(max));
+            let trimmed = result.trim_end_matches(|c: char| separator.contains(c) || c == '_' || c == '-');
+            result = trimmed.to_string();
+        }
+    }
+
+    Ok(result)
+}
+
+fn slugify_lines_blocking(
+    style: String,
+    separator: Option<String>,
+    max_length: Option<usize>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let separator = separator.unwrap_or_else(|| "-".to_string());
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let content = &snapshot.text;
+
+    let converted = content
+        .lines()
+        .map(|line| sanitize(line, &style, &separator, max_length))
+        .collect::<Result<Vec<_>, _>>()?;
+    let line_count = converted.len();
+    let result = converted.join("\n");
+    let length = result.len();
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.snapshot_document(&snapshot)?.edit(Slot::Formatted, result);
+
+    Ok(serde_json::json!({
+        "style": style,
+        "lines": line_count,
+        "formatted_length": length
+    }))
+}
+
+#[tauri::command]
+pub async fn slugify_lines(
+    app: AppHandle,
+    style: String,
+    separator: Option<String>,
+    max_length: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("slugify_lines", move || {
+        slugify_lines_blocking(style, separator, max_length, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn styles() {
+        assert_eq!(sanitize("Héllo wörld fooBar", "slug", "-", None).unwrap(), "hello-world-foo-bar");
+        assert_eq!(sanitize("Héllo wörld", "constant", "-", None).unwrap(), "HELLO_WORLD");
+        assert_eq!(sanitize("hello world", "camel", "-", None).unwrap(), "helloWorld");
+        assert_eq!(sanitize("2 fast", "snake", "-", None).unwrap(), "_2_fast");
+    }
+
+    #[test]
+    fn max_length_trims_trailing_separators() {
+        assert_eq!(sanitize("alpha beta gamma", "slug", "-", Some(11)).unwrap(), "alpha-beta");
+    }
+
+    #[test]
+    fn max_length_inside_a_multibyte_separator() {
+        // "ab·cd": the cut at 3 falls inside the two-byte "·"
+        assert_eq!(sanitize("ab cd", "slug", "·", Some(3)).unwrap(), "ab");
+        assert_eq!(sanitize("a b c d", "slug", "→", Some(6)).unwrap(), "a→b");
+    }
+}
@@ -0,0 +1,362 @@
+// Named snippets (sample payloads, test-environment JWT secrets, regexes, jq
+// filters) kept in `snippets.json`. Snippets that look like credentials, or are
+// marked secret, are masked in listings. A set of snippets can be exported to a
+// file and imported elsewhere so a team can share one library.
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_data;
+use crate::clipboard_history;
+use crate::undo::Slot;
+use crate::AppState;
+
+const SNIPPETS_FILE: &str = "snippets.json";
+const EXPORT_VERSION: u32 = 1;
+const PREVIEW_CHARS: usize = 160;
+const KINDS: &[&str] = &["payload", "secret", "regex", "jq", "text"];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Snippet {
+    pub id: u64,
+    pub name: String,
+    // One of KINDS; tells the UI which tool the snippet belongs to
+    pub kind: String,
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub secret: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Snippet {
+    fn summary(&self) -> serde_json::Value {
+        let preview = if self.secret {
+            clipboard_history::mask(&self.content)
+        } else {
+            let preview: String = self.content.chars().take(PREVIEW_CHARS).collect();
+            if preview.len() < self.content.len() {
+                format!("{}…", preview)
+            } else {
+                preview
+            }
+        };
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "kind": self.kind,
+            "tags": self.tags,
+            "secret": self.secret,
+            "length": self.content.len(),
+            "preview": preview,
+            "created_at": self.created_at,
+            "updated_at": self.updated_at
+        })
+    }
+
+    // Secret contents never match a search; only their name and tags do
+    fn matches(&self, query: &str, tags: &[String]) -> bool {
+        let tagged = tags
+            .iter()
+            .all(|tag| self.tags.iter().any(|own| own.eq_ignore_ascii_case(tag)));
+        let query = query.to_lowercase();
+        tagged
+            && (query.is_empty()
+                || self.name.to_lowercase().contains(&query)
+                || self.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
+                || (!self.secret && self.content.to_lowercase().contains(&query)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Library {
+    next_id: u64,
+    snippets: Vec<Snippet>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnippetExport {
+    version: u32,
+    snippets: Vec<Snippet>,
+}
+
+pub struct Snippets(Mutex<Library>);
+
+fn save(app: &AppHandle, library: &Library) -> Result<(), String> {
+    app_data::save(app, SNIPPETS_FILE, library, "snippets")
+}
+
+// Applies `change` and saves the library only if it succeeded
+fn update<T>(app: &AppHandle, change: impl FnOnce(&mut Library) -> Result<T, String>) -> Result<T, String> {
+    let snippets = app.state::<Snippets>();
+    let mut library = snippets.0.lock().map_err(|e| e.to_string())?;
+    let result = change(&mut library)?;
+    save(app, &library)?;
+    Ok(result)
+}
+
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if !tag.is_empty() && !cleaned.iter().any(|existing| existing.eq_ignore_ascii_case(&tag)) {
+            cleaned.push(tag);
+        }
+    }
+    cleaned
+}
+
+fn check_kind(kind: &str) -> Result<(), String> {
+    if KINDS.contains(&kind) {
+        Ok(())
+    } else {
+        Err(format!("Snippet kind must be one of {}", KINDS.join(", ")))
+    }
+}
+
+// Called from setup; a missing or unreadable file starts an empty library
+pub fn load(app: &AppHandle) {
+    let library: Library = app_data::load(app, SNIPPETS_FILE)
+        .unwrap_or_default();
+    app.manage(Snippets(Mutex::new(library)));
+}
+
+// Saves a new snippet, or replaces snippet `id`. `secret` defaults to whether
+// the content looks like a credential.
+#[tauri::command]
+pub fn save_snippet(
+    app: AppHandle,
+    id: Option<u64>,
+    name: String,
+    kind: String,
+    content: String,
+    tags: Option<Vec<String>>,
+    secret: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Snippet name cannot be empty".to_string());
+    }
+    check_kind(&kind)?;
+    let secret = secret.unwrap_or_else(|| kind == "secret" || clipboard_history::detect_secret(&content).is_some());
+    let tags = clean_tags(tags.unwrap_or_default());
+    let now = chrono::Utc::now().to_rfc3339();
+
+    update(&app, |library| match id {
+        Some(id) => {
+            let snippet = library
+                .snippets
+                .iter_mut()
+                .find(|snippet| snippet.id == id)
+                .ok_or_else(|| format!("Snippet {} not found", id))?;
+            snippet.name = name;
+            snippet.kind = kind;
+            snippet.content = content;
+            snippet.tags = tags;
+            snippet.secret = secret;
+            snippet.updated_at = now;
+            Ok(snippet.summary())
+        }
+        None => {
+            library.next_id += 1;
+            let snippet = Snippet {
+                id: library.next_id,
+                name,
+                kind,
+                content,
+                tags,
+                secret,
+                created_at: now.clone(),
+                updated_at: now,
+            };
+            let summary = snippet.summary();
+            library.snippets.push(snippet);
+            Ok(summary)
+        }
+    })
+}
+
+// Snippets whose name, tags or (non-secret) content contain `query`, limited
+// to those carrying every tag in `tags`, in name order
+#[tauri::command]
+pub fn search_snippets(
+    query: Option<String>,
+    tags: Option<Vec<String>>,
+    kind: Option<String>,
+    snippets: State<Snippets>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let query = query.unwrap_or_default();
+    let tags = tags.unwrap_or_default();
+    let library = snippets.0.lock().map_err(|e| e.to_string())?;
+    let mut found: Vec<&Snippet> = library
+        .snippets
+        .iter()
+        .filter(|snippet| kind.as_ref().is_none_or(|kind| snippet.kind == *kind))
+        .filter(|snippet| snippet.matches(query.trim(), &tags))
+        .collect();
+    found.sort_by_key(|snippet| snippet.name.to_lowercase());
+    Ok(found.iter().map(|snippet| snippet.summary()).collect())
+}
+
+// Every tag in use with how many snippets carry it, for a tag filter
+#[tauri::command]
+pub fn list_snippet_tags(snippets: State<Snippets>) -> Result<Vec<serde_json::Value>, String> {
+    let library = snippets.0.lock().map_err(|e| e.to_string())?;
+    let mut counts: std::collections::BTreeMap<String, usize> = std::collections::BTreeMap::new();
+    for tag in library.snippets.iter().flat_map(|snippet| &snippet.tags) {
+        *counts.entry(tag.to_lowercase()).or_default() += 1;
+    }
+    Ok(counts
+        .into_iter()
+        .map(|(tag, count)| serde_json::json!({ "tag": tag, "count": count }))
+        .collect())
+}
+
+// Returns the full snippet, secrets included. With `into_document` the content
+// also replaces the active document's raw content (as an undoable edit);
+// otherwise the caller hands it to a tool, e.g. a regex or jq filter field.
+#[tauri::command]
+pub fn load_snippet(
+    id: u64,
+    into_document: Option<bool>,
+    snippets: State<Snippets>,
+    state: State<AppState>,
+) -> Result<Snippet, String> {
+    let snippet = snippets
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .snippets
+        .iter()
+        .find(|snippet| snippet.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Snippet {} not found", id))?;
+    if into_document.unwrap_or(false) {
+        let mut storage = state.lock().map_err(|e| e.to_string())?;
+        storage.active_mut().edit(Slot::Raw, snippet.content.clone());
+    }
+    Ok(snippet)
+}
+
+#[tauri::command]
+pub fn delete_snippet(app: AppHandle, id: u64) -> Result<bool, String> {
+    update(&app, |library| {
+        let before = library.snippets.len();
+        library.snippets.retain(|snippet| snippet.id != id);
+        Ok(library.snippets.len() != before)
+    })
+}
+
+// Writes the chosen snippets (or all of them) to `path` for sharing. Secrets
+// are left out unless `include_secrets` is set.
+#[tauri::command]
+pub fn export_snippets(
+    path: String,
+    ids: Option<Vec<u64>>,
+    include_secrets: Option<bool>,
+    snippets: State<Snippets>,
+) -> Result<usize, String> {
+    let include_secrets = include_secrets.unwrap_or(false);
+    let exported: Vec<Snippet> = snippets
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .snippets
+        .iter()
+        .filter(|snippet| ids.as_ref().is_none_or(|ids| ids.contains(&snippet.id)))
+        .filter(|snippet| include_secrets || !snippet.secret)
+        .cloned()
+        .collect();
+    let count = exported.len();
+    let export = SnippetExport {
+        version: EXPORT_VERSION,
+        snippets: exported,
+    };
+    let json = serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize snippets: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(count)
+}
+
+// Adds the snippets from an export file under new ids. A snippet with the same
+// name and kind as an existing one replaces it, so re-importing a shared file
+// updates it rather than duplicating it.
+#[tauri::command]
+pub fn import_snippets(app: AppHandle, path: String) -> Result<serde_json::Value, String> {
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let export: SnippetExport = serde_json::from_str(&text).map_err(|e| format!("Not a snippet export: {}", e))?;
+    if export.version != EXPORT_VERSION {
+        return Err(format!("Unsupported snippet export version {}", export.version));
+    }
+
+    update(&app, |library| {
+        let (added, replaced) = merge(library, export.snippets);
+        Ok(serde_json::json!({ "added": added, "replaced": replaced }))
+    })
+}
+
+// Adds `snippets` under new ids, replacing those with the same name and kind.
+// Returns how many were added and replaced; invalid ones are skipped.
+fn merge(library: &mut Library, snippets: Vec<Snippet>) -> (usize, usize) {
+    let (mut added, mut replaced) = (0, 0);
+    let now = chrono::Utc::now().to_rfc3339();
+    for mut snippet in snippets {
+        if check_kind(&snippet.kind).is_err() || snippet.name.trim().is_empty() {
+            continue;
+        }
+        snippet.tags = clean_tags(snippet.tags);
+        snippet.updated_at = now.clone();
+        let existing = library
+            .snippets
+            .iter_mut()
+            .find(|own| own.name == snippet.name && own.kind == snippet.kind);
+        match existing {
+            Some(own) => {
+                snippet.id = own.id;
+                snippet.created_at = own.created_at.clone();
+                *own = snippet;
+                replaced += 1;
+            }
+            None => {
+                library.next_id += 1;
+                snippet.id = library.next_id;
+                library.snippets.push(snippet);
+                added += 1;
+            }
+        }
+    }
+    (added, replaced)
+}
+
+// Every snippet, for config bundles; secrets only with `include_secrets`
+pub(crate) fn shareable(app: &AppHandle, include_secrets: bool) -> Result<Vec<Snippet>, String> {
+    let snippets = app.state::<Snippets>();
+    let library = snippets.0.lock().map_err(|e| e.to_string())?;
+    Ok(library
+        .snippets
+        .iter()
+        .filter(|snippet| include_secrets || !snippet.secret)
+        .cloned()
+        .collect())
+}
+
+// Imports the snippets from a config bundle the way import_snippets does. With
+// `overwrite` the library is cleared first, except for local secrets when the
+// bundle was exported without them.
+pub(crate) fn import(
+    app: &AppHandle,
+    snippets: Vec<Snippet>,
+    overwrite: bool,
+    keep_secrets: bool,
+) -> Result<serde_json::Value, String> {
+    update(app, |library| {
+        let before = library.snippets.len();
+        if overwrite {
+            library.snippets.retain(|snippet| keep_secrets && snippet.secret);
+        }
+        let removed = before - library.snippets.len();
+        let (added, replaced) = merge(library, snippets);
+        Ok(serde_json::json!({ "added": added, "replaced": replaced, "removed": removed }))
+    })
+}
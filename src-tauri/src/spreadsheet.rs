@@ -0,0 +1,221 @@
+// Imports a sheet of a spreadsheet (.xlsx, .xlsm, .xls, .ods) into a new
+// document as CSV or a JSON array, so spreadsheets can go through the CSV and
+// JSON tools. A header row is detected unless the caller says whether there
+// is one; with a header, JSON rows become objects keyed by column name.
+use calamine::{open_workbook_auto, Data, Reader};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use crate::columns::quote_field;
+use crate::AppState;
+
+// Largest integer a JSON number (f64) holds exactly
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+fn open(file_path: &str) -> Result<calamine::Sheets<std::io::BufReader<std::fs::File>>, String> {
+    open_workbook_auto(file_path).map_err(|e| format!("Failed to open spreadsheet: {}", e))
+}
+
+fn sheet(file_path: &str, name: Option<&str>) -> Result<(String, calamine::Range<Data>), String> {
+    let mut workbook = open(file_path)?;
+    let names = workbook.sheet_names();
+    let name = match name {
+        Some(name) => names
+            .iter()
+            .find(|candidate| candidate.as_str() == name)
+            .cloned()
+            .ok_or_else(|| format!("No sheet named {}", name))?,
+        None => names
+            .first()
+            .cloned()
+            .ok_or_else(|| "The workbook has no sheets".to_string())?,
+    };
+    let range = workbook
+        .worksheet_range(&name)
+        .map_err(|e| format!("Failed to read sheet {}: {}", name, e))?;
+    Ok((name, range))
+}
+
+fn cell_json(cell: &Data) -> serde_json::Value {
+    match cell {
+        Data::Empty => serde_json::Value::Null,
+        Data::Int(value) => (*value).into(),
+        // Spreadsheets store whole numbers as floats
+        Data::Float(value) if value.fract() == 0.0 && value.abs() <= MAX_SAFE_INTEGER => (*value as i64).into(),
+        Data::Float(value) => (*value).into(),
+        Data::Bool(value) => (*value).into(),
+        Data::String(text) | Data::DateTimeIso(text) | Data::DurationIso(text) => text.clone().into(),
+        Data::DateTime(value) => match value.as_datetime() {
+            Some(datetime) if datetime.time() == chrono::NaiveTime::MIN => datetime.date().to_string().into(),
+            Some(datetime) => datetime.format("%Y-%m-%dT%H:%M:%S").to_string().into(),
+            None => value.as_f64().into(),
+        },
+        Data::Error(error) => error.to_string().into(),
+    }
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell_json(cell) {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+// A first row of distinct, non-empty text cells reads as a header, unless
+// every row looks like that (a plain list of words)
+fn detect_header(rows: &[&[Data]]) -> bool {
+    let is_label_row = |row: &[Data]| {
+        let mut seen = std::collections::HashSet::new();
+        !row.is_empty()
+            && row
+                .iter()
+                .all(|cell| matches!(cell, Data::String(text) if !text.trim().is_empty() && seen.insert(text.trim())))
+    };
+    match rows {
+        [] => false,
+        [first] => is_label_row(first),
+        [first, rest @ ..] => is_label_row(first) && !rest.iter().take(20).all(|row| is_label_row(row)),
+    }
+}
+
+// Column names from the header row; blanks and repeats get a suffix
+fn column_names(header: &[Data], width: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(width);
+    for index in 0..width {
+        let base = header.get(index).map(cell_text).unwrap_or_default().trim().to_string();
+        let base = if base.is_empty() {
+            format!("column_{}", index + 1)
+        } else {
+            base
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while names.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+fn list_sheets_blocking(file_path: &str) -> Result<Vec<serde_json::Value>, String> {
+    let mut workbook = open(file_path)?;
+    let names = workbook.sheet_names();
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let size = workbook.worksheet_range(&name).ok().map(|range| range.get_size());
+            serde_json::json!({
+                "name": name,
+                "rows": size.map(|(rows, _)| rows),
+                "columns": size.map(|(_, columns)| columns)
+            })
+        })
+        .collect())
+}
+
+fn import_sheet_blocking(
+    app: &AppHandle,
+    file_path: String,
+    sheet_name: Option<String>,
+    header: Option<bool>,
+    csv: bool,
+) -> Result<serde_json::Value, String> {
+    let (name, range) = sheet(&file_path, sheet_name.as_deref())?;
+    let width = range.width();
+    let rows: Vec<&[Data]> = range.rows().collect();
+    let has_header = header.unwrap_or_else(|| detect_header(&rows));
+    let (header_row, body) = match rows.split_first() {
+        Some((first, rest)) if has_header => (Some(*first), rest),
+        _ => (None, rows.as_slice()),
+    };
+    let columns = header_row.map(|header_row| column_names(header_row, width));
+
+    let content = if csv {
+        let line = |cells: Vec<String>| {
+            cells
+                .iter()
+                .map(|cell| quote_field(cell, ','))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let mut lines = Vec::with_capacity(rows.len());
+        if let Some(columns) = &columns {
+            lines.push(line(columns.clone()));
+        }
+        for row in body {
+            lines.push(line(
+                (0..width)
+                    .map(|index| row.get(index).map(cell_text).unwrap_or_default())
+                    .collect(),
+            ));
+        }
+        lines.join("\n") + "\n"
+    } else {
+        let records: Vec<serde_json::Value> = body
+            .iter()
+            .map(|row| match &columns {
+                Some(columns) => serde_json::Value::Object(
+                    columns
+                        .iter()
+                        .enumerate()
+                        .map(|(index, column)| (column.clone(), row.get(index).map(cell_json).unwrap_or_default()))
+                        .collect(),
+                ),
+                None => serde_json::Value::Array(row.iter().map(cell_json).collect()),
+            })
+            .collect();
+        crate::formatters::to_string_pretty(&records).map_err(|e| format!("Failed to format JSON: {}", e))?
+    };
+
+    let file_name = Path::new(&file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file_path.clone());
+    let document_id = {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let id = storage.insert_document(format!("{} - {}", file_name, name));
+        let document = storage.active_mut();
+        document.raw_content = Some(content.into());
+        document.format_type = Some(if csv { "csv" } else { "json" }.to_string());
+        id
+    };
+    crate::tray::refresh(app);
+    Ok(serde_json::json!({
+        "document_id": document_id,
+        "sheet": name,
+        "rows": body.len(),
+        "columns": columns,
+        "header": has_header
+    }))
+}
+
+// The workbook's sheets in order, with their used size
+#[tauri::command]
+pub async fn list_spreadsheet_sheets(file_path: String) -> Result<Vec<serde_json::Value>, String> {
+    crate::run_blocking("list_spreadsheet_sheets", move || list_sheets_blocking(&file_path)).await
+}
+
+// Loads sheet `sheet` (the first by default) into a new document as JSON or,
+// with `format` "csv", CSV. `header` says whether the first row holds column
+// names; it's detected when omitted.
+#[tauri::command]
+pub async fn import_spreadsheet_sheet(
+    app: AppHandle,
+    file_path: String,
+    sheet: Option<String>,
+    header: Option<bool>,
+    format: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let csv = match format.as_deref().unwrap_or("json") {
+        "json" => false,
+        "csv" => true,
+        other => return Err(format!("Unknown format {}; use json or csv", other)),
+    };
+    crate::run_blocking("import_spreadsheet_sheet", move || {
+        import_sheet_blocking(&app, file_path, sheet.filter(|sheet| !sheet.is_empty()), header, csv)
+    })
+    .await
+}
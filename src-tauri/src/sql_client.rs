@@ -0,0 +1,675 @@
+// A small PostgreSQL / MySQL query runner for quick checks. Connection details
+// live in `db_connections.json`; passwords are kept in the OS credential store
+// (Keychain, Credential Manager, Secret Service), never on disk. A query runs
+// on a fresh connection, its rows land in a new document as a JSON array of
+// objects in column order (so the JSON formatter, summary and tree work on
+// them) and are served to the result grid a page at a time, as arrays of values
+// in the order of `columns`.
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_data;
+use crate::AppState;
+
+const CONNECTIONS_FILE: &str = "db_connections.json";
+const KEYRING_SERVICE: &str = "devmate";
+const ENGINES: &[&str] = &["postgres", "mysql"];
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_MAX_ROWS: usize = 10_000;
+const MAX_ROWS_LIMIT: usize = 1_000_000;
+const DEFAULT_PAGE_SIZE: usize = 100;
+// Result sets kept for paging; older ones must be queried again
+const MAX_CACHED_RESULTS: usize = 8;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DbConnection {
+    pub id: u64,
+    pub name: String,
+    // "postgres" or "mysql"
+    pub engine: String,
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub database: String,
+    // Require TLS; otherwise Postgres still prefers it when the server offers it
+    #[serde(default)]
+    pub tls: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ConnectionList {
+    next_id: u64,
+    connections: Vec<DbConnection>,
+}
+
+pub struct DbConnections(Mutex<ConnectionList>);
+
+struct QueryResult {
+    id: u64,
+    columns: Vec<String>,
+    rows: Vec<Vec<serde_json::Value>>,
+}
+
+#[derive(Default)]
+pub struct SqlResults(Mutex<(u64, VecDeque<Arc<QueryResult>>)>);
+
+// How a column's text value becomes JSON
+#[derive(Clone, Copy)]
+enum ColumnKind {
+    Bool,
+    Integer,
+    Float,
+    Json,
+    Text,
+}
+
+fn text_value(kind: ColumnKind, text: &str) -> serde_json::Value {
+    let converted = match kind {
+        ColumnKind::Bool => match text {
+            "t" | "true" | "1" => Some(true.into()),
+            "f" | "false" | "0" => Some(false.into()),
+            _ => None,
+        },
+        ColumnKind::Integer => text
+            .parse::<i64>()
+            .map(Into::into)
+            .or_else(|_| text.parse::<u64>().map(Into::into))
+            .ok(),
+        ColumnKind::Float => text.parse::<f64>().ok().and_then(|f| serde_json::Number::from_f64(f).map(Into::into)),
+        ColumnKind::Json => serde_json::from_str(text).ok(),
+        // Decimals, dates, UUIDs, ... stay text so nothing loses precision
+        ColumnKind::Text => None,
+    };
+    converted.unwrap_or_else(|| text.into())
+}
+
+fn save(app: &AppHandle, list: &ConnectionList) -> Result<(), String> {
+    app_data::save(app, CONNECTIONS_FILE, list, "connections")
+}
+
+// Called from setup; a missing or unreadable file starts with no connections
+pub fn load(app: &AppHandle) {
+    let list: ConnectionList = app_data::load(app, CONNECTIONS_FILE)
+        .unwrap_or_default();
+    app.manage(DbConnections(Mutex::new(list)));
+}
+
+fn keyring_entry(id: u64) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, &format!("db-connection-{}", id))
+        .map_err(|e| format!("Failed to access the credential store: {}", e))
+}
+
+fn stored_password(id: u64) -> Result<Option<String>, String> {
+    match keyring_entry(id)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read the saved password: {}", e)),
+    }
+}
+
+fn summary(connection: &DbConnection) -> serde_json::Value {
+    let mut value = serde_json::to_value(connection).unwrap_or_default();
+    value["has_password"] = stored_password(connection.id).ok().flatten().is_some().into();
+    value
+}
+
+fn find_connection(app: &AppHandle, id: u64) -> Result<DbConnection, String> {
+    app.state::<DbConnections>()
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .connections
+        .iter()
+        .find(|connection| connection.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Connection {} not found", id))
+}
+
+struct RawResult {
+    columns: Vec<String>,
+    rows: Vec<Vec<serde_json::Value>>,
+    truncated: bool,
+    affected_rows: u64,
+    server_version: Option<String>,
+}
+
+fn postgres_client(connection: &DbConnection, password: Option<String>) -> Result<postgres::Client, String> {
+    let mut config = postgres::Config::new();
+    config
+        .host(&connection.host)
+        .port(connection.port)
+        .user(&connection.user)
+        .dbname(&connection.database)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .ssl_mode(if connection.tls {
+            postgres::config::SslMode::Require
+        } else {
+            postgres::config::SslMode::Prefer
+        });
+    if let Some(password) = password {
+        config.password(password);
+    }
+    let roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    config
+        .connect(tokio_postgres_rustls::MakeRustlsConnect::new(tls_config))
+        .map_err(|e| format!("Failed to connect to {}: {}", connection.name, e))
+}
+
+fn postgres_kind(column_type: &postgres::types::Type) -> ColumnKind {
+    use postgres::types::Type;
+    match *column_type {
+        Type::BOOL => ColumnKind::Bool,
+        Type::INT2 | Type::INT4 | Type::INT8 | Type::OID => ColumnKind::Integer,
+        Type::FLOAT4 | Type::FLOAT8 => ColumnKind::Float,
+        Type::JSON | Type::JSONB => ColumnKind::Json,
+        _ => ColumnKind::Text,
+    }
+}
+
+// Reads a single row-returning query through a cursor (a named portal), so at
+// most `max_rows` + 1 rows ever leave the server. None when the statement can't
+// be declared as a cursor (INSERT ... RETURNING, for one); nothing has run then.
+fn fetch_through_cursor(
+    client: &mut postgres::Client,
+    sql: &str,
+    max_rows: usize,
+) -> Result<Option<Vec<postgres::SimpleQueryMessage>>, String> {
+    let mut transaction = client.transaction().map_err(|e| format!("Query failed: {}", e))?;
+    let declare = format!(
+        "DECLARE devmate_rows NO SCROLL CURSOR FOR {}",
+        sql.trim().trim_end_matches(';')
+    );
+    if transaction.batch_execute(&declare).is_err() {
+        // Dropping the transaction rolls it back
+        return Ok(None);
+    }
+    let messages = transaction
+        .simple_query(&format!("FETCH FORWARD {} FROM devmate_rows", max_rows + 1))
+        .map_err(|e| format!("Query failed: {}", e))?;
+    transaction.commit().map_err(|e| format!("Query failed: {}", e))?;
+    Ok(Some(messages))
+}
+
+fn run_postgres(
+    connection: &DbConnection,
+    password: Option<String>,
+    sql: &str,
+    max_rows: usize,
+) -> Result<RawResult, String> {
+    let mut client = postgres_client(connection, password)?;
+    // The simple protocol returns every value as text, whatever its type; the
+    // prepared statement only supplies column names and types (it fails for
+    // several statements at once, which are then all treated as text)
+    let prepared: Option<(Vec<String>, Vec<ColumnKind>)> = client.prepare(sql).ok().map(|statement| {
+        statement
+            .columns()
+            .iter()
+            .map(|column| (column.name().to_string(), postgres_kind(column.type_())))
+            .unzip()
+    });
+    let (columns, kinds) = match prepared {
+        Some((columns, kinds)) => (columns, Some(kinds)),
+        None => (Vec::new(), None),
+    };
+    // A batch of statements is read whole; only a single query is limited
+    // at the server
+    let streamed = match &kinds {
+        Some(kinds) if !kinds.is_empty() => fetch_through_cursor(&mut client, sql, max_rows)?,
+        _ => None,
+    };
+    let through_cursor = streamed.is_some();
+    let messages = match streamed {
+        Some(messages) => messages,
+        None => client.simple_query(sql).map_err(|e| format!("Query failed: {}", e))?,
+    };
+
+    let mut result = RawResult {
+        columns,
+        rows: Vec::new(),
+        truncated: false,
+        affected_rows: 0,
+        server_version: None,
+    };
+    for message in messages {
+        match message {
+            postgres::SimpleQueryMessage::Row(row) => {
+                if result.rows.len() >= max_rows {
+                    result.truncated = true;
+                    continue;
+                }
+                if result.columns.is_empty() {
+                    result.columns = row.columns().iter().map(|column| column.name().to_string()).collect();
+                }
+                let values = (0..row.len())
+                    .map(|index| match row.get(index) {
+                        Some(text) => {
+                            let kind = kinds
+                                .as_ref()
+                                .and_then(|kinds| kinds.get(index).copied())
+                                .unwrap_or(ColumnKind::Text);
+                            text_value(kind, text)
+                        }
+                        None => serde_json::Value::Null,
+                    })
+                    .collect();
+                result.rows.push(values);
+            }
+            postgres::SimpleQueryMessage::CommandComplete(count) => result.affected_rows += count,
+            _ => {}
+        }
+    }
+    if through_cursor {
+        // The FETCH count includes the row that only flags truncation
+        result.affected_rows = result.rows.len() as u64;
+    }
+    result.server_version = client
+        .simple_query("SHOW server_version")
+        .ok()
+        .and_then(|messages| {
+            messages.into_iter().find_map(|message| match message {
+                postgres::SimpleQueryMessage::Row(row) => row.get(0).map(str::to_string),
+                _ => None,
+            })
+        });
+    Ok(result)
+}
+
+fn mysql_kind(column_type: mysql::consts::ColumnType) -> ColumnKind {
+    use mysql::consts::ColumnType;
+    match column_type {
+        ColumnType::MYSQL_TYPE_TINY
+        | ColumnType::MYSQL_TYPE_SHORT
+        | ColumnType::MYSQL_TYPE_INT24
+        | ColumnType::MYSQL_TYPE_LONG
+        | ColumnType::MYSQL_TYPE_LONGLONG
+        | ColumnType::MYSQL_TYPE_YEAR => ColumnKind::Integer,
+        ColumnType::MYSQL_TYPE_FLOAT | ColumnType::MYSQL_TYPE_DOUBLE => ColumnKind::Float,
+        ColumnType::MYSQL_TYPE_JSON => ColumnKind::Json,
+        _ => ColumnKind::Text,
+    }
+}
+
+fn mysql_value(kind: ColumnKind, value: mysql::Value) -> serde_json::Value {
+    match value {
+        mysql::Value::NULL => serde_json::Value::Null,
+        mysql::Value::Bytes(bytes) => text_value(kind, &String::from_utf8_lossy(&bytes)),
+        mysql::Value::Int(int) => int.into(),
+        mysql::Value::UInt(uint) => uint.into(),
+        mysql::Value::Float(float) => text_value(ColumnKind::Float, &float.to_string()),
+        mysql::Value::Double(double) => text_value(ColumnKind::Float, &double.to_string()),
+        mysql::Value::Date(year, month, day, hour, minute, second, micros) => format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}",
+            year, month, day, hour, minute, second, micros
+        )
+        .into(),
+        mysql::Value::Time(negative, days, hours, minutes, seconds, micros) => format!(
+            "{}{:02}:{:02}:{:02}.{:06}",
+            if negative { "-" } else { "" },
+            days * 24 + hours as u32,
+            minutes,
+            seconds,
+            micros
+        )
+        .into(),
+    }
+}
+
+fn run_mysql(
+    connection: &DbConnection,
+    password: Option<String>,
+    sql: &str,
+    max_rows: usize,
+) -> Result<RawResult, String> {
+    use mysql::prelude::Queryable;
+
+    let mut options = mysql::OptsBuilder::new()
+        .ip_or_hostname(Some(connection.host.clone()))
+        .tcp_port(connection.port)
+        .user(Some(connection.user.clone()))
+        .pass(password)
+        .db_name(Some(connection.database.clone()))
+        .tcp_connect_timeout(Some(CONNECT_TIMEOUT));
+    if connection.tls {
+        options = options.ssl_opts(Some(mysql::SslOpts::default()));
+    }
+    let mut conn =
+        mysql::Conn::new(options).map_err(|e| format!("Failed to connect to {}: {}", connection.name, e))?;
+
+    let mut result = RawResult {
+        columns: Vec::new(),
+        rows: Vec::new(),
+        truncated: false,
+        affected_rows: 0,
+        server_version: None,
+    };
+    let mut query = conn.query_iter(sql).map_err(|e| format!("Query failed: {}", e))?;
+    let mut kinds = Vec::new();
+    for row in query.by_ref() {
+        let row = row.map_err(|e| format!("Query failed: {}", e))?;
+        if result.rows.len() >= max_rows {
+            // Rows past the limit still have to be read off the connection
+            result.truncated = true;
+            continue;
+        }
+        if result.columns.is_empty() {
+            result.columns = row.columns_ref().iter().map(|column| column.name_str().into_owned()).collect();
+            kinds = row.columns_ref().iter().map(|column| mysql_kind(column.column_type())).collect();
+        }
+        let values = row
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| mysql_value(kinds.get(index).copied().unwrap_or(ColumnKind::Text), value))
+            .collect();
+        result.rows.push(values);
+    }
+    result.affected_rows = query.affected_rows();
+    drop(query);
+    let (major, minor, patch) = conn.server_version();
+    result.server_version = Some(format!("{}.{}.{}", major, minor, patch));
+    Ok(result)
+}
+
+fn execute(connection: &DbConnection, sql: &str, max_rows: usize) -> Result<RawResult, String> {
+    let password = stored_password(connection.id)?;
+    match connection.engine.as_str() {
+        "postgres" => run_postgres(connection, password, sql, max_rows),
+        "mysql" => run_mysql(connection, password, sql, max_rows),
+        other => Err(format!("Unsupported database engine {}", other)),
+    }
+}
+
+// Column names as object keys: a repeated name (`a.id, b.id` in a join) gets a
+// numeric suffix, so no value is dropped
+fn object_keys(columns: &[String]) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::with_capacity(columns.len());
+    for column in columns {
+        let mut key = column.clone();
+        let mut suffix = 1;
+        while keys.contains(&key) {
+            suffix += 1;
+            key = format!("{}_{}", column, suffix);
+        }
+        keys.push(key);
+    }
+    keys
+}
+
+// A row as an object with its keys in column order; serde_json's Map would
+// sort them
+struct RowObject<'a> {
+    keys: &'a [String],
+    values: &'a [serde_json::Value],
+}
+
+impl Serialize for RowObject<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.keys.len()))?;
+        for (key, value) in self.keys.iter().zip(self.values) {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+// The rows as the document's JSON array of objects
+fn rows_document(columns: &[String], rows: &[Vec<serde_json::Value>]) -> Result<String, String> {
+    let keys = object_keys(columns);
+    let objects: Vec<RowObject> = rows.iter().map(|values| RowObject { keys: &keys, values }).collect();
+    let indent = crate::formatters::indent(1);
+    let mut out = Vec::new();
+    let mut serializer =
+        serde_json::Serializer::with_formatter(&mut out, serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes()));
+    objects
+        .serialize(&mut serializer)
+        .map_err(|e| format!("Failed to serialize rows: {}", e))?;
+    String::from_utf8(out).map_err(|e| format!("Failed to serialize rows: {}", e))
+}
+
+fn page(result: &QueryResult, page: usize, page_size: usize) -> serde_json::Value {
+    let start = page.saturating_mul(page_size).min(result.rows.len());
+    let end = start.saturating_add(page_size).min(result.rows.len());
+    serde_json::json!({
+        "result_id": result.id,
+        "columns": result.columns,
+        "row_count": result.rows.len(),
+        "page": page,
+        "page_size": page_size,
+        "rows": &result.rows[start..end],
+        "has_more": end < result.rows.len()
+    })
+}
+
+fn run_query_blocking(
+    app: &AppHandle,
+    connection_id: u64,
+    sql: String,
+    page_size: usize,
+    max_rows: usize,
+) -> Result<serde_json::Value, String> {
+    let connection = find_connection(app, connection_id)?;
+    let started = Instant::now();
+    let raw = execute(&connection, &sql, max_rows)?;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let json = rows_document(&raw.columns, &raw.rows)?;
+
+    let document_id = {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let id = storage.insert_document(format!("{} query", connection.name));
+        let document = storage.active_mut();
+        document.raw_content = Some(json.into());
+        document.format_type = Some("json".to_string());
+        id
+    };
+    crate::tray::refresh(app);
+
+    let results = app.state::<SqlResults>();
+    let mut results = results.0.lock().map_err(|e| e.to_string())?;
+    results.0 += 1;
+    let result = Arc::new(QueryResult {
+        id: results.0,
+        columns: raw.columns,
+        rows: raw.rows,
+    });
+    results.1.push_back(result.clone());
+    while results.1.len() > MAX_CACHED_RESULTS {
+        results.1.pop_front();
+    }
+    drop(results);
+
+    let mut reply = page(&result, 0, page_size);
+    reply["document_id"] = document_id.into();
+    reply["truncated"] = raw.truncated.into();
+    reply["affected_rows"] = raw.affected_rows.into();
+    reply["elapsed_ms"] = elapsed_ms.into();
+    reply["server_version"] = raw.server_version.into();
+    Ok(reply)
+}
+
+// Saves a new connection, or updates connection `id`. `password` is stored in
+// the OS credential store; omit it to keep the saved one, or pass "" to remove it.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn save_db_connection(
+    app: AppHandle,
+    id: Option<u64>,
+    name: String,
+    engine: String,
+    host: String,
+    port: Option<u16>,
+    user: String,
+    database: String,
+    tls: Option<bool>,
+    password: Option<String>,
+) -> Result<serde_json::Value, String> {
+    if !ENGINES.contains(&engine.as_str()) {
+        return Err(format!("Database engine must be one of {}", ENGINES.join(", ")));
+    }
+    if name.trim().is_empty() || host.trim().is_empty() {
+        return Err("A connection needs a name and a host".to_string());
+    }
+    let port = port.unwrap_or(if engine == "postgres" { 5432 } else { 3306 });
+
+    let connections = app.state::<DbConnections>();
+    let mut list = connections.0.lock().map_err(|e| e.to_string())?;
+    let id = match id {
+        Some(id) if list.connections.iter().any(|connection| connection.id == id) => id,
+        Some(id) => return Err(format!("Connection {} not found", id)),
+        None => {
+            list.next_id += 1;
+            list.next_id
+        }
+    };
+    let connection = DbConnection {
+        id,
+        name: name.trim().to_string(),
+        engine,
+        host: host.trim().to_string(),
+        port,
+        user,
+        database,
+        tls: tls.unwrap_or(false),
+    };
+    match password {
+        Some(password) if password.is_empty() => match keyring_entry(id)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(format!("Failed to remove the saved password: {}", e)),
+        },
+        Some(password) => keyring_entry(id)?
+            .set_password(&password)
+            .map_err(|e| format!("Failed to save the password: {}", e))?,
+        None => {}
+    }
+    list.connections.retain(|existing| existing.id != id);
+    list.connections.push(connection.clone());
+    list.connections.sort_by_key(|connection| connection.name.to_lowercase());
+    save(&app, &list)?;
+    Ok(summary(&connection))
+}
+
+// Reading the credential store can block (or prompt), so the lookups run off
+// the main thread and after the lock is released
+#[tauri::command]
+pub async fn list_db_connections(app: AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    crate::run_blocking("list_db_connections", move || {
+        let connections = app
+            .state::<DbConnections>()
+            .0
+            .lock()
+            .map_err(|e| e.to_string())?
+            .connections
+            .clone();
+        Ok(connections.iter().map(summary).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn delete_db_connection(app: AppHandle, id: u64) -> Result<bool, String> {
+    let connections = app.state::<DbConnections>();
+    let mut list = connections.0.lock().map_err(|e| e.to_string())?;
+    let before = list.connections.len();
+    list.connections.retain(|connection| connection.id != id);
+    if list.connections.len() == before {
+        return Ok(false);
+    }
+    save(&app, &list)?;
+    if let Ok(entry) = keyring_entry(id) {
+        let _ = entry.delete_credential();
+    }
+    Ok(true)
+}
+
+// Connects and runs a trivial query; returns the server version
+#[tauri::command]
+pub async fn test_db_connection(app: AppHandle, id: u64) -> Result<serde_json::Value, String> {
+    crate::run_blocking("test_db_connection", move || {
+        let connection = find_connection(&app, id)?;
+        let started = Instant::now();
+        let result = execute(&connection, "SELECT 1", 1)?;
+        Ok(serde_json::json!({
+            "server_version": result.server_version,
+            "elapsed_ms": started.elapsed().as_millis() as u64
+        }))
+    })
+    .await
+}
+
+// Runs `sql` and loads up to `max_rows` rows (10,000 by default) into a new
+// document as a JSON array. Returns the first page of rows plus a result_id
+// for get_sql_result_page.
+#[tauri::command]
+pub async fn run_sql_query(
+    app: AppHandle,
+    connection_id: u64,
+    sql: String,
+    page_size: Option<usize>,
+    max_rows: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let max_rows = max_rows.unwrap_or(DEFAULT_MAX_ROWS).clamp(1, MAX_ROWS_LIMIT);
+    crate::run_blocking("run_sql_query", move || {
+        run_query_blocking(&app, connection_id, sql, page_size, max_rows)
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn get_sql_result_page(
+    result_id: u64,
+    page: usize,
+    page_size: Option<usize>,
+    results: State<SqlResults>,
+) -> Result<serde_json::Value, String> {
+    let result = results
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .1
+        .iter()
+        .find(|result| result.id == result_id)
+        .cloned()
+        .ok_or_else(|| "That result is no longer cached; run the query again".to_string())?;
+    Ok(self::page(&result, page, page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_column_names_get_suffixes() {
+        let columns: Vec<String> = ["id", "name", "id", "id_2", "id"].map(String::from).to_vec();
+        assert_eq!(object_keys(&columns), ["id", "name", "id_2", "id_2_2", "id_3"]);
+    }
+
+    #[test]
+    fn document_rows_keep_column_order() {
+        let columns: Vec<String> = ["zeta", "alpha", "zeta"].map(String::from).to_vec();
+        let rows = vec![vec![1.into(), "a".into(), serde_json::Value::Null]];
+        let json = rows_document(&columns, &rows).unwrap();
+        let compact: String = json.split_whitespace().collect();
+        assert_eq!(compact, r#"[{"zeta":1,"alpha":"a","zeta_2":null}]"#);
+    }
+
+    #[test]
+    fn text_values_follow_column_kinds() {
+        assert_eq!(text_value(ColumnKind::Bool, "t"), true);
+        assert_eq!(text_value(ColumnKind::Integer, "18446744073709551615"), u64::MAX);
+        assert_eq!(text_value(ColumnKind::Json, r#"{"a":1}"#), serde_json::json!({"a": 1}));
+        // Decimals stay text
+        assert_eq!(text_value(ColumnKind::Text, "12345678901234567890.123"), "12345678901234567890.123");
+    }
+}
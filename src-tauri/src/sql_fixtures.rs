@@ -0,0 +1,528 @@
+// Turns query output pasted from a database CLI into test fixtures: a JSON
+// array of row objects, or INSERT statements. Understands psql's aligned,
+// unaligned (`-A`) and expanded (`\x`) output, mysql's bordered tables and
+// tab-separated output (`mysql -B`, `psql -F $'\t'`, COPY), with their row
+// count footers. Column boundaries come from the rule line under the header
+// when there is one, so a `|` inside a value doesn't split it.
+use regex::Regex;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+const OUTPUTS: &[&str] = &["json", "insert"];
+const DIALECTS: &[&str] = &["postgres", "mysql", "sqlite", "ansi"];
+const DEFAULT_TABLE: &str = "table_name";
+// Shown for NULL by mysql, COPY / `mysql -B`, and common `\pset null` choices
+const NULL_MARKERS: &[&str] = &["NULL", "\\N", "(null)", "<null>", "[null]"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    // psql's default: ` id | name` over `----+------`
+    PsqlAligned,
+    // mysql's `+----+------+` bordered table
+    MysqlTable,
+    // psql `\x`: `-[ RECORD 1 ]` blocks of `column | value`
+    Expanded,
+    Tab,
+    // psql -A and other bare pipe-separated output
+    Pipe,
+}
+
+impl Layout {
+    fn name(self) -> &'static str {
+        match self {
+            Layout::PsqlAligned => "psql",
+            Layout::MysqlTable => "mysql",
+            Layout::Expanded => "psql-expanded",
+            Layout::Tab => "tab",
+            Layout::Pipe => "pipe",
+        }
+    }
+
+    // psql prints NULL as an empty cell unless `\pset null` says otherwise
+    fn empty_is_null(self) -> bool {
+        matches!(self, Layout::PsqlAligned | Layout::Expanded | Layout::Pipe)
+    }
+
+    fn dialect(self) -> &'static str {
+        match self {
+            Layout::PsqlAligned | Layout::Expanded => "postgres",
+            Layout::MysqlTable => "mysql",
+            Layout::Tab | Layout::Pipe => "ansi",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Integer,
+    Decimal,
+    Bool,
+    Text,
+}
+
+struct Table {
+    layout: Layout,
+    columns: Vec<String>,
+    // None is NULL
+    rows: Vec<Vec<Option<String>>>,
+}
+
+fn footer() -> &'static Regex {
+    static FOOTER: OnceLock<Regex> = OnceLock::new();
+    FOOTER.get_or_init(|| {
+        Regex::new(r"(?i)^(?:\(\d+ rows?\)|\d+ rows? in set\b.*|empty set\b.*|query ok\b.*|time: [\d.]+ ms.*)$")
+            .expect("valid footer pattern")
+    })
+}
+
+fn record_header() -> &'static Regex {
+    static RECORD: OnceLock<Regex> = OnceLock::new();
+    RECORD.get_or_init(|| Regex::new(r"^-\[ RECORD \d+ \]-*\+?-*$").expect("valid record pattern"))
+}
+
+// `----+----` under a psql header, or a mysql `+----+` border
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    line.contains('-') && line.chars().all(|c| matches!(c, '-' | '+' | '='))
+}
+
+// Splits `line` at the `|` found at each of `boundaries` (char positions); None
+// when the line doesn't line up with them
+fn split_at_boundaries(line: &str, boundaries: &[usize], bordered: bool) -> Option<Vec<String>> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut cells = Vec::new();
+    let mut start = if bordered { boundaries[0] + 1 } else { 0 };
+    let inner = if bordered { &boundaries[1..] } else { boundaries };
+    for boundary in inner {
+        if chars.get(*boundary) != Some(&'|') {
+            return None;
+        }
+        cells.push(chars[start..*boundary].iter().collect::<String>().trim().to_string());
+        start = boundary + 1;
+    }
+    if !bordered {
+        cells.push(
+            chars
+                .get(start..)
+                .unwrap_or_default()
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string(),
+        );
+    }
+    Some(cells)
+}
+
+fn split_on(line: &str, separator: char, bordered: bool) -> Vec<String> {
+    let line = line.trim();
+    let line = if bordered {
+        line.strip_prefix(separator)
+            .and_then(|line| line.strip_suffix(separator))
+            .unwrap_or(line)
+    } else {
+        line
+    };
+    line.split(separator).map(|cell| cell.trim().to_string()).collect()
+}
+
+// `\t`, `\n`, `\r` and `\\` as COPY and `mysql -B` escape them; `\N` stays
+// for NULL detection
+fn unescape_tab_field(field: &str) -> String {
+    if field == "\\N" || !field.contains('\\') {
+        return field.to_string();
+    }
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('0') => out.push('\0'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn parse_expanded(lines: &[&str]) -> Table {
+    let mut columns: Vec<String> = Vec::new();
+    let mut records: Vec<Vec<(String, String)>> = Vec::new();
+    for line in lines {
+        if record_header().is_match(line.trim()) {
+            records.push(Vec::new());
+            continue;
+        }
+        let (Some(record), Some((name, value))) = (records.last_mut(), line.split_once('|')) else {
+            continue;
+        };
+        let name = name.trim().to_string();
+        if !columns.contains(&name) {
+            columns.push(name.clone());
+        }
+        record.push((name, value.trim().to_string()));
+    }
+    let rows = records
+        .into_iter()
+        .map(|record| {
+            columns
+                .iter()
+                .map(|column| {
+                    record
+                        .iter()
+                        .find(|(name, _)| name == column)
+                        .map(|(_, value)| value.clone())
+                })
+                .collect()
+        })
+        .collect();
+    Table {
+        layout: Layout::Expanded,
+        columns,
+        rows,
+    }
+}
+
+fn parse(text: &str, header: bool) -> Result<Table, String> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty() && !footer().is_match(line.trim()))
+        .collect();
+    if lines.is_empty() {
+        return Err("No query output to convert".to_string());
+    }
+    if lines.iter().any(|line| record_header().is_match(line.trim())) {
+        return Ok(parse_expanded(&lines));
+    }
+
+    let rule = lines.iter().find(|line| is_rule(line));
+    let content: Vec<&str> = lines.iter().copied().filter(|line| !is_rule(line)).collect();
+    let first = content.first().ok_or("No rows in the query output")?;
+    let (layout, boundaries) = match rule {
+        Some(rule) => {
+            let boundaries: Vec<usize> = rule
+                .trim_end()
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '+')
+                .map(|(index, _)| index)
+                .collect();
+            if rule.trim_start().starts_with('+') {
+                (Layout::MysqlTable, boundaries)
+            } else {
+                (Layout::PsqlAligned, boundaries)
+            }
+        }
+        None if first.contains('\t') => (Layout::Tab, Vec::new()),
+        None if first.contains('|') => (Layout::Pipe, Vec::new()),
+        None => return Err("Can't find column separators: expected tabs, pipes or a psql/mysql table".to_string()),
+    };
+    let bordered = layout == Layout::MysqlTable;
+    let split = |line: &str| match layout {
+        Layout::Tab => line.split('\t').map(unescape_tab_field).collect(),
+        Layout::Pipe => split_on(line, '|', false),
+        _ => {
+            let positional = (bordered && boundaries.len() >= 2 || !bordered && !boundaries.is_empty())
+                .then(|| split_at_boundaries(line, &boundaries, bordered))
+                .flatten();
+            positional.unwrap_or_else(|| split_on(line, '|', bordered))
+        }
+    };
+
+    let mut rows: Vec<Vec<String>> = content.iter().map(|line| split(line)).collect();
+    let columns = if header {
+        rows.remove(0)
+    } else {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        (1..=width).map(|index| format!("column{}", index)).collect()
+    };
+    if let Some((index, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != columns.len()) {
+        return Err(format!(
+            "Row {} has {} fields but there are {} columns",
+            index + 1,
+            row.len(),
+            columns.len()
+        ));
+    }
+    Ok(Table {
+        layout,
+        columns,
+        rows: rows
+            .into_iter()
+            .map(|row| row.into_iter().map(Some).collect())
+            .collect(),
+    })
+}
+
+fn apply_null_markers(table: &mut Table, markers: &[String], empty_is_null: bool) {
+    for cell in table.rows.iter_mut().flatten() {
+        let null = match cell.as_deref() {
+            Some("") => empty_is_null,
+            Some(value) => markers.iter().any(|marker| marker == value),
+            None => true,
+        };
+        if null {
+            *cell = None;
+        }
+    }
+}
+
+fn is_integer(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    // Leading zeros (zip codes, IDs) are kept as text
+    !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'))
+        && value.parse::<i64>().is_ok()
+}
+
+fn is_decimal(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    match digits.split_once('.') {
+        Some((whole, fraction)) => {
+            is_integer(whole)
+                && !fraction.is_empty()
+                && fraction.chars().all(|c| c.is_ascii_digit())
+                && value.parse::<f64>().is_ok_and(f64::is_finite)
+        }
+        None => false,
+    }
+}
+
+// A column is numeric or boolean only when every non-NULL value is
+fn column_kinds(table: &Table) -> Vec<Kind> {
+    (0..table.columns.len())
+        .map(|index| {
+            let values: Vec<&str> = table.rows.iter().filter_map(|row| row[index].as_deref()).collect();
+            if values.is_empty() {
+                Kind::Text
+            } else if values.iter().all(|value| is_integer(value)) {
+                Kind::Integer
+            } else if values.iter().all(|value| is_integer(value) || is_decimal(value)) {
+                Kind::Decimal
+            } else if values.iter().all(|value| matches!(*value, "true" | "false")) {
+                Kind::Bool
+            } else {
+                Kind::Text
+            }
+        })
+        .collect()
+}
+
+fn json_value(value: Option<&str>, kind: Kind) -> serde_json::Value {
+    let Some(value) = value else {
+        return serde_json::Value::Null;
+    };
+    let converted = match kind {
+        Kind::Integer => value.parse::<i64>().ok().map(Into::into),
+        Kind::Decimal => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Into::into),
+        Kind::Bool => Some((value == "true").into()),
+        Kind::Text => None,
+    };
+    converted.unwrap_or_else(|| value.into())
+}
+
+// Pretty JSON with the keys in column order (serde_json's map would sort them)
+fn to_json(table: &Table, kinds: &[Kind]) -> Result<String, String> {
+    if table.rows.is_empty() {
+        return Ok("[]".to_string());
+    }
+    let keys: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| serde_json::to_string(column).map_err(|e| e.to_string()))
+        .collect::<Result<_, _>>()?;
+    let (outer, inner) = (crate::formatters::indent(1), crate::formatters::indent(2));
+    let mut objects = Vec::with_capacity(table.rows.len());
+    for row in &table.rows {
+        let fields: Vec<String> = row
+            .iter()
+            .zip(kinds)
+            .zip(&keys)
+            .map(|((value, kind), key)| format!("{}{}: {}", inner, key, json_value(value.as_deref(), *kind)))
+            .collect();
+        objects.push(format!("{}{{\n{}\n{}}}", outer, fields.join(",\n"), outer));
+    }
+    Ok(format!("[\n{}\n]", objects.join(",\n")))
+}
+
+// Unquoted only when no dialect would fold or reject it
+fn quote_identifier(name: &str, dialect: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    match dialect {
+        _ if plain => name.to_string(),
+        "mysql" => format!("`{}`", name.replace('`', "``")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+fn sql_literal(value: Option<&str>, kind: Kind, dialect: &str) -> String {
+    match (value, kind) {
+        (None, _) => "NULL".to_string(),
+        (Some(value), Kind::Integer | Kind::Decimal) => value.to_string(),
+        (Some(value), Kind::Bool) => value.to_ascii_uppercase(),
+        // mysql treats backslashes in strings as escapes
+        (Some(value), Kind::Text) if dialect == "mysql" => {
+            format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+        }
+        (Some(value), Kind::Text) => format!("'{}'", value.replace('\'', "''")),
+    }
+}
+
+fn to_inserts(table: &Table, kinds: &[Kind], name: &str, dialect: &str, rows_per_statement: usize) -> String {
+    let target = name
+        .split('.')
+        .map(|part| quote_identifier(part.trim(), dialect))
+        .collect::<Vec<_>>()
+        .join(".");
+    let columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| quote_identifier(column, dialect))
+        .collect();
+    let prefix = format!("INSERT INTO {} ({}) VALUES", target, columns.join(", "));
+    let tuples: Vec<String> = table
+        .rows
+        .iter()
+        .map(|row| {
+            let values: Vec<String> = row
+                .iter()
+                .zip(kinds)
+                .map(|(value, kind)| sql_literal(value.as_deref(), *kind, dialect))
+                .collect();
+            format!("({})", values.join(", "))
+        })
+        .collect();
+    let chunk = if rows_per_statement == 0 {
+        tuples.len().max(1)
+    } else {
+        rows_per_statement
+    };
+    let statements: Vec<String> = tuples
+        .chunks(chunk)
+        .map(|tuples| match tuples {
+            [tuple] => format!("{} {};", prefix, tuple),
+            tuples => format!("{}\n  {};", prefix, tuples.join(",\n  ")),
+        })
+        .collect();
+    statements.join("\n") + "\n"
+}
+
+// Converts query output in `text` (or the active document's raw content) to
+// `output` "json" (the default) or "insert". The layout and NULL markers are
+// detected; `null_marker` names the one to use instead (`""` for empty
+// cells). INSERTs go into `table`, quoted for `dialect` (guessed from the
+// layout), `rows_per_statement` rows each (1 by default, 0 for all in one).
+// `header: false` names the columns column1, column2, ... With `apply` the
+// result replaces the document's raw content as an undoable edit.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn convert_query_output(
+    app: AppHandle,
+    text: Option<String>,
+    output: Option<String>,
+    table: Option<String>,
+    dialect: Option<String>,
+    null_marker: Option<String>,
+    header: Option<bool>,
+    rows_per_statement: Option<usize>,
+    apply: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("convert_query_output", move || {
+        let output = output.unwrap_or_else(|| "json".to_string());
+        if !OUTPUTS.contains(&output.as_str()) {
+            return Err(format!("Unknown output {}; use {}", output, OUTPUTS.join(", ")));
+        }
+        if let Some(dialect) = dialect.as_deref().filter(|dialect| !DIALECTS.contains(dialect)) {
+            return Err(format!("Unknown dialect {}; use {}", dialect, DIALECTS.join(", ")));
+        }
+        let apply = apply.unwrap_or(false);
+        let from_document = text.as_deref().is_none_or(str::is_empty);
+        if apply && !from_document {
+            return Err("Only the stored content can be converted in place".to_string());
+        }
+        let text = text.filter(|text| !text.is_empty());
+        let snapshot = match text {
+            Some(_) => None,
+            None => Some(
+                app.state::<AppState>()
+                    .inner()
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .snapshot_raw()?,
+            ),
+        };
+        let content = snapshot
+            .as_ref()
+            .map_or(text.as_deref().unwrap_or_default(), |snapshot| snapshot.text.as_str());
+
+        let mut parsed = parse(content, header.unwrap_or(true))?;
+        let (markers, empty_is_null) = match null_marker {
+            Some(marker) if marker.is_empty() => (Vec::new(), true),
+            Some(marker) => (vec![marker], false),
+            None => (
+                NULL_MARKERS.iter().map(|marker| marker.to_string()).collect(),
+                parsed.layout.empty_is_null(),
+            ),
+        };
+        apply_null_markers(&mut parsed, &markers, empty_is_null);
+        let kinds = column_kinds(&parsed);
+        let dialect = dialect.unwrap_or_else(|| parsed.layout.dialect().to_string());
+        let converted = if output == "json" {
+            to_json(&parsed, &kinds)?
+        } else {
+            let name = table
+                .as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .unwrap_or(DEFAULT_TABLE);
+            to_inserts(&parsed, &kinds, name, &dialect, rows_per_statement.unwrap_or(1))
+        };
+
+        // In-place conversion was refused above unless the content came from storage
+        if let (true, Some(snapshot)) = (apply, &snapshot) {
+            let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            let document = storage.snapshot_document(snapshot)?;
+            document.edit(Slot::Raw, converted.clone());
+            document.formatted_content = None;
+        }
+        let column_types: Vec<&str> = kinds
+            .iter()
+            .map(|kind| match kind {
+                Kind::Integer => "integer",
+                Kind::Decimal => "decimal",
+                Kind::Bool => "boolean",
+                Kind::Text => "text",
+            })
+            .collect();
+        Ok(serde_json::json!({
+            "content": converted,
+            "layout": parsed.layout.name(),
+            "columns": parsed.columns,
+            "column_types": column_types,
+            "rows": parsed.rows.len(),
+            "null_markers": markers,
+            "empty_is_null": empty_is_null,
+            "dialect": dialect,
+            "applied": apply
+        }))
+    })
+    .await
+}
@@ -0,0 +1,126 @@
+// `some-command | devmate --format json` (or `--stdin` to load without
+// formatting): stdin is streamed into a new "stdin" document as a job, then
+// formatted, and the window opens on it. The outcome is announced through
+// `stdin://loaded` and kept for get_stdin_result, since the frontend may not
+// be listening yet when a short pipe finishes.
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::jobs::CancelToken;
+use crate::progress::{self, ProgressReporter};
+use crate::AppState;
+
+pub const STDIN_LOADED_EVENT: &str = "stdin://loaded";
+pub const FORMAT_FLAG: &str = "--format";
+pub const STDIN_FLAG: &str = "--stdin";
+
+#[derive(Default)]
+pub struct StdinResult(Mutex<Option<serde_json::Value>>);
+
+// What the launch arguments ask for: None when stdin shouldn't be read, else
+// the formatter to run on it (if any). Stdin must be a pipe or file; a terminal
+// is never read, so `devmate --format json` alone doesn't hang waiting for input.
+pub fn requested(args: &[String]) -> Option<Option<String>> {
+    if std::io::stdin().is_terminal() {
+        return None;
+    }
+    let mut args = args.iter().skip(1);
+    let mut wanted = None;
+    while let Some(arg) = args.next() {
+        if arg == STDIN_FLAG {
+            wanted.get_or_insert(None);
+        } else if arg == FORMAT_FLAG {
+            wanted = Some(args.next().cloned());
+        } else if let Some(format_type) = arg.strip_prefix("--format=") {
+            wanted = Some(Some(format_type.to_string()));
+        }
+    }
+    wanted.map(|format_type| format_type.filter(|f| !f.is_empty()))
+}
+
+fn load_blocking(
+    app: &AppHandle,
+    token: &CancelToken,
+    format_type: Option<String>,
+) -> Result<serde_json::Value, String> {
+    // Checked before reading so a typo doesn't cost a long pipe
+    if let Some(format_type) = &format_type {
+        if app.state::<crate::formatters::FormatterRegistry>().get(format_type).is_none() {
+            return Err(format!("Unknown format {}", format_type));
+        }
+    }
+    let document_id = app
+        .state::<AppState>()
+        .inner()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert_document("stdin".to_string());
+    crate::tray::refresh(app);
+
+    let mut reporter = ProgressReporter::new(
+        app,
+        progress::FILE_READ_PROGRESS_EVENT,
+        0,
+        serde_json::json!({ "file_path": "stdin" }),
+    );
+    let (content, encoding) =
+        crate::decode_streaming(std::io::stdin().lock(), 0, token, |processed| reporter.update(processed))?;
+    let length = content.len();
+    {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        let document = storage
+            .documents
+            .get_mut(&document_id)
+            .ok_or_else(|| "The stdin document was closed while it was loading".to_string())?;
+        document.raw_content = Some(content.clone());
+        document.format_type = format_type.clone();
+    }
+
+    let mut formatted_length = None;
+    if let Some(format_type) = &format_type {
+        token.check()?;
+        let (formatted, store) = crate::run_formatter(app, content.to_string(), format_type)?;
+        formatted_length = Some(formatted.len());
+        if store {
+            let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            if let Some(document) = storage.documents.get_mut(&document_id) {
+                document.formatted_content = Some(formatted);
+            }
+        }
+    }
+
+    Ok(serde_json::json!({
+        "document_id": document_id,
+        "length": length,
+        "encoding": encoding,
+        "format_type": format_type,
+        "formatted_length": formatted_length
+    }))
+}
+
+// Starts reading stdin; called from setup when `requested` says so
+pub fn load(app: &AppHandle, format_type: Option<String>) {
+    let outcome = crate::jobs::spawn_job(app, "stdin-load", move |app, token| {
+        let outcome = load_blocking(app, token, format_type);
+        let payload = match &outcome {
+            Ok(result) => result.clone(),
+            Err(error) => serde_json::json!({ "error": error }),
+        };
+        if let Ok(mut stored) = app.state::<StdinResult>().0.lock() {
+            *stored = Some(payload.clone());
+        }
+        let _ = app.emit(STDIN_LOADED_EVENT, payload);
+        outcome
+    });
+    if let Err(e) = outcome {
+        eprintln!("Failed to read stdin: {}", e);
+    }
+}
+
+// The stdin load's outcome, or null when the app wasn't piped into or the
+// load hasn't finished
+#[tauri::command]
+pub fn get_stdin_result(result: State<StdinResult>) -> Result<serde_json::Value, String> {
+    Ok(result.0.lock().map_err(|e| e.to_string())?.clone().unwrap_or_default())
+}
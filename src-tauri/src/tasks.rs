@@ -0,0 +1,263 @@
+// Every heavy command and job runs through this queue. At most `limit` tasks
+// run at once; the rest wait asynchronously, without holding a thread, and
+// start in the order they were submitted. The queue is process-wide rather
+// than managed state because run_blocking is called from code without an
+// AppHandle.
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+const MIN_LIMIT: usize = 2;
+
+struct TaskEntry {
+    kind: String,
+    queued_at: Instant,
+    started_at: Option<Instant>,
+}
+
+struct QueueState {
+    limit: usize,
+    // Slots still to be taken out of circulation after the limit was lowered
+    // while they were busy; each is dropped as its task finishes
+    surplus: usize,
+    next_id: u64,
+    tasks: BTreeMap<u64, TaskEntry>,
+}
+
+pub struct TaskQueue {
+    // Tokio's semaphore is fair, which gives first-in first-out start order
+    permits: Arc<Semaphore>,
+    state: Mutex<QueueState>,
+    // Run whenever the last task finishes
+    idle_hook: OnceLock<Box<dyn Fn() + Send + Sync>>,
+}
+
+pub fn queue() -> &'static TaskQueue {
+    static QUEUE: OnceLock<TaskQueue> = OnceLock::new();
+    QUEUE.get_or_init(|| {
+        let limit = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(MIN_LIMIT)
+            .max(MIN_LIMIT);
+        TaskQueue::new(limit)
+    })
+}
+
+// Removes the task's entry when it finishes, or when a queued task is dropped
+struct TaskGuard(u64);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        let idle = match queue().state.lock() {
+            Ok(mut state) => {
+                state.tasks.remove(&self.0);
+                state.tasks.is_empty()
+            }
+            Err(_) => false,
+        };
+        if let Some(hook) = queue().idle_hook.get().filter(|_| idle) {
+            hook();
+        }
+    }
+}
+
+// Hands the task's slot back when the work finishes, even if it panicked
+struct SlotGuard(Option<OwnedSemaphorePermit>);
+
+impl Drop for SlotGuard {
+    fn drop(&mut self) {
+        if let Some(permit) = self.0.take() {
+            queue().release(permit);
+        }
+    }
+}
+
+impl TaskQueue {
+    fn new(limit: usize) -> Self {
+        TaskQueue {
+            permits: Arc::new(Semaphore::new(limit)),
+            state: Mutex::new(QueueState {
+                limit,
+                surplus: 0,
+                next_id: 1,
+                tasks: BTreeMap::new(),
+            }),
+            idle_hook: OnceLock::new(),
+        }
+    }
+
+    fn register(&self, kind: &str) -> TaskGuard {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let id = state.next_id;
+        state.next_id += 1;
+        state.tasks.insert(
+            id,
+            TaskEntry {
+                kind: kind.to_string(),
+                queued_at: Instant::now(),
+                started_at: None,
+            },
+        );
+        TaskGuard(id)
+    }
+
+    // Sets the hook run each time the queue empties; only the first call counts
+    pub fn on_idle(&self, hook: impl Fn() + Send + Sync + 'static) {
+        let _ = self.idle_hook.set(Box::new(hook));
+    }
+
+    fn mark_started(&self, guard: &TaskGuard) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(task) = state.tasks.get_mut(&guard.0) {
+                task.started_at = Some(Instant::now());
+            }
+        }
+    }
+
+    // Registers the task straight away, then waits for a free slot and runs
+    // `work` on the blocking pool
+    pub fn run<T, F>(
+        &'static self,
+        kind: &str,
+        work: F,
+    ) -> impl Future<Output = Result<T, String>> + Send + 'static
+    where
+        F: FnOnce() -> Result<T, String> + Send + 'static,
+        T: Send + 'static,
+    {
+        let guard = self.register(kind);
+        async move {
+            let permit = self
+                .permits
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(|_| "Task queue is closed".to_string())?;
+            self.mark_started(&guard);
+
+            // The slot stays taken until the work itself finishes, even if the
+            // caller stops waiting for it
+            let slot = SlotGuard(Some(permit));
+            tauri::async_runtime::spawn_blocking(move || {
+                let _guard = guard;
+                let _slot = slot;
+                work()
+            })
+            .await
+            .map_err(|e| format!("Background task failed: {}", e))?
+        }
+    }
+
+    // Hands a finished task's slot back, or retires it if the limit was
+    // lowered while it ran
+    fn release(&self, permit: OwnedSemaphorePermit) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.surplus > 0 {
+            state.surplus -= 1;
+            permit.forget();
+        }
+    }
+
+    fn set_limit(&self, limit: usize) -> usize {
+        let limit = limit.max(1);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if limit > state.limit {
+            // Slots not yet retired from an earlier decrease are kept instead
+            let increase = limit - state.limit;
+            let kept = increase.min(state.surplus);
+            state.surplus -= kept;
+            self.permits.add_permits(increase - kept);
+        } else {
+            // Free slots are retired now; busy ones can't be revoked, so release
+            // retires them as their tasks finish
+            state.surplus += state.limit - limit;
+            while state.surplus > 0 {
+                let Ok(permit) = self.permits.clone().try_acquire_owned() else {
+                    break;
+                };
+                permit.forget();
+                state.surplus -= 1;
+            }
+        }
+        state.limit = limit;
+        limit
+    }
+
+    fn describe(&self) -> serde_json::Value {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let tasks: Vec<serde_json::Value> = state
+            .tasks
+            .iter()
+            .map(|(id, task)| {
+                let waited = task.started_at.unwrap_or_else(Instant::now) - task.queued_at;
+                serde_json::json!({
+                    "id": id,
+                    "kind": task.kind,
+                    "status": if task.started_at.is_some() { "running" } else { "queued" },
+                    "waited_ms": waited.as_millis() as u64,
+                    "running_ms": task.started_at.map(|started| started.elapsed().as_millis() as u64)
+                })
+            })
+            .collect();
+        let running = state
+            .tasks
+            .values()
+            .filter(|task| task.started_at.is_some())
+            .count();
+        serde_json::json!({
+            "limit": state.limit,
+            "running": running,
+            "queued": state.tasks.len() - running,
+            "tasks": tasks
+        })
+    }
+}
+
+#[tauri::command]
+pub fn list_tasks() -> serde_json::Value {
+    queue().describe()
+}
+
+// Returns the limit in effect; lowering it lets running tasks finish first
+#[tauri::command]
+pub fn set_task_concurrency(limit: usize) -> usize {
+    queue().set_limit(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowering_the_limit_retires_busy_slots_as_they_finish() {
+        let queue = TaskQueue::new(4);
+        assert_eq!(queue.set_limit(2), 2);
+        assert_eq!(queue.permits.available_permits(), 2);
+        queue.set_limit(4);
+        assert_eq!(queue.permits.available_permits(), 4);
+
+        let first = queue.permits.clone().try_acquire_owned().unwrap();
+        let second = queue.permits.clone().try_acquire_owned().unwrap();
+        queue.set_limit(1);
+        assert_eq!(queue.permits.available_permits(), 0);
+        queue.release(first);
+        assert_eq!(queue.permits.available_permits(), 0);
+        queue.release(second);
+        assert_eq!(queue.permits.available_permits(), 1);
+    }
+
+    #[test]
+    fn raising_the_limit_cancels_pending_retirements() {
+        let queue = TaskQueue::new(2);
+        let first = queue.permits.clone().try_acquire_owned().unwrap();
+        let second = queue.permits.clone().try_acquire_owned().unwrap();
+        queue.set_limit(1);
+        queue.set_limit(3);
+        assert_eq!(queue.permits.available_permits(), 1);
+        queue.release(first);
+        queue.release(second);
+        assert_eq!(queue.permits.available_permits(), 3);
+    }
+}
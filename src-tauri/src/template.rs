@@ -0,0 +1,193 @@
+use handlebars::Handlebars;
+use std::error::Error;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+// Lenient Tera rendering fills in at most this many missing variables
+const MAX_FILLED_VARIABLES: usize = 64;
+
+// Tera nests the useful message (with line/column) inside its source chain
+fn tera_error_message(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(inner) = source {
+        message.push_str(&format!("\n  caused by: {}", inner));
+        source = inner.source();
+    }
+    message
+}
+
+fn render_handlebars(
+    template: &str,
+    context: &serde_json::Value,
+    strict: bool,
+    autoescape: bool,
+) -> Result<String, serde_json::Value> {
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(strict);
+    if !autoescape {
+        handlebars.register_escape_fn(handlebars::no_escape);
+    }
+    handlebars
+        .register_template_string("template", template)
+        .map_err(|e| {
+            serde_json::json!({
+                "message": e.to_string(),
+                "line": e.pos().map(|(line, _)| line),
+                "column": e.pos().map(|(_, column)| column)
+            })
+        })?;
+    handlebars.render("template", context).map_err(|e| {
+        serde_json::json!({
+            "message": e.to_string(),
+            "line": e.line_no,
+            "column": e.column_no
+        })
+    })
+}
+
+// The variable a Tera render failed on for not being in the context
+fn missing_variable(message: &str) -> Option<&str> {
+    let rest = message.split("Variable `").nth(1)?;
+    let (name, rest) = rest.split_once('`')?;
+    rest.starts_with(" not found in context").then_some(name)
+}
+
+// Adds `path` ("user.name") to the context as an empty string. Returns false
+// when that can't help: the path is already there (a loop variable, say) or
+// runs through a value that isn't an object.
+fn fill_variable(context: &mut serde_json::Value, path: &str) -> bool {
+    let mut value = context;
+    let mut keys = path.split('.').peekable();
+    while let Some(key) = keys.next() {
+        let Some(object) = value.as_object_mut() else {
+            return false;
+        };
+        if keys.peek().is_none() {
+            if object.contains_key(key) {
+                return false;
+            }
+            object.insert(key.to_string(), "".into());
+            return true;
+        }
+        value = object.entry(key).or_insert_with(|| serde_json::json!({}));
+    }
+    false
+}
+
+// Tera always fails on an undefined variable. That is what `strict` asks for;
+// otherwise each missing variable is filled in as empty and the render is
+// retried, matching non-strict Handlebars.
+fn render_tera(
+    template: &str,
+    context: &serde_json::Value,
+    strict: bool,
+    autoescape: bool,
+) -> Result<String, serde_json::Value> {
+    let mut context = context.clone();
+    let mut filled = 0;
+    loop {
+        let tera_context = tera::Context::from_value(context.clone())
+            .map_err(|e| serde_json::json!({ "message": tera_error_message(&e) }))?;
+        let error = match tera::Tera::one_off(template, &tera_context, autoescape) {
+            Ok(output) => return Ok(output),
+            Err(e) => tera_error_message(&e),
+        };
+        let retry = !strict
+            && filled < MAX_FILLED_VARIABLES
+            && missing_variable(&error).is_some_and(|path| fill_variable(&mut context, path));
+        if !retry {
+            return Err(serde_json::json!({ "message": error }));
+        }
+        filled += 1;
+    }
+}
+
+fn render_template_blocking(
+    engine: String,
+    template: Option<String>,
+    context: Option<String>,
+    strict: Option<bool>,
+    autoescape: Option<bool>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    // Whichever of template/context isn't supplied comes from the stored content
+    let stored = {
+        let storage = state.lock().map_err(|e| e.to_string())?;
+        storage.active().raw_content.as_deref().map(str::to_string)
+    };
+    let template = match template.filter(|t| !t.is_empty()) {
+        Some(template) => template,
+        None => stored.clone().ok_or_else(|| "No template supplied or stored".to_string())?,
+    };
+    let context_text = match context.filter(|c| !c.trim().is_empty()) {
+        Some(context) => context,
+        None => stored.unwrap_or_else(|| "{}".to_string()),
+    };
+    let context: serde_json::Value = serde_json::from_str(&context_text)
+        .map_err(|e| format!("Invalid JSON context at line {} column {}: {}", e.line(), e.column(), e))?;
+
+    let strict = strict.unwrap_or(false);
+    let result = match engine.as_str() {
+        "handlebars" => render_handlebars(&template, &context, strict, autoescape.unwrap_or(true)),
+        "tera" => render_tera(&template, &context, strict, autoescape.unwrap_or(false)),
+        _ => return Err(format!("Unknown template engine: {}", engine)),
+    };
+
+    match result {
+        Ok(output) => {
+            let mut storage = state.lock().map_err(|e| e.to_string())?;
+            storage.active_mut().formatted_content = Some(output.clone().into());
+            Ok(serde_json::json!({
+                "success": true,
+                "output": output
+            }))
+        }
+        Err(error) => Ok(serde_json::json!({
+            "success": false,
+            "error": error
+        })),
+    }
+}
+
+// `strict` makes undefined variables an error instead of rendering empty.
+// `autoescape` HTML-escapes substituted values; it defaults to each engine's
+// own default, on for Handlebars and off for Tera.
+#[tauri::command]
+pub async fn render_template(
+    app: AppHandle,
+    engine: String,
+    template: Option<String>,
+    context: Option<String>,
+    strict: Option<bool>,
+    autoescape: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("render_template", move || {
+        render_template_blocking(engine, template, context, strict, autoescape, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tera_strict_rejects_undefined_variables() {
+        let context = json!({ "user": { "name": "Ada" } });
+        let error = render_tera("{{ user.email }}", &context, true, false).unwrap_err();
+        assert!(error["message"].as_str().unwrap().contains("user.email"));
+        assert_eq!(render_tera("[{{ user.email }}|{{ other }}]", &context, false, false).unwrap(), "[|]");
+    }
+
+    #[test]
+    fn autoescape_is_separate_from_strict() {
+        let context = json!({ "html": "<b>" });
+        assert_eq!(render_tera("{{ html }}", &context, true, false).unwrap(), "<b>");
+        assert_eq!(render_tera("{{ html }}", &context, false, true).unwrap(), "&lt;b&gt;");
+        assert_eq!(render_handlebars("{{html}}", &context, false, false).unwrap(), "<b>");
+        assert_eq!(render_handlebars("{{html}}", &context, false, true).unwrap(), "&lt;b&gt;");
+    }
+}
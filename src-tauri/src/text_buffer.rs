@@ -0,0 +1,451 @@
+use memmap2::Mmap;
+use rayon::prelude::*;
+use serde::{Serialize, Serializer};
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use unicode_segmentation::GraphemeCursor;
+
+use crate::jobs::CancelToken;
+use crate::progress::ProgressReporter;
+
+// UTF-8 validation runs in slices this size so progress can be reported
+const VALIDATE_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+// Compressed text is split into independently decodable frames of about this
+// size, so a chunk read only inflates the frames it overlaps
+const FRAME_SIZE: usize = 1024 * 1024;
+// Smaller texts aren't worth the CPU
+const COMPRESS_THRESHOLD: usize = 4 * 1024 * 1024;
+const COMPRESSION_LEVEL: i32 = 1;
+
+// Off by default; toggled with set_content_compression
+static COMPRESS_CONTENT: AtomicBool = AtomicBool::new(false);
+static NEXT_BUFFER_ID: AtomicU64 = AtomicU64::new(1);
+
+pub fn compression_enabled() -> bool {
+    COMPRESS_CONTENT.load(Ordering::Relaxed)
+}
+
+pub fn set_compression_enabled(enabled: bool) {
+    COMPRESS_CONTENT.store(enabled, Ordering::Relaxed);
+}
+
+enum Backing {
+    Owned(String),
+    // A private copy of the file (see map_utf8_file); `start` skips a UTF-8 BOM
+    Mapped { map: Mmap, start: usize },
+    Compressed(CompressedText),
+}
+
+struct CompressedText {
+    // zstd frames, each holding whole UTF-8 characters
+    frames: Vec<Vec<u8>>,
+    // Uncompressed offset of each frame's first byte
+    frame_starts: Vec<usize>,
+    len: usize,
+    // Whole-text accesses (Deref, line indexing) inflate everything once and
+    // keep it until release_inflated drops it again
+    expanded: OnceLock<String>,
+}
+
+impl CompressedText {
+    fn compress(text: &str) -> Option<Self> {
+        let mut frame_starts = vec![0];
+        while let Some(&last) = frame_starts.last() {
+            let mut end = (last + FRAME_SIZE).min(text.len());
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            if end == text.len() {
+                break;
+            }
+            frame_starts.push(end);
+        }
+        let frames = frame_starts
+            .par_iter()
+            .enumerate()
+            .map(|(i, start)| {
+                let end = frame_starts.get(i + 1).copied().unwrap_or(text.len());
+                zstd::bulk::compress(&text.as_bytes()[*start..end], COMPRESSION_LEVEL).ok()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(CompressedText {
+            frames,
+            frame_starts,
+            len: text.len(),
+            expanded: OnceLock::new(),
+        })
+    }
+
+    fn frame_end(&self, index: usize) -> usize {
+        self.frame_starts.get(index + 1).copied().unwrap_or(self.len)
+    }
+
+    fn inflate(&self, frames: std::ops::Range<usize>) -> String {
+        let capacity = self.frame_end(frames.end - 1) - self.frame_starts[frames.start];
+        let mut bytes = Vec::with_capacity(capacity);
+        for index in frames {
+            let frame_len = self.frame_end(index) - self.frame_starts[index];
+            let decoded = zstd::bulk::decompress(&self.frames[index], frame_len)
+                .expect("frames were compressed in-process");
+            bytes.extend_from_slice(&decoded);
+        }
+        // Frames are cut on character boundaries, so any run of them is valid UTF-8
+        String::from_utf8(bytes).expect("frames hold whole characters")
+    }
+
+    fn as_str(&self) -> &str {
+        self.expanded.get_or_init(|| {
+            if self.frames.is_empty() {
+                String::new()
+            } else {
+                self.inflate(0..self.frames.len())
+            }
+        })
+    }
+
+    fn compressed_len(&self) -> usize {
+        self.frames.iter().map(Vec::len).sum()
+    }
+}
+
+struct BufferData {
+    backing: Backing,
+    // Unique per buffer, so caches derived from the text can tell when it was replaced
+    id: u64,
+    // Byte offset of every line start, built on first line-based access
+    line_starts: OnceLock<Vec<usize>>,
+}
+
+// Stored text, either owned or a read-only map of a private on-disk copy of a
+// UTF-8 file. Mapping lets multi-GB files be viewed without copying them into
+// memory. Clones share
+// the text, so a command can take a snapshot and release the storage lock
+// before working on it.
+#[derive(Clone)]
+pub struct TextBuffer(Arc<BufferData>);
+
+impl TextBuffer {
+    fn new(backing: Backing) -> Self {
+        TextBuffer(Arc::new(BufferData {
+            backing,
+            id: NEXT_BUFFER_ID.fetch_add(1, Ordering::Relaxed),
+            line_starts: OnceLock::new(),
+        }))
+    }
+
+    pub fn id(&self) -> u64 {
+        self.0.id
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.0.backing {
+            Backing::Owned(text) => text,
+            // SAFETY: the map is of a private spill file whose bytes were all
+            // validated as UTF-8 and are never written again (map_utf8_file)
+            Backing::Mapped { map, start } => unsafe { std::str::from_utf8_unchecked(&map[*start..]) },
+            Backing::Compressed(compressed) => compressed.as_str(),
+        }
+    }
+
+    // Shadows str::len so sizes can be read without inflating compressed text
+    pub fn len(&self) -> usize {
+        match &self.0.backing {
+            Backing::Compressed(compressed) => compressed.len,
+            _ => self.as_str().len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.0.backing, Backing::Mapped { .. })
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        matches!(self.0.backing, Backing::Compressed(_))
+    }
+
+    // Bytes held in memory for this text; mapped files count as zero
+    pub fn resident_len(&self) -> usize {
+        match &self.0.backing {
+            Backing::Owned(text) => text.len(),
+            Backing::Mapped { .. } => 0,
+            Backing::Compressed(compressed) => {
+                compressed.compressed_len() + compressed.expanded.get().map(String::len).unwrap_or(0)
+            }
+        }
+    }
+
+    // Text covering at least `start..end`, with the offset of its first byte.
+    // Compressed text only inflates the overlapping frames; everything else
+    // (including already inflated text) borrows the whole string.
+    pub fn window(&self, start: usize, end: usize) -> (usize, Cow<'_, str>) {
+        match &self.0.backing {
+            Backing::Compressed(compressed) if compressed.expanded.get().is_none() && !compressed.frames.is_empty() => {
+                let first = compressed.frame_starts.partition_point(|s| *s <= start).saturating_sub(1);
+                let last = compressed.frame_starts.partition_point(|s| *s < end.max(start + 1));
+                (compressed.frame_starts[first], Cow::Owned(compressed.inflate(first..last.max(first + 1))))
+            }
+            _ => (0, Cow::Borrowed(self.as_str())),
+        }
+    }
+
+    // Drops the copy a whole-text access inflated, keeping the buffer's id and
+    // line index since the text itself is unchanged. Text still shared with a
+    // running command keeps it until the next call.
+    pub fn release_inflated(&mut self) {
+        if let Some(BufferData {
+            backing: Backing::Compressed(compressed),
+            ..
+        }) = Arc::get_mut(&mut self.0)
+        {
+            compressed.expanded.take();
+        }
+    }
+
+    // Re-stores the text to match the compression setting. Compressed text that
+    // was inflated by a whole-text access is packed down again.
+    // Text still shared with a running command is left as it is.
+    pub fn repack(self) -> TextBuffer {
+        let data = match Arc::try_unwrap(self.0) {
+            Ok(data) => data,
+            Err(shared) => return TextBuffer(shared),
+        };
+        match data.backing {
+            Backing::Owned(text) => text.into(),
+            Backing::Compressed(mut compressed) if !compression_enabled() => {
+                let text = match compressed.expanded.take() {
+                    Some(text) => text,
+                    None if compressed.frames.is_empty() => String::new(),
+                    None => compressed.inflate(0..compressed.frames.len()),
+                };
+                TextBuffer::new(Backing::Owned(text))
+            }
+            Backing::Compressed(mut compressed) => {
+                compressed.expanded = OnceLock::new();
+                TextBuffer::new(Backing::Compressed(compressed))
+            }
+            backing => TextBuffer::new(backing),
+        }
+    }
+
+    fn line_starts(&self) -> &[usize] {
+        self.0.line_starts.get_or_init(|| {
+            let mut starts = vec![0];
+            starts.extend(
+                self.as_bytes()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| **b == b'\n')
+                    .map(|(i, _)| i + 1),
+            );
+            starts
+        })
+    }
+
+    // Counted like str::lines: a trailing newline doesn't start another line
+    pub fn line_count(&self) -> usize {
+        let starts = self.line_starts().len();
+        if self.is_empty() {
+            0
+        } else if self.ends_with('\n') {
+            starts - 1
+        } else {
+            starts
+        }
+    }
+
+    pub fn line_start_offset(&self, line: usize) -> Option<usize> {
+        self.line_starts().get(line).copied()
+    }
+
+    // 0-based index of the line containing byte `offset`
+    pub fn line_of_offset(&self, offset: usize) -> usize {
+        self.line_starts().partition_point(|start| *start <= offset) - 1
+    }
+
+    // Line `index` (0-based) without its line ending
+    pub fn line(&self, index: usize) -> Option<&str> {
+        if index >= self.line_count() {
+            return None;
+        }
+        let starts = self.line_starts();
+        let end = starts.get(index + 1).map(|next| next - 1).unwrap_or(self.len());
+        let line = &self.as_str()[starts[index]..end];
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+}
+
+impl Deref for TextBuffer {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+// Serializes as the plain string, so commands can return stored text without copying it
+impl Serialize for TextBuffer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl From<String> for TextBuffer {
+    fn from(text: String) -> Self {
+        if compression_enabled() && text.len() >= COMPRESS_THRESHOLD {
+            if let Some(compressed) = CompressedText::compress(&text) {
+                return TextBuffer::new(Backing::Compressed(compressed));
+            }
+        }
+        TextBuffer::new(Backing::Owned(text))
+    }
+}
+
+// Nearest grapheme cluster boundary at or before `offset`, so a slice never
+// splits a multi-byte character or an emoji sequence
+pub fn grapheme_floor(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let mut cursor = GraphemeCursor::new(offset, text.len(), true);
+    match cursor.is_boundary(text, 0) {
+        Ok(true) => offset,
+        _ => cursor.prev_boundary(text, 0).ok().flatten().unwrap_or(offset),
+    }
+}
+
+// First grapheme cluster boundary after `offset`
+pub fn next_grapheme_boundary(text: &str, offset: usize) -> usize {
+    let mut cursor = GraphemeCursor::new(offset, text.len(), true);
+    cursor.next_boundary(text, 0).ok().flatten().unwrap_or(text.len())
+}
+
+// A file only this process can reach: unlinked as soon as it's open on Unix,
+// unshared and deleted with its last handle (the map's included) on Windows
+fn create_spill_file() -> Result<File, String> {
+    static NEXT_SPILL_ID: AtomicU64 = AtomicU64::new(1);
+    let path = std::env::temp_dir().join(format!(
+        "devmate-{}-{}.spill",
+        std::process::id(),
+        NEXT_SPILL_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        const GENERIC_READ: u32 = 0x8000_0000;
+        const GENERIC_WRITE: u32 = 0x4000_0000;
+        const DELETE: u32 = 0x0001_0000;
+        const FILE_FLAG_DELETE_ON_CLOSE: u32 = 0x0400_0000;
+        options
+            .access_mode(GENERIC_READ | GENERIC_WRITE | DELETE)
+            .share_mode(0)
+            .custom_flags(FILE_FLAG_DELETE_ON_CLOSE);
+    }
+    let file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to create spill file: {}", e))?;
+    #[cfg(not(windows))]
+    let _ = std::fs::remove_file(&path);
+    Ok(file)
+}
+
+// Copies `file` into a private spill file, checking it is UTF-8 on the way,
+// and maps the copy. Mapping the file itself isn't sound: another process
+// could rewrite it after validation and break the `str` invariant. Returns
+// Ok(None), with `file` rewound, when it isn't UTF-8 so the caller can fall
+// back to reading and transcoding it.
+pub fn map_utf8_file(
+    file: &mut File,
+    token: &CancelToken,
+    reporter: &mut ProgressReporter,
+) -> Result<Option<TextBuffer>, String> {
+    let mut spill = create_spill_file()?;
+    let mut buffer = vec![0; VALIDATE_CHUNK_SIZE];
+    // Leading bytes of a character split across reads, moved to the buffer's front
+    let mut carried = 0;
+    let mut total_read = 0;
+    loop {
+        token.check()?;
+        let read = file
+            .read(&mut buffer[carried..])
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let filled = carried + read;
+        let valid = match std::str::from_utf8(&buffer[..filled]) {
+            Ok(_) => filled,
+            // More input may complete the character
+            Err(e) if e.error_len().is_none() && read > 0 => e.valid_up_to(),
+            Err(_) => {
+                file.seek(SeekFrom::Start(0))
+                    .map_err(|e| format!("Failed to read file: {}", e))?;
+                return Ok(None);
+            }
+        };
+        spill
+            .write_all(&buffer[..valid])
+            .map_err(|e| format!("Failed to write spill file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        buffer.copy_within(valid..filled, 0);
+        carried = filled - valid;
+        total_read += read as u64;
+        reporter.update(total_read);
+    }
+
+    // SAFETY: only this process can open the spill file (see create_spill_file),
+    // every byte in it was validated as UTF-8 before being written, and nothing
+    // writes to it again, so the mapped bytes stay valid UTF-8 for the map's life.
+    let map = unsafe { Mmap::map(&spill) }.map_err(|e| format!("Failed to map file: {}", e))?;
+    let start = if map.starts_with(&[0xEF, 0xBB, 0xBF]) { 3 } else { 0 };
+    Ok(Some(TextBuffer::new(Backing::Mapped { map, start })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compressed(text: &str) -> TextBuffer {
+        TextBuffer::new(Backing::Compressed(CompressedText::compress(text).unwrap()))
+    }
+
+    #[test]
+    fn release_inflated_drops_the_whole_text_copy() {
+        let text = "line\n".repeat(FRAME_SIZE / 2);
+        let mut buffer = compressed(&text);
+        let id = buffer.id();
+        assert_eq!(buffer.line_count(), FRAME_SIZE / 2);
+        assert!(buffer.resident_len() > text.len());
+
+        buffer.release_inflated();
+        assert!(buffer.resident_len() < text.len());
+        assert_eq!(buffer.id(), id);
+        assert_eq!(buffer.as_str(), text);
+    }
+
+    #[test]
+    fn shared_text_keeps_its_inflated_copy() {
+        let mut buffer = compressed(&"x".repeat(FRAME_SIZE + 1));
+        let _ = buffer.as_str();
+        let snapshot = buffer.clone();
+        buffer.release_inflated();
+        assert!(buffer.resident_len() > FRAME_SIZE);
+        drop(snapshot);
+        buffer.release_inflated();
+        assert!(buffer.resident_len() < FRAME_SIZE);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::x509;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, ClientConnection, DigitallySignedStruct, SignatureScheme};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Accepts whatever chain the server presents so that expired or mismatched
+// certificates can still be inspected; signatures are still checked.
+#[derive(Debug)]
+struct InspectOnlyVerifier(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for InspectOnlyVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn fetch_peer_chain(host: &str, port: u16) -> Result<(Vec<CertificateDer<'static>>, String, String), String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Failed to configure TLS: {}", e))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(InspectOnlyVerifier(provider)))
+        .with_no_client_auth();
+
+    let server_name = ServerName::try_from(host.to_string())
+        .map_err(|e| format!("Invalid host name: {}", e))?;
+    let mut conn = ClientConnection::new(Arc::new(config), server_name)
+        .map_err(|e| format!("Failed to start TLS session: {}", e))?;
+
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Failed to resolve {}:{}: {}", host, port, e))?
+        .next()
+        .ok_or_else(|| format!("No addresses found for {}:{}", host, port))?;
+    let mut sock = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+        .map_err(|e| format!("Failed to connect to {}:{}: {}", host, port, e))?;
+    sock.set_read_timeout(Some(CONNECT_TIMEOUT)).ok();
+    sock.set_write_timeout(Some(CONNECT_TIMEOUT)).ok();
+
+    while conn.is_handshaking() {
+        conn.complete_io(&mut sock)
+            .map_err(|e| format!("TLS handshake failed: {}", e))?;
+    }
+
+    let chain = conn
+        .peer_certificates()
+        .map(|certs| certs.to_vec())
+        .ok_or_else(|| "Server did not present a certificate".to_string())?;
+    let protocol = conn
+        .protocol_version()
+        .map(|v| format!("{:?}", v))
+        .unwrap_or_default();
+    let cipher_suite = conn
+        .negotiated_cipher_suite()
+        .map(|s| format!("{:?}", s.suite()))
+        .unwrap_or_default();
+
+    Ok((chain, protocol, cipher_suite))
+}
+
+fn inspect_tls_certificate_blocking(
+    host: String,
+    port: Option<u16>,
+) -> Result<serde_json::Value, String> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err("Empty host".to_string());
+    }
+    let port = port.unwrap_or(443);
+
+    let (chain, protocol, cipher_suite) = fetch_peer_chain(host, port)?;
+
+    let mut certificates = Vec::new();
+    let mut warnings = Vec::new();
+    for (index, der) in chain.iter().enumerate() {
+        // One unparseable certificate doesn't hide the rest of the chain
+        let description = match x509::describe_certificate(der.as_ref()) {
+            Ok(description) => description,
+            Err(e) => {
+                warnings.push(format!("Certificate #{}: {}", index, e));
+                certificates.push(serde_json::json!({ "error": e }));
+                continue;
+            }
+        };
+        if let Some(cert_warnings) = description["warnings"].as_array() {
+            for warning in cert_warnings.iter().filter_map(|w| w.as_str()) {
+                warnings.push(format!("Certificate #{}: {}", index, warning));
+            }
+        }
+        certificates.push(description);
+    }
+
+    // A leaf that couldn't be parsed is already reported above
+    let hostname_match = match chain.first() {
+        Some(leaf) => x509::hostname_matches(leaf.as_ref(), host).unwrap_or(false),
+        None => false,
+    };
+    if !hostname_match {
+        warnings.push(format!("Certificate does not match host name '{}'", host));
+    }
+
+    Ok(serde_json::json!({
+        "host": host,
+        "port": port,
+        "protocol": protocol,
+        "cipher_suite": cipher_suite,
+        "hostname_match": hostname_match,
+        "chain_length": certificates.len(),
+        "certificates": certificates,
+        "warnings": warnings
+    }))
+}
+
+#[tauri::command]
+pub async fn inspect_tls_certificate(
+    host: String,
+    port: Option<u16>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("inspect_tls_certificate", move || inspect_tls_certificate_blocking(host, port)).await
+}
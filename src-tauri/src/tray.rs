@@ -0,0 +1,153 @@
+// Tray icon that keeps devmate resident: closing the main window only hides it,
+// and the tray menu runs the common clipboard actions or reopens a document.
+// Outcomes are reported to the frontend through `tray://action`.
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, Wry};
+
+use crate::clipboard;
+use crate::AppState;
+
+pub const TRAY_ID: &str = "main";
+pub const TRAY_ACTION_EVENT: &str = "tray://action";
+
+const MAIN_WINDOW_LABEL: &str = "main";
+const DOCUMENT_ITEM_PREFIX: &str = "document:";
+// Extra windows are labelled window-1, window-2, ...; capabilities match on window-*
+static NEXT_WINDOW: AtomicU64 = AtomicU64::new(1);
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let documents = Submenu::with_id(app, "documents", "Documents", true)?;
+    if let Ok(storage) = app.state::<AppState>().inner().lock() {
+        // Newest first, which is the closest thing to "recent" open documents have
+        for (id, document) in storage.documents.iter().rev().take(10) {
+            let label = if *id == storage.active_id {
+                format!("{} (active)", document.name)
+            } else {
+                document.name.clone()
+            };
+            let item = MenuItem::with_id(app, format!("{}{}", DOCUMENT_ITEM_PREFIX, id), label, true, None::<&str>)?;
+            documents.append(&item)?;
+        }
+    }
+
+    Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, "format-json", "Format clipboard as JSON", true, None::<&str>)?,
+            &MenuItem::with_id(app, "decode-jwt", "Decode clipboard JWT", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "new-window", "New window", true, None::<&str>)?,
+            &documents,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "show", "Show devmate", true, None::<&str>)?,
+            &MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?,
+        ],
+    )
+}
+
+// Creates the tray icon; called once from setup
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("devmate")
+        .menu(&build_menu(app)?)
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+// Rebuilds the menu so the documents list matches storage. Must be called
+// without the storage lock held.
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("Failed to rebuild tray menu: {}", e),
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn report(app: &AppHandle, action: &str, outcome: Result<serde_json::Value, String>) {
+    let payload = match outcome {
+        Ok(result) => serde_json::json!({ "action": action, "result": result }),
+        Err(error) => serde_json::json!({ "action": action, "error": error }),
+    };
+    let _ = app.emit(TRAY_ACTION_EVENT, payload);
+}
+
+fn format_clipboard(app: &AppHandle, action: &'static str, format_type: &'static str) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let worker = app.clone();
+        let outcome = crate::run_blocking(action, move || {
+            let result = clipboard::format_clipboard_blocking(&worker, format_type.to_string())?;
+            serde_json::to_value(result).map_err(|e| e.to_string())
+        })
+        .await;
+        show_main_window(&app);
+        report(&app, action, outcome);
+    });
+}
+
+fn open_new_window(app: &AppHandle) -> Result<serde_json::Value, String> {
+    let label = format!("window-{}", NEXT_WINDOW.fetch_add(1, Ordering::Relaxed));
+    WebviewWindowBuilder::new(app, &label, WebviewUrl::App("index.html".into()))
+        .title("devmate")
+        .inner_size(800.0, 600.0)
+        .build()
+        .map_err(|e| e.to_string())?;
+    Ok(serde_json::json!({ "label": label }))
+}
+
+fn switch_document(app: &AppHandle, id: u64) -> Result<serde_json::Value, String> {
+    {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        if !storage.documents.contains_key(&id) {
+            return Err(format!("Document {} not found", id));
+        }
+        storage.active_id = id;
+    }
+    refresh(app);
+    show_main_window(app);
+    Ok(serde_json::json!({ "id": id }))
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "format-json" => format_clipboard(app, "format-json", "json"),
+        "decode-jwt" => format_clipboard(app, "decode-jwt", "jwt"),
+        "new-window" => report(app, id, open_new_window(app)),
+        "show" => show_main_window(app),
+        "quit" => app.exit(0),
+        _ => {
+            if let Some(document_id) = id.strip_prefix(DOCUMENT_ITEM_PREFIX).and_then(|d| d.parse().ok()) {
+                report(app, "switch-document", switch_document(app, document_id));
+            }
+        }
+    }
+}
+
+// Hides the main window instead of closing it, so the app stays in the tray
+pub fn on_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        if window.label() == MAIN_WINDOW_LABEL && window.app_handle().tray_by_id(TRAY_ID).is_some() {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use tauri::State;
+
+use crate::responses::UndoResult;
+use crate::text_buffer::TextBuffer;
+use crate::{AppState, Document};
+
+const MAX_UNDO_STEPS: usize = 20;
+// Revisions hold whole documents, so the stack is also capped by total size
+const MAX_UNDO_BYTES: usize = 1024 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Slot {
+    Raw,
+    Formatted,
+    Binary,
+}
+
+impl Slot {
+    fn name(self) -> &'static str {
+        match self {
+            Slot::Raw => "raw",
+            Slot::Formatted => "formatted",
+            Slot::Binary => "binary",
+        }
+    }
+}
+
+// The content a slot held before an edit. Values are moved, not copied, so an
+// undo step costs no more memory than keeping the old revision alive.
+pub struct Revision {
+    slot: Slot,
+    content: Saved,
+}
+
+enum Saved {
+    Text(Option<TextBuffer>),
+    // A raw edit that also dropped the formatted view of the old text
+    RawAndFormatted {
+        raw: Option<TextBuffer>,
+        formatted: Option<TextBuffer>,
+    },
+    // Byte edits overwrite in place, so only the overwritten range is kept
+    Bytes { offset: usize, bytes: Vec<u8> },
+}
+
+impl Revision {
+    fn size(&self) -> usize {
+        match &self.content {
+            Saved::Text(content) => content.as_ref().map(|c| c.len()).unwrap_or(0),
+            Saved::RawAndFormatted { raw, formatted } => {
+                raw.as_ref().map(|c| c.len()).unwrap_or(0) + formatted.as_ref().map(|c| c.len()).unwrap_or(0)
+            }
+            Saved::Bytes { bytes, .. } => bytes.len(),
+        }
+    }
+}
+
+impl Document {
+    fn slot_mut(&mut self, slot: Slot) -> &mut Option<TextBuffer> {
+        match slot {
+            Slot::Raw => &mut self.raw_content,
+            Slot::Formatted => &mut self.formatted_content,
+            Slot::Binary => unreachable!("binary content is edited by range"),
+        }
+    }
+
+    // Replaces one slot as an undoable edit; in-place transforms go through here
+    pub fn edit(&mut self, slot: Slot, content: String) {
+        let previous = self.slot_mut(slot).replace(content.into());
+        self.push_revision(Revision {
+            slot,
+            content: Saved::Text(previous),
+        });
+    }
+
+    // Replaces the raw content as an undoable edit and drops the formatted view
+    // of the old text; undoing brings both back
+    pub fn edit_raw(&mut self, content: String) {
+        let raw = self.raw_content.replace(content.into());
+        let formatted = self.formatted_content.take();
+        self.push_revision(Revision {
+            slot: Slot::Raw,
+            content: Saved::RawAndFormatted { raw, formatted },
+        });
+    }
+
+    // Overwrites binary content from `offset` as an undoable edit and returns
+    // the bytes that were there
+    pub fn edit_bytes(&mut self, offset: usize, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let previous = overwrite(self.binary_content.as_mut(), offset, bytes)?;
+        self.push_revision(Revision {
+            slot: Slot::Binary,
+            content: Saved::Bytes {
+                offset,
+                bytes: previous.clone(),
+            },
+        });
+        Ok(previous)
+    }
+
+    fn push_revision(&mut self, revision: Revision) {
+        self.undo_stack.push(revision);
+        self.redo_stack.clear();
+
+        while self.undo_stack.len() > MAX_UNDO_STEPS
+            || self.undo_stack.iter().map(Revision::size).sum::<usize>() > MAX_UNDO_BYTES
+        {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    // New binary content; byte edits recorded against the old bytes no longer apply
+    pub fn set_binary(&mut self, bytes: Vec<u8>) {
+        self.binary_content = Some(bytes);
+        self.undo_stack.retain(|revision| revision.slot != Slot::Binary);
+        self.redo_stack.retain(|revision| revision.slot != Slot::Binary);
+    }
+
+    pub fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    fn step(&mut self, redo: bool) -> Result<Slot, String> {
+        let stack = if redo { &mut self.redo_stack } else { &mut self.undo_stack };
+        let revision = stack
+            .pop()
+            .ok_or_else(|| if redo { "Nothing to redo" } else { "Nothing to undo" }.to_string())?;
+        let current = match revision.content {
+            Saved::Text(content) => Saved::Text(std::mem::replace(self.slot_mut(revision.slot), content)),
+            Saved::RawAndFormatted { raw, formatted } => Saved::RawAndFormatted {
+                raw: std::mem::replace(&mut self.raw_content, raw),
+                formatted: std::mem::replace(&mut self.formatted_content, formatted),
+            },
+            Saved::Bytes { offset, bytes } => match overwrite(self.binary_content.as_mut(), offset, &bytes) {
+                Ok(previous) => Saved::Bytes { offset, bytes: previous },
+                Err(e) => {
+                    // Nothing was overwritten; put the revision back so the
+                    // stacks still match the content
+                    let revision = Revision {
+                        slot: revision.slot,
+                        content: Saved::Bytes { offset, bytes },
+                    };
+                    if redo {
+                        self.redo_stack.push(revision);
+                    } else {
+                        self.undo_stack.push(revision);
+                    }
+                    return Err(e);
+                }
+            },
+        };
+        let reverse = Revision {
+            slot: revision.slot,
+            content: current,
+        };
+        if redo {
+            self.undo_stack.push(reverse);
+        } else {
+            self.redo_stack.push(reverse);
+        }
+        Ok(revision.slot)
+    }
+}
+
+// Replaces `bytes.len()` bytes at `offset`, returning the replaced ones
+fn overwrite(content: Option<&mut Vec<u8>>, offset: usize, bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let content = content.ok_or_else(|| "No binary content stored".to_string())?;
+    let end = offset
+        .checked_add(bytes.len())
+        .filter(|end| *end <= content.len())
+        .ok_or_else(|| {
+            format!(
+                "Bytes {}..{} are past the end ({} bytes)",
+                offset,
+                offset.saturating_add(bytes.len()),
+                content.len()
+            )
+        })?;
+    let previous = content[offset..end].to_vec();
+    content[offset..end].copy_from_slice(bytes);
+    Ok(previous)
+}
+
+fn step_content(state: State<AppState>, redo: bool) -> Result<UndoResult, String> {
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    let document = storage.active_mut();
+    let slot = document.step(redo)?;
+
+    let length = match slot {
+        Slot::Raw => document.raw_content.as_ref().map(|c| c.len()),
+        Slot::Formatted => document.formatted_content.as_ref().map(|c| c.len()),
+        Slot::Binary => document.binary_content.as_ref().map(|b| b.len()),
+    };
+    Ok(UndoResult {
+        content_type: slot.name().to_string(),
+        length: length.unwrap_or(0),
+        undo_steps: document.undo_stack.len(),
+        redo_steps: document.redo_stack.len(),
+    })
+}
+
+#[tauri::command]
+pub fn undo_content(state: State<AppState>) -> Result<UndoResult, String> {
+    step_content(state, false)
+}
+
+#[tauri::command]
+pub fn redo_content(state: State<AppState>) -> Result<UndoResult, String> {
+    step_content(state, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_byte_undo_keeps_the_revision() {
+        let mut document = Document::default();
+        document.set_binary(vec![1, 2, 3, 4]);
+        assert_eq!(document.edit_bytes(2, &[9, 9]).unwrap(), [3, 4]);
+
+        // Content shorter than the edit, so the overwrite can't be undone
+        document.binary_content = Some(vec![1]);
+        assert!(document.step(false).is_err());
+        assert_eq!(document.undo_stack.len(), 1);
+        assert!(document.redo_stack.is_empty());
+
+        document.binary_content = Some(vec![1, 2, 9, 9]);
+        assert!(matches!(document.step(false), Ok(Slot::Binary)));
+        assert_eq!(document.binary_content.as_deref(), Some(&[1, 2, 3, 4][..]));
+        assert_eq!(document.redo_stack.len(), 1);
+    }
+
+    #[test]
+    fn raw_edit_undo_restores_the_formatted_view() {
+        let mut document = Document::default();
+        document.raw_content = Some("a".to_string().into());
+        document.formatted_content = Some("A".to_string().into());
+        document.edit_raw("b".to_string());
+        assert!(document.formatted_content.is_none());
+
+        assert!(matches!(document.step(false), Ok(Slot::Raw)));
+        assert_eq!(document.raw_content.as_deref(), Some("a"));
+        assert_eq!(document.formatted_content.as_deref(), Some("A"));
+
+        assert!(matches!(document.step(true), Ok(Slot::Raw)));
+        assert_eq!(document.raw_content.as_deref(), Some("b"));
+        assert!(document.formatted_content.is_none());
+    }
+}
@@ -0,0 +1,140 @@
+// Byte multipliers for size units; decimal (SI) and binary (IEC) side by side
+const SIZE_UNITS: &[(&str, f64)] = &[
+    ("B", 1.0),
+    ("KB", 1e3),
+    ("MB", 1e6),
+    ("GB", 1e9),
+    ("TB", 1e12),
+    ("PB", 1e15),
+    ("KiB", 1024.0),
+    ("MiB", 1_048_576.0),
+    ("GiB", 1_073_741_824.0),
+    ("TiB", 1_099_511_627_776.0),
+    ("PiB", 1_125_899_906_842_624.0),
+    ("bit", 0.125),
+    ("Kbit", 125.0),
+    ("Mbit", 125_000.0),
+    ("Gbit", 125_000_000.0),
+];
+
+// Bytes-per-second multipliers for data rates
+const RATE_UNITS: &[(&str, f64)] = &[
+    ("bps", 0.125),
+    ("Kbps", 125.0),
+    ("Mbps", 125_000.0),
+    ("Gbps", 125_000_000.0),
+    ("B/s", 1.0),
+    ("KB/s", 1e3),
+    ("MB/s", 1e6),
+    ("GB/s", 1e9),
+    ("KiB/s", 1024.0),
+    ("MiB/s", 1_048_576.0),
+    ("GiB/s", 1_073_741_824.0),
+];
+
+fn lookup_unit(units: &[(&str, f64)], unit: &str) -> Option<f64> {
+    // Exact match first so "Mb"-style ambiguity resolves predictably, then case-insensitive
+    units
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .or_else(|| units.iter().find(|(name, _)| name.eq_ignore_ascii_case(unit)))
+        .map(|(_, factor)| *factor)
+}
+
+// Parse "1.5 GiB" / "100Mbps" into (value, unit)
+fn split_quantity(input: &str) -> Result<(f64, String), String> {
+    let input = input.trim().replace(['_', ','], "");
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == 'e' || c == 'E'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+    let value = number
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("Invalid number in '{}'", input))?;
+    Ok((value, unit.trim().to_string()))
+}
+
+pub fn parse_size(input: &str) -> Result<f64, String> {
+    let (value, unit) = split_quantity(input)?;
+    let unit = if unit.is_empty() { "B".to_string() } else { unit };
+    let factor = lookup_unit(SIZE_UNITS, &unit).ok_or_else(|| format!("Unknown size unit: {}", unit))?;
+    Ok(value * factor)
+}
+
+fn parse_rate(input: &str) -> Result<f64, String> {
+    let (value, unit) = split_quantity(input)?;
+    let factor = lookup_unit(RATE_UNITS, &unit).ok_or_else(|| format!("Unknown rate unit: {}", unit))?;
+    Ok(value * factor)
+}
+
+pub fn human_readable_bytes(bytes: f64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+    let mut value = bytes;
+    let mut index = 0;
+    while value.abs() >= 1024.0 && index < units.len() - 1 {
+        value /= 1024.0;
+        index += 1;
+    }
+    format!("{:.2} {}", value, units[index])
+}
+
+fn human_readable_duration(seconds: f64) -> String {
+    if !seconds.is_finite() {
+        return "∞".to_string();
+    }
+    let total = seconds.round() as u64;
+    let (days, hours, minutes, secs) = (total / 86_400, total % 86_400 / 3600, total % 3600 / 60, total % 60);
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(format!("{}d", days));
+    }
+    if hours > 0 {
+        parts.push(format!("{}h", hours));
+    }
+    if minutes > 0 {
+        parts.push(format!("{}m", minutes));
+    }
+    if secs > 0 || parts.is_empty() {
+        if seconds < 1.0 {
+            parts.push(format!("{:.3}s", seconds));
+        } else {
+            parts.push(format!("{}s", secs));
+        }
+    }
+    parts.join(" ")
+}
+
+#[tauri::command]
+pub fn convert_data_size(input: String) -> Result<serde_json::Value, String> {
+    let bytes = parse_size(&input)?;
+
+    let mut conversions = serde_json::Map::new();
+    for (name, factor) in SIZE_UNITS.iter() {
+        conversions.insert(name.to_string(), serde_json::json!(bytes / factor));
+    }
+
+    Ok(serde_json::json!({
+        "input": input,
+        "bytes": bytes,
+        "human_readable": human_readable_bytes(bytes),
+        "conversions": conversions
+    }))
+}
+
+#[tauri::command]
+pub fn estimate_transfer_time(size: String, rate: String) -> Result<serde_json::Value, String> {
+    let bytes = parse_size(&size)?;
+    let bytes_per_second = parse_rate(&rate)?;
+    if bytes_per_second <= 0.0 {
+        return Err("Transfer rate must be greater than zero".to_string());
+    }
+
+    let seconds = bytes / bytes_per_second;
+    Ok(serde_json::json!({
+        "bytes": bytes,
+        "bytes_per_second": bytes_per_second,
+        "seconds": seconds,
+        "human_readable": human_readable_duration(seconds)
+    }))
+}
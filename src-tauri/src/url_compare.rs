@@ -0,0 +1,326 @@
+// Fetches the same path from two base URLs (staging and production, two
+// versions of a service) and compares the responses. JSON bodies are
+// normalized first (volatile fields such as timestamps and request ids are
+// dropped; keys are compared regardless of order) and diffed structurally, so
+// the result lists what actually changed by JSON path. Other bodies are
+// compared as text, reporting the first line that differs.
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Instant;
+
+use crate::json_path::{self, Segment};
+
+const MAX_BODY_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_MAX_CHANGES: usize = 500;
+
+// One ignore rule: a field name removed wherever it occurs, or a JSON path
+// whose `[*]` segments match every array item
+enum Ignore {
+    Field(String),
+    // None stands for `[*]`
+    Path(Vec<Option<Segment>>),
+}
+
+fn parse_ignore(rule: &str) -> Result<Ignore, String> {
+    let rule = rule.trim();
+    if !rule.starts_with('$') {
+        return Ok(Ignore::Field(rule.to_string()));
+    }
+    let mut segments = Vec::new();
+    for (index, piece) in rule.split("[*]").enumerate() {
+        if index > 0 {
+            segments.push(None);
+        }
+        segments.extend(json_path::parse_path(piece)?.into_iter().map(Some));
+    }
+    Ok(Ignore::Path(segments))
+}
+
+fn remove_field(value: &mut serde_json::Value, field: &str) {
+    match value {
+        serde_json::Value::Object(object) => {
+            object.remove(field);
+            object.values_mut().for_each(|child| remove_field(child, field));
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|item| remove_field(item, field)),
+        _ => {}
+    }
+}
+
+fn remove_path(value: &mut serde_json::Value, segments: &[Option<Segment>]) {
+    let Some((first, rest)) = segments.split_first() else {
+        return;
+    };
+    match (first, value) {
+        (None, serde_json::Value::Array(items)) if rest.is_empty() => items.clear(),
+        (None, serde_json::Value::Array(items)) => items.iter_mut().for_each(|item| remove_path(item, rest)),
+        (Some(Segment::Key(key)), serde_json::Value::Object(object)) if rest.is_empty() => {
+            object.remove(key);
+        }
+        (Some(Segment::Key(key)), serde_json::Value::Object(object)) => {
+            if let Some(child) = object.get_mut(key) {
+                remove_path(child, rest);
+            }
+        }
+        (Some(Segment::Index(index)), serde_json::Value::Array(items)) if rest.is_empty() && *index < items.len() => {
+            items.remove(*index);
+        }
+        (Some(Segment::Index(index)), serde_json::Value::Array(items)) => {
+            if let Some(item) = items.get_mut(*index) {
+                remove_path(item, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+// serde_json keeps object keys sorted, so after parsing only the ignored
+// fields need removing for key order to stop mattering
+fn normalize(value: &mut serde_json::Value, ignore: &[Ignore]) {
+    for rule in ignore {
+        match rule {
+            Ignore::Field(field) => remove_field(value, field),
+            Ignore::Path(segments) => remove_path(value, segments),
+        }
+    }
+}
+
+fn type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+// False once `max_changes` entries are recorded, which ends the walk
+fn record(
+    changes: &mut Vec<serde_json::Value>,
+    max_changes: usize,
+    path: &[Segment],
+    kind: &str,
+    left: Option<&serde_json::Value>,
+    right: Option<&serde_json::Value>,
+) -> bool {
+    if changes.len() >= max_changes {
+        return false;
+    }
+    changes.push(serde_json::json!({
+        "path": json_path::format_path(path),
+        "kind": kind,
+        "left": left,
+        "right": right
+    }));
+    true
+}
+
+// Structural differences between two JSON values as `{path, kind, left,
+// right}` entries, kind being "added", "removed", "changed" or "type". Arrays
+// are compared item by item. Stops after `max_changes`; returns whether it did.
+pub fn json_diff(
+    left: &serde_json::Value,
+    right: &serde_json::Value,
+    max_changes: usize,
+) -> (Vec<serde_json::Value>, bool) {
+    fn walk(
+        left: &serde_json::Value,
+        right: &serde_json::Value,
+        path: &mut Vec<Segment>,
+        changes: &mut Vec<serde_json::Value>,
+        max_changes: usize,
+    ) -> bool {
+        match (left, right) {
+            (serde_json::Value::Object(a), serde_json::Value::Object(b)) => {
+                for (key, value) in a {
+                    path.push(Segment::Key(key.clone()));
+                    let more = match b.get(key) {
+                        Some(other) => walk(value, other, path, changes, max_changes),
+                        None => record(changes, max_changes, path, "removed", Some(value), None),
+                    };
+                    path.pop();
+                    if !more {
+                        return false;
+                    }
+                }
+                for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                    path.push(Segment::Key(key.clone()));
+                    let more = record(changes, max_changes, path, "added", None, Some(value));
+                    path.pop();
+                    if !more {
+                        return false;
+                    }
+                }
+                true
+            }
+            (serde_json::Value::Array(a), serde_json::Value::Array(b)) => {
+                for index in 0..a.len().max(b.len()) {
+                    path.push(Segment::Index(index));
+                    let more = match (a.get(index), b.get(index)) {
+                        (Some(x), Some(y)) => walk(x, y, path, changes, max_changes),
+                        (Some(x), None) => record(changes, max_changes, path, "removed", Some(x), None),
+                        (None, y) => record(changes, max_changes, path, "added", None, y),
+                    };
+                    path.pop();
+                    if !more {
+                        return false;
+                    }
+                }
+                true
+            }
+            (a, b) if a == b => true,
+            (a, b) if type_name(a) != type_name(b) => record(changes, max_changes, path, "type", Some(a), Some(b)),
+            (a, b) => record(changes, max_changes, path, "changed", Some(a), Some(b)),
+        }
+    }
+
+    let mut changes = Vec::new();
+    let complete = walk(left, right, &mut Vec::new(), &mut changes, max_changes);
+    (changes, !complete)
+}
+
+struct Fetched {
+    url: String,
+    status: u16,
+    content_type: Option<String>,
+    elapsed_ms: u64,
+    body: String,
+}
+
+fn join_url(base: &str, path: &str) -> String {
+    let path = path.trim();
+    if path.is_empty() {
+        return base.trim().to_string();
+    }
+    format!("{}/{}", base.trim().trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+fn fetch(url: &str, headers: &HashMap<String, String>) -> Result<Fetched, String> {
+    let started = Instant::now();
+    let mut request = crate::fetch::http_client()?.get(crate::fetch::parse_http_url(url)?);
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = request
+        .send()
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let mut body = Vec::new();
+    response
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Failed to read the response from {}: {}", url, e))?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(format!(
+            "The response from {} is over the {} byte limit",
+            url, MAX_BODY_BYTES
+        ));
+    }
+    Ok(Fetched {
+        url: url.to_string(),
+        status,
+        content_type,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn describe(fetched: &Fetched) -> serde_json::Value {
+    serde_json::json!({
+        "url": fetched.url,
+        "status": fetched.status,
+        "content_type": fetched.content_type,
+        "elapsed_ms": fetched.elapsed_ms,
+        "bytes": fetched.body.len()
+    })
+}
+
+// 1-based number of the first line that differs, with both versions of it
+fn first_different_line(left: &str, right: &str) -> Option<serde_json::Value> {
+    let mut left_lines = left.lines();
+    let mut right_lines = right.lines();
+    let mut number = 0;
+    loop {
+        number += 1;
+        match (left_lines.next(), right_lines.next()) {
+            (None, None) => return None,
+            (a, b) if a == b => continue,
+            (a, b) => return Some(serde_json::json!({ "line": number, "left": a, "right": b })),
+        }
+    }
+}
+
+// GETs `path` from both base URLs with the same `headers` and compares the
+// bodies. `ignore` lists field names (dropped at any depth, e.g. "timestamp")
+// and JSON paths (starting with `$`, `[*]` matching every item).
+#[tauri::command]
+pub async fn compare_urls(
+    left_base: String,
+    right_base: String,
+    path: Option<String>,
+    headers: Option<HashMap<String, String>>,
+    ignore: Option<Vec<String>>,
+    max_changes: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("compare_urls", move || {
+        let path = path.unwrap_or_default();
+        let headers = headers.unwrap_or_default();
+        let ignore = ignore
+            .unwrap_or_default()
+            .iter()
+            .filter(|rule| !rule.trim().is_empty())
+            .map(|rule| parse_ignore(rule))
+            .collect::<Result<Vec<_>, _>>()?;
+        let max_changes = max_changes.unwrap_or(DEFAULT_MAX_CHANGES).max(1);
+
+        let left_url = join_url(&left_base, &path);
+        let right_url = join_url(&right_base, &path);
+        // Both requests run at once so timing-sensitive data lines up better
+        let (left, right) = std::thread::scope(|scope| {
+            let left = scope.spawn(|| fetch(&left_url, &headers));
+            let right = fetch(&right_url, &headers);
+            (
+                left.join()
+                    .unwrap_or_else(|_| Err("The request thread panicked".to_string())),
+                right,
+            )
+        });
+        let (left, right) = (left?, right?);
+
+        let mut reply = serde_json::json!({
+            "left": describe(&left),
+            "right": describe(&right),
+            "same_status": left.status == right.status
+        });
+        let parsed = (
+            serde_json::from_str::<serde_json::Value>(&left.body),
+            serde_json::from_str::<serde_json::Value>(&right.body),
+        );
+        match parsed {
+            (Ok(mut left_json), Ok(mut right_json)) => {
+                normalize(&mut left_json, &ignore);
+                normalize(&mut right_json, &ignore);
+                let (changes, truncated) = json_diff(&left_json, &right_json, max_changes);
+                reply["json"] = true.into();
+                reply["identical"] = changes.is_empty().into();
+                reply["change_count"] = changes.len().into();
+                reply["truncated"] = truncated.into();
+                reply["changes"] = changes.into();
+            }
+            _ => {
+                reply["json"] = false.into();
+                reply["identical"] = (left.body == right.body).into();
+                reply["first_difference"] = first_different_line(&left.body, &right.body).into();
+            }
+        }
+        Ok(reply)
+    })
+    .await
+}
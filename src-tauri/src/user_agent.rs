@@ -0,0 +1,306 @@
+// User-Agent strings decoded into browser, engine, operating system and device
+// class. Browsers advertise each other's tokens ("Mozilla/5.0 ... Chrome/...
+// Safari/..."), so the rules are checked most specific first: Edge and Opera
+// before Chrome, Chrome before Safari, and bots before everything.
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+// (token in the UA, reported name); the first match wins
+const BROWSERS: &[(&str, &str)] = &[
+    ("Edg/", "Edge"),
+    ("EdgA/", "Edge"),
+    ("EdgiOS/", "Edge"),
+    ("Edge/", "Edge (Legacy)"),
+    ("OPR/", "Opera"),
+    ("OPiOS/", "Opera"),
+    ("Opera/", "Opera"),
+    ("SamsungBrowser/", "Samsung Internet"),
+    ("YaBrowser/", "Yandex Browser"),
+    ("Vivaldi/", "Vivaldi"),
+    ("UCBrowser/", "UC Browser"),
+    ("Brave/", "Brave"),
+    ("DuckDuckGo/", "DuckDuckGo"),
+    ("CriOS/", "Chrome"),
+    ("FxiOS/", "Firefox"),
+    ("Firefox/", "Firefox"),
+    ("Electron/", "Electron"),
+    ("HeadlessChrome/", "Headless Chrome"),
+    ("Chromium/", "Chromium"),
+    ("Chrome/", "Chrome"),
+    ("MSIE ", "Internet Explorer"),
+    ("Trident/", "Internet Explorer"),
+    ("Version/", "Safari"),
+];
+
+// Tools and crawlers, matched case-insensitively
+const BOTS: &[(&str, &str)] = &[
+    ("googlebot", "Googlebot"),
+    ("bingbot", "Bingbot"),
+    ("duckduckbot", "DuckDuckBot"),
+    ("baiduspider", "Baiduspider"),
+    ("yandexbot", "YandexBot"),
+    ("applebot", "Applebot"),
+    ("facebookexternalhit", "Facebook"),
+    ("twitterbot", "Twitterbot"),
+    ("slackbot", "Slackbot"),
+    ("discordbot", "Discordbot"),
+    ("linkedinbot", "LinkedInBot"),
+    ("gptbot", "GPTBot"),
+    ("ahrefsbot", "AhrefsBot"),
+    ("semrushbot", "SemrushBot"),
+    ("curl/", "curl"),
+    ("wget/", "Wget"),
+    ("python-requests/", "python-requests"),
+    ("python-urllib/", "Python urllib"),
+    ("aiohttp/", "aiohttp"),
+    ("go-http-client/", "Go http client"),
+    ("okhttp/", "OkHttp"),
+    ("axios/", "axios"),
+    ("node-fetch/", "node-fetch"),
+    ("postmanruntime/", "Postman"),
+    ("insomnia/", "Insomnia"),
+    ("java/", "Java"),
+    ("apache-httpclient/", "Apache HttpClient"),
+    ("libwww-perl/", "libwww-perl"),
+    ("bot", "Unknown bot"),
+    ("crawler", "Unknown crawler"),
+    ("spider", "Unknown spider"),
+];
+
+const WINDOWS_VERSIONS: &[(&str, &str)] = &[
+    // Windows 11 also reports 10.0; only client hints tell them apart
+    ("10.0", "10/11"),
+    ("6.3", "8.1"),
+    ("6.2", "8"),
+    ("6.1", "7"),
+    ("6.0", "Vista"),
+    ("5.1", "XP"),
+    ("5.2", "XP x64"),
+];
+
+// The version that follows `token` ("Chrome/" -> "120.0.6099.71")
+fn version_after(ua: &str, token: &str) -> Option<String> {
+    let start = ua.find(token)? + token.len();
+    let version: String = ua[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '_')
+        .collect();
+    (!version.is_empty()).then(|| version.replace('_', "."))
+}
+
+fn major(version: &Option<String>) -> Option<u32> {
+    version.as_deref()?.split('.').next()?.parse().ok()
+}
+
+fn browser(ua: &str) -> (Option<&'static str>, Option<String>) {
+    for (token, name) in BROWSERS {
+        if !ua.contains(token) {
+            continue;
+        }
+        // "Version/" only means Safari when Safari is the one claiming it
+        if *name == "Safari" && !ua.contains("Safari/") {
+            continue;
+        }
+        let version = match *token {
+            "Trident/" => version_after(ua, "rv:"),
+            _ => version_after(ua, token),
+        };
+        return (Some(name), version);
+    }
+    if ua.contains("Safari/") && ua.contains("AppleWebKit/") {
+        // iOS in-app browsers (WKWebView) lack the Version/ token
+        return (Some("Safari (WebView)"), None);
+    }
+    (None, None)
+}
+
+fn engine(ua: &str, browser: Option<&str>) -> (Option<&'static str>, Option<String>) {
+    if ua.contains("Trident/") || ua.contains("MSIE ") {
+        return (Some("Trident"), version_after(ua, "Trident/"));
+    }
+    if ua.contains("Edge/") {
+        return (Some("EdgeHTML"), version_after(ua, "Edge/"));
+    }
+    // Every iOS browser is WebKit underneath
+    if ua.contains("iPhone") || ua.contains("iPad") || ua.contains("iPod") {
+        return (Some("WebKit"), version_after(ua, "AppleWebKit/"));
+    }
+    if ua.contains("Gecko/") && ua.contains("rv:") && !ua.contains("like Gecko") {
+        return (Some("Gecko"), version_after(ua, "rv:"));
+    }
+    if ua.contains("Chrome/") || ua.contains("Chromium/") {
+        let version = version_after(ua, "Chrome/").or_else(|| version_after(ua, "Chromium/"));
+        return (Some("Blink"), version);
+    }
+    if ua.contains("Presto/") {
+        return (Some("Presto"), version_after(ua, "Presto/"));
+    }
+    if ua.contains("AppleWebKit/") || browser == Some("Safari") {
+        return (Some("WebKit"), version_after(ua, "AppleWebKit/"));
+    }
+    (None, None)
+}
+
+fn operating_system(ua: &str) -> (Option<String>, Option<String>) {
+    let named = |name: &str, version: Option<String>| (Some(name.to_string()), version);
+    if ua.contains("Windows Phone") {
+        return named("Windows Phone", version_after(ua, "Windows Phone "));
+    }
+    if let Some(nt) = version_after(ua, "Windows NT ") {
+        let version = WINDOWS_VERSIONS
+            .iter()
+            .find(|(kernel, _)| *kernel == nt)
+            .map(|(_, version)| version.to_string())
+            .unwrap_or(nt);
+        return named("Windows", Some(version));
+    }
+    if ua.contains("Windows") {
+        return named("Windows", None);
+    }
+    for device in ["iPhone OS ", "CPU OS "] {
+        if let Some(version) = version_after(ua, device) {
+            let name = if ua.contains("iPad") { "iPadOS" } else { "iOS" };
+            return named(name, Some(version));
+        }
+    }
+    if ua.contains("iPhone") || ua.contains("iPad") || ua.contains("iPod") {
+        return named("iOS", None);
+    }
+    if ua.contains("Android") {
+        return named("Android", version_after(ua, "Android "));
+    }
+    if ua.contains("CrOS") {
+        return named("ChromeOS", None);
+    }
+    if let Some(version) = version_after(ua, "Mac OS X ") {
+        return named("macOS", Some(version));
+    }
+    if ua.contains("Macintosh") || ua.contains("Mac OS") {
+        return named("macOS", None);
+    }
+    for (token, name) in [
+        ("Ubuntu", "Ubuntu"),
+        ("Fedora", "Fedora"),
+        ("Debian", "Debian"),
+        ("FreeBSD", "FreeBSD"),
+        ("OpenBSD", "OpenBSD"),
+        ("Linux", "Linux"),
+    ] {
+        if ua.contains(token) {
+            return named(name, None);
+        }
+    }
+    (None, None)
+}
+
+fn device_class(ua: &str, os: Option<&str>, is_bot: bool) -> &'static str {
+    let lower = ua.to_ascii_lowercase();
+    if is_bot {
+        return "bot";
+    }
+    if [
+        "smart-tv", "smarttv", "googletv", "appletv", "hbbtv", "web0s", "tizen", "roku", "bravia",
+    ]
+    .iter()
+    .any(|token| lower.contains(token))
+    {
+        return "tv";
+    }
+    if ["playstation", "xbox", "nintendo"]
+        .iter()
+        .any(|token| lower.contains(token))
+    {
+        return "console";
+    }
+    if lower.contains("ipad") || lower.contains("tablet") || lower.contains("kindle") || lower.contains("silk/") {
+        return "tablet";
+    }
+    // Android tablets leave "Mobile" out
+    if os == Some("Android") {
+        return if lower.contains("mobile") { "mobile" } else { "tablet" };
+    }
+    if lower.contains("iphone") || lower.contains("ipod") || lower.contains("mobile") || os == Some("Windows Phone") {
+        return "mobile";
+    }
+    if os.is_some() {
+        "desktop"
+    } else {
+        "unknown"
+    }
+}
+
+fn bot(ua: &str) -> Option<(&'static str, Option<String>)> {
+    let lower = ua.to_ascii_lowercase();
+    BOTS.iter()
+        .find(|(token, _)| lower.contains(token))
+        .map(|(token, name)| {
+            let version = version_after(&lower, &format!("{}/", token.trim_end_matches('/')));
+            (*name, version)
+        })
+}
+
+pub fn parse_user_agent(ua: &str) -> Value {
+    let ua = ua.trim();
+    let ua = match ua.split_once(':') {
+        Some((name, value)) if name.eq_ignore_ascii_case("user-agent") => value.trim(),
+        _ => ua,
+    };
+    let bot = bot(ua);
+    let is_bot = bot.is_some();
+    let (browser, browser_version) = match bot {
+        Some((name, version)) => (Some(name), version),
+        None => browser(ua),
+    };
+    let (engine, engine_version) = engine(ua, browser);
+    let (os, os_version) = operating_system(ua);
+    let device = device_class(ua, os.as_deref(), is_bot);
+    // Chromium 110+ sends a reduced UA: fixed OS versions and device model
+    let frozen = (matches!(browser, Some("Chrome" | "Edge")) && major(&browser_version) >= Some(110))
+        .then_some("the OS and device details are frozen by UA reduction; use client hints for exact values");
+    json!({
+        "user_agent": ua,
+        "browser": browser,
+        "browser_version": browser_version,
+        "browser_major": major(&browser_version),
+        "engine": engine,
+        "engine_version": engine_version,
+        "os": os,
+        "os_version": os_version,
+        "device": device,
+        "is_bot": is_bot,
+        "note": frozen
+    })
+}
+
+fn ua_text(app: &AppHandle, text: Option<String>) -> Result<String, String> {
+    match text.filter(|text| !text.trim().is_empty()) {
+        Some(text) => Ok(text),
+        None => {
+            let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+            Ok(storage
+                .active()
+                .raw_content
+                .as_deref()
+                .ok_or_else(|| "No content stored".to_string())?
+                .to_string())
+        }
+    }
+}
+
+// Browser, engine, OS and device class for each User-Agent in `text` (one
+// per line), or in the active document's raw content when it's empty
+#[tauri::command]
+pub fn parse_user_agents(app: AppHandle, text: Option<String>) -> Result<Vec<Value>, String> {
+    let text = ua_text(&app, text)?;
+    let parsed: Vec<Value> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_user_agent)
+        .collect();
+    if parsed.is_empty() {
+        return Err("No User-Agent strings found".to_string());
+    }
+    Ok(parsed)
+}
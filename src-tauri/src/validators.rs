@@ -0,0 +1,814 @@
+// Check-digit validators for identifiers that testers paste around: IBANs,
+// payment card numbers (Luhn), EAN/UPC/GTIN barcodes, ISBN/ISSN and a set of
+// national ID numbers. Each result says whether the value is valid and what
+// the check digit should be for its payload, so a typo can be told apart from
+// a made-up number. A whole pasted list is checked one value per line.
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+pub const SCHEMES: &[&str] = &[
+    "iban",
+    "card",
+    "luhn",
+    "gtin",
+    "isbn",
+    "issn",
+    "verhoeff",
+    "nl-bsn",
+    "es-dni",
+    "se-personnummer",
+    "no-fodselsnummer",
+    "fi-hetu",
+    "be-national",
+    "fr-nir",
+    "br-cpf",
+    "cn-resident",
+    "in-aadhaar",
+    "pl-pesel",
+];
+
+// Lengths of IBANs per country (SWIFT IBAN registry)
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24),
+    ("AE", 23),
+    ("AL", 28),
+    ("AT", 20),
+    ("AZ", 28),
+    ("BA", 20),
+    ("BE", 16),
+    ("BG", 22),
+    ("BH", 22),
+    ("BR", 29),
+    ("BY", 28),
+    ("CH", 21),
+    ("CR", 22),
+    ("CY", 28),
+    ("CZ", 24),
+    ("DE", 22),
+    ("DK", 18),
+    ("DO", 28),
+    ("EE", 20),
+    ("EG", 29),
+    ("ES", 24),
+    ("FI", 18),
+    ("FO", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("GE", 22),
+    ("GI", 23),
+    ("GL", 18),
+    ("GR", 27),
+    ("GT", 28),
+    ("HR", 21),
+    ("HU", 28),
+    ("IE", 22),
+    ("IL", 23),
+    ("IQ", 23),
+    ("IS", 26),
+    ("IT", 27),
+    ("JO", 30),
+    ("KW", 30),
+    ("KZ", 20),
+    ("LB", 28),
+    ("LC", 32),
+    ("LI", 21),
+    ("LT", 20),
+    ("LU", 20),
+    ("LV", 21),
+    ("LY", 25),
+    ("MC", 27),
+    ("MD", 24),
+    ("ME", 22),
+    ("MK", 19),
+    ("MR", 27),
+    ("MT", 31),
+    ("MU", 30),
+    ("NL", 18),
+    ("NO", 15),
+    ("PK", 24),
+    ("PL", 28),
+    ("PS", 29),
+    ("PT", 25),
+    ("QA", 29),
+    ("RO", 24),
+    ("RS", 22),
+    ("SA", 24),
+    ("SC", 31),
+    ("SE", 24),
+    ("SI", 19),
+    ("SK", 24),
+    ("SM", 27),
+    ("ST", 25),
+    ("SV", 28),
+    ("TL", 23),
+    ("TN", 24),
+    ("TR", 26),
+    ("UA", 29),
+    ("VA", 22),
+    ("VG", 24),
+    ("XK", 20),
+];
+
+type CardBrand = (&'static str, &'static [(u32, u32)], &'static [usize]);
+
+// Card brands by number prefix (inclusive ranges over the leading digits)
+// and the lengths they issue; Maestro's catch-all range comes last
+const CARD_BRANDS: &[CardBrand] = &[
+    ("American Express", &[(34, 34), (37, 37)], &[15]),
+    ("Visa", &[(4, 4)], &[13, 16, 19]),
+    ("Mastercard", &[(51, 55), (2221, 2720)], &[16]),
+    ("Discover", &[(6011, 6011), (644, 649), (65, 65)], &[16, 17, 18, 19]),
+    ("JCB", &[(3528, 3589)], &[16, 17, 18, 19]),
+    (
+        "Diners Club",
+        &[(300, 305), (36, 36), (38, 39)],
+        &[14, 15, 16, 17, 18, 19],
+    ),
+    ("UnionPay", &[(62, 62)], &[16, 17, 18, 19]),
+    (
+        "Maestro",
+        &[(50, 50), (56, 58), (6, 6)],
+        &[12, 13, 14, 15, 16, 17, 18, 19],
+    ),
+];
+
+const VERHOEFF_D: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+const VERHOEFF_P: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 7, 6, 8, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+const VERHOEFF_INV: [u8; 10] = [0, 4, 3, 2, 1, 5, 6, 7, 8, 9];
+
+struct Outcome {
+    valid: bool,
+    // The check digit(s) the payload calls for
+    check_digit: String,
+    normalized: String,
+    details: Map<String, Value>,
+}
+
+impl Outcome {
+    fn new(valid: bool, check_digit: impl ToString, normalized: &str) -> Self {
+        Outcome {
+            valid,
+            check_digit: check_digit.to_string(),
+            normalized: normalized.to_string(),
+            details: Map::new(),
+        }
+    }
+
+    fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.details.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+// Uppercase with spaces, hyphens, dots and slashes removed
+fn compact(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '.' | '/'))
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn digits_of(text: &str, what: &str, lengths: &[usize]) -> Result<Vec<u32>, String> {
+    let digits: Vec<u32> = text.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() != text.chars().count() {
+        return Err(format!("{} numbers contain only digits", what));
+    }
+    if !lengths.contains(&digits.len()) {
+        let lengths: Vec<String> = lengths.iter().map(usize::to_string).collect();
+        return Err(format!(
+            "{} numbers have {} digits, not {}",
+            what,
+            lengths.join(" or "),
+            digits.len()
+        ));
+    }
+    Ok(digits)
+}
+
+fn weighted_sum(digits: &[u32], weights: &[u32]) -> u32 {
+    digits.iter().zip(weights).map(|(digit, weight)| digit * weight).sum()
+}
+
+fn number_of(digits: &[u32]) -> u64 {
+    digits.iter().fold(0, |number, digit| number * 10 + *digit as u64)
+}
+
+// The digit that makes `payload` followed by it pass the Luhn check
+pub fn luhn_check_digit(payload: &[u32]) -> u32 {
+    let sum: u32 = payload
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(position, digit)| {
+            if position % 2 == 0 {
+                let doubled = digit * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                *digit
+            }
+        })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+pub fn luhn_valid(digits: &[u32]) -> bool {
+    match digits.split_last() {
+        Some((last, payload)) => luhn_check_digit(payload) == *last,
+        None => false,
+    }
+}
+
+fn verhoeff_check_digit(payload: &[u32]) -> u32 {
+    let c = payload.iter().rev().enumerate().fold(0u8, |c, (position, digit)| {
+        VERHOEFF_D[c as usize][VERHOEFF_P[(position + 1) % 8][*digit as usize] as usize]
+    });
+    VERHOEFF_INV[c as usize] as u32
+}
+
+// ISO 7064 mod 97-10 over digits and letters (A = 10 ... Z = 35)
+fn mod97(text: &str) -> u32 {
+    text.chars().fold(0, |remainder, c| {
+        let value = c.to_digit(36).unwrap_or(0);
+        if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        }
+    })
+}
+
+fn iban(text: &str) -> Result<Outcome, String> {
+    let iban = compact(text);
+    let iban = iban.strip_prefix("IBAN").unwrap_or(&iban);
+    if iban.len() < 5
+        || !iban[..2].chars().all(|c| c.is_ascii_uppercase())
+        || !iban[2..4].chars().all(|c| c.is_ascii_digit())
+    {
+        return Err("An IBAN starts with a country code and two check digits".to_string());
+    }
+    if let Some(c) = iban.chars().find(|c| !c.is_ascii_alphanumeric()) {
+        return Err(format!("Unexpected '{}' in IBAN", c));
+    }
+    let country = &iban[..2];
+    let length = IBAN_LENGTHS
+        .iter()
+        .find(|(code, _)| *code == country)
+        .map(|(_, length)| *length)
+        .ok_or_else(|| format!("{} doesn't use IBANs", country))?;
+    if iban.len() != length {
+        return Err(format!(
+            "{} IBANs have {} characters, not {}",
+            country,
+            length,
+            iban.len()
+        ));
+    }
+    let bban = &iban[4..];
+    let check = 98 - mod97(&format!("{}{}00", bban, country));
+    let valid = mod97(&format!("{}{}", bban, &iban[..4])) == 1;
+    let grouped: Vec<String> = iban
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect();
+    Ok(Outcome::new(valid, format!("{:02}", check), iban)
+        .with("country", country)
+        .with("bban", bban)
+        .with("formatted", grouped.join(" ")))
+}
+
+fn card(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "Card", &(12..=19).collect::<Vec<_>>())?;
+    let brand = CARD_BRANDS.iter().find(|(_, ranges, _)| {
+        ranges.iter().any(|(low, high)| {
+            let width = low.to_string().len();
+            number
+                .get(..width)
+                .and_then(|prefix| prefix.parse::<u32>().ok())
+                .is_some_and(|prefix| (*low..=*high).contains(&prefix))
+        })
+    });
+    let length_ok = brand.is_none_or(|(_, _, lengths)| lengths.contains(&digits.len()));
+    let (payload, _) = digits.split_at(digits.len() - 1);
+    let outcome = Outcome::new(luhn_valid(&digits) && length_ok, luhn_check_digit(payload), &number);
+    Ok(match brand {
+        Some((name, _, _)) => outcome.with("brand", *name).with("length_matches_brand", length_ok),
+        None => outcome.with("brand", Value::Null),
+    })
+}
+
+fn luhn(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "Luhn", &(2..=64).collect::<Vec<_>>())?;
+    let (payload, _) = digits.split_at(digits.len() - 1);
+    Ok(Outcome::new(luhn_valid(&digits), luhn_check_digit(payload), &number))
+}
+
+fn verhoeff(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "Verhoeff", &(2..=64).collect::<Vec<_>>())?;
+    let (payload, last) = digits.split_at(digits.len() - 1);
+    let check = verhoeff_check_digit(payload);
+    Ok(Outcome::new(check == last[0], check, &number))
+}
+
+// GS1 mod 10: weights 3 and 1 alternating from the right of the payload
+fn gs1_check_digit(payload: &[u32]) -> u32 {
+    let sum: u32 = payload
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(position, digit)| if position % 2 == 0 { digit * 3 } else { *digit })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+fn gtin(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "GTIN", &[8, 12, 13, 14])?;
+    let (payload, last) = digits.split_at(digits.len() - 1);
+    let check = gs1_check_digit(payload);
+    let kind = match digits.len() {
+        8 => "EAN-8",
+        12 => "UPC-A",
+        13 if number.starts_with("978") || number.starts_with("979") => "ISBN-13 (EAN-13)",
+        13 => "EAN-13",
+        _ => "GTIN-14",
+    };
+    Ok(Outcome::new(check == last[0], check, &number).with("type", kind))
+}
+
+// Mod 11 with weights counting down to 2; 10 is written X
+fn mod11_check(payload: &[u32]) -> String {
+    let top = payload.len() as u32 + 1;
+    let sum: u32 = payload
+        .iter()
+        .enumerate()
+        .map(|(i, digit)| digit * (top - i as u32))
+        .sum();
+    match (11 - sum % 11) % 11 {
+        10 => "X".to_string(),
+        check => check.to_string(),
+    }
+}
+
+fn isbn(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let number = number.strip_prefix("ISBN").unwrap_or(&number).trim_start_matches(':');
+    if number.len() == 13 {
+        let outcome = gtin(number)?;
+        if !number.starts_with("978") && !number.starts_with("979") {
+            return Err("ISBN-13 numbers start with 978 or 979".to_string());
+        }
+        return Ok(outcome.with("type", "ISBN-13"));
+    }
+    let (payload, last) = number.split_at(number.len().saturating_sub(1));
+    let digits = digits_of(payload, "ISBN-10 payload", &[9])?;
+    let check = mod11_check(&digits);
+    let mut outcome = Outcome::new(check == last, &check, number).with("type", "ISBN-10");
+    if outcome.valid {
+        // The same book as an ISBN-13
+        let mut isbn13: Vec<u32> = vec![9, 7, 8];
+        isbn13.extend(&digits);
+        let check13 = gs1_check_digit(&isbn13);
+        let isbn13: String = isbn13.iter().map(u32::to_string).collect();
+        outcome = outcome.with("isbn13", format!("{}{}", isbn13, check13));
+    }
+    Ok(outcome)
+}
+
+fn issn(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let number = number.strip_prefix("ISSN").unwrap_or(&number);
+    let (payload, last) = number.split_at(number.len().saturating_sub(1));
+    let digits = digits_of(payload, "ISSN payload", &[7])?;
+    let check = mod11_check(&digits);
+    Ok(Outcome::new(check == last, &check, number).with("formatted", format!("{}-{}", &number[..4], &number[4..])))
+}
+
+// Dutch burgerservicenummer: the "11-proof", with the last digit weighted -1
+fn nl_bsn(text: &str) -> Result<Outcome, String> {
+    let number = format!("{:0>9}", compact(text));
+    let digits = digits_of(&number, "BSN", &[9])?;
+    let sum = weighted_sum(&digits[..8], &[9, 8, 7, 6, 5, 4, 3, 2]);
+    let check = sum % 11;
+    let outcome = Outcome::new(check == digits[8] && check < 10, check, &number);
+    Ok(if check == 10 {
+        outcome.with("note", "No check digit fits this payload")
+    } else {
+        outcome
+    })
+}
+
+// Spanish DNI (8 digits and a letter) and NIE (X/Y/Z, 7 digits, letter)
+fn es_dni(text: &str) -> Result<Outcome, String> {
+    const LETTERS: &[u8] = b"TRWAGMYFPDXBNJZSQVHLCKE";
+    let number = compact(text);
+    if number.len() != 9 {
+        return Err("DNI/NIE numbers have 9 characters".to_string());
+    }
+    let (body, letter) = number.split_at(8);
+    let (kind, digits) = match body.chars().next() {
+        Some(prefix @ ('X' | 'Y' | 'Z')) => ("NIE", format!("{}{}", (prefix as u8 - b'X'), &body[1..])),
+        _ => ("DNI", body.to_string()),
+    };
+    let digits = digits_of(&digits, kind, &[8])?;
+    let check = LETTERS[(number_of(&digits) % 23) as usize] as char;
+    Ok(Outcome::new(letter.starts_with(check), check, &number).with("type", kind))
+}
+
+// Swedish personnummer / samordningsnummer: Luhn over YYMMDDNNNC
+fn se_personnummer(text: &str) -> Result<Outcome, String> {
+    let number: String = text.trim().chars().filter(|c| c.is_ascii_digit()).collect();
+    let digits = digits_of(&number, "Personnummer", &[10, 12])?;
+    let digits = &digits[digits.len() - 10..];
+    let check = luhn_check_digit(&digits[..9]);
+    let coordination = digits[4] >= 6;
+    Ok(Outcome::new(check == digits[9], check, &number).with(
+        "type",
+        if coordination {
+            "samordningsnummer"
+        } else {
+            "personnummer"
+        },
+    ))
+}
+
+// Norwegian fødselsnummer: two mod 11 check digits
+fn no_fodselsnummer(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "Fødselsnummer", &[11])?;
+    let check = |digits: &[u32], weights: &[u32]| match 11 - weighted_sum(digits, weights) % 11 {
+        11 => Some(0),
+        10 => None,
+        check => Some(check),
+    };
+    let first = check(&digits[..9], &[3, 7, 6, 1, 8, 9, 4, 5, 2]);
+    let mut payload = digits[..9].to_vec();
+    payload.push(first.unwrap_or(0));
+    let second = check(&payload, &[5, 4, 3, 2, 7, 6, 5, 4, 3, 2]);
+    let (Some(first), Some(second)) = (first, second) else {
+        return Ok(Outcome::new(false, "", &number).with("note", "No check digits fit this payload"));
+    };
+    Ok(Outcome::new(
+        digits[9] == first && digits[10] == second,
+        format!("{}{}", first, second),
+        &number,
+    ))
+}
+
+// Finnish henkilötunnus: DDMMYY, century sign, individual number, mod 31 letter
+fn fi_hetu(text: &str) -> Result<Outcome, String> {
+    const CHECKS: &[u8] = b"0123456789ABCDEFHJKLMNPRSTUVWXY";
+    let number = text.trim().to_uppercase();
+    if number.len() != 11 {
+        return Err("Henkilötunnus numbers have 11 characters".to_string());
+    }
+    let century = match &number[6..7] {
+        "+" => 1800,
+        "-" | "Y" | "X" | "W" | "V" | "U" => 1900,
+        "A" | "B" | "C" | "D" | "E" | "F" => 2000,
+        other => return Err(format!("Unknown century sign {}", other)),
+    };
+    let digits = digits_of(&format!("{}{}", &number[..6], &number[7..10]), "Henkilötunnus", &[9])?;
+    let check = CHECKS[(number_of(&digits) % 31) as usize] as char;
+    let birth_date = format!(
+        "{}-{}-{}",
+        century + number[4..6].parse::<u32>().unwrap_or(0),
+        &number[2..4],
+        &number[..2]
+    );
+    Ok(Outcome::new(number.ends_with(check), check, &number).with("birth_date", birth_date))
+}
+
+// Belgian rijksregisternummer: 97 minus the first nine digits mod 97, with a
+// leading 2 for people born from 2000
+fn be_national(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "National register", &[11])?;
+    let payload = number_of(&digits[..9]);
+    let given = number_of(&digits[9..]);
+    let before_2000 = 97 - payload % 97;
+    let from_2000 = 97 - (2_000_000_000 + payload) % 97;
+    let (check, valid) = if given == from_2000 && given != before_2000 {
+        (from_2000, true)
+    } else {
+        (before_2000, given == before_2000)
+    };
+    Ok(Outcome::new(valid, format!("{:02}", check), &number).with("born_from_2000", given == from_2000))
+}
+
+// French NIR (numéro de sécurité sociale): 13 characters and a mod 97 key;
+// Corsican departments 2A and 2B count as 19 and 18
+fn fr_nir(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    if number.len() != 15 {
+        return Err("NIR numbers have 15 characters (13 and a 2-digit key)".to_string());
+    }
+    let body = number[..13].replacen("2A", "19", 1).replacen("2B", "18", 1);
+    let digits = digits_of(&body, "NIR", &[13])?;
+    let key = 97 - number_of(&digits) % 97;
+    let given = number[13..]
+        .parse::<u64>()
+        .map_err(|_| "The NIR key is two digits".to_string())?;
+    Ok(Outcome::new(given == key, format!("{:02}", key), &number))
+}
+
+// Brazilian CPF: two mod 11 check digits; repeated digits are never issued
+fn br_cpf(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "CPF", &[11])?;
+    let check = |payload: &[u32]| {
+        let top = payload.len() as u32 + 1;
+        let sum: u32 = payload
+            .iter()
+            .enumerate()
+            .map(|(i, digit)| digit * (top - i as u32))
+            .sum();
+        (sum * 10 % 11) % 10
+    };
+    let first = check(&digits[..9]);
+    let mut payload = digits[..9].to_vec();
+    payload.push(first);
+    let second = check(&payload);
+    let repeated = digits.iter().all(|digit| *digit == digits[0]);
+    let outcome = Outcome::new(
+        !repeated && digits[9] == first && digits[10] == second,
+        format!("{}{}", first, second),
+        &number,
+    );
+    Ok(if repeated {
+        outcome.with("note", "CPFs of one repeated digit aren't issued")
+    } else {
+        outcome
+    })
+}
+
+// Chinese resident identity card: ISO 7064 MOD 11-2 over 17 digits
+fn cn_resident(text: &str) -> Result<Outcome, String> {
+    const WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+    const CHECKS: &[u8] = b"10X98765432";
+    let number = compact(text);
+    if number.len() != 18 {
+        return Err("Resident ID numbers have 18 characters".to_string());
+    }
+    let digits = digits_of(&number[..17], "Resident ID", &[17])?;
+    let check = CHECKS[(weighted_sum(&digits, &WEIGHTS) % 11) as usize] as char;
+    Ok(Outcome::new(number.ends_with(check), check, &number).with(
+        "birth_date",
+        format!("{}-{}-{}", &number[6..10], &number[10..12], &number[12..14]),
+    ))
+}
+
+// Indian Aadhaar: 12 digits, never starting with 0 or 1, Verhoeff checked
+fn in_aadhaar(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "Aadhaar", &[12])?;
+    if digits[0] < 2 {
+        return Err("Aadhaar numbers don't start with 0 or 1".to_string());
+    }
+    let check = verhoeff_check_digit(&digits[..11]);
+    Ok(Outcome::new(check == digits[11], check, &number))
+}
+
+// Polish PESEL: weights 1 3 7 9 repeating
+fn pl_pesel(text: &str) -> Result<Outcome, String> {
+    let number = compact(text);
+    let digits = digits_of(&number, "PESEL", &[11])?;
+    let sum = weighted_sum(&digits[..10], &[1, 3, 7, 9, 1, 3, 7, 9, 1, 3]);
+    let check = (10 - sum % 10) % 10;
+    Ok(Outcome::new(check == digits[10], check, &number))
+}
+
+// The scheme a value most likely follows, judged by its shape
+fn guess_scheme(text: &str) -> Option<&'static str> {
+    let value = compact(text);
+    let digits = value.chars().filter(char::is_ascii_digit).count();
+    if value.len() >= 15 && value.chars().take(2).all(|c| c.is_ascii_uppercase()) {
+        return Some("iban");
+    }
+    if digits == value.len() {
+        return match digits {
+            8 | 12 | 13 | 14 => Some("gtin"),
+            10 => Some("isbn"),
+            15..=19 => Some("card"),
+            _ => None,
+        };
+    }
+    (value.len() == 10 && digits == 9 && value.ends_with('X')).then_some("isbn")
+}
+
+// The validators slice by byte offset, so `text` must be ASCII (see check_value)
+fn validate(text: &str, scheme: &str) -> Result<Outcome, String> {
+    match scheme {
+        "iban" => iban(text),
+        "card" => card(text),
+        "luhn" => luhn(text),
+        "gtin" | "ean" | "upc" => gtin(text),
+        "isbn" => isbn(text),
+        "issn" => issn(text),
+        "verhoeff" => verhoeff(text),
+        "nl-bsn" => nl_bsn(text),
+        "es-dni" => es_dni(text),
+        "se-personnummer" => se_personnummer(text),
+        "no-fodselsnummer" => no_fodselsnummer(text),
+        "fi-hetu" => fi_hetu(text),
+        "be-national" => be_national(text),
+        "fr-nir" => fr_nir(text),
+        "br-cpf" => br_cpf(text),
+        "cn-resident" => cn_resident(text),
+        "in-aadhaar" => in_aadhaar(text),
+        "pl-pesel" => pl_pesel(text),
+        _ => Err(format!("Unknown scheme {}; use {}", scheme, SCHEMES.join(", "))),
+    }
+}
+
+fn check_value(text: &str, scheme: Option<&str>) -> Value {
+    if !text.is_ascii() {
+        return json!({
+            "input": text,
+            "scheme": scheme,
+            "valid": false,
+            "error": "Identifiers are made of ASCII letters and digits"
+        });
+    }
+    let scheme = match scheme.or_else(|| guess_scheme(text)) {
+        Some(scheme) => scheme,
+        None => {
+            return json!({
+                "input": text,
+                "scheme": null,
+                "valid": false,
+                "error": "Can't tell which scheme this follows; pass one"
+            })
+        }
+    };
+    match validate(text, scheme) {
+        Ok(outcome) => {
+            let mut result = json!({
+                "input": text,
+                "scheme": scheme,
+                "valid": outcome.valid,
+                "check_digit": outcome.check_digit,
+                "normalized": outcome.normalized
+            });
+            if let Value::Object(result) = &mut result {
+                result.extend(outcome.details);
+            }
+            result
+        }
+        Err(error) => json!({ "input": text, "scheme": scheme, "valid": false, "error": error }),
+    }
+}
+
+fn scheme_name(scheme: Option<String>) -> Result<Option<String>, String> {
+    match scheme.map(|scheme| scheme.trim().to_ascii_lowercase()) {
+        Some(scheme) if scheme.is_empty() || scheme == "auto" => Ok(None),
+        Some(scheme) if SCHEMES.contains(&scheme.as_str()) || matches!(scheme.as_str(), "ean" | "upc") => {
+            Ok(Some(scheme))
+        }
+        Some(scheme) => Err(format!("Unknown scheme {}; use {}", scheme, SCHEMES.join(", "))),
+        None => Ok(None),
+    }
+}
+
+// Checks one value. Without a `scheme` it's guessed from the shape (IBAN,
+// GTIN, ISBN or card number); national IDs always need theirs named.
+#[tauri::command]
+pub fn validate_check_digits(value: String, scheme: Option<String>) -> Result<Value, String> {
+    let scheme = scheme_name(scheme)?;
+    Ok(check_value(value.trim(), scheme.as_deref()))
+}
+
+// Checks every non-empty line of `text` (or the active document's raw
+// content), each against `scheme` or its guessed one
+#[tauri::command]
+pub async fn validate_check_digits_bulk(
+    app: AppHandle,
+    text: Option<String>,
+    scheme: Option<String>,
+) -> Result<Value, String> {
+    crate::run_blocking("validate_check_digits_bulk", move || {
+        let scheme = scheme_name(scheme)?;
+        let content = match text.filter(|text| !text.is_empty()) {
+            Some(text) => text,
+            None => {
+                let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+                storage
+                    .active()
+                    .raw_content
+                    .as_deref()
+                    .ok_or_else(|| "No content stored".to_string())?
+                    .to_string()
+            }
+        };
+        let results: Vec<Value> = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let mut result = check_value(line.trim(), scheme.as_deref());
+                result["line"] = (index + 1).into();
+                result
+            })
+            .collect();
+        let valid = results.iter().filter(|result| result["valid"] == true).count();
+        Ok(json!({
+            "total": results.len(),
+            "valid": valid,
+            "invalid": results.len() - valid,
+            "results": results
+        }))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_identifiers_pass() {
+        for (value, scheme) in [
+            ("GB82 WEST 1234 5698 7654 32", "iban"),
+            ("4111 1111 1111 1111", "card"),
+            ("4006381333931", "gtin"),
+            ("0-306-40615-2", "isbn"),
+            ("0317-8471", "issn"),
+            ("111222333", "nl-bsn"),
+            ("12345678Z", "es-dni"),
+            ("131052-308T", "fi-hetu"),
+            ("529.982.247-25", "br-cpf"),
+            ("44051401359", "pl-pesel"),
+        ] {
+            let result = check_value(value, Some(scheme));
+            assert_eq!(result["valid"], true, "{} ({}): {}", value, scheme, result);
+        }
+    }
+
+    #[test]
+    fn wrong_check_digit_reports_the_right_one() {
+        let result = check_value("4111111111111112", Some("card"));
+        assert_eq!(result["valid"], false);
+        assert_eq!(result["check_digit"], "1");
+        assert_eq!(result["brand"], "Visa");
+    }
+
+    #[test]
+    fn scheme_is_guessed_from_shape() {
+        assert_eq!(guess_scheme("GB82WEST12345698765432"), Some("iban"));
+        assert_eq!(guess_scheme("4006381333931"), Some("gtin"));
+        assert_eq!(guess_scheme("030640615X"), Some("isbn"));
+        assert_eq!(guess_scheme("4111111111111111"), Some("card"));
+    }
+
+    #[test]
+    fn non_ascii_input_is_an_error_not_a_panic() {
+        for value in ["€12345678901234", "€€€€€€€€€€€€€€€€", "ÄB12345678901234567"] {
+            let guessed = check_value(value, None);
+            assert_eq!(guessed["valid"], false);
+            assert!(guessed["error"].is_string());
+            for scheme in SCHEMES {
+                let result = check_value(value, Some(scheme));
+                assert_eq!(result["valid"], false, "{}", scheme);
+            }
+        }
+    }
+
+    #[test]
+    fn short_input_does_not_panic() {
+        for value in ["", "1", "X", "--", "2A", "ISBN", "IBAN12", "+", "X1234567"] {
+            check_value(value, None);
+            for scheme in SCHEMES {
+                check_value(value, Some(scheme));
+            }
+        }
+    }
+}
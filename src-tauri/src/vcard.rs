@@ -0,0 +1,523 @@
+// vCard (RFC 6350, and the older 3.0 and 2.1) contacts decoded into JSON, and
+// the generator that writes vCard text back from the same JSON shape. The
+// content-line handling is shared with the iCalendar code in ics.rs.
+use serde_json::{json, Map, Value};
+
+use crate::formatters::Formatter;
+use crate::ics::{escape_text, string_field, string_list, unescape_text, ContentWriter, Property};
+
+const VERSIONS: &[&str] = &["4.0", "3.0"];
+// ADR components in order, with the JSON keys accepted for each
+const ADDRESS_PARTS: &[&[&str]] = &[
+    &["po_box"],
+    &["extended"],
+    &["street"],
+    &["locality", "city"],
+    &["region", "state"],
+    &["postal_code", "zip"],
+    &["country"],
+];
+const NAME_PARTS: &[&str] = &["family", "given", "additional", "prefixes", "suffixes"];
+
+// Splits a structured value (N, ADR, ORG) at unescaped `;` and undoes the
+// escapes in each part
+fn split_structured(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ';' => parts.push(unescape_text(&std::mem::take(&mut current))),
+            c => current.push(c),
+        }
+    }
+    parts.push(unescape_text(&current));
+    parts
+}
+
+// TYPE values from `TYPE=work,voice`, repeated TYPE parameters and 2.1's
+// bare `TEL;WORK;VOICE`, with PREF reported separately
+fn types_of(property: &Property) -> (Vec<String>, bool) {
+    let mut types = Vec::new();
+    let mut preferred = false;
+    for (key, value) in &property.params {
+        let values: Vec<String> = match (key.as_str(), value.as_str()) {
+            ("TYPE", value) => value.split(',').map(|item| item.trim().to_ascii_lowercase()).collect(),
+            ("PREF", _) => {
+                preferred = true;
+                continue;
+            }
+            (bare, "") => vec![bare.to_ascii_lowercase()],
+            _ => continue,
+        };
+        for value in values.into_iter().filter(|value| !value.is_empty()) {
+            if value == "pref" {
+                preferred = true;
+            } else if !types.contains(&value) {
+                types.push(value);
+            }
+        }
+    }
+    (types, preferred)
+}
+
+fn describe_contact_point(property: &Property, value: String) -> Value {
+    let (types, preferred) = types_of(property);
+    let mut point = json!({ "value": value });
+    if !types.is_empty() {
+        point["types"] = json!(types);
+    }
+    if preferred {
+        point["preferred"] = json!(true);
+    }
+    point
+}
+
+fn push(map: &mut Map<String, Value>, key: &str, value: Value) {
+    if let Value::Array(items) = map.entry(key).or_insert_with(|| json!([])) {
+        items.push(value);
+    }
+}
+
+fn describe_card(card: &crate::ics::Component, warnings: &mut Vec<String>, index: usize) -> Value {
+    let mut contact = Map::new();
+    let mut lists: Map<String, Value> = Map::new();
+    let mut properties: Map<String, Value> = Map::new();
+    for property in &card.properties {
+        if property
+            .param("ENCODING")
+            .is_some_and(|encoding| encoding.eq_ignore_ascii_case("QUOTED-PRINTABLE"))
+        {
+            warnings.push(format!(
+                "Contact {}: {} is quoted-printable and is shown undecoded",
+                index + 1,
+                property.name
+            ));
+        }
+        let text = unescape_text(&property.value);
+        match property.name.as_str() {
+            "VERSION" => {
+                contact.insert("version".into(), json!(property.value.trim()));
+            }
+            "FN" => {
+                contact.insert("full_name".into(), json!(text));
+            }
+            "N" => {
+                let parts = split_structured(&property.value);
+                let mut name = Map::new();
+                for (key, part) in NAME_PARTS.iter().zip(parts) {
+                    if !part.is_empty() {
+                        name.insert(key.to_string(), json!(part));
+                    }
+                }
+                contact.insert("name".into(), Value::Object(name));
+            }
+            "NICKNAME" => {
+                for nickname in property.value.split(',').map(|nickname| unescape_text(nickname.trim())) {
+                    push(&mut lists, "nicknames", json!(nickname));
+                }
+            }
+            "ORG" => {
+                let units: Vec<String> = split_structured(&property.value)
+                    .into_iter()
+                    .filter(|unit| !unit.is_empty())
+                    .collect();
+                let organization = match units.as_slice() {
+                    [single] => json!(single),
+                    _ => json!(units),
+                };
+                contact.insert("organization".into(), organization);
+            }
+            "EMAIL" => push(&mut lists, "emails", describe_contact_point(property, text)),
+            "TEL" => {
+                let number = text.strip_prefix("tel:").unwrap_or(&text).to_string();
+                push(&mut lists, "phones", describe_contact_point(property, number));
+            }
+            "ADR" => {
+                let parts = split_structured(&property.value);
+                let mut address = Map::new();
+                for (keys, part) in ADDRESS_PARTS.iter().zip(parts) {
+                    if !part.is_empty() {
+                        address.insert(keys[0].to_string(), json!(part));
+                    }
+                }
+                let (types, preferred) = types_of(property);
+                if !types.is_empty() {
+                    address.insert("types".into(), json!(types));
+                }
+                if preferred {
+                    address.insert("preferred".into(), json!(true));
+                }
+                if let Some(label) = property.param("LABEL") {
+                    address.insert("label".into(), json!(label.replace("\\n", "\n")));
+                }
+                push(&mut lists, "addresses", Value::Object(address));
+            }
+            "URL" => push(&mut lists, "urls", json!(property.value.trim())),
+            "CATEGORIES" => {
+                for category in property.value.split(',').map(|category| unescape_text(category.trim())) {
+                    push(&mut lists, "categories", json!(category));
+                }
+            }
+            "PHOTO" if property.param("ENCODING").is_none() => {
+                contact.insert("photo".into(), json!(property.value.trim()));
+            }
+            "PHOTO" => {
+                contact.insert(
+                    "photo".into(),
+                    json!({ "embedded": true, "type": property.param("TYPE"), "length": property.value.len() }),
+                );
+            }
+            name => {
+                let key = match name {
+                    "TITLE" => "title",
+                    "ROLE" => "role",
+                    "NOTE" => "note",
+                    "UID" => "uid",
+                    "BDAY" => "birthday",
+                    "ANNIVERSARY" => "anniversary",
+                    "GENDER" => "gender",
+                    "KIND" => "kind",
+                    "REV" => "revision",
+                    "PRODID" => "product",
+                    name => {
+                        push(&mut properties, name, json!(text));
+                        continue;
+                    }
+                };
+                contact.insert(key.into(), json!(text));
+            }
+        }
+    }
+    contact.extend(lists);
+    if !properties.is_empty() {
+        // A property seen once keeps a plain value
+        for value in properties.values_mut() {
+            if let Value::Array(items) = value {
+                if items.len() == 1 {
+                    *value = items.remove(0);
+                }
+            }
+        }
+        contact.insert("properties".into(), Value::Object(properties));
+    }
+    if !contact.contains_key("full_name") {
+        warnings.push(format!("Contact {} has no FN, which every vCard needs", index + 1));
+    }
+    Value::Object(contact)
+}
+
+pub fn describe_vcards(text: &str) -> Result<Value, String> {
+    let cards: Vec<crate::ics::Component> = crate::ics::parse_components(text)?
+        .into_iter()
+        .filter(|component| component.name == "VCARD")
+        .collect();
+    if cards.is_empty() {
+        return Err("No BEGIN:VCARD found".to_string());
+    }
+    let mut warnings = Vec::new();
+    let contacts: Vec<Value> = cards
+        .iter()
+        .enumerate()
+        .map(|(index, card)| describe_card(card, &mut warnings, index))
+        .collect();
+    Ok(json!({ "contacts": contacts, "warnings": warnings }))
+}
+
+pub fn parse_vcard(text: &str) -> Result<String, String> {
+    let description = describe_vcards(text)?;
+    crate::formatters::to_string_pretty(&description).map_err(|e| format!("Failed to format vCard output: {}", e))
+}
+
+fn structured(parts: &[String]) -> String {
+    parts.iter().map(|part| escape_text(part)).collect::<Vec<_>>().join(";")
+}
+
+// Emails and phones: "jane@example.com" or {"value", "types", "preferred"}
+fn write_contact_points(
+    writer: &mut ContentWriter,
+    name: &str,
+    value: Option<&Value>,
+    keys: &[&str],
+    version: &str,
+) -> Result<(), String> {
+    let points = match value {
+        None | Some(Value::Null) => return Ok(()),
+        Some(Value::Array(points)) => points.as_slice(),
+        Some(single) => std::slice::from_ref(single),
+    };
+    for point in points {
+        let (value, params) = match point {
+            Value::String(value) => (value.trim().to_string(), Vec::new()),
+            Value::Object(object) => {
+                let value = string_field(object, keys).ok_or_else(|| format!("Each {} needs a value", name))?;
+                (value, type_params(object, version))
+            }
+            _ => return Err(format!("Each {} must be a string or an object", name)),
+        };
+        writer.line(name, &params, &escape_text(&value));
+    }
+    Ok(())
+}
+
+// TYPE and PREF parameters for an object with "types" and "preferred"; 3.0
+// has no PREF parameter and marks it as a type instead
+fn type_params(object: &Map<String, Value>, version: &str) -> Vec<(&'static str, String)> {
+    let mut types: Vec<String> = string_list(object.get("types").or_else(|| object.get("type")))
+        .iter()
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty())
+        .collect();
+    let preferred = object.get("preferred").and_then(Value::as_bool).unwrap_or(false);
+    let mut params = Vec::new();
+    if preferred && version == "3.0" {
+        types.push("pref".to_string());
+    }
+    if !types.is_empty() {
+        params.push(("TYPE", types.join(",")));
+    }
+    if preferred && version != "3.0" {
+        params.push(("PREF", "1".to_string()));
+    }
+    params
+}
+
+fn write_contact(writer: &mut ContentWriter, contact: &Value, default_version: &str) -> Result<(), String> {
+    let contact = contact.as_object().ok_or("Each contact must be an object")?;
+    let version = string_field(contact, &["version"]).unwrap_or_else(|| default_version.to_string());
+    if !VERSIONS.contains(&version.as_str()) {
+        return Err(format!(
+            "vCard version {} can't be written; use {}",
+            version,
+            VERSIONS.join(" or ")
+        ));
+    }
+    let name_parts: Vec<String> = match contact.get("name") {
+        Some(Value::Object(name)) => NAME_PARTS
+            .iter()
+            .map(|key| string_list(name.get(*key)).join(","))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let organization = string_list(contact.get("organization"));
+    let emails = contact.get("emails").or_else(|| contact.get("email"));
+    let first_email = match emails {
+        Some(Value::Array(points)) => points.first(),
+        other => other,
+    }
+    .and_then(|point| match point {
+        Value::Object(object) => string_field(object, &["value", "email"]),
+        other => other.as_str().map(str::to_string),
+    });
+    // FN is required, so fall back on the name, the organization or an email
+    let full_name = string_field(contact, &["full_name", "fn"])
+        .or_else(|| {
+            let [family, given, additional, prefixes, suffixes] = name_parts.as_slice() else {
+                return None;
+            };
+            let joined = [prefixes, given, additional, family, suffixes]
+                .iter()
+                .filter(|part| !part.is_empty())
+                .map(|part| part.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            (!joined.is_empty()).then_some(joined)
+        })
+        .or_else(|| organization.first().cloned())
+        .or(first_email)
+        .ok_or("A contact needs a full_name, name, organization or email")?;
+
+    writer.line("BEGIN", &[], "VCARD");
+    writer.line("VERSION", &[], &version);
+    if let Some(product) = string_field(contact, &["product"]) {
+        writer.text("PRODID", &product);
+    }
+    if let Some(kind) = string_field(contact, &["kind"]).filter(|_| version == "4.0") {
+        writer.line("KIND", &[], &kind.to_ascii_lowercase());
+    }
+    writer.text("FN", &full_name);
+    // 3.0 requires N even when it's all empty
+    if !name_parts.is_empty() || version == "3.0" {
+        let mut parts = name_parts.clone();
+        parts.resize(NAME_PARTS.len(), String::new());
+        // The parts are comma lists; only the text inside each item is escaped
+        let value = parts
+            .iter()
+            .map(|part| {
+                part.split(',')
+                    .map(|item| escape_text(item.trim()))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        writer.line("N", &[], &value);
+    }
+    let nicknames = string_list(contact.get("nicknames").or_else(|| contact.get("nickname")));
+    if !nicknames.is_empty() {
+        let nicknames: Vec<String> = nicknames.iter().map(|nickname| escape_text(nickname)).collect();
+        writer.line("NICKNAME", &[], &nicknames.join(","));
+    }
+    if !organization.is_empty() {
+        writer.line("ORG", &[], &structured(&organization));
+    }
+    for (key, name) in [("title", "TITLE"), ("role", "ROLE")] {
+        if let Some(text) = string_field(contact, &[key]) {
+            writer.text(name, &text);
+        }
+    }
+    write_contact_points(writer, "EMAIL", emails, &["value", "email"], &version)?;
+    write_contact_points(
+        writer,
+        "TEL",
+        contact.get("phones").or_else(|| contact.get("phone")),
+        &["value", "number"],
+        &version,
+    )?;
+
+    let addresses = match contact.get("addresses") {
+        None | Some(Value::Null) => &[][..],
+        Some(Value::Array(addresses)) => addresses.as_slice(),
+        Some(_) => return Err("addresses must be an array".to_string()),
+    };
+    for address in addresses {
+        let address = address.as_object().ok_or("Each address must be an object")?;
+        let parts: Vec<String> = ADDRESS_PARTS
+            .iter()
+            .map(|keys| string_field(address, keys).unwrap_or_default())
+            .collect();
+        let mut params = type_params(address, &version);
+        let label = string_field(address, &["label"]);
+        if let Some(label) = label.as_ref().filter(|_| version == "4.0") {
+            params.push(("LABEL", label.replace('\n', "\\n")));
+        }
+        writer.line("ADR", &params, &structured(&parts));
+        if let Some(label) = label.filter(|_| version == "3.0") {
+            writer.line("LABEL", &type_params(address, &version), &escape_text(&label));
+        }
+    }
+
+    for url in string_list(contact.get("urls").or_else(|| contact.get("url"))) {
+        writer.line("URL", &[], url.trim());
+    }
+    for (key, name) in [("birthday", "BDAY"), ("anniversary", "ANNIVERSARY")] {
+        let Some(date) = string_field(contact, &[key]) else {
+            continue;
+        };
+        if name == "ANNIVERSARY" && version == "3.0" {
+            continue;
+        }
+        // 4.0 writes dates in the basic form, 19900401
+        let date = match chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+            Ok(parsed) if version == "4.0" => parsed.format("%Y%m%d").to_string(),
+            _ => date,
+        };
+        writer.line(name, &[], &date);
+    }
+    if let Some(gender) = string_field(contact, &["gender"]).filter(|_| version == "4.0") {
+        writer.line("GENDER", &[], &gender);
+    }
+    let categories = string_list(contact.get("categories"));
+    if !categories.is_empty() {
+        let categories: Vec<String> = categories.iter().map(|category| escape_text(category)).collect();
+        writer.line("CATEGORIES", &[], &categories.join(","));
+    }
+    if let Some(photo) = contact.get("photo").and_then(Value::as_str) {
+        let params = if version == "3.0" {
+            vec![("VALUE", "uri".to_string())]
+        } else {
+            Vec::new()
+        };
+        writer.line("PHOTO", &params, photo.trim());
+    }
+    if let Some(note) = string_field(contact, &["note"]) {
+        writer.text("NOTE", &note);
+    }
+    if let Some(uid) = string_field(contact, &["uid"]) {
+        writer.text("UID", &uid);
+    }
+    if let Some(revision) = string_field(contact, &["revision"]) {
+        writer.line("REV", &[], &revision);
+    }
+    crate::ics::write_extra_properties(writer, contact)?;
+    writer.line("END", &[], "VCARD");
+    Ok(())
+}
+
+// vCard text from {"contacts": [...]} as the parser produces it, a bare array
+// of contacts, or a single contact. Contacts without a "version" use
+// "version" from the top level, or 4.0.
+pub fn generate_vcard(input: &str) -> Result<String, String> {
+    let description: Value = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let (contacts, version) = match &description {
+        Value::Array(contacts) => (contacts.as_slice(), None),
+        Value::Object(object) if object.contains_key("contacts") => (
+            object["contacts"]
+                .as_array()
+                .ok_or("contacts must be an array")?
+                .as_slice(),
+            string_field(object, &["version"]),
+        ),
+        Value::Object(_) => (std::slice::from_ref(&description), None),
+        _ => return Err("Describe the contacts as a JSON object or an array".to_string()),
+    };
+    if contacts.is_empty() {
+        return Err("No contacts to write".to_string());
+    }
+    let version = version.unwrap_or_else(|| VERSIONS[0].to_string());
+    let mut writer = ContentWriter::default();
+    for (index, contact) in contacts.iter().enumerate() {
+        write_contact(&mut writer, contact, &version).map_err(|e| format!("Contact {}: {}", index + 1, e))?;
+    }
+    Ok(writer.finish())
+}
+
+pub struct VcardFormatter;
+
+impl Formatter for VcardFormatter {
+    fn id(&self) -> &'static str {
+        "vcard"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "vCard Parser"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        crate::formatters::layout_options_schema(crate::formatters::PRETTY_OPTIONS)
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        parse_vcard(input)
+    }
+}
+
+pub struct VcardGeneratorFormatter;
+
+impl Formatter for VcardGeneratorFormatter {
+    fn id(&self) -> &'static str {
+        "json-to-vcard"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "JSON to vCard"
+    }
+
+    fn input_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        generate_vcard(input)
+    }
+}
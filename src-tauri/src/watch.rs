@@ -0,0 +1,226 @@
+// Keeps a document in sync with a file on disk: every save reloads it and, if a
+// formatter is set, formats it again, then `file://changed` tells the view to
+// refresh. The parent directory is watched rather than the file itself so
+// editors and generators that replace the file by renaming still trigger.
+// Watched files are always read into memory, never mapped, since the whole
+// point is that something else keeps rewriting them.
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::jobs::CancelToken;
+use crate::open_files;
+use crate::AppState;
+
+pub const FILE_CHANGED_EVENT: &str = "file://changed";
+
+// Writers often save in several steps; wait for this much quiet before reloading
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+struct WatchEntry {
+    path: PathBuf,
+    document_id: u64,
+    // Explicit formatter; None reuses the document's last one, if any
+    format_type: Option<String>,
+    // Dropping the watcher stops events and ends the reload thread
+    _watcher: notify::RecommendedWatcher,
+}
+
+#[derive(Default)]
+pub struct FileWatches {
+    next_id: Mutex<u64>,
+    watches: Mutex<HashMap<u64, WatchEntry>>,
+}
+
+// Formats the document's current raw content in place
+fn reformat(app: &AppHandle, document_id: u64, format_type: &str) -> Result<usize, String> {
+    let content = {
+        let storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        storage
+            .document(document_id)
+            .and_then(|document| document.raw_content.clone())
+            .ok_or_else(|| "The watched document was closed".to_string())?
+    };
+    if content.is_empty() {
+        return Ok(0);
+    }
+    let (formatted, store) = crate::run_formatter(app, content.to_string(), format_type)?;
+    let formatted_length = formatted.len();
+    if store {
+        let mut storage = app.state::<AppState>().inner().lock().map_err(|e| e.to_string())?;
+        if let Some(document) = storage.documents.get_mut(&document_id) {
+            document.formatted_content = Some(formatted);
+            document.format_type = Some(format_type.to_string());
+        }
+    }
+    Ok(formatted_length)
+}
+
+fn reload(app: &AppHandle, watch_id: u64) {
+    let (path, document_id, format_type) = {
+        let watches = app.state::<FileWatches>();
+        let Ok(watches) = watches.watches.lock() else {
+            return;
+        };
+        match watches.get(&watch_id) {
+            Some(entry) => (entry.path.clone(), entry.document_id, entry.format_type.clone()),
+            // Unwatched while the reload was pending
+            None => return,
+        }
+    };
+
+    let outcome = open_files::load_path_into_document(app, &CancelToken::default(), document_id, &path, false).and_then(
+        |(file_size, _, _)| {
+            let format_type = format_type.or_else(|| {
+                let storage = app.state::<AppState>().inner().lock().ok()?;
+                storage.document(document_id)?.format_type.clone()
+            });
+            let formatted_length = match &format_type {
+                Some(format_type) => Some(reformat(app, document_id, format_type)?),
+                None => None,
+            };
+            Ok((file_size, format_type, formatted_length))
+        },
+    );
+
+    let document_closed = app
+        .state::<AppState>()
+        .inner()
+        .lock()
+        .map(|storage| storage.document(document_id).is_none())
+        .unwrap_or(false);
+    if document_closed {
+        if let Ok(mut watches) = app.state::<FileWatches>().watches.lock() {
+            watches.remove(&watch_id);
+        }
+    }
+
+    let payload = match outcome {
+        Ok((file_size, format_type, formatted_length)) => serde_json::json!({
+            "watch_id": watch_id,
+            "document_id": document_id,
+            "file_path": path.to_string_lossy(),
+            "file_size": file_size,
+            "format_type": format_type,
+            "formatted_length": formatted_length
+        }),
+        Err(error) => serde_json::json!({
+            "watch_id": watch_id,
+            "document_id": document_id,
+            "file_path": path.to_string_lossy(),
+            "error": error,
+            "unwatched": document_closed
+        }),
+    };
+    let _ = app.emit(FILE_CHANGED_EVENT, payload);
+}
+
+fn start_watcher(app: &AppHandle, watch_id: u64, path: &Path) -> Result<notify::RecommendedWatcher, String> {
+    let directory = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf();
+    let target = path.to_path_buf();
+    let (sender, receiver) = mpsc::channel::<()>();
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            && event.paths.contains(&target);
+        if relevant {
+            let _ = sender.send(());
+        }
+    })
+    .map_err(|e| format!("Failed to watch {}: {}", path.display(), e))?;
+    watcher
+        .watch(&directory, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", path.display(), e))?;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        // Ends once the watcher, and with it the sender, is dropped. The thread
+        // only debounces; reloading and formatting wait their turn in the task
+        // queue like any other heavy work, one reload per watch at a time.
+        while receiver.recv().is_ok() {
+            while receiver.recv_timeout(DEBOUNCE).is_ok() {}
+            let app = app.clone();
+            let _ = tauri::async_runtime::block_on(crate::tasks::queue().run("file_watch_reload", move || {
+                reload(&app, watch_id);
+                Ok(())
+            }));
+        }
+    });
+    Ok(watcher)
+}
+
+fn watch_file_blocking(app: &AppHandle, path: String, format_type: Option<String>) -> Result<serde_json::Value, String> {
+    // Watch events carry absolute paths, so compare against the canonical one
+    let path = std::fs::canonicalize(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let opened = open_files::open_in_new_document(app, &CancelToken::default(), &path, false)?;
+    let format_type = format_type.filter(|f| !f.is_empty());
+    let formatted_length = match &format_type {
+        Some(format_type) => Some(reformat(app, opened.id, format_type)?),
+        None => None,
+    };
+
+    let watches = app.state::<FileWatches>();
+    let watch_id = {
+        let mut next_id = watches.next_id.lock().map_err(|e| e.to_string())?;
+        *next_id += 1;
+        *next_id
+    };
+    let watcher = start_watcher(app, watch_id, &path)?;
+    watches.watches.lock().map_err(|e| e.to_string())?.insert(
+        watch_id,
+        WatchEntry {
+            path,
+            document_id: opened.id,
+            format_type,
+            _watcher: watcher,
+        },
+    );
+
+    Ok(serde_json::json!({
+        "watch_id": watch_id,
+        "file": opened,
+        "formatted_length": formatted_length
+    }))
+}
+
+// Opens `path` in a new document and keeps it reloaded (and re-formatted with
+// `format_type`, or the document's last formatter) whenever the file changes
+#[tauri::command]
+pub async fn watch_file(app: AppHandle, path: String, format_type: Option<String>) -> Result<serde_json::Value, String> {
+    crate::run_blocking("watch_file", move || watch_file_blocking(&app, path, format_type)).await
+}
+
+// Returns false when the watch was already gone
+#[tauri::command]
+pub fn unwatch_file(watch_id: u64, watches: State<FileWatches>) -> Result<bool, String> {
+    Ok(watches.watches.lock().map_err(|e| e.to_string())?.remove(&watch_id).is_some())
+}
+
+#[tauri::command]
+pub fn list_file_watches(watches: State<FileWatches>) -> Result<Vec<serde_json::Value>, String> {
+    let watches = watches.watches.lock().map_err(|e| e.to_string())?;
+    let mut list: Vec<serde_json::Value> = watches
+        .iter()
+        .map(|(id, entry)| {
+            serde_json::json!({
+                "watch_id": id,
+                "document_id": entry.document_id,
+                "file_path": entry.path.to_string_lossy(),
+                "format_type": entry.format_type
+            })
+        })
+        .collect();
+    list.sort_by_key(|watch| watch["watch_id"].as_u64());
+    Ok(list)
+}
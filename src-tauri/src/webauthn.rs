@@ -0,0 +1,477 @@
+// Decodes WebAuthn / FIDO2 payloads into annotated JSON: a whole
+// PublicKeyCredential as the browser serializes it (registration or
+// assertion), or one of its base64url parts on its own (clientDataJSON,
+// attestationObject, authenticatorData). Binary fields are unpacked: flags
+// and signature counter, the attested credential and its COSE public key,
+// the attestation statement with its certificate chain.
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::cbor::{self, Item};
+use crate::formatters::Formatter;
+
+const FLAGS: &[(u8, &str)] = &[
+    (0x01, "user_present"),
+    (0x04, "user_verified"),
+    (0x08, "backup_eligible"),
+    (0x10, "backed_up"),
+    (0x40, "attested_credential_data"),
+    (0x80, "extension_data"),
+];
+
+const COSE_ALGORITHMS: &[(i128, &str)] = &[
+    (-7, "ES256"),
+    (-8, "EdDSA"),
+    (-35, "ES384"),
+    (-36, "ES512"),
+    (-37, "PS256"),
+    (-38, "PS384"),
+    (-39, "PS512"),
+    (-47, "ES256K"),
+    (-257, "RS256"),
+    (-258, "RS384"),
+    (-259, "RS512"),
+    (-65535, "RS1"),
+];
+
+const COSE_KEY_TYPES: &[(i128, &str)] = &[(1, "OKP"), (2, "EC2"), (3, "RSA")];
+
+const COSE_CURVES: &[(i128, &str)] = &[
+    (1, "P-256"),
+    (2, "P-384"),
+    (3, "P-521"),
+    (4, "X25519"),
+    (5, "X448"),
+    (6, "Ed25519"),
+    (7, "Ed448"),
+    (8, "secp256k1"),
+];
+
+fn lookup(table: &[(i128, &'static str)], value: Option<i128>) -> Value {
+    match value {
+        Some(value) => match table.iter().find(|(id, _)| *id == value) {
+            Some((_, name)) => json!(format!("{} ({})", name, value)),
+            None => json!(value.to_string()),
+        },
+        None => Value::Null,
+    }
+}
+
+fn algorithm_name(alg: Option<i128>) -> Value {
+    lookup(COSE_ALGORITHMS, alg)
+}
+
+// Browsers send base64url without padding; tolerate padding and standard
+// base64 from other tools
+fn decode_base64url(text: &str, what: &str) -> Result<Vec<u8>, String> {
+    let normalized: String = text
+        .trim()
+        .trim_end_matches('=')
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            c => c,
+        })
+        .collect();
+    URL_SAFE_NO_PAD
+        .decode(normalized.as_bytes())
+        .map_err(|e| format!("Invalid base64url in {}: {}", what, e))
+}
+
+fn bytes_summary(bytes: &[u8]) -> Value {
+    json!({ "base64url": URL_SAFE_NO_PAD.encode(bytes), "hex": cbor::hex(bytes), "length": bytes.len() })
+}
+
+fn uuid(bytes: &[u8]) -> String {
+    let hex = cbor::hex(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+// Host of an origin such as "https://login.example.com:8443"
+fn origin_host(origin: &str) -> Option<&str> {
+    let rest = origin.split_once("://").map(|(_, rest)| rest)?;
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let host = authority.rsplit_once('@').map(|(_, host)| host).unwrap_or(authority);
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or(ipv6),
+        None => host.split(':').next().unwrap_or(host),
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+// The RP ID (the origin's host or one of its parent domains) whose SHA-256 is
+// `rp_id_hash`, if any
+fn matching_rp_id(rp_id_hash: &[u8], origin: &str) -> Option<String> {
+    let host = origin_host(origin)?;
+    let mut candidate = host;
+    loop {
+        if Sha256::digest(candidate.as_bytes()).as_slice() == rp_id_hash {
+            return Some(candidate.to_string());
+        }
+        candidate = candidate.split_once('.')?.1;
+    }
+}
+
+fn describe_client_data(bytes: &[u8]) -> Result<Value, String> {
+    let client_data: Value = serde_json::from_slice(bytes).map_err(|e| format!("Invalid clientDataJSON: {}", e))?;
+    let object = client_data.as_object().ok_or("clientDataJSON isn't a JSON object")?;
+    let kind = object.get("type").and_then(Value::as_str).unwrap_or("");
+    let ceremony = match kind {
+        "webauthn.create" => "registration",
+        "webauthn.get" => "authentication",
+        "payment.get" => "payment",
+        _ => "unknown",
+    };
+    let mut description = Map::new();
+    description.insert("type".into(), json!(kind));
+    description.insert("ceremony".into(), json!(ceremony));
+    if let Some(challenge) = object.get("challenge").and_then(Value::as_str) {
+        let mut summary = json!({ "base64url": challenge });
+        if let Ok(decoded) = decode_base64url(challenge, "challenge") {
+            summary["hex"] = json!(cbor::hex(&decoded));
+            summary["length"] = json!(decoded.len());
+        }
+        description.insert("challenge".into(), summary);
+    }
+    for (key, name) in [
+        ("origin", "origin"),
+        ("crossOrigin", "cross_origin"),
+        ("topOrigin", "top_origin"),
+    ] {
+        if let Some(value) = object.get(key) {
+            description.insert(name.into(), value.clone());
+        }
+    }
+    if let Some(token_binding) = object.get("tokenBinding") {
+        description.insert("token_binding".into(), token_binding.clone());
+    }
+    description.insert("raw".into(), client_data.clone());
+    Ok(Value::Object(description))
+}
+
+fn describe_cose_key(key: &Item) -> Value {
+    let key_type = key.get_int(1).and_then(Item::as_int);
+    let mut description = json!({
+        "key_type": lookup(COSE_KEY_TYPES, key_type),
+        "algorithm": algorithm_name(key.get_int(3).and_then(Item::as_int)),
+    });
+    let field = |label: i128| key.get_int(label).map(Item::to_json).unwrap_or(Value::Null);
+    match key_type {
+        Some(1 | 2) => {
+            description["curve"] = lookup(COSE_CURVES, key.get_int(-1).and_then(Item::as_int));
+            description["x"] = field(-2);
+            if key_type == Some(2) {
+                description["y"] = field(-3);
+            }
+        }
+        Some(3) => {
+            description["modulus_bits"] = json!(key.get_int(-1).and_then(Item::as_bytes).map(|n| {
+                let leading_zeros = n.iter().take_while(|b| **b == 0).count();
+                (n.len() - leading_zeros) * 8
+            }));
+            description["n"] = field(-1);
+            description["e"] = field(-2);
+        }
+        _ => {}
+    }
+    description["raw"] = key.to_json();
+    description
+}
+
+fn describe_authenticator_data(data: &[u8], origin: Option<&str>) -> Result<Value, String> {
+    if data.len() < 37 {
+        return Err(format!("authenticatorData is {} bytes; it's at least 37", data.len()));
+    }
+    let rp_id_hash = &data[..32];
+    let flags = data[32];
+    let sign_count = u32::from_be_bytes(data[33..37].try_into().expect("4 bytes"));
+
+    let mut flag_map = Map::new();
+    flag_map.insert("raw".into(), json!(format!("0x{:02x}", flags)));
+    for (bit, name) in FLAGS {
+        flag_map.insert((*name).into(), json!(flags & bit != 0));
+    }
+    let mut description = json!({
+        "rp_id_hash": cbor::hex(rp_id_hash),
+        "flags": flag_map,
+        "sign_count": sign_count,
+    });
+    if let Some(origin) = origin {
+        description["rp_id"] = json!(matching_rp_id(rp_id_hash, origin));
+    }
+    if sign_count == 0 {
+        description["sign_count_note"] = json!("0: the authenticator doesn't keep a signature counter");
+    }
+
+    let mut rest = &data[37..];
+    if flags & 0x40 != 0 {
+        if rest.len() < 18 {
+            return Err("Attested credential data is cut short".to_string());
+        }
+        let aaguid = &rest[..16];
+        let id_length = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+        rest = &rest[18..];
+        if rest.len() < id_length {
+            return Err("Credential ID runs past the end of authenticatorData".to_string());
+        }
+        let credential_id = &rest[..id_length];
+        let (public_key, used) =
+            cbor::decode_prefix(&rest[id_length..]).map_err(|e| format!("Invalid credential public key: {}", e))?;
+        rest = &rest[id_length + used..];
+        let mut attested = json!({
+            "aaguid": uuid(aaguid),
+            "credential_id": bytes_summary(credential_id),
+            "public_key": describe_cose_key(&public_key),
+        });
+        if aaguid.iter().all(|b| *b == 0) {
+            attested["aaguid_note"] = json!("All zeros: the authenticator doesn't identify its model");
+        }
+        description["attested_credential"] = attested;
+    }
+    if flags & 0x80 != 0 {
+        let extensions = cbor::decode(rest).map_err(|e| format!("Invalid extension data: {}", e))?;
+        description["extensions"] = extensions.to_json();
+    } else if !rest.is_empty() {
+        return Err(format!(
+            "{} unexpected bytes at the end of authenticatorData",
+            rest.len()
+        ));
+    }
+    Ok(description)
+}
+
+fn describe_attestation_statement(statement: &Item) -> Value {
+    let Item::Map(entries) = statement else {
+        return statement.to_json();
+    };
+    let mut description = Map::new();
+    for (key, value) in entries {
+        let key = key.key_string();
+        let described = match (key.as_str(), value) {
+            ("alg", value) => algorithm_name(value.as_int()),
+            ("x5c", Item::Array(certificates)) => Value::Array(
+                certificates
+                    .iter()
+                    .map(|certificate| match certificate.as_bytes() {
+                        Some(der) => crate::x509::describe_certificate(der).unwrap_or_else(|e| json!({ "error": e })),
+                        None => certificate.to_json(),
+                    })
+                    .collect(),
+            ),
+            ("sig", Item::Bytes(bytes)) => bytes_summary(bytes),
+            // android-safetynet puts a JWS here
+            (_, Item::Bytes(bytes)) => match std::str::from_utf8(bytes) {
+                Ok(text) if text.chars().all(|c| !c.is_control()) => json!(text),
+                _ => json!(cbor::hex(bytes)),
+            },
+            (_, value) => value.to_json(),
+        };
+        description.insert(key, described);
+    }
+    Value::Object(description)
+}
+
+fn describe_attestation_object(data: &[u8], origin: Option<&str>) -> Result<Value, String> {
+    let object = cbor::decode(data).map_err(|e| format!("Invalid attestationObject: {}", e))?;
+    let auth_data = object
+        .get("authData")
+        .and_then(Item::as_bytes)
+        .ok_or("attestationObject has no authData")?;
+    let format = object.get("fmt").and_then(Item::as_text).unwrap_or("");
+    Ok(json!({
+        "format": format,
+        "statement": object.get("attStmt").map(describe_attestation_statement).unwrap_or(Value::Null),
+        "authenticator_data": describe_authenticator_data(auth_data, origin)?,
+    }))
+}
+
+fn describe_user_handle(bytes: &[u8]) -> Value {
+    let mut summary = bytes_summary(bytes);
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        if !text.is_empty() && text.chars().all(|c| !c.is_control()) {
+            summary["text"] = json!(text);
+        }
+    }
+    summary
+}
+
+fn field<'a>(object: &'a Value, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| object.get(*name).and_then(Value::as_str))
+}
+
+// A PublicKeyCredential (as from `credential.toJSON()`), or just its
+// `response` member
+fn describe_credential(credential: &Value) -> Result<Value, String> {
+    let response = credential.get("response").unwrap_or(credential);
+    let mut warnings: Vec<String> = Vec::new();
+    let mut description = Map::new();
+
+    for (key, name) in [
+        ("id", "id"),
+        ("type", "type"),
+        ("authenticatorAttachment", "authenticator_attachment"),
+    ] {
+        if let Some(value) = credential.get(key) {
+            description.insert(name.into(), value.clone());
+        }
+    }
+    let id = field(credential, &["id"]);
+    if let (Some(id), Some(raw_id)) = (id, field(credential, &["rawId"])) {
+        if id.trim_end_matches('=') != raw_id.trim_end_matches('=') {
+            warnings.push("id and rawId differ".to_string());
+        }
+    }
+
+    let client_data = match field(response, &["clientDataJSON", "client_data_json"]) {
+        Some(encoded) => Some(describe_client_data(&decode_base64url(encoded, "clientDataJSON")?)?),
+        None => None,
+    };
+    let origin = client_data
+        .as_ref()
+        .and_then(|client_data| client_data.get("origin"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let authenticator_data = match field(response, &["attestationObject", "attestation_object"]) {
+        Some(encoded) => {
+            let attestation =
+                describe_attestation_object(&decode_base64url(encoded, "attestationObject")?, origin.as_deref())?;
+            description.insert("ceremony".into(), json!("registration"));
+            let authenticator_data = attestation["authenticator_data"].clone();
+            description.insert("attestation".into(), attestation);
+            Some(authenticator_data)
+        }
+        None => match field(response, &["authenticatorData", "authenticator_data"]) {
+            Some(encoded) => {
+                let authenticator_data =
+                    describe_authenticator_data(&decode_base64url(encoded, "authenticatorData")?, origin.as_deref())?;
+                description.insert("ceremony".into(), json!("authentication"));
+                description.insert("authenticator_data".into(), authenticator_data.clone());
+                Some(authenticator_data)
+            }
+            None => None,
+        },
+    };
+    if client_data.is_none() && authenticator_data.is_none() {
+        return Err("No clientDataJSON, attestationObject or authenticatorData in the credential".to_string());
+    }
+
+    if let Some(client_data) = &client_data {
+        let expected = description.get("ceremony").and_then(Value::as_str);
+        let actual = client_data["ceremony"].as_str();
+        if expected.is_some() && actual != expected {
+            warnings.push(format!(
+                "clientDataJSON type is {} but the response is a {} response",
+                client_data["type"],
+                expected.unwrap_or_default()
+            ));
+        }
+        description.insert("client_data".into(), client_data.clone());
+    }
+    if let Some(authenticator_data) = &authenticator_data {
+        if authenticator_data["flags"]["user_present"] == false {
+            warnings.push("The user presence (UP) flag isn't set".to_string());
+        }
+        if origin.is_some() && authenticator_data["rp_id"].is_null() {
+            warnings.push("rpIdHash doesn't match the origin's host or any parent domain".to_string());
+        }
+        let attested_id = authenticator_data["attested_credential"]["credential_id"]["base64url"].as_str();
+        if let (Some(id), Some(attested_id)) = (id, attested_id) {
+            if id.trim_end_matches('=') != attested_id {
+                warnings.push("The credential ID in authenticatorData differs from the credential's id".to_string());
+            }
+        }
+    }
+
+    if let Some(signature) = field(response, &["signature"]) {
+        description.insert(
+            "signature".into(),
+            bytes_summary(&decode_base64url(signature, "signature")?),
+        );
+    }
+    if let Some(user_handle) = field(response, &["userHandle", "user_handle"]) {
+        description.insert(
+            "user_handle".into(),
+            describe_user_handle(&decode_base64url(user_handle, "userHandle")?),
+        );
+    }
+    if let Some(algorithm) = response.get("publicKeyAlgorithm").and_then(Value::as_i64) {
+        description.insert("public_key_algorithm".into(), algorithm_name(Some(algorithm as i128)));
+    }
+    if let Some(transports) = response.get("transports") {
+        description.insert("transports".into(), transports.clone());
+    }
+    if let Some(extensions) = credential.get("clientExtensionResults") {
+        description.insert("client_extension_results".into(), extensions.clone());
+    }
+    description.insert("warnings".into(), json!(warnings));
+    Ok(Value::Object(description))
+}
+
+// A single base64url part: clientDataJSON decodes to JSON, an attestation
+// object to a CBOR map with authData, anything else is authenticatorData
+fn describe_part(encoded: &str) -> Result<Value, String> {
+    let bytes = decode_base64url(encoded, "input")?;
+    if bytes.first() == Some(&b'{') {
+        return Ok(json!({ "client_data": describe_client_data(&bytes)? }));
+    }
+    if let Ok(item) = cbor::decode(&bytes) {
+        if item.get("authData").is_some() {
+            return Ok(json!({ "attestation": describe_attestation_object(&bytes, None)? }));
+        }
+    }
+    Ok(json!({ "authenticator_data": describe_authenticator_data(&bytes, None)? }))
+}
+
+pub fn decode_webauthn(text: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Empty WebAuthn input".to_string());
+    }
+    let description = if text.starts_with('{') {
+        let value: Value = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+        if value.get("challenge").is_some() && value.get("type").is_some() {
+            json!({ "client_data": describe_client_data(text.as_bytes())? })
+        } else {
+            describe_credential(&value)?
+        }
+    } else {
+        describe_part(text)?
+    };
+    crate::formatters::to_string_pretty(&description).map_err(|e| format!("Failed to format WebAuthn output: {}", e))
+}
+
+pub struct WebAuthnFormatter;
+
+impl Formatter for WebAuthnFormatter {
+    fn id(&self) -> &'static str {
+        "webauthn"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "WebAuthn / FIDO2 Decoder"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        crate::formatters::layout_options_schema(crate::formatters::PRETTY_OPTIONS)
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        decode_webauthn(input)
+    }
+}
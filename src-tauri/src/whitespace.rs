@@ -0,0 +1,147 @@
+use tauri::{AppHandle, Manager};
+
+use crate::undo::Slot;
+use crate::AppState;
+
+fn expand_tabs(line: &str, width: usize) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut column = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = width - (column % width);
+            out.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            out.push(c);
+            column += 1;
+        }
+    }
+    out
+}
+
+// Only leading indentation is converted; tabs inside content are left alone
+fn tabify_indent(line: &str, width: usize) -> String {
+    let indent_len = line.len() - line.trim_start_matches(' ').len();
+    let tabs = indent_len / width;
+    let spaces = indent_len % width;
+    format!("{}{}{}", "\t".repeat(tabs), " ".repeat(spaces), &line[indent_len..])
+}
+
+fn map_lines(text: &str, f: impl Fn(&str) -> String) -> String {
+    // split_inclusive keeps the original line endings intact
+    text.split_inclusive('\n')
+        .map(|line| {
+            let body = line.trim_end_matches(['\n', '\r']);
+            format!("{}{}", f(body), &line[body.len()..])
+        })
+        .collect()
+}
+
+pub fn normalize(text: &str, operation: &str, width: usize) -> Result<String, String> {
+    if width == 0 {
+        return Err("Tab width must be greater than zero".to_string());
+    }
+    let result = match operation {
+        "tabs-to-spaces" => map_lines(text, |line| expand_tabs(line, width)),
+        "spaces-to-tabs" => map_lines(text, |line| tabify_indent(line, width)),
+        "crlf-to-lf" => text.replace("\r\n", "\n"),
+        "lf-to-crlf" => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+        "strip-trailing-newline" => text.trim_end_matches(['\n', '\r']).to_string(),
+        "ensure-trailing-newline" => {
+            if text.ends_with('\n') {
+                text.to_string()
+            } else {
+                let ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+                format!("{}{}", text, ending)
+            }
+        }
+        _ => return Err(format!("Unknown whitespace operation: {}", operation)),
+    };
+    Ok(result)
+}
+
+pub fn whitespace_report(text: &str) -> serde_json::Value {
+    let crlf = text.matches("\r\n").count();
+    let lf = text.matches('\n').count() - crlf;
+    let cr = text.matches('\r').count() - crlf;
+
+    let mut tab_indented = 0;
+    let mut space_indented = 0;
+    let mut mixed_indent = 0;
+    let mut trailing_whitespace = 0;
+    for line in text.lines() {
+        let indent: &str = &line[..line.len() - line.trim_start().len()];
+        match (indent.contains('\t'), indent.contains(' ')) {
+            (true, true) => mixed_indent += 1,
+            (true, false) => tab_indented += 1,
+            (false, true) => space_indented += 1,
+            _ => {}
+        }
+        if line.ends_with([' ', '\t']) {
+            trailing_whitespace += 1;
+        }
+    }
+
+    let line_endings = match (crlf > 0, lf > 0, cr > 0) {
+        (false, false, false) => "none",
+        (true, false, false) => "CRLF",
+        (false, true, false) => "LF",
+        (false, false, true) => "CR",
+        _ => "mixed",
+    };
+
+    serde_json::json!({
+        "line_endings": line_endings,
+        "crlf_count": crlf,
+        "lf_count": lf,
+        "cr_count": cr,
+        "tab_indented_lines": tab_indented,
+        "space_indented_lines": space_indented,
+        "mixed_indent_lines": mixed_indent,
+        "trailing_whitespace_lines": trailing_whitespace,
+        "ends_with_newline": text.ends_with('\n')
+    })
+}
+
+fn analyze_whitespace_blocking(state: &AppState) -> Result<serde_json::Value, String> {
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    Ok(whitespace_report(&snapshot.text))
+}
+
+#[tauri::command]
+pub async fn analyze_whitespace(app: AppHandle) -> Result<serde_json::Value, String> {
+    crate::run_blocking("analyze_whitespace", move || analyze_whitespace_blocking(app.state::<AppState>().inner())).await
+}
+
+fn normalize_whitespace_blocking(
+    operation: String,
+    tab_width: Option<usize>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let snapshot = state.lock().map_err(|e| e.to_string())?.snapshot_raw()?;
+    let content = &snapshot.text;
+
+    let result = normalize(content, &operation, tab_width.unwrap_or(4))?;
+    let report = whitespace_report(&result);
+    let length = result.len();
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.snapshot_document(&snapshot)?.edit(Slot::Formatted, result);
+
+    Ok(serde_json::json!({
+        "operation": operation,
+        "formatted_length": length,
+        "report": report
+    }))
+}
+
+#[tauri::command]
+pub async fn normalize_whitespace(
+    app: AppHandle,
+    operation: String,
+    tab_width: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("normalize_whitespace", move || {
+        normalize_whitespace_blocking(operation, tab_width, app.state::<AppState>().inner())
+    })
+    .await
+}
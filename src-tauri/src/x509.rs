@@ -0,0 +1,209 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::time::{SystemTime, UNIX_EPOCH};
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::*;
+
+use crate::formatters::Formatter;
+
+// Certificates expiring within this many days are flagged in the report
+pub const EXPIRY_WARNING_DAYS: i64 = 30;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// Extract the DER bytes of the first certificate in PEM (or bare base64) text
+pub fn pem_to_der(text: &str) -> Result<Vec<u8>, String> {
+    let mut in_block = !text.contains("-----BEGIN");
+    let mut body = String::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("-----BEGIN") {
+            in_block = true;
+        } else if line.starts_with("-----END") {
+            break;
+        } else if in_block {
+            body.push_str(line);
+        }
+    }
+
+    if body.is_empty() {
+        return Err("Empty certificate input".to_string());
+    }
+
+    STANDARD
+        .decode(body.as_bytes())
+        .map_err(|e| format!("Invalid base64 in certificate: {}", e))
+}
+
+pub fn describe_certificate(der: &[u8]) -> Result<serde_json::Value, String> {
+    let (_, cert) = X509Certificate::from_der(der)
+        .map_err(|e| format!("Failed to parse X.509 certificate: {}", e))?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let not_before = cert.validity().not_before.timestamp();
+    let not_after = cert.validity().not_after.timestamp();
+    // Floored, so a certificate that expired a few hours ago is at -1, not 0
+    let days_remaining = (not_after - now).div_euclid(SECONDS_PER_DAY);
+
+    let mut warnings = Vec::new();
+    if now < not_before {
+        warnings.push("Certificate is not yet valid".to_string());
+    }
+    warnings.extend(expiry_warning(not_after - now));
+
+    Ok(serde_json::json!({
+        "version": cert.version().0 + 1,
+        "serial": cert.raw_serial_as_string(),
+        "subject": cert.subject().to_string(),
+        "issuer": cert.issuer().to_string(),
+        "common_name": common_name(&cert),
+        "subject_alt_names": subject_alt_names(&cert),
+        "not_before": format_time(&cert.validity().not_before),
+        "not_after": format_time(&cert.validity().not_after),
+        "days_remaining": days_remaining,
+        "is_ca": cert.is_ca(),
+        "self_signed": cert.subject().as_raw() == cert.issuer().as_raw(),
+        "signature_algorithm": cert.signature_algorithm.algorithm.to_id_string(),
+        "public_key_algorithm": cert.public_key().algorithm.algorithm.to_id_string(),
+        "warnings": warnings
+    }))
+}
+
+// Less than a day either side of expiry is reported in hours
+fn expiry_warning(seconds_left: i64) -> Option<String> {
+    let count = |n: i64, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    let span = |seconds: i64| {
+        if seconds < SECONDS_PER_DAY {
+            count(seconds / 3600, "hour")
+        } else {
+            count(seconds / SECONDS_PER_DAY, "day")
+        }
+    };
+    if seconds_left < 0 {
+        Some(format!("Certificate expired {} ago", span(-seconds_left)))
+    } else if seconds_left / SECONDS_PER_DAY <= EXPIRY_WARNING_DAYS {
+        Some(format!("Certificate expires in {}", span(seconds_left)))
+    } else {
+        None
+    }
+}
+
+pub fn common_name(cert: &X509Certificate) -> Option<String> {
+    cert.subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(|cn| cn.to_string())
+}
+
+pub fn subject_alt_names(cert: &X509Certificate) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(Some(san)) = cert.subject_alternative_name() {
+        for name in san.value.general_names.iter() {
+            match name {
+                GeneralName::DNSName(dns) => names.push(dns.to_string()),
+                GeneralName::IPAddress(bytes) => names.push(format_ip(bytes)),
+                GeneralName::RFC822Name(email) => names.push(format!("email:{}", email)),
+                GeneralName::URI(uri) => names.push(format!("uri:{}", uri)),
+                _ => {}
+            }
+        }
+    }
+    names
+}
+
+// Check a hostname against the certificate's SANs (falling back to CN), honouring
+// single-label wildcards like *.example.com
+pub fn hostname_matches(der: &[u8], hostname: &str) -> Result<bool, String> {
+    let (_, cert) = X509Certificate::from_der(der)
+        .map_err(|e| format!("Failed to parse X.509 certificate: {}", e))?;
+
+    let hostname = hostname.trim_end_matches('.').to_ascii_lowercase();
+    let mut candidates = subject_alt_names(&cert);
+    if candidates.is_empty() {
+        candidates.extend(common_name(&cert));
+    }
+
+    Ok(candidates
+        .iter()
+        .any(|pattern| matches_pattern(&pattern.to_ascii_lowercase(), &hostname)))
+}
+
+fn matches_pattern(pattern: &str, hostname: &str) -> bool {
+    if let Some(suffix) = pattern.strip_prefix("*.") {
+        match hostname.split_once('.') {
+            Some((label, rest)) => !label.is_empty() && rest == suffix,
+            None => false,
+        }
+    } else {
+        pattern == hostname
+    }
+}
+
+fn format_time(time: &ASN1Time) -> String {
+    time.to_rfc2822().unwrap_or_else(|_| time.timestamp().to_string())
+}
+
+fn format_ip(bytes: &[u8]) -> String {
+    match bytes.len() {
+        4 => std::net::Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string(),
+        16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(bytes);
+            std::net::Ipv6Addr::from(octets).to_string()
+        }
+        _ => bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"),
+    }
+}
+
+pub fn decode_x509(text: &str) -> Result<String, String> {
+    let der = pem_to_der(text)?;
+    let description = describe_certificate(&der)?;
+    crate::formatters::to_string_pretty(&description)
+        .map_err(|e| format!("Failed to format certificate output: {}", e))
+}
+
+pub struct X509Formatter;
+
+impl Formatter for X509Formatter {
+    fn id(&self) -> &'static str {
+        "x509"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "X.509 Certificate Decoder"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "json"
+    }
+
+    fn options_schema(&self) -> serde_json::Value {
+        crate::formatters::layout_options_schema(crate::formatters::PRETTY_OPTIONS)
+    }
+
+    // Validity and days remaining are computed against the current time
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        decode_x509(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiry_under_a_day_is_reported_in_hours() {
+        assert_eq!(expiry_warning(-2 * 3600).as_deref(), Some("Certificate expired 2 hours ago"));
+        assert_eq!(expiry_warning(-3 * SECONDS_PER_DAY - 5).as_deref(), Some("Certificate expired 3 days ago"));
+        assert_eq!(expiry_warning(3600 + 59).as_deref(), Some("Certificate expires in 1 hour"));
+        assert_eq!(expiry_warning(10 * SECONDS_PER_DAY).as_deref(), Some("Certificate expires in 10 days"));
+        assert_eq!(expiry_warning(90 * SECONDS_PER_DAY), None);
+        assert_eq!((-2 * 3600_i64).div_euclid(SECONDS_PER_DAY), -1);
+    }
+}
@@ -0,0 +1,171 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager};
+
+use crate::formatters::Formatter;
+use crate::AppState;
+
+// Parse every `---`-separated document, keeping per-document errors instead of
+// failing the whole stream on the first bad one
+pub fn parse_documents(text: &str) -> Vec<Result<serde_yaml::Value, String>> {
+    serde_yaml::Deserializer::from_str(text)
+        .map(|document| serde_yaml::Value::deserialize(document).map_err(|e| e.to_string()))
+        .collect()
+}
+
+fn document_label(value: &serde_yaml::Value) -> Option<String> {
+    // Kubernetes-style manifests: "Deployment/my-app"
+    let kind = value.get("kind").and_then(|k| k.as_str())?;
+    match value.get("metadata").and_then(|m| m.get("name")).and_then(|n| n.as_str()) {
+        Some(name) => Some(format!("{}/{}", kind, name)),
+        None => Some(kind.to_string()),
+    }
+}
+
+pub fn format_yaml(text: &str) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Err("Empty YAML input".to_string());
+    }
+
+    let mut rendered = Vec::new();
+    for (index, document) in parse_documents(text).into_iter().enumerate() {
+        let value = document.map_err(|e| format!("Invalid YAML in document #{}: {}", index, e))?;
+        let yaml = serde_yaml::to_string(&value)
+            .map_err(|e| format!("Failed to format YAML document #{}: {}", index, e))?;
+        rendered.push(yaml.trim_end().to_string());
+    }
+    Ok(rendered.join("\n---\n"))
+}
+
+fn yaml_source(text: Option<String>, state: &AppState) -> Result<String, String> {
+    match text {
+        Some(text) if !text.is_empty() => Ok(text),
+        _ => {
+            let storage = state.lock().map_err(|e| e.to_string())?;
+            storage
+                .active()
+                .raw_content
+                .as_deref()
+                .map(str::to_string)
+                .ok_or_else(|| "No content stored".to_string())
+        }
+    }
+}
+
+fn list_yaml_documents_blocking(
+    text: Option<String>,
+    state: &AppState,
+) -> Result<serde_json::Value, String> {
+    let text = yaml_source(text, state)?;
+    let documents = parse_documents(&text);
+
+    let mut kinds: BTreeMap<String, usize> = BTreeMap::new();
+    let mut valid = 0;
+    let listed: Vec<serde_json::Value> = documents
+        .iter()
+        .enumerate()
+        .map(|(index, document)| match document {
+            Ok(value) => {
+                valid += 1;
+                let label = document_label(value);
+                if let Some(kind) = value.get("kind").and_then(|k| k.as_str()) {
+                    *kinds.entry(kind.to_string()).or_insert(0) += 1;
+                }
+                let keys: Vec<String> = value
+                    .as_mapping()
+                    .map(|m| m.keys().filter_map(|k| k.as_str().map(|s| s.to_string())).collect())
+                    .unwrap_or_default();
+                serde_json::json!({
+                    "index": index,
+                    "valid": true,
+                    "label": label,
+                    "top_level_keys": keys
+                })
+            }
+            Err(e) => serde_json::json!({
+                "index": index,
+                "valid": false,
+                "error": e
+            }),
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "document_count": listed.len(),
+        "valid_count": valid,
+        "invalid_count": listed.len() - valid,
+        "kinds": kinds,
+        "documents": listed
+    }))
+}
+
+#[tauri::command]
+pub async fn list_yaml_documents(
+    app: AppHandle,
+    text: Option<String>,
+) -> Result<serde_json::Value, String> {
+    crate::run_blocking("list_yaml_documents", move || {
+        list_yaml_documents_blocking(text, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+fn select_yaml_document_blocking(
+    index: usize,
+    as_json: bool,
+    text: Option<String>,
+    state: &AppState,
+) -> Result<String, String> {
+    let text = yaml_source(text, state)?;
+    let mut documents = parse_documents(&text);
+    let total = documents.len();
+    if index >= total {
+        return Err(format!("YAML document #{} not found ({} documents)", index, total));
+    }
+
+    let value = documents
+        .swap_remove(index)
+        .map_err(|e| format!("Invalid YAML in document #{}: {}", index, e))?;
+    let result = if as_json {
+        crate::formatters::to_string_pretty(&value).map_err(|e| format!("Failed to convert YAML to JSON: {}", e))?
+    } else {
+        serde_yaml::to_string(&value).map_err(|e| format!("Failed to format YAML: {}", e))?
+    };
+
+    let mut storage = state.lock().map_err(|e| e.to_string())?;
+    storage.active_mut().formatted_content = Some(result.clone().into());
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn select_yaml_document(
+    app: AppHandle,
+    index: usize,
+    as_json: bool,
+    text: Option<String>,
+) -> Result<String, String> {
+    crate::run_blocking("select_yaml_document", move || {
+        select_yaml_document_blocking(index, as_json, text, app.state::<AppState>().inner())
+    })
+    .await
+}
+
+pub struct YamlFormatter;
+
+impl Formatter for YamlFormatter {
+    fn id(&self) -> &'static str {
+        "yaml"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "YAML Format"
+    }
+
+    fn output_kind(&self) -> &'static str {
+        "yaml"
+    }
+
+    fn format(&self, input: &str) -> Result<String, String> {
+        format_yaml(input)
+    }
+}